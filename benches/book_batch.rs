@@ -0,0 +1,103 @@
+//! Batched vs sequential price level updates on a 200-level book.
+//!
+//! ```text
+//! cargo bench --bench book_batch
+//! ```
+
+use chrono::Utc;
+use criterion::{BatchSize, BenchmarkId, Criterion, criterion_group, criterion_main};
+use polysqueeze::book::OrderBook;
+use polysqueeze::types::{OrderDelta, PriceChange, Side};
+use rust_decimal::Decimal;
+use std::hint::black_box;
+
+const TOKEN_ID: &str = "bench_token";
+
+fn seeded_book() -> OrderBook {
+    let mut book = OrderBook::new(TOKEN_ID.to_string(), 500);
+    // 100 bids from 0.0100 to 0.4950 and 100 asks from 0.5050 to 0.9900
+    let levels = (0..100u32).flat_map(|i| {
+        [
+            (Side::BUY, Decimal::new(100 + i as i64 * 49, 4)),
+            (Side::SELL, Decimal::new(5050 + i as i64 * 49, 4)),
+        ]
+    });
+    for (sequence, (side, price)) in levels.enumerate() {
+        book.apply_delta(OrderDelta {
+            token_id: TOKEN_ID.to_string(),
+            timestamp: Utc::now(),
+            side,
+            price,
+            size: Decimal::from(100),
+            sequence: sequence as u64 + 1,
+        })
+        .unwrap();
+    }
+    book
+}
+
+/// A mix of new levels, resizes and removals spread across both sides
+fn changes(count: usize) -> Vec<PriceChange> {
+    (0..count)
+        .map(|i| {
+            let side = if i % 2 == 0 { Side::BUY } else { Side::SELL };
+            let offset = (i * 37 % 4900) as i64;
+            let price = match side {
+                Side::BUY => Decimal::new(100 + offset, 4),
+                Side::SELL => Decimal::new(5050 + offset, 4),
+            };
+            let size = Decimal::from((i % 5) as i64 * 40);
+            PriceChange { side, price, size }
+        })
+        .collect()
+}
+
+fn bench_price_change_batch(c: &mut Criterion) {
+    let mut group = c.benchmark_group("price_change_batch");
+    for count in [10, 100, 1000] {
+        let batch = changes(count);
+        let deltas: Vec<OrderDelta> = batch
+            .iter()
+            .enumerate()
+            .map(|(i, change)| OrderDelta {
+                token_id: TOKEN_ID.to_string(),
+                timestamp: Utc::now(),
+                side: change.side,
+                price: change.price,
+                size: change.size,
+                sequence: 1_000 + i as u64,
+            })
+            .collect();
+
+        group.bench_with_input(BenchmarkId::new("batched", count), &batch, |b, batch| {
+            b.iter_batched(
+                seeded_book,
+                |mut book| {
+                    black_box(book.apply_price_change_batch(batch));
+                    book
+                },
+                BatchSize::SmallInput,
+            )
+        });
+        group.bench_with_input(
+            BenchmarkId::new("sequential", count),
+            &deltas,
+            |b, deltas| {
+                b.iter_batched(
+                    || (seeded_book(), deltas.clone()),
+                    |(mut book, deltas)| {
+                        for delta in deltas {
+                            book.apply_delta(delta).unwrap();
+                        }
+                        book
+                    },
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_price_change_batch);
+criterion_main!(benches);
@@ -0,0 +1,87 @@
+//! Full-snapshot replacement on books of 10 to 500 levels per side.
+//!
+//! ```text
+//! cargo bench --bench book_snapshot
+//! cargo bench --bench book_snapshot --features fast-book
+//! ```
+//!
+//! Each size also prints how many heap allocations one snapshot costs once the book
+//! is warm, so the two storage variants can be compared on allocations as well as time.
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use polysqueeze::book::OrderBook;
+use polysqueeze::types::OrderSummary;
+use rust_decimal::Decimal;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// `depth` bids below 0.50 and `depth` asks above it, bids best-first as the market
+/// channel sends them; `shift` moves every size so consecutive snapshots differ
+fn snapshot(depth: usize, shift: i64) -> (Vec<OrderSummary>, Vec<OrderSummary>) {
+    let level = |ticks: i64, i: usize| OrderSummary {
+        price: Decimal::new(ticks, 4),
+        size: Decimal::from(100 + (i as i64 + shift) % 7 * 10),
+    };
+    let step = 4900 / depth as i64;
+    let bids = (0..depth)
+        .map(|i| level(4950 - step * i as i64, i))
+        .collect();
+    let asks = (0..depth)
+        .map(|i| level(5050 + step * i as i64, i))
+        .collect();
+    (bids, asks)
+}
+
+fn bench_snapshot_replace(c: &mut Criterion) {
+    let mut group = c.benchmark_group("book_snapshot");
+    for depth in [10, 50, 100, 500] {
+        let snapshots = [snapshot(depth, 0), snapshot(depth, 1)];
+        let mut book = OrderBook::new("bench_token".to_string(), depth);
+        book.apply_book_snapshot(&snapshots[0].0, &snapshots[0].1);
+
+        let before = ALLOCATIONS.load(Ordering::Relaxed);
+        for (bids, asks) in snapshots.iter().cycle().take(100) {
+            book.apply_book_snapshot(bids, asks);
+        }
+        let per_snapshot = (ALLOCATIONS.load(Ordering::Relaxed) - before) as f64 / 100.0;
+        println!("book_snapshot/{depth}: {per_snapshot:.1} allocations per snapshot");
+
+        group.throughput(Throughput::Elements(2 * depth as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(depth), &snapshots, |b, s| {
+            let mut next = 0;
+            b.iter(|| {
+                let (bids, asks) = &s[next];
+                next ^= 1;
+                book.apply_book_snapshot(black_box(bids), black_box(asks));
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_snapshot_replace);
+criterion_main!(benches);
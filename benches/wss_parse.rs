@@ -0,0 +1,52 @@
+//! Websocket frame parsing throughput over captured market/user channel fixtures.
+//!
+//! Compare backends with:
+//!
+//! ```text
+//! cargo bench --bench wss_parse
+//! cargo bench --bench wss_parse --features simd-json
+//! ```
+
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use polysqueeze::wss::{parse_market_events, parse_user_events};
+use std::hint::black_box;
+
+const MARKET_BOOK: &str = include_str!("../tests/fixtures/wss/market_book.json");
+const PRICE_CHANGE: &str = include_str!("../tests/fixtures/wss/price_change.json");
+const MARKET_MISC: &str = include_str!("../tests/fixtures/wss/market_misc.json");
+const USER_EVENTS: &str = include_str!("../tests/fixtures/wss/user_events.json");
+
+fn backend() -> &'static str {
+    if cfg!(feature = "simd-json") {
+        "simd-json"
+    } else {
+        "serde_json"
+    }
+}
+
+fn bench_market_frames(c: &mut Criterion) {
+    let mut group = c.benchmark_group(format!("market_frames/{}", backend()));
+    for (name, frame) in [
+        ("book", MARKET_BOOK),
+        ("price_change", PRICE_CHANGE),
+        ("tick_and_trade", MARKET_MISC),
+    ] {
+        group.throughput(Throughput::Bytes(frame.len() as u64));
+        group.bench_function(name, |b| {
+            b.iter(|| parse_market_events(black_box(frame)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn bench_user_frames(c: &mut Criterion) {
+    let mut group = c.benchmark_group(format!("user_frames/{}", backend()));
+    group.throughput(Throughput::Bytes(USER_EVENTS.len() as u64));
+    group.bench_function("trade_and_order", |b| {
+        b.iter(|| parse_user_events(black_box(USER_EVENTS)).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_market_frames, bench_user_frames);
+criterion_main!(benches);
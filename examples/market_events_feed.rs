@@ -0,0 +1,44 @@
+use chrono::{Duration, Utc};
+use polysqueeze::{client::ClobClient, errors::Result, types::MarketEvent};
+use std::env;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let base_url =
+        env::var("POLY_API_URL").unwrap_or_else(|_| "https://clob.polymarket.com".into());
+    let condition_id = env::var("POLY_CONDITION_ID")
+        .expect("POLY_CONDITION_ID must be set for the market events example");
+
+    let client = ClobClient::new(&base_url);
+    let since = Utc::now() - Duration::hours(24);
+    let events = client.get_market_events_feed(&condition_id, since).await?;
+    println!("{} events in the last 24h", events.len());
+
+    for event in &events {
+        match event {
+            MarketEvent::PriceChanged {
+                token_id,
+                old_price,
+                new_price,
+                timestamp,
+            } => println!("{timestamp} price {token_id}: {old_price} -> {new_price}"),
+            MarketEvent::TradeExecuted {
+                trade_id,
+                token_id,
+                price,
+                size,
+                timestamp,
+            } => println!("{timestamp} trade {trade_id} on {token_id}: {size} @ {price}"),
+            MarketEvent::OrderBookChanged { timestamp, summary } => {
+                println!("{timestamp} book: {summary}")
+            }
+            MarketEvent::MarketResolved {
+                winning_outcome,
+                timestamp,
+            } => println!("{timestamp} resolved: {winning_outcome}"),
+            MarketEvent::MarketClosed { timestamp } => println!("{timestamp} closed"),
+        }
+    }
+
+    Ok(())
+}
@@ -8,7 +8,7 @@ use std::env;
 
 /// Helper to fail fast if a required environment variable is missing.
 fn env_var(key: &str) -> String {
-    env::var(key).expect(&format!("{} must be set for the order example", key))
+    env::var(key).unwrap_or_else(|_| panic!("{} must be set for the order example", key))
 }
 
 #[tokio::main]
@@ -42,7 +42,7 @@ async fn main() -> Result<()> {
 
     let token_id = market.clob_token_ids.first().unwrap();
 
-    let book = client.get_order_book(&token_id).await?;
+    let book = client.get_order_book(token_id, None).await?;
     let best_bid = book.bids.first().expect("order book has no bids").price;
     let best_ask = book.asks.first().expect("order book has no asks").price;
     let book_mid = (best_bid + best_ask) / Decimal::from(2);
@@ -50,7 +50,7 @@ async fn main() -> Result<()> {
     let order_size = min_order_size;
     let order_price = book_mid;
 
-    let args = OrderArgs::new(&token_id, order_price, order_size, Side::BUY);
+    let args = OrderArgs::new(token_id, order_price, order_size, Side::BUY);
     let signed_order = client.create_order(&args, None, None, None).await?;
 
     let response = client.post_order(signed_order, OrderType::GTC).await?;
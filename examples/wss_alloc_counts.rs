@@ -0,0 +1,96 @@
+//! Count heap allocations when parsing a recorded burst of market channel frames.
+//!
+//! Compares the owned `parse_market_events` path with the borrowed
+//! `parse_market_events_borrowed` path, and the borrowed path fed straight into an
+//! `OrderBookManager`.
+//!
+//! ```bash
+//! cargo run --release --example wss_alloc_counts
+//! ```
+
+use polysqueeze::book::OrderBookManager;
+use polysqueeze::wss::{parse_market_events, parse_market_events_borrowed};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const BURST: [&str; 3] = [
+    include_str!("../tests/fixtures/wss/market_book.json"),
+    include_str!("../tests/fixtures/wss/price_change.json"),
+    include_str!("../tests/fixtures/wss/market_misc.json"),
+];
+const ROUNDS: u64 = 100;
+
+fn count(label: &str, mut f: impl FnMut()) {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    for _ in 0..ROUNDS {
+        f();
+    }
+    let total = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    println!(
+        "{:<28} {:>8} allocations ({} per burst)",
+        label,
+        total,
+        total / ROUNDS
+    );
+}
+
+fn main() {
+    let levels: usize = parse_market_events_borrowed(BURST[0])
+        .unwrap()
+        .iter()
+        .map(|event| match event {
+            polysqueeze::wss::WssMarketEventRef::Book(book) => book.bids.len() + book.asks.len(),
+            _ => 0,
+        })
+        .sum();
+    println!(
+        "burst: {} frames, {} book levels, {} rounds\n",
+        BURST.len(),
+        levels,
+        ROUNDS
+    );
+
+    count("owned", || {
+        for frame in BURST {
+            black_box(parse_market_events(frame).unwrap());
+        }
+    });
+    count("borrowed", || {
+        for frame in BURST {
+            black_box(parse_market_events_borrowed(frame).unwrap());
+        }
+    });
+
+    let manager = OrderBookManager::new(100);
+    count("borrowed -> OrderBookManager", || {
+        for frame in BURST {
+            for event in parse_market_events_borrowed(frame).unwrap() {
+                manager.apply_event(&event).unwrap();
+            }
+        }
+    });
+}
@@ -3,7 +3,7 @@ use std::env;
 
 /// Fail fast when an expected environment variable is missing.
 fn env_var(key: &str) -> String {
-    env::var(key).expect(&format!("{} must be set for the cancel example", key))
+    env::var(key).unwrap_or_else(|_| panic!("{} must be set for the cancel example", key))
 }
 
 #[tokio::main]
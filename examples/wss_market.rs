@@ -1,7 +1,5 @@
 use polysqueeze::Result;
 use polysqueeze::client::ClobClient;
-use polysqueeze::errors::PolyError;
-use polysqueeze::types::{GammaListParams, Market};
 use polysqueeze::wss::{WssMarketClient, WssMarketEvent};
 use rust_decimal::Decimal;
 use std::env;
@@ -19,40 +17,41 @@ async fn main() -> Result<()> {
         .and_then(|value| Decimal::from_str(&value).ok())
         .unwrap_or_else(|| Decimal::from(1_000_000));
 
-    let params = GammaListParams {
-        limit: Some(50),
-        liquidity_num_min: Some(min_liquidity),
-        ..Default::default()
-    };
-
-    let response = clob.get_markets(None, Some(&params)).await?;
-    let market = pick_liquid_market(&response.data, min_liquidity)?;
+    // Pick a random eligible market so repeated runs cover different books
+    let (market, asset_ids) = clob
+        .find_subscribable_market_with(min_liquidity, |candidates| {
+            let seed = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_nanos() as usize)
+                .unwrap_or(0);
+            seed % candidates.len()
+        })
+        .await?;
 
     println!(
         "Selected market {} (liquidity={:?})",
         market.condition_id, market.liquidity_num
     );
 
-    let asset_ids = derive_asset_ids(market).unwrap_or_else(|| Vec::new());
-
-    if asset_ids.is_empty() {
-        return Err(PolyError::validation(
-            "failed to derive asset IDs for the selected market",
-        ));
-    }
-
     let mut client = WssMarketClient::new();
-    client.subscribe(asset_ids.clone()).await?;
+    client.subscribe_market_channel(asset_ids.clone()).await?;
 
     println!("Subscribed to market channel for assets={:?}", asset_ids);
 
     for _ in 0..20 {
         match client.next_event().await {
             Ok(WssMarketEvent::PriceChange(change)) => {
-                println!(
-                    "price_change for {}: {:?}",
-                    change.market, change.price_changes
-                );
+                for level in &change.price_changes {
+                    println!(
+                        "price_change for {}: {:?} {} @ {} (best {} / {})",
+                        change.market,
+                        level.side,
+                        level.size,
+                        level.price,
+                        level.best_bid,
+                        level.best_ask
+                    );
+                }
             }
             Ok(WssMarketEvent::Book(book)) => {
                 println!(
@@ -74,6 +73,16 @@ async fn main() -> Result<()> {
                     trade.market, trade.side, trade.price
                 );
             }
+            Ok(WssMarketEvent::SubscriptionError(error)) => {
+                eprintln!(
+                    "subscription error for {}: {}",
+                    error.asset_id.as_deref().unwrap_or("<all>"),
+                    error.reason
+                );
+            }
+            Ok(WssMarketEvent::BufferOverflow { dropped_count }) => {
+                eprintln!("fell behind, dropped {} events", dropped_count);
+            }
             Err(err) => {
                 eprintln!("stream error: {}", err);
                 break;
@@ -83,36 +92,3 @@ async fn main() -> Result<()> {
 
     Ok(())
 }
-
-fn pick_liquid_market(markets: &[Market], min_liquidity: Decimal) -> Result<&Market> {
-    let eligible: Vec<&Market> = markets
-        .iter()
-        .filter(|m| m.liquidity_num.unwrap_or_default() >= min_liquidity)
-        .collect();
-
-    if eligible.is_empty() {
-        return Err(PolyError::validation("no liquid markets available"));
-    }
-
-    let seed = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_nanos() as usize)
-        .unwrap_or(0);
-    let idx = seed % eligible.len();
-    Ok(eligible[idx])
-}
-
-fn derive_asset_ids(market: &Market) -> Option<Vec<String>> {
-    if !market.clob_token_ids.is_empty() {
-        return Some(market.clob_token_ids.clone());
-    }
-
-    let ids = market
-        .tokens
-        .iter()
-        .map(|token| token.token_id.clone())
-        .filter(|id| !id.is_empty())
-        .collect::<Vec<_>>();
-
-    if ids.is_empty() { None } else { Some(ids) }
-}
@@ -10,7 +10,7 @@ use std::{env, str::FromStr};
 
 /// Fail fast when an expected environment variable is missing.
 fn env_var(key: &str) -> String {
-    env::var(key).expect(&format!("{} must be set for the user-channel example", key))
+    env::var(key).unwrap_or_else(|_| panic!("{} must be set for the user-channel example", key))
 }
 
 #[tokio::main]
@@ -71,7 +71,7 @@ async fn main() -> Result<()> {
         })?;
     let token_id = primary_market.clob_token_ids.first().unwrap().clone();
 
-    let book = l2_client.get_order_book(&token_id).await?;
+    let book = l2_client.get_order_book(&token_id, None).await?;
     let _best_ask = book.asks.first().ok_or_else(|| {
         PolyError::validation("Order book has no asks; cannot derive a safe price")
     })?;
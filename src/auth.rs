@@ -8,7 +8,7 @@ use crate::types::ApiCredentials;
 use alloy_primitives::{Address, U256, hex::encode_prefixed};
 use alloy_signer::SignerSync;
 use alloy_signer_local::PrivateKeySigner;
-use alloy_sol_types::{eip712_domain, sol};
+use alloy_sol_types::{Eip712Domain, SolStruct, eip712_domain, sol};
 use base64::engine::Engine;
 use base64::engine::general_purpose::{STANDARD, URL_SAFE, URL_SAFE_NO_PAD};
 use hmac::{Hmac, Mac};
@@ -113,20 +113,27 @@ pub fn sign_order_message(
     chain_id: u64,
     verifying_contract: Address,
 ) -> Result<String> {
-    let domain = eip712_domain!(
-        name: "Polymarket CTF Exchange",
-        version: "1",
-        chain_id: chain_id,
-        verifying_contract: verifying_contract,
-    );
-
     let signature = signer
-        .sign_typed_data_sync(&order, &domain)
+        .sign_typed_data_sync(&order, &order_domain(chain_id, verifying_contract))
         .map_err(|e| PolyError::crypto(format!("Order signature failed: {}", e)))?;
 
     Ok(encode_prefixed(signature.as_bytes()))
 }
 
+/// EIP-712 digest of `order`, the hash the CLOB reports as its order ID
+pub fn order_hash(order: &Order, chain_id: u64, verifying_contract: Address) -> String {
+    encode_prefixed(order.eip712_signing_hash(&order_domain(chain_id, verifying_contract)))
+}
+
+fn order_domain(chain_id: u64, verifying_contract: Address) -> Eip712Domain {
+    eip712_domain!(
+        name: "Polymarket CTF Exchange",
+        version: "1",
+        chain_id: chain_id,
+        verifying_contract: verifying_contract,
+    )
+}
+
 /// Build HMAC signature for L2 authentication
 pub fn build_hmac_signature<T>(
     secret: &str,
@@ -182,11 +189,32 @@ pub fn create_l2_headers<T>(
     req_path: &str,
     body: Option<&T>,
 ) -> Result<Headers>
+where
+    T: ?Sized + Serialize,
+{
+    create_l2_headers_at(
+        signer,
+        api_creds,
+        method,
+        req_path,
+        body,
+        get_current_unix_time_secs(),
+    )
+}
+
+/// [`create_l2_headers`] stamped with `timestamp` (Unix seconds) instead of the current time
+pub fn create_l2_headers_at<T>(
+    signer: &PrivateKeySigner,
+    api_creds: &ApiCredentials,
+    method: &str,
+    req_path: &str,
+    body: Option<&T>,
+    timestamp: u64,
+) -> Result<Headers>
 where
     T: ?Sized + Serialize,
 {
     let address = encode_prefixed(signer.address().as_slice());
-    let timestamp = get_current_unix_time_secs();
 
     let hmac_signature =
         build_hmac_signature(&api_creds.secret, timestamp, method, req_path, body)?;
@@ -1,11 +1,14 @@
 //! Order book management for Polymarket client
 
+mod levels;
+
+use self::levels::Levels;
 use crate::errors::{PolyError, Result};
 use crate::types::*;
 use crate::utils::math;
+use crate::wss::WssMarketEventRef;
 use chrono::Utc;
 use rust_decimal::Decimal;
-use std::collections::BTreeMap; // BTreeMap keeps prices sorted automatically - crucial for order books
 use std::sync::{Arc, RwLock}; // For thread-safe access across multiple tasks
 use tracing::{debug, trace, warn}; // Logging for debugging and monitoring
 
@@ -46,14 +49,17 @@ pub struct OrderBook {
     /// - Integer comparisons are ~10x faster than Decimal comparisons
     /// - No memory allocation for each price level
     /// - Better CPU cache utilization (smaller data structures)
-    bids: BTreeMap<Price, Qty>,
+    ///
+    /// With the `fast-book` feature the map is a sorted ring buffer that snapshots
+    /// refill in place (see `book/levels.rs`); the ordering is the same.
+    bids: Levels,
 
     /// Ask side (price -> size, sorted ascending) - NOW USING FIXED-POINT!
     /// BTreeMap keeps lowest asks first - people selling at cheapest prices
     ///
     /// BEFORE (slow): asks: BTreeMap<Decimal, Decimal>,
     /// AFTER (fast):  asks: BTreeMap<Price, Qty>,
-    asks: BTreeMap<Price, Qty>,
+    asks: Levels,
 
     /// Minimum tick size for this market in ticks (like 10 for $0.001 increments)
     /// Some markets only allow certain price increments
@@ -71,6 +77,30 @@ pub struct OrderBook {
     ///
     /// Typical values: 10-50 for retail, 100-500 for institutional HFT systems
     max_depth: usize,
+
+    /// Condition ID and server timestamp from the last snapshot or price change.
+    /// Both feed the server's book hash, so they're kept verbatim.
+    market: String,
+    server_timestamp: String,
+
+    /// Market parameters the server's book hash also covers, kept as their decimal
+    /// strings. Unknown ones hash as `null`, as they do in the reference client.
+    min_order_size: Option<String>,
+    hash_tick_size: Option<String>,
+    neg_risk: Option<bool>,
+    last_trade_price: Option<String>,
+
+    /// Set when a server-published hash didn't match our reconstruction
+    hash_mismatch: bool,
+
+    /// Set while the levels come from a truncated REST snapshot, so the real book may
+    /// be deeper than ours and depth figures are only a lower bound
+    truncated: bool,
+
+    /// Incremental updates applied, and when the levels were last replaced or checked
+    /// against a full snapshot
+    updates_since_reconcile: u64,
+    reconciled_at: chrono::DateTime<Utc>,
 }
 
 impl OrderBook {
@@ -91,10 +121,20 @@ impl OrderBook {
             token_id_hash,
             sequence: 0, // Start at 0, will increment as we get updates
             timestamp: Utc::now(),
-            bids: BTreeMap::new(), // Empty to start - using Price/Qty types
-            asks: BTreeMap::new(), // Empty to start - using Price/Qty types
+            bids: Levels::new(),   // Empty to start - using Price/Qty types
+            asks: Levels::new(),   // Empty to start - using Price/Qty types
             tick_size_ticks: None, // We'll set this later when we learn about the market
             max_depth,
+            market: String::new(),
+            server_timestamp: String::new(),
+            min_order_size: None,
+            hash_tick_size: None,
+            neg_risk: None,
+            last_trade_price: None,
+            hash_mismatch: false,
+            truncated: false,
+            updates_since_reconcile: 0,
+            reconciled_at: Utc::now(),
         }
     }
 
@@ -361,7 +401,7 @@ impl OrderBook {
             // }
 
             // AFTER (fast, ~2ns, pure integer):
-            if tick_size_ticks > 0 && delta.price % tick_size_ticks != 0 {
+            if tick_size_ticks > 0 && !delta.price.is_multiple_of(tick_size_ticks) {
                 // Price is not aligned to tick size - reject the update
                 warn!(
                     "Rejecting misaligned price: {} not divisible by tick size {}",
@@ -374,6 +414,7 @@ impl OrderBook {
         // Update our tracking info
         self.sequence = delta.sequence;
         self.timestamp = delta.timestamp;
+        self.updates_since_reconcile += 1;
 
         // Apply the actual change to the appropriate side (FAST VERSION)
         match delta.side {
@@ -395,6 +436,167 @@ impl OrderBook {
         Ok(())
     }
 
+    /// Apply a batch of price level updates in one pass
+    ///
+    /// Applying deltas one at a time costs a tree lookup (and possibly a rebalance)
+    /// per update. Here each side's updates are sorted by price and merged linearly
+    /// with the existing levels, and the side is rebuilt from the merged, already
+    /// sorted output.
+    ///
+    /// If the same price appears more than once, the last update in `changes` wins.
+    /// Updates that don't convert to fixed-point or aren't tick aligned are skipped.
+    /// The counts in the result are taken before the depth limit is applied.
+    pub fn apply_price_change_batch(&mut self, changes: &[PriceChange]) -> ApplyResult {
+        let mut bid_updates = Vec::new();
+        let mut ask_updates = Vec::new();
+
+        for change in changes {
+            let (Ok(price), Ok(size)) =
+                (decimal_to_price(change.price), decimal_to_qty(change.size))
+            else {
+                warn!("Skipping unrepresentable price change: {:?}", change);
+                continue;
+            };
+            if let Some(tick_size_ticks) = self.tick_size_ticks
+                && tick_size_ticks > 0
+                && !price.is_multiple_of(tick_size_ticks)
+            {
+                warn!(
+                    "Skipping misaligned price: {} not divisible by tick size {}",
+                    price, tick_size_ticks
+                );
+                continue;
+            }
+            match change.side {
+                Side::BUY => bid_updates.push((price, size)),
+                Side::SELL => ask_updates.push((price, size)),
+            }
+        }
+
+        let mut result = ApplyResult::default();
+        merge_levels(&mut self.bids, &mut bid_updates, &mut result);
+        merge_levels(&mut self.asks, &mut ask_updates, &mut result);
+        self.updates_since_reconcile += (bid_updates.len() + ask_updates.len()) as u64;
+
+        if result.levels_changed() > 0 {
+            self.timestamp = Utc::now();
+            self.trim_depth();
+        }
+
+        result
+    }
+
+    /// Replace both sides with a full book snapshot
+    /// The market channel sends one of these on subscribe and whenever a trade
+    /// reshapes the book, so any levels we had before are discarded.
+    ///
+    /// Levels that don't convert to fixed-point are skipped.
+    pub fn apply_book_snapshot(&mut self, bids: &[OrderSummary], asks: &[OrderSummary]) {
+        fn to_levels(levels: &[OrderSummary]) -> impl Iterator<Item = (Price, Qty)> + '_ {
+            levels.iter().filter_map(|level| {
+                let price = decimal_to_price(level.price).ok()?;
+                let size = decimal_to_qty(level.size).ok()?;
+                (size > 0).then_some((price, size))
+            })
+        }
+
+        levels::refill(&mut self.bids, to_levels(bids));
+        levels::refill(&mut self.asks, to_levels(asks));
+        self.truncated = false;
+        self.timestamp = Utc::now();
+        self.trim_depth();
+        self.updates_since_reconcile = 0;
+        self.reconciled_at = self.timestamp;
+    }
+
+    /// Whether `config` calls for checking this book against a fresh snapshot as of `now`
+    pub fn reconciliation_due(&self, config: &ReconcileConfig, now: chrono::DateTime<Utc>) -> bool {
+        let by_count = config
+            .every_updates
+            .is_some_and(|limit| self.updates_since_reconcile >= limit.max(1));
+        let by_age = config.every.is_some_and(|interval| {
+            self.updates_since_reconcile > 0
+                && chrono::Duration::from_std(interval)
+                    .is_ok_and(|interval| now - self.reconciled_at >= interval)
+        });
+        by_count || by_age
+    }
+
+    /// Largest size difference at any price level between this book and a snapshot
+    ///
+    /// The snapshot is cut to `max_depth` first, so levels the local book dropped on
+    /// purpose don't count. A level present on one side only counts its full size.
+    pub fn divergence_from(&self, bids: &[OrderSummary], asks: &[OrderSummary]) -> Decimal {
+        let mut fresh = OrderBook::new(self.token_id.clone(), self.max_depth);
+        fresh.apply_book_snapshot(bids, asks);
+
+        fn side_divergence(local: &Levels, fresh: &Levels) -> Qty {
+            local
+                .keys()
+                .chain(fresh.keys())
+                .map(|price| {
+                    let local = local.get(price).copied().unwrap_or(0);
+                    let fresh = fresh.get(price).copied().unwrap_or(0);
+                    (local - fresh).abs()
+                })
+                .max()
+                .unwrap_or(0)
+        }
+
+        qty_to_decimal(
+            side_divergence(&self.bids, &fresh.bids).max(side_divergence(&self.asks, &fresh.asks)),
+        )
+    }
+
+    /// Compare the book with a fresh REST snapshot, replacing the levels when they differ
+    /// by more than `tolerance`
+    ///
+    /// A [`truncated`](OrderBookSummary::truncated) snapshot is only compared with our
+    /// best levels up to its depth, since it says nothing about the ones below. Books
+    /// replaced from one report [`is_truncated`](Self::is_truncated) until the next full
+    /// snapshot. The reconciliation clock restarts either way.
+    pub fn reconcile(
+        &mut self,
+        snapshot: &OrderBookSummary,
+        tolerance: Decimal,
+    ) -> BookReconciliation {
+        let divergence = if snapshot.truncated {
+            let depth = snapshot.bids.len().max(snapshot.asks.len());
+            let mut top = self.clone();
+            top.max_depth = depth.min(self.max_depth);
+            top.trim_depth();
+            top.divergence_from(&snapshot.bids, &snapshot.asks)
+        } else {
+            self.divergence_from(&snapshot.bids, &snapshot.asks)
+        };
+        let updates = self.updates_since_reconcile;
+        let replaced = divergence > tolerance;
+        if replaced {
+            warn!(
+                "book {} drifted by {} over {} updates; replacing with snapshot",
+                self.token_id, divergence, updates
+            );
+            self.apply_book_snapshot(&snapshot.bids, &snapshot.asks);
+            self.truncated = snapshot.truncated;
+            self.set_server_context(&snapshot.market, &snapshot.timestamp.to_string());
+            self.set_hash_params_from(snapshot);
+            self.hash_mismatch = false;
+        } else {
+            debug!(
+                "book {} matches snapshot after {} updates",
+                self.token_id, updates
+            );
+            self.updates_since_reconcile = 0;
+            self.reconciled_at = Utc::now();
+        }
+        BookReconciliation {
+            token_id: self.token_id.clone(),
+            divergence,
+            replaced,
+            updates,
+        }
+    }
+
     #[cfg(test)]
     /// Apply a bid-side delta (someone wants to buy) - LEGACY VERSION
     /// If size is 0, it means "remove this price level entirely"
@@ -498,6 +700,9 @@ impl OrderBook {
     /// 1. Break it into smaller pieces over time
     /// 2. Use a different trading strategy
     /// 3. Accept that there's not enough liquidity right now
+    ///
+    /// On a book built from a truncated snapshot (see [`is_truncated`](Self::is_truncated))
+    /// a `None` only means our levels run out; the real book may still fill the order.
     pub fn calculate_market_impact(&self, side: Side, size: Decimal) -> Option<MarketImpact> {
         // PERFORMANCE NOTE: This method still uses Decimal for external compatibility,
         // but the internal order book lookups now use our fast fixed-point data structures.
@@ -536,6 +741,12 @@ impl OrderBook {
         }
 
         if remaining_size > Decimal::ZERO {
+            if self.truncated {
+                debug!(
+                    "book {} is from a truncated snapshot; {} left unfilled is a lower bound on depth",
+                    self.token_id, remaining_size
+                );
+            }
             // Not enough liquidity to fill the whole order
             // This is a perfect example of why we don't need infinite depth:
             // If we can't fill your order with the top N levels, you probably
@@ -597,6 +808,8 @@ impl OrderBook {
 
     /// Get the total liquidity within a price range
     /// Useful for understanding how much depth exists in a certain price band
+    ///
+    /// Only a lower bound when the book [`is_truncated`](Self::is_truncated).
     pub fn liquidity_in_range(
         &self,
         min_price: Decimal,
@@ -624,9 +837,21 @@ impl OrderBook {
 
         // Sum up the sizes, converting from fixed-point back to Decimal
         let total_size_units: i64 = levels.into_iter().map(|(_, &size)| size).sum();
+        if self.truncated {
+            debug!(
+                "book {} is from a truncated snapshot; liquidity in range is a lower bound",
+                self.token_id
+            );
+        }
         qty_to_decimal(total_size_units)
     }
 
+    /// Whether the levels came from a truncated REST snapshot, so the real book may be
+    /// deeper and depth-based figures are a lower bound
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
     /// Validate that prices are properly ordered
     /// A healthy book should have best bid < best ask (otherwise there's an arbitrage opportunity)
     pub fn is_valid(&self) -> bool {
@@ -635,6 +860,200 @@ impl OrderBook {
             _ => true,                                       // Empty book is technically valid
         }
     }
+
+    /// Check the book's structural invariants, returning every violation found
+    ///
+    /// Each side must be strictly ordered by price with no zero-size levels and no more
+    /// than `max_depth` of them, every price must sit on the tick grid when a tick size
+    /// is set, and the best bid must be below the best ask. Incremental updates don't
+    /// resolve a crossed book on their own, so only the feed being consistent keeps the
+    /// last one true.
+    pub fn check_invariants(&self) -> std::result::Result<(), Vec<String>> {
+        fn check_side(name: &str, side: &Levels, book: &OrderBook, violations: &mut Vec<String>) {
+            let mut previous: Option<Price> = None;
+            for (&price, &size) in side.iter() {
+                if previous.is_some_and(|previous| previous >= price) {
+                    violations.push(format!(
+                        "{} levels out of order at {}",
+                        name,
+                        price_to_decimal(price)
+                    ));
+                }
+                if size == 0 {
+                    violations.push(format!(
+                        "{} level at {} has zero size",
+                        name,
+                        price_to_decimal(price)
+                    ));
+                }
+                if let Some(tick_size_ticks) = book.tick_size_ticks
+                    && tick_size_ticks > 0
+                    && !price.is_multiple_of(tick_size_ticks)
+                {
+                    violations.push(format!(
+                        "{} level at {} is off the tick grid",
+                        name,
+                        price_to_decimal(price)
+                    ));
+                }
+                previous = Some(price);
+            }
+            if side.len() > book.max_depth {
+                violations.push(format!(
+                    "{} {} levels exceed max depth {}",
+                    side.len(),
+                    name,
+                    book.max_depth
+                ));
+            }
+        }
+
+        let mut violations = Vec::new();
+        check_side("bid", &self.bids, self, &mut violations);
+        check_side("ask", &self.asks, self, &mut violations);
+        if let Some((bid, ask)) = self.best_prices_fast()
+            && bid >= ask
+        {
+            violations.push(format!(
+                "book crossed: best bid {} >= best ask {}",
+                price_to_decimal(bid),
+                price_to_decimal(ask)
+            ));
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    /// Record the condition ID and server timestamp that the next hash check should use
+    pub fn set_server_context(&mut self, market: &str, server_timestamp: &str) {
+        self.market.clear();
+        self.market.push_str(market);
+        self.server_timestamp.clear();
+        self.server_timestamp.push_str(server_timestamp);
+    }
+
+    /// Record the market parameters the server signs into the book hash
+    pub fn set_market_params(
+        &mut self,
+        min_order_size: Decimal,
+        tick_size: Decimal,
+        neg_risk: bool,
+    ) {
+        self.min_order_size = Some(min_order_size.to_string());
+        self.hash_tick_size = Some(tick_size.to_string());
+        self.neg_risk = Some(neg_risk);
+    }
+
+    /// Record the last trade price, which the server also signs into the book hash
+    pub fn set_last_trade_price(&mut self, price: Decimal) {
+        self.last_trade_price = Some(price.to_string());
+    }
+
+    /// Take whichever hashed market parameters a REST snapshot carries
+    fn set_hash_params_from(&mut self, snapshot: &OrderBookSummary) {
+        if let Some(min_order_size) = snapshot.min_order_size {
+            self.min_order_size = Some(min_order_size.to_string());
+        }
+        if let Some(tick_size) = snapshot.tick_size {
+            self.hash_tick_size = Some(tick_size.to_string());
+        }
+        if let Some(neg_risk) = snapshot.neg_risk {
+            self.neg_risk = Some(neg_risk);
+        }
+        if let Some(price) = snapshot.last_trade_price {
+            self.set_last_trade_price(price);
+        }
+    }
+
+    /// Hash of the book as the server computes it for `book` and `price_change` events
+    ///
+    /// SHA-1 (lowercase hex) over the compact JSON summary
+    /// `{"market","asset_id","timestamp","bids","asks","min_order_size","neg_risk",
+    /// "tick_size","last_trade_price","hash":""}`, with levels as `{"price","size"}`
+    /// strings listed the way the server sends them: bids ascending, asks descending,
+    /// best level last on both sides. This is the scheme the reference Python client
+    /// uses. Market parameters come from [`set_market_params`](Self::set_market_params),
+    /// REST snapshots, and tick size and trade events; any not seen yet hash as `null`.
+    /// It only matches while the book holds every level the server has, so size
+    /// `max_depth` above the real depth when verifying.
+    pub fn summary_hash(&self) -> String {
+        use sha1::{Digest, Sha1};
+
+        #[derive(serde::Serialize)]
+        struct Level {
+            price: String,
+            size: String,
+        }
+
+        #[derive(serde::Serialize)]
+        struct Summary<'a> {
+            market: &'a str,
+            asset_id: &'a str,
+            timestamp: &'a str,
+            bids: Vec<Level>,
+            asks: Vec<Level>,
+            min_order_size: Option<&'a str>,
+            neg_risk: Option<bool>,
+            tick_size: Option<&'a str>,
+            last_trade_price: Option<&'a str>,
+            hash: &'a str,
+        }
+
+        let level = |(&price, &size): (&Price, &Qty)| Level {
+            price: price_to_decimal(price).normalize().to_string(),
+            size: qty_to_decimal(size).normalize().to_string(),
+        };
+        let summary = Summary {
+            market: &self.market,
+            asset_id: &self.token_id,
+            timestamp: &self.server_timestamp,
+            bids: self.bids.iter().map(level).collect(),
+            asks: self.asks.iter().rev().map(level).collect(),
+            min_order_size: self.min_order_size.as_deref(),
+            neg_risk: self.neg_risk,
+            tick_size: self.hash_tick_size.as_deref(),
+            last_trade_price: self.last_trade_price.as_deref(),
+            hash: "",
+        };
+
+        // Serializing plain strings into a Vec can't fail
+        let json = serde_json::to_vec(&summary).unwrap_or_default();
+        Sha1::digest(&json)
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    /// Whether `expected` (with or without a `0x` prefix) matches [`summary_hash`](Self::summary_hash)
+    pub fn verify_hash(&self, expected: &str) -> bool {
+        let expected = expected.strip_prefix("0x").unwrap_or(expected);
+        self.summary_hash().eq_ignore_ascii_case(expected)
+    }
+
+    /// Whether the book is uncrossed and its last checked hash matched the server's
+    pub fn is_in_sync(&self) -> bool {
+        self.is_valid() && !self.hash_mismatch
+    }
+
+    /// Check `expected` and remember the outcome for [`is_in_sync`](Self::is_in_sync)
+    fn check_hash(&mut self, expected: &str) {
+        if expected.is_empty() {
+            return;
+        }
+        self.hash_mismatch = !self.verify_hash(expected);
+        if self.hash_mismatch {
+            warn!(
+                "book {} hash mismatch: server {} local {}",
+                self.token_id,
+                expected,
+                self.summary_hash()
+            );
+        }
+    }
 }
 
 /// Market impact calculation result
@@ -647,6 +1066,125 @@ pub struct MarketImpact {
     pub size_filled: Decimal,   // How much of your order got filled
 }
 
+/// Level counts from `OrderBook::apply_price_change_batch`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ApplyResult {
+    pub levels_added: u32,   // New price levels created
+    pub levels_removed: u32, // Existing levels cleared by a zero size
+    pub levels_updated: u32, // Existing levels given a new size
+}
+
+impl ApplyResult {
+    /// Total number of levels touched by the batch
+    pub fn levels_changed(&self) -> u32 {
+        self.levels_added + self.levels_removed + self.levels_updated
+    }
+}
+
+/// When [`OrderBookManager`] cross-checks books against REST snapshots
+///
+/// Folding thousands of price changes into a book can leave it wrong in ways a hash
+/// check only reports, for instance after a dropped frame. A book is due once either
+/// limit is reached; a full `book` event from the feed restarts both.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReconcileConfig {
+    /// Incremental updates (price change entries or deltas) between checks
+    pub every_updates: Option<u64>,
+    /// Time between checks, counted only while updates arrive
+    pub every: Option<std::time::Duration>,
+    /// Level size difference tolerated before the local levels are replaced
+    pub tolerance: Decimal,
+}
+
+impl Default for ReconcileConfig {
+    /// Every 1000 updates or five minutes, replacing on any difference
+    fn default() -> Self {
+        Self {
+            every_updates: Some(1000),
+            every: Some(std::time::Duration::from_secs(300)),
+            tolerance: Decimal::ZERO,
+        }
+    }
+}
+
+/// Outcome of checking one book against a snapshot
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BookReconciliation {
+    pub token_id: String,
+    /// Largest size difference found at any level
+    pub divergence: Decimal,
+    /// Whether the local levels were replaced by the snapshot
+    pub replaced: bool,
+    /// Incremental updates applied since the previous check
+    pub updates: u64,
+}
+
+/// Below this many existing levels per update, rebuilding a side costs more than
+/// updating the touched levels in place (see `benches/book_batch.rs`)
+const MERGE_MIN_UPDATE_RATIO: usize = 4;
+
+/// Merge a batch of `(price, size)` updates into one side of the book
+///
+/// The updates are sorted in place (stably, so later updates to the same price
+/// win), then walked alongside the existing levels in a single pass. Small
+/// batches against deep books are applied level by level instead, since a
+/// rebuild would touch every level for the sake of a few.
+fn merge_levels(levels: &mut Levels, updates: &mut [(Price, Qty)], result: &mut ApplyResult) {
+    if updates.is_empty() {
+        return;
+    }
+    updates.sort_by_key(|&(price, _)| price);
+
+    // Only the last update of a run of equal prices counts
+    let last_per_price = updates
+        .iter()
+        .enumerate()
+        .filter(|&(i, &(price, _))| updates.get(i + 1).is_none_or(|&(next, _)| next != price))
+        .map(|(_, &update)| update);
+
+    if updates.len() * MERGE_MIN_UPDATE_RATIO < levels.len() {
+        for (price, size) in last_per_price {
+            if size == 0 {
+                if levels.remove(&price).is_some() {
+                    result.levels_removed += 1;
+                }
+            } else if levels.insert(price, size).is_some() {
+                result.levels_updated += 1;
+            } else {
+                result.levels_added += 1;
+            }
+        }
+        return;
+    }
+
+    let mut existing = std::mem::take(levels).into_iter().peekable();
+    let mut merged = Vec::with_capacity(existing.len() + updates.len());
+
+    for (price, size) in last_per_price {
+        while let Some(level) = existing.next_if(|&(p, _)| p < price) {
+            merged.push(level);
+        }
+
+        let current = existing.next_if(|&(p, _)| p == price);
+        match (current, size) {
+            (Some(_), 0) => result.levels_removed += 1,
+            (Some(_), size) => {
+                result.levels_updated += 1;
+                merged.push((price, size));
+            }
+            (None, 0) => {} // Clearing a level we never had
+            (None, size) => {
+                result.levels_added += 1;
+                merged.push((price, size));
+            }
+        }
+    }
+    merged.extend(existing);
+
+    // Collecting sorted input builds the tree in linear time
+    *levels = merged.into_iter().collect();
+}
+
 /// Thread-safe order book manager
 /// This manages multiple order books (one per token) and handles concurrent access
 /// Multiple threads can read/write different books simultaneously
@@ -661,6 +1199,8 @@ pub struct MarketImpact {
 pub struct OrderBookManager {
     books: Arc<RwLock<std::collections::HashMap<String, OrderBook>>>, // Token ID -> OrderBook
     max_depth: usize,
+    verify_hashes: bool,
+    reconcile: Option<ReconcileConfig>,
 }
 
 impl OrderBookManager {
@@ -670,7 +1210,87 @@ impl OrderBookManager {
         Self {
             books: Arc::new(RwLock::new(std::collections::HashMap::new())),
             max_depth,
+            verify_hashes: false,
+            reconcile: None,
+        }
+    }
+
+    /// Check server book hashes on every applied event
+    ///
+    /// Books whose reconstruction drifts from the server's are reported by
+    /// [`out_of_sync_books`](Self::out_of_sync_books) until the next matching hash
+    /// (usually a fresh snapshot). See [`OrderBook::summary_hash`] for the depth caveat.
+    pub fn with_hash_verification(mut self) -> Self {
+        self.verify_hashes = true;
+        self
+    }
+
+    /// Cross-check books against REST snapshots on `config`'s schedule
+    ///
+    /// Call [`reconcile_due`](Self::reconcile_due) periodically (say, once a second)
+    /// to run the checks that have come due.
+    pub fn with_reconciliation(mut self, config: ReconcileConfig) -> Self {
+        self.reconcile = Some(config);
+        self
+    }
+
+    /// Tokens whose books are due for a check; empty without
+    /// [`with_reconciliation`](Self::with_reconciliation)
+    pub fn books_due_for_reconciliation(&self) -> Result<Vec<String>> {
+        let Some(config) = self.reconcile else {
+            return Ok(Vec::new());
+        };
+        let books = self
+            .books
+            .read()
+            .map_err(|_| PolyError::internal_simple("Failed to acquire book lock"))?;
+
+        let now = Utc::now();
+        Ok(books
+            .values()
+            .filter(|book| book.reconciliation_due(&config, now))
+            .map(|book| book.token_id.clone())
+            .collect())
+    }
+
+    /// Check the book for `snapshot.asset_id` against it, replacing the levels past
+    /// the configured tolerance (zero without [`with_reconciliation`](Self::with_reconciliation))
+    pub fn reconcile_book(&self, snapshot: &OrderBookSummary) -> Result<BookReconciliation> {
+        let tolerance = self
+            .reconcile
+            .map_or(Decimal::ZERO, |config| config.tolerance);
+        let mut books = self
+            .books
+            .write()
+            .map_err(|_| PolyError::internal_simple("Failed to acquire book lock"))?;
+
+        let book = books.get_mut(&snapshot.asset_id).ok_or_else(|| {
+            PolyError::market_data(
+                format!("No book found for token: {}", snapshot.asset_id),
+                crate::errors::MarketDataErrorKind::TokenNotFound,
+            )
+        })?;
+        Ok(book.reconcile(snapshot, tolerance))
+    }
+
+    /// Fetch snapshots for every book that is due and reconcile against them
+    ///
+    /// One batched `/books` request covers all due books. Returns one event per
+    /// book checked, whether or not it was replaced.
+    pub async fn reconcile_due(
+        &self,
+        client: &crate::client::ClobClient,
+    ) -> Result<Vec<BookReconciliation>> {
+        let due = self.books_due_for_reconciliation()?;
+        if due.is_empty() {
+            return Ok(Vec::new());
         }
+        let snapshots = client.get_order_books(&due).await?;
+        snapshots
+            .iter()
+            .filter(|snapshot| due.contains(&snapshot.asset_id))
+            .map(|snapshot| self.reconcile_book(snapshot))
+            .collect()
     }
 
     /// Get or create an order book for a token
@@ -711,6 +1331,73 @@ impl OrderBookManager {
         book.apply_delta(delta)
     }
 
+    /// Feed a borrowed market channel event straight into the books
+    ///
+    /// Book snapshots create the book if we haven't seen the token yet; price
+    /// changes are batched per token and skip tokens we have no book for.
+    /// Tick size and trade events don't touch the levels, only the market parameters
+    /// the book hash covers.
+    pub fn apply_event(&self, event: &WssMarketEventRef<'_>) -> Result<()> {
+        let mut books = self
+            .books
+            .write()
+            .map_err(|_| PolyError::internal_simple("Failed to acquire book lock"))?;
+
+        match event {
+            WssMarketEventRef::Book(snapshot) => {
+                let asset_id = snapshot.asset_id.as_ref();
+                if !books.contains_key(asset_id) {
+                    books.insert(
+                        asset_id.to_string(),
+                        OrderBook::new(asset_id.to_string(), self.max_depth),
+                    );
+                }
+                if let Some(book) = books.get_mut(asset_id) {
+                    book.apply_book_snapshot(&snapshot.bids, &snapshot.asks);
+                    book.set_server_context(&snapshot.market, &snapshot.timestamp);
+                    if self.verify_hashes {
+                        book.check_hash(&snapshot.hash);
+                    }
+                }
+            }
+            WssMarketEventRef::PriceChange(message) => {
+                // Entries usually arrive grouped by asset, so batch consecutive runs
+                let mut changes = Vec::with_capacity(message.price_changes.len());
+                for (i, entry) in message.price_changes.iter().enumerate() {
+                    changes.push(PriceChange::from(entry));
+                    let run_ends = message
+                        .price_changes
+                        .get(i + 1)
+                        .is_none_or(|next| next.asset_id != entry.asset_id);
+                    if run_ends {
+                        if let Some(book) = books.get_mut(entry.asset_id.as_ref()) {
+                            book.apply_price_change_batch(&changes);
+                            book.set_server_context(&message.market, &message.timestamp);
+                            // The last entry's hash describes the book after the whole run
+                            if self.verify_hashes {
+                                book.check_hash(&entry.hash);
+                            }
+                        }
+                        changes.clear();
+                    }
+                }
+            }
+            WssMarketEventRef::TickSizeChange(change) => {
+                if let Some(book) = books.get_mut(&change.asset_id) {
+                    book.hash_tick_size = Some(change.new_tick_size.to_string());
+                }
+            }
+            WssMarketEventRef::LastTrade(trade) => {
+                if let Some(book) = books.get_mut(&trade.asset_id) {
+                    book.set_last_trade_price(trade.price);
+                }
+            }
+            WssMarketEventRef::SubscriptionError(_) => {}
+        }
+
+        Ok(())
+    }
+
     /// Get a book snapshot
     /// Returns a copy of the current book state that won't change
     pub fn get_book(&self, token_id: &str) -> Result<crate::types::OrderBook> {
@@ -741,6 +1428,20 @@ impl OrderBookManager {
         Ok(books.values().map(|book| book.snapshot()).collect())
     }
 
+    /// Tokens whose book is crossed or failed its last hash check
+    pub fn out_of_sync_books(&self) -> Result<Vec<String>> {
+        let books = self
+            .books
+            .read()
+            .map_err(|_| PolyError::internal_simple("Failed to acquire book lock"))?;
+
+        Ok(books
+            .values()
+            .filter(|book| !book.is_in_sync())
+            .map(|book| book.token_id.clone())
+            .collect())
+    }
+
     /// Remove stale books
     /// Cleans up books that haven't been updated recently (probably disconnected)
     /// This prevents memory leaks from accumulating dead books
@@ -817,6 +1518,7 @@ impl OrderBook {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
     use rust_decimal_macros::dec;
     use std::str::FromStr;
     use std::time::Duration; // Convenient macro for creating Decimal literals
@@ -1135,4 +1837,405 @@ mod tests {
         assert!(spread_fast.is_some()); // Should have a spread
         assert!(mid_fast.is_some()); // Should have a mid price
     }
+
+    fn change(side: Side, price: Decimal, size: Decimal) -> PriceChange {
+        PriceChange { side, price, size }
+    }
+
+    #[test]
+    fn test_price_change_batch_counts() {
+        let mut book = OrderBook::new("test_token".to_string(), 10);
+        book.apply_bid_delta(dec!(0.50), dec!(100));
+        book.apply_bid_delta(dec!(0.49), dec!(50));
+        book.apply_ask_delta(dec!(0.52), dec!(75));
+
+        let result = book.apply_price_change_batch(&[
+            change(Side::BUY, dec!(0.51), dec!(10)),  // new level
+            change(Side::BUY, dec!(0.49), dec!(0)),   // removes an existing level
+            change(Side::SELL, dec!(0.52), dec!(80)), // resizes an existing level
+            change(Side::SELL, dec!(0.60), dec!(0)),  // clears a level we don't have
+        ]);
+
+        assert_eq!(
+            result,
+            ApplyResult {
+                levels_added: 1,
+                levels_removed: 1,
+                levels_updated: 1,
+            }
+        );
+        assert_eq!(book.best_bid().unwrap().price, dec!(0.51));
+        assert_eq!(book.bids(None).len(), 2);
+        assert_eq!(book.best_ask().unwrap().size, dec!(80));
+    }
+
+    #[test]
+    fn test_price_change_batch_last_update_wins() {
+        let mut book = OrderBook::new("test_token".to_string(), 10);
+        let result = book.apply_price_change_batch(&[
+            change(Side::BUY, dec!(0.40), dec!(10)),
+            change(Side::BUY, dec!(0.40), dec!(0)),
+            change(Side::BUY, dec!(0.40), dec!(25)),
+        ]);
+
+        assert_eq!(result.levels_added, 1);
+        assert_eq!(book.best_bid().unwrap().size, dec!(25));
+    }
+
+    #[test]
+    fn test_price_change_batch_matches_sequential() {
+        let mut batched = OrderBook::new("test_token".to_string(), 20);
+        let mut sequential = OrderBook::new("test_token".to_string(), 20);
+        batched.set_tick_size(dec!(0.01)).unwrap();
+        sequential.set_tick_size(dec!(0.01)).unwrap();
+
+        let changes: Vec<PriceChange> = (0..60u32)
+            .map(|i| {
+                let side = if i % 3 == 0 { Side::SELL } else { Side::BUY };
+                let price = Decimal::from(i * 7 % 40 + 30) / dec!(100);
+                let size = Decimal::from(i % 4 * 25);
+                change(side, price, size)
+            })
+            .chain([change(Side::BUY, dec!(0.555), dec!(10))]) // misaligned, skipped
+            .collect();
+
+        batched.apply_price_change_batch(&changes);
+        for (i, c) in changes.iter().enumerate() {
+            let delta = OrderDelta {
+                token_id: "test_token".to_string(),
+                timestamp: Utc::now(),
+                side: c.side,
+                price: c.price,
+                size: c.size,
+                sequence: i as u64 + 1,
+            };
+            let _ = sequential.apply_delta(delta);
+        }
+
+        assert_eq!(batched.bids_fast(None), sequential.bids_fast(None));
+        assert_eq!(batched.asks_fast(None), sequential.asks_fast(None));
+    }
+
+    #[test]
+    fn test_manager_applies_borrowed_events() {
+        let manager = OrderBookManager::new(10);
+        let frame = r#"[
+            {"event_type": "book", "asset_id": "123", "market": "0xabc", "timestamp": "1",
+             "hash": "0x01", "bids": [{"price": "0.48", "size": "30"}, {"price": "0.49", "size": "20"}],
+             "asks": [{"price": "0.52", "size": "25"}]},
+            {"event_type": "price_change", "market": "0xabc", "timestamp": "2", "price_changes": [
+                {"asset_id": "123", "price": "0.50", "size": "15", "side": "BUY", "hash": "0x02",
+                 "best_bid": "0.50", "best_ask": "0.52"},
+                {"asset_id": "123", "price": "0.52", "size": "0", "side": "SELL", "hash": "0x03",
+                 "best_bid": "0.50", "best_ask": "0"},
+                {"asset_id": "456", "price": "0.10", "size": "5", "side": "BUY", "hash": "0x04",
+                 "best_bid": "0.10", "best_ask": "0"}
+            ]}
+        ]"#;
+
+        for event in crate::wss::parse_market_events_borrowed(frame).unwrap() {
+            manager.apply_event(&event).unwrap();
+        }
+
+        let book = manager.get_book("123").unwrap();
+        assert_eq!(book.bids.len(), 3);
+        assert_eq!(book.bids[0].price, dec!(0.50));
+        assert!(book.asks.is_empty());
+        // No snapshot was seen for 456, so its changes are dropped
+        assert!(manager.get_book("456").is_err());
+    }
+
+    #[test]
+    fn test_hash_verification_flags_drift() {
+        // Computed outside this crate with Python's hashlib, over the summary JSON that
+        // py-clob-client's generate_orderbook_summary_hash builds for the snapshot below:
+        // first with no market parameters known, then with all of them
+        let hash = "305e4899f3b8610fc342d0e03e175e36153167f5";
+        let signed_hash = "0c7011f264eae480a5d9fcfdbb16046c2d84d51d";
+        let frame = format!(
+            r#"[
+            {{"event_type": "book", "asset_id": "123", "market": "0xabc", "timestamp": "1",
+             "hash": "0x{hash}", "bids": [{{"price": "0.48", "size": "30"}}, {{"price": "0.49", "size": "20"}}],
+             "asks": [{{"price": "0.53", "size": "40"}}, {{"price": "0.52", "size": "25"}}]}}
+        ]"#
+        );
+        let manager = OrderBookManager::new(10).with_hash_verification();
+        manager.get_or_create_book("123").unwrap();
+        for event in crate::wss::parse_market_events_borrowed(&frame).unwrap() {
+            manager.apply_event(&event).unwrap();
+        }
+        assert!(manager.out_of_sync_books().unwrap().is_empty());
+
+        // A change whose hash doesn't describe our resulting book marks it out of sync
+        let drift = r#"[{"event_type": "price_change", "market": "0xabc", "timestamp": "2",
+            "price_changes": [{"asset_id": "123", "price": "0.50", "size": "15", "side": "BUY",
+             "hash": "00", "best_bid": "0.50", "best_ask": "0.52"}]}]"#;
+        for event in crate::wss::parse_market_events_borrowed(drift).unwrap() {
+            manager.apply_event(&event).unwrap();
+        }
+        assert_eq!(
+            manager.out_of_sync_books().unwrap(),
+            vec!["123".to_string()]
+        );
+
+        let mut book = OrderBook::new("123".to_string(), 10);
+        book.set_server_context("0xabc", "1");
+        book.apply_book_snapshot(
+            &[
+                OrderSummary {
+                    price: dec!(0.48),
+                    size: dec!(30),
+                },
+                OrderSummary {
+                    price: dec!(0.49),
+                    size: dec!(20),
+                },
+            ],
+            &[
+                OrderSummary {
+                    price: dec!(0.52),
+                    size: dec!(25),
+                },
+                OrderSummary {
+                    price: dec!(0.53),
+                    size: dec!(40),
+                },
+            ],
+        );
+        assert!(book.verify_hash(&hash.to_uppercase()));
+        assert!(!book.verify_hash("0x00"));
+
+        // The market parameters are signed too
+        book.set_market_params(dec!(5), dec!(0.01), false);
+        book.set_last_trade_price(dec!(0.51));
+        assert!(book.verify_hash(signed_hash));
+        assert!(!book.verify_hash(hash));
+    }
+
+    #[tokio::test]
+    async fn test_reconciliation_corrects_diverged_book() {
+        let manager = OrderBookManager::new(10).with_reconciliation(ReconcileConfig {
+            every_updates: Some(3),
+            every: None,
+            tolerance: dec!(0.5),
+        });
+        let snapshot = r#"[{"event_type": "book", "asset_id": "123", "market": "0xabc",
+            "timestamp": "1", "hash": "", "bids": [{"price": "0.48", "size": "30"}],
+            "asks": [{"price": "0.52", "size": "25"}]}]"#;
+        for event in crate::wss::parse_market_events_borrowed(snapshot).unwrap() {
+            manager.apply_event(&event).unwrap();
+        }
+
+        // Two updates whose sizes disagree with what the server ends up holding
+        let change = |size: &str| {
+            format!(
+                r#"[{{"event_type": "price_change", "market": "0xabc", "timestamp": "2",
+                "price_changes": [{{"asset_id": "123", "price": "0.48", "size": "{size}",
+                 "side": "BUY", "hash": "", "best_bid": "0.48", "best_ask": "0.52"}}]}}]"#
+            )
+        };
+        for size in ["31", "33"] {
+            for event in crate::wss::parse_market_events_borrowed(&change(size)).unwrap() {
+                manager.apply_event(&event).unwrap();
+            }
+        }
+        assert!(manager.books_due_for_reconciliation().unwrap().is_empty());
+        for event in crate::wss::parse_market_events_borrowed(&change("36")).unwrap() {
+            manager.apply_event(&event).unwrap();
+        }
+        assert_eq!(manager.books_due_for_reconciliation().unwrap(), ["123"]);
+
+        let mut server = crate::testkit::MockExchange::empty().await;
+        let books = server
+            .mock("POST", "/books")
+            .with_status(200)
+            .with_body(
+                serde_json::json!([{
+                    "market": "0xabc", "asset_id": "123", "hash": "0xfresh", "timestamp": "3",
+                    "bids": [{"price": "0.48", "size": "35"}, {"price": "0.47", "size": "10"}],
+                    "asks": [{"price": "0.52", "size": "25"}]
+                }])
+                .to_string(),
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let client = crate::client::ClobClient::new(&server.url());
+
+        let events = manager.reconcile_due(&client).await.unwrap();
+        assert_eq!(
+            events,
+            [BookReconciliation {
+                token_id: "123".to_string(),
+                divergence: dec!(10),
+                replaced: true,
+                updates: 3,
+            }]
+        );
+        let book = manager.get_book("123").unwrap();
+        assert_eq!(book.bids.len(), 2);
+        assert_eq!(book.bids[0].size, dec!(35));
+
+        // Nothing is due again until three more updates arrive
+        assert!(manager.reconcile_due(&client).await.unwrap().is_empty());
+        books.assert_async().await;
+
+        // Within tolerance the levels are kept
+        let mut local = OrderBook::new("123".to_string(), 10);
+        local.apply_book_snapshot(
+            &[OrderSummary {
+                price: dec!(0.48),
+                size: dec!(30.25),
+            }],
+            &[],
+        );
+        let fresh: OrderBookSummary = serde_json::from_value(serde_json::json!({
+            "market": "0xabc", "asset_id": "123", "hash": "", "timestamp": "4",
+            "bids": [{"price": "0.48", "size": "30"}], "asks": []
+        }))
+        .unwrap();
+        let outcome = local.reconcile(&fresh, dec!(0.5));
+        assert_eq!(outcome.divergence, dec!(0.25));
+        assert!(!outcome.replaced);
+        assert_eq!(local.bids(None)[0].size, dec!(30.25));
+    }
+
+    #[test]
+    fn test_reconcile_against_truncated_snapshot_compares_top_levels() {
+        let level = |price, size| OrderSummary { price, size };
+        let mut local = OrderBook::new("123".to_string(), 10);
+        local.apply_book_snapshot(
+            &[level(dec!(0.48), dec!(30)), level(dec!(0.40), dec!(50))],
+            &[level(dec!(0.52), dec!(20)), level(dec!(0.60), dec!(5))],
+        );
+        let mut snapshot: OrderBookSummary = serde_json::from_value(serde_json::json!({
+            "market": "0xabc", "asset_id": "123", "hash": "", "timestamp": "4",
+            "bids": [{"price": "0.40", "size": "50"}, {"price": "0.48", "size": "30"}],
+            "asks": [{"price": "0.60", "size": "5"}, {"price": "0.52", "size": "20"}]
+        }))
+        .unwrap();
+        snapshot.truncate_to_depth(1);
+        assert!(snapshot.truncated);
+
+        // The levels past the snapshot's depth aren't missing, just unreported
+        let outcome = local.reconcile(&snapshot, Decimal::ZERO);
+        assert_eq!(outcome.divergence, Decimal::ZERO);
+        assert!(!outcome.replaced);
+        assert_eq!(local.bids(None).len(), 2);
+        assert!(!local.is_truncated());
+
+        // A real change at the top still replaces, and the book remembers the cut
+        snapshot.bids[0].size = dec!(25);
+        let outcome = local.reconcile(&snapshot, Decimal::ZERO);
+        assert_eq!(outcome.divergence, dec!(5));
+        assert!(outcome.replaced);
+        assert!(local.is_truncated());
+        assert_eq!(local.bids(None).len(), 1);
+        assert_eq!(
+            local.liquidity_in_range(dec!(0.01), dec!(0.99), Side::SELL),
+            dec!(25)
+        );
+
+        // A full snapshot clears it
+        local.apply_book_snapshot(&[level(dec!(0.48), dec!(30))], &[]);
+        assert!(!local.is_truncated());
+    }
+
+    #[test]
+    fn test_check_invariants_reports_violations() {
+        let mut book = OrderBook::new("test_token".to_string(), 2);
+        book.apply_bid_delta(dec!(0.50), dec!(100));
+        book.apply_ask_delta(dec!(0.52), dec!(75));
+        assert_eq!(book.check_invariants(), Ok(()));
+
+        book.apply_ask_delta(dec!(0.49), dec!(10));
+        book.apply_ask_delta(dec!(0.55), dec!(10));
+        book.bids.insert(decimal_to_price(dec!(0.40)).unwrap(), 0);
+        let violations = book.check_invariants().unwrap_err();
+        assert_eq!(
+            violations,
+            [
+                "bid level at 0.40 has zero size",
+                "3 ask levels exceed max depth 2",
+                "book crossed: best bid 0.50 >= best ask 0.49",
+            ]
+        );
+    }
+
+    /// A book on a 0.01 grid with bids below 0.50 and asks above it
+    fn arb_order_book() -> impl Strategy<Value = OrderBook> {
+        let side = |cents| prop::collection::vec((cents, 1..=10_000i64), 0..30);
+        (1..=20usize, side(1..=49i64), side(51..=99i64)).prop_map(|(max_depth, bids, asks)| {
+            let levels = |levels: Vec<(i64, i64)>| -> Vec<OrderSummary> {
+                levels
+                    .into_iter()
+                    .map(|(cents, size)| OrderSummary {
+                        price: Decimal::new(cents, 2),
+                        size: Decimal::from(size),
+                    })
+                    .collect()
+            };
+            let mut book = OrderBook::new("test_token".to_string(), max_depth);
+            book.set_tick_size(dec!(0.01)).unwrap();
+            book.apply_book_snapshot(&levels(bids), &levels(asks));
+            book
+        })
+    }
+
+    /// Batches of changes that stay on their side of 0.50, some of them off the
+    /// 0.01 grid and about a quarter of them removals
+    fn arb_price_changes() -> impl Strategy<Value = Vec<PriceChange>> {
+        arb_changes(10..=490, 510..=990)
+    }
+
+    fn arb_changes(
+        bid_mills: std::ops::RangeInclusive<i64>,
+        ask_mills: std::ops::RangeInclusive<i64>,
+    ) -> impl Strategy<Value = Vec<PriceChange>> {
+        let size = prop_oneof![
+            1 => Just(Decimal::ZERO),
+            3 => (1..=1_000_000i64).prop_map(|hundredths| Decimal::new(hundredths, 2)),
+        ];
+        let one = prop_oneof![
+            (bid_mills, size.clone()).prop_map(|(mills, size)| change(
+                Side::BUY,
+                Decimal::new(mills, 3),
+                size
+            )),
+            (ask_mills, size).prop_map(|(mills, size)| change(
+                Side::SELL,
+                Decimal::new(mills, 3),
+                size
+            )),
+        ];
+        prop::collection::vec(one, 0..40)
+    }
+
+    proptest! {
+        #[test]
+        fn prop_price_change_batches_keep_invariants(
+            mut book in arb_order_book(),
+            batches in prop::collection::vec(arb_price_changes(), 1..8),
+        ) {
+            prop_assert_eq!(book.check_invariants(), Ok(()));
+            for batch in &batches {
+                book.apply_price_change_batch(batch);
+                prop_assert_eq!(book.check_invariants(), Ok(()));
+            }
+        }
+
+        #[test]
+        fn prop_crossing_changes_only_cross_the_book(
+            mut book in arb_order_book(),
+            batches in prop::collection::vec(arb_changes(10..=990, 10..=990), 1..8),
+        ) {
+            for batch in &batches {
+                book.apply_price_change_batch(batch);
+                if let Err(violations) = book.check_invariants() {
+                    prop_assert_eq!(violations.len(), 1);
+                    prop_assert!(violations[0].starts_with("book crossed"));
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,194 @@
+//! Storage for one side of an [`OrderBook`](super::OrderBook)
+//!
+//! By default a side is a `BTreeMap`, which allocates a node per handful of levels and
+//! frees them all whenever a snapshot replaces the side. With the `fast-book` feature
+//! it is a sorted ring buffer instead: a snapshot clears it and refills the same
+//! allocation, so a book that keeps receiving snapshots of a similar depth stops
+//! allocating after the first one. Lookups are binary searches; inserting or removing
+//! a level in the middle shifts its neighbours, which at book depths of a few hundred
+//! levels is a short `memmove`.
+//!
+//! Both variants expose the slice of the `BTreeMap` API the book uses, in ascending
+//! price order.
+
+use crate::types::{Price, Qty};
+
+#[cfg(not(feature = "fast-book"))]
+pub(crate) type Levels = std::collections::BTreeMap<Price, Qty>;
+
+/// Replace every level with `levels`, which may be unsorted; a later level at the same
+/// price replaces an earlier one
+#[cfg(not(feature = "fast-book"))]
+pub(crate) fn refill(side: &mut Levels, levels: impl Iterator<Item = (Price, Qty)>) {
+    *side = levels.collect();
+}
+
+#[cfg(feature = "fast-book")]
+pub(crate) use ring::Levels;
+
+#[cfg(feature = "fast-book")]
+pub(crate) fn refill(side: &mut Levels, levels: impl Iterator<Item = (Price, Qty)>) {
+    side.refill(levels);
+}
+
+#[cfg(feature = "fast-book")]
+mod ring {
+    use crate::types::{Price, Qty};
+    use std::collections::VecDeque;
+    use std::ops::RangeInclusive;
+
+    /// Price levels sorted ascending, one entry per price
+    #[derive(Debug, Clone, Default)]
+    pub(crate) struct Levels {
+        levels: VecDeque<(Price, Qty)>,
+    }
+
+    impl Levels {
+        pub(crate) fn new() -> Self {
+            Self::default()
+        }
+
+        pub(crate) fn len(&self) -> usize {
+            self.levels.len()
+        }
+
+        pub(crate) fn iter(&self) -> impl DoubleEndedIterator<Item = (&Price, &Qty)> + '_ {
+            self.levels.iter().map(|(price, size)| (price, size))
+        }
+
+        pub(crate) fn keys(&self) -> impl DoubleEndedIterator<Item = &Price> + '_ {
+            self.levels.iter().map(|(price, _)| price)
+        }
+
+        pub(crate) fn values(&self) -> impl DoubleEndedIterator<Item = &Qty> + '_ {
+            self.levels.iter().map(|(_, size)| size)
+        }
+
+        pub(crate) fn get(&self, price: &Price) -> Option<&Qty> {
+            let index = self.search(*price).ok()?;
+            Some(&self.levels[index].1)
+        }
+
+        pub(crate) fn insert(&mut self, price: Price, size: Qty) -> Option<Qty> {
+            match self.search(price) {
+                Ok(index) => Some(std::mem::replace(&mut self.levels[index].1, size)),
+                Err(index) => {
+                    self.levels.insert(index, (price, size));
+                    None
+                }
+            }
+        }
+
+        pub(crate) fn remove(&mut self, price: &Price) -> Option<Qty> {
+            let index = self.search(*price).ok()?;
+            self.levels.remove(index).map(|(_, size)| size)
+        }
+
+        pub(crate) fn pop_first(&mut self) -> Option<(Price, Qty)> {
+            self.levels.pop_front()
+        }
+
+        pub(crate) fn pop_last(&mut self) -> Option<(Price, Qty)> {
+            self.levels.pop_back()
+        }
+
+        pub(crate) fn range(
+            &self,
+            range: RangeInclusive<Price>,
+        ) -> impl DoubleEndedIterator<Item = (&Price, &Qty)> + '_ {
+            let start = self
+                .levels
+                .partition_point(|&(price, _)| price < *range.start());
+            let end = self
+                .levels
+                .partition_point(|&(price, _)| price <= *range.end())
+                .max(start);
+            self.levels
+                .range(start..end)
+                .map(|(price, size)| (price, size))
+        }
+
+        /// Clear and refill in place, keeping the allocation
+        pub(crate) fn refill(&mut self, levels: impl Iterator<Item = (Price, Qty)>) {
+            self.levels.clear();
+            self.levels.extend(levels);
+            self.normalize();
+        }
+
+        fn search(&self, price: Price) -> Result<usize, usize> {
+            self.levels.binary_search_by_key(&price, |&(p, _)| p)
+        }
+
+        /// Sort by price and keep the last entry of each price, as collecting into a
+        /// map would
+        fn normalize(&mut self) {
+            let levels = self.levels.make_contiguous();
+            if levels.is_sorted_by(|a, b| a.0 < b.0) {
+                return;
+            }
+            levels.sort_by_key(|&(price, _)| price);
+
+            let mut kept = 0;
+            for i in 0..levels.len() {
+                if kept > 0 && levels[kept - 1].0 == levels[i].0 {
+                    levels[kept - 1].1 = levels[i].1;
+                } else {
+                    levels[kept] = levels[i];
+                    kept += 1;
+                }
+            }
+            self.levels.truncate(kept);
+        }
+    }
+
+    impl FromIterator<(Price, Qty)> for Levels {
+        fn from_iter<I: IntoIterator<Item = (Price, Qty)>>(iter: I) -> Self {
+            let mut levels = Self {
+                levels: iter.into_iter().collect(),
+            };
+            levels.normalize();
+            levels
+        }
+    }
+
+    impl IntoIterator for Levels {
+        type Item = (Price, Qty);
+        type IntoIter = std::collections::vec_deque::IntoIter<(Price, Qty)>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            self.levels.into_iter()
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_matches_map_semantics() {
+            let mut levels: Levels = [(30, 1), (10, 2), (30, 3), (20, 4)].into_iter().collect();
+            assert_eq!(
+                levels.iter().map(|(p, q)| (*p, *q)).collect::<Vec<_>>(),
+                [(10, 2), (20, 4), (30, 3)]
+            );
+
+            assert_eq!(levels.insert(15, 5), None);
+            assert_eq!(levels.insert(20, 6), Some(4));
+            assert_eq!(levels.remove(&10), Some(2));
+            assert_eq!(levels.remove(&10), None);
+            assert_eq!(levels.get(&20), Some(&6));
+            assert_eq!(
+                levels.range(15..=25).map(|(p, _)| *p).collect::<Vec<_>>(),
+                [15, 20]
+            );
+            assert_eq!(levels.range(40..=50).count(), 0);
+            assert_eq!(levels.pop_first(), Some((15, 5)));
+            assert_eq!(levels.pop_last(), Some((30, 3)));
+
+            let capacity = levels.levels.capacity();
+            levels.refill([(5, 1), (1, 1)].into_iter());
+            assert_eq!(levels.keys().copied().collect::<Vec<_>>(), [1, 5]);
+            assert_eq!(levels.levels.capacity(), capacity);
+        }
+    }
+}
@@ -0,0 +1,226 @@
+//! Circuit breaking for sustained API outages
+//!
+//! A [`CircuitBreaker`] set with
+//! [`ClobClient::with_circuit_breaker`](crate::client::ClobClient::with_circuit_breaker)
+//! watches every request the client sends. A request that gets no response, or a 5xx
+//! one, is a failure; any other response resets the count. Once
+//! [`threshold`](CircuitBreaker::threshold) failures in a row fall within
+//! [`window`](CircuitBreaker::window) of the first of them, the circuit opens and
+//! requests fail with [`PolyError::CircuitOpen`](crate::errors::PolyError::CircuitOpen)
+//! before reaching the network.
+//!
+//! After [`recovery`](CircuitBreaker::recovery) the circuit half-opens: the next request
+//! goes out as a trial while concurrent ones keep failing fast. The trial succeeding
+//! closes the circuit; failing opens it for another `recovery`. A trial dropped before it
+//! completes says nothing about the API, so the request after it becomes the trial.
+
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// When to stop sending requests to a failing API, and when to try again
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitBreaker {
+    /// Consecutive failures that open the circuit; zero behaves as one
+    pub threshold: u32,
+    /// Span the failures must fall within, from the first of them
+    pub window: Duration,
+    /// How long the circuit stays open before a trial request
+    pub recovery: Duration,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self {
+            threshold: 5,
+            window: Duration::from_secs(30),
+            recovery: Duration::from_secs(30),
+        }
+    }
+}
+
+/// A [`CircuitBreaker`] and where the client's requests stand against it
+#[derive(Debug)]
+pub(crate) struct Circuit {
+    config: CircuitBreaker,
+    state: Mutex<State>,
+}
+
+#[derive(Debug)]
+enum State {
+    /// Failures in a row so far, and when the first of them happened
+    Closed {
+        failures: u32,
+        since: Option<Instant>,
+    },
+    Open {
+        until: Instant,
+    },
+    /// A trial request is in flight; others are turned away until `retry_after`
+    HalfOpen {
+        retry_after: Instant,
+    },
+}
+
+impl Circuit {
+    pub(crate) fn new(config: CircuitBreaker) -> Self {
+        Self {
+            config,
+            state: Mutex::new(State::Closed {
+                failures: 0,
+                since: None,
+            }),
+        }
+    }
+
+    /// Let a request through, or return when the circuit will next allow one
+    pub(crate) fn admit(&self) -> std::result::Result<Permit<'_>, Instant> {
+        let now = Instant::now();
+        let mut state = self.lock();
+        let trial = match *state {
+            State::Closed { .. } => false,
+            State::Open { until } if now < until => return Err(until),
+            State::Open { .. } => {
+                *state = State::HalfOpen {
+                    retry_after: now + self.config.recovery,
+                };
+                true
+            }
+            State::HalfOpen { retry_after } => return Err(retry_after),
+        };
+        Ok(Permit {
+            circuit: self,
+            trial,
+            recorded: false,
+        })
+    }
+
+    fn record(&self, trial: bool, success: bool) {
+        let now = Instant::now();
+        let mut state = self.lock();
+        match &mut *state {
+            State::HalfOpen { .. } if trial => {
+                if success {
+                    info!("trial request succeeded, circuit closed");
+                    *state = State::Closed {
+                        failures: 0,
+                        since: None,
+                    };
+                } else {
+                    warn!(recovery = ?self.config.recovery, "trial request failed, circuit reopened");
+                    *state = State::Open {
+                        until: now + self.config.recovery,
+                    };
+                }
+            }
+            State::Closed { failures, since } => {
+                if success {
+                    (*failures, *since) = (0, None);
+                    return;
+                }
+                match since {
+                    Some(first) if now.duration_since(*first) <= self.config.window => {
+                        *failures += 1
+                    }
+                    _ => (*failures, *since) = (1, Some(now)),
+                }
+                if *failures >= self.config.threshold.max(1) {
+                    warn!(failures = *failures, recovery = ?self.config.recovery, "circuit opened");
+                    *state = State::Open {
+                        until: now + self.config.recovery,
+                    };
+                }
+            }
+            // Requests let through before the circuit opened don't move it
+            _ => {}
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// One request let through by a [`Circuit`]; report how it went with [`Permit::record`]
+pub(crate) struct Permit<'a> {
+    circuit: &'a Circuit,
+    trial: bool,
+    recorded: bool,
+}
+
+impl Permit<'_> {
+    pub(crate) fn record(mut self, success: bool) {
+        self.recorded = true;
+        self.circuit.record(self.trial, success);
+    }
+}
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        if self.trial && !self.recorded {
+            let mut state = self.circuit.lock();
+            if matches!(*state, State::HalfOpen { .. }) {
+                *state = State::Open {
+                    until: Instant::now(),
+                };
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn circuit(threshold: u32, window_ms: u64, recovery_ms: u64) -> Circuit {
+        Circuit::new(CircuitBreaker {
+            threshold,
+            window: Duration::from_millis(window_ms),
+            recovery: Duration::from_millis(recovery_ms),
+        })
+    }
+
+    #[test]
+    fn test_opens_after_consecutive_failures_and_recovers() {
+        let circuit = circuit(3, 60_000, 20);
+        circuit.admit().unwrap().record(false);
+        circuit.admit().unwrap().record(false);
+        // A success breaks the run
+        circuit.admit().unwrap().record(true);
+        for _ in 0..3 {
+            circuit.admit().unwrap().record(false);
+        }
+        let retry_after = circuit.admit().err().unwrap();
+        assert!(retry_after > Instant::now());
+
+        std::thread::sleep(Duration::from_millis(25));
+        let trial = circuit.admit().unwrap();
+        assert!(circuit.admit().is_err(), "only one trial at a time");
+        trial.record(false);
+        assert!(circuit.admit().is_err(), "failed trial reopens");
+
+        std::thread::sleep(Duration::from_millis(25));
+        circuit.admit().unwrap().record(true);
+        circuit.admit().unwrap().record(false);
+        assert!(circuit.admit().is_ok(), "success closed the circuit");
+    }
+
+    #[test]
+    fn test_failures_outside_window_and_dropped_trials() {
+        let circuit = circuit(2, 10, 0);
+        circuit.admit().unwrap().record(false);
+        std::thread::sleep(Duration::from_millis(15));
+        circuit.admit().unwrap().record(false);
+        assert!(
+            circuit.admit().is_ok(),
+            "first failure fell out of the window"
+        );
+
+        let circuit = self::circuit(1, 60_000, 0);
+        circuit.admit().unwrap().record(false);
+        drop(circuit.admit().unwrap());
+        // The abandoned trial leaves room for another
+        circuit.admit().unwrap().record(true);
+        assert!(matches!(*circuit.lock(), State::Closed { failures: 0, .. }));
+    }
+}
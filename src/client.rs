@@ -3,9 +3,14 @@
 //! This module provides a production-ready client for interacting with
 //! Polymarket, optimized for high-frequency trading environments.
 
-use crate::auth::{create_l1_headers, create_l2_headers};
+use crate::auth::{create_l1_headers, create_l2_headers_at};
+use crate::clock::{Clock, SystemClock};
+use crate::decode::NumberMode;
 use crate::errors::{PolyError, Result};
-use crate::types::{OrderOptions, PostOrder, SignedOrderRequest};
+use crate::failover::{FailoverPolicy, FailoverStats, HostPool};
+use crate::limiter::{LimiterStats, Priority, RateLimiter, RequestLimiter, RequestLimits};
+use crate::market_meta::{MarketMeta, MarketMetaCache};
+use crate::types::{OrderOptions, PostOrder, SignedOrderPreview, SignedOrderRequest};
 use alloy_primitives::{Address, U256};
 use alloy_signer_local::PrivateKeySigner;
 use async_trait::async_trait;
@@ -21,16 +26,193 @@ use serde::de::DeserializeOwned;
 use serde_json::{self, Value};
 use std::env;
 use std::str::FromStr;
+use std::sync::{Arc, Mutex, PoisonError};
 
+const DEFAULT_CLOB_BASE: &str = "https://clob.polymarket.com";
 const DEFAULT_GAMMA_BASE: &str = "https://gamma-api.polymarket.com";
 const DEFAULT_WS_BASE: &str = "wss://ws-subscriptions-clob.polymarket.com/ws/";
 const DEFAULT_RTDS_BASE: &str = "wss://ws-live-data.polymarket.com";
 const DEFAULT_DATA_API_BASE: &str = "https://data-api.polymarket.com";
 const GAMMA_MARKETS_LIMIT: u32 = 50;
+/// Token IDs per Gamma request when prefetching market metadata
+const META_PREFETCH_CHUNK: usize = 20;
+/// Longest query string sent to Gamma before a multi-value lookup is split
+const GAMMA_MAX_QUERY_LEN: usize = 4_000;
+/// Tokens per `/books` request when scanning spreads
+const SCAN_BATCH_SIZE: usize = 50;
+/// `/books` requests kept in flight by `scan_spreads`
+const SCAN_CONCURRENCY: usize = 4;
+/// Fills fetched for `get_market_microstructure`
+const MICROSTRUCTURE_TRADES_LIMIT: u32 = 500;
+/// Seconds a protocol insurance, stats or prediction index response is reused for
+const PROTOCOL_CACHE_TTL_SECS: u64 = 60;
+/// Seconds between reward epoch checks when epoch cache invalidation is on
+const EPOCH_POLL_SECS: u64 = 300;
+/// Highest-volume markets fetched as candidates for a prediction index
+const PREDICTION_INDEX_CANDIDATES: u32 = 100;
+/// Markets averaged into a prediction index
+const PREDICTION_INDEX_MARKETS: usize = 20;
+/// Positions per Data API page when listing the funder's positions
+const FUNDER_POSITIONS_PAGE: u32 = 500;
 
 // Re-export types for compatibility
 pub use crate::types::{ApiCredentials as ApiCreds, OrderType, Side};
 
+/// Sends a request through the client's concurrency limiter and host failover
+trait ClientSend {
+    async fn send_via(
+        self,
+        client: &ClobClient,
+    ) -> std::result::Result<reqwest::Response, SendError>;
+}
+
+impl ClientSend for RequestBuilder {
+    async fn send_via(
+        self,
+        client: &ClobClient,
+    ) -> std::result::Result<reqwest::Response, SendError> {
+        let (http_client, request) = self.build_split();
+        let request = request?;
+        let circuit = match &client.circuit {
+            Some(circuit) => Some(
+                circuit
+                    .admit()
+                    .map_err(|retry_after| SendError::CircuitOpen { retry_after })?,
+            ),
+            None => None,
+        };
+        if let Some(rate_limiter) = &client.rate_limiter {
+            rate_limiter.until_ready().await;
+        }
+        let result = dispatch(client, &http_client, request).await;
+        if let Some(circuit) = circuit {
+            circuit.record(matches!(&result, Ok(response) if !response.status().is_server_error()));
+        }
+        result
+    }
+}
+
+/// Hand `request` to the tower middleware when one is set, else to the host pools
+/// through the client's limiter
+///
+/// Middleware does its own limiting with a
+/// [`PolyRateLimitLayer`](crate::middleware::PolyRateLimitLayer), so the client's
+/// limiter is skipped rather than admitting each request twice.
+async fn dispatch(
+    client: &ClobClient,
+    http_client: &Client,
+    request: reqwest::Request,
+) -> std::result::Result<reqwest::Response, SendError> {
+    #[cfg(feature = "tower")]
+    if let Some(middleware) = &client.middleware {
+        use tower::ServiceExt;
+        return middleware
+            .clone()
+            .oneshot(request)
+            .await
+            .map_err(SendError::Middleware);
+    }
+    let _permit = match &client.request_limiter {
+        Some(limiter) => Some(
+            limiter
+                .acquire(Priority::for_request(
+                    request.method(),
+                    request.url().path(),
+                ))
+                .await,
+        ),
+        None => None,
+    };
+    let pools = [&client.clob_hosts, &client.gamma_hosts];
+    Ok(execute_request(http_client, pools.into_iter().flatten(), request).await?)
+}
+
+/// Send `request` on `http_client`, through whichever of `pools` covers its host
+pub(crate) async fn execute_request<'a>(
+    http_client: &Client,
+    pools: impl IntoIterator<Item = &'a Arc<HostPool>>,
+    request: reqwest::Request,
+) -> reqwest::Result<reqwest::Response> {
+    let pool = pools.into_iter().find(|pool| pool.covers(request.url()));
+    let timer = crate::telemetry::RequestTimer::start(request.url());
+    let result = match pool {
+        Some(pool) => pool.execute(http_client, request).await,
+        None => http_client.execute(request).await,
+    };
+    timer.finish(&result);
+    result
+}
+
+/// Failure to get a response: from the HTTP client itself, from tower middleware, or
+/// refused by the circuit breaker
+#[derive(Debug)]
+pub(crate) enum SendError {
+    Http(reqwest::Error),
+    CircuitOpen {
+        retry_after: std::time::Instant,
+    },
+    #[cfg(feature = "tower")]
+    Middleware(tower::BoxError),
+}
+
+impl std::fmt::Display for SendError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SendError::Http(err) => err.fmt(f),
+            SendError::CircuitOpen { .. } => f.write_str("circuit open"),
+            #[cfg(feature = "tower")]
+            SendError::Middleware(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for SendError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SendError::Http(err) => Some(err),
+            SendError::CircuitOpen { .. } => None,
+            #[cfg(feature = "tower")]
+            SendError::Middleware(err) => Some(err.as_ref()),
+        }
+    }
+}
+
+impl From<reqwest::Error> for SendError {
+    fn from(err: reqwest::Error) -> Self {
+        SendError::Http(err)
+    }
+}
+
+impl From<SendError> for PolyError {
+    fn from(err: SendError) -> Self {
+        match err {
+            SendError::Http(err) => err.into(),
+            SendError::CircuitOpen { retry_after } => PolyError::CircuitOpen { retry_after },
+            // Layers usually pass the transport's or our own errors through unchanged
+            #[cfg(feature = "tower")]
+            SendError::Middleware(err) => match err.downcast::<reqwest::Error>() {
+                Ok(err) => (*err).into(),
+                Err(err) => match err.downcast::<PolyError>() {
+                    Ok(err) => *err,
+                    Err(err) => PolyError::Network {
+                        message: format!("Request failed in middleware: {}", err),
+                        source: Some(err),
+                    },
+                },
+            },
+        }
+    }
+}
+
+/// A network error for a request that got no response, unless the circuit breaker
+/// refused it
+fn request_failed(err: SendError) -> PolyError {
+    match err {
+        SendError::CircuitOpen { .. } => err.into(),
+        err => PolyError::network(format!("Request failed: {}", err), err),
+    }
+}
+
 // Compatibility types
 #[derive(Debug, Clone)]
 pub struct OrderArgs {
@@ -49,6 +231,93 @@ impl OrderArgs {
             side,
         }
     }
+
+    /// Collateral the order trades if it fills completely: `price * size`
+    pub fn notional(&self) -> Decimal {
+        self.price * self.size
+    }
+
+    /// Most the order can lose if it fills completely
+    ///
+    /// A buy can lose what it pays, `price * size`. A sell gives up a token that pays
+    /// out 1 if its outcome wins, so it stands to lose `(1 - price) * size`.
+    pub fn max_loss(&self) -> Decimal {
+        match self.side {
+            Side::BUY => self.price * self.size,
+            Side::SELL => (Decimal::ONE - self.price) * self.size,
+        }
+    }
+}
+
+impl OrderArgs {
+    /// Set the price from the probability of `market`'s first ("Yes") outcome.
+    ///
+    /// `token_id` must already be set and belong to `market`; the second outcome is priced
+    /// at `1 - probability`. The price is rounded to the market's minimum tick size.
+    pub fn probability(
+        mut self,
+        probability: Decimal,
+        market: &crate::types::Market,
+    ) -> Result<Self> {
+        let price = market.price_for_probability(&self.token_id, probability)?;
+        self.price = crate::utils::math::round_to_tick(price, market.minimum_tick_size);
+        Ok(self)
+    }
+
+    /// Check the order against `market`'s own constraints before signing it.
+    ///
+    /// The token must belong to `market`, the price must sit on the market's tick inside
+    /// `[tick, 1 - tick]`, and the size must reach the minimum order size. Markets that
+    /// don't report a tick or minimum size fall back to
+    /// [`DEFAULT_MIN_TICK_SIZE`](crate::types::DEFAULT_MIN_TICK_SIZE) and
+    /// [`DEFAULT_MIN_ORDER_SIZE`](crate::types::DEFAULT_MIN_ORDER_SIZE) when parsed.
+    pub fn validate(&self, market: &crate::types::Market) -> Result<()> {
+        use crate::errors::OrderErrorKind;
+
+        if !market
+            .tokens
+            .iter()
+            .any(|token| token.token_id == self.token_id)
+        {
+            return Err(PolyError::validation(format!(
+                "Token {} is not part of market {}",
+                self.token_id, market.condition_id
+            )));
+        }
+
+        let tick = market.minimum_tick_size;
+        if !crate::utils::math::is_valid_price(self.price, tick) {
+            return Err(PolyError::order(
+                format!(
+                    "Price {} outside [{}, {}] for tick size {}",
+                    self.price,
+                    tick,
+                    Decimal::ONE - tick,
+                    tick
+                ),
+                OrderErrorKind::InvalidPrice,
+            ));
+        }
+        if !(self.price % tick).is_zero() {
+            return Err(PolyError::order(
+                format!(
+                    "Price {} is not a multiple of tick size {}",
+                    self.price, tick
+                ),
+                OrderErrorKind::PriceConstraint,
+            ));
+        }
+        if self.size < market.minimum_order_size {
+            return Err(PolyError::order(
+                format!(
+                    "Size {} below the market minimum of {}",
+                    self.size, market.minimum_order_size
+                ),
+                OrderErrorKind::SizeConstraint,
+            ));
+        }
+        Ok(())
+    }
 }
 
 impl Default for OrderArgs {
@@ -73,6 +342,12 @@ pub struct DataApiClient {
     base_url: String,
 }
 
+impl Default for DataApiClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl DataApiClient {
     /// Create a data API client using the default base URL.
     pub fn new() -> Self {
@@ -98,6 +373,15 @@ impl DataApiClient {
         }
     }
 
+    async fn send(&self, request: RequestBuilder) -> reqwest::Result<reqwest::Response> {
+        let (http_client, request) = request.build_split();
+        let request = request?;
+        let timer = crate::telemetry::RequestTimer::start(request.url());
+        let result = http_client.execute(request).await;
+        timer.finish(&result);
+        result
+    }
+
     /// Fetch the total positions value for a single user wallet.
     ///
     /// Returns the same structure as the `GET /value` public endpoint.
@@ -106,10 +390,11 @@ impl DataApiClient {
         user: &str,
     ) -> Result<Vec<crate::types::DataPositionValue>> {
         let response = self
-            .http_client
-            .get(self.data_api_url("value"))
-            .query(&[("user", user)])
-            .send()
+            .send(
+                self.http_client
+                    .get(self.data_api_url("value"))
+                    .query(&[("user", user)]),
+            )
             .await?;
 
         if !response.status().is_success() {
@@ -139,10 +424,11 @@ impl DataApiClient {
         query.push(("user", user.to_string()));
 
         let response = self
-            .http_client
-            .get(self.data_api_url("positions"))
-            .query(&query)
-            .send()
+            .send(
+                self.http_client
+                    .get(self.data_api_url("positions"))
+                    .query(&query),
+            )
             .await?;
 
         if !response.status().is_success() {
@@ -157,34 +443,313 @@ impl DataApiClient {
             .await
             .map_err(|e| PolyError::parse(format!("Failed to parse response: {}", e), None))
     }
+
+    /// Most recent fills in a market, newest first
+    ///
+    /// Wraps `GET /trades` filtered to `condition_id`; both of the market's tokens are
+    /// included, told apart by `asset`.
+    pub async fn get_market_trades(
+        &self,
+        condition_id: &str,
+        limit: u32,
+    ) -> Result<Vec<crate::types::DataTrade>> {
+        let response = self
+            .send(self.http_client.get(self.data_api_url("trades")).query(&[
+                ("market", condition_id.to_string()),
+                ("limit", limit.max(1).to_string()),
+            ]))
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(PolyError::api(
+                response.status().as_u16(),
+                "Failed to fetch market trades",
+            ));
+        }
+
+        response
+            .json::<Vec<crate::types::DataTrade>>()
+            .await
+            .map_err(|e| PolyError::parse(format!("Failed to parse response: {}", e), None))
+    }
+}
+
+/// The order id of a `POST /order` response, or the exchange's reason for refusing it
+pub(crate) fn accepted_order_id(response: &Value) -> Result<String> {
+    let success = response
+        .get("success")
+        .and_then(Value::as_bool)
+        .unwrap_or(true);
+    let order_id = response
+        .get("orderID")
+        .or_else(|| response.get("orderId"))
+        .and_then(Value::as_str)
+        .filter(|id| !id.is_empty());
+    match (success, order_id) {
+        (true, Some(id)) => Ok(id.to_string()),
+        _ => {
+            let reason = response
+                .get("errorMsg")
+                .and_then(Value::as_str)
+                .filter(|msg| !msg.is_empty())
+                .unwrap_or("order was not accepted");
+            Err(PolyError::order(
+                format!("Order rejected: {}", reason),
+                crate::errors::OrderErrorKind::ExecutionFailed,
+            ))
+        }
+    }
+}
+
+/// `address` in EIP-55 form, whatever case the config wrote it in
+fn checksummed(address: &str) -> Result<String> {
+    Address::from_str(address)
+        .map(|address| address.to_checksum(None))
+        .map_err(|e| PolyError::internal(format!("Invalid contract address {}", address), e))
+}
+
+/// Terminal states recognised by the fill-waiting helpers
+enum FillOutcome {
+    Filled,
+    Canceled,
+}
+
+fn fill_outcome(order: &crate::types::OpenOrder) -> Option<FillOutcome> {
+    let status = order.status.to_ascii_uppercase();
+    if status == "MATCHED"
+        || (order.original_size > Decimal::ZERO && order.size_matched >= order.original_size)
+    {
+        Some(FillOutcome::Filled)
+    } else if matches!(status.as_str(), "CANCELED" | "CANCELLED" | "UNMATCHED") {
+        Some(FillOutcome::Canceled)
+    } else {
+        None
+    }
+}
+
+/// Incremental `text/event-stream` parser yielding each event's `data` payload
+#[derive(Default)]
+struct SseDecoder {
+    buffer: Vec<u8>,
+    data: String,
+}
+
+impl SseDecoder {
+    /// Consume a body chunk and return the events it completed
+    fn feed(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(chunk);
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|byte| *byte == b'\n') {
+            let raw: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&raw);
+            let line = line.trim_end_matches(['\n', '\r']);
+
+            // A blank line dispatches the event; `event`, `id`, `retry` and `:` comments
+            // (keep-alives) carry nothing we use
+            if line.is_empty() {
+                if !self.data.is_empty() {
+                    events.push(std::mem::take(&mut self.data));
+                }
+            } else if let Some(value) = line.strip_prefix("data:") {
+                if !self.data.is_empty() {
+                    self.data.push('\n');
+                }
+                self.data.push_str(value.strip_prefix(' ').unwrap_or(value));
+            }
+        }
+        events
+    }
+}
+
+/// A raw Gamma `/markets` page plus the window it was requested with
+struct MarketsPage {
+    body: String,
+    offset: u64,
+    limit: u32,
+}
+
+impl MarketsPage {
+    /// `raw_count` is the number of records in the page before any were dropped, so the
+    /// cursor never stalls on pages thinned out by parse failures or the game window.
+    fn into_response(
+        self,
+        gamma_markets: Vec<crate::types::GammaMarket>,
+        raw_count: usize,
+        params: Option<&crate::types::GammaListParams>,
+    ) -> crate::types::MarketsResponse {
+        let next_cursor = if raw_count < self.limit as usize {
+            None
+        } else {
+            Some(ClobClient::encode_cursor(self.offset + raw_count as u64))
+        };
+        let markets = gamma_markets
+            .into_iter()
+            .map(crate::types::Market::from)
+            .filter(|market| params.is_none_or(|options| options.matches_game_window(market)))
+            .collect::<Vec<_>>();
+
+        crate::types::MarketsResponse {
+            limit: Decimal::from(self.limit),
+            count: Decimal::from_i64(markets.len() as i64).unwrap_or(Decimal::ZERO),
+            next_cursor,
+            offset: Some(self.offset),
+            data: markets,
+        }
+    }
+}
+
+/// `query` trimmed, with whitespace runs collapsed to one space; empty is an error
+fn sanitize_search_query(query: &str) -> Result<String> {
+    let query = query.split_whitespace().collect::<Vec<_>>().join(" ");
+    if query.is_empty() {
+        return Err(PolyError::validation("search query is empty"));
+    }
+    Ok(query)
+}
+
+/// Split `values` into runs whose repeated `key=value` pairs fit in `max_len` bytes.
+///
+/// Every run holds at least one value, so a single oversized value still goes out alone.
+fn query_value_chunks<'a>(key: &str, values: &'a [String], max_len: usize) -> Vec<&'a [String]> {
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut len = 0;
+    for (idx, value) in values.iter().enumerate() {
+        // `key=value` plus the `&` separator
+        let pair_len = key.len() + value.len() + 2;
+        if idx > start && len + pair_len > max_len {
+            chunks.push(&values[start..idx]);
+            start = idx;
+            len = 0;
+        }
+        len += pair_len;
+    }
+    if start < values.len() {
+        chunks.push(&values[start..]);
+    }
+    chunks
+}
+
+/// A response kept with the client-clock second it was fetched at
+type CachedResponse<T> = Mutex<Option<(u64, T)>>;
+
+/// Start day, end day and token filter of a realized P&L query
+type PnlWindow = (chrono::NaiveDate, chrono::NaiveDate, Option<String>);
+
+/// Last protocol-wide responses; these move slowly enough to share across callers
+#[derive(Default)]
+struct ProtocolCache {
+    insurance: CachedResponse<crate::types::InsuranceFund>,
+    stats: CachedResponse<crate::types::ProtocolStats>,
+    /// Prediction indices keyed by lowercased category, empty for all markets
+    indices: Mutex<std::collections::HashMap<String, (u64, crate::types::PredictionIndex)>>,
+}
+
+impl ProtocolCache {
+    fn clear(&self) {
+        *self
+            .insurance
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = None;
+        *self.stats.lock().unwrap_or_else(PoisonError::into_inner) = None;
+        self.indices
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+}
+
+/// The last reward epoch seen, and when it was last asked for
+#[derive(Default)]
+struct EpochWatch {
+    /// Set by [`ClobClient::with_epoch_cache_invalidation`]
+    invalidate_caches: bool,
+    state: Mutex<EpochState>,
+}
+
+#[derive(Default)]
+struct EpochState {
+    /// Client-clock second of the last `/epoch` request, successful or not
+    checked_at: Option<u64>,
+    epoch: Option<crate::types::Epoch>,
 }
 
 /// Main client for interacting with Polymarket API
 pub struct ClobClient {
     http_client: Client,
-    base_url: String,
+    clob_base_url: String,
     gamma_base_url: String,
+    data_api_base_url: String,
     ws_base_url: String,
     rtds_base_url: String,
     chain_id: u64,
     signer: Option<PrivateKeySigner>,
     api_creds: Option<ApiCreds>,
     order_builder: Option<crate::orders::OrderBuilder>,
+    number_mode: NumberMode,
+    market_meta: Option<Arc<MarketMetaCache>>,
+    request_limiter: Option<RequestLimiter>,
+    rate_limiter: Option<RateLimiter>,
+    clob_hosts: Option<Arc<HostPool>>,
+    gamma_hosts: Option<Arc<HostPool>>,
+    circuit: Option<Arc<crate::circuit::Circuit>>,
+    clock: Arc<dyn Clock>,
+    max_order_notional: Option<Decimal>,
+    protocol_cache: ProtocolCache,
+    epoch: EpochWatch,
+    /// Last open interest per token, with the client-clock second it was fetched at;
+    /// the baseline of [`ClobClient::get_order_flow_statistics`]
+    open_interest_baselines:
+        Mutex<std::collections::HashMap<String, (i64, crate::types::OpenInterest)>>,
+    /// Closed lots from the last [`ClobClient::get_realized_pnl_history`] replay, with
+    /// the window they were filtered to; later pages of that window are served from here
+    realized_pnl: Mutex<Option<(PnlWindow, Arc<[crate::pnl::RealizedPnlEntry]>)>>,
+    #[cfg(feature = "tower")]
+    middleware: Option<
+        tower::util::BoxCloneSyncService<reqwest::Request, reqwest::Response, tower::BoxError>,
+    >,
+}
+
+impl Default for ClobClient {
+    /// Unauthenticated client against the production CLOB and Gamma hosts
+    fn default() -> Self {
+        Self::new(DEFAULT_CLOB_BASE)
+    }
 }
 
 impl ClobClient {
     /// Create a new client
+    ///
+    /// `host` is the CLOB base URL used for books, prices and orders. Market listings
+    /// come from Gamma, which defaults to the production Gamma host; see
+    /// [`with_gamma_base`](Self::with_gamma_base).
     pub fn new(host: &str) -> Self {
         Self {
             http_client: Client::new(),
-            base_url: host.to_string(),
+            clob_base_url: host.to_string(),
             gamma_base_url: DEFAULT_GAMMA_BASE.to_string(),
+            data_api_base_url: DEFAULT_DATA_API_BASE.to_string(),
             ws_base_url: DEFAULT_WS_BASE.to_string(),
             rtds_base_url: DEFAULT_RTDS_BASE.to_string(),
             chain_id: 137, // Default to Polygon
             signer: None,
             api_creds: None,
             order_builder: None,
+            number_mode: NumberMode::default(),
+            market_meta: None,
+            request_limiter: None,
+            rate_limiter: None,
+            clob_hosts: None,
+            gamma_hosts: None,
+            circuit: None,
+            clock: Arc::new(SystemClock),
+            max_order_notional: None,
+            protocol_cache: ProtocolCache::default(),
+            epoch: EpochWatch::default(),
+            open_interest_baselines: Mutex::default(),
+            realized_pnl: Mutex::default(),
+            #[cfg(feature = "tower")]
+            middleware: None,
         }
     }
 
@@ -211,7 +776,7 @@ impl ClobClient {
     }
 
     fn clob_url(&self, path: &str) -> String {
-        Self::build_url(&self.base_url, path)
+        Self::build_url(&self.clob_base_url, path)
     }
 
     fn gamma_url(&self, path: &str) -> String {
@@ -228,14 +793,30 @@ impl ClobClient {
 
         Self {
             http_client: Client::new(),
-            base_url: host.to_string(),
+            clob_base_url: host.to_string(),
             gamma_base_url: DEFAULT_GAMMA_BASE.to_string(),
+            data_api_base_url: DEFAULT_DATA_API_BASE.to_string(),
             ws_base_url: DEFAULT_WS_BASE.to_string(),
             rtds_base_url: DEFAULT_RTDS_BASE.to_string(),
             chain_id,
             signer: Some(signer),
             api_creds: None,
             order_builder: Some(order_builder),
+            number_mode: NumberMode::default(),
+            market_meta: None,
+            request_limiter: None,
+            rate_limiter: None,
+            clob_hosts: None,
+            gamma_hosts: None,
+            circuit: None,
+            clock: Arc::new(SystemClock),
+            max_order_notional: None,
+            protocol_cache: ProtocolCache::default(),
+            epoch: EpochWatch::default(),
+            open_interest_baselines: Mutex::default(),
+            realized_pnl: Mutex::default(),
+            #[cfg(feature = "tower")]
+            middleware: None,
         }
     }
 
@@ -254,14 +835,30 @@ impl ClobClient {
 
         Self {
             http_client: Client::new(),
-            base_url: host.to_string(),
+            clob_base_url: host.to_string(),
             gamma_base_url: DEFAULT_GAMMA_BASE.to_string(),
+            data_api_base_url: DEFAULT_DATA_API_BASE.to_string(),
             ws_base_url: DEFAULT_WS_BASE.to_string(),
             rtds_base_url: DEFAULT_RTDS_BASE.to_string(),
             chain_id,
             signer: Some(signer),
             api_creds: Some(api_creds),
             order_builder: Some(order_builder),
+            number_mode: NumberMode::default(),
+            market_meta: None,
+            request_limiter: None,
+            rate_limiter: None,
+            clob_hosts: None,
+            gamma_hosts: None,
+            circuit: None,
+            clock: Arc::new(SystemClock),
+            max_order_notional: None,
+            protocol_cache: ProtocolCache::default(),
+            epoch: EpochWatch::default(),
+            open_interest_baselines: Mutex::default(),
+            realized_pnl: Mutex::default(),
+            #[cfg(feature = "tower")]
+            middleware: None,
         }
     }
 
@@ -271,6 +868,10 @@ impl ClobClient {
     }
 
     /// Override the funder/maker address used when creating signed orders.
+    ///
+    /// The signature type is inferred from the address: the signer's own address signs as
+    /// an EOA, any other address as a Polymarket proxy wallet. Use
+    /// [`set_funder_with_sig_type`](Self::set_funder_with_sig_type) for Gnosis safes.
     pub fn set_funder(&mut self, funder: &str) -> Result<()> {
         let address = Address::from_str(funder)
             .map_err(|err| PolyError::validation(format!("Invalid funder address: {}", err)))?;
@@ -284,12 +885,105 @@ impl ClobClient {
         Ok(())
     }
 
+    /// Override the funder/maker address with an explicit signature type.
+    ///
+    /// Returns a config error when the type contradicts the funder (an EOA type with a
+    /// foreign funder, or a proxy type funded by the signer itself).
+    pub fn set_funder_with_sig_type(
+        &mut self,
+        funder: &str,
+        sig_type: crate::orders::SigType,
+    ) -> Result<()> {
+        let address = Address::from_str(funder)
+            .map_err(|err| PolyError::validation(format!("Invalid funder address: {}", err)))?;
+
+        let order_builder = self
+            .order_builder
+            .as_mut()
+            .ok_or_else(|| PolyError::config("Order builder not initialized"))?;
+
+        order_builder.set_funder_with_sig_type(address, sig_type)
+    }
+
+    /// Override the CLOB API base URL
+    pub fn with_clob_base(mut self, url: &str) -> Self {
+        self.clob_base_url = url.to_string();
+        self
+    }
+
     /// Override the Gamma API base URL
     pub fn with_gamma_base(mut self, url: &str) -> Self {
         self.gamma_base_url = url.to_string();
         self
     }
 
+    /// Send requests through `client` instead of a default one, e.g. to add root
+    /// certificates, a proxy or timeouts
+    ///
+    /// The TLS backend is still the one picked by this crate's `native-tls` or
+    /// `rustls-tls` feature, since both configure the same reqwest build.
+    pub fn with_http_client(mut self, client: Client) -> Self {
+        self.http_client = client;
+        self
+    }
+
+    /// Base URL for order book, pricing and order endpoints
+    pub fn clob_base_url(&self) -> &str {
+        &self.clob_base_url
+    }
+
+    /// Base URL for market and event listings
+    pub fn gamma_base_url(&self) -> &str {
+        &self.gamma_base_url
+    }
+
+    /// Send CLOB requests to `hosts` in priority order, failing over between them.
+    ///
+    /// The first host replaces the base URL passed to the constructor. See
+    /// [`crate::failover`] for which errors fail over and why signed requests don't need
+    /// re-signing on a backup. An empty list leaves the client unchanged.
+    pub fn with_clob_hosts<S: AsRef<str>>(mut self, hosts: &[S], policy: FailoverPolicy) -> Self {
+        if let Some(pool) = HostPool::new(hosts, policy) {
+            self.clob_base_url = pool.primary().to_string();
+            self.clob_hosts = Some(Arc::new(pool));
+        }
+        self
+    }
+
+    /// Send Gamma requests to `hosts` in priority order, failing over between them
+    pub fn with_gamma_hosts<S: AsRef<str>>(mut self, hosts: &[S], policy: FailoverPolicy) -> Self {
+        if let Some(pool) = HostPool::new(hosts, policy) {
+            self.gamma_base_url = pool.primary().to_string();
+            self.gamma_hosts = Some(Arc::new(pool));
+        }
+        self
+    }
+
+    /// Failover counters for the CLOB host list, when one is configured
+    pub fn clob_failover_stats(&self) -> Option<FailoverStats> {
+        self.clob_hosts.as_ref().map(|pool| pool.stats())
+    }
+
+    /// Failover counters for the Gamma host list, when one is configured
+    pub fn gamma_failover_stats(&self) -> Option<FailoverStats> {
+        self.gamma_hosts.as_ref().map(|pool| pool.stats())
+    }
+
+    /// Stop sending requests while the API keeps failing; see [`crate::circuit`].
+    ///
+    /// Covers every request made through this client, CLOB and Gamma alike. While the
+    /// circuit is open they fail with [`PolyError::CircuitOpen`] without a network call.
+    pub fn with_circuit_breaker(mut self, breaker: crate::circuit::CircuitBreaker) -> Self {
+        self.circuit = Some(Arc::new(crate::circuit::Circuit::new(breaker)));
+        self
+    }
+
+    /// Override the Data API base URL
+    pub fn with_data_api_base(mut self, url: &str) -> Self {
+        self.data_api_base_url = url.to_string();
+        self
+    }
+
     /// Override the WebSocket base URL
     pub fn with_ws_base(mut self, url: &str) -> Self {
         self.ws_base_url = url.to_string();
@@ -302,71 +996,393 @@ impl ClobClient {
         self
     }
 
-    /// Test basic connectivity
-    pub async fn get_ok(&self) -> bool {
-        match self.http_client.get(self.clob_url("ok")).send().await {
-            Ok(response) => response.status().is_success(),
-            Err(_) => false,
-        }
+    /// Choose how decimal fields in typed responses are parsed (lenient by default)
+    pub fn with_number_mode(mut self, mode: NumberMode) -> Self {
+        self.number_mode = mode;
+        self
     }
 
-    /// Get server time
-    pub async fn get_server_time(&self) -> Result<u64> {
-        let response = self.http_client.get(self.clob_url("time")).send().await?;
+    /// Serve tick sizes and neg-risk flags for the order path from `cache`.
+    ///
+    /// Pass the same `Arc` to [`WssMarketClient::with_meta_cache`](crate::wss::WssMarketClient::with_meta_cache)
+    /// so tick size changes reach the cache before the TTL runs out.
+    pub fn with_market_meta_cache(mut self, cache: Arc<MarketMetaCache>) -> Self {
+        self.market_meta = Some(cache);
+        self
+    }
 
-        if !response.status().is_success() {
-            return Err(PolyError::api(
-                response.status().as_u16(),
-                "Failed to get server time",
-            ));
-        }
+    /// The attached market metadata cache, if any
+    pub fn market_meta_cache(&self) -> Option<&Arc<MarketMetaCache>> {
+        self.market_meta.as_ref()
+    }
 
-        let time_text = response.text().await?;
-        let timestamp = time_text
-            .trim()
-            .parse::<u64>()
-            .map_err(|e| PolyError::parse(format!("Invalid timestamp format: {}", e), None))?;
+    /// Cap the number of requests this client has in flight at once.
+    ///
+    /// Requests past the cap wait for a free slot instead of failing, which smooths the
+    /// bursts from fan-out helpers like [`scan_spreads`](Self::scan_spreads). A slot is held
+    /// until the response headers arrive. A limit of 0 is treated as 1.
+    ///
+    /// Order posts and cancels are queued ahead of other requests and always have a
+    /// slot kept free for them; see [`RequestLimits::new`] for the defaults and
+    /// [`with_request_limits`](Self::with_request_limits) to tune them.
+    pub fn with_max_concurrency(self, limit: usize) -> Self {
+        self.with_request_limits(RequestLimits::new(limit))
+    }
 
-        Ok(timestamp)
+    /// Cap in-flight requests with explicit per-[`Priority`] weights and caps
+    pub fn with_request_limits(mut self, limits: RequestLimits) -> Self {
+        self.request_limiter = Some(RequestLimiter::new(limits));
+        self
     }
 
-    /// Get order book for a token
-    pub async fn get_order_book(&self, token_id: &str) -> Result<OrderBookSummary> {
-        let response = self
-            .http_client
-            .get(self.clob_url("book"))
-            .query(&[("token_id", token_id)])
-            .send()
-            .await?;
+    /// Send at most `requests_per_second` requests each second
+    ///
+    /// A [`RateLimiter`] token bucket: up to a second's worth go out at once, and the
+    /// rest are spaced evenly. It applies to every request, with or without tower
+    /// middleware, on top of any in-flight cap from
+    /// [`with_request_limits`](Self::with_request_limits). 0 is treated as 1.
+    pub fn with_rate_limit(mut self, requests_per_second: u32) -> Self {
+        self.rate_limiter = Some(RateLimiter::new(requests_per_second));
+        self
+    }
 
-        if !response.status().is_success() {
-            return Err(PolyError::api(
-                response.status().as_u16(),
-                "Failed to get order book",
-            ));
-        }
+    /// Pace requests to the account's own allowance
+    ///
+    /// Fetches [`get_user_limits`](Self::get_user_limits) and applies its
+    /// `requests_per_second` with [`with_rate_limit`](Self::with_rate_limit), replacing
+    /// any rate limit already set. The in-flight cap is left as it is.
+    pub async fn with_auto_rate_limits(self) -> Result<Self> {
+        let limits = self.get_user_limits().await?;
+        Ok(self.with_rate_limit(limits.requests_per_second))
+    }
 
-        let order_book: OrderBookSummary = response.json().await?;
-        Ok(order_book)
+    /// Refuse to post any order whose notional exceeds `limit` USDC
+    ///
+    /// Checked locally in [`post_order`](Self::post_order),
+    /// [`post_orders`](Self::post_orders) and
+    /// [`post_order_dry_run`](Self::post_order_dry_run) before anything is sent. An
+    /// oversized order fails with a
+    /// [`SizeConstraint`](crate::errors::OrderErrorKind::SizeConstraint) order error,
+    /// and a batch containing one is not posted at all.
+    pub fn with_max_order_notional(mut self, limit: Decimal) -> Self {
+        self.max_order_notional = Some(limit);
+        self
     }
 
-    /// Get midpoint for a token
-    pub async fn get_midpoint(&self, token_id: &str) -> Result<MidpointResponse> {
-        let response = self
-            .http_client
-            .get(self.clob_url("midpoint"))
-            .query(&[("token_id", token_id)])
-            .send()
-            .await?;
+    /// Clear cached responses whenever the reward epoch changes
+    ///
+    /// With this on, the market metadata cache, the protocol insurance and stats
+    /// responses and the prediction indices are dropped when a new epoch ID shows up.
+    /// Reads from those caches check [`get_current_epoch`](Self::get_current_epoch)
+    /// first once five minutes have passed since the last check, by the client's clock;
+    /// a failed check is logged and the read goes ahead. Nothing runs in the background,
+    /// so an idle client notices the change on its next cached read.
+    pub fn with_epoch_cache_invalidation(mut self, enabled: bool) -> Self {
+        self.epoch.invalidate_caches = enabled;
+        self
+    }
 
-        if !response.status().is_success() {
-            return Err(PolyError::api(
-                response.status().as_u16(),
+    /// Time source for the L2 header timestamps; the system clock by default
+    ///
+    /// A [`MockClock`](crate::clock::MockClock) makes signed requests reproducible.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// The transport under every request, as a tower service
+    ///
+    /// Wrap it in layers and hand the result to
+    /// [`with_tower_middleware`](Self::with_tower_middleware). It keeps this client's
+    /// host failover and metrics, as they stood when it was taken.
+    #[cfg(feature = "tower")]
+    pub fn http_service(&self) -> crate::middleware::HttpService {
+        crate::middleware::HttpService::new(
+            self.http_client.clone(),
+            [&self.clob_hosts, &self.gamma_hosts]
+                .into_iter()
+                .flatten()
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// Send every request through `service` instead of straight to the transport
+    ///
+    /// `service` is normally a stack built with `tower::ServiceBuilder` around
+    /// [`http_service`](Self::http_service). Requests are built and signed first, so
+    /// middleware sees exactly what would go on the wire. The stack takes over limiting:
+    /// a limiter set with [`with_request_limits`](Self::with_request_limits) is not
+    /// consulted while middleware is installed, so put a
+    /// [`PolyRateLimitLayer`](crate::middleware::PolyRateLimitLayer) in the stack
+    /// instead. A reqwest or [`PolyError`] coming out of the stack is reported as
+    /// itself; any other middleware error becomes a network error.
+    #[cfg(feature = "tower")]
+    pub fn with_tower_middleware<S>(mut self, service: S) -> Self
+    where
+        S: tower::Service<reqwest::Request, Response = reqwest::Response>
+            + Clone
+            + Send
+            + Sync
+            + 'static,
+        S::Error: Into<tower::BoxError>,
+        S::Future: Send + 'static,
+    {
+        let service = tower::ServiceExt::map_err(service, Into::into);
+        self.middleware = Some(tower::util::BoxCloneSyncService::new(service));
+        self
+    }
+
+    /// In-flight and queued request counts, when a cap is set
+    pub fn request_limiter_stats(&self) -> Option<LimiterStats> {
+        self.request_limiter.as_ref().map(RequestLimiter::stats)
+    }
+
+    /// Conditional Token Framework (ERC-1155) contract on the client's chain
+    ///
+    /// Outcome tokens of standard and neg-risk markets live in the same contract.
+    pub fn get_conditional_token_address(&self) -> Result<String> {
+        self.contracts()
+            .map(|contracts| contracts.conditional_tokens)
+    }
+
+    /// USDC contract used as collateral on the client's chain
+    pub fn get_collateral_token_address(&self) -> Result<String> {
+        self.contracts()
+            .and_then(|contracts| checksummed(&contracts.collateral))
+    }
+
+    /// Wallet that `user_address` trades through, as the maker of its orders
+    ///
+    /// Derived locally for the client's signature type (see
+    /// [`proxy_wallet_address`](crate::config::proxy_wallet_address)), so it is known
+    /// before the wallet is deployed. Needs a signer for the signature type; EOA
+    /// signers get `user_address` back.
+    pub fn get_proxy_wallet_address(&self, user_address: &str) -> Result<String> {
+        let owner = Address::from_str(user_address.trim()).map_err(|e| {
+            PolyError::validation(format!("Invalid address {}: {}", user_address, e))
+        })?;
+        let sig_type = self
+            .order_builder
+            .as_ref()
+            .ok_or_else(|| PolyError::config("Signer not configured"))?
+            .sig_type();
+        crate::config::proxy_wallet_address(self.chain_id, sig_type, owner)
+            .map(|wallet| wallet.to_checksum(None))
+            .ok_or_else(|| {
+                PolyError::config(format!(
+                    "No wallet factories known for chain {}",
+                    self.chain_id
+                ))
+            })
+    }
+
+    fn contracts(&self) -> Result<crate::config::ContractConfig> {
+        crate::config::get_contract_config(self.chain_id, false).ok_or_else(|| {
+            PolyError::config(format!(
+                "No contracts configured for chain {}",
+                self.chain_id
+            ))
+        })
+    }
+
+    /// Apply the request-shaping settings from a [`ClientConfig`](crate::types::ClientConfig).
+    ///
+    /// Only `max_concurrency` is read here; `None` removes any existing cap.
+    pub fn with_client_config(mut self, config: &crate::types::ClientConfig) -> Self {
+        self.request_limiter = None;
+        match config.max_concurrency {
+            Some(limit) => self.with_max_concurrency(limit),
+            None => self,
+        }
+    }
+
+    /// Read a response body and deserialize it under the client's number mode
+    async fn decode_json<T: DeserializeOwned>(&self, response: reqwest::Response) -> Result<T> {
+        let body = response.bytes().await?;
+        crate::decode::from_slice_with_mode(&body, self.number_mode)
+    }
+
+    /// Test basic connectivity
+    pub async fn get_ok(&self) -> bool {
+        match self
+            .http_client
+            .get(self.clob_url("ok"))
+            .send_via(self)
+            .await
+        {
+            Ok(response) => response.status().is_success(),
+            Err(_) => false,
+        }
+    }
+
+    /// Get server time
+    pub async fn get_server_time(&self) -> Result<u64> {
+        let response = self
+            .http_client
+            .get(self.clob_url("time"))
+            .send_via(self)
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(PolyError::api(
+                response.status().as_u16(),
+                "Failed to get server time",
+            ));
+        }
+
+        let time_text = response.text().await?;
+        let timestamp = time_text
+            .trim()
+            .parse::<u64>()
+            .map_err(|e| PolyError::parse(format!("Invalid timestamp format: {}", e), None))?;
+
+        Ok(timestamp)
+    }
+
+    /// Probe the CLOB with `GET /time` and time the round trip
+    ///
+    /// An error status comes back as `ok: false` rather than an error. A request that
+    /// gets no response at all is an error, as is one refused by an open circuit breaker.
+    pub async fn check_health(&self) -> Result<crate::types::HealthStatus> {
+        let started = std::time::Instant::now();
+        let response = self
+            .http_client
+            .get(self.clob_url("time"))
+            .send_via(self)
+            .await?;
+        let server_time = if response.status().is_success() {
+            response.text().await?.trim().parse::<i64>().ok()
+        } else {
+            None
+        };
+
+        Ok(crate::types::HealthStatus {
+            ok: server_time.is_some(),
+            latency_ms: started.elapsed().as_millis() as u64,
+            timestamp: server_time.unwrap_or(self.clock.now_secs() as i64),
+        })
+    }
+
+    /// Get order book for a token
+    ///
+    /// The CLOB returns every resting level; there's no server-side depth limit today.
+    /// Pass `depth` to keep only the best `depth` levels per side - the returned
+    /// summary has `truncated` set when that dropped anything. Sides come back best
+    /// first only when `depth` is `Some`; otherwise they keep the server's order.
+    pub async fn get_order_book(
+        &self,
+        token_id: &str,
+        depth: Option<usize>,
+    ) -> Result<OrderBookSummary> {
+        let response = self
+            .http_client
+            .get(self.clob_url("book"))
+            .query(&[("token_id", token_id)])
+            .send_via(self)
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(PolyError::api(
+                response.status().as_u16(),
+                "Failed to get order book",
+            ));
+        }
+
+        let mut order_book: OrderBookSummary = self.decode_json(response).await?;
+        if let Some(depth) = depth {
+            order_book.truncate_to_depth(depth);
+        }
+        Ok(order_book)
+    }
+
+    /// Stream market channel events for `token_ids` over server-sent events
+    ///
+    /// An alternative to [`WssMarketClient`](crate::wss::WssMarketClient) for
+    /// environments where WebSockets are blocked. Each `data:` payload of
+    /// `GET /sse/orderbook` is parsed like a websocket frame and stamped with its arrival
+    /// time. SSE is one-way: there is no ping/pong, so a silently dead connection is only
+    /// noticed once the server or a proxy closes it, and reconnecting (left to the
+    /// caller, e.g. re-fetching a snapshot first) is slower than a websocket resubscribe.
+    ///
+    /// The stream ends when the server closes the response. Dropping it closes the
+    /// connection.
+    pub async fn subscribe_orderbook_updates(
+        &self,
+        token_ids: &[&str],
+    ) -> Result<impl futures::Stream<Item = Result<crate::wss::WssMarketEvent>> + Send + 'static>
+    {
+        use futures::stream::{self, StreamExt};
+
+        let response = self
+            .http_client
+            .get(self.clob_url("sse/orderbook"))
+            .query(&[("token_ids", token_ids.join(","))])
+            .header("Accept", "text/event-stream")
+            .send_via(self)
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(PolyError::api(
+                response.status().as_u16(),
+                "Failed to subscribe to order book updates",
+            ));
+        }
+
+        let state = (
+            Box::pin(response.bytes_stream()),
+            SseDecoder::default(),
+            std::collections::VecDeque::new(),
+        );
+        Ok(stream::unfold(
+            state,
+            |(mut body, mut decoder, mut pending)| async move {
+                loop {
+                    if let Some(item) = pending.pop_front() {
+                        return Some((item, (body, decoder, pending)));
+                    }
+                    let chunk = match body.next().await? {
+                        Ok(chunk) => chunk,
+                        Err(err) => {
+                            let err = PolyError::stream(
+                                format!("SSE connection lost: {}", err),
+                                crate::errors::StreamErrorKind::ConnectionLost,
+                            );
+                            return Some((Err(err), (body, decoder, pending)));
+                        }
+                    };
+                    let received_at = std::time::SystemTime::now();
+                    for data in decoder.feed(&chunk) {
+                        match crate::wss::parse_market_events(&data) {
+                            Ok(events) => pending.extend(events.into_iter().map(|mut event| {
+                                event.stamp_received(received_at);
+                                Ok(event)
+                            })),
+                            Err(err) => pending.push_back(Err(err)),
+                        }
+                    }
+                }
+            },
+        ))
+    }
+
+    /// Get midpoint for a token
+    pub async fn get_midpoint(&self, token_id: &str) -> Result<MidpointResponse> {
+        let response = self
+            .http_client
+            .get(self.clob_url("midpoint"))
+            .query(&[("token_id", token_id)])
+            .send_via(self)
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(PolyError::api(
+                response.status().as_u16(),
                 "Failed to get midpoint",
             ));
         }
 
-        let midpoint: MidpointResponse = response.json().await?;
+        let midpoint: MidpointResponse = self.decode_json(response).await?;
         Ok(midpoint)
     }
 
@@ -376,7 +1392,7 @@ impl ClobClient {
             .http_client
             .get(self.clob_url("spread"))
             .query(&[("token_id", token_id)])
-            .send()
+            .send_via(self)
             .await?;
 
         if !response.status().is_success() {
@@ -386,10 +1402,39 @@ impl ClobClient {
             ));
         }
 
-        let spread: SpreadResponse = response.json().await?;
+        let spread: SpreadResponse = self.decode_json(response).await?;
         Ok(spread)
     }
 
+    /// Get the funding rate for a leveraged/perpetual token.
+    ///
+    /// Future-proofing: Polymarket has no leveraged markets yet, so `GET /funding-rate` may
+    /// not exist. A 404 is reported as a `TokenNotFound` market data error, which is also
+    /// what spot tokens will return if the endpoint ships.
+    pub async fn get_funding_rate(&self, token_id: &str) -> Result<crate::types::FundingRate> {
+        let response = self
+            .http_client
+            .get(self.clob_url("funding-rate"))
+            .query(&[("token_id", token_id)])
+            .send_via(self)
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(PolyError::market_data(
+                "funding rate not available for spot markets",
+                crate::errors::MarketDataErrorKind::TokenNotFound,
+            ));
+        }
+        if !response.status().is_success() {
+            return Err(PolyError::api(
+                response.status().as_u16(),
+                "Failed to get funding rate",
+            ));
+        }
+
+        self.decode_json(response).await
+    }
+
     /// Get spreads for multiple tokens (batch)
     pub async fn get_spreads(
         &self,
@@ -408,7 +1453,7 @@ impl ClobClient {
             .http_client
             .post(self.clob_url("spreads"))
             .json(&request_data)
-            .send()
+            .send_via(self)
             .await?;
 
         if !response.status().is_success() {
@@ -418,10 +1463,308 @@ impl ClobClient {
             ));
         }
 
-        response
-            .json::<std::collections::HashMap<String, Decimal>>()
-            .await
-            .map_err(|e| PolyError::parse(format!("Failed to parse response: {}", e), None))
+        let spreads: std::collections::HashMap<String, crate::serde_decimal::Wire> =
+            self.decode_json(response).await?;
+        Ok(spreads
+            .into_iter()
+            .map(|(token, spread)| (token, spread.0))
+            .collect())
+    }
+
+    /// Price history for a token between two unix times, one point per
+    /// `fidelity_minutes`
+    pub async fn get_price_history(
+        &self,
+        token_id: &str,
+        start_ts: u64,
+        end_ts: u64,
+        fidelity_minutes: u32,
+    ) -> Result<Vec<crate::volatility::PricePoint>> {
+        if start_ts >= end_ts {
+            return Err(PolyError::validation(format!(
+                "start_ts {} must be before end_ts {}",
+                start_ts, end_ts
+            )));
+        }
+
+        let response = self
+            .http_client
+            .get(self.clob_url("prices-history"))
+            .query(&[
+                ("market", token_id.to_string()),
+                ("startTs", start_ts.to_string()),
+                ("endTs", end_ts.to_string()),
+                ("fidelity", fidelity_minutes.max(1).to_string()),
+            ])
+            .send_via(self)
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(PolyError::api(
+                response.status().as_u16(),
+                "Failed to get price history",
+            ));
+        }
+
+        #[derive(serde::Deserialize)]
+        struct History {
+            #[serde(default)]
+            history: Vec<crate::volatility::PricePoint>,
+        }
+        let body: History = self.decode_json(response).await?;
+        Ok(body.history)
+    }
+
+    /// Realized volatility of a token over the last `lookback_hours`, from 1-minute
+    /// price history
+    ///
+    /// See [`crate::volatility`] for how the figures are derived.
+    pub async fn get_market_volatility(
+        &self,
+        token_id: &str,
+        lookback_hours: u32,
+    ) -> Result<crate::volatility::VolatilityMetrics> {
+        if lookback_hours == 0 {
+            return Err(PolyError::validation("lookback_hours must be at least 1"));
+        }
+        let end_ts = self.clock.now_secs();
+        let start_ts = end_ts.saturating_sub(u64::from(lookback_hours) * 3600);
+        let history = self
+            .get_price_history(token_id, start_ts, end_ts, 1)
+            .await?;
+        crate::volatility::VolatilityMetrics::from_history(&history, lookback_hours)
+    }
+
+    /// Correlation of the tokens' daily returns over the last `lookback_days`
+    ///
+    /// Fetches daily price history for every token concurrently; see
+    /// [`crate::correlation`] for how pairs are aligned and flat tokens handled.
+    pub async fn get_market_correlation(
+        &self,
+        token_ids: &[&str],
+        lookback_days: u32,
+    ) -> Result<crate::correlation::CorrelationMatrix> {
+        if lookback_days < 2 {
+            return Err(PolyError::validation("lookback_days must be at least 2"));
+        }
+        if token_ids.len() < 2 {
+            return Err(PolyError::validation(
+                "Need at least 2 tokens for a correlation matrix",
+            ));
+        }
+        let end_ts = self.clock.now_secs();
+        let start_ts = end_ts.saturating_sub(u64::from(lookback_days) * 86_400);
+        let histories = futures::future::try_join_all(
+            token_ids
+                .iter()
+                .map(|token_id| self.get_price_history(token_id, start_ts, end_ts, 1440)),
+        )
+        .await?;
+        let token_ids: Vec<String> = token_ids.iter().map(|id| id.to_string()).collect();
+        crate::correlation::CorrelationMatrix::from_histories(&token_ids, &histories)
+    }
+
+    /// Spread, impact and liquidity metrics for `token_id` over the last `window_hours`
+    ///
+    /// Computed client-side from one-minute [`get_price_history`](Self::get_price_history)
+    /// and the market's most recent [`get_market_trades`](Self::get_market_trades); see
+    /// [`Microstructure`](crate::microstructure::Microstructure) for the definitions.
+    /// Busy markets may have more fills in the window than one trades page holds, in
+    /// which case only the latest are used.
+    pub async fn get_market_microstructure(
+        &self,
+        token_id: &str,
+        window_hours: u32,
+    ) -> Result<crate::microstructure::Microstructure> {
+        if window_hours == 0 {
+            return Err(PolyError::validation("window_hours must be at least 1"));
+        }
+        let market = self
+            .get_markets_by_token_ids(&[token_id.to_string()])
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| PolyError::api(404, format!("No market for token {}", token_id)))?;
+
+        let end_ts = self.clock.now_secs();
+        let start_ts = end_ts.saturating_sub(u64::from(window_hours) * 3600);
+        let (history, trades) = futures::try_join!(
+            self.get_price_history(token_id, start_ts, end_ts, 1),
+            self.get_market_trades(&market.condition_id, MICROSTRUCTURE_TRADES_LIMIT),
+        )?;
+        let trades: Vec<_> = trades
+            .into_iter()
+            .filter(|trade| trade.asset == token_id && trade.timestamp >= start_ts as i64)
+            .collect();
+        crate::microstructure::Microstructure::from_trades(&trades, &history, window_hours)
+    }
+
+    /// Approximate bid and ask depth of `token_id` per `interval` between `start_ts`
+    /// and `end_ts`
+    ///
+    /// The CLOB serves no historical books, so this is reconstructed client-side from
+    /// price history at the interval's fidelity and the market's most recent
+    /// [`get_market_trades`](Self::get_market_trades) page: depth is liquidity that
+    /// traded, not what rested. See [`crate::microstructure`] for the method. Fills older
+    /// than that page are missing, which shows as zero depth in early intervals of a
+    /// busy market. At most [`MAX_DEPTH_SNAPSHOTS`] snapshots come back, from `start_ts`
+    /// on.
+    ///
+    /// [`MAX_DEPTH_SNAPSHOTS`]: crate::microstructure::MAX_DEPTH_SNAPSHOTS
+    pub async fn get_market_depth_history(
+        &self,
+        token_id: &str,
+        start_ts: i64,
+        end_ts: i64,
+        interval: crate::microstructure::BookDepthInterval,
+    ) -> Result<Vec<crate::microstructure::HistoricalDepthSnapshot>> {
+        if start_ts < 0 || start_ts >= end_ts {
+            return Err(PolyError::validation(format!(
+                "start_ts {} must be non-negative and before end_ts {}",
+                start_ts, end_ts
+            )));
+        }
+        let step = interval.as_secs();
+        let span = step.saturating_mul(crate::microstructure::MAX_DEPTH_SNAPSHOTS as i64);
+        let end_ts = end_ts.min(start_ts.div_euclid(step) * step + span);
+
+        let market = self
+            .get_markets_by_token_ids(&[token_id.to_string()])
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| PolyError::api(404, format!("No market for token {}", token_id)))?;
+        let (history, trades) = futures::try_join!(
+            self.get_price_history(token_id, start_ts as u64, end_ts as u64, (step / 60) as u32),
+            self.get_market_trades(&market.condition_id, MICROSTRUCTURE_TRADES_LIMIT),
+        )?;
+        let trades: Vec<_> = trades
+            .into_iter()
+            .filter(|trade| trade.asset == token_id)
+            .collect();
+        Ok(crate::microstructure::depth_history(
+            &trades, &history, start_ts, end_ts, interval,
+        ))
+    }
+
+    /// Candles of `token_id` for the last `lookback_periods` buckets of `resolution`,
+    /// up to the current one by the client's clock
+    ///
+    /// Price history at the resolution's fidelity and the market's most recent
+    /// [`get_market_trades`](Self::get_market_trades) page are fetched concurrently and
+    /// bucketed by [`market_history`](crate::microstructure::market_history);
+    /// `lookback_periods` is capped at
+    /// [`MAX_HISTORY_CANDLES`](crate::microstructure::MAX_HISTORY_CANDLES). If one of
+    /// the two fails the candles are built from the other, with a warning logged; if
+    /// both fail the error is a market data error naming both causes.
+    pub async fn get_market_history(
+        &self,
+        token_id: &str,
+        resolution: crate::microstructure::PriceResolution,
+        lookback_periods: u32,
+    ) -> Result<crate::microstructure::MarketHistory> {
+        if lookback_periods == 0 {
+            return Err(PolyError::validation("lookback_periods must be at least 1"));
+        }
+        let periods = lookback_periods.min(crate::microstructure::MAX_HISTORY_CANDLES);
+        let step = resolution.as_secs();
+        let end_ts = self.clock.now_secs() as i64;
+        let start_ts = (end_ts.div_euclid(step) - i64::from(periods - 1)) * step;
+
+        let trades = async {
+            let market = self
+                .get_markets_by_token_ids(&[token_id.to_string()])
+                .await?
+                .into_iter()
+                .next()
+                .ok_or_else(|| PolyError::api(404, format!("No market for token {}", token_id)))?;
+            self.get_market_trades(&market.condition_id, MICROSTRUCTURE_TRADES_LIMIT)
+                .await
+        };
+        let (history, trades) = futures::join!(
+            self.get_price_history(
+                token_id,
+                start_ts.max(0) as u64,
+                end_ts.max(start_ts + 1) as u64,
+                (step / 60) as u32,
+            ),
+            trades,
+        );
+        let (history, trades) = match (history, trades) {
+            (Err(history), Err(trades)) => {
+                return Err(PolyError::market_data(
+                    format!(
+                        "No history for token {}: price history failed ({}) and trades failed ({})",
+                        token_id, history, trades
+                    ),
+                    crate::errors::MarketDataErrorKind::IncompleteData,
+                ));
+            }
+            (Err(error), Ok(trades)) => {
+                tracing::warn!(token_id, %error, "price history failed, using trades only");
+                (Vec::new(), trades)
+            }
+            (Ok(history), Err(error)) => {
+                tracing::warn!(token_id, %error, "market trades failed, using price history only");
+                (history, Vec::new())
+            }
+            (Ok(history), Ok(trades)) => (history, trades),
+        };
+        let trades: Vec<_> = trades
+            .into_iter()
+            .filter(|trade| trade.asset == token_id)
+            .collect();
+        Ok(crate::microstructure::market_history(
+            token_id, &trades, &history, end_ts, resolution, periods,
+        ))
+    }
+
+    /// Taker and maker order flow of `token_id` over the last `window_hours`
+    ///
+    /// Computed client-side from the market's most recent
+    /// [`get_market_trades`](Self::get_market_trades) page and a
+    /// [`get_open_interest`](Self::get_open_interest) snapshot; see
+    /// [`OrderFlowStats`](crate::microstructure::OrderFlowStats). The client keeps each
+    /// token's last snapshot as the baseline for the maker volumes, so they cover the time
+    /// since the previous call for the token and are zero on the first.
+    pub async fn get_order_flow_statistics(
+        &self,
+        token_id: &str,
+        window_hours: u32,
+    ) -> Result<crate::microstructure::OrderFlowStats> {
+        if window_hours == 0 {
+            return Err(PolyError::validation("window_hours must be at least 1"));
+        }
+        let trades = async {
+            let market = self
+                .get_markets_by_token_ids(&[token_id.to_string()])
+                .await?
+                .into_iter()
+                .next()
+                .ok_or_else(|| PolyError::api(404, format!("No market for token {}", token_id)))?;
+            self.get_market_trades(&market.condition_id, MICROSTRUCTURE_TRADES_LIMIT)
+                .await
+        };
+        let (trades, open_interest) = futures::try_join!(trades, self.get_open_interest(token_id))?;
+        let trades: Vec<_> = trades
+            .into_iter()
+            .filter(|trade| trade.asset == token_id)
+            .collect();
+
+        let now = self.clock.now_secs() as i64;
+        let baseline = self
+            .open_interest_baselines
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(token_id.to_string(), (now, open_interest.clone()));
+        Ok(crate::microstructure::OrderFlowStats::from_trades(
+            &trades,
+            now - i64::from(window_hours) * 3600,
+            &open_interest,
+            baseline
+                .as_ref()
+                .map(|(taken_at, snapshot)| (*taken_at, snapshot)),
+        ))
     }
 
     /// Get price for a token and side
@@ -430,7 +1773,7 @@ impl ClobClient {
             .http_client
             .get(self.clob_url("price"))
             .query(&[("token_id", token_id), ("side", side.as_str())])
-            .send()
+            .send_via(self)
             .await?;
 
         if !response.status().is_success() {
@@ -440,7 +1783,7 @@ impl ClobClient {
             ));
         }
 
-        let price: PriceResponse = response.json().await?;
+        let price: PriceResponse = self.decode_json(response).await?;
         Ok(price)
     }
 
@@ -450,7 +1793,7 @@ impl ClobClient {
             .http_client
             .get(self.clob_url("tick-size"))
             .query(&[("token_id", token_id)])
-            .send()
+            .send_via(self)
             .await?;
 
         if !response.status().is_success() {
@@ -460,18 +1803,8 @@ impl ClobClient {
             ));
         }
 
-        let tick_size_response: Value = response.json().await?;
-        let tick_size = tick_size_response["minimum_tick_size"]
-            .as_str()
-            .and_then(|s| Decimal::from_str(s).ok())
-            .or_else(|| {
-                tick_size_response["minimum_tick_size"]
-                    .as_f64()
-                    .map(|f| Decimal::from_f64(f).unwrap_or(Decimal::ZERO))
-            })
-            .ok_or_else(|| PolyError::parse("Invalid tick size format", None))?;
-
-        Ok(tick_size)
+        let tick_size: crate::types::TickSizeResponse = self.decode_json(response).await?;
+        Ok(tick_size.minimum_tick_size)
     }
 
     /// Create a new API key
@@ -485,7 +1818,7 @@ impl ClobClient {
         let req =
             self.create_request_with_headers(Method::POST, "/auth/api-key", headers.into_iter());
 
-        let response = req.send().await?;
+        let response = req.send_via(self).await?;
         if !response.status().is_success() {
             let status = response.status().as_u16();
             let body = response
@@ -498,7 +1831,7 @@ impl ClobClient {
             ));
         }
 
-        Ok(response.json::<ApiCreds>().await?)
+        self.decode_json::<ApiCreds>(response).await
     }
 
     /// Derive an existing API key
@@ -515,7 +1848,7 @@ impl ClobClient {
             headers.into_iter(),
         );
 
-        let response = req.send().await?;
+        let response = req.send_via(self).await?;
         if !response.status().is_success() {
             let status = response.status().as_u16();
             let body = response
@@ -528,7 +1861,7 @@ impl ClobClient {
             ));
         }
 
-        Ok(response.json::<ApiCreds>().await?)
+        self.decode_json::<ApiCreds>(response).await
     }
 
     /// Create or derive API key (try create first, fallback to derive)
@@ -552,8 +1885,14 @@ impl ClobClient {
 
         let method = Method::GET;
         let endpoint = "/auth/api-keys";
-        let headers =
-            create_l2_headers::<Value>(signer, api_creds, method.as_str(), endpoint, None)?;
+        let headers = create_l2_headers_at::<Value>(
+            signer,
+            api_creds,
+            method.as_str(),
+            endpoint,
+            None,
+            self.clock.now_secs(),
+        )?;
 
         let response = self
             .http_client
@@ -564,14 +1903,11 @@ impl ClobClient {
                     .map(|(k, v)| (HeaderName::from_static(k), v.parse().unwrap()))
                     .collect(),
             )
-            .send()
+            .send_via(self)
             .await
-            .map_err(|e| PolyError::network(format!("Request failed: {}", e), e))?;
+            .map_err(request_failed)?;
 
-        let api_keys_response: crate::types::ApiKeysResponse = response
-            .json()
-            .await
-            .map_err(|e| PolyError::parse(format!("Failed to parse response: {}", e), None))?;
+        let api_keys_response: crate::types::ApiKeysResponse = self.decode_json(response).await?;
 
         Ok(api_keys_response.api_keys)
     }
@@ -589,8 +1925,14 @@ impl ClobClient {
 
         let method = Method::DELETE;
         let endpoint = "/auth/api-key";
-        let headers =
-            create_l2_headers::<Value>(signer, api_creds, method.as_str(), endpoint, None)?;
+        let headers = create_l2_headers_at::<Value>(
+            signer,
+            api_creds,
+            method.as_str(),
+            endpoint,
+            None,
+            self.clock.now_secs(),
+        )?;
 
         let response = self
             .http_client
@@ -601,9 +1943,9 @@ impl ClobClient {
                     .map(|(k, v)| (HeaderName::from_static(k), v.parse().unwrap()))
                     .collect(),
             )
-            .send()
+            .send_via(self)
             .await
-            .map_err(|e| PolyError::network(format!("Request failed: {}", e), e))?;
+            .map_err(request_failed)?;
 
         response
             .text()
@@ -628,7 +1970,7 @@ impl ClobClient {
             .http_client
             .get(self.clob_url("neg-risk"))
             .query(&[("token_id", token_id)])
-            .send()
+            .send_via(self)
             .await?;
 
         if !response.status().is_success() {
@@ -646,15 +1988,130 @@ impl ClobClient {
         Ok(neg_risk)
     }
 
-    /// Resolve tick size for an order
-    async fn resolve_tick_size(
-        &self,
-        token_id: &str,
-        tick_size: Option<Decimal>,
-    ) -> Result<Decimal> {
-        let min_tick_size = self.get_tick_size(token_id).await?;
-
-        match tick_size {
+    /// Bulk-load tick sizes, neg-risk flags, and minimum sizes into the metadata cache.
+    ///
+    /// Token IDs are looked up on Gamma in batches of 20 rather than two CLOB requests per
+    /// token. Returns how many of `token_ids` were cached; tokens Gamma doesn't return are
+    /// left for the order path to fetch individually.
+    pub async fn prefetch_market_meta(&self, token_ids: &[String]) -> Result<usize> {
+        use futures::stream::{self, StreamExt};
+
+        let cache = self
+            .market_meta
+            .as_ref()
+            .ok_or_else(|| PolyError::config("Market metadata cache not configured"))?;
+        let wanted: std::collections::HashSet<&str> =
+            token_ids.iter().map(String::as_str).collect();
+
+        let pages: Vec<Result<Vec<crate::types::GammaMarket>>> =
+            stream::iter(token_ids.chunks(META_PREFETCH_CHUNK))
+                .map(|chunk| self.get_gamma_markets_by_token(chunk))
+                .buffer_unordered(4)
+                .collect()
+                .await;
+
+        let mut loaded = 0;
+        for market in pages
+            .into_iter()
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+        {
+            let (Some(tick_size), Some(neg_risk)) = (market.order_tick_size, market.neg_risk)
+            else {
+                continue;
+            };
+            let meta = MarketMeta {
+                tick_size,
+                neg_risk,
+                min_order_size: market.order_min_size,
+            };
+            for token_id in market.parse_token_ids() {
+                if wanted.contains(token_id.as_str()) {
+                    cache.insert(token_id, meta);
+                    loaded += 1;
+                }
+            }
+        }
+
+        Ok(loaded)
+    }
+
+    async fn get_gamma_markets_by_token(
+        &self,
+        token_ids: &[String],
+    ) -> Result<Vec<crate::types::GammaMarket>> {
+        self.get_gamma_markets_by("clob_token_ids", token_ids).await
+    }
+
+    /// One `/markets` request filtered by a repeated `key`, e.g. `id=1&id=2`
+    async fn get_gamma_markets_by(
+        &self,
+        key: &'static str,
+        values: &[String],
+    ) -> Result<Vec<crate::types::GammaMarket>> {
+        let mut query: Vec<(&str, String)> =
+            values.iter().map(|value| (key, value.clone())).collect();
+        query.push(("limit", values.len().to_string()));
+
+        let response = self
+            .http_client
+            .get(self.gamma_url("markets"))
+            .query(&query)
+            .send_via(self)
+            .await
+            .map_err(request_failed)?;
+
+        if !response.status().is_success() {
+            return Err(PolyError::api(
+                response.status().as_u16(),
+                "Failed to fetch markets by token",
+            ));
+        }
+
+        self.decode_json(response).await
+    }
+
+    /// Tick size and neg-risk flag from the cache, fetching (and caching) both on a miss
+    ///
+    /// A `neg_risk` the caller already knows wins over the cached flag, and on a miss
+    /// only the tick size is fetched. The cache holds complete entries only, so that
+    /// lookup isn't cached.
+    async fn cached_market_meta(
+        &self,
+        cache: &MarketMetaCache,
+        token_id: &str,
+        neg_risk: Option<bool>,
+    ) -> Result<(Decimal, bool)> {
+        self.poll_epoch().await;
+        if let Some(meta) = cache.get(token_id) {
+            return Ok((meta.tick_size, neg_risk.unwrap_or(meta.neg_risk)));
+        }
+
+        if let Some(neg_risk) = neg_risk {
+            return Ok((self.get_tick_size(token_id).await?, neg_risk));
+        }
+
+        let (tick_size, neg_risk) =
+            futures::try_join!(self.get_tick_size(token_id), self.get_neg_risk(token_id))?;
+        cache.insert(
+            token_id,
+            MarketMeta {
+                tick_size,
+                neg_risk,
+                min_order_size: None,
+            },
+        );
+        Ok((tick_size, neg_risk))
+    }
+
+    /// Resolve tick size for an order
+    fn resolve_tick_size(
+        token_id: &str,
+        tick_size: Option<Decimal>,
+        min_tick_size: Decimal,
+    ) -> Result<Decimal> {
+        match tick_size {
             None => Ok(min_tick_size),
             Some(t) => {
                 if t < min_tick_size {
@@ -680,11 +2137,18 @@ impl ClobClient {
             None => (None, None, None),
         };
 
-        let tick_size = self.resolve_tick_size(token_id, tick_size).await?;
-        let neg_risk = match neg_risk {
-            Some(nr) => nr,
-            None => self.get_neg_risk(token_id).await?,
+        let (min_tick_size, neg_risk) = match &self.market_meta {
+            Some(cache) => self.cached_market_meta(cache, token_id, neg_risk).await?,
+            None => {
+                let min_tick_size = self.get_tick_size(token_id).await?;
+                let neg_risk = match neg_risk {
+                    Some(nr) => nr,
+                    None => self.get_neg_risk(token_id).await?,
+                };
+                (min_tick_size, neg_risk)
+            }
         };
+        let tick_size = Self::resolve_tick_size(token_id, tick_size, min_tick_size)?;
 
         Ok(OrderOptions {
             tick_size: Some(tick_size),
@@ -743,7 +2207,7 @@ impl ClobClient {
         side: Side,
         amount: Decimal,
     ) -> Result<Decimal> {
-        let book = self.get_order_book(token_id).await?;
+        let book = self.get_order_book(token_id, None).await?;
         let order_builder = self
             .order_builder
             .as_ref()
@@ -815,6 +2279,60 @@ impl ClobClient {
         order: SignedOrderRequest,
         order_type: OrderType,
     ) -> Result<Value> {
+        let (body, headers) = self.prepare_post_order(order, order_type)?;
+        if env::var("POLY_LOG_REQUEST").is_ok()
+            && let Ok(body_text) = serde_json::to_string(&body)
+        {
+            println!("rust request url    : {}", self.clob_url("/order"));
+            println!("rust request method : POST");
+            println!("rust request headers: {:?}", headers);
+            println!("rust request body   : {}", body_text);
+        }
+        let req = self.create_request_with_headers(Method::POST, "/order", headers.into_iter());
+
+        let response = req.json(&body).send_via(self).await?;
+        if !response.status().is_success() {
+            return Err(PolyError::api(
+                response.status().as_u16(),
+                format!("Failed to post order {}", response.text().await.unwrap()),
+            ));
+        }
+
+        Ok(response.json::<Value>().await?)
+    }
+
+    /// Build and sign `order_args` as [`create_and_post_order`](Self::create_and_post_order)
+    /// would, and return the request instead of sending it
+    ///
+    /// The tick size and neg-risk lookups behind [`create_order`](Self::create_order)
+    /// still run (from the metadata cache when one is attached); only the post itself
+    /// is skipped. Headers are stamped from the client's clock (see
+    /// [`with_clock`](Self::with_clock)). The order salt is random, so the salt and the
+    /// order signature differ between calls.
+    pub async fn post_order_dry_run(&self, order_args: &OrderArgs) -> Result<SignedOrderPreview> {
+        let order = self.create_order(order_args, None, None, None).await?;
+        let (body, headers) = self.prepare_post_order(order, OrderType::GTC)?;
+        let body_text = serde_json::to_string(&body)
+            .map_err(|e| PolyError::parse(format!("Failed to serialize order: {}", e), None))?;
+
+        Ok(SignedOrderPreview {
+            method: Method::POST.to_string(),
+            url: self.clob_url("/order"),
+            headers: headers
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value))
+                .collect(),
+            body: body_text,
+            order: body.order,
+        })
+    }
+
+    /// Wrap `order` for `POST /order` and sign the request
+    fn prepare_post_order(
+        &self,
+        order: SignedOrderRequest,
+        order_type: OrderType,
+    ) -> Result<(PostOrder, std::collections::HashMap<&'static str, String>)> {
         let signer = self
             .signer
             .as_ref()
@@ -824,28 +2342,45 @@ impl ClobClient {
             .as_ref()
             .ok_or_else(|| PolyError::auth("API credentials not set"))?;
 
+        self.check_order_notional(&order)?;
         let body = PostOrder::new(order, api_creds.api_key.clone(), order_type);
+        let headers = create_l2_headers_at(
+            signer,
+            api_creds,
+            "POST",
+            "/order",
+            Some(&body),
+            self.clock.now_secs(),
+        )?;
+        Ok((body, headers))
+    }
 
-        let headers = create_l2_headers(signer, api_creds, "POST", "/order", Some(&body))?;
-        if env::var("POLY_LOG_REQUEST").is_ok() {
-            if let Ok(body_text) = serde_json::to_string(&body) {
-                println!("rust request url    : {}", self.clob_url("/order"));
-                println!("rust request method : POST");
-                println!("rust request headers: {:?}", headers);
-                println!("rust request body   : {}", body_text);
-            }
-        }
-        let req = self.create_request_with_headers(Method::POST, "/order", headers.into_iter());
-
-        let response = req.json(&body).send().await?;
-        if !response.status().is_success() {
-            return Err(PolyError::api(
-                response.status().as_u16(),
-                format!("Failed to post order {}", response.text().await.unwrap()),
+    /// Enforce [`with_max_order_notional`](Self::with_max_order_notional) on a signed order
+    fn check_order_notional(&self, order: &SignedOrderRequest) -> Result<()> {
+        let Some(limit) = self.max_order_notional else {
+            return Ok(());
+        };
+        // The collateral leg is what a buy pays and what a sell receives
+        let collateral = if order.side.eq_ignore_ascii_case("BUY") {
+            &order.maker_amount
+        } else {
+            &order.taker_amount
+        };
+        let units: u64 = collateral
+            .parse()
+            .map_err(|_| PolyError::validation(format!("Invalid order amount: {}", collateral)))?;
+        let notional = crate::utils::math::token_units_to_decimal(units);
+        if notional > limit {
+            return Err(PolyError::order(
+                format!(
+                    "Order notional {} exceeds the limit of {}",
+                    notional.normalize(),
+                    limit
+                ),
+                crate::errors::OrderErrorKind::SizeConstraint,
             ));
         }
-
-        Ok(response.json::<Value>().await?)
+        Ok(())
     }
 
     /// Post multiple orders in a single batch request
@@ -868,26 +2403,36 @@ impl ClobClient {
             .api_creds
             .as_ref()
             .ok_or_else(|| PolyError::auth("API credentials not set"))?;
+        for order in &orders {
+            self.check_order_notional(order)?;
+        }
 
         let batch: Vec<PostOrder> = orders
             .into_iter()
-            .map(|order| PostOrder::new(order, api_creds.api_key.clone(), order_type.clone()))
+            .map(|order| PostOrder::new(order, api_creds.api_key.clone(), order_type))
             .collect();
 
-        let headers = create_l2_headers(signer, api_creds, "POST", "/orders", Some(&batch))?;
+        let headers = create_l2_headers_at(
+            signer,
+            api_creds,
+            "POST",
+            "/orders",
+            Some(&batch),
+            self.clock.now_secs(),
+        )?;
 
-        if env::var("POLY_LOG_REQUEST").is_ok() {
-            if let Ok(body_text) = serde_json::to_string(&batch) {
-                println!("rust request url    : {}", self.clob_url("/orders"));
-                println!("rust request method : POST");
-                println!("rust request headers: {:?}", headers);
-                println!("rust request body   : {}", body_text);
-            }
+        if env::var("POLY_LOG_REQUEST").is_ok()
+            && let Ok(body_text) = serde_json::to_string(&batch)
+        {
+            println!("rust request url    : {}", self.clob_url("/orders"));
+            println!("rust request method : POST");
+            println!("rust request headers: {:?}", headers);
+            println!("rust request body   : {}", body_text);
         }
 
         let req = self.create_request_with_headers(Method::POST, "/orders", headers.into_iter());
 
-        let response = req.json(&batch).send().await?;
+        let response = req.json(&batch).send_via(self).await?;
         if !response.status().is_success() {
             return Err(PolyError::api(
                 response.status().as_u16(),
@@ -904,6 +2449,152 @@ impl ClobClient {
         self.post_order(order, OrderType::GTC).await
     }
 
+    /// Post `order`, retrying transient failures, and wait for the exchange to know it
+    ///
+    /// The order is signed once and the same signed order is re-posted on every attempt,
+    /// so a post that was accepted despite a failed response can't be doubled: the
+    /// exchange rejects the repeat as a duplicate. After such a failure a duplicate
+    /// rejection is looked up by the order's hash (its ID, see
+    /// [`signed_order_hash`](crate::orders::signed_order_hash)) and counts as success,
+    /// returning `{"success": true, "orderID", "status"}` built from the lookup.
+    ///
+    /// Network errors, timeouts and 5xx responses are retried after `policy.backoff`;
+    /// anything else, including an order the exchange refuses, is returned at once. An
+    /// accepted order is confirmed with [`get_order`](Self::get_order) in any status, so
+    /// one that filled or was killed before the lookup still counts; one the exchange
+    /// still doesn't know after `policy.confirmation_timeout` is treated as lost and
+    /// posted again. Orders the response reports as `matched` are not looked up.
+    pub async fn place_order_with_retry(
+        &self,
+        order: &crate::types::OrderRequest,
+        policy: &crate::types::RetryPolicy,
+    ) -> Result<Value> {
+        let args = OrderArgs::new(&order.token_id, order.price, order.size, order.side);
+        let expiration = order.expiration.map(|at| at.timestamp().max(0) as u64);
+        let options = self.get_filled_order_options(&order.token_id, None).await?;
+        let signed = self
+            .create_order(&args, expiration, None, Some(&options))
+            .await?;
+        let order_hash = crate::orders::signed_order_hash(
+            &signed,
+            self.chain_id,
+            options.neg_risk.unwrap_or_default(),
+        )?;
+
+        // Whether an earlier attempt may have reached the exchange
+        let mut maybe_posted = false;
+        let max_attempts = policy.max_attempts.max(1);
+        for attempt in 1..=max_attempts {
+            if attempt > 1 {
+                tokio::time::sleep(policy.backoff).await;
+            }
+
+            let posted = self
+                .post_order(signed.clone(), order.order_type)
+                .await
+                .and_then(|response| Ok((accepted_order_id(&response)?, response)));
+            let (order_id, response) = match posted {
+                Ok(posted) => posted,
+                Err(err)
+                    if maybe_posted && err.to_string().to_lowercase().contains("duplicate") =>
+                {
+                    if let Some(existing) = self.confirm_order(&order_hash, policy).await {
+                        return Ok(serde_json::json!({
+                            "success": true,
+                            "orderID": existing.id,
+                            "status": existing.status,
+                        }));
+                    }
+                    return Err(err);
+                }
+                Err(err) if err.is_retryable() && attempt < max_attempts => {
+                    tracing::warn!(attempt, error = %err, "order post failed, retrying");
+                    maybe_posted = true;
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+
+            let status = response.get("status").and_then(Value::as_str);
+            if status.is_some_and(|s| s.eq_ignore_ascii_case("matched"))
+                || self.confirm_order(&order_id, policy).await.is_some()
+            {
+                return Ok(response);
+            }
+            tracing::warn!(attempt, order_id, "order missing after submission");
+            maybe_posted = true;
+        }
+
+        Err(PolyError::validation("order lost after submission"))
+    }
+
+    /// Poll [`get_order`](Self::get_order) until `order_id` is found, in any status, or
+    /// the confirmation window closes
+    async fn confirm_order(
+        &self,
+        order_id: &str,
+        policy: &crate::types::RetryPolicy,
+    ) -> Option<crate::types::OpenOrder> {
+        let deadline = tokio::time::Instant::now() + policy.confirmation_timeout;
+        loop {
+            // A failed lookup proves nothing either way; keep trying until the deadline
+            if let Ok(found) = self.get_order(order_id).await
+                && found.id.eq_ignore_ascii_case(order_id)
+            {
+                return Some(found);
+            }
+
+            let now = tokio::time::Instant::now();
+            if now >= deadline {
+                return None;
+            }
+            tokio::time::sleep(policy.poll_interval.min(deadline - now)).await;
+        }
+    }
+
+    /// Post a GTC limit order priced from the probability of the market's "Yes" outcome.
+    ///
+    /// The token's market is looked up on Gamma to tell the Yes token (priced at
+    /// `probability`) from the No token (priced at `1 - probability`), and the price is
+    /// rounded to the token's tick size, taken from the metadata cache when one is attached.
+    pub async fn create_order_from_probability(
+        &self,
+        token_id: &str,
+        probability: Decimal,
+        size: Decimal,
+        side: Side,
+    ) -> Result<Value> {
+        crate::types::validate_probability(probability)?;
+
+        let market = self
+            .get_gamma_markets_by_token(&[token_id.to_string()])
+            .await?
+            .into_iter()
+            .map(crate::types::Market::from)
+            .find(|market| market.tokens.iter().any(|token| token.token_id == token_id))
+            .ok_or_else(|| {
+                PolyError::market_data(
+                    format!("No market found for token: {}", token_id),
+                    crate::errors::MarketDataErrorKind::TokenNotFound,
+                )
+            })?;
+        let price = market.price_for_probability(token_id, probability)?;
+
+        let options = self.get_filled_order_options(token_id, None).await?;
+        let tick_size = options.tick_size.expect("Should be filled");
+        let order_args = OrderArgs::new(
+            token_id,
+            crate::utils::math::round_to_tick(price, tick_size),
+            size,
+            side,
+        );
+
+        let order = self
+            .create_order(&order_args, None, None, Some(&options))
+            .await?;
+        self.post_order(order, OrderType::GTC).await
+    }
+
     /// Cancel an order
     pub async fn cancel(&self, order_id: &str) -> Result<Value> {
         let signer = self
@@ -917,10 +2608,17 @@ impl ClobClient {
 
         let body = std::collections::HashMap::from([("orderID", order_id)]);
 
-        let headers = create_l2_headers(signer, api_creds, "DELETE", "/order", Some(&body))?;
+        let headers = create_l2_headers_at(
+            signer,
+            api_creds,
+            "DELETE",
+            "/order",
+            Some(&body),
+            self.clock.now_secs(),
+        )?;
         let req = self.create_request_with_headers(Method::DELETE, "/order", headers.into_iter());
 
-        let response = req.json(&body).send().await?;
+        let response = req.json(&body).send_via(self).await?;
         if !response.status().is_success() {
             return Err(PolyError::api(
                 response.status().as_u16(),
@@ -942,10 +2640,17 @@ impl ClobClient {
             .as_ref()
             .ok_or_else(|| PolyError::auth("API credentials not set"))?;
 
-        let headers = create_l2_headers(signer, api_creds, "DELETE", "/orders", Some(order_ids))?;
+        let headers = create_l2_headers_at(
+            signer,
+            api_creds,
+            "DELETE",
+            "/orders",
+            Some(order_ids),
+            self.clock.now_secs(),
+        )?;
         let req = self.create_request_with_headers(Method::DELETE, "/orders", headers.into_iter());
 
-        let response = req.json(order_ids).send().await?;
+        let response = req.json(order_ids).send_via(self).await?;
         if !response.status().is_success() {
             return Err(PolyError::api(
                 response.status().as_u16(),
@@ -967,11 +2672,18 @@ impl ClobClient {
             .as_ref()
             .ok_or_else(|| PolyError::auth("API credentials not set"))?;
 
-        let headers = create_l2_headers::<Value>(signer, api_creds, "DELETE", "/cancel-all", None)?;
+        let headers = create_l2_headers_at::<Value>(
+            signer,
+            api_creds,
+            "DELETE",
+            "/cancel-all",
+            None,
+            self.clock.now_secs(),
+        )?;
         let req =
             self.create_request_with_headers(Method::DELETE, "/cancel-all", headers.into_iter());
 
-        let response = req.send().await?;
+        let response = req.send_via(self).await?;
         if !response.status().is_success() {
             return Err(PolyError::api(
                 response.status().as_u16(),
@@ -1006,8 +2718,14 @@ impl ClobClient {
 
         let method = Method::GET;
         let endpoint = "/data/orders";
-        let headers =
-            create_l2_headers::<Value>(signer, api_creds, method.as_str(), endpoint, None)?;
+        let headers = create_l2_headers_at::<Value>(
+            signer,
+            api_creds,
+            method.as_str(),
+            endpoint,
+            None,
+            self.clock.now_secs(),
+        )?;
 
         let query_params = match params {
             None => Vec::new(),
@@ -1031,9 +2749,9 @@ impl ClobClient {
                 .fold(req, |r, (k, v)| r.header(HeaderName::from_static(k), v));
 
             let resp = r
-                .send()
+                .send_via(self)
                 .await
-                .map_err(|e| PolyError::network(format!("Request failed: {}", e), e))?
+                .map_err(request_failed)?
                 .json::<Value>()
                 .await
                 .map_err(|e| PolyError::parse(format!("Failed to parse response: {}", e), None))?;
@@ -1085,8 +2803,14 @@ impl ClobClient {
 
         let method = Method::GET;
         let endpoint = "/data/trades";
-        let headers =
-            create_l2_headers::<Value>(signer, api_creds, method.as_str(), endpoint, None)?;
+        let headers = create_l2_headers_at::<Value>(
+            signer,
+            api_creds,
+            method.as_str(),
+            endpoint,
+            None,
+            self.clock.now_secs(),
+        )?;
 
         let query_params = match trade_params {
             None => Vec::new(),
@@ -1110,9 +2834,9 @@ impl ClobClient {
                 .fold(req, |r, (k, v)| r.header(HeaderName::from_static(k), v));
 
             let resp = r
-                .send()
+                .send_via(self)
                 .await
-                .map_err(|e| PolyError::network(format!("Request failed: {}", e), e))?
+                .map_err(request_failed)?
                 .json::<Value>()
                 .await
                 .map_err(|e| PolyError::parse(format!("Failed to parse response: {}", e), None))?;
@@ -1131,6 +2855,225 @@ impl ClobClient {
         Ok(output)
     }
 
+    /// Realized P&L per closed lot, for tax reporting
+    ///
+    /// Replays the authenticated trade history up to `end_date` and matches sells
+    /// against earlier buys first-in, first-out (see [`crate::pnl`]), so lots opened
+    /// before `start_date` are priced from their real entry. Only lots closed within
+    /// the window are returned, oldest first, paged by `limit`/`cursor`. The history is
+    /// replayed for the first page only; following a cursor pages the same result.
+    pub async fn get_realized_pnl_history(
+        &self,
+        params: &crate::pnl::PnlHistoryParams<'_>,
+    ) -> Result<crate::types::PagedResponse<crate::pnl::RealizedPnlEntry>> {
+        params.validate()?;
+        let window: PnlWindow = (
+            params.start_date,
+            params.end_date,
+            params.token_id.map(str::to_string),
+        );
+        let cached = params
+            .cursor
+            .and_then(|_| {
+                self.realized_pnl
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .clone()
+            })
+            .filter(|(cached_window, _)| *cached_window == window)
+            .map(|(_, entries)| entries);
+        let entries = match cached {
+            Some(entries) => entries,
+            None => {
+                let entries: Arc<[_]> = crate::pnl::filter_entries(
+                    crate::pnl::realize_fifo(self.get_trade_history(params).await?),
+                    params,
+                )
+                .into();
+                *self
+                    .realized_pnl
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner) = Some((window, entries.clone()));
+                entries
+            }
+        };
+
+        let total = entries.len();
+        let offset = match params.cursor {
+            Some(cursor) => Self::decode_cursor(cursor)
+                .ok_or_else(|| PolyError::validation(format!("Invalid cursor: {}", cursor)))?
+                as usize,
+            None => 0,
+        };
+        let limit = params.limit.unwrap_or(total).max(1);
+        let data: Vec<_> = entries.iter().skip(offset).take(limit).cloned().collect();
+        let end = offset + data.len();
+        Ok(crate::types::PagedResponse {
+            next_cursor: (end < total).then(|| Self::encode_cursor(end as u64)),
+            data,
+            total,
+        })
+    }
+
+    /// Realized P&L totals for the UTC calendar year `year`
+    pub async fn get_realized_pnl_summary(
+        &self,
+        year: u32,
+    ) -> Result<crate::pnl::AnnualPnlSummary> {
+        let day = |month, day| {
+            chrono::NaiveDate::from_ymd_opt(year as i32, month, day)
+                .ok_or_else(|| PolyError::validation(format!("Invalid tax year: {}", year)))
+        };
+        let params = crate::pnl::PnlHistoryParams::new(day(1, 1)?, day(12, 31)?);
+        let page = self.get_realized_pnl_history(&params).await?;
+        Ok(crate::pnl::AnnualPnlSummary::from_entries(year, &page.data))
+    }
+
+    /// Write the authenticated trade history matching `params` as CSV, oldest first
+    ///
+    /// Every page is fetched before anything is written. Columns are
+    /// [`TRADE_HEADERS`](crate::export::csv::TRADE_HEADERS); pass `excel_compat` to
+    /// prefix a UTF-8 byte order mark for Excel. Returns the number of data rows.
+    #[cfg(feature = "csv")]
+    pub async fn export_trade_history_csv(
+        &self,
+        params: &crate::types::TradeParams,
+        writer: impl std::io::Write,
+        excel_compat: bool,
+    ) -> Result<u64> {
+        let mut trades = self.get_historical_trades(params).await?;
+        crate::export::csv::write_trades_csv(&mut trades, writer, excel_compat)
+    }
+
+    /// Write the funder wallet's positions as CSV, as listed by the Data API
+    ///
+    /// Columns are [`POSITION_HEADERS`](crate::export::csv::POSITION_HEADERS); see
+    /// [`export_trade_history_csv`](Self::export_trade_history_csv) for `excel_compat`.
+    #[cfg(feature = "csv")]
+    pub async fn export_positions_csv(
+        &self,
+        writer: impl std::io::Write,
+        excel_compat: bool,
+    ) -> Result<u64> {
+        let positions = self.get_funder_positions().await?;
+        crate::export::csv::write_positions_csv(&positions, writer, excel_compat)
+    }
+
+    /// Download every listed market in one file
+    ///
+    /// `Json` returns the body of `GET /snapshot/markets` untouched. `Csv` (columns
+    /// [`MARKET_HEADERS`](crate::export::csv::MARKET_HEADERS)) and `Parquet` (see
+    /// [`write_markets_to_parquet`](crate::export::write_markets_to_parquet)) are
+    /// transcoded from that JSON here, and fail with a config error before any request
+    /// is made when the matching feature is off.
+    pub async fn download_market_snapshot(
+        &self,
+        format: crate::export::SnapshotFormat,
+    ) -> Result<Vec<u8>> {
+        format.ensure_available()?;
+        let body = self.request_market_snapshot().await?.bytes().await?;
+        if format == crate::export::SnapshotFormat::Json {
+            return Ok(Vec::from(body));
+        }
+        let markets: Vec<crate::types::Market> =
+            crate::decode::from_slice_with_mode(&body, self.number_mode)?;
+        format.encode_markets(&markets)
+    }
+
+    /// [`download_market_snapshot`](Self::download_market_snapshot) straight into a
+    /// file at `path`, created or truncated, returning the number of bytes written
+    ///
+    /// A JSON snapshot is streamed to disk as it arrives; the other formats are
+    /// transcoded in memory first.
+    pub async fn download_market_snapshot_to_file(
+        &self,
+        format: crate::export::SnapshotFormat,
+        path: &std::path::Path,
+    ) -> Result<u64> {
+        use futures::StreamExt;
+        use tokio::io::AsyncWriteExt;
+
+        let file_error = |e: std::io::Error| {
+            PolyError::internal(format!("Failed to write {}", path.display()), e)
+        };
+        if format != crate::export::SnapshotFormat::Json {
+            let bytes = self.download_market_snapshot(format).await?;
+            tokio::fs::write(path, &bytes).await.map_err(file_error)?;
+            return Ok(bytes.len() as u64);
+        }
+
+        let response = self.request_market_snapshot().await?;
+        let mut file = tokio::fs::File::create(path).await.map_err(file_error)?;
+        let mut body = response.bytes_stream();
+        let mut written = 0u64;
+        while let Some(chunk) = body.next().await {
+            let chunk = chunk?;
+            file.write_all(&chunk).await.map_err(file_error)?;
+            written += chunk.len() as u64;
+        }
+        file.flush().await.map_err(file_error)?;
+        Ok(written)
+    }
+
+    async fn request_market_snapshot(&self) -> Result<reqwest::Response> {
+        let response = self
+            .http_client
+            .get(self.clob_url("snapshot/markets"))
+            .send_via(self)
+            .await
+            .map_err(request_failed)?;
+        if !response.status().is_success() {
+            return Err(PolyError::api(
+                response.status().as_u16(),
+                "Failed to download market snapshot",
+            ));
+        }
+        Ok(response)
+    }
+
+    /// Every trade up to the end of the P&L window, across all pages
+    async fn get_trade_history(
+        &self,
+        params: &crate::pnl::PnlHistoryParams<'_>,
+    ) -> Result<Vec<crate::pnl::HistoricalTrade>> {
+        let trade_params = crate::types::TradeParams {
+            id: None,
+            maker_address: None,
+            market: None,
+            asset_id: params.token_id.map(str::to_string),
+            before: u64::try_from(params.end_exclusive_ts()).ok(),
+            after: None,
+        };
+        self.get_historical_trades(&trade_params).await
+    }
+
+    /// The authenticated user's leg of every trade matching `params`, across all pages
+    async fn get_historical_trades(
+        &self,
+        params: &crate::types::TradeParams,
+    ) -> Result<Vec<crate::pnl::HistoricalTrade>> {
+        let pages = self.get_trades(Some(params), None).await?;
+
+        let api_key = self
+            .api_creds
+            .as_ref()
+            .map(|creds| creds.api_key.as_str())
+            .unwrap_or_default();
+        let funder = self.order_builder.as_ref().map(|builder| builder.funder());
+
+        let mut trades = Vec::new();
+        for page in pages {
+            let records: Vec<crate::pnl::TradeRecord> = serde_json::from_value(page)
+                .map_err(|e| PolyError::parse(format!("Failed to parse trades: {}", e), None))?;
+            trades.extend(
+                records
+                    .into_iter()
+                    .flat_map(|record| record.user_legs(api_key, funder)),
+            );
+        }
+        Ok(trades)
+    }
+
     /// Get balance and allowance information for all assets
     ///
     /// This returns the current balance and allowance for each asset in your account.
@@ -1165,8 +3108,14 @@ impl ClobClient {
 
         let method = Method::GET;
         let endpoint = "/balance-allowance";
-        let headers =
-            create_l2_headers::<Value>(signer, api_creds, method.as_str(), endpoint, None)?;
+        let headers = create_l2_headers_at::<Value>(
+            signer,
+            api_creds,
+            method.as_str(),
+            endpoint,
+            None,
+            self.clock.now_secs(),
+        )?;
 
         let response = self
             .http_client
@@ -1178,9 +3127,9 @@ impl ClobClient {
                     .collect(),
             )
             .query(&query_params)
-            .send()
+            .send_via(self)
             .await
-            .map_err(|e| PolyError::network(format!("Request failed: {}", e), e))?;
+            .map_err(request_failed)?;
 
         response
             .json::<Value>()
@@ -1208,8 +3157,14 @@ impl ClobClient {
 
         let method = Method::GET;
         let endpoint = "/notifications";
-        let headers =
-            create_l2_headers::<Value>(signer, api_creds, method.as_str(), endpoint, None)?;
+        let headers = create_l2_headers_at::<Value>(
+            signer,
+            api_creds,
+            method.as_str(),
+            endpoint,
+            None,
+            self.clock.now_secs(),
+        )?;
 
         let response = self
             .http_client
@@ -1229,9 +3184,9 @@ impl ClobClient {
                     .get_sig_type()
                     .to_string(),
             )])
-            .send()
+            .send_via(self)
             .await
-            .map_err(|e| PolyError::network(format!("Request failed: {}", e), e))?;
+            .map_err(request_failed)?;
 
         response
             .json::<Value>()
@@ -1263,7 +3218,7 @@ impl ClobClient {
             .http_client
             .post(self.clob_url("midpoints"))
             .json(&request_data)
-            .send()
+            .send_via(self)
             .await?;
 
         if !response.status().is_success() {
@@ -1273,8 +3228,30 @@ impl ClobClient {
             ));
         }
 
-        let midpoints: std::collections::HashMap<String, Decimal> = response.json().await?;
-        Ok(midpoints)
+        let midpoints: std::collections::HashMap<String, crate::serde_decimal::Wire> =
+            self.decode_json(response).await?;
+        Ok(midpoints
+            .into_iter()
+            .map(|(token, mid)| (token, mid.0))
+            .collect())
+    }
+
+    /// Midpoints of `token_ids` in the order given, for pricing every outcome of an event
+    ///
+    /// Pass the result to [`Market::outcome_probabilities`] or
+    /// [`Market::outcome_price_deviation`]. Fails with a validation error naming the
+    /// first token the CLOB returned no midpoint for.
+    pub async fn get_outcome_prices(&self, token_ids: &[String]) -> Result<Vec<Decimal>> {
+        let midpoints = self.get_midpoints(token_ids).await?;
+        token_ids
+            .iter()
+            .map(|id| {
+                midpoints
+                    .get(id)
+                    .copied()
+                    .ok_or_else(|| PolyError::validation(format!("no midpoint for token {}", id)))
+            })
+            .collect()
     }
 
     /// Get bid/ask/mid prices for multiple tokens in a single request
@@ -1302,7 +3279,7 @@ impl ClobClient {
             .http_client
             .post(self.clob_url("prices"))
             .json(&request_data)
-            .send()
+            .send_via(self)
             .await?;
 
         if !response.status().is_success() {
@@ -1312,9 +3289,17 @@ impl ClobClient {
             ));
         }
 
-        let prices: std::collections::HashMap<String, std::collections::HashMap<Side, Decimal>> =
-            response.json().await?;
-        Ok(prices)
+        let prices: std::collections::HashMap<
+            String,
+            std::collections::HashMap<Side, crate::serde_decimal::Wire>,
+        > = self.decode_json(response).await?;
+        Ok(prices
+            .into_iter()
+            .map(|(token, sides)| {
+                let sides = sides.into_iter().map(|(side, price)| (side, price.0));
+                (token, sides.collect())
+            })
+            .collect())
     }
 
     /// Get order book for multiple tokens (batch) - reference implementation compatible
@@ -1332,14 +3317,39 @@ impl ClobClient {
             .http_client
             .post(self.clob_url("books"))
             .json(&request_data)
-            .send()
+            .send_via(self)
             .await
-            .map_err(|e| PolyError::network(format!("Request failed: {}", e), e))?;
+            .map_err(request_failed)?;
 
-        response
-            .json::<Vec<OrderBookSummary>>()
-            .await
-            .map_err(|e| PolyError::parse(format!("Failed to parse response: {}", e), None))
+        self.decode_json::<Vec<OrderBookSummary>>(response).await
+    }
+
+    /// Summarize best bid/ask, spread, and top-level depth for many tokens at once.
+    ///
+    /// Books are fetched through [`ClobClient::get_order_books`] in batches of
+    /// 50 tokens with at most four batches in flight, which keeps a scan of a few hundred
+    /// markets well inside the CLOB rate limits. Results are sorted with
+    /// [`SpreadSummary::cmp_tightness`](crate::types::SpreadSummary::cmp_tightness), so
+    /// the tightest, deepest books come first.
+    pub async fn scan_spreads(
+        &self,
+        token_ids: &[String],
+    ) -> Result<Vec<crate::types::SpreadSummary>> {
+        use futures::stream::{self, StreamExt, TryStreamExt};
+
+        let books: Vec<Vec<OrderBookSummary>> = stream::iter(token_ids.chunks(SCAN_BATCH_SIZE))
+            .map(|chunk| self.get_order_books(chunk))
+            .buffer_unordered(SCAN_CONCURRENCY)
+            .try_collect()
+            .await?;
+
+        let mut summaries: Vec<crate::types::SpreadSummary> = books
+            .iter()
+            .flatten()
+            .map(crate::types::SpreadSummary::from_book)
+            .collect();
+        summaries.sort_by(|a, b| a.cmp_tightness(b));
+        Ok(summaries)
     }
 
     /// Get single order by ID
@@ -1355,8 +3365,14 @@ impl ClobClient {
 
         let method = Method::GET;
         let endpoint = &format!("/data/order/{}", order_id);
-        let headers =
-            create_l2_headers::<Value>(signer, api_creds, method.as_str(), endpoint, None)?;
+        let headers = create_l2_headers_at::<Value>(
+            signer,
+            api_creds,
+            method.as_str(),
+            endpoint,
+            None,
+            self.clock.now_secs(),
+        )?;
 
         let response = self
             .http_client
@@ -1367,14 +3383,101 @@ impl ClobClient {
                     .map(|(k, v)| (HeaderName::from_static(k), v.parse().unwrap()))
                     .collect(),
             )
-            .send()
+            .send_via(self)
             .await
-            .map_err(|e| PolyError::network(format!("Request failed: {}", e), e))?;
+            .map_err(request_failed)?;
 
-        response
-            .json::<crate::types::OpenOrder>()
-            .await
-            .map_err(|e| PolyError::parse(format!("Failed to parse response: {}", e), None))
+        self.decode_json::<crate::types::OpenOrder>(response).await
+    }
+
+    /// Poll [`ClobClient::get_order`] until the order fills, is canceled, or `timeout` passes.
+    ///
+    /// An order counts as filled once it reports `MATCHED` or its matched size reaches the
+    /// original size; `CANCELED` (and `UNMATCHED`, for marketable orders that never crossed)
+    /// return a validation error. Retryable errors from `get_order` are swallowed until the
+    /// deadline.
+    ///
+    /// Polling costs one request per `poll_interval` and only notices a fill on the next
+    /// poll, but needs nothing besides the REST client. Prefer
+    /// [`ClobClient::wait_for_order_fill_via_ws`] when a user channel connection is at hand.
+    pub async fn wait_for_order_fill(
+        &self,
+        order_id: &str,
+        timeout: std::time::Duration,
+        poll_interval: std::time::Duration,
+    ) -> Result<crate::types::OpenOrder> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            match self.get_order(order_id).await {
+                Ok(order) => match fill_outcome(&order) {
+                    Some(FillOutcome::Filled) => return Ok(order),
+                    Some(FillOutcome::Canceled) => {
+                        return Err(PolyError::validation("order cancelled"));
+                    }
+                    None => {}
+                },
+                Err(err) if err.is_retryable() => {}
+                Err(err) => return Err(err),
+            }
+
+            let now = tokio::time::Instant::now();
+            if now >= deadline {
+                return Err(PolyError::timeout(timeout, "wait_for_order_fill"));
+            }
+            tokio::time::sleep(poll_interval.min(deadline - now)).await;
+        }
+    }
+
+    /// Wait for an order to fill by listening on the authenticated user channel.
+    ///
+    /// Reacts to fills as they are pushed instead of once per poll interval, and only
+    /// touches REST to read the order before listening (so fills that already happened
+    /// aren't missed) and again whenever an event mentions it. The tradeoff is that
+    /// `user_ws` is borrowed for the duration: events for other orders read meanwhile are
+    /// consumed and dropped. If `user_ws` isn't subscribed yet it is subscribed to the
+    /// order's market.
+    pub async fn wait_for_order_fill_via_ws(
+        &self,
+        order_id: &str,
+        user_ws: &mut crate::wss::WssUserClient,
+        timeout: std::time::Duration,
+    ) -> Result<crate::types::OpenOrder> {
+        use crate::wss::WssUserEvent;
+
+        let deadline = tokio::time::Instant::now() + timeout;
+        let mut order = self.get_order(order_id).await?;
+        if !user_ws.is_subscribed() {
+            user_ws.subscribe(vec![order.market.clone()]).await?;
+            // Re-read now that we're listening, closing the gap before the subscription
+            order = self.get_order(order_id).await?;
+        }
+
+        loop {
+            match fill_outcome(&order) {
+                Some(FillOutcome::Filled) => return Ok(order),
+                Some(FillOutcome::Canceled) => {
+                    return Err(PolyError::validation("order cancelled"));
+                }
+                None => {}
+            }
+
+            let event = tokio::time::timeout_at(deadline, user_ws.next_event())
+                .await
+                .map_err(|_| PolyError::timeout(timeout, "wait_for_order_fill_via_ws"))??;
+            let relevant = match &event {
+                WssUserEvent::Order(msg) => msg.id == order_id,
+                WssUserEvent::Trade(trade) => {
+                    trade.taker_order_id == order_id
+                        || trade
+                            .maker_orders
+                            .iter()
+                            .any(|maker| maker.order_id == order_id)
+                }
+            };
+            if relevant {
+                order = self.get_order(order_id).await?;
+            }
+        }
     }
 
     /// Get last trade price for a token
@@ -1383,9 +3486,9 @@ impl ClobClient {
             .http_client
             .get(self.clob_url("last-trade-price"))
             .query(&[("token_id", token_id)])
-            .send()
+            .send_via(self)
             .await
-            .map_err(|e| PolyError::network(format!("Request failed: {}", e), e))?;
+            .map_err(request_failed)?;
 
         response
             .json::<Value>()
@@ -1408,9 +3511,9 @@ impl ClobClient {
             .http_client
             .post(self.clob_url("last-trades-prices"))
             .json(&request_data)
-            .send()
+            .send_via(self)
             .await
-            .map_err(|e| PolyError::network(format!("Request failed: {}", e), e))?;
+            .map_err(request_failed)?;
 
         response
             .json::<Value>()
@@ -1418,12 +3521,211 @@ impl ClobClient {
             .map_err(|e| PolyError::parse(format!("Failed to parse response: {}", e), None))
     }
 
-    /// Cancel market orders with optional filters
+    /// Get open interest (total resting bid/ask size) for a token
+    pub async fn get_open_interest(&self, token_id: &str) -> Result<crate::types::OpenInterest> {
+        let response = self
+            .http_client
+            .get(self.clob_url("open-interest"))
+            .query(&[("token_id", token_id)])
+            .send_via(self)
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(PolyError::api(
+                response.status().as_u16(),
+                "Failed to get open interest",
+            ));
+        }
+
+        let open_interest: crate::types::OpenInterest = self.decode_json(response).await?;
+        Ok(open_interest)
+    }
+
+    /// Get open interest for every token of a condition.
+    ///
+    /// Resolves the condition's tokens via `GET /markets/{condition_id}` and then queries
+    /// each token concurrently. Results follow the market's token order.
+    pub async fn get_condition_open_interest(
+        &self,
+        condition_id: &str,
+    ) -> Result<Vec<crate::types::OpenInterest>> {
+        let response = self
+            .http_client
+            .get(self.clob_url(&format!("markets/{}", condition_id)))
+            .send_via(self)
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(PolyError::api(
+                response.status().as_u16(),
+                "Failed to get market",
+            ));
+        }
+
+        let market: Value = response.json().await?;
+        let token_ids: Vec<&str> = market["tokens"]
+            .as_array()
+            .map(|tokens| {
+                tokens
+                    .iter()
+                    .filter_map(|token| token["token_id"].as_str())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        if token_ids.is_empty() {
+            return Err(PolyError::market_data(
+                format!("No tokens found for condition {}", condition_id),
+                crate::errors::MarketDataErrorKind::MarketNotFound,
+            ));
+        }
+
+        futures::future::try_join_all(token_ids.into_iter().map(|id| self.get_open_interest(id)))
+            .await
+    }
+
+    /// Mark the funder wallet's positions to market
+    ///
+    /// Positions come from the Data API `/positions` endpoint for the funder address (the
+    /// signer unless `set_funder` was called). Each token is priced at its CLOB midpoint,
+    /// fetched five at a time; tokens without a live book (e.g. resolved markets) fall back
+    /// to the Data API's `curPrice`.
+    pub async fn get_portfolio_value(&self) -> Result<crate::types::PortfolioValue> {
+        use futures::stream::{self, StreamExt};
+
+        let positions = self.get_funder_positions().await?;
+
+        let mut valued: Vec<(usize, crate::types::PositionValue)> =
+            stream::iter(positions.into_iter().enumerate())
+                .map(|(index, position)| async move {
+                    let current_price = match self.get_midpoint(&position.asset).await {
+                        Ok(midpoint) => midpoint.mid,
+                        Err(_) => position.cur_price,
+                    };
+                    (
+                        index,
+                        crate::types::PositionValue::new(position, current_price),
+                    )
+                })
+                .buffer_unordered(5)
+                .collect()
+                .await;
+        valued.sort_by_key(|(index, _)| *index);
+
+        Ok(crate::types::PortfolioValue::from_positions(
+            valued.into_iter().map(|(_, position)| position).collect(),
+        ))
+    }
+
+    /// Most recent fills in a market from the Data API, newest first
+    ///
+    /// Public; see [`DataApiClient::get_market_trades`].
+    pub async fn get_market_trades(
+        &self,
+        condition_id: &str,
+        limit: u32,
+    ) -> Result<Vec<crate::types::DataTrade>> {
+        self.data_api().get_market_trades(condition_id, limit).await
+    }
+
+    fn data_api(&self) -> DataApiClient {
+        DataApiClient {
+            http_client: self.http_client.clone(),
+            base_url: self.data_api_base_url.clone(),
+        }
+    }
+
+    /// Poll the funder wallet's positions and yield each change in size
+    ///
+    /// A pure-HTTP alternative to [`WssUserClient`](crate::wss::WssUserClient) for
+    /// environments where WebSockets are blocked. The first poll only records a
+    /// baseline; every `poll_interval` after that, the positions are fetched again and
+    /// diffed against the last snapshot (see [`PositionUpdate::diff`]). A failed poll
+    /// yields its error and keeps the old snapshot, so polling carries on; without a
+    /// signer the stream yields one auth error and ends.
+    ///
+    /// [`PositionUpdate::diff`]: crate::types::PositionUpdate::diff
+    pub fn subscribe_position_updates(
+        &self,
+        poll_interval: std::time::Duration,
+    ) -> impl futures::Stream<Item = Result<crate::types::PositionUpdate>> + Send + '_ {
+        use futures::stream;
+
+        let state = (
+            None::<Vec<crate::types::DataPosition>>,
+            std::collections::VecDeque::new(),
+            false,
+            false,
+        );
+        stream::unfold(
+            state,
+            move |(mut snapshot, mut pending, mut polled, done)| async move {
+                if done {
+                    return None;
+                }
+                loop {
+                    if let Some(update) = pending.pop_front() {
+                        return Some((Ok(update), (snapshot, pending, polled, false)));
+                    }
+                    if polled {
+                        tokio::time::sleep(poll_interval).await;
+                    }
+                    polled = true;
+                    match self.get_funder_positions().await {
+                        Ok(current) => {
+                            if let Some(previous) = &snapshot {
+                                pending
+                                    .extend(crate::types::PositionUpdate::diff(previous, &current));
+                            }
+                            snapshot = Some(current);
+                        }
+                        Err(err) => {
+                            let done = matches!(err, PolyError::Auth { .. });
+                            return Some((Err(err), (snapshot, pending, polled, done)));
+                        }
+                    }
+                }
+            },
+        )
+    }
+
+    /// The funder wallet's positions from the Data API, dust included, paged until a
+    /// short page comes back
+    async fn get_funder_positions(&self) -> Result<Vec<crate::types::DataPosition>> {
+        let order_builder = self
+            .order_builder
+            .as_ref()
+            .ok_or_else(|| PolyError::auth("Signer required to look up positions"))?;
+        let user = order_builder.funder().to_checksum(None);
+
+        let data_api = self.data_api();
+        let mut positions = Vec::new();
+        loop {
+            let params = crate::types::DataApiPositionsParams {
+                size_threshold: Some(0),
+                limit: Some(FUNDER_POSITIONS_PAGE),
+                offset: Some(positions.len() as u32),
+                ..Default::default()
+            };
+            let page = data_api.get_positions(&user, Some(params)).await?;
+            let full = page.len() >= FUNDER_POSITIONS_PAGE as usize;
+            positions.extend(page);
+            if !full {
+                return Ok(positions);
+            }
+        }
+    }
+
+    /// Cancel every open order in one market, leaving other markets alone
+    ///
+    /// `market` is the condition ID; `asset_id` narrows it to one outcome token and may
+    /// be given on its own. At least one of the two is required; use
+    /// [`ClobClient::cancel_all`] to cancel everything.
     pub async fn cancel_market_orders(
         &self,
         market: Option<&str>,
         asset_id: Option<&str>,
-    ) -> Result<Value> {
+    ) -> Result<crate::types::CancelResponse> {
         let signer = self
             .signer
             .as_ref()
@@ -1432,15 +3734,29 @@ impl ClobClient {
             .api_creds
             .as_ref()
             .ok_or_else(|| PolyError::config("API credentials not configured"))?;
+        if market.is_none() && asset_id.is_none() {
+            return Err(PolyError::validation(
+                "cancel_market_orders needs a market or asset_id",
+            ));
+        }
 
         let method = Method::DELETE;
         let endpoint = "/cancel-market-orders";
-        let body = std::collections::HashMap::from([
-            ("market", market.unwrap_or("")),
-            ("asset_id", asset_id.unwrap_or("")),
-        ]);
+        // Empty filters are sent as absent so they can't be read as "match anything"
+        let body: std::collections::HashMap<&str, &str> =
+            [("market", market), ("asset_id", asset_id)]
+                .into_iter()
+                .filter_map(|(key, value)| value.map(|value| (key, value)))
+                .collect();
 
-        let headers = create_l2_headers(signer, api_creds, method.as_str(), endpoint, Some(&body))?;
+        let headers = create_l2_headers_at(
+            signer,
+            api_creds,
+            method.as_str(),
+            endpoint,
+            Some(&body),
+            self.clock.now_secs(),
+        )?;
 
         let response = self
             .http_client
@@ -1452,18 +3768,86 @@ impl ClobClient {
                     .collect(),
             )
             .json(&body)
-            .send()
+            .send_via(self)
             .await
-            .map_err(|e| PolyError::network(format!("Request failed: {}", e), e))?;
+            .map_err(request_failed)?;
+        if !response.status().is_success() {
+            return Err(PolyError::api(
+                response.status().as_u16(),
+                "Failed to cancel market orders",
+            ));
+        }
 
-        response
-            .json::<Value>()
+        self.decode_json::<crate::types::CancelResponse>(response)
             .await
+    }
+
+    /// Cancel the open orders on `token_id` priced outside `[min_price, max_price]`
+    ///
+    /// Orders priced at either bound are kept. With `side` set, only that side's orders
+    /// are considered, so a bracket can be trimmed on one side at a time. The matching
+    /// orders go out in a single [`cancel_orders`](Self::cancel_orders) call; when none
+    /// match, nothing is sent and the response is empty.
+    pub async fn batch_cancel_by_price_range(
+        &self,
+        token_id: &str,
+        min_price: Decimal,
+        max_price: Decimal,
+        side: Option<Side>,
+    ) -> Result<crate::types::CancelResponse> {
+        let in_unit = |price: Decimal| price > Decimal::ZERO && price < Decimal::ONE;
+        if !in_unit(min_price) || !in_unit(max_price) {
+            return Err(PolyError::validation(format!(
+                "Price range [{}, {}] must lie inside (0, 1)",
+                min_price, max_price
+            )));
+        }
+        if min_price >= max_price {
+            return Err(PolyError::validation(format!(
+                "min_price {} must be below max_price {}",
+                min_price, max_price
+            )));
+        }
+
+        let params = crate::types::OpenOrderParams {
+            id: None,
+            asset_id: Some(token_id.to_string()),
+            market: None,
+        };
+        let order_ids: Vec<String> = self
+            .get_orders(Some(&params), None)
+            .await?
+            .into_iter()
+            .filter(|order| order.asset_id == token_id)
+            .filter(|order| side.is_none_or(|side| order.side == side))
+            .filter(|order| order.price < min_price || order.price > max_price)
+            .map(|order| order.id)
+            .collect();
+        if order_ids.is_empty() {
+            return Ok(crate::types::CancelResponse::default());
+        }
+
+        let response = self.cancel_orders(&order_ids).await?;
+        serde_json::from_value(response)
             .map_err(|e| PolyError::parse(format!("Failed to parse response: {}", e), None))
     }
 
-    /// Drop (delete) notifications by IDs
-    pub async fn drop_notifications(&self, ids: &[String]) -> Result<Value> {
+    /// Redeem the authenticated user's winning tokens in the resolved market `condition_id`
+    ///
+    /// The exchange submits the redemption on-chain for the user, so the USDC isn't
+    /// spendable when this returns: the receipt's `tx_hash` is `None` until the
+    /// transaction is sent, and the balance moves once it is mined on Polygon (usually
+    /// a few seconds, longer when the network is congested). Poll
+    /// [`get_balance_allowance`](Self::get_balance_allowance) rather than assuming the
+    /// funds have arrived. To redeem from your own wallet instead, see
+    /// `onchain::SettlementClient` (behind the `onchain` feature).
+    ///
+    /// Returns a validation error when the market hasn't resolved yet, or when the
+    /// user holds none of the winning outcome.
+    pub async fn redeem_positions(
+        &self,
+        condition_id: &str,
+    ) -> Result<crate::types::RedemptionReceipt> {
         let signer = self
             .signer
             .as_ref()
@@ -1473,10 +3857,17 @@ impl ClobClient {
             .as_ref()
             .ok_or_else(|| PolyError::config("API credentials not configured"))?;
 
-        let method = Method::DELETE;
-        let endpoint = "/notifications";
-        let headers =
-            create_l2_headers::<Value>(signer, api_creds, method.as_str(), endpoint, None)?;
+        let method = Method::POST;
+        let endpoint = "/redeem";
+        let body = serde_json::json!({ "condition_id": condition_id });
+        let headers = create_l2_headers_at(
+            signer,
+            api_creds,
+            method.as_str(),
+            endpoint,
+            Some(&body),
+            self.clock.now_secs(),
+        )?;
 
         let response = self
             .http_client
@@ -1487,22 +3878,47 @@ impl ClobClient {
                     .map(|(k, v)| (HeaderName::from_static(k), v.parse().unwrap()))
                     .collect(),
             )
-            .query(&[("ids", ids.join(","))])
-            .send()
+            .json(&body)
+            .send_via(self)
             .await
-            .map_err(|e| PolyError::network(format!("Request failed: {}", e), e))?;
+            .map_err(request_failed)?;
 
-        response
-            .json::<Value>()
-            .await
-            .map_err(|e| PolyError::parse(format!("Failed to parse response: {}", e), None))
+        let status = response.status();
+        if !status.is_success() {
+            let message = response.text().await.unwrap_or_default();
+            if status.is_client_error() && message.to_ascii_lowercase().contains("not resolved") {
+                return Err(PolyError::validation("market not resolved"));
+            }
+            return Err(PolyError::api(
+                status.as_u16(),
+                format!("Failed to redeem positions: {}", message),
+            ));
+        }
+
+        let receipt: crate::types::RedemptionReceipt = self.decode_json(response).await?;
+        if receipt.redeemed_tokens.is_empty() {
+            return Err(PolyError::validation("no winning position held"));
+        }
+        Ok(receipt)
     }
 
-    /// Update balance allowance
-    pub async fn update_balance_allowance(
+    /// One page of the account's activity log, oldest first
+    ///
+    /// Covers order placements, cancels and fills, redemptions, deposits and
+    /// withdrawals. Pass the returned `next_cursor` back in `params.cursor` for the
+    /// following page.
+    pub async fn get_user_activity(
         &self,
-        params: Option<crate::types::BalanceAllowanceParams>,
-    ) -> Result<Value> {
+        params: &crate::types::ActivityParams,
+    ) -> Result<crate::types::PagedResponse<crate::types::ActivityEntry>> {
+        if let (Some(start), Some(end)) = (params.start_ts, params.end_ts)
+            && start > end
+        {
+            return Err(PolyError::validation(format!(
+                "start_ts {} is after end_ts {}",
+                start, end
+            )));
+        }
         let signer = self
             .signer
             .as_ref()
@@ -1512,22 +3928,16 @@ impl ClobClient {
             .as_ref()
             .ok_or_else(|| PolyError::config("API credentials not configured"))?;
 
-        let mut params = params.unwrap_or_default();
-        if params.signature_type.is_none() {
-            params.set_signature_type(
-                self.order_builder
-                    .as_ref()
-                    .expect("OrderBuilder not set")
-                    .get_sig_type(),
-            );
-        }
-
-        let query_params = params.to_query_params();
-
         let method = Method::GET;
-        let endpoint = "/balance-allowance/update";
-        let headers =
-            create_l2_headers::<Value>(signer, api_creds, method.as_str(), endpoint, None)?;
+        let endpoint = "/activity";
+        let headers = create_l2_headers_at::<Value>(
+            signer,
+            api_creds,
+            method.as_str(),
+            endpoint,
+            None,
+            self.clock.now_secs(),
+        )?;
 
         let response = self
             .http_client
@@ -1538,19 +3948,114 @@ impl ClobClient {
                     .map(|(k, v)| (HeaderName::from_static(k), v.parse().unwrap()))
                     .collect(),
             )
-            .query(&query_params)
-            .send()
+            .query(&params.to_query_params())
+            .send_via(self)
             .await
-            .map_err(|e| PolyError::network(format!("Request failed: {}", e), e))?;
+            .map_err(request_failed)?;
 
-        response
-            .json::<Value>()
+        if !response.status().is_success() {
+            return Err(PolyError::api(
+                response.status().as_u16(),
+                format!(
+                    "Failed to get activity: {}",
+                    response.text().await.unwrap_or_default()
+                ),
+            ));
+        }
+
+        let page: crate::types::CursorPage<crate::types::ActivityEntry> =
+            self.decode_json(response).await?;
+        Ok(page.into_paged())
+    }
+
+    /// Number of activity log entries of each type since `since`
+    ///
+    /// Walks every page of [`get_user_activity`](Self::get_user_activity), so a long
+    /// window costs one request per page.
+    pub async fn get_user_activity_summary(
+        &self,
+        since: chrono::DateTime<Utc>,
+    ) -> Result<crate::types::ActivitySummary> {
+        let mut params = crate::types::ActivityParams {
+            start_ts: Some(since.timestamp().max(0) as u64),
+            ..Default::default()
+        };
+        let mut counts = std::collections::HashMap::new();
+        let mut seen = std::collections::HashSet::new();
+        loop {
+            let page = self.get_user_activity(&params).await?;
+            for entry in &page.data {
+                *counts.entry(entry.event_type).or_insert(0) += 1;
+            }
+            // A cursor handed out before would replay the same pages forever
+            match page.next_cursor {
+                Some(cursor)
+                    if cursor != crate::types::END_CURSOR && seen.insert(cursor.clone()) =>
+                {
+                    params.cursor = Some(cursor)
+                }
+                _ => break,
+            }
+        }
+        Ok(crate::types::ActivitySummary { since, counts })
+    }
+
+    /// Rate and trading limits of the authenticated account
+    pub async fn get_user_limits(&self) -> Result<crate::types::UserLimits> {
+        let limits = self
+            .request_signed::<Value>(Method::GET, "/user/limits", &[], None)
+            .await?;
+        serde_json::from_value(limits)
+            .map_err(|e| PolyError::parse(format!("Failed to parse user limits: {}", e), None))
+    }
+
+    /// The account's loss-alert threshold in USDC, `None` when no alert is set
+    pub async fn get_alert_threshold(&self) -> Result<Option<Decimal>> {
+        let body = self
+            .request_signed::<Value>(Method::GET, "/alerts/threshold", &[], None)
+            .await?;
+        match body.get("threshold") {
+            None | Some(Value::Null) => Ok(None),
+            Some(threshold) => crate::serde_decimal::deserialize(threshold)
+                .map(Some)
+                .map_err(|e| PolyError::parse(format!("Invalid alert threshold: {}", e), None)),
+        }
+    }
+
+    /// Have the server alert the account once unrealized P&L falls to `threshold_usdc`
+    ///
+    /// The threshold is a loss, so it must be negative: `-500` alerts at a $500 loss.
+    pub async fn set_alert_threshold(&self, threshold_usdc: Decimal) -> Result<()> {
+        if threshold_usdc >= Decimal::ZERO {
+            return Err(PolyError::validation(format!(
+                "alert threshold must be negative, got {}",
+                threshold_usdc
+            )));
+        }
+        let body = serde_json::json!({ "threshold": threshold_usdc.normalize().to_string() });
+        self.request_signed(Method::PUT, "/alerts/threshold", &[], Some(&body))
             .await
-            .map_err(|e| PolyError::parse(format!("Failed to parse response: {}", e), None))
+            .map(|_| ())
     }
 
-    /// Check if an order is scoring
-    pub async fn is_order_scoring(&self, order_id: &str) -> Result<bool> {
+    /// Loss alerts raised since `since`, oldest first
+    pub async fn get_triggered_alerts(
+        &self,
+        since: chrono::DateTime<Utc>,
+    ) -> Result<Vec<crate::types::Alert>> {
+        let since = since.timestamp().to_string();
+        let alerts = self
+            .request_signed::<Value>(Method::GET, "/alerts", &[("since", &since)], None)
+            .await?;
+        match alerts {
+            Value::Null => Ok(Vec::new()),
+            alerts => serde_json::from_value(alerts)
+                .map_err(|e| PolyError::parse(format!("Failed to parse alerts: {}", e), None)),
+        }
+    }
+
+    /// Drop (delete) notifications by IDs
+    pub async fn drop_notifications(&self, ids: &[String]) -> Result<Value> {
         let signer = self
             .signer
             .as_ref()
@@ -1560,10 +4065,16 @@ impl ClobClient {
             .as_ref()
             .ok_or_else(|| PolyError::config("API credentials not configured"))?;
 
-        let method = Method::GET;
-        let endpoint = "/order-scoring";
-        let headers =
-            create_l2_headers::<Value>(signer, api_creds, method.as_str(), endpoint, None)?;
+        let method = Method::DELETE;
+        let endpoint = "/notifications";
+        let headers = create_l2_headers_at::<Value>(
+            signer,
+            api_creds,
+            method.as_str(),
+            endpoint,
+            None,
+            self.clock.now_secs(),
+        )?;
 
         let response = self
             .http_client
@@ -1574,24 +4085,37 @@ impl ClobClient {
                     .map(|(k, v)| (HeaderName::from_static(k), v.parse().unwrap()))
                     .collect(),
             )
-            .query(&[("order_id", order_id)])
-            .send()
+            .query(&[("ids", ids.join(","))])
+            .send_via(self)
             .await
-            .map_err(|e| PolyError::network(format!("Request failed: {}", e), e))?;
+            .map_err(request_failed)?;
 
-        let result: Value = response
-            .json()
+        response
+            .json::<Value>()
             .await
-            .map_err(|e| PolyError::parse(format!("Failed to parse response: {}", e), None))?;
-
-        Ok(result["scoring"].as_bool().unwrap_or(false))
+            .map_err(|e| PolyError::parse(format!("Failed to parse response: {}", e), None))
     }
 
-    /// Check if multiple orders are scoring
-    pub async fn are_orders_scoring(
+    /// Send an L2-authenticated request to an arbitrary CLOB endpoint.
+    ///
+    /// **Escape hatch**: this exists for endpoints the crate hasn't wrapped yet and gives
+    /// no typing or stability guarantees beyond "it's signed correctly". Prefer the typed
+    /// methods whenever one exists.
+    ///
+    /// `path` is relative to the CLOB base URL (e.g. `/rewards/user`). The body, if any,
+    /// is serialized once so the bytes sent are exactly the bytes signed. Non-2xx responses
+    /// become [`PolyError::Api`] carrying the response text; an empty success body yields
+    /// `Value::Null`.
+    pub async fn request_signed<B>(
         &self,
-        order_ids: &[&str],
-    ) -> Result<std::collections::HashMap<String, bool>> {
+        method: Method,
+        path: &str,
+        query: &[(&str, &str)],
+        body: Option<&B>,
+    ) -> Result<Value>
+    where
+        B: serde::Serialize + ?Sized,
+    {
         let signer = self
             .signer
             .as_ref()
@@ -1601,55 +4125,217 @@ impl ClobClient {
             .as_ref()
             .ok_or_else(|| PolyError::config("API credentials not configured"))?;
 
-        let method = Method::POST;
-        let endpoint = "/orders-scoring";
-        let headers = create_l2_headers(
+        let endpoint = format!("/{}", path.trim_start_matches('/'));
+        let body = body
+            .map(serde_json::value::to_raw_value)
+            .transpose()
+            .map_err(|e| PolyError::parse(format!("Failed to serialize body: {}", e), None))?;
+        let headers = create_l2_headers_at(
             signer,
             api_creds,
             method.as_str(),
-            endpoint,
-            Some(order_ids),
+            &endpoint,
+            body.as_deref(),
+            self.clock.now_secs(),
         )?;
 
-        let response = self
+        let mut request = self
             .http_client
-            .request(method, self.clob_url(endpoint))
+            .request(method, self.clob_url(&endpoint))
             .headers(
                 headers
                     .into_iter()
                     .map(|(k, v)| (HeaderName::from_static(k), v.parse().unwrap()))
                     .collect(),
-            )
-            .json(order_ids)
-            .send()
-            .await
-            .map_err(|e| PolyError::network(format!("Request failed: {}", e), e))?;
+            );
+        if !query.is_empty() {
+            request = request.query(query);
+        }
+        if let Some(body) = body {
+            request = request
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(body.get().to_owned());
+        }
 
-        response
-            .json::<std::collections::HashMap<String, bool>>()
+        let response = request.send_via(self).await.map_err(request_failed)?;
+
+        let status = response.status();
+        let text = response
+            .text()
             .await
+            .map_err(|e| PolyError::parse(format!("Failed to read response body: {}", e), None))?;
+        if !status.is_success() {
+            return Err(PolyError::api(status.as_u16(), text));
+        }
+        if text.trim().is_empty() {
+            return Ok(Value::Null);
+        }
+
+        serde_json::from_str(&text)
             .map_err(|e| PolyError::parse(format!("Failed to parse response: {}", e), None))
     }
 
-    /// Get sampling markets with pagination
-    pub async fn get_sampling_markets(
+    /// Update balance allowance
+    pub async fn update_balance_allowance(
         &self,
-        next_cursor: Option<&str>,
-    ) -> Result<crate::types::MarketsResponse> {
+        params: Option<crate::types::BalanceAllowanceParams>,
+    ) -> Result<Value> {
+        let signer = self
+            .signer
+            .as_ref()
+            .ok_or_else(|| PolyError::config("Signer not configured"))?;
+        let api_creds = self
+            .api_creds
+            .as_ref()
+            .ok_or_else(|| PolyError::config("API credentials not configured"))?;
+
+        let mut params = params.unwrap_or_default();
+        if params.signature_type.is_none() {
+            params.set_signature_type(
+                self.order_builder
+                    .as_ref()
+                    .expect("OrderBuilder not set")
+                    .get_sig_type(),
+            );
+        }
+
+        let query_params = params.to_query_params();
+
+        let method = Method::GET;
+        let endpoint = "/balance-allowance/update";
+        let headers = create_l2_headers_at::<Value>(
+            signer,
+            api_creds,
+            method.as_str(),
+            endpoint,
+            None,
+            self.clock.now_secs(),
+        )?;
+
+        let response = self
+            .http_client
+            .request(method, self.clob_url(endpoint))
+            .headers(
+                headers
+                    .into_iter()
+                    .map(|(k, v)| (HeaderName::from_static(k), v.parse().unwrap()))
+                    .collect(),
+            )
+            .query(&query_params)
+            .send_via(self)
+            .await
+            .map_err(request_failed)?;
+
+        response
+            .json::<Value>()
+            .await
+            .map_err(|e| PolyError::parse(format!("Failed to parse response: {}", e), None))
+    }
+
+    /// Check if an order is scoring
+    pub async fn is_order_scoring(&self, order_id: &str) -> Result<bool> {
+        let signer = self
+            .signer
+            .as_ref()
+            .ok_or_else(|| PolyError::config("Signer not configured"))?;
+        let api_creds = self
+            .api_creds
+            .as_ref()
+            .ok_or_else(|| PolyError::config("API credentials not configured"))?;
+
+        let method = Method::GET;
+        let endpoint = "/order-scoring";
+        let headers = create_l2_headers_at::<Value>(
+            signer,
+            api_creds,
+            method.as_str(),
+            endpoint,
+            None,
+            self.clock.now_secs(),
+        )?;
+
+        let response = self
+            .http_client
+            .request(method, self.clob_url(endpoint))
+            .headers(
+                headers
+                    .into_iter()
+                    .map(|(k, v)| (HeaderName::from_static(k), v.parse().unwrap()))
+                    .collect(),
+            )
+            .query(&[("order_id", order_id)])
+            .send_via(self)
+            .await
+            .map_err(request_failed)?;
+
+        let result: Value = response
+            .json()
+            .await
+            .map_err(|e| PolyError::parse(format!("Failed to parse response: {}", e), None))?;
+
+        Ok(result["scoring"].as_bool().unwrap_or(false))
+    }
+
+    /// Check if multiple orders are scoring
+    pub async fn are_orders_scoring(
+        &self,
+        order_ids: &[&str],
+    ) -> Result<std::collections::HashMap<String, bool>> {
+        let signer = self
+            .signer
+            .as_ref()
+            .ok_or_else(|| PolyError::config("Signer not configured"))?;
+        let api_creds = self
+            .api_creds
+            .as_ref()
+            .ok_or_else(|| PolyError::config("API credentials not configured"))?;
+
+        let method = Method::POST;
+        let endpoint = "/orders-scoring";
+        let headers = create_l2_headers_at(
+            signer,
+            api_creds,
+            method.as_str(),
+            endpoint,
+            Some(order_ids),
+            self.clock.now_secs(),
+        )?;
+
+        let response = self
+            .http_client
+            .request(method, self.clob_url(endpoint))
+            .headers(
+                headers
+                    .into_iter()
+                    .map(|(k, v)| (HeaderName::from_static(k), v.parse().unwrap()))
+                    .collect(),
+            )
+            .json(order_ids)
+            .send_via(self)
+            .await
+            .map_err(request_failed)?;
+
+        self.decode_json::<std::collections::HashMap<String, bool>>(response)
+            .await
+    }
+
+    /// Get sampling markets with pagination
+    pub async fn get_sampling_markets(
+        &self,
+        next_cursor: Option<&str>,
+    ) -> Result<crate::types::MarketsResponse> {
         let next_cursor = next_cursor.unwrap_or("MA=="); // INITIAL_CURSOR
 
         let response = self
             .http_client
             .get(self.gamma_url("sampling-markets"))
             .query(&[("next_cursor", next_cursor)])
-            .send()
+            .send_via(self)
             .await
-            .map_err(|e| PolyError::network(format!("Request failed: {}", e), e))?;
+            .map_err(request_failed)?;
 
-        response
-            .json::<crate::types::MarketsResponse>()
+        self.decode_json::<crate::types::MarketsResponse>(response)
             .await
-            .map_err(|e| PolyError::parse(format!("Failed to parse response: {}", e), None))
     }
 
     /// Get sampling simplified markets with pagination
@@ -1663,22 +4349,349 @@ impl ClobClient {
             .http_client
             .get(self.gamma_url("sampling-simplified-markets"))
             .query(&[("next_cursor", next_cursor)])
-            .send()
+            .send_via(self)
             .await
-            .map_err(|e| PolyError::network(format!("Request failed: {}", e), e))?;
+            .map_err(request_failed)?;
 
-        response
-            .json::<crate::types::SimplifiedMarketsResponse>()
+        self.decode_json::<crate::types::SimplifiedMarketsResponse>(response)
             .await
-            .map_err(|e| PolyError::parse(format!("Failed to parse response: {}", e), None))
     }
 
     /// Get markets with pagination
+    ///
+    /// Fails if any market in the page doesn't parse; see
+    /// [`ClobClient::get_markets_lossy`] to keep the rest of the page instead.
     pub async fn get_markets(
         &self,
         next_cursor: Option<&str>,
         params: Option<&crate::types::GammaListParams>,
     ) -> Result<crate::types::MarketsResponse> {
+        let page = self.fetch_markets_page(next_cursor, params).await?;
+        let gamma_markets: Vec<crate::types::GammaMarket> = serde_json::from_str(&page.body)
+            .map_err(|e| PolyError::parse(format!("Failed to parse response: {}", e), None))?;
+
+        let count = gamma_markets.len();
+        Ok(page.into_response(gamma_markets, count, params))
+    }
+
+    /// Like [`ClobClient::get_markets`], but parses each market independently.
+    ///
+    /// Markets that fail to deserialize are skipped and returned alongside the page as
+    /// `(index in page, error, raw JSON)`, with a warning logged for each one. Pagination
+    /// still advances over the whole page.
+    pub async fn get_markets_lossy(
+        &self,
+        next_cursor: Option<&str>,
+        params: Option<&crate::types::GammaListParams>,
+    ) -> Result<(
+        crate::types::MarketsResponse,
+        Vec<crate::types::SkippedRecord>,
+    )> {
+        let page = self.fetch_markets_page(next_cursor, params).await?;
+        let records: Vec<Box<serde_json::value::RawValue>> = serde_json::from_str(&page.body)
+            .map_err(|e| PolyError::parse(format!("Failed to parse response: {}", e), None))?;
+
+        let count = records.len();
+        let mut gamma_markets = Vec::with_capacity(count);
+        let mut skipped = Vec::new();
+        for (index, record) in records.iter().enumerate() {
+            match serde_json::from_str::<crate::types::GammaMarket>(record.get()) {
+                Ok(market) => gamma_markets.push(market),
+                Err(error) => {
+                    tracing::warn!(index, %error, "Skipping malformed Gamma market");
+                    let raw = serde_json::from_str(record.get()).unwrap_or(Value::Null);
+                    skipped.push((index, error, raw));
+                }
+            }
+        }
+
+        Ok((page.into_response(gamma_markets, count, params), skipped))
+    }
+
+    /// Full-text search over market questions, most relevant first
+    ///
+    /// Wraps Gamma's `GET /markets/search`. The query is trimmed and has runs of
+    /// whitespace collapsed; an empty one is a validation error. It is percent-encoded
+    /// with the rest of the query string, so quotes and `&` are safe to pass. Filters in
+    /// `params` are sent as-is next to the search term, without the liquidity and end
+    /// date defaults [`get_markets`](Self::get_markets) applies. The page is `params.limit`
+    /// results from `params.offset` (50 from 0 by default), and `next_cursor` carries the
+    /// offset of the following page, to be set as `params.offset`.
+    pub async fn search_markets(
+        &self,
+        query: &str,
+        params: Option<&crate::types::GammaListParams>,
+    ) -> Result<crate::types::PagedResponse<crate::types::Market>> {
+        let query = sanitize_search_query(query)?;
+        let limit = params
+            .and_then(|options| options.limit)
+            .unwrap_or(GAMMA_MARKETS_LIMIT);
+        let offset = params.and_then(|options| options.offset).unwrap_or(0);
+
+        let mut request_query = vec![
+            ("q", query),
+            ("limit", limit.to_string()),
+            ("offset", offset.to_string()),
+        ];
+        if let Some(options) = params {
+            request_query.extend(
+                options
+                    .to_query_params()
+                    .into_iter()
+                    .filter(|(key, _)| !matches!(*key, "limit" | "offset")),
+            );
+        }
+
+        let response = self
+            .http_client
+            .get(self.gamma_url("markets/search"))
+            .query(&request_query)
+            .send_via(self)
+            .await
+            .map_err(request_failed)?;
+
+        if !response.status().is_success() {
+            return Err(PolyError::api(
+                response.status().as_u16(),
+                "Failed to search markets",
+            ));
+        }
+
+        let payload: Value = response
+            .json()
+            .await
+            .map_err(|e| PolyError::parse(format!("Failed to parse response: {}", e), None))?;
+        let reported_total = payload
+            .get("total")
+            .and_then(Value::as_u64)
+            .map(|total| total as usize);
+        let gamma_markets: Vec<crate::types::GammaMarket> =
+            self.parse_gamma_list(payload, "market search results")?;
+
+        let count = gamma_markets.len();
+        let next_cursor = (count >= limit as usize).then(|| (offset as usize + count).to_string());
+        let data: Vec<crate::types::Market> = gamma_markets
+            .into_iter()
+            .map(crate::types::Market::from)
+            .filter(|market| params.is_none_or(|options| options.matches_game_window(market)))
+            .collect();
+        Ok(crate::types::PagedResponse {
+            data,
+            next_cursor,
+            total: reported_total.unwrap_or(offset as usize + count),
+        })
+    }
+
+    /// Every market matching `query`, most relevant first, fetched a page at a time
+    ///
+    /// Pages through [`search_markets`](Self::search_markets) with no other filters. The
+    /// stream ends after the last page, or after the first error.
+    pub fn search_markets_stream<'a>(
+        &'a self,
+        query: &'a str,
+    ) -> impl futures::Stream<Item = Result<crate::types::Market>> + Send + 'a {
+        use futures::stream;
+
+        let state = (Some(0u32), std::collections::VecDeque::new());
+        stream::unfold(state, move |(mut offset, mut pending)| async move {
+            loop {
+                if let Some(market) = pending.pop_front() {
+                    return Some((Ok(market), (offset, pending)));
+                }
+                let params = crate::types::GammaListParams {
+                    offset: Some(offset?),
+                    ..Default::default()
+                };
+                match self.search_markets(query, Some(&params)).await {
+                    Ok(page) => {
+                        offset = page.next_cursor.and_then(|next| next.parse().ok());
+                        pending.extend(page.data);
+                    }
+                    Err(err) => return Some((Err(err), (None, pending))),
+                }
+            }
+        })
+    }
+
+    /// Pick a market to stream, along with the asset IDs to subscribe to
+    ///
+    /// Fetches up to 50 Gamma markets with at least `min_liquidity`, drops those
+    /// without token IDs and returns the most liquid one. Fails with a validation error
+    /// when none qualify. Use
+    /// [`find_subscribable_market_with`](Self::find_subscribable_market_with) to choose
+    /// among the candidates yourself.
+    pub async fn find_subscribable_market(
+        &self,
+        min_liquidity: Decimal,
+    ) -> Result<(crate::types::Market, Vec<String>)> {
+        self.find_subscribable_market_with(min_liquidity, |_| 0)
+            .await
+    }
+
+    /// Like [`find_subscribable_market`](Self::find_subscribable_market), but `select`
+    /// returns the index of the market to use among the candidates, which are sorted
+    /// most liquid first
+    pub async fn find_subscribable_market_with<F>(
+        &self,
+        min_liquidity: Decimal,
+        select: F,
+    ) -> Result<(crate::types::Market, Vec<String>)>
+    where
+        F: FnOnce(&[crate::types::Market]) -> usize,
+    {
+        let params = crate::types::GammaListParams {
+            limit: Some(50),
+            liquidity_num_min: Some(min_liquidity),
+            ..Default::default()
+        };
+        let response = self.get_markets(None, Some(&params)).await?;
+
+        let mut candidates: Vec<crate::types::Market> = response
+            .data
+            .into_iter()
+            .filter(|market| market.liquidity_num.unwrap_or_default() >= min_liquidity)
+            .filter(|market| !market.asset_ids().is_empty())
+            .collect();
+        if candidates.is_empty() {
+            return Err(PolyError::validation(format!(
+                "no subscribable markets with liquidity of at least {}",
+                min_liquidity
+            )));
+        }
+        candidates.sort_by(|a, b| {
+            b.liquidity_num
+                .cmp(&a.liquidity_num)
+                .then_with(|| a.condition_id.cmp(&b.condition_id))
+        });
+
+        let index = select(&candidates);
+        if index >= candidates.len() {
+            return Err(PolyError::validation(format!(
+                "selected market {} of {} candidates",
+                index,
+                candidates.len()
+            )));
+        }
+        let market = candidates.swap_remove(index);
+        let asset_ids = market.asset_ids();
+        Ok((market, asset_ids))
+    }
+
+    /// The `n` highest-scoring markets from one page of Gamma results
+    ///
+    /// This is a best-effort top N: only the page selected by `params` (its `limit`,
+    /// `offset` and filters) is fetched and ranked, so a better market on a later page
+    /// is never seen. Use `order` and the filters in `params` to make that page a good
+    /// sample. Ties keep Gamma's order.
+    pub async fn get_top_markets(
+        &self,
+        params: &crate::types::GammaListParams,
+        n: usize,
+        scorer: &dyn crate::scoring::MarketScorer,
+    ) -> Result<Vec<crate::types::Market>> {
+        let response = self.get_markets(None, Some(params)).await?;
+        let mut scored: Vec<(Decimal, crate::types::Market)> = response
+            .data
+            .into_iter()
+            .map(|market| (scorer.score(&market), market))
+            .collect();
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        Ok(scored
+            .into_iter()
+            .take(n)
+            .map(|(_, market)| market)
+            .collect())
+    }
+
+    /// Fetch specific Gamma markets by ID
+    ///
+    /// IDs are sent as repeated `id` keys, split over several requests when the query
+    /// would get too long for one URL. Unlike [`ClobClient::get_markets`] no default
+    /// liquidity or end-date filters apply. Unknown IDs are simply missing from the result.
+    pub async fn get_markets_by_ids(&self, ids: &[String]) -> Result<Vec<crate::types::Market>> {
+        self.get_markets_chunked("id", ids).await
+    }
+
+    /// Fetch the Gamma markets containing any of `token_ids`, chunked like
+    /// [`ClobClient::get_markets_by_ids`]
+    pub async fn get_markets_by_token_ids(
+        &self,
+        token_ids: &[String],
+    ) -> Result<Vec<crate::types::Market>> {
+        self.get_markets_chunked("clob_token_ids", token_ids).await
+    }
+
+    /// Every market of the neg-risk group `condition_id` belongs to, that market first
+    ///
+    /// Looks the market up on Gamma, then fetches the event it is listed under and keeps
+    /// the event's markets sharing its `neg_risk_market_id`, in the event's order. A
+    /// market without a group ID is a validation error; an unknown condition ID an API
+    /// error with status 404.
+    pub async fn get_neg_risk_siblings(
+        &self,
+        condition_id: &str,
+    ) -> Result<Vec<crate::types::Market>> {
+        let condition_id = condition_id.trim();
+        if condition_id.is_empty() {
+            return Err(PolyError::validation("condition id is empty"));
+        }
+
+        let gamma = self
+            .get_gamma_markets_by("condition_ids", &[condition_id.to_string()])
+            .await?
+            .into_iter()
+            .find(|market| market.condition_id == condition_id)
+            .ok_or_else(|| {
+                PolyError::api(404, format!("No market with condition id {}", condition_id))
+            })?;
+        let event_id = gamma.events.first().map(|event| event.id.clone());
+        let market = crate::types::Market::from(gamma);
+        let Some(group_id) = market.neg_risk_market_id.clone() else {
+            return Err(PolyError::validation(format!(
+                "market {} is not in a neg-risk group",
+                condition_id
+            )));
+        };
+        let Some(event_id) = event_id else {
+            return Ok(vec![market]);
+        };
+
+        let others: Vec<String> = self
+            .get_event_by_id(&event_id)
+            .await?
+            .markets
+            .into_iter()
+            .map(|market| market.condition_id)
+            .filter(|id| id != condition_id)
+            .collect();
+        let mut siblings = self.get_markets_chunked("condition_ids", &others).await?;
+        siblings.retain(|market| market.neg_risk_market_id.as_deref() == Some(&group_id));
+        siblings.sort_by_key(|market| others.iter().position(|id| *id == market.condition_id));
+
+        let mut group = vec![market];
+        group.extend(siblings);
+        Ok(group)
+    }
+
+    async fn get_markets_chunked(
+        &self,
+        key: &'static str,
+        values: &[String],
+    ) -> Result<Vec<crate::types::Market>> {
+        let mut markets = Vec::with_capacity(values.len());
+        for chunk in query_value_chunks(key, values, GAMMA_MAX_QUERY_LEN) {
+            let page = self.get_gamma_markets_by(key, chunk).await?;
+            markets.extend(page.into_iter().map(crate::types::Market::from));
+        }
+        Ok(markets)
+    }
+
+    /// Fetch one raw page of Gamma markets, applying the default filters
+    async fn fetch_markets_page(
+        &self,
+        next_cursor: Option<&str>,
+        params: Option<&crate::types::GammaListParams>,
+    ) -> Result<MarketsPage> {
         let offset = params
             .and_then(|options| options.offset.map(u64::from))
             .or_else(|| next_cursor.and_then(Self::decode_cursor))
@@ -1722,6 +4735,9 @@ impl ClobClient {
             if let Some(tag_id) = &options.tag_id {
                 query.push(("tag_id", tag_id.clone()));
             }
+            if let Some(category_id) = options.category_id {
+                query.push(("category_id", category_id.to_string()));
+            }
             if let Some(exclude_tag_id) = &options.exclude_tag_id {
                 query.push(("exclude_tag_id", exclude_tag_id.clone()));
             }
@@ -1734,6 +4750,8 @@ impl ClobClient {
             if let Some(ascending) = options.ascending {
                 query.push(("ascending", ascending.to_string()));
             }
+            query.extend(options.multi_value_query_params());
+            query.extend(options.volume_query_params());
         } else {
             query.push(("closed", "false".to_string()));
         }
@@ -1742,9 +4760,9 @@ impl ClobClient {
             .http_client
             .get(self.gamma_url("markets"))
             .query(&query)
-            .send()
+            .send_via(self)
             .await
-            .map_err(|e| PolyError::network(format!("Request failed: {}", e), e))?;
+            .map_err(request_failed)?;
 
         if !response.status().is_success() {
             return Err(PolyError::api(
@@ -1758,27 +4776,12 @@ impl ClobClient {
             .await
             .map_err(|e| PolyError::parse(format!("Failed to read response body: {}", e), None))?;
 
-        let gamma_markets: Vec<crate::types::GammaMarket> = serde_json::from_str(&body)
-            .map_err(|e| PolyError::parse(format!("Failed to parse response: {}", e), None))?;
-
-        let count = gamma_markets.len();
-        let next_cursor = if count < limit as usize {
-            None
-        } else {
-            Some(Self::encode_cursor(offset + count as u64))
-        };
-        let markets = gamma_markets
-            .into_iter()
-            .map(|gamma| gamma.into())
-            .collect::<Vec<_>>();
-
-        Ok(crate::types::MarketsResponse {
-            limit: Decimal::from(limit),
-            count: Decimal::from_i64(count as i64).unwrap_or(Decimal::ZERO),
-            next_cursor,
-            data: markets,
-        })
-    }
+        Ok(MarketsPage {
+            body,
+            offset,
+            limit,
+        })
+    }
 
     /// Get simplified markets with pagination
     pub async fn get_simplified_markets(
@@ -1791,14 +4794,12 @@ impl ClobClient {
             .http_client
             .get(self.gamma_url("simplified-markets"))
             .query(&[("next_cursor", next_cursor)])
-            .send()
+            .send_via(self)
             .await
-            .map_err(|e| PolyError::network(format!("Request failed: {}", e), e))?;
+            .map_err(request_failed)?;
 
-        response
-            .json::<crate::types::SimplifiedMarketsResponse>()
+        self.decode_json::<crate::types::SimplifiedMarketsResponse>(response)
             .await
-            .map_err(|e| PolyError::parse(format!("Failed to parse response: {}", e), None))
     }
 
     /// Get single market by condition ID
@@ -1806,9 +4807,9 @@ impl ClobClient {
         let response = self
             .http_client
             .get(self.gamma_url(&format!("markets/{}", market_id)))
-            .send()
+            .send_via(self)
             .await
-            .map_err(|e| PolyError::network(format!("Request failed: {}", e), e))?;
+            .map_err(request_failed)?;
 
         if !response.status().is_success() {
             return Err(PolyError::api(
@@ -1833,14 +4834,596 @@ impl ClobClient {
         Ok(gamma_market.into())
     }
 
+    /// Fetch a market by the slug in its Polymarket URL
+    ///
+    /// Takes the bare slug (`will-it-rain-tomorrow`) or a pasted link, in which case
+    /// the last path segment is used. An unknown slug is an API error with status 404.
+    pub async fn get_market_by_slug(&self, slug: &str) -> Result<crate::types::Market> {
+        let slug = slug
+            .split(['?', '#'])
+            .next()
+            .unwrap_or_default()
+            .trim_end_matches('/')
+            .rsplit('/')
+            .next()
+            .unwrap_or_default()
+            .trim();
+        if slug.is_empty() {
+            return Err(PolyError::validation("market slug is empty"));
+        }
+
+        let response = self
+            .http_client
+            .get(self.gamma_url(&format!("markets/slug/{}", slug)))
+            .send_via(self)
+            .await
+            .map_err(request_failed)?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(PolyError::api(404, format!("No market with slug {}", slug)));
+        }
+        if !response.status().is_success() {
+            return Err(PolyError::api(
+                response.status().as_u16(),
+                "Failed to fetch Gamma market",
+            ));
+        }
+
+        let gamma_market: crate::types::GammaMarket = self.decode_json(response).await?;
+        Ok(gamma_market.into())
+    }
+
+    /// Addresses that traded in a market, biggest volume first
+    ///
+    /// The server is asked to order by volume, so pages follow on from each other;
+    /// each page is also sorted locally in case the server ignores the order. Public;
+    /// no credentials needed. Pass the returned `next_cursor` back in `params.cursor`
+    /// for the following page.
+    pub async fn get_market_participants(
+        &self,
+        condition_id: &str,
+        params: &crate::types::ParticipantParams,
+    ) -> Result<crate::types::PagedResponse<crate::types::Participant>> {
+        let condition_id = condition_id.trim();
+        if condition_id.is_empty() {
+            return Err(PolyError::validation("condition id is empty"));
+        }
+
+        let response = self
+            .http_client
+            .get(self.clob_url(&format!("markets/{}/participants", condition_id)))
+            .query(&params.to_query_params())
+            .query(&[("order", "volumeNum"), ("ascending", "false")])
+            .send_via(self)
+            .await
+            .map_err(request_failed)?;
+
+        if !response.status().is_success() {
+            return Err(PolyError::api(
+                response.status().as_u16(),
+                format!("Failed to get participants of {}", condition_id),
+            ));
+        }
+
+        let page: crate::types::CursorPage<crate::types::Participant> =
+            self.decode_json(response).await?;
+        let mut participants = page.into_paged();
+        participants
+            .data
+            .sort_by_key(|participant| std::cmp::Reverse(participant.volume_traded));
+        Ok(participants)
+    }
+
+    /// Trading totals for `address` across every market
+    pub async fn get_participant_stats(
+        &self,
+        address: &str,
+    ) -> Result<crate::types::GlobalParticipantStats> {
+        let address = crate::utils::address::parse_address(address)?;
+
+        let response = self
+            .http_client
+            .get(self.clob_url(&format!("participants/{:#x}/stats", address)))
+            .send_via(self)
+            .await
+            .map_err(request_failed)?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(PolyError::api(
+                404,
+                format!("No trading history for {:#x}", address),
+            ));
+        }
+        if !response.status().is_success() {
+            return Err(PolyError::api(
+                response.status().as_u16(),
+                "Failed to get participant stats",
+            ));
+        }
+
+        self.decode_json(response).await
+    }
+
+    /// Resolution schedule of a market that settles on a calendar, weekly or monthly
+    pub async fn get_market_schedule(
+        &self,
+        condition_id: &str,
+    ) -> Result<crate::types::MarketSchedule> {
+        let condition_id = condition_id.trim();
+        if condition_id.is_empty() {
+            return Err(PolyError::validation("condition id is empty"));
+        }
+
+        let response = self
+            .http_client
+            .get(self.clob_url(&format!("markets/{}/schedule", condition_id)))
+            .send_via(self)
+            .await
+            .map_err(request_failed)?;
+
+        if !response.status().is_success() {
+            return Err(PolyError::api(
+                response.status().as_u16(),
+                format!("Failed to get schedule of {}", condition_id),
+            ));
+        }
+
+        self.decode_json(response).await
+    }
+
+    /// Payout reports posted on-chain for a condition, oldest first
+    ///
+    /// Public; no credentials needed. Empty until the market has resolved.
+    pub async fn get_settlement_transactions(
+        &self,
+        condition_id: &str,
+    ) -> Result<Vec<crate::types::SettlementTx>> {
+        let condition_id = condition_id.trim();
+        if condition_id.is_empty() {
+            return Err(PolyError::validation("condition id is empty"));
+        }
+
+        let response = self
+            .http_client
+            .get(self.clob_url("settlements"))
+            .query(&[("condition_id", condition_id)])
+            .send_via(self)
+            .await
+            .map_err(request_failed)?;
+
+        if !response.status().is_success() {
+            return Err(PolyError::api(
+                response.status().as_u16(),
+                format!("Failed to get settlements of {}", condition_id),
+            ));
+        }
+
+        self.decode_json(response).await
+    }
+
+    /// Balance and coverage of the protocol insurance fund
+    ///
+    /// Public; no credentials needed. Responses are reused for 60 seconds, timed by the
+    /// client's clock.
+    pub async fn get_insurance_fund_balance(&self) -> Result<crate::types::InsuranceFund> {
+        self.get_protocol_cached(
+            &self.protocol_cache.insurance,
+            "protocol/insurance",
+            "insurance fund",
+        )
+        .await
+    }
+
+    /// Protocol-wide volume, open interest and activity counts
+    ///
+    /// Public and cached like [`get_insurance_fund_balance`](Self::get_insurance_fund_balance);
+    /// pass both results to [`ProtocolStats::is_healthy`](crate::types::ProtocolStats::is_healthy).
+    pub async fn get_protocol_stats(&self) -> Result<crate::types::ProtocolStats> {
+        self.get_protocol_cached(
+            &self.protocol_cache.stats,
+            "protocol/stats",
+            "protocol stats",
+        )
+        .await
+    }
+
+    /// Volume-weighted mean "Yes" price of the busiest active markets, optionally only
+    /// those whose Gamma `category` matches `category`, ignoring case
+    ///
+    /// Computed client-side from one page of Gamma markets ordered by lifetime volume,
+    /// so the top markets are taken from the highest-volume candidates under the usual
+    /// [`get_markets`](Self::get_markets) defaults. Results are cached per category for
+    /// 60 seconds by the client's clock. A validation error when no market qualifies.
+    pub async fn get_prediction_market_index(
+        &self,
+        category: Option<&str>,
+    ) -> Result<crate::types::PredictionIndex> {
+        self.poll_epoch().await;
+        let key = category.map(str::to_lowercase).unwrap_or_default();
+        let now = self.clock.now_secs();
+        if let Some((fetched_at, index)) = self
+            .protocol_cache
+            .indices
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&key)
+            && now.saturating_sub(*fetched_at) < PROTOCOL_CACHE_TTL_SECS
+        {
+            return Ok(index.clone());
+        }
+
+        let params = crate::types::GammaListParams {
+            limit: Some(PREDICTION_INDEX_CANDIDATES),
+            order: Some("volumeNum".to_string()),
+            ascending: Some(false),
+            ..Default::default()
+        };
+        let page = self.fetch_markets_page(None, Some(&params)).await?;
+        let mut markets: Vec<crate::types::GammaMarket> = serde_json::from_str(&page.body)
+            .map_err(|e| PolyError::parse(format!("Failed to parse response: {}", e), None))?;
+        if let Some(category) = category {
+            markets.retain(|market| {
+                market
+                    .category
+                    .as_deref()
+                    .is_some_and(|name| name.eq_ignore_ascii_case(category))
+            });
+        }
+        let computed_at = chrono::DateTime::from_timestamp(now as i64, 0).unwrap_or_else(Utc::now);
+        let index = crate::types::PredictionIndex::from_markets(
+            &markets,
+            PREDICTION_INDEX_MARKETS,
+            computed_at,
+        )
+        .ok_or_else(|| {
+            PolyError::validation(match category {
+                Some(category) => format!("No active markets with volume in category {}", category),
+                None => "No active markets with volume".to_string(),
+            })
+        })?;
+
+        self.protocol_cache
+            .indices
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(key, (now, index.clone()));
+        Ok(index)
+    }
+
+    async fn get_protocol_cached<T: DeserializeOwned + Clone>(
+        &self,
+        slot: &CachedResponse<T>,
+        path: &str,
+        what: &str,
+    ) -> Result<T> {
+        self.poll_epoch().await;
+        let now = self.clock.now_secs();
+        if let Some((fetched_at, value)) = &*slot.lock().unwrap_or_else(PoisonError::into_inner)
+            && now.saturating_sub(*fetched_at) < PROTOCOL_CACHE_TTL_SECS
+        {
+            return Ok(value.clone());
+        }
+
+        let response = self
+            .http_client
+            .get(self.clob_url(path))
+            .send_via(self)
+            .await
+            .map_err(request_failed)?;
+
+        if !response.status().is_success() {
+            return Err(PolyError::api(
+                response.status().as_u16(),
+                format!("Failed to get {}", what),
+            ));
+        }
+
+        let value: T = self.decode_json(response).await?;
+        *slot.lock().unwrap_or_else(PoisonError::into_inner) = Some((now, value.clone()));
+        Ok(value)
+    }
+
+    /// The current liquidity reward epoch
+    ///
+    /// Public; no credentials needed. The result is also kept for
+    /// [`current_epoch`](Self::current_epoch), and with
+    /// [`with_epoch_cache_invalidation`](Self::with_epoch_cache_invalidation) on, a
+    /// different epoch ID than the last one seen clears the client's caches.
+    pub async fn get_current_epoch(&self) -> Result<crate::types::Epoch> {
+        let now = self.clock.now_secs();
+        self.epoch
+            .state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .checked_at = Some(now);
+
+        let response = self
+            .http_client
+            .get(self.clob_url("epoch"))
+            .send_via(self)
+            .await
+            .map_err(request_failed)?;
+
+        if !response.status().is_success() {
+            return Err(PolyError::api(
+                response.status().as_u16(),
+                "Failed to get current epoch",
+            ));
+        }
+
+        let epoch: crate::types::Epoch = self.decode_json(response).await?;
+        let previous = self
+            .epoch
+            .state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .epoch
+            .replace(epoch);
+        if self.epoch.invalidate_caches
+            && let Some(previous) = previous
+            && previous.id != epoch.id
+        {
+            tracing::info!(
+                previous = previous.id,
+                current = epoch.id,
+                "reward epoch changed, clearing caches"
+            );
+            if let Some(cache) = &self.market_meta {
+                cache.clear();
+            }
+            self.protocol_cache.clear();
+        }
+        Ok(epoch)
+    }
+
+    /// The epoch from the last successful [`get_current_epoch`](Self::get_current_epoch),
+    /// without a request
+    pub fn current_epoch(&self) -> Option<crate::types::Epoch> {
+        self.epoch
+            .state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .epoch
+    }
+
+    /// Check the epoch when invalidation is on and the last check is due for renewal
+    async fn poll_epoch(&self) {
+        if !self.epoch.invalidate_caches {
+            return;
+        }
+        let checked_at = self
+            .epoch
+            .state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .checked_at;
+        if checked_at.is_some_and(|at| self.clock.now_secs().saturating_sub(at) < EPOCH_POLL_SECS) {
+            return;
+        }
+        if let Err(error) = self.get_current_epoch().await {
+            tracing::warn!(%error, "reward epoch check failed");
+        }
+    }
+
+    /// Liquidation prices of `positions` if each had been bought on margin
+    ///
+    /// Polymarket itself doesn't lend, so this is a what-if computed locally: each
+    /// position is taken to have posted `margin_ratio` of its entry cost (`size` times
+    /// `avg_price`) in cash and borrowed the rest, and is liquidated once its value at
+    /// `cur_price` no longer covers the loan. That puts the liquidation price at
+    /// `avg_price * (1 - margin_ratio)`, so a ratio of one, fully cash-secured, never
+    /// liquidates. `margin_ratio` must be in `(0, 1]` and sizes can't be negative.
+    pub fn simulate_liquidation(
+        &self,
+        positions: &[crate::types::DataPosition],
+        margin_ratio: Decimal,
+    ) -> Result<crate::types::LiquidationScenario> {
+        if margin_ratio <= Decimal::ZERO || margin_ratio > Decimal::ONE {
+            return Err(PolyError::validation(format!(
+                "margin ratio {} is outside (0, 1]",
+                margin_ratio
+            )));
+        }
+
+        let mut token_liquidation_prices = std::collections::HashMap::new();
+        let (mut margin_used, mut borrowed, mut value) =
+            (Decimal::ZERO, Decimal::ZERO, Decimal::ZERO);
+        let mut distance_to_liquidation = Decimal::ONE;
+        for position in positions {
+            if position.size.is_sign_negative() {
+                return Err(PolyError::validation(format!(
+                    "position in {} has negative size {}",
+                    position.asset, position.size
+                )));
+            }
+            let cost = position.size * position.avg_price;
+            margin_used += cost * margin_ratio;
+            borrowed += cost - cost * margin_ratio;
+            value += position.size * position.cur_price;
+
+            let liquidation_price = Some(position.avg_price * (Decimal::ONE - margin_ratio))
+                .filter(|price| *price > Decimal::ZERO && !position.size.is_zero());
+            if let Some(price) = liquidation_price {
+                let distance = if position.cur_price > Decimal::ZERO {
+                    ((position.cur_price - price) / position.cur_price).max(Decimal::ZERO)
+                } else {
+                    Decimal::ZERO
+                };
+                distance_to_liquidation = distance_to_liquidation.min(distance);
+            }
+            token_liquidation_prices.insert(position.asset.clone(), liquidation_price);
+        }
+
+        let margin_buffer = if value > Decimal::ZERO {
+            ((value - borrowed) / value).max(Decimal::ZERO)
+        } else if borrowed.is_zero() {
+            Decimal::ONE
+        } else {
+            Decimal::ZERO
+        };
+        Ok(crate::types::LiquidationScenario {
+            token_liquidation_prices,
+            total_margin_used: margin_used,
+            margin_buffer,
+            distance_to_liquidation,
+        })
+    }
+
+    /// Gas needed to redeem the funder wallet's winning tokens of `condition_id`
+    ///
+    /// Checks the wallet's Data API positions first and returns a validation error when
+    /// none of them in the condition is redeemable with a positive price, so no
+    /// estimate is asked for a redemption that would pay nothing. The Data API marks
+    /// losing positions of a resolved market redeemable too, at a price of zero.
+    pub async fn estimate_gas_for_redemption(
+        &self,
+        condition_id: &str,
+    ) -> Result<crate::types::GasEstimate> {
+        let condition_id = condition_id.trim();
+        if condition_id.is_empty() {
+            return Err(PolyError::validation("condition id is empty"));
+        }
+        let redeemable = self.get_funder_positions().await?.iter().any(|position| {
+            position.condition_id == condition_id
+                && position.redeemable
+                && position.size > Decimal::ZERO
+                && position.cur_price > Decimal::ZERO
+        });
+        if !redeemable {
+            return Err(PolyError::validation("no redeemable position"));
+        }
+
+        let estimate = self
+            .request_signed::<Value>(
+                Method::GET,
+                "/gas-estimate",
+                &[("action", "redeem"), ("condition_id", condition_id)],
+                None,
+            )
+            .await?;
+        serde_json::from_value(estimate)
+            .map_err(|e| PolyError::parse(format!("Failed to parse gas estimate: {}", e), None))
+    }
+
+    /// The authenticated user's redemptions, each with the settlement it redeemed against
+    pub async fn get_my_settlement_receipts(
+        &self,
+    ) -> Result<Vec<crate::types::PersonalSettlement>> {
+        let receipts = self
+            .request_signed::<Value>(Method::GET, "/data/settlements", &[], None)
+            .await?;
+        match receipts {
+            Value::Null => Ok(Vec::new()),
+            receipts => serde_json::from_value(receipts).map_err(|e| {
+                PolyError::parse(format!("Failed to parse settlement receipts: {}", e), None)
+            }),
+        }
+    }
+
+    /// Everything that happened in a market since `since`, oldest first
+    ///
+    /// Price moves, fills, book changes and the market's closing and resolution in one
+    /// timeline, for replaying history the websocket feed no longer has. Public; no
+    /// credentials needed.
+    pub async fn get_market_events_feed(
+        &self,
+        condition_id: &str,
+        since: chrono::DateTime<Utc>,
+    ) -> Result<Vec<crate::types::MarketEvent>> {
+        let condition_id = condition_id.trim();
+        if condition_id.is_empty() {
+            return Err(PolyError::validation("condition id is empty"));
+        }
+
+        let response = self
+            .http_client
+            .get(self.clob_url(&format!("markets/{}/events", condition_id)))
+            .query(&[("since", since.timestamp().to_string())])
+            .send_via(self)
+            .await
+            .map_err(request_failed)?;
+
+        if !response.status().is_success() {
+            return Err(PolyError::api(
+                response.status().as_u16(),
+                format!("Failed to get events of {}", condition_id),
+            ));
+        }
+
+        let mut events: Vec<crate::types::MarketEvent> = self.decode_json(response).await?;
+        events.sort_by_key(crate::types::MarketEvent::timestamp);
+        Ok(events)
+    }
+
+    /// Entities, date confidence, ambiguity and similar markets for a market's question
+    ///
+    /// Asks `GET /markets/{condition_id}/analysis` first. When the server has no such
+    /// endpoint (a 404), the analysis is built client-side instead: the market is looked
+    /// up on Gamma, its question scanned as described in [`crate::question`], and similar
+    /// markets ranked from a [`search_markets`](Self::search_markets) for the question's
+    /// keywords. An unknown condition ID is then an API error with status 404; a failed
+    /// search is logged and leaves `similar_markets` empty.
+    pub async fn get_market_question_analysis(
+        &self,
+        condition_id: &str,
+    ) -> Result<crate::question::QuestionAnalysis> {
+        let condition_id = condition_id.trim();
+        if condition_id.is_empty() {
+            return Err(PolyError::validation("condition id is empty"));
+        }
+
+        let response = self
+            .http_client
+            .get(self.clob_url(&format!("markets/{}/analysis", condition_id)))
+            .send_via(self)
+            .await
+            .map_err(request_failed)?;
+
+        match response.status().as_u16() {
+            404 => {}
+            status if !response.status().is_success() => {
+                return Err(PolyError::api(
+                    status,
+                    format!("Failed to get question analysis of {}", condition_id),
+                ));
+            }
+            _ => return self.decode_json(response).await,
+        }
+
+        let market = self
+            .get_markets_chunked("condition_ids", &[condition_id.to_string()])
+            .await?
+            .into_iter()
+            .find(|market| market.condition_id == condition_id)
+            .ok_or_else(|| {
+                PolyError::api(404, format!("No market with condition id {}", condition_id))
+            })?;
+        // Similar markets are a nice-to-have; the analysis of the question itself
+        // stands without them
+        let candidates = match crate::question::search_query(&market.question) {
+            Some(query) => match self.search_markets(&query, None).await {
+                Ok(found) => found.data,
+                Err(err) => {
+                    tracing::warn!(condition_id, %err, "Market search failed; no similar markets");
+                    Vec::new()
+                }
+            },
+            None => Vec::new(),
+        };
+        Ok(crate::question::QuestionAnalysis::heuristic(
+            &market,
+            &candidates,
+        ))
+    }
+
     /// Get market trades events
     pub async fn get_market_trades_events(&self, condition_id: &str) -> Result<Value> {
         let response = self
             .http_client
             .get(self.clob_url(&format!("live-activity/events/{}", condition_id)))
-            .send()
+            .send_via(self)
             .await
-            .map_err(|e| PolyError::network(format!("Request failed: {}", e), e))?;
+            .map_err(request_failed)?;
 
         response
             .json::<Value>()
@@ -1859,10 +5442,7 @@ impl ClobClient {
             request = request.query(&options.to_query_params());
         }
 
-        let response = request
-            .send()
-            .await
-            .map_err(|e| PolyError::network(format!("Request failed: {}", e), e))?;
+        let response = request.send_via(self).await.map_err(request_failed)?;
 
         if !response.status().is_success() {
             return Err(PolyError::api(
@@ -1884,9 +5464,9 @@ impl ClobClient {
         let response = self
             .http_client
             .get(self.gamma_url(&format!("events/slug/{}", slug)))
-            .send()
+            .send_via(self)
             .await
-            .map_err(|e| PolyError::network(format!("Request failed: {}", e), e))?;
+            .map_err(request_failed)?;
 
         if !response.status().is_success() {
             return Err(PolyError::api(
@@ -1895,10 +5475,7 @@ impl ClobClient {
             ));
         }
 
-        response
-            .json::<crate::types::GammaEvent>()
-            .await
-            .map_err(|e| PolyError::parse(format!("Failed to parse response: {}", e), None))
+        self.decode_json::<crate::types::GammaEvent>(response).await
     }
 
     /// Fetch a single Gamma event by numeric ID
@@ -1906,9 +5483,9 @@ impl ClobClient {
         let response = self
             .http_client
             .get(self.gamma_url(&format!("events/{}", event_id)))
-            .send()
+            .send_via(self)
             .await
-            .map_err(|e| PolyError::network(format!("Request failed: {}", e), e))?;
+            .map_err(request_failed)?;
 
         if !response.status().is_success() {
             return Err(PolyError::api(
@@ -1917,10 +5494,39 @@ impl ClobClient {
             ));
         }
 
-        response
-            .json::<crate::types::GammaEvent>()
+        self.decode_json::<crate::types::GammaEvent>(response).await
+    }
+
+    /// News articles Gamma links to a market
+    ///
+    /// The CLOB has no news endpoint, so this asks Gamma's
+    /// `GET /markets/{condition_id}/news`. Feed the result to
+    /// [`news_sentiment_score`](crate::types::news_sentiment_score) for a single
+    /// signal.
+    pub async fn get_market_news(
+        &self,
+        condition_id: &str,
+    ) -> Result<Vec<crate::types::NewsArticle>> {
+        let response = self
+            .http_client
+            .get(self.gamma_url(&format!("markets/{}/news", condition_id)))
+            .send_via(self)
             .await
-            .map_err(|e| PolyError::parse(format!("Failed to parse response: {}", e), None))
+            .map_err(request_failed)?;
+
+        if !response.status().is_success() {
+            return Err(PolyError::api(
+                response.status().as_u16(),
+                "Failed to fetch market news",
+            ));
+        }
+
+        let payload: Value = response
+            .json()
+            .await
+            .map_err(|e| PolyError::parse(format!("Failed to parse response: {}", e), None))?;
+
+        self.parse_gamma_list(payload, "market news")
     }
 
     /// Fetch available Gamma tags
@@ -1928,9 +5534,9 @@ impl ClobClient {
         let response = self
             .http_client
             .get(self.gamma_url("tags"))
-            .send()
+            .send_via(self)
             .await
-            .map_err(|e| PolyError::network(format!("Request failed: {}", e), e))?;
+            .map_err(request_failed)?;
 
         if !response.status().is_success() {
             return Err(PolyError::api(
@@ -1947,14 +5553,38 @@ impl ClobClient {
         self.parse_gamma_list(payload, "Gamma tags")
     }
 
+    /// Fetch Gamma's market category taxonomy
+    pub async fn get_market_categories(&self) -> Result<Vec<crate::types::Category>> {
+        let response = self
+            .http_client
+            .get(self.gamma_url("categories"))
+            .send_via(self)
+            .await
+            .map_err(request_failed)?;
+
+        if !response.status().is_success() {
+            return Err(PolyError::api(
+                response.status().as_u16(),
+                "Failed to fetch Gamma categories",
+            ));
+        }
+
+        let payload: Value = response
+            .json()
+            .await
+            .map_err(|e| PolyError::parse(format!("Failed to parse response: {}", e), None))?;
+
+        self.parse_gamma_list(payload, "Gamma categories")
+    }
+
     /// Fetch available Gamma sports metadata
     pub async fn get_sports(&self) -> Result<Vec<crate::types::Sport>> {
         let response = self
             .http_client
             .get(self.gamma_url("sports"))
-            .send()
+            .send_via(self)
             .await
-            .map_err(|e| PolyError::network(format!("Request failed: {}", e), e))?;
+            .map_err(request_failed)?;
 
         if !response.status().is_success() {
             return Err(PolyError::api(
@@ -2005,12 +5635,166 @@ pub struct CreateOrderOptions {
 // Re-export for compatibility
 pub type PolyClient = ClobClient;
 
-#[async_trait]
-pub trait MarketClient: Send + Sync {
-    async fn get_markets(
-        &self,
-        next_cursor: Option<&str>,
-        params: Option<&crate::types::GammaListParams>,
+/// One set of hosts, credentials and HTTP settings for the REST and websocket clients
+///
+/// Built once from a [`ClientConfig`](crate::types::ClientConfig); [`clob`](Self::clob),
+/// [`market_ws`](Self::market_ws) and [`user_ws`](Self::user_ws) then hand out clients
+/// configured from it. Every `ClobClient` derived this way shares one connection pool
+/// and, when `max_concurrency` is set, one in-flight cap, so cloning clients per task
+/// doesn't multiply connections or requests.
+///
+/// The request timeout and `max_connections` (idle connections kept per host) apply to
+/// REST calls only; the websocket clients take the WSS base and the API credentials.
+#[derive(Clone)]
+pub struct PolyFacade {
+    http_client: Client,
+    config: crate::types::ClientConfig,
+    signer: Option<PrivateKeySigner>,
+    /// Funder and signature type resolved from the config, for signed orders
+    funding: Option<(Address, crate::orders::SigType)>,
+    request_limiter: Option<RequestLimiter>,
+    wss_base_url: String,
+}
+
+impl std::fmt::Debug for PolyFacade {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PolyFacade")
+            .field("base_url", &self.config.base_url)
+            .field("wss_base_url", &self.wss_base_url)
+            .field("chain_id", &self.config.chain_id)
+            .field(
+                "signer",
+                &self.signer.as_ref().map(PrivateKeySigner::address),
+            )
+            .field("funding", &self.funding)
+            .field(
+                "has_api_credentials",
+                &self.config.api_credentials.is_some(),
+            )
+            .finish_non_exhaustive()
+    }
+}
+
+impl PolyFacade {
+    /// Build the shared HTTP client and parse the signing key and funder, if any
+    ///
+    /// A `funder` needs a `private_key`; it and `signature_type` are checked with
+    /// [`SigType::infer`](crate::orders::SigType::infer), so a pairing the exchange
+    /// would reject is a config error here rather than on the first order.
+    pub fn new(config: crate::types::ClientConfig) -> Result<Self> {
+        let mut builder = Client::builder();
+        if let Some(timeout) = config.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(max_connections) = config.max_connections {
+            builder = builder.pool_max_idle_per_host(max_connections);
+        }
+        let http_client = builder
+            .build()
+            .map_err(|e| PolyError::internal("Failed to build HTTP client", e))?;
+
+        let signer = config
+            .private_key
+            .as_deref()
+            .map(|key| {
+                key.parse::<PrivateKeySigner>()
+                    .map_err(|e| PolyError::config(format!("Invalid private key: {}", e)))
+            })
+            .transpose()?;
+        let funder = config
+            .funder
+            .as_deref()
+            .map(|funder| {
+                Address::from_str(funder)
+                    .map_err(|e| PolyError::config(format!("Invalid funder address: {}", e)))
+            })
+            .transpose()?;
+        let funding = match &signer {
+            Some(signer) => {
+                let sig_type =
+                    crate::orders::SigType::infer(signer.address(), funder, config.signature_type)?;
+                Some((funder.unwrap_or(signer.address()), sig_type))
+            }
+            None if funder.is_some() || config.signature_type.is_some() => {
+                return Err(PolyError::config(
+                    "A funder or signature type needs a private key to sign with",
+                ));
+            }
+            None => None,
+        };
+        let request_limiter = config
+            .max_concurrency
+            .map(|limit| RequestLimiter::new(RequestLimits::new(limit)));
+
+        Ok(Self {
+            http_client,
+            config,
+            signer,
+            funding,
+            request_limiter,
+            wss_base_url: crate::wss::DEFAULT_WSS_BASE.to_string(),
+        })
+    }
+
+    /// Override the websocket base URL the channel paths are appended to
+    pub fn with_wss_base(mut self, url: &str) -> Self {
+        self.wss_base_url = url.to_string();
+        self
+    }
+
+    pub fn config(&self) -> &crate::types::ClientConfig {
+        &self.config
+    }
+
+    /// The HTTP client shared by every derived `ClobClient`
+    pub fn http_client(&self) -> &Client {
+        &self.http_client
+    }
+
+    /// A REST client on the shared connection pool, signing with the configured key,
+    /// funder, signature type and API credentials
+    pub fn clob(&self) -> ClobClient {
+        let mut client = ClobClient::new(&self.config.base_url);
+        client.http_client = self.http_client.clone();
+        client.chain_id = self.config.chain_id;
+        client.api_creds = self.config.api_credentials.clone();
+        client.request_limiter = self.request_limiter.clone();
+        if let (Some(signer), Some((funder, sig_type))) = (&self.signer, self.funding) {
+            client.order_builder = Some(crate::orders::OrderBuilder::new(
+                signer.clone(),
+                Some(sig_type),
+                Some(funder),
+            ));
+            client.signer = Some(signer.clone());
+        }
+        client
+    }
+
+    /// A market channel client against the configured WSS base
+    pub fn market_ws(&self) -> crate::wss::WssMarketClient {
+        crate::wss::WssMarketClient::with_url(&self.wss_base_url)
+    }
+
+    /// A user channel client authenticated with the configured API credentials
+    ///
+    /// Returns a config error when the config has none.
+    pub fn user_ws(&self) -> Result<crate::wss::WssUserClient> {
+        let auth = self.config.api_credentials.clone().ok_or_else(|| {
+            PolyError::config("API credentials are required for the user channel")
+        })?;
+        Ok(crate::wss::WssUserClient::with_url(
+            &self.wss_base_url,
+            auth,
+        ))
+    }
+}
+
+#[async_trait]
+pub trait MarketClient: Send + Sync {
+    async fn get_markets(
+        &self,
+        next_cursor: Option<&str>,
+        params: Option<&crate::types::GammaListParams>,
     ) -> Result<crate::types::MarketsResponse>;
     async fn get_order_books(
         &self,
@@ -2021,7 +5805,7 @@ pub trait MarketClient: Send + Sync {
         &self,
         market: Option<&str>,
         asset_id: Option<&str>,
-    ) -> Result<serde_json::Value>;
+    ) -> Result<crate::types::CancelResponse>;
     async fn create_order(
         &self,
         order_args: &OrderArgs,
@@ -2054,14 +5838,14 @@ impl MarketClient for ClobClient {
     }
 
     async fn get_order_book(&self, token_id: &str) -> Result<crate::types::OrderBookSummary> {
-        ClobClient::get_order_book(self, token_id).await
+        ClobClient::get_order_book(self, token_id, None).await
     }
 
     async fn cancel_market_orders(
         &self,
         market: Option<&str>,
         asset_id: Option<&str>,
-    ) -> Result<serde_json::Value> {
+    ) -> Result<crate::types::CancelResponse> {
         ClobClient::cancel_market_orders(self, market, asset_id).await
     }
 
@@ -2087,8 +5871,9 @@ impl MarketClient for ClobClient {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testkit::MockExchange;
     use crate::types::*;
-    use mockito::{Matcher, Server};
+    use mockito::Matcher;
     use rust_decimal::Decimal;
     use std::str::FromStr;
     use tokio;
@@ -2106,10 +5891,69 @@ mod tests {
         .with_gamma_base(base_url)
     }
 
+    fn create_test_client_with_l2(base_url: &str) -> ClobClient {
+        ClobClient::with_l2_headers(
+            base_url,
+            "0x1234567890123456789012345678901234567890123456789012345678901234",
+            137,
+            ApiCredentials {
+                api_key: "test_key".to_string(),
+                secret: "dGVzdF9zZWNyZXQ=".to_string(),
+                passphrase: "test_passphrase".to_string(),
+            },
+        )
+        .with_gamma_base(base_url)
+    }
+
+    fn open_order_json(order_id: &str, status: &str, size_matched: &str) -> String {
+        serde_json::json!({
+            "associate_trades": [],
+            "id": order_id,
+            "status": status,
+            "market": "0xmarket",
+            "original_size": "10",
+            "outcome": "Yes",
+            "maker_address": "0xmaker",
+            "owner": "owner",
+            "price": "0.5",
+            "side": "BUY",
+            "size_matched": size_matched,
+            "asset_id": "123",
+            "expiration": "0",
+            "type": "GTC",
+            "created_at": "1700000000",
+        })
+        .to_string()
+    }
+
+    /// Mock `GET /data/order/{id}` returning LIVE for the first `live_polls` calls, then MATCHED
+    async fn mock_order_fills_after(
+        server: &mut MockExchange,
+        order_id: &'static str,
+        live_polls: usize,
+    ) -> mockito::Mock {
+        let polls = std::sync::atomic::AtomicUsize::new(0);
+        server
+            .mock("GET", format!("/data/order/{}", order_id).as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body_from_request(move |_| {
+                let poll = polls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                if poll < live_polls {
+                    open_order_json(order_id, "LIVE", "0").into()
+                } else {
+                    open_order_json(order_id, "MATCHED", "10").into()
+                }
+            })
+            .expect(live_polls + 1)
+            .create_async()
+            .await
+    }
+
     #[tokio::test]
     async fn test_client_creation() {
         let client = create_test_client("https://test.example.com");
-        assert_eq!(client.base_url, "https://test.example.com");
+        assert_eq!(client.clob_base_url, "https://test.example.com");
         assert!(client.signer.is_none());
         assert!(client.api_creds.is_none());
     }
@@ -2117,7 +5961,7 @@ mod tests {
     #[tokio::test]
     async fn test_client_with_l1_headers() {
         let client = create_test_client_with_auth("https://test.example.com");
-        assert_eq!(client.base_url, "https://test.example.com");
+        assert_eq!(client.clob_base_url, "https://test.example.com");
         assert!(client.signer.is_some());
         assert_eq!(client.chain_id, 137);
     }
@@ -2137,7 +5981,7 @@ mod tests {
             api_creds.clone(),
         );
 
-        assert_eq!(client.base_url, "https://test.example.com");
+        assert_eq!(client.clob_base_url, "https://test.example.com");
         assert!(client.signer.is_some());
         assert!(client.api_creds.is_some());
         assert_eq!(client.chain_id, 137);
@@ -2159,9 +6003,86 @@ mod tests {
         assert_eq!(client.api_creds.unwrap().api_key, "test_key");
     }
 
+    #[tokio::test]
+    async fn test_request_signed_attaches_l2_headers() {
+        let mut server = MockExchange::empty().await;
+        let api_creds = ApiCredentials {
+            api_key: "test_key".to_string(),
+            secret: "dGVzdF9zZWNyZXQ=".to_string(),
+            passphrase: "test_passphrase".to_string(),
+        };
+        let client = ClobClient::with_l2_headers(
+            &server.url(),
+            "0x1234567890123456789012345678901234567890123456789012345678901234",
+            137,
+            api_creds.clone(),
+        );
+        let address = format!("{:#x}", client.signer.as_ref().unwrap().address());
+
+        // Echo the signed pieces back so the test can recompute the HMAC
+        let mock = server
+            .mock("POST", "/rewards/new-endpoint")
+            .match_query(Matcher::UrlEncoded("market".into(), "0xabc".into()))
+            .match_header("poly_api_key", "test_key")
+            .match_header("poly_passphrase", "test_passphrase")
+            .match_header("poly_address", address.as_str())
+            .match_header("content-type", "application/json")
+            .with_status(200)
+            .with_body_from_request(|request| {
+                let header = |name: &str| request.header(name)[0].to_str().unwrap().to_string();
+                serde_json::json!({
+                    "ok": true,
+                    "timestamp": header("poly_timestamp"),
+                    "signature": header("poly_signature"),
+                    "body": String::from_utf8(request.body().unwrap().clone()).unwrap(),
+                })
+                .to_string()
+                .into()
+            })
+            .create_async()
+            .await;
+
+        let body = serde_json::json!({"size": "10", "price": 0.5});
+        let response = client
+            .request_signed(
+                Method::POST,
+                "rewards/new-endpoint",
+                &[("market", "0xabc")],
+                Some(&body),
+            )
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(response["ok"], true);
+
+        let sent_body = response["body"].as_str().unwrap();
+        assert_eq!(serde_json::from_str::<Value>(sent_body).unwrap(), body);
+        let timestamp: u64 = response["timestamp"].as_str().unwrap().parse().unwrap();
+        let raw_body = serde_json::value::RawValue::from_string(sent_body.to_string()).unwrap();
+        let expected = crate::auth::build_hmac_signature(
+            &api_creds.secret,
+            timestamp,
+            "POST",
+            "/rewards/new-endpoint",
+            Some(&*raw_body),
+        )
+        .unwrap();
+        assert_eq!(response["signature"], expected);
+    }
+
+    #[tokio::test]
+    async fn test_request_signed_requires_credentials() {
+        let client = create_test_client_with_auth("https://test.example.com");
+        let result = client
+            .request_signed::<Value>(Method::GET, "/anything", &[], None)
+            .await;
+        assert!(matches!(result, Err(PolyError::Config { .. })));
+    }
+
     #[tokio::test]
     async fn test_get_sampling_markets_success() {
-        let mut server = Server::new_async().await;
+        let mut server = MockExchange::empty().await;
         let mock_response = r#"{
             "limit": "10",
             "count": "2", 
@@ -2223,7 +6144,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_get_sampling_markets_with_cursor() {
-        let mut server = Server::new_async().await;
+        let mut server = MockExchange::empty().await;
         let mock_response = r#"{
             "limit": "5",
             "count": "0",
@@ -2246,30 +6167,3396 @@ mod tests {
         let client = create_test_client(&server.url());
         let result = client.get_sampling_markets(Some("test_cursor")).await;
 
-        mock.assert_async().await;
-        assert!(result.is_ok());
-        let markets = result.unwrap();
-        assert_eq!(markets.data.len(), 0);
+        mock.assert_async().await;
+        assert!(result.is_ok());
+        let markets = result.unwrap();
+        assert_eq!(markets.data.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_get_markets_filters_by_game_window() {
+        let mut server = MockExchange::empty().await;
+        let mock_response = r#"[
+            {
+                "conditionId": "0xearly",
+                "slug": "early-game",
+                "active": true,
+                "closed": false,
+                "gameStartTime": "2025-10-12 17:00:00+00",
+                "sportsMarketType": "moneyline",
+                "gameId": "9001"
+            },
+            {
+                "conditionId": "0xlate",
+                "slug": "late-game",
+                "active": true,
+                "closed": false,
+                "gameStartTime": "2025-10-13T01:30:00Z",
+                "sportsMarketType": "spreads",
+                "gameId": "9002",
+                "line": -3.5
+            },
+            {
+                "conditionId": "0xpolitics",
+                "slug": "no-game",
+                "active": true,
+                "closed": false
+            }
+        ]"#;
+
+        let mock = server
+            .mock("GET", "/markets")
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(mock_response)
+            .create_async()
+            .await;
+
+        let window_start = "2025-10-12T12:00:00Z"
+            .parse::<chrono::DateTime<Utc>>()
+            .unwrap();
+        let params = crate::types::GammaListParams {
+            limit: Some(3),
+            game_start_min: Some(window_start),
+            game_start_max: Some(window_start + Duration::hours(6)),
+            ..Default::default()
+        };
+
+        let client = create_test_client(&server.url());
+        let markets = client.get_markets(None, Some(&params)).await.unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(markets.data.len(), 1);
+        // The cursor still advances past the whole page
+        assert!(markets.next_cursor.is_some());
+
+        let market = &markets.data[0];
+        assert_eq!(market.condition_id, "0xearly");
+        assert_eq!(market.sports_market_type.as_deref(), Some("moneyline"));
+        assert!(market.is_sports());
+        assert_eq!(
+            market.seconds_until_game_start(window_start),
+            Some(5 * 3600)
+        );
+        assert!(market.has_game_started(window_start + Duration::hours(5)));
+    }
+
+    #[test]
+    fn test_query_value_chunks_respect_length() {
+        let values: Vec<String> = ["1", "22", "333", "4444"].map(String::from).to_vec();
+        // id=1& id=22& id=333& id=4444& -> 5, 6, 7, 8 bytes
+        let chunks = query_value_chunks("id", &values, 12);
+        assert_eq!(chunks, vec![&values[..2], &values[2..3], &values[3..]]);
+        assert_eq!(query_value_chunks("id", &values, 1).len(), 4);
+        assert!(query_value_chunks("id", &[], 12).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_markets_by_ids_repeats_keys_and_chunks() {
+        let mut server = MockExchange::empty().await;
+        let ids: Vec<String> = (0..500).map(|i| format!("{}", 100_000 + i)).collect();
+        // 500 pairs of `id=1000xx&` are 5,000 bytes, so the lookup needs two requests
+        let first = server
+            .mock("GET", "/markets")
+            .match_query(Matcher::Regex("^id=100000&id=100001&.*&limit=".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[{"conditionId": "0xfirst", "slug": "first", "active": true, "closed": false}]"#,
+            )
+            .create_async()
+            .await;
+        let second = server
+            .mock("GET", "/markets")
+            .match_query(Matcher::Regex("^id=100400&.*id=100499&limit=100$".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"[{"conditionId": "0xsecond", "slug": "second", "active": true, "closed": false}]"#)
+            .create_async()
+            .await;
+        let exact = server
+            .mock("GET", "/markets")
+            .match_query(Matcher::Exact(
+                "clob_token_ids=111&clob_token_ids=222&limit=2".into(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body("[]")
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let markets = client.get_markets_by_ids(&ids).await.unwrap();
+        let tokens = ["111".to_string(), "222".to_string()];
+        assert!(
+            client
+                .get_markets_by_token_ids(&tokens)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        first.assert_async().await;
+        second.assert_async().await;
+        exact.assert_async().await;
+        let condition_ids: Vec<&str> = markets.iter().map(|m| m.condition_id.as_str()).collect();
+        assert_eq!(condition_ids, vec!["0xfirst", "0xsecond"]);
+    }
+
+    #[tokio::test]
+    async fn test_get_neg_risk_siblings_reads_the_event() {
+        let mut server = MockExchange::empty().await;
+        let lookup = server
+            .mock("GET", "/markets")
+            .match_query(Matcher::Exact("condition_ids=0xa&limit=1".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[{"conditionId": "0xa", "slug": "a", "active": true, "closed": false,
+                    "negRisk": true, "negRiskMarketID": "0xgroup",
+                    "events": [{"id": "903", "slug": "winner"}]}]"#,
+            )
+            .create_async()
+            .await;
+        let event = server
+            .mock("GET", "/events/903")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"id": "903", "slug": "winner", "markets": [
+                    {"conditionId": "0xc"}, {"conditionId": "0xa"}, {"conditionId": "0xb"}]}"#,
+            )
+            .create_async()
+            .await;
+        let siblings = server
+            .mock("GET", "/markets")
+            .match_query(Matcher::Exact(
+                "condition_ids=0xc&condition_ids=0xb&limit=2".into(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[{"conditionId": "0xb", "slug": "b", "active": true, "closed": false,
+                     "negRisk": true, "negRiskMarketID": "0xgroup"},
+                    {"conditionId": "0xc", "slug": "c", "active": true, "closed": false,
+                     "negRisk": true, "negRiskMarketID": "0xgroup"}]"#,
+            )
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/markets")
+            .match_query(Matcher::Exact("condition_ids=0xplain&limit=1".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[{"conditionId": "0xplain", "slug": "p", "active": true, "closed": false}]"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let group = client.get_neg_risk_siblings("0xa").await.unwrap();
+        let condition_ids: Vec<&str> = group.iter().map(|m| m.condition_id.as_str()).collect();
+        assert_eq!(condition_ids, vec!["0xa", "0xc", "0xb"]);
+        assert!(group.iter().all(|m| m.is_neg_risk()));
+        assert_eq!(group[0].complement_condition_id(&group), None);
+
+        let err = client.get_neg_risk_siblings("0xplain").await.unwrap_err();
+        assert!(matches!(err, PolyError::Validation { .. }));
+
+        lookup.assert_async().await;
+        event.assert_async().await;
+        siblings.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_markets_sends_volume_bounds() {
+        let mut server = MockExchange::empty().await;
+        let mock = server
+            .mock("GET", "/markets")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("volumeNumMin".into(), "10000".into()),
+                Matcher::UrlEncoded("volumeNumMax".into(), "500000".into()),
+                Matcher::UrlEncoded("volume24hMin".into(), "250.5".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[{"conditionId": "0xvol", "slug": "busy", "active": true, "closed": false,
+                    "volumeNum": 42000, "volume24hr": 1200.25}]"#,
+            )
+            .create_async()
+            .await;
+
+        let params = crate::types::GammaListParams {
+            volume_num_min: Some(Decimal::from(10_000)),
+            volume_num_max: Some(Decimal::from(500_000)),
+            volume_24h_min: Some(Decimal::from_str("250.5").unwrap()),
+            ..Default::default()
+        };
+
+        let client = create_test_client(&server.url());
+        let markets = client.get_markets(None, Some(&params)).await.unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(markets.data.len(), 1);
+        assert_eq!(markets.data[0].condition_id, "0xvol");
+    }
+
+    #[tokio::test]
+    async fn test_search_markets_sends_sanitized_query_and_filters() {
+        let mut server = MockExchange::empty().await;
+        let search = server
+            .mock("GET", "/markets/search")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("q".into(), "trump & \"biden\"".into()),
+                Matcher::UrlEncoded("limit".into(), "2".into()),
+                Matcher::UrlEncoded("offset".into(), "4".into()),
+                Matcher::UrlEncoded("closed".into(), "false".into()),
+            ]))
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"total": 9, "data": [
+                    {"conditionId": "0xbest", "slug": "best", "active": true, "closed": false},
+                    {"conditionId": "0xnext", "slug": "next", "active": true, "closed": false}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let params = crate::types::GammaListParams {
+            limit: Some(2),
+            offset: Some(4),
+            closed: Some(false),
+            ..Default::default()
+        };
+        let page = client
+            .search_markets("  trump \t&  \"biden\" ", Some(&params))
+            .await
+            .unwrap();
+        let ids: Vec<&str> = page.data.iter().map(|m| m.condition_id.as_str()).collect();
+        assert_eq!(ids, ["0xbest", "0xnext"]);
+        assert_eq!(page.next_cursor.as_deref(), Some("6"));
+        assert_eq!(page.total, 9);
+        search.assert_async().await;
+
+        assert!(matches!(
+            client.search_markets(" \n ", None).await,
+            Err(PolyError::Validation { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_search_markets_stream_follows_pages() {
+        use futures::StreamExt;
+
+        let mut server = MockExchange::empty().await;
+        let full_page: Vec<Value> = (0..GAMMA_MARKETS_LIMIT)
+            .map(|i| serde_json::json!({"conditionId": format!("0x{}", i), "slug": "m", "active": true, "closed": false}))
+            .collect();
+        let first = server
+            .mock("GET", "/markets/search")
+            .match_query(Matcher::UrlEncoded("offset".into(), "0".into()))
+            .with_header("content-type", "application/json")
+            .with_body(Value::from(full_page).to_string())
+            .create_async()
+            .await;
+        let last = server
+            .mock("GET", "/markets/search")
+            .match_query(Matcher::UrlEncoded("offset".into(), "50".into()))
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[{"conditionId": "0xlast", "slug": "m", "active": true, "closed": false}]"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let markets: Vec<_> = client
+            .search_markets_stream("election")
+            .map(|market| market.unwrap().condition_id)
+            .collect()
+            .await;
+        assert_eq!(markets.len(), 51);
+        assert_eq!(markets[0], "0x0");
+        assert_eq!(markets[50], "0xlast");
+        first.assert_async().await;
+        last.assert_async().await;
+
+        let failed: Vec<_> = client.search_markets_stream("").collect().await;
+        assert!(matches!(failed[..], [Err(PolyError::Validation { .. })]));
+    }
+
+    #[tokio::test]
+    async fn test_get_markets_lossy_skips_malformed_records() {
+        let mut server = MockExchange::empty().await;
+        let mock_response = r#"[
+            {"conditionId": "0xgood1", "slug": "good-1", "active": true, "closed": false},
+            {"conditionId": "0xbad", "slug": "bad", "active": "yes", "closed": false},
+            {"conditionId": "0xgood2", "slug": "good-2", "active": true, "closed": false}
+        ]"#;
+
+        let mock = server
+            .mock("GET", "/markets")
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(mock_response)
+            .expect(3)
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let params = crate::types::GammaListParams {
+            limit: Some(3),
+            ..Default::default()
+        };
+
+        assert!(client.get_markets(None, Some(&params)).await.is_err());
+
+        let (markets, skipped) = client.get_markets_lossy(None, Some(&params)).await.unwrap();
+
+        let ids: Vec<&str> = markets
+            .data
+            .iter()
+            .map(|market| market.condition_id.as_str())
+            .collect();
+        assert_eq!(ids, ["0xgood1", "0xgood2"]);
+        assert_eq!(markets.count, Decimal::from(2));
+        // The page was full, so pagination continues past the bad record
+        assert_eq!(
+            markets.next_cursor.as_deref(),
+            Some(ClobClient::encode_cursor(3).as_str())
+        );
+        assert!(markets.has_more());
+        assert_eq!(markets.offset, Some(0));
+
+        // A short page is the last one
+        let params = crate::types::GammaListParams {
+            limit: Some(4),
+            ..params
+        };
+        let (partial, _) = client.get_markets_lossy(None, Some(&params)).await.unwrap();
+        assert!(!partial.has_more());
+        mock.assert_async().await;
+
+        assert_eq!(skipped.len(), 1);
+        let (index, error, raw) = &skipped[0];
+        assert_eq!(*index, 1);
+        assert!(error.to_string().contains("expected a boolean"));
+        assert_eq!(raw["conditionId"], "0xbad");
+    }
+
+    #[tokio::test]
+    async fn test_get_order_book_success() {
+        let mut server = MockExchange::empty().await;
+        let mock_response = r#"{
+            "market": "0x123",
+            "asset_id": "0x123",
+            "hash": "0xabc123",
+            "timestamp": "1234567890",
+            "bids": [
+                {"price": "0.75", "size": "100.0"}
+            ],
+            "asks": [
+                {"price": "0.76", "size": "50.0"}
+            ]
+        }"#;
+
+        let mock = server
+            .mock("GET", "/book")
+            .match_query(Matcher::UrlEncoded("token_id".into(), "0x123".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(mock_response)
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let result = client.get_order_book("0x123", None).await;
+
+        mock.assert_async().await;
+        assert!(result.is_ok());
+        let book = result.unwrap();
+        assert_eq!(book.market, "0x123");
+        assert_eq!(book.bids.len(), 1);
+        assert_eq!(book.asks.len(), 1);
+    }
+
+    #[test]
+    fn test_order_args_validate_uses_market_constraints() {
+        let market: crate::types::Market = crate::types::GammaMarket {
+            order_min_size: Some(Decimal::from(15)),
+            order_tick_size: Some(Decimal::from_str("0.001").unwrap()),
+            ..serde_json::from_value(serde_json::json!({
+                "conditionId": "0xabc",
+                "slug": "constraints",
+                "active": true,
+                "closed": false,
+                "clobTokenIds": "[\"1\", \"2\"]"
+            }))
+            .unwrap()
+        }
+        .into();
+        let order = |token: &str, price: &str, size: u32| {
+            OrderArgs::new(
+                token,
+                Decimal::from_str(price).unwrap(),
+                Decimal::from(size),
+                Side::BUY,
+            )
+        };
+
+        assert!(order("1", "0.505", 15).validate(&market).is_ok());
+        assert!(matches!(
+            order("3", "0.5", 15).validate(&market),
+            Err(PolyError::Validation { .. })
+        ));
+        let kind = |result: crate::errors::Result<()>| match result {
+            Err(PolyError::Order { kind, .. }) => kind,
+            other => panic!("expected an order error, got {:?}", other),
+        };
+        use crate::errors::OrderErrorKind;
+        assert_eq!(
+            kind(order("1", "0.9995", 15).validate(&market)),
+            OrderErrorKind::InvalidPrice
+        );
+        assert_eq!(
+            kind(order("1", "0.5055", 15).validate(&market)),
+            OrderErrorKind::PriceConstraint
+        );
+        assert_eq!(
+            kind(order("2", "0.5", 14).validate(&market)),
+            OrderErrorKind::SizeConstraint
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_market_categories_and_filter() {
+        let mut server = MockExchange::empty().await;
+        let categories_mock = server
+            .mock("GET", "/categories")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[{"id": "7", "label": "Sports", "slug": "sports", "icon": "https://x/s.png",
+                     "marketCount": 412},
+                    {"id": 9, "name": "Crypto", "slug": "crypto"}]"#,
+            )
+            .create_async()
+            .await;
+        let markets = server
+            .mock("GET", "/markets")
+            .match_query(Matcher::UrlEncoded("category_id".into(), "7".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[{"conditionId": "0xa", "slug": "a", "active": true, "closed": false,
+                     "categoryId": "7", "category": "Sports"},
+                    {"conditionId": "0xb", "slug": "b", "active": true, "closed": false,
+                     "category": "crypto"}]"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let categories = client.get_market_categories().await.unwrap();
+        let params = crate::types::GammaListParams {
+            category_id: Some(categories[0].id),
+            ..Default::default()
+        };
+        let page = client.get_markets(None, Some(&params)).await.unwrap();
+
+        categories_mock.assert_async().await;
+        markets.assert_async().await;
+        assert_eq!(categories[0].market_count, 412);
+        assert_eq!(categories[1].icon_url, None);
+        assert_eq!(
+            serde_json::to_value(&categories[0]).unwrap(),
+            serde_json::json!({"id": 7, "name": "Sports", "slug": "sports",
+                "icon_url": "https://x/s.png", "market_count": 412})
+        );
+
+        let [sports_market, crypto_market] = &page.data[..] else {
+            panic!("expected two markets");
+        };
+        assert_eq!(sports_market.category_id, Some(7));
+        assert!(sports_market.matches_category(&categories[0]));
+        assert!(!sports_market.matches_category(&categories[1]));
+        // No ID on the market, so the name/slug decides
+        assert!(crypto_market.matches_category(&categories[1]));
+    }
+
+    #[tokio::test]
+    async fn test_clob_and_gamma_requests_use_their_own_hosts() {
+        let mut clob = MockExchange::empty().await;
+        let mut gamma = MockExchange::empty().await;
+        let book = clob
+            .mock("GET", "/book")
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"market": "0x1", "asset_id": "1", "hash": "0x0", "timestamp": "1",
+                    "bids": [], "asks": []}"#,
+            )
+            .create_async()
+            .await;
+        let markets = gamma
+            .mock("GET", "/markets")
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body("[]")
+            .create_async()
+            .await;
+        let stray_clob = clob
+            .mock("GET", "/markets")
+            .match_query(Matcher::Any)
+            .expect(0)
+            .create_async()
+            .await;
+        let stray_gamma = gamma
+            .mock("GET", "/book")
+            .match_query(Matcher::Any)
+            .expect(0)
+            .create_async()
+            .await;
+
+        let client = ClobClient::default()
+            .with_clob_base(&clob.url())
+            .with_gamma_base(&gamma.url());
+        assert_eq!(client.clob_base_url(), clob.url());
+        client.get_order_book("1", None).await.unwrap();
+        client.get_markets(None, None).await.unwrap();
+
+        book.assert_async().await;
+        markets.assert_async().await;
+        stray_clob.assert_async().await;
+        stray_gamma.assert_async().await;
+
+        let defaults = ClobClient::default();
+        assert_eq!(defaults.clob_base_url(), "https://clob.polymarket.com");
+        assert_eq!(
+            defaults.gamma_base_url(),
+            "https://gamma-api.polymarket.com"
+        );
+    }
+
+    #[test]
+    fn test_sse_decoder_handles_split_chunks() {
+        let mut decoder = SseDecoder::default();
+        assert!(decoder.feed(b": keep-alive\n\ndata: {\"a\":").is_empty());
+        assert_eq!(
+            decoder.feed(b"1}\r\n\r\nevent: x\ndata: [1,\ndata: 2]\n\n"),
+            vec!["{\"a\":1}".to_string(), "[1,\n2]".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_orderbook_updates_streams_events() {
+        use futures::StreamExt;
+
+        let mut server = MockExchange::empty().await;
+        let body = concat!(
+            ": connected\n\n",
+            r#"data: {"event_type": "book", "asset_id": "1", "market": "0xabc", "timestamp": "1700000000000", "hash": "0x01", "bids": [{"price": "0.48", "size": "30"}], "asks": []}"#,
+            "\n\n",
+            r#"data: [{"event_type": "last_trade_price", "asset_id": "2", "market": "0xabc", "price": "0.5", "side": "BUY", "size": "10", "fee_rate_bps": "0", "timestamp": "1700000000001"}]"#,
+            "\n\n",
+            "data: not json\n\n",
+        );
+        let mock = server
+            .mock("GET", "/sse/orderbook")
+            .match_query(Matcher::UrlEncoded("token_ids".into(), "1,2".into()))
+            .match_header("accept", "text/event-stream")
+            .with_status(200)
+            .with_header("content-type", "text/event-stream")
+            .with_body(body)
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let events: Vec<_> = client
+            .subscribe_orderbook_updates(&["1", "2"])
+            .await
+            .unwrap()
+            .collect()
+            .await;
+
+        mock.assert_async().await;
+        assert_eq!(events.len(), 3);
+        match &events[0] {
+            Ok(crate::wss::WssMarketEvent::Book(book)) => {
+                assert_eq!(book.asset_id, "1");
+                assert!(book.received_at.is_some());
+            }
+            other => panic!("expected book, got {:?}", other),
+        }
+        assert!(matches!(
+            events[1],
+            Ok(crate::wss::WssMarketEvent::LastTrade(_))
+        ));
+        assert!(events[2].is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_order_book_with_depth_marks_truncation() {
+        let mut server = MockExchange::empty().await;
+        let mock_response = r#"{
+            "market": "0x123",
+            "asset_id": "0x123",
+            "hash": "0xabc123",
+            "timestamp": "1234567890",
+            "bids": [
+                {"price": "0.70", "size": "10.0"},
+                {"price": "0.71", "size": "20.0"},
+                {"price": "0.72", "size": "30.0"},
+                {"price": "0.73", "size": "40.0"}
+            ],
+            "asks": [
+                {"price": "0.76", "size": "15.0"},
+                {"price": "0.75", "size": "25.0"}
+            ]
+        }"#;
+
+        let mock = server
+            .mock("GET", "/book")
+            .match_query(Matcher::UrlEncoded("token_id".into(), "0x123".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(mock_response)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let book = client.get_order_book("0x123", Some(2)).await.unwrap();
+        assert!(book.truncated);
+        let bid_prices: Vec<Decimal> = book.bids.iter().map(|level| level.price).collect();
+        assert_eq!(
+            bid_prices,
+            vec![
+                Decimal::from_str("0.73").unwrap(),
+                Decimal::from_str("0.72").unwrap()
+            ]
+        );
+        assert_eq!(book.asks[0].price, Decimal::from_str("0.75").unwrap());
+
+        let full = client.get_order_book("0x123", None).await.unwrap();
+        assert!(!full.truncated);
+        assert_eq!(full.bids.len(), 4);
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_midpoint_success() {
+        let mut server = MockExchange::empty().await;
+        let mock_response = r#"{
+            "mid": "0.755"
+        }"#;
+
+        let mock = server
+            .mock("GET", "/midpoint")
+            .match_query(Matcher::UrlEncoded("token_id".into(), "0x123".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(mock_response)
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let result = client.get_midpoint("0x123").await;
+
+        mock.assert_async().await;
+        assert!(result.is_ok());
+        let response = result.unwrap();
+        assert_eq!(response.mid, Decimal::from_str("0.755").unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_number_mode_controls_decimal_parsing() {
+        let mut server = MockExchange::empty().await;
+        let mock = server
+            .mock("GET", "/midpoint")
+            .match_query(Matcher::UrlEncoded("token_id".into(), "0x123".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"mid": 0.755}"#)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let lenient = create_test_client(&server.url());
+        let mid = lenient.get_midpoint("0x123").await.unwrap().mid;
+        assert_eq!(mid, Decimal::from_str("0.755").unwrap());
+
+        let strict = create_test_client(&server.url()).with_number_mode(NumberMode::Strict);
+        let err = strict.get_midpoint("0x123").await.unwrap_err();
+        assert!(matches!(err, PolyError::Parse { .. }));
+
+        mock.assert_async().await;
+
+        // Batch endpoints honor the mode too, map-shaped ones included
+        let books = server
+            .mock("POST", "/books")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[{"market": "0xabc", "asset_id": "1", "hash": "", "timestamp": "1",
+                     "bids": [{"price": 0.48, "size": "30"}], "asks": []}]"#,
+            )
+            .expect(2)
+            .create_async()
+            .await;
+        let midpoints = server
+            .mock("POST", "/midpoints")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"1": 0.5}"#)
+            .expect(2)
+            .create_async()
+            .await;
+        let ids = ["1".to_string()];
+        let book = &lenient.get_order_books(&ids).await.unwrap()[0];
+        assert_eq!(book.bids[0].price, Decimal::from_str("0.48").unwrap());
+        assert_eq!(
+            lenient.get_midpoints(&ids).await.unwrap()["1"],
+            Decimal::from_str("0.5").unwrap()
+        );
+        assert!(matches!(
+            strict.get_order_books(&ids).await,
+            Err(PolyError::Parse { .. })
+        ));
+        assert!(matches!(
+            strict.get_midpoints(&ids).await,
+            Err(PolyError::Parse { .. })
+        ));
+        books.assert_async().await;
+        midpoints.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_spread_success() {
+        let mut server = MockExchange::empty().await;
+        let mock_response = r#"{
+            "spread": "0.01"
+        }"#;
+
+        let mock = server
+            .mock("GET", "/spread")
+            .match_query(Matcher::UrlEncoded("token_id".into(), "0x123".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(mock_response)
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let result = client.get_spread("0x123").await;
+
+        mock.assert_async().await;
+        assert!(result.is_ok());
+        let response = result.unwrap();
+        assert_eq!(response.spread, Decimal::from_str("0.01").unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_get_market_volatility_from_history() {
+        use crate::clock::MockClock;
+
+        let mut server = MockExchange::empty().await;
+        let mock = server
+            .mock("GET", "/prices-history")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("market".into(), "0x123".into()),
+                Matcher::UrlEncoded("startTs".into(), "1699992800".into()),
+                Matcher::UrlEncoded("endTs".into(), "1700000000".into()),
+                Matcher::UrlEncoded("fidelity".into(), "1".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"history":[
+                    {"t":1699999820,"p":0.5},
+                    {"t":1699999880,"p":0.55},
+                    {"t":1699999940,"p":0.5},
+                    {"t":1700000000,"p":0.55}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+
+        let client =
+            create_test_client(&server.url()).with_clock(Arc::new(MockClock::new(1_700_000_000)));
+        let metrics = client.get_market_volatility("0x123", 2).await.unwrap();
+        mock.assert_async().await;
+        assert_eq!(metrics.lookback_hours, 2);
+        assert_eq!(metrics.num_observations, 3);
+        assert!(metrics.mean_return > Decimal::ZERO);
+        assert!(metrics.is_high_vol());
+
+        let err = client.get_market_volatility("0x123", 0).await.unwrap_err();
+        assert!(matches!(err, PolyError::Validation { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_get_market_correlation_fetches_daily_history() {
+        use crate::clock::MockClock;
+
+        let mut server = MockExchange::empty().await;
+        let mut mocks = Vec::new();
+        for (token, prices) in [("1", [0.4, 0.5, 0.4]), ("2", [0.6, 0.5, 0.6])] {
+            let history: Vec<Value> = prices
+                .iter()
+                .enumerate()
+                .map(|(day, price)| serde_json::json!({"t": 1_699_776_000 + 86_400 * day, "p": price}))
+                .collect();
+            mocks.push(
+                server
+                    .mock("GET", "/prices-history")
+                    .match_query(Matcher::AllOf(vec![
+                        Matcher::UrlEncoded("market".into(), token.into()),
+                        Matcher::UrlEncoded("startTs".into(), "1699740800".into()),
+                        Matcher::UrlEncoded("fidelity".into(), "1440".into()),
+                    ]))
+                    .with_header("content-type", "application/json")
+                    .with_body(serde_json::json!({ "history": history }).to_string())
+                    .create_async()
+                    .await,
+            );
+        }
+
+        let client =
+            create_test_client(&server.url()).with_clock(Arc::new(MockClock::new(1_700_000_000)));
+        let matrix = client.get_market_correlation(&["1", "2"], 3).await.unwrap();
+        assert_eq!(matrix.token_ids, ["1", "2"]);
+        assert_eq!(matrix.get("1", "2"), Some(Decimal::NEGATIVE_ONE));
+        for mock in mocks {
+            mock.assert_async().await;
+        }
+
+        assert!(matches!(
+            client.get_market_correlation(&["1"], 3).await,
+            Err(PolyError::Validation { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_market_microstructure_joins_trades_and_history() {
+        use crate::clock::MockClock;
+
+        let mut server = MockExchange::empty().await;
+        server
+            .mock("GET", "/markets")
+            .match_query(Matcher::Exact("clob_token_ids=111&limit=1".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"[{"conditionId": "0xc", "slug": "c", "active": true, "closed": false}]"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/prices-history")
+            .match_query(Matcher::UrlEncoded("market".into(), "111".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"history":[
+                    {"t":1699999000,"p":0.50},
+                    {"t":1699999300,"p":0.52},
+                    {"t":1699999600,"p":0.52}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+        let trades = server
+            .mock("GET", "/trades")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("market".into(), "0xc".into()),
+                Matcher::UrlEncoded("limit".into(), "500".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[
+                    {"side": "BUY", "asset": "111", "conditionId": "0xc", "size": 10,
+                     "price": 0.51, "timestamp": 1699999100},
+                    {"side": "SELL", "asset": "222", "conditionId": "0xc", "size": 10,
+                     "price": 0.49, "timestamp": 1699999100},
+                    {"side": "BUY", "asset": "111", "conditionId": "0xc", "size": 10,
+                     "price": 0.51, "timestamp": 1699990000}
+                ]"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url())
+            .with_data_api_base(&server.url())
+            .with_clock(Arc::new(MockClock::new(1_700_000_000)));
+        let metrics = client.get_market_microstructure("111", 1).await.unwrap();
+        trades.assert_async().await;
+        // The other token's fill and the one before the window are dropped
+        assert_eq!(metrics.num_trades, 1);
+        assert_eq!(metrics.effective_spread, Decimal::from_str("0.02").unwrap());
+        assert_eq!(metrics.realized_spread, Decimal::from_str("-0.02").unwrap());
+
+        let err = client
+            .get_market_microstructure("111", 0)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, PolyError::Validation { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_order_flow_statistics_diffs_open_interest() {
+        use crate::clock::MockClock;
+
+        let mut server = MockExchange::empty().await;
+        server
+            .mock("GET", "/markets")
+            .match_query(Matcher::Exact("clob_token_ids=111&limit=1".into()))
+            .with_header("content-type", "application/json")
+            .with_body(r#"[{"conditionId": "0xc", "slug": "c", "active": true, "closed": false}]"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/trades")
+            .match_query(Matcher::UrlEncoded("market".into(), "0xc".into()))
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[
+                    {"side": "BUY", "asset": "111", "conditionId": "0xc", "size": 30,
+                     "price": 0.51, "timestamp": 1699999900},
+                    {"side": "SELL", "asset": "111", "conditionId": "0xc", "size": 10,
+                     "price": 0.50, "timestamp": 1699999950},
+                    {"side": "SELL", "asset": "111", "conditionId": "0xc", "size": 99,
+                     "price": 0.50, "timestamp": 1699990000},
+                    {"side": "SELL", "asset": "222", "conditionId": "0xc", "size": 99,
+                     "price": 0.49, "timestamp": 1699999950}
+                ]"#,
+            )
+            .create_async()
+            .await;
+        let open_interest = |total: u32| {
+            format!(
+                r#"{{"token_id": "111", "bid_open_interest": "{total}",
+                    "ask_open_interest": "0", "total_open_interest": "{total}"}}"#
+            )
+        };
+        let first = server
+            .mock("GET", "/open-interest")
+            .match_query(Matcher::UrlEncoded("token_id".into(), "111".into()))
+            .with_body(open_interest(2000))
+            .create_async()
+            .await;
+
+        let clock = Arc::new(MockClock::new(1_700_000_000));
+        let client = create_test_client(&server.url())
+            .with_data_api_base(&server.url())
+            .with_clock(clock.clone());
+        let stats = client.get_order_flow_statistics("111", 1).await.unwrap();
+        assert_eq!(stats.taker_buy_volume, Decimal::from(30));
+        assert_eq!(stats.taker_sell_volume, Decimal::from(10));
+        assert_eq!(stats.net_taker_flow, Decimal::from(20));
+        assert_eq!(stats.maker_add_volume, Decimal::ZERO);
+        assert_eq!(stats.maker_cancel_volume, Decimal::ZERO);
+        assert_eq!(stats.vpin_score(), Decimal::from_str("0.98").unwrap());
+        assert!(stats.is_informed_trading_detected());
+
+        // No fills since the first snapshot, so the 10 shares that left the book were
+        // cancelled
+        first.remove_async().await;
+        server
+            .mock("GET", "/open-interest")
+            .match_query(Matcher::UrlEncoded("token_id".into(), "111".into()))
+            .with_body(open_interest(1990))
+            .create_async()
+            .await;
+        clock.advance(600);
+        let stats = client.get_order_flow_statistics("111", 1).await.unwrap();
+        assert_eq!(stats.maker_add_volume, Decimal::ZERO);
+        assert_eq!(stats.maker_cancel_volume, Decimal::from(10));
+
+        assert!(matches!(
+            client.get_order_flow_statistics("111", 0).await,
+            Err(PolyError::Validation { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_market_history_joins_prices_and_trades() {
+        use crate::clock::MockClock;
+        use crate::microstructure::PriceResolution;
+
+        let mut server = MockExchange::empty().await;
+        server
+            .mock("GET", "/markets")
+            .match_query(Matcher::Exact("clob_token_ids=111&limit=1".into()))
+            .with_header("content-type", "application/json")
+            .with_body(r#"[{"conditionId": "0xc", "slug": "c", "active": true, "closed": false}]"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/markets")
+            .match_query(Matcher::Exact("clob_token_ids=333&limit=1".into()))
+            .with_header("content-type", "application/json")
+            .with_body("[]")
+            .create_async()
+            .await;
+        let history = server
+            .mock("GET", "/prices-history")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("market".into(), "111".into()),
+                Matcher::UrlEncoded("startTs".into(), "1699995600".into()),
+                Matcher::UrlEncoded("fidelity".into(), "60".into()),
+            ]))
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"history":[{"t":1699996000,"p":0.50},{"t":1699999300,"p":0.52}]}"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/prices-history")
+            .match_query(Matcher::UrlEncoded("market".into(), "333".into()))
+            .with_status(500)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/trades")
+            .match_query(Matcher::UrlEncoded("market".into(), "0xc".into()))
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[
+                    {"side": "BUY", "asset": "111", "conditionId": "0xc", "size": 10,
+                     "price": 0.51, "timestamp": 1699996100},
+                    {"side": "SELL", "asset": "222", "conditionId": "0xc", "size": 10,
+                     "price": 0.49, "timestamp": 1699996100}
+                ]"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url())
+            .with_data_api_base(&server.url())
+            .with_clock(Arc::new(MockClock::new(1_700_000_000)));
+        let market = client
+            .get_market_history("111", PriceResolution::Hourly, 2)
+            .await
+            .unwrap();
+        history.assert_async().await;
+        assert_eq!(market.resolution, PriceResolution::Hourly);
+        let candles: Vec<_> = market
+            .candles
+            .iter()
+            .map(|c| {
+                (
+                    c.timestamp,
+                    c.close.to_string(),
+                    c.trade_count,
+                    c.volume.to_string(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            candles,
+            [
+                (1_699_995_600, "0.5".to_string(), 1, "5.10".to_string()),
+                (1_699_999_200, "0.52".to_string(), 0, "0".to_string()),
+            ]
+        );
+
+        let err = client
+            .get_market_history("333", PriceResolution::Hourly, 2)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, PolyError::MarketData { .. }));
+        assert!(err.to_string().contains("trades failed"));
+        assert!(matches!(
+            client
+                .get_market_history("111", PriceResolution::Hourly, 0)
+                .await,
+            Err(PolyError::Validation { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_market_depth_history_replays_fills() {
+        use crate::microstructure::BookDepthInterval;
+
+        let mut server = MockExchange::empty().await;
+        server
+            .mock("GET", "/markets")
+            .match_query(Matcher::Exact("clob_token_ids=111&limit=1".into()))
+            .with_header("content-type", "application/json")
+            .with_body(r#"[{"conditionId": "0xc", "slug": "c", "active": true, "closed": false}]"#)
+            .create_async()
+            .await;
+        let history = server
+            .mock("GET", "/prices-history")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("market".into(), "111".into()),
+                Matcher::UrlEncoded("fidelity".into(), "60".into()),
+            ]))
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"history":[{"t":1699996000,"p":0.50},{"t":1699999300,"p":0.52}]}"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/trades")
+            .match_query(Matcher::UrlEncoded("market".into(), "0xc".into()))
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[
+                    {"side": "BUY", "asset": "111", "conditionId": "0xc", "size": 10,
+                     "price": 0.51, "timestamp": 1699996100},
+                    {"side": "SELL", "asset": "222", "conditionId": "0xc", "size": 10,
+                     "price": 0.49, "timestamp": 1699996100},
+                    {"side": "BUY", "asset": "111", "conditionId": "0xc", "size": 4,
+                     "price": 0.53, "timestamp": 1699999500}
+                ]"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url()).with_data_api_base(&server.url());
+        let start = 1_699_995_600;
+        let snapshots = client
+            .get_market_depth_history("111", start, start + 7200, BookDepthInterval::Hourly)
+            .await
+            .unwrap();
+        history.assert_async().await;
+        let summary: Vec<_> = snapshots
+            .iter()
+            .map(|s| {
+                (
+                    s.timestamp,
+                    s.bid_depth,
+                    s.ask_depth,
+                    s.mid_price.to_string(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (start, Decimal::ZERO, Decimal::from(10), "0.5".to_string()),
+                (
+                    start + 3600,
+                    Decimal::ZERO,
+                    Decimal::from(4),
+                    "0.52".to_string()
+                ),
+            ]
+        );
+
+        let err = client
+            .get_market_depth_history("111", start, start, BookDepthInterval::Daily)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, PolyError::Validation { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_get_price_success() {
+        let mut server = MockExchange::empty().await;
+        let mock_response = r#"{
+            "price": "0.76"
+        }"#;
+
+        let mock = server
+            .mock("GET", "/price")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("token_id".into(), "0x123".into()),
+                Matcher::UrlEncoded("side".into(), "BUY".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(mock_response)
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let result = client.get_price("0x123", Side::BUY).await;
+
+        mock.assert_async().await;
+        assert!(result.is_ok());
+        let response = result.unwrap();
+        assert_eq!(response.price, Decimal::from_str("0.76").unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_prefetched_market_meta_skips_per_token_requests() {
+        let mut server = MockExchange::empty().await;
+        let gamma = server
+            .mock("GET", "/markets")
+            .match_query(Matcher::Regex("clob_token_ids=1&clob_token_ids=3".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[
+                    {
+                        "conditionId": "0xaaa",
+                        "slug": "neg-risk-market",
+                        "active": true,
+                        "closed": false,
+                        "clobTokenIds": "[\"1\", \"2\"]",
+                        "orderPriceMinTickSize": 0.001,
+                        "orderMinSize": 5,
+                        "negRisk": true
+                    },
+                    {
+                        "conditionId": "0xbbb",
+                        "slug": "missing-flags",
+                        "active": true,
+                        "closed": false,
+                        "clobTokenIds": "[\"3\", \"4\"]"
+                    }
+                ]"#,
+            )
+            .create_async()
+            .await;
+        let tick_size = server
+            .mock("GET", "/tick-size")
+            .match_query(Matcher::UrlEncoded("token_id".into(), "3".into()))
+            .with_status(200)
+            .with_body(r#"{"minimum_tick_size": "0.01"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let neg_risk = server
+            .mock("GET", "/neg-risk")
+            .match_query(Matcher::UrlEncoded("token_id".into(), "3".into()))
+            .with_status(200)
+            .with_body(r#"{"neg_risk": false}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let cache = Arc::new(MarketMetaCache::default());
+        let client =
+            create_test_client_with_auth(&server.url()).with_market_meta_cache(cache.clone());
+
+        let loaded = client
+            .prefetch_market_meta(&["1".to_string(), "3".to_string()])
+            .await
+            .unwrap();
+        gamma.assert_async().await;
+        assert_eq!(loaded, 1);
+        let meta = cache.get("1").unwrap();
+        assert_eq!(meta.tick_size, Decimal::from_str("0.001").unwrap());
+        assert!(meta.neg_risk);
+        assert_eq!(meta.min_order_size, Some(Decimal::from(5)));
+
+        // Served from the cache: the tick size mocks only see token 3
+        let args = OrderArgs::new(
+            "1",
+            Decimal::from_str("0.505").unwrap(),
+            Decimal::from(10),
+            Side::BUY,
+        );
+        client.create_order(&args, None, None, None).await.unwrap();
+
+        // Not prefetched: falls back to per-token requests once, then caches
+        let args = OrderArgs::new(
+            "3",
+            Decimal::from_str("0.5").unwrap(),
+            Decimal::from(10),
+            Side::BUY,
+        );
+        client.create_order(&args, None, None, None).await.unwrap();
+        client.create_order(&args, None, None, None).await.unwrap();
+
+        // A caller-supplied neg-risk flag leaves only the tick size to fetch
+        let supplied_tick_size = server
+            .mock("GET", "/tick-size")
+            .match_query(Matcher::UrlEncoded("token_id".into(), "5".into()))
+            .with_status(200)
+            .with_body(r#"{"minimum_tick_size": "0.01"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let supplied_neg_risk = server
+            .mock("GET", "/neg-risk")
+            .match_query(Matcher::UrlEncoded("token_id".into(), "5".into()))
+            .expect(0)
+            .create_async()
+            .await;
+        let args = OrderArgs::new(
+            "5",
+            Decimal::from_str("0.5").unwrap(),
+            Decimal::from(10),
+            Side::BUY,
+        );
+        let options = OrderOptions {
+            tick_size: None,
+            neg_risk: Some(true),
+            fee_rate_bps: None,
+        };
+        client
+            .create_order(&args, None, None, Some(&options))
+            .await
+            .unwrap();
+
+        tick_size.assert_async().await;
+        neg_risk.assert_async().await;
+        supplied_tick_size.assert_async().await;
+        supplied_neg_risk.assert_async().await;
+        // Hits: the direct lookup above, token 1, and the second order for token 3
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (3, 2, 2));
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_market_meta_requires_cache() {
+        let client = create_test_client("https://test.example.com");
+        assert!(
+            client
+                .prefetch_market_meta(&["1".to_string()])
+                .await
+                .is_err()
+        );
+    }
+
+    const PROBABILITY_MARKET: &str = r#"[
+        {
+            "conditionId": "0xprob",
+            "slug": "probability-market",
+            "active": true,
+            "closed": false,
+            "clobTokenIds": "[\"101\", \"102\"]",
+            "outcomes": "[\"Yes\", \"No\"]",
+            "orderPriceMinTickSize": 0.01
+        }
+    ]"#;
+
+    #[test]
+    fn test_order_args_probability_prices_each_outcome() {
+        let mut gamma: Vec<crate::types::GammaMarket> =
+            serde_json::from_str(PROBABILITY_MARKET).unwrap();
+        let market = crate::types::Market::from(gamma.remove(0));
+        let probability = Decimal::from_str("0.653").unwrap();
+
+        let yes = OrderArgs::new("101", Decimal::ZERO, Decimal::from(10), Side::BUY)
+            .probability(probability, &market)
+            .unwrap();
+        assert_eq!(yes.price, Decimal::from_str("0.65").unwrap());
+
+        let no = OrderArgs::new("102", Decimal::ZERO, Decimal::from(10), Side::BUY)
+            .probability(probability, &market)
+            .unwrap();
+        assert_eq!(no.price, Decimal::from_str("0.35").unwrap());
+
+        let unknown = OrderArgs::new("999", Decimal::ZERO, Decimal::from(10), Side::BUY);
+        assert!(unknown.probability(probability, &market).is_err());
+        let yes = OrderArgs::new("101", Decimal::ZERO, Decimal::from(10), Side::BUY);
+        assert!(yes.clone().probability(Decimal::ONE, &market).is_err());
+        assert!(yes.probability(Decimal::ZERO, &market).is_err());
+    }
+
+    #[test]
+    fn test_order_args_notional_and_max_loss() {
+        let order = |price: &str, size: u32, side| {
+            OrderArgs::new(
+                "1",
+                Decimal::from_str(price).unwrap(),
+                Decimal::from(size),
+                side,
+            )
+        };
+        let buy = order("0.3", 100, Side::BUY);
+        assert_eq!(buy.notional(), Decimal::from(30));
+        assert_eq!(buy.max_loss(), Decimal::from(30));
+
+        let sell = order("0.3", 100, Side::SELL);
+        assert_eq!(sell.notional(), Decimal::from(30));
+        assert_eq!(sell.max_loss(), Decimal::from(70));
+
+        let long_shot = order("0.02", 50, Side::SELL);
+        assert_eq!(long_shot.notional(), Decimal::ONE);
+        assert_eq!(long_shot.max_loss(), Decimal::from(49));
+    }
+
+    #[tokio::test]
+    async fn test_max_order_notional_rejects_locally() {
+        let mut server = MockExchange::empty().await;
+        let post = server.mock("POST", "/order").expect(0).create_async().await;
+
+        let cache = Arc::new(MarketMetaCache::default());
+        cache.insert(
+            "102",
+            MarketMeta {
+                tick_size: Decimal::from_str("0.01").unwrap(),
+                neg_risk: false,
+                min_order_size: None,
+            },
+        );
+        let client = create_test_client_with_l2(&server.url())
+            .with_market_meta_cache(cache)
+            .with_max_order_notional(Decimal::from(5));
+        let order = |price: &str, size: u32, side| {
+            OrderArgs::new(
+                "102",
+                Decimal::from_str(price).unwrap(),
+                Decimal::from(size),
+                side,
+            )
+        };
+
+        assert!(
+            client
+                .post_order_dry_run(&order("0.3", 10, Side::BUY))
+                .await
+                .is_ok()
+        );
+        // A sell is measured by the collateral it receives, not its max loss
+        assert!(
+            client
+                .post_order_dry_run(&order("0.2", 20, Side::SELL))
+                .await
+                .is_ok()
+        );
+
+        let oversized = client
+            .create_order(&order("0.6", 10, Side::BUY), None, None, None)
+            .await
+            .unwrap();
+        let err = client
+            .post_order(oversized, OrderType::GTC)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            PolyError::Order {
+                kind: crate::errors::OrderErrorKind::SizeConstraint,
+                ..
+            }
+        ));
+        assert!(err.to_string().contains("exceeds the limit of 5"));
+        post.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_place_order_with_retry_recovers_lost_response() {
+        let order_path = Matcher::Regex(r"^/data/order/0x[0-9a-f]{64}$".to_string());
+        let echo_order = |status: &'static str| {
+            move |request: &mockito::Request| {
+                let id = request.path().trim_start_matches("/data/order/");
+                open_order_json(id, status, "10").into_bytes()
+            }
+        };
+        let cache = Arc::new(MarketMetaCache::default());
+        cache.insert(
+            "102",
+            MarketMeta {
+                tick_size: Decimal::from_str("0.01").unwrap(),
+                neg_risk: false,
+                min_order_size: None,
+            },
+        );
+        let order = crate::types::OrderRequest {
+            token_id: "102".to_string(),
+            side: Side::BUY,
+            price: Decimal::from_str("0.3").unwrap(),
+            size: Decimal::from(10),
+            order_type: OrderType::GTC,
+            expiration: None,
+            client_id: None,
+        };
+        let policy = crate::types::RetryPolicy {
+            max_attempts: 3,
+            backoff: std::time::Duration::from_millis(1),
+            confirmation_timeout: std::time::Duration::from_millis(20),
+            poll_interval: std::time::Duration::from_millis(5),
+        };
+
+        // The first post lands but its response is lost; the repeat is a duplicate
+        let mut server = MockExchange::empty().await;
+        let lost = server
+            .mock("POST", "/order")
+            .with_status(503)
+            .expect(1)
+            .create_async()
+            .await;
+        let duplicate = server
+            .mock("POST", "/order")
+            .with_status(400)
+            .with_body(r#"{"error": "Duplicated order"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        server
+            .mock("GET", order_path.clone())
+            .with_body_from_request(echo_order("LIVE"))
+            .create_async()
+            .await;
+        let client =
+            create_test_client_with_l2(&server.url()).with_market_meta_cache(cache.clone());
+        let response = client
+            .place_order_with_retry(&order, &policy)
+            .await
+            .unwrap();
+        lost.assert_async().await;
+        duplicate.assert_async().await;
+        assert_eq!(response["success"], true);
+        assert_eq!(response["status"], "LIVE");
+        assert_eq!(response["orderID"].as_str().unwrap().len(), 66);
+
+        // An order that filled before the lookup is confirmed, not re-posted
+        let mut server = MockExchange::empty().await;
+        let posted = server
+            .mock("POST", "/order")
+            .with_body(r#"{"success":true,"orderID":"0xfilled","status":"live"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/data/order/0xfilled")
+            .with_body(open_order_json("0xfilled", "MATCHED", "10"))
+            .create_async()
+            .await;
+        let client =
+            create_test_client_with_l2(&server.url()).with_market_meta_cache(cache.clone());
+        let response = client
+            .place_order_with_retry(&order, &policy)
+            .await
+            .unwrap();
+        assert_eq!(response["orderID"], "0xfilled");
+        posted.assert_async().await;
+
+        // Out of attempts while the exchange still doesn't know the order
+        let policy = crate::types::RetryPolicy {
+            max_attempts: 1,
+            ..policy
+        };
+        let mut server = MockExchange::empty().await;
+        server
+            .mock("POST", "/order")
+            .with_body(r#"{"success":true,"orderID":"0xlost","status":"live"}"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/data/order/0xlost")
+            .with_status(404)
+            .create_async()
+            .await;
+        let client = create_test_client_with_l2(&server.url()).with_market_meta_cache(cache);
+        let err = client
+            .place_order_with_retry(&order, &policy)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, PolyError::Validation { .. }));
+        assert!(err.to_string().contains("order lost after submission"));
+    }
+
+    #[tokio::test]
+    async fn test_post_order_dry_run_matches_fixture() {
+        use crate::auth::build_hmac_signature;
+        use crate::clock::MockClock;
+
+        let mut server = MockExchange::empty().await;
+        let post = server.mock("POST", "/order").expect(0).create_async().await;
+
+        let cache = Arc::new(MarketMetaCache::default());
+        cache.insert(
+            "102",
+            MarketMeta {
+                tick_size: Decimal::from_str("0.01").unwrap(),
+                neg_risk: false,
+                min_order_size: None,
+            },
+        );
+        let clock = Arc::new(MockClock::new(1_700_000_000));
+        let client = create_test_client_with_l2(&server.url())
+            .with_market_meta_cache(cache)
+            .with_clock(clock.clone());
+
+        let args = OrderArgs::new(
+            "102",
+            Decimal::from_str("0.3").unwrap(),
+            Decimal::from(10),
+            Side::BUY,
+        );
+        let preview = client.post_order_dry_run(&args).await.unwrap();
+        post.assert_async().await;
+
+        assert_eq!(preview.method, "POST");
+        assert_eq!(preview.url, format!("{}/order", server.url()));
+
+        // Salt and signature are random per order; everything else is fixed
+        let mut body: Value = serde_json::from_str(&preview.body).unwrap();
+        assert_eq!(body["order"]["salt"], preview.order.salt);
+        assert_eq!(body["order"]["signature"], preview.order.signature.as_str());
+        body["order"]["salt"] = Value::Null;
+        body["order"]["signature"] = Value::Null;
+        let expected: Value =
+            serde_json::from_str(include_str!("../tests/fixtures/http/order_preview.json"))
+                .unwrap();
+        assert_eq!(body, expected["body"]);
+
+        let mut headers = preview.headers.clone();
+        let signature = headers.remove("poly_signature").unwrap();
+        let expected_headers: std::collections::BTreeMap<String, String> =
+            serde_json::from_value(expected["headers"].clone()).unwrap();
+        assert_eq!(headers, expected_headers);
+        let post_body = PostOrder::new(
+            preview.order.clone(),
+            "test_key".to_string(),
+            OrderType::GTC,
+        );
+        assert_eq!(
+            signature,
+            build_hmac_signature(
+                "dGVzdF9zZWNyZXQ=",
+                1_700_000_000,
+                "POST",
+                "/order",
+                Some(&post_body)
+            )
+            .unwrap()
+        );
+
+        clock.advance(30);
+        let later = client.post_order_dry_run(&args).await.unwrap();
+        assert_eq!(later.headers["poly_timestamp"], "1700000030");
+    }
+
+    #[tokio::test]
+    async fn test_create_order_from_probability_prices_no_token_at_complement() {
+        let mut server = MockExchange::empty().await;
+        let gamma = server
+            .mock("GET", "/markets")
+            .match_query(Matcher::UrlEncoded("clob_token_ids".into(), "102".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(PROBABILITY_MARKET)
+            .create_async()
+            .await;
+        // A 70% Yes view means buying No at 0.30: 10 shares cost 3 USDC
+        let post = server
+            .mock("POST", "/order")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::Regex(r#""tokenId":"102""#.into()),
+                Matcher::Regex(r#""makerAmount":"3000000""#.into()),
+                Matcher::Regex(r#""takerAmount":"10000000""#.into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"success": true, "orderID": "0xprob-order"}"#)
+            .create_async()
+            .await;
+
+        let cache = Arc::new(MarketMetaCache::default());
+        cache.insert(
+            "102",
+            MarketMeta {
+                tick_size: Decimal::from_str("0.01").unwrap(),
+                neg_risk: false,
+                min_order_size: None,
+            },
+        );
+        let client = create_test_client_with_l2(&server.url()).with_market_meta_cache(cache);
+
+        let response = client
+            .create_order_from_probability(
+                "102",
+                Decimal::from_str("0.7").unwrap(),
+                Decimal::from(10),
+                Side::BUY,
+            )
+            .await
+            .unwrap();
+
+        gamma.assert_async().await;
+        post.assert_async().await;
+        assert_eq!(response["orderID"], "0xprob-order");
+
+        let err = client
+            .create_order_from_probability("102", Decimal::ONE, Decimal::from(10), Side::BUY)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, PolyError::Validation { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_max_concurrency_caps_in_flight_requests() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        const LIMIT: usize = 3;
+        const REQUESTS: usize = 12;
+
+        // Hand-rolled server so overlapping requests are actually served concurrently
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        {
+            let (in_flight, peak) = (in_flight.clone(), peak.clone());
+            tokio::spawn(async move {
+                loop {
+                    let (mut socket, _) = listener.accept().await.unwrap();
+                    let (in_flight, peak) = (in_flight.clone(), peak.clone());
+                    tokio::spawn(async move {
+                        let mut request = Vec::new();
+                        let mut buf = [0u8; 1024];
+                        while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                            let read = socket.read(&mut buf).await.unwrap();
+                            if read == 0 {
+                                return;
+                            }
+                            request.extend_from_slice(&buf[..read]);
+                        }
+
+                        let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                        peak.fetch_max(now, Ordering::SeqCst);
+                        tokio::time::sleep(std::time::Duration::from_millis(40)).await;
+                        in_flight.fetch_sub(1, Ordering::SeqCst);
+
+                        let body = r#"{"minimum_tick_size": "0.01"}"#;
+                        let response = format!(
+                            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                            body.len(),
+                            body
+                        );
+                        socket.write_all(response.as_bytes()).await.unwrap();
+                    });
+                }
+            });
+        }
+
+        let config = crate::types::ClientConfig {
+            max_concurrency: Some(LIMIT),
+            ..Default::default()
+        };
+        let client = create_test_client(&url).with_client_config(&config);
+        let requests = (0..REQUESTS).map(|i| {
+            let client = &client;
+            async move { client.get_tick_size(&i.to_string()).await }
+        });
+        let results = futures::future::join_all(requests).await;
+
+        assert!(results.iter().all(|result| result.is_ok()));
+        let peak = peak.load(Ordering::SeqCst);
+        assert!(
+            peak <= LIMIT,
+            "peak in-flight {} exceeded cap {}",
+            peak,
+            LIMIT
+        );
+        assert!(peak > 1, "requests never overlapped");
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_short_circuits_and_recovers() {
+        let mut server = MockExchange::empty().await;
+        let failing = server
+            .mock("GET", "/time")
+            .with_status(503)
+            .expect(2)
+            .create_async()
+            .await;
+        let client = create_test_client(&server.url()).with_circuit_breaker(
+            crate::circuit::CircuitBreaker {
+                threshold: 2,
+                window: std::time::Duration::from_secs(60),
+                recovery: std::time::Duration::from_millis(50),
+            },
+        );
+
+        for _ in 0..2 {
+            assert!(!client.check_health().await.unwrap().ok);
+        }
+        let err = client.get_server_time().await.unwrap_err();
+        assert!(matches!(err, PolyError::CircuitOpen { .. }));
+        assert!(err.retry_delay().unwrap() <= std::time::Duration::from_millis(50));
+        failing.assert_async().await;
+
+        failing.remove_async().await;
+        server
+            .mock("GET", "/time")
+            .with_body("1700000000")
+            .create_async()
+            .await;
+        tokio::time::sleep(std::time::Duration::from_millis(60)).await;
+        let health = client.check_health().await.unwrap();
+        assert!(health.ok);
+        assert_eq!(health.timestamp, 1_700_000_000);
+        assert_eq!(client.get_server_time().await.unwrap(), 1_700_000_000);
+    }
+
+    #[tokio::test]
+    async fn test_clob_hosts_fail_over_on_connection_error() {
+        // Grab a free port and close it so connections are refused
+        let dead = {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            format!("http://{}", listener.local_addr().unwrap())
+        };
+        let mut backup = MockExchange::empty().await;
+        let tick_size = backup
+            .mock("GET", "/tick-size")
+            .match_query(Matcher::UrlEncoded("token_id".into(), "1".into()))
+            .with_status(200)
+            .with_body(r#"{"minimum_tick_size": "0.01"}"#)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let client = create_test_client("https://unused.example.com").with_clob_hosts(
+            &[dead.as_str(), backup.url().as_str()],
+            crate::failover::FailoverPolicy::default(),
+        );
+        for _ in 0..2 {
+            assert_eq!(
+                client.get_tick_size("1").await.unwrap(),
+                Decimal::from_str("0.01").unwrap()
+            );
+        }
+
+        tick_size.assert_async().await;
+        let stats = client.clob_failover_stats().unwrap();
+        assert_eq!(stats.active_host, backup.url());
+        assert_eq!((stats.failovers, stats.failed_attempts), (1, 1));
+        assert!(client.gamma_failover_stats().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_clob_hosts_restore_primary_and_never_replay_posts() {
+        let mut primary = MockExchange::empty().await;
+        let mut backup = MockExchange::empty().await;
+        let primary_down = primary
+            .mock("GET", "/time")
+            .with_status(503)
+            .create_async()
+            .await;
+        let backup_time = backup
+            .mock("GET", "/time")
+            .with_status(200)
+            .with_body("1700000000")
+            .expect(1)
+            .create_async()
+            .await;
+        let primary_post = primary
+            .mock("POST", "/orders")
+            .with_status(502)
+            .expect(1)
+            .create_async()
+            .await;
+        let backup_post = backup
+            .mock("POST", "/orders")
+            .expect(0)
+            .create_async()
+            .await;
+
+        let client = create_test_client_with_l2(&primary.url()).with_clob_hosts(
+            &[primary.url(), backup.url()],
+            crate::failover::FailoverPolicy {
+                probe_interval: std::time::Duration::ZERO,
+            },
+        );
+        assert_eq!(client.get_server_time().await.unwrap(), 1700000000);
+        assert_eq!(client.clob_failover_stats().unwrap().active_index, 1);
+
+        // A 5xx on a POST may have been accepted upstream, so it is not retried elsewhere
+        assert!(
+            client
+                .post_orders(Vec::new(), OrderType::GTC)
+                .await
+                .is_err()
+        );
+
+        primary_down.remove_async().await;
+        let primary_time = primary
+            .mock("GET", "/time")
+            .with_status(200)
+            .with_body("1700000001")
+            .create_async()
+            .await;
+        assert_eq!(client.get_server_time().await.unwrap(), 1700000001);
+
+        let stats = client.clob_failover_stats().unwrap();
+        assert_eq!(
+            (stats.active_index, stats.failovers, stats.restores),
+            (0, 1, 1)
+        );
+        backup_time.assert_async().await;
+        primary_time.assert_async().await;
+        backup_post.assert_async().await;
+        // The probe was due, so the POST went to the primary and its 502 came straight back
+        primary_post.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_funding_rate() {
+        let mut server = MockExchange::empty().await;
+        let perp = server
+            .mock("GET", "/funding-rate")
+            .match_query(Matcher::UrlEncoded("token_id".into(), "perp".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"token_id": "perp", "rate": "0.0001", "interval_hours": 8,
+                    "next_funding_at": 1700000000, "cumulative_rate": 0.0042}"#,
+            )
+            .create_async()
+            .await;
+        let spot = server
+            .mock("GET", "/funding-rate")
+            .match_query(Matcher::UrlEncoded("token_id".into(), "spot".into()))
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let funding = client.get_funding_rate("perp").await.unwrap();
+        assert_eq!(funding.interval_hours, 8);
+        assert_eq!(
+            funding.cumulative_rate,
+            Decimal::from_str("0.0042").unwrap()
+        );
+        assert_eq!(
+            funding.annualized_rate(),
+            Decimal::from_str("0.1095").unwrap()
+        );
+
+        let err = client.get_funding_rate("spot").await.unwrap_err();
+        assert!(matches!(
+            err,
+            PolyError::MarketData {
+                kind: crate::errors::MarketDataErrorKind::TokenNotFound,
+                ..
+            }
+        ));
+
+        perp.assert_async().await;
+        spot.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_scan_spreads_sorts_tight_liquid_books_first() {
+        let mut server = MockExchange::empty().await;
+        let book = |asset: &str, bids: Value, asks: Value| {
+            serde_json::json!({
+                "market": format!("0x{}", asset),
+                "asset_id": asset,
+                "hash": "0x0",
+                "timestamp": "1700000000",
+                "bids": bids,
+                "asks": asks,
+            })
+        };
+        let level = |price: &str, size: &str| serde_json::json!({"price": price, "size": size});
+        let books = serde_json::json!([
+            book(
+                "wide",
+                serde_json::json!([level("0.48", "100")]),
+                serde_json::json!([level("0.50", "100")])
+            ),
+            book(
+                "thin",
+                serde_json::json!([level("0.60", "500"), level("0.61", "50")]),
+                serde_json::json!([level("0.62", "80")]),
+            ),
+            book(
+                "deep",
+                serde_json::json!([level("0.30", "200"), level("0.30", "100")]),
+                serde_json::json!([level("0.33", "999"), level("0.31", "250")]),
+            ),
+            book(
+                "one-sided",
+                serde_json::json!([level("0.10", "1000")]),
+                serde_json::json!([])
+            ),
+        ]);
+
+        let mock = server
+            .mock("POST", "/books")
+            .match_body(Matcher::PartialJson(serde_json::json!([
+                {"token_id": "wide"},
+                {"token_id": "thin"},
+                {"token_id": "deep"},
+                {"token_id": "one-sided"},
+            ])))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(books.to_string())
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let token_ids: Vec<String> = ["wide", "thin", "deep", "one-sided"]
+            .iter()
+            .map(|id| id.to_string())
+            .collect();
+        let summaries = client.scan_spreads(&token_ids).await.unwrap();
+
+        mock.assert_async().await;
+        let order: Vec<&str> = summaries.iter().map(|s| s.token_id.as_str()).collect();
+        assert_eq!(order, ["deep", "thin", "wide", "one-sided"]);
+
+        let deep = &summaries[0];
+        assert_eq!(deep.best_bid, Some(Decimal::from_str("0.30").unwrap()));
+        assert_eq!(deep.best_ask, Some(Decimal::from_str("0.31").unwrap()));
+        assert_eq!(deep.spread, Some(Decimal::from_str("0.01").unwrap()));
+        assert_eq!(deep.bid_depth, Decimal::from(300));
+        assert_eq!(deep.ask_depth, Decimal::from(250));
+        assert_eq!(deep.top_depth(), Decimal::from(250));
+
+        let thin = &summaries[1];
+        assert_eq!(thin.best_bid, Some(Decimal::from_str("0.61").unwrap()));
+        assert_eq!(thin.midpoint, Some(Decimal::from_str("0.615").unwrap()));
+
+        let one_sided = &summaries[3];
+        assert_eq!(one_sided.spread, None);
+        assert_eq!(one_sided.ask_depth, Decimal::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_realized_pnl_history_pages_closed_lots() {
+        let mut server = MockExchange::empty().await;
+        let trade = |id: &str, side: &str, size: &str, price: &str, match_time: &str| {
+            serde_json::json!({
+                "id": id, "asset_id": "111", "market": "0xabc", "outcome": "Yes",
+                "side": side, "size": size, "price": price, "fee_rate_bps": "0",
+                "match_time": match_time, "status": "CONFIRMED"
+            })
+        };
+        // 2024-01-01 buy, two sells in 2024, one in 2025 (excluded by `before`)
+        let first = server
+            .mock("GET", "/data/trades")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("next_cursor".into(), "MA==".into()),
+                Matcher::UrlEncoded("before".into(), "1735689600".into()),
+            ]))
+            .with_body(
+                serde_json::json!({
+                    "next_cursor": "MQ==",
+                    "data": [trade("s2", "SELL", "6", "0.55", "1720000000"),
+                             trade("s1", "SELL", "4", "0.30", "1710000000")]
+                })
+                .to_string(),
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let second = server
+            .mock("GET", "/data/trades")
+            .match_query(Matcher::UrlEncoded("next_cursor".into(), "MQ==".into()))
+            .with_body(
+                serde_json::json!({
+                    "next_cursor": "LTE=",
+                    // Our resting bid filled by a taker selling 50
+                    "data": [{
+                        "id": "b1", "asset_id": "111", "market": "0xabc", "outcome": "Yes",
+                        "side": "SELL", "size": "50", "price": "0.38", "fee_rate_bps": "0",
+                        "match_time": "1704067200", "trader_side": "MAKER",
+                        "maker_orders": [{
+                            "owner": "test_key", "asset_id": "111", "outcome": "Yes",
+                            "side": "BUY", "matched_amount": "10", "price": "0.40"
+                        }]
+                    }]
+                })
+                .to_string(),
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = create_test_client_with_l2(&server.url());
+        let day = |m, d| chrono::NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+        let params = crate::pnl::PnlHistoryParams::new(day(3, 1), day(12, 31)).with_limit(1);
+        let page = client.get_realized_pnl_history(&params).await.unwrap();
+        assert_eq!((page.total, page.data.len()), (2, 1));
+        assert_eq!(page.data[0].trade_id, "s1");
+        assert_eq!(page.data[0].net_pnl, Decimal::from_str("-0.40").unwrap());
+        let cursor = page.next_cursor.unwrap();
+
+        let page = client
+            .get_realized_pnl_history(&params.with_cursor(&cursor))
+            .await
+            .unwrap();
+        assert_eq!(page.data[0].trade_id, "s2");
+        assert_eq!(page.next_cursor, None);
+
+        first.assert_async().await;
+        second.assert_async().await;
+
+        let err = create_test_client(&server.url())
+            .get_realized_pnl_summary(2024)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, PolyError::Auth { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_download_market_snapshot() {
+        use crate::export::SnapshotFormat;
+
+        let mut server = MockExchange::empty().await;
+        let body = serde_json::json!([{
+            "condition_id": "0xabc",
+            "tokens": [
+                {"token_id": "1", "outcome": "Yes"},
+                {"token_id": "2", "outcome": "No"}
+            ],
+            "rewards": {"min_size": "0", "max_spread": "0"},
+            "active": true,
+            "closed": false,
+            "question_id": "0xq",
+            "description": "",
+            "question": "Will it rain?",
+            "market_slug": "rain",
+            "seconds_delay": "0",
+            "icon": "",
+            "fpmm": "",
+            "volume_num": "1500.50"
+        }])
+        .to_string();
+        let snapshot = server
+            .mock("GET", "/snapshot/markets")
+            .with_body(&body)
+            .expect(2 + usize::from(cfg!(feature = "csv")) + usize::from(cfg!(feature = "parquet")))
+            .create_async()
+            .await;
+        let client = create_test_client(&server.url());
+
+        let json = client
+            .download_market_snapshot(SnapshotFormat::Json)
+            .await
+            .unwrap();
+        assert_eq!(json, body.as_bytes());
+
+        let path = std::env::temp_dir().join(format!("snapshot-{}.json", std::process::id()));
+        let written = client
+            .download_market_snapshot_to_file(SnapshotFormat::Json, &path)
+            .await
+            .unwrap();
+        assert_eq!(written, body.len() as u64);
+        assert_eq!(std::fs::read(&path).unwrap(), body.as_bytes());
+        std::fs::remove_file(&path).unwrap();
+
+        let csv = client.download_market_snapshot(SnapshotFormat::Csv).await;
+        if cfg!(feature = "csv") {
+            let csv = String::from_utf8(csv.unwrap()).unwrap();
+            let row = csv.lines().nth(1).unwrap();
+            assert!(row.starts_with("0xabc,Will it rain?,rain,,true,false,false,"));
+            assert!(row.ends_with(",1,Yes,2,No,,1500.5"));
+        } else {
+            assert!(matches!(csv, Err(PolyError::Config { .. })));
+        }
+
+        let parquet = client
+            .download_market_snapshot(SnapshotFormat::Parquet)
+            .await;
+        #[cfg(feature = "parquet")]
+        {
+            let markets =
+                crate::export::read_markets_from_parquet(std::io::Cursor::new(parquet.unwrap()))
+                    .unwrap();
+            assert_eq!(markets.len(), 1);
+            assert_eq!(markets[0].condition_id, "0xabc");
+            assert_eq!(markets[0].volume_num, Some(Decimal::new(150050, 2)));
+        }
+        #[cfg(not(feature = "parquet"))]
+        assert!(matches!(parquet, Err(PolyError::Config { .. })));
+        snapshot.assert_async().await;
+    }
+
+    #[cfg(feature = "csv")]
+    #[tokio::test]
+    async fn test_export_trade_history_csv_walks_pages() {
+        let mut server = MockExchange::empty().await;
+        let trade = |id: &str, side: &str, match_time: &str| {
+            serde_json::json!({
+                "id": id, "asset_id": "111", "market": "0xabc", "outcome": "Oui",
+                "side": side, "size": "10", "price": "0.5", "fee_rate_bps": "0",
+                "match_time": match_time
+            })
+        };
+        let first = server
+            .mock("GET", "/data/trades")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("next_cursor".into(), "MA==".into()),
+                Matcher::UrlEncoded("market".into(), "0xabc".into()),
+            ]))
+            .with_body(
+                serde_json::json!({"next_cursor": "MQ==", "data": [trade("s1", "SELL", "200")]})
+                    .to_string(),
+            )
+            .create_async()
+            .await;
+        let second = server
+            .mock("GET", "/data/trades")
+            .match_query(Matcher::UrlEncoded("next_cursor".into(), "MQ==".into()))
+            .with_body(
+                serde_json::json!({"next_cursor": "LTE=", "data": [{
+                    // A taker selling 25 into our bid of 10 at 0.45
+                    "id": "b1", "asset_id": "111", "market": "0xabc", "outcome": "Oui",
+                    "side": "SELL", "size": "25", "price": "0.44", "fee_rate_bps": "0",
+                    "match_time": "100", "trader_side": "MAKER",
+                    "maker_orders": [{
+                        "owner": "test_key", "asset_id": "111", "outcome": "Oui",
+                        "side": "BUY", "matched_amount": "10", "price": "0.45"
+                    }]
+                }]})
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let params = crate::types::TradeParams {
+            id: None,
+            maker_address: None,
+            market: Some("0xabc".to_string()),
+            asset_id: None,
+            before: None,
+            after: None,
+        };
+        let mut out = Vec::new();
+        let rows = create_test_client_with_l2(&server.url())
+            .export_trade_history_csv(&params, &mut out, true)
+            .await
+            .unwrap();
+        assert_eq!(rows, 2);
+        first.assert_async().await;
+        second.assert_async().await;
+
+        let text = String::from_utf8(out).unwrap();
+        let rows: Vec<Vec<&str>> = text
+            .trim_start_matches('\u{feff}')
+            .lines()
+            .skip(1)
+            .map(|line| line.split(',').take(5).collect())
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                ["b1", "111", "BUY", "0.45", "10"],
+                ["s1", "111", "SELL", "0.5", "10"]
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cancel_market_orders_sends_filter_and_parses_ids() {
+        let mut server = MockExchange::empty().await;
+        let mock = server
+            .mock("DELETE", "/cancel-market-orders")
+            .match_body(Matcher::Json(serde_json::json!({"market": "0xabc"})))
+            .with_body(
+                r#"{"canceled": ["0x1", "0x2"], "not_canceled": {"0x3": "order already matched", "0x4": null}}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client_with_l2(&server.url());
+        let response = client
+            .cancel_market_orders(Some("0xabc"), None)
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(response.canceled, vec!["0x1", "0x2"]);
+        assert_eq!(response.not_canceled["0x3"], "order already matched");
+        assert_eq!(response.not_canceled["0x4"], "null");
+
+        let err = client.cancel_market_orders(None, None).await.unwrap_err();
+        assert!(matches!(err, PolyError::Validation { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_redeem_positions_receipt_and_refusals() {
+        let mut server = MockExchange::empty().await;
+        let mut redeem = |condition_id: &'static str| {
+            server
+                .mock("POST", "/redeem")
+                .match_header("poly_api_key", "test_key")
+                .match_body(Matcher::Json(
+                    serde_json::json!({ "condition_id": condition_id }),
+                ))
+        };
+        let won = redeem("0xwon")
+            .with_body(
+                r#"{"condition_id": "0xwon", "redeemed_tokens": [
+                    {"token_id": "111", "outcome": "Yes", "size": "40", "usdc_received": "40"}
+                ], "total_usdc_received": "40", "gas_estimate": "182000", "tx_hash": null}"#,
+            )
+            .create_async()
+            .await;
+        redeem("0xlost")
+            .with_body(
+                r#"{"condition_id": "0xlost", "redeemed_tokens": [],
+                    "total_usdc_received": "0", "gas_estimate": 0}"#,
+            )
+            .create_async()
+            .await;
+        redeem("0xopen")
+            .with_status(400)
+            .with_body(r#"{"error": "market 0xopen is not resolved"}"#)
+            .create_async()
+            .await;
+
+        let client = create_test_client_with_l2(&server.url());
+        let receipt = client.redeem_positions("0xwon").await.unwrap();
+        won.assert_async().await;
+        assert_eq!(receipt.redeemed_tokens.len(), 1);
+        assert_eq!(receipt.redeemed_tokens[0].size, Decimal::from(40));
+        assert_eq!(receipt.total_usdc_received, Decimal::from(40));
+        assert_eq!(receipt.gas_estimate, 182_000);
+        assert_eq!(receipt.tx_hash, None);
+
+        for (condition_id, reason) in [
+            ("0xlost", "no winning position held"),
+            ("0xopen", "market not resolved"),
+        ] {
+            let err = client.redeem_positions(condition_id).await.unwrap_err();
+            assert!(
+                matches!(&err, PolyError::Validation { message, .. } if message == reason),
+                "{}: {:?}",
+                condition_id,
+                err
+            );
+        }
+
+        let anonymous = create_test_client(&server.url());
+        assert!(anonymous.redeem_positions("0xwon").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_user_activity_pages_and_summary() {
+        use crate::types::{ActivityParams, ActivityType};
+
+        let mut server = MockExchange::empty().await;
+        let first = server
+            .mock("GET", "/activity")
+            .match_header("poly_api_key", "test_key")
+            .match_query(Matcher::Exact("start_ts=1700000000".into()))
+            .with_body(
+                r#"{"data": [
+                    {"id": "a1", "event_type": "DEPOSIT", "timestamp": 1700000100,
+                     "metadata": {"amount": "100"}},
+                    {"id": "a2", "event_type": "ORDER_PLACED", "timestamp": "1700000200",
+                     "metadata": {"order_id": "0x1"}}
+                ], "next_cursor": "MQ==", "count": 3}"#,
+            )
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/activity")
+            .match_query(Matcher::UrlEncoded("next_cursor".into(), "MQ==".into()))
+            .with_body(
+                r#"{"data": [
+                    {"id": "a3", "event_type": "ORDER_FILLED", "timestamp": 1700000300}
+                ], "next_cursor": "LTE=", "count": 3}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client_with_l2(&server.url());
+        let params = ActivityParams {
+            start_ts: Some(1_700_000_000),
+            ..Default::default()
+        };
+        let page = client.get_user_activity(&params).await.unwrap();
+        first.assert_async().await;
+        assert_eq!(page.total, 3);
+        assert_eq!(page.next_cursor.as_deref(), Some("MQ=="));
+        assert_eq!(page.data[0].event_type, ActivityType::Deposit);
+        assert_eq!(page.data[0].metadata["amount"], "100");
+        assert_eq!(page.data[1].timestamp.timestamp(), 1_700_000_200);
+
+        let since = chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let summary = client.get_user_activity_summary(since).await.unwrap();
+        assert_eq!(summary.total(), 3);
+        assert_eq!(summary.count(ActivityType::OrderFilled), 1);
+        assert_eq!(summary.count(ActivityType::Withdrawal), 0);
+
+        // A server that keeps handing back the same cursor ends the walk
+        server
+            .mock("GET", "/activity")
+            .match_query(Matcher::Exact("start_ts=1800000000".into()))
+            .with_body(
+                r#"{"data": [{"id": "b1", "event_type": "DEPOSIT", "timestamp": 1800000100}],
+                    "next_cursor": "Mg=="}"#,
+            )
+            .create_async()
+            .await;
+        let stuck = server
+            .mock("GET", "/activity")
+            .match_query(Matcher::UrlEncoded("next_cursor".into(), "Mg==".into()))
+            .with_body(
+                r#"{"data": [{"id": "b2", "event_type": "DEPOSIT", "timestamp": 1800000200}],
+                    "next_cursor": "Mg=="}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let since = chrono::DateTime::from_timestamp(1_800_000_000, 0).unwrap();
+        let summary = client.get_user_activity_summary(since).await.unwrap();
+        stuck.assert_async().await;
+        assert_eq!(summary.count(ActivityType::Deposit), 2);
+
+        let backwards = ActivityParams {
+            start_ts: Some(10),
+            end_ts: Some(5),
+            ..Default::default()
+        };
+        assert!(matches!(
+            client.get_user_activity(&backwards).await,
+            Err(PolyError::Validation { .. })
+        ));
+        let anonymous = create_test_client(&server.url());
+        assert!(anonymous.get_user_activity(&params).await.is_err());
+    }
+
+    #[test]
+    fn test_contract_and_wallet_addresses() {
+        let mut client = create_test_client_with_auth("http://localhost");
+        assert_eq!(
+            client.get_conditional_token_address().unwrap(),
+            "0x4D97DCd97eC945f40cF65F87097ACe5EA0476045"
+        );
+        assert_eq!(
+            client.get_collateral_token_address().unwrap(),
+            "0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174"
+        );
+
+        let user = "0x1111111111111111111111111111111111111111";
+        assert_eq!(client.get_proxy_wallet_address(user).unwrap(), user);
+        client
+            .set_funder_with_sig_type(
+                "0x2222222222222222222222222222222222222222",
+                crate::orders::SigType::PolyGnosisSafe,
+            )
+            .unwrap();
+        let safe = client.get_proxy_wallet_address(user).unwrap();
+        assert_eq!(
+            safe,
+            crate::config::proxy_wallet_address(
+                137,
+                crate::orders::SigType::PolyGnosisSafe,
+                Address::from_str(user).unwrap()
+            )
+            .unwrap()
+            .to_checksum(None)
+        );
+        assert!(matches!(
+            client.get_proxy_wallet_address("not an address"),
+            Err(PolyError::Validation { .. })
+        ));
+        assert!(matches!(
+            ClobClient::new("http://localhost").get_proxy_wallet_address(user),
+            Err(PolyError::Config { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_settlement_transactions_and_receipts() {
+        let settlement = r#"{"tx_hash": "0xsettle", "block_number": 61234567,
+            "timestamp": 1717200000, "winning_condition_index": 1,
+            "payout_numerators": [0, 1], "payout_denominator": 1}"#;
+        let mut server = MockExchange::empty().await;
+        let public = server
+            .mock("GET", "/settlements")
+            .match_query(Matcher::UrlEncoded("condition_id".into(), "0xc".into()))
+            .with_header("content-type", "application/json")
+            .with_body(format!("[{}]", settlement))
+            .create_async()
+            .await;
+        let receipts = server
+            .mock("GET", "/data/settlements")
+            .match_header("poly_api_key", "test_key")
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"[{{"condition_id": "0xc", "outcome_index": 1, "size": "40",
+                    "payout": "40", "redeem_tx_hash": "0xredeem", "settlement": {}}}]"#,
+                settlement
+            ))
+            .create_async()
+            .await;
+
+        let client = create_test_client_with_l2(&server.url());
+        let txs = client.get_settlement_transactions("0xc").await.unwrap();
+        assert_eq!(txs.len(), 1);
+        assert_eq!(txs[0].winning_payout_fraction(1), Some(Decimal::ONE));
+
+        let mine = client.get_my_settlement_receipts().await.unwrap();
+        assert_eq!(mine[0].redeem_tx_hash, "0xredeem");
+        assert_eq!(mine[0].settlement, txs[0]);
+
+        assert!(matches!(
+            create_test_client(&server.url())
+                .get_my_settlement_receipts()
+                .await,
+            Err(PolyError::Config { .. })
+        ));
+        public.assert_async().await;
+        receipts.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_protocol_health_is_cached_for_a_minute() {
+        use crate::clock::MockClock;
+
+        let mut server = MockExchange::empty().await;
+        let insurance = server
+            .mock("GET", "/protocol/insurance")
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"balance_usdc": "2500000", "coverage_ratio": "1.25",
+                    "last_updated": "2024-06-01T00:00:00Z"}"#,
+            )
+            .expect(2)
+            .create_async()
+            .await;
+        let stats = server
+            .mock("GET", "/protocol/stats")
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"total_volume_usdc": "9000000000", "total_open_interest_usdc": "2000000",
+                    "active_markets": 1200, "total_users": 1500000, "total_trades": 98000000}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let clock = Arc::new(MockClock::new(1_717_200_000));
+        let client = create_test_client(&server.url()).with_clock(clock.clone());
+        let fund = client.get_insurance_fund_balance().await.unwrap();
+        assert_eq!(fund.coverage_ratio, Decimal::from_str("1.25").unwrap());
+        let protocol = client.get_protocol_stats().await.unwrap();
+        assert_eq!(protocol.active_markets, 1200);
+        assert!(protocol.is_healthy(&fund));
+
+        clock.advance(59);
+        assert_eq!(client.get_insurance_fund_balance().await.unwrap(), fund);
+        assert_eq!(client.get_protocol_stats().await.unwrap(), protocol);
+        clock.advance(1);
+        client.get_insurance_fund_balance().await.unwrap();
+
+        let thin = crate::types::InsuranceFund {
+            coverage_ratio: Decimal::from_str("0.8").unwrap(),
+            ..fund
+        };
+        assert!(!protocol.is_healthy(&thin));
+        insurance.assert_async().await;
+        stats.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_prediction_market_index_weights_by_volume() {
+        use crate::clock::MockClock;
+
+        let mut server = MockExchange::empty().await;
+        let mock = server
+            .mock("GET", "/markets")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("order".into(), "volumeNum".into()),
+                Matcher::UrlEncoded("ascending".into(), "false".into()),
+            ]))
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[{"conditionId": "0xa", "slug": "a", "active": true, "closed": false,
+                     "category": "Politics", "volumeNum": 3000, "outcomePrices": "[\"0.6\", \"0.4\"]"},
+                    {"conditionId": "0xb", "slug": "b", "active": true, "closed": false,
+                     "category": "politics", "volumeNum": 1000, "outcomePrices": "[\"0.2\", \"0.8\"]"},
+                    {"conditionId": "0xc", "slug": "c", "active": true, "closed": true,
+                     "category": "Politics", "volumeNum": 9000, "outcomePrices": "[\"0.99\", \"0.01\"]"},
+                    {"conditionId": "0xd", "slug": "d", "active": true, "closed": false,
+                     "category": "Sports", "volumeNum": 5000, "outcomePrices": "[\"0.9\", \"0.1\"]"}]"#,
+            )
+            .expect(3)
+            .create_async()
+            .await;
+
+        let clock = Arc::new(MockClock::new(1_717_200_000));
+        let client = create_test_client(&server.url()).with_clock(clock.clone());
+        let politics = client
+            .get_prediction_market_index(Some("POLITICS"))
+            .await
+            .unwrap();
+        assert_eq!(politics.composite_score, Decimal::from_str("0.5").unwrap());
+        assert_eq!(politics.normalized_score(), Decimal::from(50));
+        assert_eq!(politics.computed_at.timestamp(), 1_717_200_000);
+        let ids: Vec<&str> = politics
+            .top_markets
+            .iter()
+            .map(|component| component.condition_id.as_str())
+            .collect();
+        assert_eq!(ids, ["0xa", "0xb"]);
+        assert_eq!(
+            politics.top_markets[0].weight,
+            Decimal::from_str("0.75").unwrap()
+        );
+
+        let all = client.get_prediction_market_index(None).await.unwrap();
+        assert_eq!(all.top_markets[0].condition_id, "0xd");
+        assert_eq!(
+            all.composite_score.round_dp(4),
+            Decimal::from_str("0.7222").unwrap()
+        );
+
+        clock.advance(30);
+        assert_eq!(
+            client
+                .get_prediction_market_index(Some("politics"))
+                .await
+                .unwrap(),
+            politics
+        );
+        assert!(matches!(
+            client.get_prediction_market_index(Some("Crypto")).await,
+            Err(PolyError::Validation { .. })
+        ));
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_question_analysis_falls_back_to_heuristic() {
+        let mut server = MockExchange::empty().await;
+        let served = server
+            .mock("GET", "/markets/0xserved/analysis")
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"entities": [{"text": "Fed", "kind": "organization"}],
+                    "resolution_date_confidence": "0.9", "ambiguity_score": "0.1",
+                    "similar_markets": ["0xother"]}"#,
+            )
+            .create_async()
+            .await;
+        let missing = server
+            .mock("GET", "/markets/0xq/analysis")
+            .with_status(404)
+            .create_async()
+            .await;
+        let lookup = server
+            .mock("GET", "/markets")
+            .match_query(Matcher::UrlEncoded("condition_ids".into(), "0xq".into()))
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[{"conditionId": "0xq", "slug": "q", "active": true, "closed": false,
+                     "question": "Could the Fed cut rates by March 15, 2025?"}]"#,
+            )
+            .create_async()
+            .await;
+        let search = server
+            .mock("GET", "/markets/search")
+            .match_query(Matcher::UrlEncoded(
+                "q".into(),
+                "Fed cut rates March".into(),
+            ))
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"total": 2, "data": [
+                    {"conditionId": "0xq", "slug": "q", "active": true, "closed": false,
+                     "question": "Could the Fed cut rates by March 15, 2025?"},
+                    {"conditionId": "0xr", "slug": "r", "active": true, "closed": false,
+                     "question": "Will the Fed cut rates in June?"}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let analysis = client
+            .get_market_question_analysis("0xserved")
+            .await
+            .unwrap();
+        assert_eq!(
+            analysis.entities[0].kind,
+            crate::question::EntityKind::Other
+        );
+        assert_eq!(analysis.similar_markets, ["0xother"]);
+
+        let analysis = client.get_market_question_analysis("0xq").await.unwrap();
+        assert_eq!(analysis.entities[0].text, "March 15, 2025");
+        assert_eq!(analysis.resolution_date_confidence, Decimal::ONE);
+        assert_eq!(analysis.ambiguity_score, Decimal::from_str("0.25").unwrap());
+        assert_eq!(analysis.similar_markets, ["0xr"]);
+        missing.assert_async().await;
+        lookup.assert_async().await;
+        search.assert_async().await;
+        search.remove_async().await;
+
+        let failing = server
+            .mock("GET", "/markets/search")
+            .match_query(Matcher::Any)
+            .with_status(500)
+            .create_async()
+            .await;
+        let analysis = client.get_market_question_analysis("0xq").await.unwrap();
+        assert_eq!(analysis.entities[0].text, "March 15, 2025");
+        assert!(analysis.similar_markets.is_empty());
+
+        served.assert_async().await;
+        failing.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_epoch_change_clears_caches() {
+        use crate::clock::MockClock;
+
+        let epoch = |id: u32| {
+            format!(
+                r#"{{"id": {}, "start_ts": 1717200000, "end_ts": 1717804800, "is_active": true,
+                    "reward_pool_usdc": "25000"}}"#,
+                id
+            )
+        };
+        let mut server = MockExchange::empty().await;
+        let first = server
+            .mock("GET", "/epoch")
+            .with_header("content-type", "application/json")
+            .with_body(epoch(7))
+            .expect(1)
+            .create_async()
+            .await;
+        let stats = server
+            .mock("GET", "/protocol/stats")
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"total_volume_usdc": "9000000000", "total_open_interest_usdc": "2000000",
+                    "active_markets": 1200, "total_users": 1500000, "total_trades": 98000000}"#,
+            )
+            .expect(2)
+            .create_async()
+            .await;
+
+        let cache = Arc::new(MarketMetaCache::default());
+        cache.insert(
+            "102",
+            MarketMeta {
+                tick_size: Decimal::from_str("0.01").unwrap(),
+                neg_risk: false,
+                min_order_size: None,
+            },
+        );
+        let clock = Arc::new(MockClock::new(1_717_200_000));
+        let client = create_test_client(&server.url())
+            .with_clock(clock.clone())
+            .with_market_meta_cache(cache.clone())
+            .with_epoch_cache_invalidation(true);
+        assert_eq!(client.current_epoch(), None);
+
+        // The first cached read checks the epoch; seeing one for the first time clears nothing
+        client.get_protocol_stats().await.unwrap();
+        let current = client.current_epoch().unwrap();
+        assert_eq!(current.id, 7);
+        assert_eq!(current.reward_pool_usdc, Decimal::from(25_000));
+        assert!(cache.get("102").is_some());
+        first.assert_async().await;
+
+        let second = server
+            .mock("GET", "/epoch")
+            .with_header("content-type", "application/json")
+            .with_body(epoch(8))
+            .expect(1)
+            .create_async()
+            .await;
+        clock.advance(10);
+        client.get_protocol_stats().await.unwrap();
+        assert_eq!(client.get_current_epoch().await.unwrap().id, 8);
+        assert!(cache.get("102").is_none());
+
+        // Cleared, so fetched again even though a minute hasn't passed
+        client.get_protocol_stats().await.unwrap();
+        second.assert_async().await;
+        stats.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_market_events_feed_is_one_timeline() {
+        let mut server = MockExchange::empty().await;
+        let mock = server
+            .mock("GET", "/markets/0xc/events")
+            .match_query(Matcher::UrlEncoded("since".into(), "1717200000".into()))
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[
+                    {"type": "MARKET_CLOSED", "timestamp": 1717203000},
+                    {"type": "TRADE_EXECUTED", "trade_id": "t1", "token_id": "1",
+                     "price": "0.61", "size": "25", "timestamp": 1717201000},
+                    {"type": "PRICE_CHANGED", "token_id": "1", "old_price": "0.60",
+                     "new_price": "0.61", "timestamp": 1717201000},
+                    {"type": "ORDER_BOOK_CHANGED", "summary": "bid 0.60 removed",
+                     "timestamp": 1717200500},
+                    {"type": "MARKET_RESOLVED", "winning_outcome": "Yes",
+                     "timestamp": 1717204000}
+                ]"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let since = chrono::DateTime::from_timestamp(1_717_200_000, 0).unwrap();
+        let events = client.get_market_events_feed("0xc", since).await.unwrap();
+        mock.assert_async().await;
+
+        let times: Vec<i64> = events.iter().map(|e| e.timestamp().timestamp()).collect();
+        assert_eq!(
+            times,
+            [1717200500, 1717201000, 1717201000, 1717203000, 1717204000]
+        );
+        // Events at the same time keep the server's order
+        assert!(
+            matches!(&events[1], MarketEvent::TradeExecuted { trade_id, .. } if trade_id == "t1")
+        );
+        assert!(
+            matches!(&events[4], MarketEvent::MarketResolved { winning_outcome, .. } if winning_outcome == "Yes")
+        );
+
+        assert!(matches!(
+            client.get_market_events_feed(" ", since).await,
+            Err(PolyError::Validation { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_auto_rate_limits_set_the_rate() {
+        let mut server = MockExchange::empty().await;
+        let mock = server
+            .mock("GET", "/user/limits")
+            .match_header("poly_api_key", "test_key")
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"requests_per_second": 12, "orders_per_day": 5000, "max_open_orders": 200,
+                    "max_order_size": "25000", "max_position_size_usdc": "100000.50",
+                    "tier": "MARKET_MAKER"}"#,
+            )
+            .expect(2)
+            .create_async()
+            .await;
+
+        let client = create_test_client_with_l2(&server.url());
+        let limits = client.get_user_limits().await.unwrap();
+        assert_eq!(limits.tier, AccountTier::MarketMaker);
+        assert_eq!(limits.max_position_size_usdc.to_string(), "100000.50");
+        assert!(!limits.is_at_capacity(199));
+        assert!(limits.is_at_capacity(200));
+
+        let client = client.with_auto_rate_limits().await.unwrap();
+        assert_eq!(client.rate_limiter.as_ref().unwrap().per_second(), 12);
+        assert!(client.request_limiter.is_none());
+        mock.assert_async().await;
+
+        let err = match create_test_client(&server.url())
+            .with_auto_rate_limits()
+            .await
+        {
+            Err(err) => err,
+            Ok(_) => panic!("limits need credentials"),
+        };
+        assert!(matches!(err, PolyError::Config { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_market_participants_sorted_by_volume() {
+        use crate::types::ParticipantParams;
+
+        let participant = |address: &str, volume: &str, maker: bool| {
+            serde_json::json!({
+                "address": address, "volume_traded": volume, "num_trades": "12",
+                "avg_price": "0.41", "net_position": "-50", "is_market_maker": maker,
+            })
+        };
+        let mut server = MockExchange::empty().await;
+        let participants = server
+            .mock("GET", "/markets/0xabc/participants")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("min_volume".into(), "100".into()),
+                Matcher::UrlEncoded("limit".into(), "3".into()),
+                Matcher::UrlEncoded("order".into(), "volumeNum".into()),
+                Matcher::UrlEncoded("ascending".into(), "false".into()),
+            ]))
+            .with_body(
+                serde_json::json!({
+                    "data": [
+                        participant("0x1", "150", false),
+                        participant("0x2", "9000.5", true),
+                        participant("0x3", "420", false),
+                    ],
+                    "next_cursor": "Mw==",
+                    "count": 7,
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let address = "0x00000000000000000000000000000000000000aa";
+        let stats = server
+            .mock("GET", format!("/participants/{}/stats", address).as_str())
+            .with_body(
+                serde_json::json!({
+                    "address": address, "volume_traded": "12500", "num_trades": 340,
+                    "markets_traded": "18", "markets_made": 4, "realized_pnl": "-75.5",
+                    "first_trade_at": 1700000000,
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let params = ParticipantParams {
+            min_volume: Some(Decimal::from(100)),
+            limit: Some(3),
+            ..Default::default()
+        };
+        let page = client
+            .get_market_participants("0xabc", &params)
+            .await
+            .unwrap();
+        participants.assert_async().await;
+        let addresses: Vec<&str> = page.data.iter().map(|p| p.address.as_str()).collect();
+        assert_eq!(addresses, ["0x2", "0x3", "0x1"]);
+        assert!(page.data[0].is_market_maker);
+        assert_eq!(page.data[0].num_trades, 12);
+        assert_eq!((page.total, page.next_cursor.as_deref()), (7, Some("Mw==")));
+
+        let global = client
+            .get_participant_stats(&format!("0x{}", address[2..].to_uppercase()))
+            .await
+            .unwrap();
+        stats.assert_async().await;
+        assert_eq!(global.markets_traded, 18);
+        assert_eq!(global.realized_pnl, Decimal::from_str("-75.5").unwrap());
+        assert_eq!(global.last_trade_at, None);
+        assert!(matches!(
+            client.get_participant_stats("not-an-address").await,
+            Err(PolyError::Validation { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_batch_cancel_by_price_range() {
+        let order = |id: &str, price: &str, side: &str| {
+            let mut order: Value = serde_json::from_str(&open_order_json(id, "LIVE", "0")).unwrap();
+            order["price"] = price.into();
+            order["side"] = side.into();
+            order
+        };
+        let mut server = MockExchange::empty().await;
+        server
+            .mock("GET", "/data/orders")
+            .match_query(Matcher::UrlEncoded("asset_id".into(), "123".into()))
+            .with_body(
+                serde_json::json!({
+                    "data": [
+                        order("low_bid", "0.30", "BUY"),
+                        order("edge_bid", "0.40", "BUY"),
+                        order("inside_ask", "0.55", "SELL"),
+                        order("high_ask", "0.75", "SELL"),
+                    ],
+                    "next_cursor": "LTE=",
+                })
+                .to_string(),
+            )
+            .expect(3)
+            .create_async()
+            .await;
+        let cancel_both = server
+            .mock("DELETE", "/orders")
+            .match_body(Matcher::Json(serde_json::json!(["low_bid", "high_ask"])))
+            .with_body(
+                r#"{"canceled": ["low_bid"], "not_canceled": {"high_ask": "already matched"}}"#,
+            )
+            .create_async()
+            .await;
+        let cancel_asks = server
+            .mock("DELETE", "/orders")
+            .match_body(Matcher::Json(serde_json::json!(["high_ask"])))
+            .with_body(r#"{"canceled": ["high_ask"]}"#)
+            .create_async()
+            .await;
+
+        let client = create_test_client_with_l2(&server.url());
+        let price = |p: &str| Decimal::from_str(p).unwrap();
+        let response = client
+            .batch_cancel_by_price_range("123", price("0.4"), price("0.6"), None)
+            .await
+            .unwrap();
+        cancel_both.assert_async().await;
+        assert_eq!(response.canceled, ["low_bid"]);
+        assert_eq!(response.not_canceled["high_ask"], "already matched");
+
+        let response = client
+            .batch_cancel_by_price_range("123", price("0.4"), price("0.6"), Some(Side::SELL))
+            .await
+            .unwrap();
+        cancel_asks.assert_async().await;
+        assert_eq!(response.canceled, ["high_ask"]);
+
+        // Nothing outside the range: no cancel is sent
+        let response = client
+            .batch_cancel_by_price_range("123", price("0.01"), price("0.99"), None)
+            .await
+            .unwrap();
+        assert_eq!(response, crate::types::CancelResponse::default());
+
+        for (min, max) in [("0.6", "0.4"), ("0", "0.5"), ("0.5", "1")] {
+            assert!(matches!(
+                client
+                    .batch_cancel_by_price_range("123", price(min), price(max), None)
+                    .await,
+                Err(PolyError::Validation { .. })
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_order_fill_polls_until_matched() {
+        let mut server = MockExchange::empty().await;
+        let mock = mock_order_fills_after(&mut server, "0xfill", 2).await;
+
+        let client = create_test_client_with_l2(&server.url());
+        let order = client
+            .wait_for_order_fill(
+                "0xfill",
+                std::time::Duration::from_secs(5),
+                std::time::Duration::from_millis(5),
+            )
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(order.status, "MATCHED");
+        assert_eq!(order.size_matched, Decimal::from(10));
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_order_fill_cancel_and_timeout() {
+        let mut server = MockExchange::empty().await;
+        server
+            .mock("GET", "/data/order/0xcanceled")
+            .with_status(200)
+            .with_body(open_order_json("0xcanceled", "CANCELED", "0"))
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/data/order/0xresting")
+            .with_status(200)
+            .with_body(open_order_json("0xresting", "LIVE", "4"))
+            .create_async()
+            .await;
+
+        let client = create_test_client_with_l2(&server.url());
+        let poll = std::time::Duration::from_millis(5);
+
+        let canceled = client
+            .wait_for_order_fill("0xcanceled", std::time::Duration::from_secs(5), poll)
+            .await;
+        assert!(matches!(canceled, Err(PolyError::Validation { .. })));
+
+        let timed_out = client
+            .wait_for_order_fill("0xresting", std::time::Duration::from_millis(30), poll)
+            .await;
+        assert!(matches!(timed_out, Err(PolyError::Timeout { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_order_fill_via_ws_reacts_to_user_events() {
+        use futures::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite::Message;
+
+        let mut server = MockExchange::empty().await;
+        // Initial read, the re-read after subscribing, then the event-triggered read
+        let mock = mock_order_fills_after(&mut server, "0xws", 2).await;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let ws_url = format!("ws://{}", listener.local_addr().unwrap());
+        let ws_server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let Some(Ok(Message::Text(subscription))) = socket.next().await else {
+                panic!("expected a subscription frame");
+            };
+            let subscription: Value = serde_json::from_str(&subscription).unwrap();
+            assert_eq!(subscription["markets"], serde_json::json!(["0xmarket"]));
+
+            let update = |order_id: &str| {
+                serde_json::json!({
+                    "event_type": "order",
+                    "asset_id": "123",
+                    "id": order_id,
+                    "market": "0xmarket",
+                    "order_owner": "owner",
+                    "original_size": "10",
+                    "outcome": "Yes",
+                    "owner": "owner",
+                    "price": "0.5",
+                    "side": "BUY",
+                    "size_matched": "10",
+                    "timestamp": "1700000001",
+                    "type": "UPDATE",
+                })
+                .to_string()
+            };
+            for order_id in ["0xother", "0xws"] {
+                socket
+                    .send(Message::Text(update(order_id).into()))
+                    .await
+                    .unwrap();
+            }
+            let _ = socket.next().await;
+        });
+
+        let client = create_test_client_with_l2(&server.url());
+        let mut user_ws = crate::wss::WssUserClient::with_url(
+            &ws_url,
+            ApiCredentials {
+                api_key: "test_key".to_string(),
+                secret: "dGVzdF9zZWNyZXQ=".to_string(),
+                passphrase: "test_passphrase".to_string(),
+            },
+        );
+        let order = client
+            .wait_for_order_fill_via_ws("0xws", &mut user_ws, std::time::Duration::from_secs(5))
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(order.status, "MATCHED");
+
+        drop(user_ws);
+        ws_server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_get_tick_size_success() {
+        let mut server = MockExchange::empty().await;
+        let mock_response = r#"{
+            "minimum_tick_size": "0.01"
+        }"#;
+
+        let mock = server
+            .mock("GET", "/tick-size")
+            .match_query(Matcher::UrlEncoded("token_id".into(), "0x123".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(mock_response)
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let result = client.get_tick_size("0x123").await;
+
+        mock.assert_async().await;
+        assert!(result.is_ok());
+        let tick_size = result.unwrap();
+        assert_eq!(tick_size, Decimal::from_str("0.01").unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_get_neg_risk_success() {
+        let mut server = MockExchange::empty().await;
+        let mock_response = r#"{
+            "neg_risk": false
+        }"#;
+
+        let mock = server
+            .mock("GET", "/neg-risk")
+            .match_query(Matcher::UrlEncoded("token_id".into(), "0x123".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(mock_response)
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let result = client.get_neg_risk("0x123").await;
+
+        mock.assert_async().await;
+        assert!(result.is_ok());
+        let neg_risk = result.unwrap();
+        assert!(!neg_risk);
+    }
+
+    #[tokio::test]
+    async fn test_recorded_fixtures_parse_through_the_client() {
+        use crate::testkit::{MockExchange, fixtures};
+
+        let mut exchange = MockExchange::start().await;
+        let client = exchange.clob_client();
+
+        let markets = client.get_markets(None, None).await.unwrap();
+        assert_eq!(markets.data.len(), 2);
+        let market = &markets.data[0];
+        assert_eq!(market.condition_id, fixtures::CONDITION_ID);
+        assert_eq!(market.tokens[0].token_id, fixtures::YES_TOKEN_ID);
+        assert_eq!(market.tokens[1].token_id, fixtures::NO_TOKEN_ID);
+
+        let book = client
+            .get_order_book(fixtures::YES_TOKEN_ID, None)
+            .await
+            .unwrap();
+        assert_eq!(book.market, fixtures::CONDITION_ID);
+        assert_eq!(
+            client.get_tick_size(fixtures::YES_TOKEN_ID).await.unwrap(),
+            Decimal::from_str("0.01").unwrap()
+        );
+        assert!(!client.get_neg_risk(fixtures::YES_TOKEN_ID).await.unwrap());
+
+        // Routes added afterwards win over the fixtures
+        let halted = exchange
+            .server()
+            .mock("GET", "/midpoint")
+            .match_query(Matcher::Any)
+            .with_status(404)
+            .create_async()
+            .await;
+        assert!(client.get_midpoint(fixtures::YES_TOKEN_ID).await.is_err());
+        halted.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_find_subscribable_market() {
+        use crate::testkit::{MockExchange, fixtures};
+
+        let exchange = MockExchange::start().await;
+        let client = exchange.clob_client();
+
+        let (market, asset_ids) = client
+            .find_subscribable_market(Decimal::from(100_000))
+            .await
+            .unwrap();
+        assert_eq!(market.condition_id, fixtures::CONDITION_ID);
+        assert_eq!(asset_ids, [fixtures::YES_TOKEN_ID, fixtures::NO_TOKEN_ID]);
+
+        // Candidates come most liquid first, so the sibling is second
+        let (sibling, _) = client
+            .find_subscribable_market_with(Decimal::ZERO, |candidates| candidates.len() - 1)
+            .await
+            .unwrap();
+        assert_ne!(sibling.condition_id, fixtures::CONDITION_ID);
+
+        // The fixture route ignores the query, so the liquidity floor is enforced locally
+        let err = client
+            .find_subscribable_market(Decimal::from(1_000_000))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, PolyError::Validation { .. }));
+        let err = client
+            .find_subscribable_market_with(Decimal::ZERO, |_| 5)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, PolyError::Validation { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_get_top_markets_ranks_one_page() {
+        use crate::scoring::{LiquidityScorer, SpreadScorer};
+        use crate::testkit::{MockExchange, fixtures};
+
+        let exchange = MockExchange::start().await;
+        let client = exchange.clob_client();
+        let params = crate::types::GammaListParams::default();
+
+        let top = client
+            .get_top_markets(&params, 1, &LiquidityScorer)
+            .await
+            .unwrap();
+        assert_eq!(top.len(), 1);
+        assert_eq!(top[0].condition_id, fixtures::CONDITION_ID);
+
+        // The sibling quotes the tighter spread
+        let top = client
+            .get_top_markets(&params, 5, &SpreadScorer)
+            .await
+            .unwrap();
+        assert_eq!(top.len(), 2);
+        assert_ne!(top[0].condition_id, fixtures::CONDITION_ID);
+        assert_eq!(top[1].condition_id, fixtures::CONDITION_ID);
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn test_requests_are_counted_by_endpoint_and_status() {
+        use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        metrics::with_local_recorder(&recorder, || {
+            runtime.block_on(async {
+                let mut server = MockExchange::empty().await;
+                server
+                    .mock("GET", "/midpoint")
+                    .match_query(Matcher::Any)
+                    .with_body(r#"{"mid": "0.5"}"#)
+                    .create_async()
+                    .await;
+                server
+                    .mock("GET", "/markets/0xabc")
+                    .with_status(429)
+                    .create_async()
+                    .await;
+
+                let client = create_test_client(&server.url());
+                client.get_midpoint("1").await.unwrap();
+                client.get_midpoint("2").await.unwrap();
+                assert!(client.get_market("0xabc").await.is_err());
+            })
+        });
+
+        let metrics: Vec<_> = snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .map(|(key, _, _, value)| {
+                let key = key.key();
+                let labels: Vec<String> = key
+                    .labels()
+                    .map(|label| format!("{}={}", label.key(), label.value()))
+                    .collect();
+                (key.name().to_string(), labels, value)
+            })
+            .collect();
+        let find = |name: &str, labels: &[&str]| {
+            metrics
+                .iter()
+                .find(|(n, l, _)| n == name && l == labels)
+                .map(|(_, _, value)| value)
+        };
+
+        assert_eq!(
+            find(
+                crate::telemetry::HTTP_REQUESTS_TOTAL,
+                &["endpoint=/midpoint", "status=200"]
+            ),
+            Some(&DebugValue::Counter(2))
+        );
+        assert_eq!(
+            find(
+                crate::telemetry::RATE_LIMITED_TOTAL,
+                &["endpoint=/markets/:id"]
+            ),
+            Some(&DebugValue::Counter(1))
+        );
+        assert!(matches!(
+            find(
+                crate::telemetry::HTTP_REQUEST_DURATION_SECONDS,
+                &["endpoint=/midpoint"]
+            ),
+            Some(DebugValue::Histogram(samples)) if samples.len() == 2
+        ));
     }
 
     #[tokio::test]
-    async fn test_get_order_book_success() {
-        let mut server = Server::new_async().await;
+    async fn test_get_open_interest_success() {
+        let mut server = MockExchange::empty().await;
         let mock_response = r#"{
-            "market": "0x123",
-            "asset_id": "0x123",
-            "hash": "0xabc123",
-            "timestamp": "1234567890",
-            "bids": [
-                {"price": "0.75", "size": "100.0"}
-            ],
-            "asks": [
-                {"price": "0.76", "size": "50.0"}
-            ]
+            "token_id": "0x123",
+            "bid_open_interest": "1500",
+            "ask_open_interest": "500",
+            "total_open_interest": "2000"
         }"#;
 
         let mock = server
-            .mock("GET", "/book")
+            .mock("GET", "/open-interest")
             .match_query(Matcher::UrlEncoded("token_id".into(), "0x123".into()))
             .with_status(200)
             .with_header("content-type", "application/json")
@@ -2278,147 +9565,423 @@ mod tests {
             .await;
 
         let client = create_test_client(&server.url());
-        let result = client.get_order_book("0x123").await;
+        let result = client.get_open_interest("0x123").await;
 
         mock.assert_async().await;
-        assert!(result.is_ok());
-        let book = result.unwrap();
-        assert_eq!(book.market, "0x123");
-        assert_eq!(book.bids.len(), 1);
-        assert_eq!(book.asks.len(), 1);
+        let open_interest = result.unwrap();
+        assert_eq!(open_interest.total_open_interest, Decimal::from(2000));
+        assert_eq!(
+            open_interest.imbalance_ratio(),
+            Decimal::from_str("0.5").unwrap()
+        );
     }
 
     #[tokio::test]
-    async fn test_get_midpoint_success() {
-        let mut server = Server::new_async().await;
-        let mock_response = r#"{
-            "mid": "0.755"
-        }"#;
+    async fn test_get_condition_open_interest() {
+        let mut server = MockExchange::empty().await;
 
-        let mock = server
-            .mock("GET", "/midpoint")
-            .match_query(Matcher::UrlEncoded("token_id".into(), "0x123".into()))
+        let market_mock = server
+            .mock("GET", "/markets/0xcondition")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(mock_response)
+            .with_body(
+                r#"{"condition_id": "0xcondition", "tokens": [
+                    {"token_id": "111", "outcome": "Yes"},
+                    {"token_id": "222", "outcome": "No"}
+                ]}"#,
+            )
             .create_async()
             .await;
 
+        let mut oi_mocks = Vec::new();
+        for (token, bid, ask) in [("111", "10", "30"), ("222", "0", "0")] {
+            let mock = server
+                .mock("GET", "/open-interest")
+                .match_query(Matcher::UrlEncoded("token_id".into(), token.into()))
+                .with_status(200)
+                .with_header("content-type", "application/json")
+                .with_body(format!(
+                    r#"{{"token_id": "{}", "bid_open_interest": "{}", "ask_open_interest": "{}", "total_open_interest": "40"}}"#,
+                    token, bid, ask
+                ))
+                .create_async()
+                .await;
+            oi_mocks.push(mock);
+        }
+
         let client = create_test_client(&server.url());
-        let result = client.get_midpoint("0x123").await;
+        let result = client
+            .get_condition_open_interest("0xcondition")
+            .await
+            .unwrap();
 
-        mock.assert_async().await;
-        assert!(result.is_ok());
-        let response = result.unwrap();
-        assert_eq!(response.mid, Decimal::from_str("0.755").unwrap());
+        market_mock.assert_async().await;
+        for mock in oi_mocks {
+            mock.assert_async().await;
+        }
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].token_id, "111");
+        assert_eq!(
+            result[0].imbalance_ratio(),
+            Decimal::from_str("-0.5").unwrap()
+        );
+        assert_eq!(result[1].imbalance_ratio(), Decimal::ZERO);
     }
 
-    #[tokio::test]
-    async fn test_get_spread_success() {
-        let mut server = Server::new_async().await;
-        let mock_response = r#"{
-            "spread": "0.01"
-        }"#;
+    fn position_json(
+        asset: &str,
+        size: &str,
+        avg_price: &str,
+        cur_price: &str,
+        realized: &str,
+    ) -> String {
+        format!(
+            r#"{{"proxyWallet": "0xabc", "asset": "{asset}", "conditionId": "0xcond", "size": {size},
+                "avgPrice": {avg_price}, "initialValue": 0, "currentValue": 0, "cashPnl": 0,
+                "percentPnl": 0, "totalBought": 0, "realizedPnl": {realized},
+                "percentRealizedPnl": 0, "curPrice": {cur_price}, "redeemable": false,
+                "mergeable": false}}"#
+        )
+    }
 
-        let mock = server
-            .mock("GET", "/spread")
-            .match_query(Matcher::UrlEncoded("token_id".into(), "0x123".into()))
-            .with_status(200)
+    #[test]
+    fn test_simulate_liquidation() {
+        let positions: Vec<DataPosition> = [
+            position_json("a", "100", "0.50", "0.60", "0"),
+            position_json("b", "50", "0.40", "0.30", "0"),
+        ]
+        .iter()
+        .map(|json| serde_json::from_str(json).unwrap())
+        .collect();
+        let client = ClobClient::new("http://localhost");
+        let half = Decimal::from_str("0.5").unwrap();
+
+        let scenario = client.simulate_liquidation(&positions, half).unwrap();
+        assert_eq!(
+            scenario.token_liquidation_prices["a"],
+            Some(Decimal::from_str("0.25").unwrap())
+        );
+        assert_eq!(
+            scenario.token_liquidation_prices["b"],
+            Some(Decimal::from_str("0.2").unwrap())
+        );
+        assert_eq!(scenario.total_margin_used, Decimal::from(35));
+        assert_eq!(
+            scenario.margin_buffer.round_dp(4),
+            Decimal::from_str("0.5333").unwrap()
+        );
+        assert_eq!(
+            scenario.distance_to_liquidation.round_dp(4),
+            Decimal::from_str("0.3333").unwrap()
+        );
+        assert!(scenario.is_safe());
+
+        let cash = client
+            .simulate_liquidation(&positions, Decimal::ONE)
+            .unwrap();
+        assert!(cash.token_liquidation_prices.values().all(Option::is_none));
+        assert_eq!(
+            (cash.margin_buffer, cash.distance_to_liquidation),
+            (Decimal::ONE, Decimal::ONE)
+        );
+
+        let thin = client
+            .simulate_liquidation(&positions, Decimal::from_str("0.1").unwrap())
+            .unwrap();
+        assert_eq!(thin.margin_buffer, Decimal::from_str("0.16").unwrap());
+        assert!(!thin.is_safe());
+
+        assert!(matches!(
+            client.simulate_liquidation(&positions, Decimal::ZERO),
+            Err(PolyError::Validation { .. })
+        ));
+        let mut short = positions[0].clone();
+        short.size = -short.size;
+        assert!(matches!(
+            client.simulate_liquidation(&[short], half),
+            Err(PolyError::Validation { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_alert_threshold_and_triggered_alerts() {
+        let mut server = MockExchange::empty().await;
+        let get = server
+            .mock("GET", "/alerts/threshold")
+            .match_header("poly_api_key", "test_key")
             .with_header("content-type", "application/json")
-            .with_body(mock_response)
+            .with_body(r#"{"threshold": "-250.5"}"#)
+            .create_async()
+            .await;
+        let put = server
+            .mock("PUT", "/alerts/threshold")
+            .match_body(Matcher::Json(serde_json::json!({ "threshold": "-500" })))
+            .with_body("")
+            .create_async()
+            .await;
+        let alerts = server
+            .mock("GET", "/alerts")
+            .match_query(Matcher::UrlEncoded("since".into(), "1717200000".into()))
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"[{{"alert_id": "al_1", "triggered_at": "2024-06-01T12:00:00Z",
+                    "pnl_at_trigger": "-512.25", "positions_at_trigger": [{}]}}]"#,
+                position_json("111", "100", "0.6", "0.1", "0")
+            ))
             .create_async()
             .await;
 
-        let client = create_test_client(&server.url());
-        let result = client.get_spread("0x123").await;
+        let client = create_test_client_with_l2(&server.url());
+        assert_eq!(
+            client.get_alert_threshold().await.unwrap(),
+            Some(Decimal::from_str("-250.5").unwrap())
+        );
+        client
+            .set_alert_threshold(Decimal::from_str("-500.00").unwrap())
+            .await
+            .unwrap();
+        let err = client
+            .set_alert_threshold(Decimal::from(100))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, PolyError::Validation { .. }));
 
-        mock.assert_async().await;
-        assert!(result.is_ok());
-        let response = result.unwrap();
-        assert_eq!(response.spread, Decimal::from_str("0.01").unwrap());
+        let since = chrono::DateTime::from_timestamp(1_717_200_000, 0).unwrap();
+        let triggered = client.get_triggered_alerts(since).await.unwrap();
+        assert_eq!(triggered[0].alert_id, "al_1");
+        assert_eq!(triggered[0].positions_at_trigger[0].asset, "111");
+
+        get.assert_async().await;
+        put.assert_async().await;
+        alerts.assert_async().await;
     }
 
     #[tokio::test]
-    async fn test_get_price_success() {
-        let mut server = Server::new_async().await;
-        let mock_response = r#"{
-            "price": "0.76"
-        }"#;
+    async fn test_position_updates_diff_successive_polls() {
+        use futures::StreamExt;
+
+        let mut server = MockExchange::empty().await;
+        let mut polls = Vec::new();
+        for body in [
+            format!("[{}]", position_json("111", "100", "0.4", "0.5", "0")),
+            format!(
+                "[{}, {}]",
+                position_json("111", "60", "0.4", "0.5", "0"),
+                position_json("222", "10", "0.3", "0.3", "0")
+            ),
+            format!("[{}]", position_json("222", "10", "0.3", "0.3", "0")),
+        ] {
+            polls.push(
+                server
+                    .mock("GET", "/positions")
+                    .match_query(Matcher::Any)
+                    .with_header("content-type", "application/json")
+                    .with_body(body)
+                    .expect(1)
+                    .create_async()
+                    .await,
+            );
+        }
 
-        let mock = server
-            .mock("GET", "/price")
+        let client = create_test_client_with_auth(&server.url()).with_data_api_base(&server.url());
+        let updates: Vec<_> = client
+            .subscribe_position_updates(std::time::Duration::from_millis(1))
+            .take(3)
+            .map(|update| update.unwrap())
+            .collect()
+            .await;
+        let summary: Vec<_> = updates
+            .iter()
+            .map(|u| (u.token_id.as_str(), u.direction, u.change.to_string()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (
+                    "111",
+                    PositionChangeDirection::Decreasing,
+                    "-40".to_string()
+                ),
+                ("222", PositionChangeDirection::Opened, "10".to_string()),
+                ("111", PositionChangeDirection::Closed, "-60".to_string()),
+            ]
+        );
+        assert_eq!(updates[2].previous_size, Some(Decimal::from(60)));
+        assert_eq!(updates[2].current_size, Decimal::ZERO);
+        for poll in polls {
+            poll.assert_async().await;
+        }
+
+        let unsigned = create_test_client(&server.url());
+        let mut unsigned =
+            Box::pin(unsigned.subscribe_position_updates(std::time::Duration::from_millis(1)));
+        assert!(matches!(
+            unsigned.next().await,
+            Some(Err(PolyError::Auth { .. }))
+        ));
+        assert!(unsigned.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_estimate_gas_for_redemption() {
+        let mut server = MockExchange::empty().await;
+        let winning = position_json("111", "25", "0.4", "1", "0")
+            .replace(r#""redeemable": false"#, r#""redeemable": true"#);
+        let losing = position_json("112", "25", "0.6", "0", "0")
+            .replace(r#""redeemable": false"#, r#""redeemable": true"#)
+            .replace("0xcond", "0xlost");
+        server
+            .mock("GET", "/positions")
+            .match_query(Matcher::Any)
+            .with_header("content-type", "application/json")
+            .with_body(format!("[{}, {}]", winning, losing))
+            .create_async()
+            .await;
+        let estimate = server
+            .mock("GET", "/gas-estimate")
             .match_query(Matcher::AllOf(vec![
-                Matcher::UrlEncoded("token_id".into(), "0x123".into()),
-                Matcher::UrlEncoded("side".into(), "BUY".into()),
+                Matcher::UrlEncoded("action".into(), "redeem".into()),
+                Matcher::UrlEncoded("condition_id".into(), "0xcond".into()),
             ]))
-            .with_status(200)
+            .match_header("poly_api_key", "test_key")
             .with_header("content-type", "application/json")
-            .with_body(mock_response)
+            .with_body(
+                r#"{"gas_units": "180000", "gas_price_gwei": "30", "total_cost_eth": "0.0054",
+                    "total_cost_usd": "0.02"}"#,
+            )
+            .expect(1)
             .create_async()
             .await;
 
-        let client = create_test_client(&server.url());
-        let result = client.get_price("0x123", Side::BUY).await;
+        let client = create_test_client_with_l2(&server.url()).with_data_api_base(&server.url());
+        let gas = client.estimate_gas_for_redemption("0xcond").await.unwrap();
+        assert_eq!(gas.gas_units, 180_000);
+        assert!(gas.is_worth_redeeming(Decimal::from_str("0.03").unwrap()));
+        assert!(!gas.is_worth_redeeming(Decimal::from_str("0.022").unwrap()));
 
-        mock.assert_async().await;
-        assert!(result.is_ok());
-        let response = result.unwrap();
-        assert_eq!(response.price, Decimal::from_str("0.76").unwrap());
+        for condition_id in ["0xother", "0xlost"] {
+            let err = client
+                .estimate_gas_for_redemption(condition_id)
+                .await
+                .unwrap_err();
+            assert!(matches!(err, PolyError::Validation { .. }));
+            assert!(err.to_string().contains("no redeemable position"));
+        }
+        estimate.assert_async().await;
     }
 
     #[tokio::test]
-    async fn test_get_tick_size_success() {
-        let mut server = Server::new_async().await;
-        let mock_response = r#"{
-            "minimum_tick_size": "0.01"
-        }"#;
-
-        let mock = server
-            .mock("GET", "/tick-size")
-            .match_query(Matcher::UrlEncoded("token_id".into(), "0x123".into()))
-            .with_status(200)
+    async fn test_funder_positions_walk_every_page() {
+        let mut server = MockExchange::empty().await;
+        let held = position_json("112", "25", "0.6", "0.5", "0");
+        let first = server
+            .mock("GET", "/positions")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("limit".into(), "500".into()),
+                Matcher::UrlEncoded("offset".into(), "0".into()),
+            ]))
             .with_header("content-type", "application/json")
-            .with_body(mock_response)
+            .with_body(format!("[{}]", vec![held; 500].join(",")))
+            .expect(1)
+            .create_async()
+            .await;
+        // The only redeemable position sits past the first page
+        let second = server
+            .mock("GET", "/positions")
+            .match_query(Matcher::UrlEncoded("offset".into(), "500".into()))
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                "[{}]",
+                position_json("111", "25", "0.4", "1", "0")
+                    .replace(r#""redeemable": false"#, r#""redeemable": true"#)
+            ))
+            .expect(1)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/gas-estimate")
+            .match_query(Matcher::Any)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"gas_units": "180000", "gas_price_gwei": "30", "total_cost_eth": "0.0054",
+                    "total_cost_usd": "0.02"}"#,
+            )
             .create_async()
             .await;
 
-        let client = create_test_client(&server.url());
-        let result = client.get_tick_size("0x123").await;
-
-        mock.assert_async().await;
-        assert!(result.is_ok());
-        let tick_size = result.unwrap();
-        assert_eq!(tick_size, Decimal::from_str("0.01").unwrap());
+        let client = create_test_client_with_l2(&server.url()).with_data_api_base(&server.url());
+        let gas = client.estimate_gas_for_redemption("0xcond").await.unwrap();
+        assert_eq!(gas.gas_units, 180_000);
+        first.assert_async().await;
+        second.assert_async().await;
     }
 
     #[tokio::test]
-    async fn test_get_neg_risk_success() {
-        let mut server = Server::new_async().await;
-        let mock_response = r#"{
-            "neg_risk": false
-        }"#;
+    async fn test_get_portfolio_value() {
+        let mut server = MockExchange::empty().await;
 
-        let mock = server
-            .mock("GET", "/neg-risk")
-            .match_query(Matcher::UrlEncoded("token_id".into(), "0x123".into()))
+        let positions_mock = server
+            .mock("GET", "/positions")
+            .match_query(Matcher::Regex("user=0x".into()))
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(mock_response)
+            .with_body(format!(
+                "[{}, {}]",
+                position_json("111", "100", "0.40", "0.50", "2"),
+                position_json("222", "50", "0.60", "0.20", "-1")
+            ))
+            .create_async()
+            .await;
+        let live_mock = server
+            .mock("GET", "/midpoint")
+            .match_query(Matcher::UrlEncoded("token_id".into(), "111".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"mid": "0.55"}"#)
+            .create_async()
+            .await;
+        // Resolved market: no book, so the Data API price is used instead
+        let resolved_mock = server
+            .mock("GET", "/midpoint")
+            .match_query(Matcher::UrlEncoded("token_id".into(), "222".into()))
+            .with_status(404)
+            .with_body(r#"{"error": "No orderbook exists for the requested token id"}"#)
             .create_async()
             .await;
 
-        let client = create_test_client(&server.url());
-        let result = client.get_neg_risk("0x123").await;
+        let client = create_test_client_with_auth(&server.url()).with_data_api_base(&server.url());
+        let portfolio = client.get_portfolio_value().await.unwrap();
 
-        mock.assert_async().await;
-        assert!(result.is_ok());
-        let neg_risk = result.unwrap();
-        assert!(!neg_risk);
+        positions_mock.assert_async().await;
+        live_mock.assert_async().await;
+        resolved_mock.assert_async().await;
+
+        assert_eq!(portfolio.positions.len(), 2);
+        assert_eq!(portfolio.positions[0].position.asset, "111");
+        assert_eq!(
+            portfolio.positions[0].current_price,
+            Decimal::from_str("0.55").unwrap()
+        );
+        assert_eq!(
+            portfolio.positions[1].current_price,
+            Decimal::from_str("0.20").unwrap()
+        );
+        assert_eq!(portfolio.total_mark_to_market, Decimal::from(65));
+        assert_eq!(portfolio.total_cost_basis, Decimal::from(70));
+        assert_eq!(portfolio.total_unrealized_pnl, Decimal::from(-5));
+        assert_eq!(portfolio.total_realized_pnl, Decimal::from(1));
+    }
+
+    #[tokio::test]
+    async fn test_get_portfolio_value_requires_signer() {
+        let client = create_test_client("https://test.example.com");
+        let err = client.get_portfolio_value().await.unwrap_err();
+        assert!(matches!(err, PolyError::Auth { .. }));
     }
 
     #[tokio::test]
     async fn test_api_error_handling() {
-        let mut server = Server::new_async().await;
+        let mut server = MockExchange::empty().await;
 
         let mock = server
             .mock("GET", "/book")
@@ -2433,7 +9996,7 @@ mod tests {
             .await;
 
         let client = create_test_client(&server.url());
-        let result = client.get_order_book("invalid_token").await;
+        let result = client.get_order_book("invalid_token", None).await;
 
         mock.assert_async().await;
         assert!(result.is_err());
@@ -2449,7 +10012,7 @@ mod tests {
     async fn test_network_error_handling() {
         // Test with invalid URL to simulate network error
         let client = create_test_client("http://invalid-host-that-does-not-exist.com");
-        let result = client.get_order_book("0x123").await;
+        let result = client.get_order_book("0x123", None).await;
 
         assert!(result.is_err());
         let error = result.unwrap_err();
@@ -2459,15 +10022,40 @@ mod tests {
     #[test]
     fn test_client_url_validation() {
         let client = create_test_client("https://test.example.com");
-        assert_eq!(client.base_url, "https://test.example.com");
+        assert_eq!(client.clob_base_url, "https://test.example.com");
 
         let client2 = create_test_client("http://localhost:8080");
-        assert_eq!(client2.base_url, "http://localhost:8080");
+        assert_eq!(client2.clob_base_url, "http://localhost:8080");
+    }
+
+    #[tokio::test]
+    async fn test_get_outcome_prices_keeps_order() {
+        let mut server = MockExchange::empty().await;
+        server
+            .mock("POST", "/midpoints")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"3": "0.25", "1": "0.50", "2": "0.35"}"#)
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let ids: Vec<String> = ["1", "2", "3"].map(String::from).to_vec();
+        let prices = client.get_outcome_prices(&ids).await.unwrap();
+        assert_eq!(
+            prices,
+            ["0.50", "0.35", "0.25"].map(|p| Decimal::from_str(p).unwrap())
+        );
+
+        let mut ids = ids;
+        ids.push("4".to_string());
+        let err = client.get_outcome_prices(&ids).await.unwrap_err();
+        assert!(matches!(err, PolyError::Validation { .. }));
     }
 
     #[tokio::test]
     async fn test_get_midpoints_batch() {
-        let mut server = Server::new_async().await;
+        let mut server = MockExchange::empty().await;
         let mock_response = r#"{
             "0x123": "0.755",
             "0x456": "0.623"
@@ -2502,7 +10090,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_get_gamma_events_success() {
-        let mut server = Server::new_async().await;
+        let mut server = MockExchange::empty().await;
         let mock_response = r#"[
             {"event_id": "evt-1", "slug": "event-one"}
         ]"#;
@@ -2526,9 +10114,59 @@ mod tests {
         assert_eq!(events[0].slug, "event-one");
     }
 
+    #[tokio::test]
+    async fn test_get_market_by_slug() {
+        let mut server = MockExchange::empty().await;
+        let found = server
+            .mock("GET", "/markets/slug/will-it-rain-tomorrow")
+            .with_status(200)
+            .with_body(
+                r#"{"conditionId": "0xrain", "slug": "will-it-rain-tomorrow",
+                    "question": "Will it rain tomorrow?", "active": true, "closed": false,
+                    "clobTokenIds": "[\"111\", \"222\"]"}"#,
+            )
+            .expect(2)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/markets/slug/no-such-market")
+            .with_status(404)
+            .with_body(r#"{"error": "not found"}"#)
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let market = client
+            .get_market_by_slug("will-it-rain-tomorrow")
+            .await
+            .unwrap();
+        assert_eq!(market.condition_id, "0xrain");
+        assert_eq!(market.market_slug, "will-it-rain-tomorrow");
+        assert_eq!(market.clob_token_ids, ["111", "222"]);
+
+        // Links pasted from the website resolve to their last segment
+        let pasted = client
+            .get_market_by_slug("https://polymarket.com/event/weather/will-it-rain-tomorrow?tid=1")
+            .await
+            .unwrap();
+        assert_eq!(pasted.condition_id, "0xrain");
+        found.assert_async().await;
+
+        let err = client
+            .get_market_by_slug("no-such-market")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, PolyError::Api { status: 404, .. }));
+        assert!(err.to_string().contains("no-such-market"));
+        assert!(matches!(
+            client.get_market_by_slug(" / ").await,
+            Err(PolyError::Validation { .. })
+        ));
+    }
+
     #[tokio::test]
     async fn test_get_gamma_event_by_slug_success() {
-        let mut server = Server::new_async().await;
+        let mut server = MockExchange::empty().await;
         let mock_response = r#"{ "event_id": "evt-2", "slug": "event-two" }"#;
 
         let mock = server
@@ -2549,9 +10187,54 @@ mod tests {
         assert_eq!(event.slug, "event-two");
     }
 
+    #[tokio::test]
+    async fn test_get_market_news() {
+        use crate::types::NewsSentiment;
+
+        let mut server = MockExchange::empty().await;
+        let mock_response = r#"{"data": [
+            {"title": "Poll moves", "url": "https://example.com/a", "source": "Wire",
+             "publishedAt": "2025-10-01T12:00:00Z", "sentiment": "positive"},
+            {"title": "Debate recap", "url": "https://example.com/b",
+             "publishedAt": "2025-10-02T08:30:00Z", "sentiment": "Negative"},
+            {"title": "Explainer", "url": "https://example.com/c", "source": "Blog",
+             "publishedAt": "2025-10-03T00:00:00Z", "sentiment": "positive"},
+            {"title": "Schedule", "url": "https://example.com/d", "source": "Wire",
+             "publishedAt": "2025-10-03T09:00:00Z"}
+        ]}"#;
+
+        let mock = server
+            .mock("GET", "/markets/0xabc/news")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(mock_response)
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let articles = client.get_market_news("0xabc").await.unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(articles.len(), 4);
+        assert_eq!(articles[1].source, "");
+        assert_eq!(articles[1].sentiment, Some(NewsSentiment::Negative));
+        assert_eq!(articles[3].sentiment, None);
+        assert_eq!(
+            articles[0].published_at.to_rfc3339(),
+            "2025-10-01T12:00:00+00:00"
+        );
+
+        // (1 - 1 + 1) / 3; the unlabelled article is skipped
+        assert_eq!(
+            news_sentiment_score(&articles),
+            Some(Decimal::ONE / Decimal::from(3))
+        );
+        assert_eq!(news_sentiment_score(&articles[3..]), None);
+    }
+
     #[tokio::test]
     async fn test_get_gamma_tags_success() {
-        let mut server = Server::new_async().await;
+        let mut server = MockExchange::empty().await;
         let mock_response = r#"[
             {"id": "tag-1", "slug": "politics", "name": "Politics"}
         ]"#;
@@ -2576,7 +10259,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_get_gamma_sports_success() {
-        let mut server = Server::new_async().await;
+        let mut server = MockExchange::empty().await;
         let mock_response = r#"[
             {"id": "sport-1", "name": "Soccer"}
         ]"#;
@@ -2612,4 +10295,92 @@ mod tests {
         assert!(auth_client.signer.is_some());
         assert_eq!(auth_client.chain_id, 137);
     }
+
+    #[test]
+    fn test_poly_facade_derives_clients_from_one_config() {
+        let config = ClientConfig {
+            base_url: "https://clob.example.com".to_string(),
+            chain_id: 80002,
+            private_key: Some(
+                "0x1234567890123456789012345678901234567890123456789012345678901234".to_string(),
+            ),
+            api_credentials: Some(ApiCredentials {
+                api_key: "test_key".to_string(),
+                secret: "dGVzdF9zZWNyZXQ=".to_string(),
+                passphrase: "test_passphrase".to_string(),
+            }),
+            max_concurrency: Some(4),
+            ..Default::default()
+        };
+        let poly = PolyFacade::new(config)
+            .unwrap()
+            .with_wss_base("wss://ws.example.com");
+
+        let clob = poly.clob();
+        assert_eq!(clob.clob_base_url(), "https://clob.example.com");
+        assert_eq!(clob.chain_id, 80002);
+        assert_eq!(clob.api_creds.as_ref().unwrap().api_key, "test_key");
+        assert_eq!(
+            clob.signer.as_ref().unwrap().address().to_string(),
+            "0x2e988A386a799F506693793c6A5AF6B54dfAaBfB"
+        );
+        assert!(clob.order_builder.is_some());
+
+        // Sibling REST clients share the in-flight cap
+        let _permit = futures::executor::block_on(
+            clob.request_limiter
+                .as_ref()
+                .unwrap()
+                .acquire(Priority::Normal),
+        );
+        assert_eq!(poly.clob().request_limiter_stats().unwrap().in_flight, 1);
+
+        assert_eq!(poly.market_ws().url(), "wss://ws.example.com/ws/market");
+        assert_eq!(
+            poly.user_ws().unwrap().url(),
+            "wss://ws.example.com/ws/user"
+        );
+
+        let anonymous = PolyFacade::new(ClientConfig::default()).unwrap();
+        assert!(anonymous.clob().signer.is_none());
+        assert!(matches!(anonymous.user_ws(), Err(PolyError::Config { .. })));
+        assert!(
+            PolyFacade::new(ClientConfig {
+                private_key: Some("not a key".to_string()),
+                ..Default::default()
+            })
+            .is_err()
+        );
+
+        // Orders from a Gnosis safe carry its address and signature type
+        let safe = "0x3f2b1c4d5e6a7b8c9d0e1f2a3b4c5d6e7f8a9b0c";
+        let funded = PolyFacade::new(ClientConfig {
+            funder: Some(safe.to_string()),
+            signature_type: Some(crate::orders::SigType::PolyGnosisSafe),
+            ..poly.config().clone()
+        })
+        .unwrap();
+        let builder = funded.clob().order_builder.unwrap();
+        assert_eq!(builder.funder(), Address::from_str(safe).unwrap());
+        assert_eq!(
+            builder.get_sig_type(),
+            crate::orders::SigType::PolyGnosisSafe as u8
+        );
+        for config in [
+            ClientConfig {
+                funder: Some(safe.to_string()),
+                ..Default::default()
+            },
+            ClientConfig {
+                signature_type: Some(crate::orders::SigType::Eoa),
+                funder: Some(safe.to_string()),
+                ..poly.config().clone()
+            },
+        ] {
+            assert!(matches!(
+                PolyFacade::new(config),
+                Err(PolyError::Config { .. })
+            ));
+        }
+    }
 }
@@ -3,8 +3,20 @@
 //! This module contains contract addresses and configuration for different
 //! networks and environments.
 
+use crate::orders::SigType;
+use alloy_primitives::{Address, B256, address, b256, keccak256};
 use std::collections::HashMap;
 
+/// Factory of the proxy wallets behind email and Magic accounts on Polygon
+const PROXY_FACTORY: Address = address!("0xaB45c5A4B0c941a2F231C04C3f49182e1A254052");
+const PROXY_INIT_CODE_HASH: B256 =
+    b256!("0xd21df8dc65880a8606f09fe0ce3df9b8869287ab0b058be05aa9e8af6330a00b");
+
+/// Factory of the Gnosis Safes behind browser-wallet accounts on Polygon
+const SAFE_FACTORY: Address = address!("0xaacFeEa03eb1561C4e67d661e40682Bd20E3541b");
+const SAFE_INIT_CODE_HASH: B256 =
+    b256!("0x2bce2127ff07fb632d16c8347c4ebf501f4841168bed00d9e6ef715ddb6fcecf");
+
 /// Contract configuration for a specific network
 #[derive(Debug, Clone)]
 pub struct ContractConfig {
@@ -51,6 +63,25 @@ pub fn get_contract_config(chain_id: u64, neg_risk: bool) -> Option<ContractConf
     }
 }
 
+/// Address of the wallet `owner` trades through under `sig_type`
+///
+/// Both wallet kinds are deployed with CREATE2, so the address is known before the
+/// wallet exists on chain: proxy wallets are salted with the packed owner address,
+/// Safes with it ABI-encoded. An EOA trades from itself. `None` for networks without
+/// Polymarket's wallet factories, which is every chain but Polygon mainnet.
+pub fn proxy_wallet_address(chain_id: u64, sig_type: SigType, owner: Address) -> Option<Address> {
+    match sig_type {
+        SigType::Eoa => Some(owner),
+        _ if chain_id != 137 => None,
+        SigType::PolyProxy => {
+            Some(PROXY_FACTORY.create2(keccak256(owner.as_slice()), PROXY_INIT_CODE_HASH))
+        }
+        SigType::PolyGnosisSafe => {
+            Some(SAFE_FACTORY.create2(keccak256(owner.into_word()), SAFE_INIT_CODE_HASH))
+        }
+    }
+}
+
 /// Network configuration
 #[derive(Debug, Clone)]
 pub struct NetworkConfig {
@@ -162,6 +193,23 @@ mod tests {
         assert!(!config.conditional_tokens.is_empty());
     }
 
+    #[test]
+    fn test_proxy_wallet_address() {
+        let owner = address!("0x1111111111111111111111111111111111111111");
+        assert_eq!(proxy_wallet_address(137, SigType::Eoa, owner), Some(owner));
+        assert_eq!(proxy_wallet_address(80002, SigType::PolyProxy, owner), None);
+
+        let proxy = proxy_wallet_address(137, SigType::PolyProxy, owner).unwrap();
+        let safe = proxy_wallet_address(137, SigType::PolyGnosisSafe, owner).unwrap();
+        assert_ne!(proxy, safe);
+        // CREATE2: last 20 bytes of keccak256(0xff ++ factory ++ salt ++ init code hash)
+        let mut preimage = vec![0xff];
+        preimage.extend_from_slice(PROXY_FACTORY.as_slice());
+        preimage.extend_from_slice(keccak256(owner.as_slice()).as_slice());
+        preimage.extend_from_slice(PROXY_INIT_CODE_HASH.as_slice());
+        assert_eq!(proxy.as_slice(), &keccak256(&preimage)[12..]);
+    }
+
     #[test]
     fn test_network_config() {
         let polygon = NetworkConfig::polygon_mainnet();
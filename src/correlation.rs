@@ -0,0 +1,227 @@
+//! Pearson correlation between tokens' daily returns
+//!
+//! [`CorrelationMatrix::from_histories`] reduces each price history to one closing price
+//! per UTC day, the last point of that day, and correlates the simple returns between
+//! consecutive days. Each pair is compared only over the days both tokens traded, so a
+//! market listed last week still gets a figure against an older one, taken over the
+//! shorter span.
+//!
+//! A token whose price never moved over the shared days has no defined correlation; it
+//! is reported as zero against everything else, which keeps it out of the top of
+//! [`most_correlated`](CorrelationMatrix::most_correlated) lists.
+
+use crate::errors::{PolyError, Result};
+use crate::volatility::PricePoint;
+use rust_decimal::{Decimal, MathematicalOps};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Pairwise return correlations of a set of tokens
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CorrelationMatrix {
+    pub token_ids: Vec<String>,
+    /// `data[i][j]` is the correlation of `token_ids[i]` with `token_ids[j]`; symmetric,
+    /// with ones on the diagonal
+    pub data: Vec<Vec<Decimal>>,
+}
+
+impl CorrelationMatrix {
+    /// Correlate `histories[i]`, the price history of `token_ids[i]`
+    ///
+    /// Points at a non-positive price are skipped. Pairs with fewer than two shared
+    /// daily returns, or where either side is flat, get zero.
+    pub fn from_histories(token_ids: &[String], histories: &[Vec<PricePoint>]) -> Result<Self> {
+        if token_ids.len() != histories.len() {
+            return Err(PolyError::validation(format!(
+                "{} token ids but {} price histories",
+                token_ids.len(),
+                histories.len()
+            )));
+        }
+
+        let closes: Vec<BTreeMap<i64, Decimal>> = histories
+            .iter()
+            .map(|history| daily_closes(history))
+            .collect();
+        let n = token_ids.len();
+        let mut data = vec![vec![Decimal::ZERO; n]; n];
+        for i in 0..n {
+            data[i][i] = Decimal::ONE;
+            for j in i + 1..n {
+                let correlation = pair_correlation(&closes[i], &closes[j])?;
+                data[i][j] = correlation;
+                data[j][i] = correlation;
+            }
+        }
+
+        Ok(Self {
+            token_ids: token_ids.to_vec(),
+            data,
+        })
+    }
+
+    /// The correlation of two tokens, if both are in the matrix
+    pub fn get(&self, a: &str, b: &str) -> Option<Decimal> {
+        let i = self.index_of(a)?;
+        let j = self.index_of(b)?;
+        Some(self.data[i][j])
+    }
+
+    /// Up to `n` other tokens, most positively correlated with `token_id` first
+    ///
+    /// Ties are broken by token ID. Empty when `token_id` isn't in the matrix.
+    pub fn most_correlated(&self, token_id: &str, n: usize) -> Vec<(String, Decimal)> {
+        let Some(i) = self.index_of(token_id) else {
+            return Vec::new();
+        };
+        let mut others: Vec<(String, Decimal)> = self
+            .token_ids
+            .iter()
+            .zip(&self.data[i])
+            .enumerate()
+            .filter(|(j, _)| *j != i)
+            .map(|(_, (other, correlation))| (other.clone(), *correlation))
+            .collect();
+        others.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        others.truncate(n);
+        others
+    }
+
+    fn index_of(&self, token_id: &str) -> Option<usize> {
+        self.token_ids.iter().position(|id| id == token_id)
+    }
+}
+
+/// Last price of each UTC day, keyed by day number
+fn daily_closes(history: &[PricePoint]) -> BTreeMap<i64, Decimal> {
+    let mut points: Vec<&PricePoint> = history
+        .iter()
+        .filter(|point| point.price > Decimal::ZERO)
+        .collect();
+    points.sort_by_key(|point| point.timestamp);
+    points
+        .into_iter()
+        .map(|point| (point.timestamp.div_euclid(SECONDS_PER_DAY), point.price))
+        .collect()
+}
+
+fn pair_correlation(a: &BTreeMap<i64, Decimal>, b: &BTreeMap<i64, Decimal>) -> Result<Decimal> {
+    let shared: Vec<(Decimal, Decimal)> = a
+        .iter()
+        .filter_map(|(day, price)| b.get(day).map(|other| (*price, *other)))
+        .collect();
+    let (x, y): (Vec<Decimal>, Vec<Decimal>) = shared
+        .windows(2)
+        .map(|pair| {
+            (
+                pair[1].0 / pair[0].0 - Decimal::ONE,
+                pair[1].1 / pair[0].1 - Decimal::ONE,
+            )
+        })
+        .unzip();
+    pearson(&x, &y)
+}
+
+/// Sample correlation of `x` and `y`; zero for fewer than two pairs or a flat side
+fn pearson(x: &[Decimal], y: &[Decimal]) -> Result<Decimal> {
+    if x.len() < 2 {
+        return Ok(Decimal::ZERO);
+    }
+    let n = Decimal::from(x.len());
+    let mean_x = x.iter().sum::<Decimal>() / n;
+    let mean_y = y.iter().sum::<Decimal>() / n;
+    let (mut covariance, mut var_x, mut var_y) = (Decimal::ZERO, Decimal::ZERO, Decimal::ZERO);
+    for (x, y) in x.iter().zip(y) {
+        covariance += (x - mean_x) * (y - mean_y);
+        var_x += (x - mean_x) * (x - mean_x);
+        var_y += (y - mean_y) * (y - mean_y);
+    }
+    if var_x.is_zero() || var_y.is_zero() {
+        return Ok(Decimal::ZERO);
+    }
+    let scale = (var_x * var_y).sqrt().ok_or_else(|| {
+        PolyError::internal_simple(format!("sqrt of {} out of range", var_x * var_y))
+    })?;
+    // The square root's last digits are noise, and can land a perfect correlation just
+    // past one
+    Ok((covariance / scale)
+        .round_dp(18)
+        .clamp(Decimal::NEGATIVE_ONE, Decimal::ONE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn daily(prices: &[Decimal]) -> Vec<PricePoint> {
+        prices
+            .iter()
+            .enumerate()
+            .map(|(day, price)| PricePoint {
+                timestamp: 1_700_006_400 + SECONDS_PER_DAY * day as i64,
+                price: *price,
+            })
+            .collect()
+    }
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn test_correlation_signs_and_ranking() {
+        let up_down = [dec!(0.40), dec!(0.44), dec!(0.40), dec!(0.48)];
+        // Same daily returns as `up_down`, from a different level
+        let scaled = [dec!(0.20), dec!(0.22), dec!(0.20), dec!(0.24)];
+        let opposite = [dec!(0.60), dec!(0.55), dec!(0.60), dec!(0.50)];
+        let flat = [dec!(0.5); 4];
+        let matrix = CorrelationMatrix::from_histories(
+            &ids(&["a", "b", "c", "d"]),
+            &[
+                daily(&up_down),
+                daily(&scaled),
+                daily(&opposite),
+                daily(&flat),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(matrix.get("a", "a"), Some(Decimal::ONE));
+        assert_eq!(matrix.get("a", "b"), Some(Decimal::ONE));
+        assert_eq!(matrix.data[0][2], matrix.data[2][0]);
+        assert!(matrix.get("a", "c").unwrap() < dec!(-0.9));
+        assert_eq!(matrix.get("a", "d"), Some(Decimal::ZERO));
+        assert_eq!(matrix.get("a", "x"), None);
+
+        let top = matrix.most_correlated("a", 2);
+        assert_eq!(
+            top,
+            [
+                ("b".to_string(), Decimal::ONE),
+                ("d".to_string(), Decimal::ZERO)
+            ]
+        );
+        assert!(matrix.most_correlated("x", 2).is_empty());
+    }
+
+    #[test]
+    fn test_pairs_use_shared_days_only() {
+        // Intraday points collapse to the day's last price; `b` starts a day later
+        let mut a = daily(&[dec!(0.5), dec!(0.1), dec!(0.5), dec!(0.6)]);
+        a.push(PricePoint {
+            timestamp: a[1].timestamp + 3600,
+            price: dec!(0.6),
+        });
+        let b = daily(&[dec!(0.9), dec!(0.3), dec!(0.4), dec!(0.3)])[1..].to_vec();
+        let matrix = CorrelationMatrix::from_histories(&ids(&["a", "b"]), &[a, b]).unwrap();
+        assert_eq!(matrix.get("a", "b"), Some(Decimal::NEGATIVE_ONE));
+
+        assert!(matches!(
+            CorrelationMatrix::from_histories(&ids(&["a"]), &[]),
+            Err(PolyError::Validation { .. })
+        ));
+    }
+}
@@ -0,0 +1,179 @@
+//! Conditional Token Framework (CTF) helpers
+//!
+//! Polymarket outcome tokens are ERC-1155 positions minted by Gnosis' ConditionalTokens
+//! contract. Their IDs are a pure function of the collateral token, the condition and the
+//! outcome slot, so they can be derived offline when the API doesn't hand us `clob_token_ids`.
+//!
+//! The derivation mirrors `CTHelpers.sol`:
+//! - `collectionId = getCollectionId(parentCollectionId, conditionId, indexSet)`, which hashes
+//!   `conditionId ++ indexSet` onto the alt_bn128 curve and compresses the resulting point
+//! - `positionId = uint256(keccak256(collateral ++ collectionId))`
+
+use crate::errors::{PolyError, Result};
+use alloy_primitives::{Address, B256, U256, keccak256};
+use std::str::FromStr;
+
+/// Field modulus of the alt_bn128 curve (`y^2 = x^3 + 3`) used by `CTHelpers`
+const ALT_BN128_P: U256 = U256::from_limbs([
+    0x3c208c16d87cfd47,
+    0x97816a916871ca8d,
+    0xb85045b68181585d,
+    0x30644e72e131a029,
+]);
+
+/// Curve constant `b` of alt_bn128
+const ALT_BN128_B: U256 = U256::from_limbs([3, 0, 0, 0]);
+
+/// Wrapped collateral minted by the NegRiskAdapter on Polygon.
+/// Neg-risk positions are collateralized by this token rather than USDC directly.
+pub const NEG_RISK_WRAPPED_COLLATERAL: &str = "0x3A3BD7bb9528E159577F7C2e685CC81A765002E2";
+
+/// Compute the CTF collection ID for an outcome subset of a condition.
+///
+/// `index_set` is the outcome bitmask: `1` for the first outcome slot, `2` for the second, etc.
+/// Only top-level collections (zero parent) are supported, which covers every Polymarket market.
+pub fn collection_id(
+    parent_collection_id: B256,
+    condition_id: B256,
+    index_set: U256,
+) -> Result<B256> {
+    if !parent_collection_id.is_zero() {
+        return Err(PolyError::validation(
+            "Nested collections (non-zero parent collection ID) are not supported",
+        ));
+    }
+
+    let mut preimage = [0u8; 64];
+    preimage[..32].copy_from_slice(condition_id.as_slice());
+    preimage[32..].copy_from_slice(&index_set.to_be_bytes::<32>());
+
+    let mut x = U256::from_be_bytes(keccak256(preimage).0);
+    let odd = x.bit(255);
+
+    // Walk x forward until x^3 + b is a quadratic residue, i.e. we land on the curve
+    let mut y;
+    loop {
+        x = x.add_mod(U256::from(1), ALT_BN128_P);
+        let yy = x
+            .mul_mod(x.mul_mod(x, ALT_BN128_P), ALT_BN128_P)
+            .add_mod(ALT_BN128_B, ALT_BN128_P);
+        y = mod_sqrt(yy);
+        if y.mul_mod(y, ALT_BN128_P) == yy {
+            break;
+        }
+    }
+
+    // The top bit of the hash picks which of the two roots we use
+    if odd != y.bit(0) {
+        y = ALT_BN128_P - y;
+    }
+
+    // Compress the point: the parity of y is stored in bit 254 of x
+    if y.bit(0) {
+        x ^= U256::from(1) << 254;
+    }
+
+    Ok(B256::from(x.to_be_bytes::<32>()))
+}
+
+/// Compute the ERC-1155 position ID for a collection backed by `collateral`
+pub fn position_id(collateral: Address, collection_id: B256) -> U256 {
+    let mut preimage = [0u8; 52];
+    preimage[..20].copy_from_slice(collateral.as_slice());
+    preimage[20..].copy_from_slice(collection_id.as_slice());
+    U256::from_be_bytes(keccak256(preimage).0)
+}
+
+/// Derive the CLOB token ID for one outcome of a binary market.
+///
+/// `outcome_index` is the position of the outcome in the market's `outcomes` list
+/// (0 = "Yes", 1 = "No" on standard markets). Neg-risk markets are collateralized by the
+/// adapter's wrapped USDC, so `neg_risk` must match the market's flag to get the right ID.
+pub fn token_id_for_outcome(
+    condition_id: &str,
+    outcome_index: usize,
+    neg_risk: bool,
+) -> Result<String> {
+    if outcome_index > 1 {
+        return Err(PolyError::validation(format!(
+            "Outcome index {} out of range for a binary market",
+            outcome_index
+        )));
+    }
+
+    let condition_id = B256::from_str(condition_id)
+        .map_err(|e| PolyError::validation(format!("Invalid condition ID: {}", e)))?;
+    let collateral = if neg_risk {
+        NEG_RISK_WRAPPED_COLLATERAL.to_string()
+    } else {
+        crate::config::get_contract_config(137, false)
+            .map(|config| config.collateral)
+            .ok_or_else(|| PolyError::config("Missing Polygon contract config"))?
+    };
+    let collateral = Address::from_str(&collateral)
+        .map_err(|e| PolyError::config(format!("Invalid collateral address: {}", e)))?;
+
+    let index_set = U256::from(1u64) << outcome_index;
+    let collection = collection_id(B256::ZERO, condition_id, index_set)?;
+    Ok(position_id(collateral, collection).to_string())
+}
+
+/// Derive both token IDs (`[outcome 0, outcome 1]`) for a binary market
+pub fn token_ids_for_condition(condition_id: &str, neg_risk: bool) -> Result<[String; 2]> {
+    Ok([
+        token_id_for_outcome(condition_id, 0, neg_risk)?,
+        token_id_for_outcome(condition_id, 1, neg_risk)?,
+    ])
+}
+
+/// Modular square root for `P ≡ 3 (mod 4)`: `a^((P + 1) / 4) mod P`.
+/// Callers must check the result squares back to `a`; non-residues yield garbage.
+fn mod_sqrt(a: U256) -> U256 {
+    let exponent = (ALT_BN128_P + U256::from(1)) >> 2;
+    a.pow_mod(exponent, ALT_BN128_P)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // "Will Donald Trump win the 2024 US Presidential Election?" (neg-risk market)
+    const TRUMP_2024_CONDITION: &str =
+        "0xdd22472e552920b8438158ea7238bfadfa4f736aa4cee91a6b86c39ead110917";
+    const TRUMP_2024_YES: &str =
+        "21742633143463906290569050155826241533067272736897614950488156847949938836455";
+    const TRUMP_2024_NO: &str =
+        "48331043336612883890938759509493159234755048973500640148014422747788308965732";
+
+    #[test]
+    fn test_field_modulus() {
+        let expected = U256::from_str(
+            "21888242871839275222246405745257275088696311157297823662689037894645226208583",
+        )
+        .unwrap();
+        assert_eq!(ALT_BN128_P, expected);
+    }
+
+    #[test]
+    fn test_token_ids_known_answer() {
+        let [yes, no] = token_ids_for_condition(TRUMP_2024_CONDITION, true).unwrap();
+        assert_eq!(yes, TRUMP_2024_YES);
+        assert_eq!(no, TRUMP_2024_NO);
+    }
+
+    #[test]
+    fn test_collateral_changes_token_id() {
+        let neg_risk = token_id_for_outcome(TRUMP_2024_CONDITION, 0, true).unwrap();
+        let standard = token_id_for_outcome(TRUMP_2024_CONDITION, 0, false).unwrap();
+        assert_ne!(neg_risk, standard);
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        assert!(token_id_for_outcome(TRUMP_2024_CONDITION, 2, true).is_err());
+        assert!(token_id_for_outcome("0x1234", 0, false).is_err());
+
+        let condition = B256::from_str(TRUMP_2024_CONDITION).unwrap();
+        assert!(collection_id(B256::repeat_byte(1), condition, U256::from(1)).is_err());
+    }
+}
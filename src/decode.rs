@@ -12,11 +12,68 @@ use serde::{Deserialize, Deserializer};
 use serde_json::Value;
 use std::str::FromStr;
 
+/// How decimal fields react to representations other than the API's canonical string
+///
+/// The CLOB documents prices and sizes as decimal strings, but some endpoints (and some
+/// deploys) send bare JSON numbers, scientific notation, or empty strings for missing
+/// optional values. Lenient mode accepts those so production traffic keeps flowing;
+/// strict mode rejects them so tests and staging catch API drift early. Neither mode
+/// invents a value for a required field that arrives as `null` or `""`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NumberMode {
+    /// Accept strings and numbers; `null`/`""` are `None` for optional fields
+    #[default]
+    Lenient,
+    /// Only accept non-empty decimal strings (`null` is still fine for optional fields)
+    Strict,
+}
+
+thread_local! {
+    static NUMBER_MODE: std::cell::Cell<NumberMode> =
+        const { std::cell::Cell::new(NumberMode::Lenient) };
+}
+
+/// Mode used by the decimal deserializers on the current thread
+pub fn number_mode() -> NumberMode {
+    NUMBER_MODE.with(|mode| mode.get())
+}
+
+/// Run `f` with the given number mode, restoring the previous mode afterwards.
+///
+/// Deserialization is synchronous, so wrapping the `serde_json` call is enough even on a
+/// multi-threaded runtime - just don't hold the scope across an `.await`.
+pub fn with_number_mode<R>(mode: NumberMode, f: impl FnOnce() -> R) -> R {
+    struct Restore(NumberMode);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            NUMBER_MODE.with(|mode| mode.set(self.0));
+        }
+    }
+
+    let _restore = Restore(NUMBER_MODE.with(|current| current.replace(mode)));
+    f()
+}
+
+/// Parse JSON bytes with an explicit number mode
+pub fn from_slice_with_mode<T: serde::de::DeserializeOwned>(
+    bytes: &[u8],
+    mode: NumberMode,
+) -> Result<T> {
+    with_number_mode(mode, || serde_json::from_slice(bytes)).map_err(PolyError::from)
+}
+
 /// Fast string to number deserializers
 pub mod deserializers {
     use super::*;
     use std::fmt::Display;
 
+    /// Deserialize a decimal string or number honoring the current [`NumberMode`]
+    ///
+    /// Same as `#[serde(with = "crate::serde_decimal")]`; kept for `deserialize_with` users.
+    pub use crate::serde_decimal::deserialize as decimal;
+    /// Optional form of [`decimal`]; see [`crate::serde_decimal::option`]
+    pub use crate::serde_decimal::option::deserialize as optional_decimal;
+
     /// Deserialize number from string or number
     pub fn number_from_string<'de, T, D>(deserializer: D) -> std::result::Result<T, D::Error>
     where
@@ -108,6 +165,100 @@ pub mod deserializers {
             None => Ok(None),
         }
     }
+
+    /// Deserialize an optional datetime written in any of the formats Gamma uses.
+    ///
+    /// `gameStartTime` shows up as RFC 3339 (`2025-10-12T17:00:00Z`), as a Postgres
+    /// timestamp (`2025-10-12 17:00:00+00`), or without any offset at all, which we read
+    /// as UTC. Empty strings and unrecognised formats become `None` rather than failing
+    /// the whole market.
+    pub fn optional_flexible_datetime<'de, D>(
+        deserializer: D,
+    ) -> std::result::Result<Option<DateTime<Utc>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let raw = Option::<String>::deserialize(deserializer)?;
+        Ok(raw.as_deref().and_then(|raw| {
+            let parsed = parse_flexible_datetime(raw);
+            if parsed.is_none() && !raw.trim().is_empty() {
+                tracing::warn!("Ignoring unrecognised datetime {:?}", raw);
+            }
+            parsed
+        }))
+    }
+
+    /// Parse a datetime in RFC 3339, Postgres (`+00` offsets), or offset-less UTC form
+    pub fn parse_flexible_datetime(raw: &str) -> Option<DateTime<Utc>> {
+        let raw = raw.trim();
+        if raw.is_empty() {
+            return None;
+        }
+        if let Ok(parsed) = DateTime::parse_from_rfc3339(raw) {
+            return Some(parsed.with_timezone(&Utc));
+        }
+
+        const WITH_OFFSET: [&str; 2] = ["%Y-%m-%d %H:%M:%S%.f%#z", "%Y-%m-%dT%H:%M:%S%.f%#z"];
+        const NAIVE: [&str; 2] = ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"];
+
+        WITH_OFFSET
+            .iter()
+            .find_map(|fmt| DateTime::parse_from_str(raw, fmt).ok())
+            .map(|parsed| parsed.with_timezone(&Utc))
+            .or_else(|| {
+                NAIVE
+                    .iter()
+                    .find_map(|fmt| chrono::NaiveDateTime::parse_from_str(raw, fmt).ok())
+                    .map(|parsed| parsed.and_utc())
+            })
+    }
+
+    /// Deserialize a list of strings that Gamma may send as an array, as a JSON-encoded
+    /// array inside a string (like `clobTokenIds`), or as null.
+    ///
+    /// Object elements are read by their `label`, `slug` or `name`, so tag lists come out
+    /// as plain names. Empty strings are dropped.
+    pub fn string_list<'de, D>(deserializer: D) -> std::result::Result<Vec<String>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let items = match serde_json::Value::deserialize(deserializer)? {
+            serde_json::Value::Null => return Ok(Vec::new()),
+            serde_json::Value::Array(items) => items,
+            serde_json::Value::String(raw) if raw.trim().is_empty() => return Ok(Vec::new()),
+            serde_json::Value::String(raw) => serde_json::from_str(&raw).unwrap_or_else(|_| {
+                // A bare value rather than an encoded array
+                vec![serde_json::Value::String(raw)]
+            }),
+            _ => return Err(serde::de::Error::custom("Expected a list of strings")),
+        };
+        let name = |item: serde_json::Value| match item {
+            serde_json::Value::String(value) => Some(value),
+            serde_json::Value::Object(fields) => ["label", "slug", "name"]
+                .iter()
+                .find_map(|key| fields.get(*key)?.as_str().map(str::to_string)),
+            _ => None,
+        };
+        Ok(items
+            .into_iter()
+            .filter_map(name)
+            .filter(|value| !value.is_empty())
+            .collect())
+    }
+
+    /// Parse a Unix epoch timestamp, in seconds or milliseconds, as sent on the websockets.
+    ///
+    /// Values below 10^11 are read as seconds (that cut-off is the year 5138 in seconds and
+    /// 1973 in millis, so the two never overlap for live data). Empty, non-numeric, and
+    /// out-of-range values are `None`.
+    pub fn parse_epoch_timestamp(raw: &str) -> Option<DateTime<Utc>> {
+        let value: i64 = raw.trim().parse().ok()?;
+        if value.unsigned_abs() < 100_000_000_000 {
+            DateTime::from_timestamp(value, 0)
+        } else {
+            DateTime::from_timestamp_millis(value)
+        }
+    }
 }
 
 /// Raw API response types for efficient parsing
@@ -124,9 +275,9 @@ pub struct RawOrderBookResponse {
 
 #[derive(Debug, Deserialize)]
 pub struct RawBookLevel {
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::serde_decimal")]
     pub price: Decimal,
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::serde_decimal")]
     pub size: Decimal,
 }
 
@@ -142,11 +293,11 @@ pub struct RawOrderResponse {
     #[serde(rename = "type")]
     pub order_type: OrderType,
     pub side: Side,
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::serde_decimal")]
     pub original_size: Decimal,
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::serde_decimal")]
     pub price: Decimal,
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::serde_decimal")]
     pub size_matched: Decimal,
     #[serde(deserialize_with = "deserializers::number_from_string")]
     pub expiration: u64,
@@ -160,9 +311,9 @@ pub struct RawTradeResponse {
     pub market: String,
     pub asset_id: String,
     pub side: Side,
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::serde_decimal")]
     pub price: Decimal,
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::serde_decimal")]
     pub size: Decimal,
     pub maker_address: String,
     pub taker_address: String,
@@ -176,13 +327,17 @@ pub struct RawMarketResponse {
     pub tokens: [RawToken; 2],
     pub active: bool,
     pub closed: bool,
+    #[serde(default)]
+    pub neg_risk: bool,
+    #[serde(default)]
+    pub neg_risk_market_id: Option<String>,
     pub question: String,
     pub description: String,
     pub category: Option<String>,
     pub end_date_iso: Option<String>,
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::serde_decimal")]
     pub minimum_order_size: Decimal,
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::serde_decimal")]
     pub minimum_tick_size: Decimal,
 }
 
@@ -337,13 +492,19 @@ impl Decoder<Market> for RawMarketResponse {
             max_incentive_spread: None,
             active: self.active,
             closed: self.closed,
+            neg_risk: self.neg_risk,
+            neg_risk_market_id: self.neg_risk_market_id.clone().filter(|id| !id.is_empty()),
             question_id: self.condition_id.clone(), // Use condition_id as fallback
             minimum_order_size: self.minimum_order_size,
             minimum_tick_size: self.minimum_tick_size,
             description: self.description.clone(),
             category: self.category.clone(),
+            category_id: None,
             end_date_iso: self.end_date_iso.clone(),
             game_start_time: None,
+            sports_market_type: None,
+            game_id: None,
+            line: None,
             question: self.question.clone(),
             market_slug: format!("market-{}", self.condition_id), // Generate a slug
             seconds_delay: Decimal::ZERO,
@@ -369,6 +530,12 @@ impl Decoder<Market> for RawMarketResponse {
             volume_1yr_clob: None,
             volume_amm: None,
             volume_clob: None,
+            spread: None,
+            icon_url: None,
+            banner_url: None,
+            category_icon_url: None,
+            resolution_source: None,
+            tags: Vec::new(),
         })
     }
 }
@@ -546,4 +713,90 @@ mod tests {
         let results: Vec<serde_json::Value> = decoder.parse_json_stream(data).unwrap();
         assert_eq!(results.len(), 2);
     }
+
+    #[derive(Debug, Deserialize)]
+    struct Ambiguous {
+        #[serde(with = "crate::serde_decimal")]
+        price: Decimal,
+        #[serde(with = "crate::serde_decimal")]
+        size: Decimal,
+        #[serde(default, with = "crate::serde_decimal::option")]
+        fee: Option<Decimal>,
+    }
+
+    const AMBIGUOUS: &str = r#"{"price": 0.52, "size": "1e1", "fee": ""}"#;
+
+    #[test]
+    fn test_lenient_number_mode() {
+        let parsed: Ambiguous =
+            from_slice_with_mode(AMBIGUOUS.as_bytes(), NumberMode::Lenient).unwrap();
+        assert_eq!(parsed.price, Decimal::from_str("0.52").unwrap());
+        assert_eq!(parsed.size, Decimal::from(10));
+        assert_eq!(parsed.fee, None);
+
+        // A missing required value is never read as zero
+        for size in ["null", r#""""#] {
+            let body = format!(r#"{{"price": "0.52", "size": {}}}"#, size);
+            let result: Result<Ambiguous> =
+                from_slice_with_mode(body.as_bytes(), NumberMode::Lenient);
+            assert!(result.is_err(), "size {} was accepted", size);
+        }
+    }
+
+    #[test]
+    fn test_strict_number_mode() {
+        let result: Result<Ambiguous> =
+            from_slice_with_mode(AMBIGUOUS.as_bytes(), NumberMode::Strict);
+        assert!(result.is_err());
+
+        let canonical = r#"{"price": "0.52", "size": "10", "fee": null}"#;
+        let parsed: Ambiguous =
+            from_slice_with_mode(canonical.as_bytes(), NumberMode::Strict).unwrap();
+        assert_eq!(parsed.size, Decimal::from(10));
+
+        // The scope is restored once parsing is done
+        assert_eq!(number_mode(), NumberMode::Lenient);
+    }
+
+    #[test]
+    fn test_parse_flexible_datetime() {
+        let expected = DateTime::parse_from_rfc3339("2025-10-12T17:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        for raw in [
+            "2025-10-12T17:00:00Z",
+            "2025-10-12T19:00:00+02:00",
+            "2025-10-12 17:00:00+00",
+            "2025-10-12 17:00:00.000+00:00",
+            "2025-10-12 17:00:00",
+            "2025-10-12T17:00:00",
+        ] {
+            assert_eq!(
+                deserializers::parse_flexible_datetime(raw),
+                Some(expected),
+                "{}",
+                raw
+            );
+        }
+
+        assert_eq!(deserializers::parse_flexible_datetime(""), None);
+        assert_eq!(deserializers::parse_flexible_datetime("next sunday"), None);
+    }
+
+    #[test]
+    fn test_parse_epoch_timestamp_units() {
+        let expected = DateTime::from_timestamp_millis(1_757_908_892_351).unwrap();
+        assert_eq!(
+            deserializers::parse_epoch_timestamp("1757908892351"),
+            Some(expected)
+        );
+        assert_eq!(
+            deserializers::parse_epoch_timestamp(" 1757908892 "),
+            DateTime::from_timestamp(1_757_908_892, 0)
+        );
+        for raw in ["", "soon", "99999999999999999999", "9223372036854775807"] {
+            assert_eq!(deserializers::parse_epoch_timestamp(raw), None, "{}", raw);
+        }
+    }
 }
@@ -4,7 +4,7 @@
 //! for clear error handling in trading environments where fast error recovery
 //! is critical.
 
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
 /// Main error type for the Polymarket client
@@ -73,6 +73,10 @@ pub enum PolyError {
         retry_after: Option<Duration>,
     },
 
+    /// Requests refused by an open circuit breaker without being sent
+    #[error("Circuit open: requests are paused after repeated failures")]
+    CircuitOpen { retry_after: Instant },
+
     /// WebSocket/streaming errors
     #[error("Stream error: {message}")]
     Stream {
@@ -179,6 +183,9 @@ impl PolyError {
                 retry_after.or(Some(Duration::from_secs(1)))
             }
             PolyError::Stream { .. } => Some(Duration::from_millis(250)),
+            PolyError::CircuitOpen { retry_after } => {
+                Some(retry_after.saturating_duration_since(Instant::now()))
+            }
             _ => None,
         }
     }
@@ -208,6 +215,7 @@ impl PolyError {
             PolyError::Parse { .. } => "parse",
             PolyError::Timeout { .. } => "timeout",
             PolyError::RateLimit { .. } => "rate_limit",
+            PolyError::CircuitOpen { .. } => "circuit_open",
             PolyError::Stream { .. } => "stream",
             PolyError::Validation { .. } => "validation",
             PolyError::Internal { .. } => "internal",
@@ -350,6 +358,23 @@ impl From<serde_json::Error> for PolyError {
     }
 }
 
+#[cfg(feature = "csv")]
+impl From<csv::Error> for PolyError {
+    fn from(err: csv::Error) -> Self {
+        PolyError::internal(format!("CSV error: {}", err), err)
+    }
+}
+
+#[cfg(feature = "parquet")]
+impl From<parquet::errors::ParquetError> for PolyError {
+    fn from(err: parquet::errors::ParquetError) -> Self {
+        PolyError::Parse {
+            message: format!("Parquet error: {}", err),
+            source: Some(Box::new(err)),
+        }
+    }
+}
+
 impl From<url::ParseError> for PolyError {
     fn from(err: url::ParseError) -> Self {
         PolyError::config(format!("Invalid URL: {}", err))
@@ -421,6 +446,9 @@ impl Clone for PolyError {
                 message: message.clone(),
                 retry_after: *retry_after,
             },
+            PolyError::CircuitOpen { retry_after } => PolyError::CircuitOpen {
+                retry_after: *retry_after,
+            },
             PolyError::Stream { message, kind } => PolyError::Stream {
                 message: message.clone(),
                 kind: kind.clone(),
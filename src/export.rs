@@ -0,0 +1,62 @@
+//! Bulk export helpers for market and account datasets
+//!
+//! Each format lives behind its own feature flag so the default build doesn't
+//! pull in heavy encoders.
+
+use crate::errors::{PolyError, Result};
+use crate::types::Market;
+
+#[cfg(feature = "csv")]
+pub mod csv;
+#[cfg(feature = "parquet")]
+pub mod parquet;
+
+#[cfg(feature = "parquet")]
+pub use self::parquet::{
+    MARKET_SCHEMA_VERSION, read_markets_from_parquet, write_markets_to_parquet,
+};
+
+/// Encoding of a bulk market snapshot download
+///
+/// `Json` is passed through from the API as-is; `Csv` and `Parquet` are transcoded from
+/// it on the client and need the matching feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SnapshotFormat {
+    Json,
+    Csv,
+    Parquet,
+}
+
+impl SnapshotFormat {
+    /// Fail with a config error when this build lacks the format's feature
+    pub(crate) fn ensure_available(self) -> Result<()> {
+        match self {
+            Self::Csv if !cfg!(feature = "csv") => Err(self.unavailable("csv")),
+            Self::Parquet if !cfg!(feature = "parquet") => Err(self.unavailable("parquet")),
+            _ => Ok(()),
+        }
+    }
+
+    /// Encode `markets` in this format
+    pub(crate) fn encode_markets(self, markets: &[Market]) -> Result<Vec<u8>> {
+        let mut out = Vec::new();
+        match self {
+            Self::Json => serde_json::to_writer(&mut out, markets)?,
+            #[cfg(feature = "csv")]
+            Self::Csv => {
+                csv::write_markets_csv(markets, &mut out, false)?;
+            }
+            #[cfg(not(feature = "csv"))]
+            Self::Csv => return Err(self.unavailable("csv")),
+            #[cfg(feature = "parquet")]
+            Self::Parquet => parquet::write_markets_to_parquet(markets, &mut out)?,
+            #[cfg(not(feature = "parquet"))]
+            Self::Parquet => return Err(self.unavailable("parquet")),
+        }
+        Ok(out)
+    }
+
+    fn unavailable(self, feature: &str) -> PolyError {
+        PolyError::config(format!("{self:?} snapshots need the `{feature}` feature"))
+    }
+}
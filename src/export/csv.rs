@@ -0,0 +1,264 @@
+//! Spreadsheet-friendly CSV dumps of trade history, positions and market listings
+//!
+//! Output is UTF-8. With `excel_compat` set, a byte order mark is written first so
+//! Excel detects the encoding instead of mangling non-ASCII outcome and market names.
+//!
+//! Trade rows carry signed cash flows: `gross_proceeds` is `price * size`, positive
+//! for sells and negative for buys, and `net_proceeds` is `gross_proceeds - fee`.
+//! Each row is the user's own leg, so a fill against a resting order carries that
+//! order's side, matched size and price rather than the taker's.
+
+use crate::errors::{PolyError, Result};
+use crate::pnl::HistoricalTrade;
+use crate::types::{DataPosition, Market, Side};
+use rust_decimal::Decimal;
+use std::io::Write;
+
+/// Column order of [`write_trades_csv`]
+pub const TRADE_HEADERS: [&str; 9] = [
+    "trade_id",
+    "token_id",
+    "side",
+    "price",
+    "size",
+    "timestamp",
+    "fee",
+    "gross_proceeds",
+    "net_proceeds",
+];
+
+/// Column order of [`write_positions_csv`]
+pub const POSITION_HEADERS: [&str; 12] = [
+    "token_id",
+    "condition_id",
+    "title",
+    "outcome",
+    "size",
+    "avg_price",
+    "cur_price",
+    "initial_value",
+    "current_value",
+    "cash_pnl",
+    "realized_pnl",
+    "redeemable",
+];
+
+/// Column order of [`write_markets_csv`]; tokens are flattened to `token0_*` / `token1_*`
+pub const MARKET_HEADERS: [&str; 16] = [
+    "condition_id",
+    "question",
+    "market_slug",
+    "category",
+    "active",
+    "closed",
+    "neg_risk",
+    "end_date_iso",
+    "minimum_tick_size",
+    "minimum_order_size",
+    "token0_id",
+    "token0_outcome",
+    "token1_id",
+    "token1_outcome",
+    "liquidity_num",
+    "volume_num",
+];
+
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+fn csv_writer<W: Write>(mut writer: W, excel_compat: bool) -> Result<csv::Writer<W>> {
+    if excel_compat {
+        writer
+            .write_all(UTF8_BOM)
+            .map_err(|e| PolyError::internal("Failed to write CSV", e))?;
+    }
+    Ok(csv::Writer::from_writer(writer))
+}
+
+fn finish<W: Write>(mut writer: csv::Writer<W>, rows: u64) -> Result<u64> {
+    writer
+        .flush()
+        .map_err(|e| PolyError::internal("Failed to write CSV", e))?;
+    Ok(rows)
+}
+
+/// Write one row per trade, oldest first, and return the number of rows
+pub(crate) fn write_trades_csv<W: Write>(
+    trades: &mut [HistoricalTrade],
+    writer: W,
+    excel_compat: bool,
+) -> Result<u64> {
+    trades.sort_by(|a, b| a.match_time.cmp(&b.match_time).then(a.id.cmp(&b.id)));
+
+    let mut writer = csv_writer(writer, excel_compat)?;
+    writer.write_record(TRADE_HEADERS)?;
+    let mut rows = 0;
+    for trade in trades.iter() {
+        let fee = trade.fee();
+        let notional = trade.price * trade.size;
+        let gross = match trade.side {
+            Side::BUY => -notional,
+            Side::SELL => notional,
+        };
+        writer.write_record([
+            trade.id.clone(),
+            trade.asset_id.clone(),
+            trade.side.as_str().to_string(),
+            trade.price.to_string(),
+            trade.size.to_string(),
+            trade.time().to_rfc3339(),
+            fee.normalize().to_string(),
+            gross.normalize().to_string(),
+            (gross - fee).normalize().to_string(),
+        ])?;
+        rows += 1;
+    }
+    finish(writer, rows)
+}
+
+/// Write one row per position, in the order given, and return the number of rows
+pub(crate) fn write_positions_csv<W: Write>(
+    positions: &[DataPosition],
+    writer: W,
+    excel_compat: bool,
+) -> Result<u64> {
+    let decimal = |value: Decimal| value.normalize().to_string();
+
+    let mut writer = csv_writer(writer, excel_compat)?;
+    writer.write_record(POSITION_HEADERS)?;
+    let mut rows = 0;
+    for position in positions {
+        writer.write_record([
+            position.asset.clone(),
+            position.condition_id.clone(),
+            position.title.clone().unwrap_or_default(),
+            position.outcome.clone().unwrap_or_default(),
+            decimal(position.size),
+            decimal(position.avg_price),
+            decimal(position.cur_price),
+            decimal(position.initial_value),
+            decimal(position.current_value),
+            decimal(position.cash_pnl),
+            decimal(position.realized_pnl),
+            position.redeemable.to_string(),
+        ])?;
+        rows += 1;
+    }
+    finish(writer, rows)
+}
+
+/// Write one row per market, in the order given, and return the number of rows
+///
+/// Missing optional values are left as empty cells.
+pub(crate) fn write_markets_csv<W: Write>(
+    markets: &[Market],
+    writer: W,
+    excel_compat: bool,
+) -> Result<u64> {
+    let decimal = |value: Option<Decimal>| {
+        value
+            .map(|value| value.normalize().to_string())
+            .unwrap_or_default()
+    };
+
+    let mut writer = csv_writer(writer, excel_compat)?;
+    writer.write_record(MARKET_HEADERS)?;
+    let mut rows = 0;
+    for market in markets {
+        let [yes, no] = &market.tokens;
+        writer.write_record([
+            market.condition_id.clone(),
+            market.question.clone(),
+            market.market_slug.clone(),
+            market.category.clone().unwrap_or_default(),
+            market.active.to_string(),
+            market.closed.to_string(),
+            market.neg_risk.to_string(),
+            market.end_date_iso.clone().unwrap_or_default(),
+            decimal(Some(market.minimum_tick_size)),
+            decimal(Some(market.minimum_order_size)),
+            yes.token_id.clone(),
+            yes.outcome.clone(),
+            no.token_id.clone(),
+            no.outcome.clone(),
+            decimal(market.liquidity_num),
+            decimal(market.volume_num),
+        ])?;
+        rows += 1;
+    }
+    finish(writer, rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(id: &str, side: Side, match_time: u64) -> HistoricalTrade {
+        let record: crate::pnl::TradeRecord = serde_json::from_value(serde_json::json!({
+            "id": id,
+            "asset_id": "111",
+            "market": "0xabc",
+            "outcome": "Sí",
+            "side": side.as_str(),
+            "size": "10",
+            "price": "0.40",
+            "fee_rate_bps": "100",
+            "match_time": match_time.to_string(),
+        }))
+        .unwrap();
+        record.user_legs("", None).remove(0)
+    }
+
+    #[test]
+    fn test_trades_csv_rows_and_proceeds() {
+        let mut trades = vec![
+            trade("t2", Side::SELL, 1_700_000_100),
+            trade("t1", Side::BUY, 1_700_000_000),
+        ];
+        let mut out = Vec::new();
+        let rows = write_trades_csv(&mut trades, &mut out, false).unwrap();
+        assert_eq!(rows, 2);
+
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], TRADE_HEADERS.join(","));
+        assert_eq!(
+            lines[1],
+            "t1,111,BUY,0.40,10,2023-11-14T22:13:20+00:00,0.04,-4,-4.04"
+        );
+        assert!(lines[2].starts_with("t2,111,SELL,"));
+        assert!(lines[2].ends_with(",0.04,4,3.96"));
+    }
+
+    #[test]
+    fn test_excel_compat_prefixes_bom() {
+        let position: DataPosition = serde_json::from_value(serde_json::json!({
+            "proxyWallet": "0x1",
+            "asset": "111",
+            "conditionId": "0xabc",
+            "size": "5",
+            "avgPrice": "0.5",
+            "initialValue": "2.5",
+            "currentValue": "3",
+            "cashPnl": "0.5",
+            "percentPnl": "20",
+            "totalBought": "5",
+            "realizedPnl": "0",
+            "percentRealizedPnl": "0",
+            "curPrice": "0.6",
+            "redeemable": false,
+            "mergeable": false,
+            "title": "Élection, \"2028\"",
+            "outcome": "Sí"
+        }))
+        .unwrap();
+
+        let mut out = Vec::new();
+        let rows = write_positions_csv(&[position], &mut out, true).unwrap();
+        assert_eq!(rows, 1);
+        assert!(out.starts_with(UTF8_BOM));
+
+        let text = std::str::from_utf8(&out[UTF8_BOM.len()..]).unwrap();
+        let row = text.lines().nth(1).unwrap();
+        assert!(row.starts_with("111,0xabc,\"Élection, \"\"2028\"\"\",Sí,5,"));
+    }
+}
@@ -0,0 +1,857 @@
+//! Columnar storage for [`Market`] snapshots using Apache Parquet
+//!
+//! The schema is static and mirrors the `Market` struct field by field:
+//! - `Decimal` → `BYTE_ARRAY` annotated `DECIMAL(38, 18)`
+//! - `bool` → `BOOLEAN`
+//! - `u32` → `INT32` annotated `UINT_32`
+//! - `String` → `BYTE_ARRAY` annotated `UTF8`
+//! - `DateTime<Utc>` → `INT64` annotated `TIMESTAMP(MICROS, true)`
+//! - `tokens` is flattened into `token0_*` / `token1_*` columns
+//! - `rewards` is a nested group, with `rates` kept as a JSON string
+//!
+//! The schema version is stored in the file's key/value metadata under
+//! [`SCHEMA_VERSION_KEY`]. Files from any earlier version still read: columns are
+//! looked up by name, a column the file predates reads as null (an empty list, or
+//! `false` for a flag), and a column whose type changed is read in either form. Files
+//! from a newer version are refused.
+
+use crate::errors::{PolyError, Result};
+use crate::types::{Market, Rewards, Token};
+use bytes::Bytes;
+use parquet::basic::Compression;
+use parquet::column::writer::ColumnWriterImpl;
+use parquet::data_type::{BoolType, ByteArray, ByteArrayType, DataType, Int32Type, Int64Type};
+use parquet::file::metadata::KeyValue;
+use parquet::file::properties::WriterProperties;
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::file::writer::SerializedFileWriter;
+use parquet::record::{Field, Row};
+use parquet::schema::parser::parse_message_type;
+use parquet::schema::types::SchemaDescriptor;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::io::{Read, Seek, Write};
+use std::sync::Arc;
+
+/// Version of the market schema written by this module
+pub const MARKET_SCHEMA_VERSION: &str = "11";
+
+/// File metadata key holding [`MARKET_SCHEMA_VERSION`]
+pub const SCHEMA_VERSION_KEY: &str = "polysqueeze.market_schema_version";
+
+/// Fixed scale used for every decimal column
+const DECIMAL_SCALE: u32 = 18;
+
+/// Largest unscaled value that fits in `DECIMAL(38, _)`
+const DECIMAL_MAX_UNSCALED: i128 = 10i128.pow(38) - 1;
+
+/// Leaf columns must stay in the same order as [`market_columns`]
+const MARKET_SCHEMA: &str = "
+message market {
+    required binary condition_id (UTF8);
+    required binary token0_id (UTF8);
+    required binary token0_outcome (UTF8);
+    required binary token1_id (UTF8);
+    required binary token1_outcome (UTF8);
+    required group clob_token_ids (LIST) {
+        repeated group list {
+            required binary element (UTF8);
+        }
+    }
+    required group rewards {
+        optional binary rates (UTF8);
+        required binary min_size (DECIMAL(38, 18));
+        required binary max_spread (DECIMAL(38, 18));
+        optional binary event_start_date (UTF8);
+        optional binary event_end_date (UTF8);
+        optional binary in_game_multiplier (DECIMAL(38, 18));
+        optional binary reward_epoch (DECIMAL(38, 18));
+    }
+    optional binary min_incentive_size (DECIMAL(38, 18));
+    optional binary max_incentive_spread (DECIMAL(38, 18));
+    required boolean active;
+    required boolean closed;
+    required boolean neg_risk;
+    optional binary neg_risk_market_id (UTF8);
+    required binary question_id (UTF8);
+    required binary minimum_order_size (DECIMAL(38, 18));
+    required binary minimum_tick_size (DECIMAL(38, 18));
+    required binary description (UTF8);
+    optional binary category (UTF8);
+    optional int32 category_id (UINT_32);
+    optional binary end_date_iso (UTF8);
+    optional int64 game_start_time (TIMESTAMP(MICROS, true));
+    optional binary sports_market_type (UTF8);
+    optional binary game_id (UTF8);
+    optional binary line (DECIMAL(38, 18));
+    required binary question (UTF8);
+    required binary market_slug (UTF8);
+    required binary seconds_delay (DECIMAL(38, 18));
+    required binary icon (UTF8);
+    required binary fpmm (UTF8);
+    optional binary liquidity (UTF8);
+    optional binary liquidity_num (DECIMAL(38, 18));
+    optional binary liquidity_amm (DECIMAL(38, 18));
+    optional binary liquidity_clob (DECIMAL(38, 18));
+    optional binary volume (UTF8);
+    optional binary volume_num (DECIMAL(38, 18));
+    optional binary volume_24hr (DECIMAL(38, 18));
+    optional binary volume_1wk (DECIMAL(38, 18));
+    optional binary volume_1mo (DECIMAL(38, 18));
+    optional binary volume_1yr (DECIMAL(38, 18));
+    optional binary volume_24hr_amm (DECIMAL(38, 18));
+    optional binary volume_1wk_amm (DECIMAL(38, 18));
+    optional binary volume_1mo_amm (DECIMAL(38, 18));
+    optional binary volume_1yr_amm (DECIMAL(38, 18));
+    optional binary volume_24hr_clob (DECIMAL(38, 18));
+    optional binary volume_1wk_clob (DECIMAL(38, 18));
+    optional binary volume_1mo_clob (DECIMAL(38, 18));
+    optional binary volume_1yr_clob (DECIMAL(38, 18));
+    optional binary volume_amm (DECIMAL(38, 18));
+    optional binary volume_clob (DECIMAL(38, 18));
+    optional binary spread (DECIMAL(38, 18));
+    optional binary icon_url (UTF8);
+    optional binary banner_url (UTF8);
+    optional binary category_icon_url (UTF8);
+    optional binary resolution_source (UTF8);
+    required group tags (LIST) {
+        repeated group list {
+            required binary element (UTF8);
+        }
+    }
+}
+";
+
+/// Column-major values for one leaf of the schema
+enum Column {
+    Utf8(Vec<Option<String>>),
+    Decimal(Vec<Option<Decimal>>),
+    Bool(Vec<bool>),
+    UInt32(Vec<Option<u32>>),
+    TimestampMicros(Vec<Option<i64>>),
+    Utf8List(Vec<Vec<String>>),
+}
+
+/// Write `markets` as a single row group Parquet file
+pub fn write_markets_to_parquet(markets: &[Market], writer: impl Write) -> Result<()> {
+    write_columns(
+        MARKET_SCHEMA,
+        MARKET_SCHEMA_VERSION,
+        market_columns(markets)?,
+        writer,
+    )
+}
+
+fn write_columns(
+    schema: &str,
+    version: &str,
+    columns: Vec<Column>,
+    mut writer: impl Write,
+) -> Result<()> {
+    let schema = Arc::new(parse_message_type(schema)?);
+    let props = Arc::new(
+        WriterProperties::builder()
+            .set_compression(Compression::UNCOMPRESSED)
+            .set_key_value_metadata(Some(vec![KeyValue::new(
+                SCHEMA_VERSION_KEY.to_string(),
+                version.to_string(),
+            )]))
+            .build(),
+    );
+
+    if SchemaDescriptor::new(schema.clone()).num_columns() != columns.len() {
+        return Err(PolyError::internal_simple(
+            "Parquet schema is out of sync with Market columns",
+        ));
+    }
+
+    // SerializedFileWriter needs `Write + Send`; buffer so callers can pass any writer
+    let mut buffer = Vec::new();
+    let mut file_writer = SerializedFileWriter::new(&mut buffer, schema, props)?;
+    let mut row_group = file_writer.next_row_group()?;
+
+    for column in columns {
+        let mut column_writer = row_group
+            .next_column()?
+            .ok_or_else(|| PolyError::internal_simple("Parquet row group ran out of columns"))?;
+
+        match column {
+            Column::Utf8(values) => write_optional(
+                column_writer.typed::<ByteArrayType>(),
+                values
+                    .into_iter()
+                    .map(|v| v.map(|s| ByteArray::from(s.into_bytes())))
+                    .collect(),
+            )?,
+            Column::Decimal(values) => write_optional(
+                column_writer.typed::<ByteArrayType>(),
+                values
+                    .into_iter()
+                    .map(|v| v.map(decimal_to_bytes).transpose())
+                    .collect::<Result<_>>()?,
+            )?,
+            Column::UInt32(values) => write_optional(
+                column_writer.typed::<Int32Type>(),
+                // UINT_32 is stored in the INT32 physical type bit for bit
+                values.into_iter().map(|v| v.map(|n| n as i32)).collect(),
+            )?,
+            Column::TimestampMicros(values) => {
+                write_optional(column_writer.typed::<Int64Type>(), values)?
+            }
+            Column::Bool(values) => {
+                column_writer
+                    .typed::<BoolType>()
+                    .write_batch(&values, None, None)?;
+            }
+            Column::Utf8List(lists) => {
+                let mut values = Vec::new();
+                let mut def_levels = Vec::new();
+                let mut rep_levels = Vec::new();
+                for list in lists {
+                    if list.is_empty() {
+                        def_levels.push(0);
+                        rep_levels.push(0);
+                        continue;
+                    }
+                    for (i, item) in list.into_iter().enumerate() {
+                        values.push(ByteArray::from(item.into_bytes()));
+                        def_levels.push(1);
+                        rep_levels.push(if i == 0 { 0 } else { 1 });
+                    }
+                }
+                column_writer.typed::<ByteArrayType>().write_batch(
+                    &values,
+                    Some(&def_levels),
+                    Some(&rep_levels),
+                )?;
+            }
+        }
+
+        column_writer.close()?;
+    }
+
+    row_group.close()?;
+    file_writer.close()?;
+
+    writer
+        .write_all(&buffer)
+        .map_err(|e| PolyError::internal("Failed to write parquet output", e))
+}
+
+/// Read markets previously written by [`write_markets_to_parquet`]
+pub fn read_markets_from_parquet(mut reader: impl Read + Seek) -> Result<Vec<Market>> {
+    let mut buffer = Vec::new();
+    reader
+        .read_to_end(&mut buffer)
+        .map_err(|e| PolyError::internal("Failed to read parquet input", e))?;
+
+    let file_reader = SerializedFileReader::new(Bytes::from(buffer))?;
+
+    let version = file_reader
+        .metadata()
+        .file_metadata()
+        .key_value_metadata()
+        .and_then(|kv| kv.iter().find(|entry| entry.key == SCHEMA_VERSION_KEY))
+        .and_then(|entry| entry.value.clone());
+    let supported = version
+        .as_deref()
+        .and_then(|version| version.parse::<u32>().ok())
+        .is_some_and(|version| {
+            (1..=MARKET_SCHEMA_VERSION.parse().unwrap_or_default()).contains(&version)
+        });
+    if !supported {
+        return Err(PolyError::parse(
+            format!(
+                "Unsupported market schema version {:?}, expected 1 to {}",
+                version, MARKET_SCHEMA_VERSION
+            ),
+            None,
+        ));
+    }
+
+    file_reader
+        .get_row_iter(None)?
+        .map(|row| market_from_row(row?))
+        .collect()
+}
+
+/// Flatten markets into one column per schema leaf, in schema order
+fn market_columns(markets: &[Market]) -> Result<Vec<Column>> {
+    let utf8 = |f: fn(&Market) -> Option<String>| Column::Utf8(markets.iter().map(f).collect());
+    let decimal =
+        |f: fn(&Market) -> Option<Decimal>| Column::Decimal(markets.iter().map(f).collect());
+    let boolean = |f: fn(&Market) -> bool| Column::Bool(markets.iter().map(f).collect());
+
+    let rates = markets
+        .iter()
+        .map(|m| {
+            m.rewards
+                .rates
+                .as_ref()
+                .map(serde_json::to_string)
+                .transpose()
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    Ok(vec![
+        utf8(|m| Some(m.condition_id.clone())),
+        utf8(|m| Some(m.tokens[0].token_id.clone())),
+        utf8(|m| Some(m.tokens[0].outcome.clone())),
+        utf8(|m| Some(m.tokens[1].token_id.clone())),
+        utf8(|m| Some(m.tokens[1].outcome.clone())),
+        Column::Utf8List(markets.iter().map(|m| m.clob_token_ids.clone()).collect()),
+        Column::Utf8(rates),
+        decimal(|m| Some(m.rewards.min_size)),
+        decimal(|m| Some(m.rewards.max_spread)),
+        utf8(|m| m.rewards.event_start_date.clone()),
+        utf8(|m| m.rewards.event_end_date.clone()),
+        decimal(|m| m.rewards.in_game_multiplier),
+        decimal(|m| m.rewards.reward_epoch),
+        decimal(|m| m.min_incentive_size),
+        decimal(|m| m.max_incentive_spread),
+        boolean(|m| m.active),
+        boolean(|m| m.closed),
+        boolean(|m| m.neg_risk),
+        utf8(|m| m.neg_risk_market_id.clone()),
+        utf8(|m| Some(m.question_id.clone())),
+        decimal(|m| Some(m.minimum_order_size)),
+        decimal(|m| Some(m.minimum_tick_size)),
+        utf8(|m| Some(m.description.clone())),
+        utf8(|m| m.category.clone()),
+        Column::UInt32(markets.iter().map(|m| m.category_id).collect()),
+        utf8(|m| m.end_date_iso.clone()),
+        Column::TimestampMicros(
+            markets
+                .iter()
+                .map(|m| m.game_start_time.map(|start| start.timestamp_micros()))
+                .collect(),
+        ),
+        utf8(|m| m.sports_market_type.clone()),
+        utf8(|m| m.game_id.clone()),
+        decimal(|m| m.line),
+        utf8(|m| Some(m.question.clone())),
+        utf8(|m| Some(m.market_slug.clone())),
+        decimal(|m| Some(m.seconds_delay)),
+        utf8(|m| Some(m.icon.clone())),
+        utf8(|m| Some(m.fpmm.clone())),
+        utf8(|m| m.liquidity.clone()),
+        decimal(|m| m.liquidity_num),
+        decimal(|m| m.liquidity_amm),
+        decimal(|m| m.liquidity_clob),
+        utf8(|m| m.volume.clone()),
+        decimal(|m| m.volume_num),
+        decimal(|m| m.volume_24hr),
+        decimal(|m| m.volume_1wk),
+        decimal(|m| m.volume_1mo),
+        decimal(|m| m.volume_1yr),
+        decimal(|m| m.volume_24hr_amm),
+        decimal(|m| m.volume_1wk_amm),
+        decimal(|m| m.volume_1mo_amm),
+        decimal(|m| m.volume_1yr_amm),
+        decimal(|m| m.volume_24hr_clob),
+        decimal(|m| m.volume_1wk_clob),
+        decimal(|m| m.volume_1mo_clob),
+        decimal(|m| m.volume_1yr_clob),
+        decimal(|m| m.volume_amm),
+        decimal(|m| m.volume_clob),
+        decimal(|m| m.spread),
+        utf8(|m| m.icon_url.clone()),
+        utf8(|m| m.banner_url.clone()),
+        utf8(|m| m.category_icon_url.clone()),
+        utf8(|m| m.resolution_source.clone()),
+        Column::Utf8List(markets.iter().map(|m| m.tags.clone()).collect()),
+    ])
+}
+
+/// Write a flat column, emitting definition levels only when the leaf is optional
+fn write_optional<T: DataType>(
+    writer: &mut ColumnWriterImpl<'_, T>,
+    values: Vec<Option<T::T>>,
+) -> Result<()> {
+    let optional = writer.get_descriptor().max_def_level() > 0;
+    let def_levels: Vec<i16> = values.iter().map(|v| i16::from(v.is_some())).collect();
+    let present: Vec<T::T> = values.into_iter().flatten().collect();
+
+    writer.write_batch(&present, optional.then_some(&def_levels[..]), None)?;
+    Ok(())
+}
+
+/// Encode a decimal as a big-endian two's complement integer at [`DECIMAL_SCALE`]
+fn decimal_to_bytes(value: Decimal) -> Result<ByteArray> {
+    let value = value.round_dp(DECIMAL_SCALE);
+    let unscaled = value
+        .mantissa()
+        .checked_mul(10i128.pow(DECIMAL_SCALE - value.scale()))
+        .filter(|unscaled| unscaled.abs() <= DECIMAL_MAX_UNSCALED)
+        .ok_or_else(|| {
+            PolyError::validation(format!("Decimal {} does not fit DECIMAL(38, 18)", value))
+        })?;
+
+    Ok(ByteArray::from(unscaled.to_be_bytes().to_vec()))
+}
+
+fn decimal_from_field(field: &parquet::data_type::Decimal) -> Result<Decimal> {
+    let bytes = field.data();
+    if bytes.is_empty() || bytes.len() > 16 {
+        return Err(PolyError::parse("Invalid parquet decimal width", None));
+    }
+
+    // Sign-extend the big-endian bytes into an i128
+    let fill = if bytes[0] & 0x80 != 0 { 0xff } else { 0x00 };
+    let mut buf = [fill; 16];
+    buf[16 - bytes.len()..].copy_from_slice(bytes);
+
+    // Strip trailing zeros first so large values still fit rust_decimal's 96-bit mantissa
+    let mut unscaled = i128::from_be_bytes(buf);
+    let mut scale = field.scale() as u32;
+    while scale > 0 && unscaled != 0 && unscaled % 10 == 0 {
+        unscaled /= 10;
+        scale -= 1;
+    }
+
+    Decimal::try_from_i128_with_scale(unscaled, scale)
+        .map_err(|e| PolyError::parse(format!("Decimal out of range: {}", e), None))
+}
+
+fn market_from_row(row: Row) -> Result<Market> {
+    let mut fields = RowFields::new(row);
+    let mut rewards = match fields.take("rewards")? {
+        Field::Group(group) => RowFields::new(group),
+        other => return Err(unexpected("rewards", &other)),
+    };
+
+    let condition_id = fields.string("condition_id")?;
+    let tokens = [
+        Token {
+            token_id: fields.string("token0_id")?,
+            outcome: fields.string("token0_outcome")?,
+        },
+        Token {
+            token_id: fields.string("token1_id")?,
+            outcome: fields.string("token1_outcome")?,
+        },
+    ];
+    let clob_token_ids = fields.string_list("clob_token_ids")?;
+
+    let rewards = Rewards {
+        rates: rewards
+            .opt_string("rates")?
+            .map(|raw| serde_json::from_str(&raw))
+            .transpose()?,
+        min_size: rewards.decimal("min_size")?,
+        max_spread: rewards.decimal("max_spread")?,
+        event_start_date: rewards.opt_string("event_start_date")?,
+        event_end_date: rewards.opt_string("event_end_date")?,
+        in_game_multiplier: rewards.opt_decimal("in_game_multiplier")?,
+        reward_epoch: rewards.opt_decimal("reward_epoch")?,
+    };
+
+    Ok(Market {
+        condition_id,
+        tokens,
+        clob_token_ids,
+        rewards,
+        min_incentive_size: fields.opt_decimal("min_incentive_size")?,
+        max_incentive_spread: fields.opt_decimal("max_incentive_spread")?,
+        active: fields.boolean("active")?,
+        closed: fields.boolean("closed")?,
+        neg_risk: fields.boolean("neg_risk")?,
+        neg_risk_market_id: fields.opt_string("neg_risk_market_id")?,
+        question_id: fields.string("question_id")?,
+        minimum_order_size: fields.decimal("minimum_order_size")?,
+        minimum_tick_size: fields.decimal("minimum_tick_size")?,
+        description: fields.string("description")?,
+        category: fields.opt_string("category")?,
+        category_id: fields.opt_u32("category_id")?,
+        end_date_iso: fields.opt_string("end_date_iso")?,
+        game_start_time: fields.opt_timestamp("game_start_time")?,
+        sports_market_type: fields.opt_string("sports_market_type")?,
+        game_id: fields.opt_string("game_id")?,
+        line: fields.opt_decimal("line")?,
+        question: fields.string("question")?,
+        market_slug: fields.string("market_slug")?,
+        seconds_delay: fields.decimal("seconds_delay")?,
+        icon: fields.string("icon")?,
+        fpmm: fields.string("fpmm")?,
+        liquidity: fields.opt_string("liquidity")?,
+        liquidity_num: fields.opt_decimal("liquidity_num")?,
+        liquidity_amm: fields.opt_decimal("liquidity_amm")?,
+        liquidity_clob: fields.opt_decimal("liquidity_clob")?,
+        volume: fields.opt_string("volume")?,
+        volume_num: fields.opt_decimal("volume_num")?,
+        volume_24hr: fields.opt_decimal("volume_24hr")?,
+        volume_1wk: fields.opt_decimal("volume_1wk")?,
+        volume_1mo: fields.opt_decimal("volume_1mo")?,
+        volume_1yr: fields.opt_decimal("volume_1yr")?,
+        volume_24hr_amm: fields.opt_decimal("volume_24hr_amm")?,
+        volume_1wk_amm: fields.opt_decimal("volume_1wk_amm")?,
+        volume_1mo_amm: fields.opt_decimal("volume_1mo_amm")?,
+        volume_1yr_amm: fields.opt_decimal("volume_1yr_amm")?,
+        volume_24hr_clob: fields.opt_decimal("volume_24hr_clob")?,
+        volume_1wk_clob: fields.opt_decimal("volume_1wk_clob")?,
+        volume_1mo_clob: fields.opt_decimal("volume_1mo_clob")?,
+        volume_1yr_clob: fields.opt_decimal("volume_1yr_clob")?,
+        volume_amm: fields.opt_decimal("volume_amm")?,
+        volume_clob: fields.opt_decimal("volume_clob")?,
+        spread: fields.opt_decimal("spread")?,
+        icon_url: fields.opt_string("icon_url")?,
+        banner_url: fields.opt_string("banner_url")?,
+        category_icon_url: fields.opt_string("category_icon_url")?,
+        // Versions 5 to 10 kept a list of source URLs instead
+        resolution_source: match fields.opt_string("resolution_source")? {
+            Some(source) => Some(source),
+            None => fields.string_list("source_urls")?.into_iter().next(),
+        },
+        tags: fields.string_list("tags")?,
+    })
+}
+
+fn unexpected(column: &str, field: &Field) -> PolyError {
+    PolyError::parse(
+        format!("Unexpected value in column {}: {:?}", column, field),
+        None,
+    )
+}
+
+/// Name-indexed view over a parquet record
+struct RowFields(HashMap<String, Field>);
+
+impl RowFields {
+    fn new(row: Row) -> Self {
+        Self(row.into_columns().into_iter().collect())
+    }
+
+    /// The named column, or null when the file predates it
+    fn take(&mut self, name: &str) -> Result<Field> {
+        Ok(self.0.remove(name).unwrap_or(Field::Null))
+    }
+
+    fn opt_string(&mut self, name: &str) -> Result<Option<String>> {
+        match self.take(name)? {
+            Field::Str(value) => Ok(Some(value)),
+            Field::Null => Ok(None),
+            other => Err(unexpected(name, &other)),
+        }
+    }
+
+    fn string_list(&mut self, name: &str) -> Result<Vec<String>> {
+        match self.take(name)? {
+            Field::ListInternal(list) => list
+                .elements()
+                .iter()
+                .map(|item| match item {
+                    Field::Str(value) => Ok(value.clone()),
+                    other => Err(unexpected(name, other)),
+                })
+                .collect(),
+            Field::Null => Ok(Vec::new()),
+            other => Err(unexpected(name, &other)),
+        }
+    }
+
+    fn string(&mut self, name: &str) -> Result<String> {
+        self.opt_string(name)?
+            .ok_or_else(|| PolyError::parse(format!("Null in required column {}", name), None))
+    }
+
+    /// Also reads a decimal string, as versions before 8 stored the incentive fields
+    fn opt_decimal(&mut self, name: &str) -> Result<Option<Decimal>> {
+        match self.take(name)? {
+            Field::Decimal(value) => decimal_from_field(&value).map(Some),
+            Field::Str(value) if value.is_empty() => Ok(None),
+            Field::Str(value) => value.parse().map(Some).map_err(|e| {
+                PolyError::parse(
+                    format!("Invalid decimal in column {}", name),
+                    Some(Box::new(e)),
+                )
+            }),
+            Field::Null => Ok(None),
+            other => Err(unexpected(name, &other)),
+        }
+    }
+
+    fn opt_u32(&mut self, name: &str) -> Result<Option<u32>> {
+        match self.take(name)? {
+            Field::UInt(value) => Ok(Some(value)),
+            Field::Null => Ok(None),
+            other => Err(unexpected(name, &other)),
+        }
+    }
+
+    /// Microseconds since the epoch, or RFC 3339 text as written before version 10
+    fn opt_timestamp(&mut self, name: &str) -> Result<Option<chrono::DateTime<chrono::Utc>>> {
+        match self.take(name)? {
+            Field::TimestampMicros(micros) => chrono::DateTime::from_timestamp_micros(micros)
+                .map(Some)
+                .ok_or_else(|| PolyError::parse(format!("Invalid {} {}", name, micros), None)),
+            Field::Str(raw) => chrono::DateTime::parse_from_rfc3339(&raw)
+                .map(|time| Some(time.with_timezone(&chrono::Utc)))
+                .map_err(|e| PolyError::parse(format!("Invalid {} {}: {}", name, raw, e), None)),
+            Field::Null => Ok(None),
+            other => Err(unexpected(name, &other)),
+        }
+    }
+
+    fn decimal(&mut self, name: &str) -> Result<Decimal> {
+        self.opt_decimal(name)?
+            .ok_or_else(|| PolyError::parse(format!("Null in required column {}", name), None))
+    }
+
+    fn boolean(&mut self, name: &str) -> Result<bool> {
+        match self.take(name)? {
+            Field::Bool(value) => Ok(value),
+            Field::Null => Ok(false),
+            other => Err(unexpected(name, &other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use std::io::Cursor;
+
+    fn synthetic_market(i: usize) -> Market {
+        let optional = |value: Decimal| {
+            if i.is_multiple_of(3) {
+                None
+            } else {
+                Some(value)
+            }
+        };
+        let seq = Decimal::from(i as u64);
+
+        Market {
+            condition_id: format!("0x{:064x}", i),
+            tokens: [
+                Token {
+                    token_id: format!("{}1", i),
+                    outcome: "Yes".to_string(),
+                },
+                Token {
+                    token_id: format!("{}2", i),
+                    outcome: "No".to_string(),
+                },
+            ],
+            clob_token_ids: if i.is_multiple_of(4) {
+                Vec::new()
+            } else {
+                vec![format!("{}1", i), format!("{}2", i)]
+            },
+            rewards: Rewards {
+                rates: (i.is_multiple_of(2)).then(|| serde_json::json!([{ "rate": i }])),
+                min_size: Decimal::from(i as u64 * 10),
+                max_spread: dec!(3.5),
+                event_start_date: None,
+                event_end_date: Some("2030-01-01".to_string()),
+                in_game_multiplier: optional(dec!(1.25)),
+                reward_epoch: None,
+            },
+            min_incentive_size: Some(dec!(5)),
+            max_incentive_spread: None,
+            active: i.is_multiple_of(2),
+            closed: i.is_multiple_of(5),
+            neg_risk: i.is_multiple_of(3),
+            neg_risk_market_id: i.is_multiple_of(3).then(|| format!("0xgroup{}", i / 6)),
+            question_id: format!("q-{}", i),
+            minimum_order_size: dec!(5),
+            minimum_tick_size: dec!(0.01),
+            description: format!("Synthetic market #{} ✓", i),
+            category: (i % 2 == 1).then(|| "Sports".to_string()),
+            category_id: (i % 2 == 1).then_some(i as u32),
+            end_date_iso: Some("2030-01-01T00:00:00Z".to_string()),
+            game_start_time: (i % 2 == 1)
+                .then(|| chrono::DateTime::from_timestamp(1_900_000_000 + i as i64, 0).unwrap()),
+            sports_market_type: (i % 2 == 1).then(|| "spreads".to_string()),
+            game_id: (i % 2 == 1).then(|| format!("game-{}", i)),
+            line: (i % 2 == 1).then(|| dec!(-3.5)),
+            question: format!("Will synthetic event {} happen?", i),
+            market_slug: format!("synthetic-{}", i),
+            seconds_delay: Decimal::ZERO,
+            icon: String::new(),
+            fpmm: String::new(),
+            liquidity: Some(format!("{}.5", i)),
+            liquidity_num: optional(seq * dec!(1000.123456789012345678)),
+            liquidity_amm: optional(Decimal::ZERO),
+            liquidity_clob: optional(-seq),
+            volume: None,
+            volume_num: optional(seq * dec!(98765.4321)),
+            volume_24hr: optional(dec!(0.000000000000000001)),
+            volume_1wk: optional(dec!(12345678901234567890)),
+            volume_1mo: None,
+            volume_1yr: None,
+            volume_24hr_amm: None,
+            volume_1wk_amm: None,
+            volume_1mo_amm: None,
+            volume_1yr_amm: None,
+            volume_24hr_clob: optional(seq),
+            volume_1wk_clob: None,
+            volume_1mo_clob: None,
+            volume_1yr_clob: None,
+            volume_amm: None,
+            volume_clob: optional(seq / dec!(7)),
+            spread: optional(dec!(0.01)),
+            icon_url: (i % 2 == 1).then(|| format!("https://img.example.com/{}.png", i)),
+            banner_url: None,
+            category_icon_url: (i % 2 == 1).then(|| "https://img.example.com/sports.png".into()),
+            resolution_source: Some(format!("https://source.example.com/{}", i)),
+            tags: vec!["Sports".to_string(), format!("tag-{}", i)],
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let markets: Vec<Market> = (0..100).map(synthetic_market).collect();
+
+        let mut buffer = Vec::new();
+        write_markets_to_parquet(&markets, &mut buffer).unwrap();
+        let decoded = read_markets_from_parquet(Cursor::new(buffer)).unwrap();
+
+        assert_eq!(decoded.len(), markets.len());
+        for (original, decoded) in markets.iter().zip(&decoded) {
+            assert_eq!(original.clob_token_ids, decoded.clob_token_ids);
+            assert_eq!(original.volume_1wk, decoded.volume_1wk);
+            assert_eq!(original.liquidity_clob, decoded.liquidity_clob);
+            // seq / 7 has 28 significant digits, parquet keeps the first 18 decimals
+            assert_eq!(
+                original.volume_clob.map(|v| v.round_dp(DECIMAL_SCALE)),
+                decoded.volume_clob
+            );
+            assert_eq!(original.rewards.rates, decoded.rewards.rates);
+            assert_eq!(original.description, decoded.description);
+            assert_eq!(original.category, decoded.category);
+            assert_eq!(original.category_id, decoded.category_id);
+            assert_eq!(original.game_start_time, decoded.game_start_time);
+            assert_eq!(original.sports_market_type, decoded.sports_market_type);
+            assert_eq!(original.line, decoded.line);
+            assert_eq!(original.spread, decoded.spread);
+            assert_eq!(original.icon_url, decoded.icon_url);
+            assert_eq!(original.category_icon_url, decoded.category_icon_url);
+            assert_eq!(original.resolution_source, decoded.resolution_source);
+            assert_eq!(original.tags, decoded.tags);
+            assert_eq!(original.active, decoded.active);
+            assert_eq!(original.closed, decoded.closed);
+            assert_eq!(original.neg_risk, decoded.neg_risk);
+            assert_eq!(original.neg_risk_market_id, decoded.neg_risk_market_id);
+            assert_eq!(original.minimum_tick_size, decoded.minimum_tick_size);
+            assert_eq!(original.tokens[1].token_id, decoded.tokens[1].token_id);
+        }
+    }
+
+    #[test]
+    fn test_schema_version_is_embedded() {
+        let mut buffer = Vec::new();
+        write_markets_to_parquet(&[synthetic_market(1)], &mut buffer).unwrap();
+
+        let reader = SerializedFileReader::new(Bytes::from(buffer)).unwrap();
+        let metadata = reader
+            .metadata()
+            .file_metadata()
+            .key_value_metadata()
+            .unwrap();
+        let entry = metadata
+            .iter()
+            .find(|entry| entry.key == SCHEMA_VERSION_KEY)
+            .unwrap();
+        assert_eq!(entry.value.as_deref(), Some(MARKET_SCHEMA_VERSION));
+    }
+
+    #[test]
+    fn test_game_start_time_is_a_timestamp() {
+        let mut buffer = Vec::new();
+        write_markets_to_parquet(&[synthetic_market(1)], &mut buffer).unwrap();
+
+        let reader = SerializedFileReader::new(Bytes::from(buffer)).unwrap();
+        let schema = reader.metadata().file_metadata().schema_descr();
+        let column = (0..schema.num_columns())
+            .map(|i| schema.column(i))
+            .find(|column| column.name() == "game_start_time")
+            .unwrap();
+        assert_eq!(column.physical_type(), parquet::basic::Type::INT64);
+        assert_eq!(
+            column.converted_type(),
+            parquet::basic::ConvertedType::TIMESTAMP_MICROS
+        );
+        assert!(matches!(
+            column.logical_type_ref(),
+            Some(parquet::basic::LogicalType::Timestamp(timestamp))
+                if timestamp.is_adjusted_to_u_t_c
+        ));
+    }
+
+    #[test]
+    fn test_reads_older_schema_versions() {
+        let v7 = include_bytes!("../../tests/fixtures/parquet/markets_v7.parquet");
+        let decoded = read_markets_from_parquet(Cursor::new(&v7[..])).unwrap();
+        assert_eq!(decoded.len(), 2);
+        for (decoded, i) in decoded.iter().zip([1, 3]) {
+            let original = synthetic_market(i);
+            assert_eq!(decoded.condition_id, original.condition_id);
+            // Stored as strings before version 8
+            assert_eq!(decoded.min_incentive_size, Some(dec!(5)));
+            assert_eq!(decoded.max_incentive_spread, None);
+            assert_eq!(decoded.game_start_time, original.game_start_time);
+            assert_eq!(decoded.neg_risk, original.neg_risk);
+            assert_eq!(decoded.tags, original.tags);
+            assert_eq!(decoded.resolution_source, original.resolution_source);
+        }
+
+        // A file that predates some columns reads them as null
+        let dropped = ["neg_risk", "neg_risk_market_id", "category_id", "spread"];
+        let schema = SchemaDescriptor::new(Arc::new(parse_message_type(MARKET_SCHEMA).unwrap()));
+        let market = synthetic_market(3);
+        let columns = market_columns(std::slice::from_ref(&market))
+            .unwrap()
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| !dropped.contains(&schema.column(*i).name()))
+            .map(|(_, column)| column)
+            .collect();
+        let old_schema: Vec<&str> = MARKET_SCHEMA
+            .lines()
+            .filter(|line| {
+                let name = line
+                    .split_whitespace()
+                    .nth(2)
+                    .map(|n| n.trim_end_matches(';'));
+                !name.is_some_and(|name| dropped.contains(&name))
+            })
+            .collect();
+        let mut old = Vec::new();
+        write_columns(&old_schema.join("\n"), "3", columns, &mut old).unwrap();
+        let decoded = read_markets_from_parquet(Cursor::new(old)).unwrap();
+        assert!(!decoded[0].neg_risk);
+        assert_eq!(decoded[0].neg_risk_market_id, None);
+        assert_eq!(decoded[0].spread, None);
+        assert_eq!(decoded[0].question, market.question);
+
+        for version in ["999", "x"] {
+            let mut newer = Vec::new();
+            write_columns(
+                MARKET_SCHEMA,
+                version,
+                market_columns(std::slice::from_ref(&market)).unwrap(),
+                &mut newer,
+            )
+            .unwrap();
+            assert!(read_markets_from_parquet(Cursor::new(newer)).is_err());
+        }
+    }
+
+    #[test]
+    fn test_decimal_encoding() {
+        for value in [
+            dec!(0),
+            dec!(-1.5),
+            dec!(0.000001),
+            dec!(99999999999.123456),
+        ] {
+            let bytes = decimal_to_bytes(value).unwrap();
+            let field = parquet::data_type::Decimal::from_bytes(bytes, 38, 18);
+            assert_eq!(decimal_from_field(&field).unwrap(), value);
+        }
+
+        assert!(decimal_to_bytes(Decimal::MAX).is_err());
+    }
+}
@@ -0,0 +1,260 @@
+//! Ordered host lists with failover for the REST services
+//!
+//! A [`HostPool`] holds a primary base URL followed by backups. Requests are always built
+//! against the primary; at send time the pool swaps the base for the host currently in
+//! use and moves down the list when a host fails. While a backup is active the primary
+//! is re-tried at most once per [`FailoverPolicy::probe_interval`], and the pool switches
+//! back the first time the primary answers.
+//!
+//! Which failures move on depends on whether replaying the request is safe. Connection
+//! errors always fail over, since the request never reached the host. Server errors
+//! (5xx) and timeouts fail over only for `GET`/`HEAD`; a `POST /order` that timed out may
+//! still have been accepted, so it is reported to the caller instead of being replayed.
+//!
+//! L2 signatures cover the timestamp, method, request path and body, but not the host,
+//! so a signed request stays valid on a backup without re-signing. That holds only while
+//! the backup serves the same paths as the primary (a relay mounted under a path prefix
+//! must strip it before forwarding).
+
+use reqwest::{Client, Method, Request, Response, Url};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// When to give the primary another chance after failing over
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FailoverPolicy {
+    /// Minimum time between attempts to restore the primary
+    pub probe_interval: Duration,
+}
+
+impl Default for FailoverPolicy {
+    fn default() -> Self {
+        Self {
+            probe_interval: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Counters for observing failover behaviour
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailoverStats {
+    /// Base URL requests are currently sent to
+    pub active_host: String,
+    /// Position of `active_host` in the configured list (0 is the primary)
+    pub active_index: usize,
+    /// Switches away from the host in use
+    pub failovers: u64,
+    /// Switches back to the primary after a successful probe
+    pub restores: u64,
+    /// Attempts that failed and were retried on another host
+    pub failed_attempts: u64,
+}
+
+/// Primary base URL plus ordered backups for one service
+#[derive(Debug)]
+pub struct HostPool {
+    hosts: Vec<String>,
+    policy: FailoverPolicy,
+    active: AtomicUsize,
+    /// When the last switch or primary probe happened
+    last_probe: Mutex<Instant>,
+    failovers: AtomicU64,
+    restores: AtomicU64,
+    failed_attempts: AtomicU64,
+}
+
+impl HostPool {
+    /// Build a pool from base URLs in priority order; `None` when `hosts` is empty
+    pub fn new<S: AsRef<str>>(hosts: &[S], policy: FailoverPolicy) -> Option<Self> {
+        let hosts: Vec<String> = hosts
+            .iter()
+            .map(|host| host.as_ref().trim_end_matches('/').to_string())
+            .collect();
+        if hosts.is_empty() {
+            return None;
+        }
+        Some(Self {
+            hosts,
+            policy,
+            active: AtomicUsize::new(0),
+            last_probe: Mutex::new(Instant::now()),
+            failovers: AtomicU64::new(0),
+            restores: AtomicU64::new(0),
+            failed_attempts: AtomicU64::new(0),
+        })
+    }
+
+    /// The primary base URL requests are built against
+    pub fn primary(&self) -> &str {
+        &self.hosts[0]
+    }
+
+    pub fn hosts(&self) -> &[String] {
+        &self.hosts
+    }
+
+    pub fn stats(&self) -> FailoverStats {
+        let active_index = self.active.load(Ordering::Relaxed);
+        FailoverStats {
+            active_host: self.hosts[active_index].clone(),
+            active_index,
+            failovers: self.failovers.load(Ordering::Relaxed),
+            restores: self.restores.load(Ordering::Relaxed),
+            failed_attempts: self.failed_attempts.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Whether `url` was built against this pool's primary
+    pub fn covers(&self, url: &Url) -> bool {
+        self.suffix(url).is_some()
+    }
+
+    fn suffix<'a>(&self, url: &'a Url) -> Option<&'a str> {
+        let rest = url.as_str().strip_prefix(self.primary())?;
+        (rest.is_empty() || rest.starts_with(['/', '?'])).then_some(rest)
+    }
+
+    /// Host indices to try, in order, for the next request
+    fn candidates(&self) -> Vec<usize> {
+        let active = self.active.load(Ordering::Relaxed);
+        let probe_due = active != 0 && {
+            let mut last_probe = self
+                .last_probe
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            let due = last_probe.elapsed() >= self.policy.probe_interval;
+            if due {
+                *last_probe = Instant::now();
+            }
+            due
+        };
+        let first = if probe_due { 0 } else { active };
+        std::iter::once(first)
+            .chain((0..self.hosts.len()).filter(move |idx| *idx != first))
+            .collect()
+    }
+
+    fn record_success(&self, idx: usize) {
+        let previous = self.active.swap(idx, Ordering::Relaxed);
+        if previous == idx {
+            return;
+        }
+        *self
+            .last_probe
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = Instant::now();
+        if idx == 0 {
+            self.restores.fetch_add(1, Ordering::Relaxed);
+            info!("restored primary host {}", self.hosts[0]);
+        } else {
+            self.failovers.fetch_add(1, Ordering::Relaxed);
+            warn!(
+                "failed over from {} to {}",
+                self.hosts[previous], self.hosts[idx]
+            );
+        }
+    }
+
+    fn point_at(&self, request: &mut Request, idx: usize, suffix: &str) {
+        if let Ok(url) = Url::parse(&format!("{}{}", self.hosts[idx], suffix)) {
+            *request.url_mut() = url;
+        }
+    }
+
+    /// Send `request`, moving through the host list on failures that are safe to retry
+    pub async fn execute(
+        &self,
+        client: &Client,
+        mut request: Request,
+    ) -> reqwest::Result<Response> {
+        let Some(suffix) = self.suffix(request.url()).map(str::to_string) else {
+            return client.execute(request).await;
+        };
+        let idempotent = matches!(*request.method(), Method::GET | Method::HEAD);
+        let candidates = self.candidates();
+
+        for (attempt, &idx) in candidates.iter().enumerate() {
+            let has_next = attempt + 1 < candidates.len();
+            // Streaming bodies can't be replayed, so they get a single attempt
+            let Some(mut current) = has_next.then(|| request.try_clone()).flatten() else {
+                self.point_at(&mut request, idx, &suffix);
+                let result = client.execute(request).await;
+                if let Ok(response) = &result
+                    && !response.status().is_server_error()
+                {
+                    self.record_success(idx);
+                }
+                return result;
+            };
+            self.point_at(&mut current, idx, &suffix);
+
+            let host = &self.hosts[idx];
+            match client.execute(current).await {
+                Ok(response) if idempotent && response.status().is_server_error() => {
+                    self.failed_attempts.fetch_add(1, Ordering::Relaxed);
+                    warn!("{} returned {}, trying next host", host, response.status());
+                }
+                Err(err) if err.is_connect() || (idempotent && err.is_timeout()) => {
+                    self.failed_attempts.fetch_add(1, Ordering::Relaxed);
+                    warn!("request to {} failed ({}), trying next host", host, err);
+                }
+                Ok(response) => {
+                    if !response.status().is_server_error() {
+                        self.record_success(idx);
+                    }
+                    return Ok(response);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        unreachable!("the last candidate always returns")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_covers_only_primary_prefix() {
+        let pool = HostPool::new(
+            &["https://clob.example.com/", "https://relay.example.com"],
+            FailoverPolicy::default(),
+        )
+        .unwrap();
+        assert_eq!(pool.primary(), "https://clob.example.com");
+
+        let url = |raw: &str| Url::parse(raw).unwrap();
+        assert!(pool.covers(&url("https://clob.example.com/book?token_id=1")));
+        assert!(!pool.covers(&url("https://clob.example.com.evil/book")));
+        assert!(!pool.covers(&url("https://gamma.example.com/markets")));
+        assert!(HostPool::new::<&str>(&[], FailoverPolicy::default()).is_none());
+    }
+
+    #[test]
+    fn test_candidates_probe_primary_after_interval() {
+        let pool = HostPool::new(
+            &["http://a", "http://b", "http://c"],
+            FailoverPolicy {
+                probe_interval: Duration::from_millis(20),
+            },
+        )
+        .unwrap();
+        assert_eq!(pool.candidates(), vec![0, 1, 2]);
+
+        pool.record_success(2);
+        assert_eq!(pool.candidates(), vec![2, 0, 1]);
+        std::thread::sleep(Duration::from_millis(30));
+        // One probe per interval
+        assert_eq!(pool.candidates(), vec![0, 1, 2]);
+        assert_eq!(pool.candidates(), vec![2, 0, 1]);
+
+        pool.record_success(0);
+        let stats = pool.stats();
+        assert_eq!((stats.failovers, stats.restores), (1, 1));
+        assert_eq!(stats.active_host, "http://a");
+    }
+}
@@ -135,26 +135,26 @@ impl FillEngine {
         }
 
         // Check slippage
-        if let Some(slippage) = self.calculate_slippage(order, &fills) {
-            if slippage > self.max_slippage_pct {
-                warn!(
-                    "Slippage {}% exceeds maximum {}%",
-                    slippage, self.max_slippage_pct
-                );
-                return Ok(FillResult {
-                    order_id: order
-                        .client_id
-                        .clone()
-                        .unwrap_or_else(|| "market_order".to_string()),
-                    fills: Vec::new(),
-                    total_size: Decimal::ZERO,
-                    average_price: Decimal::ZERO,
-                    total_cost: Decimal::ZERO,
-                    fees: Decimal::ZERO,
-                    status: FillStatus::Rejected,
-                    timestamp: start_time,
-                });
-            }
+        if let Some(slippage) = self.calculate_slippage(order, &fills)
+            && slippage > self.max_slippage_pct
+        {
+            warn!(
+                "Slippage {}% exceeds maximum {}%",
+                slippage, self.max_slippage_pct
+            );
+            return Ok(FillResult {
+                order_id: order
+                    .client_id
+                    .clone()
+                    .unwrap_or_else(|| "market_order".to_string()),
+                fills: Vec::new(),
+                total_size: Decimal::ZERO,
+                average_price: Decimal::ZERO,
+                total_cost: Decimal::ZERO,
+                fees: Decimal::ZERO,
+                status: FillStatus::Rejected,
+                timestamp: start_time,
+            });
         }
 
         // Determine status
@@ -630,6 +630,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_fill_engine_advanced_creation() {
         // Test that we can create a fill engine with parameters
         let _engine = FillEngine::new(dec!(1.0), dec!(0.05), 50); // min_fill_size, max_slippage, fee_rate_bps
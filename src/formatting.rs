@@ -0,0 +1,106 @@
+//! Display strings for prices, sizes and PnL, following Polymarket's UI
+//!
+//! These are for showing numbers to people, not for sending them to the API: use
+//! [`canonical_decimal`] for request bodies. Every formatter is a pure function of
+//! its arguments, with rounding done as the order builder does it (half toward zero),
+//! so a price shown here is the price an order at that level would carry.
+
+use crate::orders::canonical_decimal;
+use rust_decimal::Decimal;
+
+/// `price` with as many decimal places as `tick_size` has
+///
+/// A 0.01 tick shows `0.62`, a 0.001 tick `0.620`. A zero or negative tick falls
+/// back to two places.
+pub fn format_price(price: Decimal, tick_size: Decimal) -> String {
+    let places = if tick_size > Decimal::ZERO {
+        tick_size.normalize().scale()
+    } else {
+        2
+    };
+    canonical_decimal(price, places)
+}
+
+/// Share count with thousands separators and at most two decimal places
+///
+/// Trailing zeros are dropped, so `1234.50` shows as `1,234.5` and `1000` as `1,000`.
+pub fn format_size(size: Decimal) -> String {
+    let rounded = canonical_decimal(size, 2);
+    group_thousands(rounded.trim_end_matches('0').trim_end_matches('.'))
+}
+
+/// An outcome price read as a probability, to one decimal place: `0.62` is `62.0%`
+pub fn format_probability_pct(price: Decimal) -> String {
+    format!("{}%", canonical_decimal(price * Decimal::ONE_HUNDRED, 1))
+}
+
+/// Signed dollar amount in cents: `+$1,234.56`, `-$567.89`, or `$0.00` for nothing
+pub fn format_pnl(pnl: Decimal) -> String {
+    let amount = canonical_decimal(pnl.abs(), 2);
+    let sign = if amount == "0.00" {
+        ""
+    } else if pnl.is_sign_negative() {
+        "-"
+    } else {
+        "+"
+    };
+    format!("{sign}${}", group_thousands(&amount))
+}
+
+/// Insert commas into the integer part of a plain decimal string
+fn group_thousands(value: &str) -> String {
+    let (sign, digits) = match value.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", value),
+    };
+    let (int_part, fraction) = match digits.split_once('.') {
+        Some((int_part, fraction)) => (int_part, Some(fraction)),
+        None => (digits, None),
+    };
+
+    let mut grouped = String::with_capacity(value.len() + int_part.len() / 3);
+    grouped.push_str(sign);
+    for (i, digit) in int_part.chars().enumerate() {
+        if i > 0 && (int_part.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    if let Some(fraction) = fraction {
+        grouped.push('.');
+        grouped.push_str(fraction);
+    }
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_price_places_follow_tick() {
+        assert_eq!(format_price(dec!(0.62), dec!(0.01)), "0.62");
+        assert_eq!(format_price(dec!(0.62), dec!(0.001)), "0.620");
+        assert_eq!(format_price(dec!(0.6249), dec!(0.010)), "0.62");
+        assert_eq!(format_price(dec!(0.5), dec!(0)), "0.50");
+    }
+
+    #[test]
+    fn test_size_and_probability() {
+        assert_eq!(format_size(dec!(1234.50)), "1,234.5");
+        assert_eq!(format_size(dec!(1000000)), "1,000,000");
+        assert_eq!(format_size(dec!(999.999)), "1,000");
+        assert_eq!(format_size(dec!(12)), "12");
+        assert_eq!(format_probability_pct(dec!(0.62)), "62.0%");
+        assert_eq!(format_probability_pct(dec!(0.0345)), "3.4%");
+    }
+
+    #[test]
+    fn test_pnl_sign_and_grouping() {
+        assert_eq!(format_pnl(dec!(1234.56)), "+$1,234.56");
+        assert_eq!(format_pnl(dec!(-567.89)), "-$567.89");
+        assert_eq!(format_pnl(dec!(-0.001)), "$0.00");
+        assert_eq!(format_pnl(dec!(-1234567)), "-$1,234,567.00");
+    }
+}
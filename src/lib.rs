@@ -2,21 +2,60 @@
 //! WebSocket APIs.
 //! Use it to authenticate, build signed orders, stream live book data, or query
 //! historical fills and markets.
+//!
+//! HTTPS and WSS share one TLS backend, chosen by cargo feature: `native-tls` (the
+//! default, OpenSSL/Schannel/Secure Transport) or `rustls-tls` (rustls with the
+//! webpki root set), which needs `default-features = false`. If feature unification
+//! turns both on, both clients use native-tls. Building with neither is a compile
+//! error.
+
+#[cfg(not(any(feature = "native-tls", feature = "rustls-tls")))]
+compile_error!(
+    "polysqueeze needs a TLS backend: enable the `native-tls` (default) or `rustls-tls` feature"
+);
 
 pub mod auth;
 pub mod book;
+pub mod circuit;
 pub mod client;
+pub mod clock;
 pub mod config;
+pub mod correlation;
+pub mod ctf;
 pub mod decode;
 pub mod errors;
+pub mod export;
+pub mod failover;
 pub mod fill;
+pub mod formatting;
+pub mod limiter;
+pub mod market_making;
+pub mod market_meta;
+pub mod microstructure;
+#[cfg(feature = "tower")]
+pub mod middleware;
+#[cfg(feature = "onchain")]
+pub mod onchain;
 pub mod orders;
+pub mod pnl;
+pub mod question;
+pub mod quoter;
+pub mod scoring;
+pub mod serde_decimal;
+pub mod telemetry;
+#[cfg(any(test, feature = "testkit"))]
+pub mod testkit;
+pub mod tracker;
 pub mod types;
 pub mod utils;
+pub mod volatility;
 pub mod ws;
 pub mod wss;
 
-pub use crate::client::{ClobClient, CreateOrderOptions, DataApiClient, MarketClient, OrderArgs, PolyClient};
+pub use crate::client::{ClobClient, CreateOrderOptions, DataApiClient, MarketClient, OrderArgs, PolyClient, PolyFacade};
 pub use crate::errors::{PolyError, Result};
 pub use crate::types::{ApiCredentials, SignedOrderRequest};
-pub use crate::wss::{WssMarketClient, WssMarketEvent, WssUserClient, WssUserEvent};
+pub use crate::wss::{
+    EventTiming, WssActivityClient, WssClient, WssEvent, WssMarketClient, WssMarketEvent,
+    WssUserClient, WssUserEvent,
+};
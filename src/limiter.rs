@@ -0,0 +1,452 @@
+//! Request admission with priority classes
+//!
+//! A [`RequestLimiter`] caps how many requests a client has in flight and decides who
+//! goes next when the cap is reached. Each [`Priority`] class has its own FIFO queue,
+//! and free slots are handed out across the non-empty queues by smooth weighted
+//! round-robin, so a backlog of normal requests only delays an order post by its share
+//! of the weights rather than by the length of the backlog. A per-class cap on top of
+//! that keeps slots free for the other class: by default normal requests may use all
+//! but one slot, so a cancel never waits for a metadata fetch to finish.
+//!
+//! Waiting is cancellation-safe: dropping a queued `acquire` removes nothing from the
+//! accounting, and a slot handed to a waiter that went away is released again.
+//!
+//! A [`RateLimiter`] bounds rate rather than concurrency: it is a token bucket that
+//! spaces requests out to a steady number per second, however quickly they complete.
+
+use crate::telemetry;
+use reqwest::Method;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::time::Instant;
+
+/// Scheduling class of a request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Priority {
+    /// Order placement and cancellation
+    High,
+    /// Everything else
+    Normal,
+}
+
+impl Priority {
+    const ALL: [Priority; 2] = [Priority::High, Priority::Normal];
+
+    fn index(self) -> usize {
+        match self {
+            Priority::High => 0,
+            Priority::Normal => 1,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Priority::High => "high",
+            Priority::Normal => "normal",
+        }
+    }
+
+    /// Class the client assigns to a CLOB request
+    ///
+    /// Writes to the order endpoints (`/order`, `/orders`, `/cancel-all`,
+    /// `/cancel-market-orders`) are high priority; reads, including `GET` on those
+    /// paths, are normal.
+    pub fn for_request(method: &Method, path: &str) -> Self {
+        let path = path.trim_end_matches('/');
+        let order_path = matches!(
+            path.rsplit('/').next(),
+            Some("order" | "orders" | "cancel-all" | "cancel-market-orders")
+        );
+        if order_path && (method == Method::POST || method == Method::DELETE) {
+            Priority::High
+        } else {
+            Priority::Normal
+        }
+    }
+}
+
+/// Scheduling settings for one class
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClassLimits {
+    /// Share of free slots when both classes are waiting (0 is treated as 1)
+    pub weight: u32,
+    /// Most requests of this class in flight at once; `None` for only the global cap
+    pub max_in_flight: Option<usize>,
+}
+
+/// Settings for a [`RequestLimiter`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestLimits {
+    /// Requests in flight across all classes (0 is treated as 1)
+    pub max_in_flight: usize,
+    pub high: ClassLimits,
+    pub normal: ClassLimits,
+}
+
+impl RequestLimits {
+    /// `max_in_flight` slots, high priority weighted 4:1 and normal requests kept
+    /// one slot short of the cap (when there is more than one slot)
+    pub fn new(max_in_flight: usize) -> Self {
+        let max_in_flight = max_in_flight.max(1);
+        Self {
+            max_in_flight,
+            high: ClassLimits {
+                weight: 4,
+                max_in_flight: None,
+            },
+            normal: ClassLimits {
+                weight: 1,
+                max_in_flight: (max_in_flight > 1).then(|| max_in_flight - 1),
+            },
+        }
+    }
+
+    fn class(&self, priority: Priority) -> &ClassLimits {
+        match priority {
+            Priority::High => &self.high,
+            Priority::Normal => &self.normal,
+        }
+    }
+}
+
+/// Point-in-time view of a [`RequestLimiter`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LimiterStats {
+    pub in_flight: usize,
+    pub high_in_flight: usize,
+    pub normal_in_flight: usize,
+    pub high_queued: usize,
+    pub normal_queued: usize,
+}
+
+#[derive(Debug)]
+struct State {
+    in_flight: usize,
+    class_in_flight: [usize; 2],
+    queues: [VecDeque<oneshot::Sender<RequestPermit>>; 2],
+    /// Smooth weighted round-robin credit per class
+    credit: [i64; 2],
+}
+
+#[derive(Debug)]
+struct Inner {
+    limits: RequestLimits,
+    state: Mutex<State>,
+}
+
+/// Shared admission gate for a client's requests
+#[derive(Debug, Clone)]
+pub struct RequestLimiter {
+    inner: Arc<Inner>,
+}
+
+/// A slot held by one request; released on drop
+#[derive(Debug)]
+pub struct RequestPermit {
+    inner: Arc<Inner>,
+    priority: Priority,
+    armed: bool,
+}
+
+impl Drop for RequestPermit {
+    fn drop(&mut self) {
+        if self.armed {
+            let mut state = self.inner.lock();
+            self.inner.release(&mut state, self.priority);
+        }
+    }
+}
+
+impl Inner {
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn has_room(&self, state: &State, priority: Priority) -> bool {
+        let class_cap = self.limits.class(priority).max_in_flight;
+        state.in_flight < self.limits.max_in_flight
+            && class_cap.is_none_or(|cap| state.class_in_flight[priority.index()] < cap.max(1))
+    }
+
+    fn release(self: &Arc<Self>, state: &mut State, priority: Priority) {
+        state.in_flight -= 1;
+        state.class_in_flight[priority.index()] -= 1;
+        self.dispatch(state);
+    }
+
+    /// Hand free slots to queued waiters until none fit
+    fn dispatch(self: &Arc<Self>, state: &mut State) {
+        while let Some(priority) = self.next_class(state) {
+            let Some(waiter) = state.queues[priority.index()].pop_front() else {
+                continue;
+            };
+            state.in_flight += 1;
+            state.class_in_flight[priority.index()] += 1;
+            let permit = RequestPermit {
+                inner: self.clone(),
+                priority,
+                armed: true,
+            };
+            if let Err(mut permit) = waiter.send(permit) {
+                // The waiter was dropped; undo without re-entering the lock
+                permit.armed = false;
+                state.in_flight -= 1;
+                state.class_in_flight[priority.index()] -= 1;
+            }
+        }
+        for priority in Priority::ALL {
+            telemetry::request_queue_depth(priority.as_str(), state.queues[priority.index()].len());
+        }
+    }
+
+    fn next_class(&self, state: &mut State) -> Option<Priority> {
+        let eligible: Vec<Priority> = Priority::ALL
+            .into_iter()
+            .filter(|p| !state.queues[p.index()].is_empty() && self.has_room(state, *p))
+            .collect();
+        if eligible.len() <= 1 {
+            return eligible.first().copied();
+        }
+        let weight = |p: Priority| i64::from(self.limits.class(p).weight.max(1));
+        let total: i64 = eligible.iter().map(|p| weight(*p)).sum();
+        for p in &eligible {
+            state.credit[p.index()] += weight(*p);
+        }
+        let chosen = *eligible.iter().max_by_key(|p| state.credit[p.index()])?;
+        state.credit[chosen.index()] -= total;
+        Some(chosen)
+    }
+}
+
+impl RequestLimiter {
+    pub fn new(limits: RequestLimits) -> Self {
+        let limits = RequestLimits {
+            max_in_flight: limits.max_in_flight.max(1),
+            ..limits
+        };
+        Self {
+            inner: Arc::new(Inner {
+                limits,
+                state: Mutex::new(State {
+                    in_flight: 0,
+                    class_in_flight: [0; 2],
+                    queues: [VecDeque::new(), VecDeque::new()],
+                    credit: [0; 2],
+                }),
+            }),
+        }
+    }
+
+    pub fn limits(&self) -> &RequestLimits {
+        &self.inner.limits
+    }
+
+    /// Wait for a slot in `priority`'s class
+    pub async fn acquire(&self, priority: Priority) -> RequestPermit {
+        let receiver = {
+            let mut state = self.inner.lock();
+            let queued = !state.queues[priority.index()].is_empty();
+            if !queued && self.inner.has_room(&state, priority) {
+                state.in_flight += 1;
+                state.class_in_flight[priority.index()] += 1;
+                return RequestPermit {
+                    inner: self.inner.clone(),
+                    priority,
+                    armed: true,
+                };
+            }
+            let (sender, receiver) = oneshot::channel();
+            state.queues[priority.index()].push_back(sender);
+            telemetry::request_queue_depth(priority.as_str(), state.queues[priority.index()].len());
+            receiver
+        };
+        // Senders are only dropped after sending, so this never fails
+        receiver
+            .await
+            .expect("request limiter dropped a queued waiter")
+    }
+
+    pub fn stats(&self) -> LimiterStats {
+        let state = self.inner.lock();
+        // Waiters that gave up are only pruned at dispatch, so count live ones
+        let queued = |p: Priority| {
+            state.queues[p.index()]
+                .iter()
+                .filter(|sender| !sender.is_closed())
+                .count()
+        };
+        LimiterStats {
+            in_flight: state.in_flight,
+            high_in_flight: state.class_in_flight[Priority::High.index()],
+            normal_in_flight: state.class_in_flight[Priority::Normal.index()],
+            high_queued: queued(Priority::High),
+            normal_queued: queued(Priority::Normal),
+        }
+    }
+}
+
+/// Token bucket admitting a steady number of requests per second
+///
+/// The bucket holds up to one second's worth of tokens and starts full, so a burst of
+/// that size goes out at once and later requests are spaced evenly. Clones share the
+/// bucket. A wait that is dropped part-way still uses up its token.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    per_second: u32,
+    bucket: Arc<Mutex<Bucket>>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    /// Negative while requests are waiting for tokens not yet refilled
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl RateLimiter {
+    /// `per_second` requests a second; 0 is treated as 1
+    pub fn new(per_second: u32) -> Self {
+        let per_second = per_second.max(1);
+        Self {
+            per_second,
+            bucket: Arc::new(Mutex::new(Bucket {
+                tokens: f64::from(per_second),
+                refilled_at: Instant::now(),
+            })),
+        }
+    }
+
+    pub fn per_second(&self) -> u32 {
+        self.per_second
+    }
+
+    /// Take a token, waiting until one has been refilled when the bucket is empty
+    pub async fn until_ready(&self) {
+        let wait = {
+            let mut bucket = self.bucket.lock().unwrap_or_else(PoisonError::into_inner);
+            let rate = f64::from(self.per_second);
+            let now = Instant::now();
+            let refilled = now.duration_since(bucket.refilled_at).as_secs_f64() * rate;
+            bucket.tokens = (bucket.tokens + refilled).min(rate) - 1.0;
+            bucket.refilled_at = now;
+            (bucket.tokens < 0.0).then(|| Duration::from_secs_f64(-bucket.tokens / rate))
+        };
+        if let Some(wait) = wait {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_order_writes_are_high_priority() {
+        assert_eq!(
+            Priority::for_request(&Method::POST, "/order"),
+            Priority::High
+        );
+        assert_eq!(
+            Priority::for_request(&Method::DELETE, "/cancel-market-orders"),
+            Priority::High
+        );
+        assert_eq!(
+            Priority::for_request(&Method::GET, "/orders"),
+            Priority::Normal
+        );
+        assert_eq!(
+            Priority::for_request(&Method::GET, "/book"),
+            Priority::Normal
+        );
+    }
+
+    #[tokio::test]
+    async fn test_high_priority_skips_normal_backlog() {
+        let limiter = RequestLimiter::new(RequestLimits::new(2));
+        let first = limiter.acquire(Priority::Normal).await;
+
+        // Normal requests are capped one short, so these queue
+        let waiting: Vec<_> = (0..3)
+            .map(|_| {
+                let limiter = limiter.clone();
+                tokio::spawn(async move { limiter.acquire(Priority::Normal).await })
+            })
+            .collect();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(limiter.stats().normal_queued, 3);
+
+        let high = tokio::time::timeout(Duration::from_secs(1), limiter.acquire(Priority::High))
+            .await
+            .expect("high priority must not wait behind the normal queue");
+        assert_eq!(limiter.stats().in_flight, 2);
+
+        drop(high);
+        drop(first);
+        for task in waiting {
+            drop(task.await.unwrap());
+        }
+        assert_eq!(limiter.stats(), LimiterStats::default());
+    }
+
+    #[tokio::test]
+    async fn test_slots_are_shared_by_weight() {
+        let mut limits = RequestLimits::new(1);
+        limits.high.weight = 2;
+        let limiter = RequestLimiter::new(limits);
+        let held = limiter.acquire(Priority::Normal).await;
+
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let mut tasks = Vec::new();
+        for priority in [Priority::Normal; 3].into_iter().chain([Priority::High; 3]) {
+            let (limiter, order) = (limiter.clone(), order.clone());
+            tasks.push(tokio::spawn(async move {
+                let _permit = limiter.acquire(priority).await;
+                order.lock().unwrap().push(priority);
+            }));
+            tokio::task::yield_now().await;
+        }
+        drop(held);
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        use Priority::{High as H, Normal as N};
+        assert_eq!(*order.lock().unwrap(), [H, N, H, H, N, N]);
+    }
+
+    #[tokio::test]
+    async fn test_abandoned_waiters_release_their_slot() {
+        let limiter = RequestLimiter::new(RequestLimits::new(1));
+        let held = limiter.acquire(Priority::High).await;
+        let abandoned =
+            tokio::time::timeout(Duration::from_millis(10), limiter.acquire(Priority::Normal))
+                .await;
+        assert!(abandoned.is_err());
+        assert_eq!(limiter.stats().normal_queued, 0);
+
+        drop(held);
+        assert_eq!(limiter.stats().in_flight, 0);
+        let _next = limiter.acquire(Priority::Normal).await;
+        assert_eq!(limiter.stats().in_flight, 1);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_spaces_requests_past_the_burst() {
+        let limiter = RateLimiter::new(20);
+        let started = Instant::now();
+        for _ in 0..20 {
+            limiter.until_ready().await;
+        }
+        assert!(started.elapsed() < Duration::from_millis(40));
+
+        // 50ms per token once the first second's worth is spent, shared by clones
+        let clone = limiter.clone();
+        limiter.until_ready().await;
+        clone.until_ready().await;
+        assert!(started.elapsed() >= Duration::from_millis(95));
+        assert_eq!(RateLimiter::new(0).per_second(), 1);
+    }
+}
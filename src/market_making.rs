@@ -0,0 +1,169 @@
+//! Inventory-skewed two-sided quotes from a fair-value estimate
+//!
+//! [`QuoteEngine`] is the pricing half of market making with no I/O: given a fair value
+//! and the current position it returns a bid and an ask, which the caller posts however
+//! it likes. Holding inventory shifts both quotes by `skew_per_unit` per share, down when
+//! long and up when short, so the side that unwinds the position is the one more likely
+//! to fill. For quotes kept around the book mid instead, see [`Quoter`].
+//!
+//! [`Quoter`]: crate::quoter::Quoter
+
+use crate::errors::{PolyError, Result};
+use crate::types::{OrderRequest, OrderType, Side};
+use rust_decimal::Decimal;
+
+/// Quoting parameters for one token
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuoteEngine {
+    /// The caller's estimate of the token's value
+    pub fair_value: Decimal,
+    /// Distance from the skewed fair value to each quote
+    pub half_spread: Decimal,
+    /// Size posted on each side
+    pub max_size: Decimal,
+    /// Market tick size; quote prices are rounded onto it
+    pub tick_size: Decimal,
+    /// Price shift per share of inventory
+    pub skew_per_unit: Decimal,
+}
+
+/// A bid and an ask computed by [`QuoteEngine::compute_quotes`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quotes {
+    pub bid_price: Decimal,
+    pub ask_price: Decimal,
+    pub bid_size: Decimal,
+    pub ask_size: Decimal,
+}
+
+impl QuoteEngine {
+    /// Rejects a tick size that leaves no room for a bid below an ask inside `(0, 1)`,
+    /// i.e. one that is not positive or is above a third
+    pub fn new(
+        fair_value: Decimal,
+        half_spread: Decimal,
+        max_size: Decimal,
+        tick_size: Decimal,
+        skew_per_unit: Decimal,
+    ) -> Result<Self> {
+        if tick_size <= Decimal::ZERO || tick_size * Decimal::from(3) > Decimal::ONE {
+            return Err(PolyError::validation(format!(
+                "quote tick_size {} must be positive and at most 1/3",
+                tick_size
+            )));
+        }
+        Ok(Self {
+            fair_value,
+            half_spread,
+            max_size,
+            tick_size,
+            skew_per_unit,
+        })
+    }
+
+    /// Quotes for a position of `current_inventory` shares, negative when short
+    ///
+    /// The bid is `fair_value - half_spread - skew_per_unit * current_inventory` and the
+    /// ask the same with `+ half_spread`, each rounded away from the fair value onto the
+    /// tick grid. Both are then kept within `[tick, 1 - tick]` and at least one tick
+    /// apart, so a large skew pins the pair to the edge of the range rather than
+    /// crossing it. A tick size of zero or less leaves prices unrounded and unclamped.
+    pub fn compute_quotes(&self, current_inventory: Decimal) -> Quotes {
+        let center = self.fair_value - self.skew_per_unit * current_inventory;
+        let mut bid = center - self.half_spread;
+        let mut ask = center + self.half_spread;
+
+        let tick = self.tick_size;
+        if tick > Decimal::ZERO {
+            let max_price = Decimal::ONE - tick;
+            bid = ((bid / tick).floor() * tick)
+                .min(max_price - tick)
+                .max(tick);
+            ask = ((ask / tick).ceil() * tick).min(max_price).max(bid + tick);
+        }
+
+        Quotes {
+            bid_price: bid.normalize(),
+            ask_price: ask.normalize(),
+            bid_size: self.max_size,
+            ask_size: self.max_size,
+        }
+    }
+}
+
+impl Quotes {
+    /// Distance between the ask and the bid
+    pub fn spread(&self) -> Decimal {
+        self.ask_price - self.bid_price
+    }
+
+    /// The bid and the ask, in that order, as GTC orders on `token_id`
+    pub fn to_order_requests(&self, token_id: &str) -> [OrderRequest; 2] {
+        let order = |side, price, size| OrderRequest {
+            token_id: token_id.to_string(),
+            side,
+            price,
+            size,
+            order_type: OrderType::GTC,
+            expiration: None,
+            client_id: None,
+        };
+        [
+            order(Side::BUY, self.bid_price, self.bid_size),
+            order(Side::SELL, self.ask_price, self.ask_size),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn engine() -> QuoteEngine {
+        QuoteEngine::new(dec!(0.50), dec!(0.02), dec!(100), dec!(0.01), dec!(0.0001)).unwrap()
+    }
+
+    #[test]
+    fn test_inventory_skews_both_quotes() {
+        let flat = engine().compute_quotes(Decimal::ZERO);
+        assert_eq!((flat.bid_price, flat.ask_price), (dec!(0.48), dec!(0.52)));
+        assert_eq!((flat.bid_size, flat.ask_size), (dec!(100), dec!(100)));
+
+        // Long 150 shares moves the centre down 0.015; rounding widens away from it
+        let long = engine().compute_quotes(dec!(150));
+        assert_eq!((long.bid_price, long.ask_price), (dec!(0.46), dec!(0.51)));
+
+        let short = engine().compute_quotes(dec!(-100));
+        assert_eq!((short.bid_price, short.ask_price), (dec!(0.49), dec!(0.53)));
+        assert_eq!(short.spread(), dec!(0.04));
+    }
+
+    #[test]
+    fn test_quotes_stay_in_price_range() {
+        let long = engine().compute_quotes(dec!(10000));
+        assert_eq!((long.bid_price, long.ask_price), (dec!(0.01), dec!(0.02)));
+        let short = engine().compute_quotes(dec!(-10000));
+        assert_eq!((short.bid_price, short.ask_price), (dec!(0.98), dec!(0.99)));
+
+        let mut tight = engine();
+        tight.half_spread = Decimal::ZERO;
+        let quotes = tight.compute_quotes(Decimal::ZERO);
+        assert_eq!(
+            (quotes.bid_price, quotes.ask_price),
+            (dec!(0.50), dec!(0.51))
+        );
+
+        for tick in [dec!(0), dec!(0.5), dec!(0.6)] {
+            assert!(QuoteEngine::new(dec!(0.5), dec!(0.02), dec!(1), tick, dec!(0)).is_err());
+        }
+
+        let [bid, ask] = engine()
+            .compute_quotes(Decimal::ZERO)
+            .to_order_requests("tok");
+        assert_eq!((bid.side, bid.price), (Side::BUY, dec!(0.48)));
+        assert_eq!((ask.side, ask.price), (Side::SELL, dec!(0.52)));
+        assert_eq!(ask.token_id, "tok");
+        assert_eq!(ask.order_type, OrderType::GTC);
+    }
+}
@@ -0,0 +1,213 @@
+//! Shared cache of per-token order metadata
+//!
+//! Building an order needs the token's tick size and neg-risk flag. Fetching them per
+//! order costs two round trips, which adds up fast when quoting dozens of markets.
+//! [`MarketMetaCache`] keeps those values behind a TTL so the order path can skip the
+//! requests; it is `Send + Sync` and meant to be shared through an `Arc` between the
+//! REST client and a [`WssMarketClient`](crate::wss::WssMarketClient), which keeps tick
+//! sizes current from `tick_size_change` events.
+
+use crate::wss::WssMarketEvent;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{PoisonError, RwLock};
+use std::time::{Duration, Instant};
+
+/// How long cached metadata stays valid unless overridden
+pub const DEFAULT_META_TTL: Duration = Duration::from_secs(300);
+
+/// Order metadata for a single token
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MarketMeta {
+    pub tick_size: Decimal,
+    pub neg_risk: bool,
+    /// Minimum order size, when the source reported one
+    pub min_order_size: Option<Decimal>,
+}
+
+/// Counters for diagnosing stale-tick rejections
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MarketMetaCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Lookups that found an entry past its TTL (also counted as misses)
+    pub expired: u64,
+    /// Tick sizes rewritten from websocket `tick_size_change` events
+    pub tick_size_updates: u64,
+    pub entries: usize,
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    meta: MarketMeta,
+    stored_at: Instant,
+}
+
+/// Thread-safe, TTL-based cache of [`MarketMeta`] keyed by token ID
+#[derive(Debug)]
+pub struct MarketMetaCache {
+    entries: RwLock<HashMap<String, CacheEntry>>,
+    ttl: Duration,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    expired: AtomicU64,
+    tick_size_updates: AtomicU64,
+}
+
+impl Default for MarketMetaCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_META_TTL)
+    }
+}
+
+impl MarketMetaCache {
+    /// Create an empty cache whose entries expire after `ttl`
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            entries: RwLock::new(HashMap::new()),
+            ttl,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            expired: AtomicU64::new(0),
+            tick_size_updates: AtomicU64::new(0),
+        }
+    }
+
+    /// Entry lifetime
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Cached metadata for `token_id`, if present and still fresh
+    pub fn get(&self, token_id: &str) -> Option<MarketMeta> {
+        let entries = self.entries.read().unwrap_or_else(PoisonError::into_inner);
+        match entries.get(token_id) {
+            Some(entry) if entry.stored_at.elapsed() < self.ttl => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                Some(entry.meta)
+            }
+            Some(_) => {
+                self.expired.fetch_add(1, Ordering::Relaxed);
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
+    /// Store (or refresh) metadata for `token_id`
+    pub fn insert(&self, token_id: impl Into<String>, meta: MarketMeta) {
+        let mut entries = self.entries.write().unwrap_or_else(PoisonError::into_inner);
+        entries.insert(
+            token_id.into(),
+            CacheEntry {
+                meta,
+                stored_at: Instant::now(),
+            },
+        );
+    }
+
+    /// Drop the entry for `token_id` so the next lookup refetches it
+    pub fn invalidate(&self, token_id: &str) {
+        let mut entries = self.entries.write().unwrap_or_else(PoisonError::into_inner);
+        entries.remove(token_id);
+    }
+
+    /// Drop every entry
+    pub fn clear(&self) {
+        let mut entries = self.entries.write().unwrap_or_else(PoisonError::into_inner);
+        entries.clear();
+    }
+
+    /// Apply a tick size change pushed by the market channel.
+    ///
+    /// Known tokens get the new tick size and a fresh TTL; unknown tokens are left alone
+    /// because the event doesn't carry the neg-risk flag.
+    pub fn apply_tick_size_change(&self, token_id: &str, new_tick_size: Decimal) {
+        let mut entries = self.entries.write().unwrap_or_else(PoisonError::into_inner);
+        if let Some(entry) = entries.get_mut(token_id) {
+            entry.meta.tick_size = new_tick_size;
+            entry.stored_at = Instant::now();
+            self.tick_size_updates.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Feed a market channel event through the cache; only tick size changes matter
+    pub fn observe(&self, event: &WssMarketEvent) {
+        if let WssMarketEvent::TickSizeChange(change) = event {
+            self.apply_tick_size_change(&change.asset_id, change.new_tick_size);
+        }
+    }
+
+    /// Snapshot of the cache counters
+    pub fn stats(&self) -> MarketMetaCacheStats {
+        let entries = self.entries.read().unwrap_or_else(PoisonError::into_inner);
+        MarketMetaCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            expired: self.expired.load(Ordering::Relaxed),
+            tick_size_updates: self.tick_size_updates.load(Ordering::Relaxed),
+            entries: entries.len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wss::TickSizeChangeMessage;
+    use rust_decimal_macros::dec;
+
+    fn meta(tick_size: Decimal) -> MarketMeta {
+        MarketMeta {
+            tick_size,
+            neg_risk: false,
+            min_order_size: Some(dec!(5)),
+        }
+    }
+
+    #[test]
+    fn test_hits_misses_and_expiry() {
+        let cache = MarketMetaCache::new(Duration::from_millis(20));
+        assert_eq!(cache.get("1"), None);
+
+        cache.insert("1", meta(dec!(0.01)));
+        assert_eq!(cache.get("1"), Some(meta(dec!(0.01))));
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(cache.get("1"), None);
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.expired), (1, 2, 1));
+        assert_eq!(stats.entries, 1);
+    }
+
+    #[test]
+    fn test_tick_size_change_event_updates_entry() {
+        let cache = MarketMetaCache::default();
+        cache.insert("1", meta(dec!(0.01)));
+
+        let change = |asset_id: &str| {
+            WssMarketEvent::TickSizeChange(TickSizeChangeMessage {
+                event_type: "tick_size_change".to_string(),
+                asset_id: asset_id.to_string(),
+                market: "0xabc".to_string(),
+                old_tick_size: dec!(0.01),
+                new_tick_size: dec!(0.001),
+                side: "BUY".to_string(),
+                timestamp: "0".to_string(),
+                received_at: None,
+            })
+        };
+        cache.observe(&change("1"));
+        cache.observe(&change("unknown"));
+
+        assert_eq!(cache.get("1").unwrap().tick_size, dec!(0.001));
+        assert_eq!(cache.get("unknown"), None);
+        assert_eq!(cache.stats().tick_size_updates, 1);
+    }
+}
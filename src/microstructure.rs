@@ -0,0 +1,725 @@
+//! Spread, impact and liquidity measures from a token's fills and price history
+//!
+//! [`Microstructure::from_trades`] marks every fill against the price history, taken as
+//! the midpoint: the last point at or before the fill is the mid it traded against, the
+//! last point at or before [`REALIZED_SPREAD_HORIZON_SECS`] later is the mid it is judged
+//! by afterwards. Fills are signed by their `side`, +1 for a buy and -1 for a sell, so a
+//! buyer paying above the mid widens the effective spread.
+//!
+//! The regression-based measures work per history interval: [`kyle_lambda`] regresses
+//! each interval's mid change on the signed size traded in it, and [`amihud_illiquidity`]
+//! averages absolute return over USDC volume across the intervals that saw a trade.
+//!
+//!
+//! [`depth_history`] uses the same inputs to approximate how a book's depth moved over
+//! time. The CLOB keeps no book snapshots, so each interval's depth is read off the fills
+//! in it: shares sold into the bids stand in for bid depth, shares bought from the asks
+//! for ask depth, and the gap between the two sides' average fill prices for the spread.
+//! Those figures are liquidity that was taken, a lower bound on what was resting, and an
+//! interval without fills shows zero depth even if the book was full.
+//!
+//! [`market_history`] buckets both into candles: prices from the history points in each
+//! bucket, or from its fills when the history has none there, with the fills' USDC
+//! volume and effective spread alongside.
+//!
+//! [`OrderFlowStats::from_trades`] splits the window's volume by initiator. Every Data API
+//! fill is reported from the taker's side, so `side` is the taker's direction. Makers
+//! only show up through resting size: the change in open interest between two snapshots,
+//! plus what fills took out of the book in between, is what makers added net of what
+//! they cancelled. Only that net figure is observable, so one of the maker volumes is
+//! always zero. Toxicity is VPIN: the window's fills are cut into [`VPIN_BUCKETS`]
+//! buckets of equal volume, a fill straddling a boundary split across both, and VPIN is
+//! the mean buy/sell imbalance per bucket as a share of the bucket's volume.
+//!
+//! [`kyle_lambda`]: Microstructure::kyle_lambda
+//! [`amihud_illiquidity`]: Microstructure::amihud_illiquidity
+
+use crate::errors::{PolyError, Result};
+use crate::types::{DataTrade, OpenInterest, Side};
+use crate::volatility::PricePoint;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// How long after a fill the realized spread and price impact look, in seconds
+pub const REALIZED_SPREAD_HORIZON_SECS: i64 = 300;
+
+const BPS: Decimal = Decimal::from_parts(10_000, 0, 0, false, 0);
+
+/// Order-flow metrics of one token over a window
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Microstructure {
+    /// Mean of `2 * sign * (price - mid)`, in price units
+    pub effective_spread: Decimal,
+    /// Mean of `2 * sign * (price - mid five minutes later)`, in price units; what the
+    /// liquidity provider kept once the mid moved
+    pub realized_spread: Decimal,
+    /// Signed mid move over the following five minutes, in basis points of the mid,
+    /// per share traded
+    pub price_impact_bps: Decimal,
+    /// OLS slope of each interval's mid change on its signed traded size
+    pub kyle_lambda: Decimal,
+    /// Mean absolute interval return per USDC traded in it
+    pub amihud_illiquidity: Decimal,
+    pub window_hours: u32,
+    /// Fills that fell inside the price history
+    pub num_trades: u32,
+}
+
+impl Microstructure {
+    /// Compute the metrics from `trades` and `history`, both in any order
+    ///
+    /// Trades before the first price point are skipped, and those too close to the end
+    /// of the history to have a later mid only count towards the effective spread.
+    /// Needs two usable price points and one trade inside the history; the realized
+    /// spread, impact and lambda are zero when nothing they need was traded.
+    pub fn from_trades(
+        trades: &[DataTrade],
+        history: &[PricePoint],
+        window_hours: u32,
+    ) -> Result<Self> {
+        let mut mids: Vec<PricePoint> = history
+            .iter()
+            .copied()
+            .filter(|point| point.price > Decimal::ZERO)
+            .collect();
+        mids.sort_by_key(|point| point.timestamp);
+        mids.dedup_by_key(|point| point.timestamp);
+        if mids.len() < 2 {
+            return Err(PolyError::validation(format!(
+                "Need at least 2 price points for microstructure, got {}",
+                mids.len()
+            )));
+        }
+        let last_ts = mids[mids.len() - 1].timestamp;
+
+        let mut effective = Vec::new();
+        let mut realized = Vec::new();
+        let mut impact_bps = Decimal::ZERO;
+        let mut impact_size = Decimal::ZERO;
+        // Signed size and USDC volume of the trades landing in each interval
+        let mut flow = vec![Decimal::ZERO; mids.len() - 1];
+        let mut volume = vec![Decimal::ZERO; mids.len() - 1];
+
+        for trade in trades {
+            let Some(mid) = mid_at(&mids, trade.timestamp) else {
+                continue;
+            };
+            let sign = match trade.side {
+                Side::BUY => Decimal::ONE,
+                Side::SELL => Decimal::NEGATIVE_ONE,
+            };
+            effective.push(Decimal::TWO * sign * (trade.price - mid));
+
+            let later_ts = trade.timestamp + REALIZED_SPREAD_HORIZON_SECS;
+            if later_ts <= last_ts
+                && let Some(later) = mid_at(&mids, later_ts)
+            {
+                realized.push(Decimal::TWO * sign * (trade.price - later));
+                impact_bps += sign * (later - mid) / mid * BPS;
+                impact_size += trade.size;
+            }
+
+            // Interval (t0, t1] the trade falls in
+            let next = mids.partition_point(|point| point.timestamp < trade.timestamp);
+            if (1..mids.len()).contains(&next) {
+                flow[next - 1] += sign * trade.size;
+                volume[next - 1] += trade.price * trade.size;
+            }
+        }
+        if effective.is_empty() {
+            return Err(PolyError::validation(
+                "No trades fall within the price history",
+            ));
+        }
+
+        let changes: Vec<Decimal> = mids
+            .windows(2)
+            .map(|pair| pair[1].price - pair[0].price)
+            .collect();
+        let illiquidity: Vec<Decimal> = mids
+            .windows(2)
+            .zip(&volume)
+            .filter(|(_, volume)| **volume > Decimal::ZERO)
+            .map(|(pair, volume)| (pair[1].price / pair[0].price - Decimal::ONE).abs() / volume)
+            .collect();
+
+        Ok(Self {
+            effective_spread: mean(&effective),
+            realized_spread: mean(&realized),
+            price_impact_bps: if impact_size.is_zero() {
+                Decimal::ZERO
+            } else {
+                impact_bps / impact_size
+            },
+            kyle_lambda: ols_slope(&flow, &changes),
+            amihud_illiquidity: mean(&illiquidity),
+            window_hours,
+            num_trades: effective.len() as u32,
+        })
+    }
+}
+
+/// Most snapshots [`depth_history`] returns
+pub const MAX_DEPTH_SNAPSHOTS: usize = 1000;
+
+/// Spacing of [`depth_history`] snapshots and width of [`market_history`] candles
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BookDepthInterval {
+    Minute,
+    FiveMinute,
+    FifteenMinute,
+    Hourly,
+    Daily,
+}
+
+impl BookDepthInterval {
+    pub fn as_secs(&self) -> i64 {
+        match self {
+            BookDepthInterval::Minute => 60,
+            BookDepthInterval::FiveMinute => 300,
+            BookDepthInterval::FifteenMinute => 900,
+            BookDepthInterval::Hourly => 3_600,
+            BookDepthInterval::Daily => 86_400,
+        }
+    }
+}
+
+/// Approximate book state over one interval, reconstructed from fills
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoricalDepthSnapshot {
+    /// Start of the interval, in Unix seconds
+    pub timestamp: i64,
+    /// Shares sold into the bids during the interval
+    pub bid_depth: Decimal,
+    /// Shares bought from the asks during the interval
+    pub ask_depth: Decimal,
+    /// Average buy price minus average sell price, floored at zero; zero unless both
+    /// sides traded
+    pub spread: Decimal,
+    /// Last price-history point at or before the interval's end
+    pub mid_price: Decimal,
+}
+
+/// One snapshot per `interval` from `start_ts`, up to `end_ts` or the first
+/// [`MAX_DEPTH_SNAPSHOTS`] intervals
+///
+/// Intervals are aligned to multiples of their length, and those ending before the first
+/// price point are left out; see the [module docs](self) for what the figures mean.
+pub fn depth_history(
+    trades: &[DataTrade],
+    history: &[PricePoint],
+    start_ts: i64,
+    end_ts: i64,
+    interval: BookDepthInterval,
+) -> Vec<HistoricalDepthSnapshot> {
+    let step = interval.as_secs();
+    let mut mids: Vec<PricePoint> = history
+        .iter()
+        .copied()
+        .filter(|point| point.price > Decimal::ZERO)
+        .collect();
+    mids.sort_by_key(|point| point.timestamp);
+
+    let first = start_ts.div_euclid(step) * step;
+    // Intervals with no price point still count towards the cap, so a sparse history
+    // can't stretch the walk across the whole range
+    let span = end_ts.saturating_sub(first).max(0);
+    let buckets = (span / step + i64::from(span % step != 0)).min(MAX_DEPTH_SNAPSHOTS as i64);
+    let mut snapshots = Vec::new();
+    for index in 0..buckets {
+        let bucket = first + index * step;
+        let Some(bucket_end) = bucket.checked_add(step) else {
+            break;
+        };
+        let Some(mid_price) = mid_at(&mids, bucket_end - 1) else {
+            continue;
+        };
+
+        let (mut bought, mut bought_value) = (Decimal::ZERO, Decimal::ZERO);
+        let (mut sold, mut sold_value) = (Decimal::ZERO, Decimal::ZERO);
+        for trade in trades
+            .iter()
+            .filter(|trade| (bucket..bucket_end).contains(&trade.timestamp))
+        {
+            match trade.side {
+                Side::BUY => {
+                    bought += trade.size;
+                    bought_value += trade.price * trade.size;
+                }
+                Side::SELL => {
+                    sold += trade.size;
+                    sold_value += trade.price * trade.size;
+                }
+            }
+        }
+        let spread = if bought.is_zero() || sold.is_zero() {
+            Decimal::ZERO
+        } else {
+            (bought_value / bought - sold_value / sold).max(Decimal::ZERO)
+        };
+
+        snapshots.push(HistoricalDepthSnapshot {
+            timestamp: bucket,
+            bid_depth: sold,
+            ask_depth: bought,
+            spread,
+            mid_price,
+        });
+    }
+    snapshots
+}
+
+/// Most candles [`market_history`] returns
+pub const MAX_HISTORY_CANDLES: u32 = 1000;
+
+/// Width of a [`PriceCandle`]
+pub type PriceResolution = BookDepthInterval;
+
+/// Prices, volume and spread of one token over one bucket
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PriceCandle {
+    /// Start of the bucket, in Unix seconds
+    pub timestamp: i64,
+    pub open: Decimal,
+    pub high: Decimal,
+    pub low: Decimal,
+    pub close: Decimal,
+    /// USDC traded in the bucket
+    pub volume: Decimal,
+    pub trade_count: u32,
+    /// Mean effective spread of the bucket's fills against the mid, as in
+    /// [`Microstructure::effective_spread`]; zero without fills
+    pub spread_avg: Decimal,
+}
+
+/// One token's candles, oldest first
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MarketHistory {
+    pub token_id: String,
+    pub resolution: PriceResolution,
+    pub candles: Vec<PriceCandle>,
+}
+
+/// The last `periods` buckets of `resolution` up to the one holding `end_ts`, at most
+/// [`MAX_HISTORY_CANDLES`]
+///
+/// Buckets are aligned to multiples of their length. One with neither a price point
+/// nor a fill is left out, so a quiet market returns fewer candles than asked for.
+pub fn market_history(
+    token_id: &str,
+    trades: &[DataTrade],
+    history: &[PricePoint],
+    end_ts: i64,
+    resolution: PriceResolution,
+    periods: u32,
+) -> MarketHistory {
+    let step = resolution.as_secs();
+    let mut mids: Vec<PricePoint> = history
+        .iter()
+        .copied()
+        .filter(|point| point.price > Decimal::ZERO)
+        .collect();
+    mids.sort_by_key(|point| point.timestamp);
+    let mut fills: Vec<&DataTrade> = trades.iter().collect();
+    fills.sort_by_key(|trade| trade.timestamp);
+
+    let periods = i64::from(periods.min(MAX_HISTORY_CANDLES));
+    let last = end_ts.div_euclid(step) * step;
+    let mut candles = Vec::new();
+    for bucket in (0..periods).rev().map(|back| last - back * step) {
+        let in_bucket = |timestamp: i64| (bucket..bucket + step).contains(&timestamp);
+        let bucket_fills: Vec<&DataTrade> = fills
+            .iter()
+            .copied()
+            .filter(|trade| in_bucket(trade.timestamp))
+            .collect();
+        let mut prices: Vec<Decimal> = mids
+            .iter()
+            .filter(|point| in_bucket(point.timestamp))
+            .map(|point| point.price)
+            .collect();
+        if prices.is_empty() {
+            prices = bucket_fills.iter().map(|trade| trade.price).collect();
+        }
+        let (Some(&open), Some(&close)) = (prices.first(), prices.last()) else {
+            continue;
+        };
+
+        let spreads: Vec<Decimal> = bucket_fills
+            .iter()
+            .filter_map(|trade| {
+                let mid = mid_at(&mids, trade.timestamp)?;
+                let sign = match trade.side {
+                    Side::BUY => Decimal::ONE,
+                    Side::SELL => Decimal::NEGATIVE_ONE,
+                };
+                Some(Decimal::TWO * sign * (trade.price - mid))
+            })
+            .collect();
+        candles.push(PriceCandle {
+            timestamp: bucket,
+            open,
+            high: prices.iter().copied().max().unwrap_or(open),
+            low: prices.iter().copied().min().unwrap_or(open),
+            close,
+            volume: bucket_fills
+                .iter()
+                .map(|trade| trade.price * trade.size)
+                .sum(),
+            trade_count: bucket_fills.len() as u32,
+            spread_avg: mean(&spreads),
+        });
+    }
+
+    MarketHistory {
+        token_id: token_id.to_string(),
+        resolution,
+        candles,
+    }
+}
+
+/// Number of equal-volume buckets [`OrderFlowStats::from_trades`] computes VPIN over
+pub const VPIN_BUCKETS: u32 = 50;
+
+/// VPIN above which [`OrderFlowStats::is_informed_trading_detected`] flags the flow
+pub const INFORMED_TRADING_VPIN: Decimal = Decimal::from_parts(5, 0, 0, false, 1);
+
+/// Taker and maker volume of one token over a window, in shares
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrderFlowStats {
+    pub taker_buy_volume: Decimal,
+    pub taker_sell_volume: Decimal,
+    /// Resting size makers added beyond what they cancelled since the baseline snapshot
+    pub maker_add_volume: Decimal,
+    /// Resting size makers cancelled beyond what they added since the baseline snapshot
+    pub maker_cancel_volume: Decimal,
+    /// VPIN over the window's fills, zero without any
+    pub order_flow_toxicity: Decimal,
+    /// `taker_buy_volume - taker_sell_volume`
+    pub net_taker_flow: Decimal,
+}
+
+impl OrderFlowStats {
+    /// Compute the stats from `trades` at or after `start_ts`, in any order
+    ///
+    /// `open_interest` is the book now and `baseline` an earlier snapshot with the Unix
+    /// second it was taken at; fills after that second are added back to the change in
+    /// resting size. Without a baseline both maker volumes are zero.
+    pub fn from_trades(
+        trades: &[DataTrade],
+        start_ts: i64,
+        open_interest: &OpenInterest,
+        baseline: Option<(i64, &OpenInterest)>,
+    ) -> Self {
+        let mut fills: Vec<&DataTrade> = trades
+            .iter()
+            .filter(|trade| trade.timestamp >= start_ts && trade.size > Decimal::ZERO)
+            .collect();
+        fills.sort_by_key(|trade| trade.timestamp);
+
+        let volume = |side: Side| -> Decimal {
+            fills
+                .iter()
+                .filter(|trade| trade.side == side)
+                .map(|trade| trade.size)
+                .sum()
+        };
+        let (taker_buy_volume, taker_sell_volume) = (volume(Side::BUY), volume(Side::SELL));
+
+        let net_added = baseline.map_or(Decimal::ZERO, |(taken_at, baseline)| {
+            let filled: Decimal = trades
+                .iter()
+                .filter(|trade| trade.timestamp > taken_at)
+                .map(|trade| trade.size)
+                .sum();
+            open_interest.total_open_interest - baseline.total_open_interest + filled
+        });
+
+        Self {
+            taker_buy_volume,
+            taker_sell_volume,
+            maker_add_volume: net_added.max(Decimal::ZERO),
+            maker_cancel_volume: (-net_added).max(Decimal::ZERO),
+            order_flow_toxicity: vpin(&fills, taker_buy_volume + taker_sell_volume),
+            net_taker_flow: taker_buy_volume - taker_sell_volume,
+        }
+    }
+
+    /// [`order_flow_toxicity`](Self::order_flow_toxicity) clamped to `[0, 1]`
+    pub fn vpin_score(&self) -> Decimal {
+        self.order_flow_toxicity.clamp(Decimal::ZERO, Decimal::ONE)
+    }
+
+    /// Whether the VPIN score is above [`INFORMED_TRADING_VPIN`]
+    pub fn is_informed_trading_detected(&self) -> bool {
+        self.vpin_score() > INFORMED_TRADING_VPIN
+    }
+}
+
+/// Mean `|buy - sell| / volume` over [`VPIN_BUCKETS`] equal-volume buckets of `fills`,
+/// which must be sorted and total `total` shares
+fn vpin(fills: &[&DataTrade], total: Decimal) -> Decimal {
+    if total.is_zero() {
+        return Decimal::ZERO;
+    }
+    let bucket_size = total / Decimal::from(VPIN_BUCKETS);
+    let (mut imbalance, mut filled, mut signed) = (Decimal::ZERO, Decimal::ZERO, Decimal::ZERO);
+    for trade in fills {
+        let sign = match trade.side {
+            Side::BUY => Decimal::ONE,
+            Side::SELL => Decimal::NEGATIVE_ONE,
+        };
+        let mut left = trade.size;
+        while left > Decimal::ZERO {
+            let take = left.min(bucket_size - filled);
+            filled += take;
+            signed += sign * take;
+            left -= take;
+            if filled >= bucket_size {
+                imbalance += signed.abs();
+                (filled, signed) = (Decimal::ZERO, Decimal::ZERO);
+            }
+        }
+    }
+    // `bucket_size` is rounded, so the last bucket may come up a hair short
+    imbalance += signed.abs();
+    (imbalance / total).round_dp(18)
+}
+
+/// Price of the last point at or before `timestamp`; `mids` must be sorted
+fn mid_at(mids: &[PricePoint], timestamp: i64) -> Option<Decimal> {
+    let after = mids.partition_point(|point| point.timestamp <= timestamp);
+    after.checked_sub(1).map(|i| mids[i].price)
+}
+
+fn mean(values: &[Decimal]) -> Decimal {
+    if values.is_empty() {
+        return Decimal::ZERO;
+    }
+    values.iter().sum::<Decimal>() / Decimal::from(values.len())
+}
+
+/// Slope of `y` on `x` with an intercept; zero when `x` never varies
+fn ols_slope(x: &[Decimal], y: &[Decimal]) -> Decimal {
+    let (mean_x, mean_y) = (mean(x), mean(y));
+    let (mut covariance, mut variance) = (Decimal::ZERO, Decimal::ZERO);
+    for (x, y) in x.iter().zip(y) {
+        covariance += (x - mean_x) * (y - mean_y);
+        variance += (x - mean_x) * (x - mean_x);
+    }
+    if variance.is_zero() {
+        Decimal::ZERO
+    } else {
+        covariance / variance
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn point(minute: i64, price: Decimal) -> PricePoint {
+        PricePoint {
+            timestamp: 60 * minute,
+            price,
+        }
+    }
+
+    fn trade(second: i64, side: Side, price: Decimal, size: Decimal) -> DataTrade {
+        DataTrade {
+            proxy_wallet: None,
+            side,
+            asset: "1".to_string(),
+            condition_id: "0xc".to_string(),
+            size,
+            price,
+            timestamp: second,
+            transaction_hash: None,
+        }
+    }
+
+    #[test]
+    fn test_spreads_and_impact() {
+        // Mid steps 0.50 → 0.52 at minute 5, then → 0.51 at minute 10
+        let history = [
+            point(0, dec!(0.50)),
+            point(5, dec!(0.52)),
+            point(10, dec!(0.51)),
+        ];
+        let trades = [
+            trade(60, Side::BUY, dec!(0.51), dec!(100)),
+            trade(420, Side::SELL, dec!(0.515), dec!(50)),
+        ];
+        let metrics = Microstructure::from_trades(&trades, &history, 1).unwrap();
+
+        assert_eq!(metrics.num_trades, 2);
+        // Buy: 2 * (0.51 - 0.50); sell: -2 * (0.515 - 0.52)
+        assert_eq!(metrics.effective_spread, dec!(0.015));
+        // Only the buy has a mid five minutes later (0.52 at 360s)
+        assert_eq!(metrics.realized_spread, dec!(-0.02));
+        assert_eq!(metrics.price_impact_bps, dec!(4));
+        // Flow +100 then -50 against mid changes +0.02 and -0.01
+        assert_eq!(metrics.kyle_lambda, dec!(0.0002));
+        let amihud = (dec!(0.04) / dec!(51)
+            + (dec!(0.51) / dec!(0.52) - Decimal::ONE).abs() / dec!(25.75))
+            / Decimal::TWO;
+        assert_eq!(metrics.amihud_illiquidity, amihud);
+    }
+
+    #[test]
+    fn test_depth_history_buckets_fills() {
+        let history = [point(0, dec!(0.50)), point(7, dec!(0.54))];
+        let trades = [
+            trade(30, Side::BUY, dec!(0.52), dec!(10)),
+            trade(90, Side::BUY, dec!(0.54), dec!(30)),
+            trade(200, Side::SELL, dec!(0.49), dec!(20)),
+            trade(400, Side::SELL, dec!(0.53), dec!(5)),
+        ];
+        let snapshots = depth_history(&trades, &history, 10, 900, BookDepthInterval::FiveMinute);
+
+        assert_eq!(snapshots.len(), 3);
+        let first = &snapshots[0];
+        assert_eq!(first.timestamp, 0);
+        assert_eq!((first.bid_depth, first.ask_depth), (dec!(20), dec!(40)));
+        // Buys average 0.535, the sell 0.49
+        assert_eq!(first.spread, dec!(0.045));
+        assert_eq!(first.mid_price, dec!(0.50));
+        // Only a sell; the mid has moved by the end of the interval
+        assert_eq!(snapshots[1].spread, Decimal::ZERO);
+        assert_eq!(snapshots[1].mid_price, dec!(0.54));
+        assert_eq!(snapshots[2].bid_depth, Decimal::ZERO);
+
+        let capped = depth_history(&[], &history, 0, i64::MAX, BookDepthInterval::FiveMinute);
+        assert_eq!(capped.len(), MAX_DEPTH_SNAPSHOTS);
+        // The cap counts intervals, not snapshots, so a late first price bounds the walk
+        let late = [point(3_000_000, dec!(0.50))];
+        assert!(depth_history(&[], &late, 0, i64::MAX, BookDepthInterval::FiveMinute).is_empty());
+        // The last interval before i64::MAX can't be closed and is left out
+        let edge = depth_history(
+            &[],
+            &history,
+            i64::MAX - 10,
+            i64::MAX,
+            BookDepthInterval::Daily,
+        );
+        assert!(edge.is_empty());
+        // Intervals ending before the first price point are dropped
+        assert!(
+            depth_history(
+                &trades,
+                &history[1..],
+                0,
+                300,
+                BookDepthInterval::FiveMinute
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_market_history_candles() {
+        // Minute buckets 0..=3 with the last holding second 200
+        let history = [
+            point(0, dec!(0.50)),
+            PricePoint {
+                timestamp: 30,
+                price: dec!(0.56),
+            },
+            PricePoint {
+                timestamp: 50,
+                price: dec!(0.52),
+            },
+            point(2, dec!(0.60)),
+        ];
+        let trades = [
+            trade(10, Side::BUY, dec!(0.51), dec!(100)),
+            trade(40, Side::SELL, dec!(0.55), dec!(10)),
+            trade(70, Side::BUY, dec!(0.58), dec!(20)),
+        ];
+        let candles =
+            market_history("1", &trades, &history, 200, PriceResolution::Minute, 4).candles;
+
+        // Minute 3 has neither a point nor a fill
+        assert_eq!(candles.len(), 3);
+        let first = &candles[0];
+        assert_eq!(
+            (first.open, first.high, first.low, first.close),
+            (dec!(0.50), dec!(0.56), dec!(0.50), dec!(0.52))
+        );
+        assert_eq!(first.volume, dec!(56.5));
+        assert_eq!(first.trade_count, 2);
+        // Buy 2 * (0.51 - 0.50), sell -2 * (0.55 - 0.56)
+        assert_eq!(first.spread_avg, dec!(0.02));
+        // No history point in minute 1, so prices come from its fill
+        assert_eq!(
+            (candles[1].open, candles[1].close),
+            (dec!(0.58), dec!(0.58))
+        );
+        assert_eq!(candles[1].spread_avg, dec!(0.12));
+        assert_eq!((candles[2].timestamp, candles[2].trade_count), (120, 0));
+
+        let every_minute: Vec<PricePoint> =
+            (0..1200).map(|minute| point(minute, dec!(0.5))).collect();
+        let capped = market_history(
+            "1",
+            &[],
+            &every_minute,
+            1199 * 60,
+            PriceResolution::Minute,
+            5000,
+        );
+        assert_eq!(capped.candles.len(), MAX_HISTORY_CANDLES as usize);
+        assert_eq!(capped.candles[0].timestamp, 200 * 60);
+    }
+
+    #[test]
+    fn test_requires_trades_inside_history() {
+        let history = [point(10, dec!(0.5)), point(11, dec!(0.5))];
+        let early = [trade(0, Side::BUY, dec!(0.5), dec!(1))];
+        assert!(matches!(
+            Microstructure::from_trades(&early, &history, 1),
+            Err(PolyError::Validation { .. })
+        ));
+        assert!(Microstructure::from_trades(&early, &history[..1], 1).is_err());
+    }
+
+    #[test]
+    fn test_order_flow_balanced_and_maker_net() {
+        let open_interest = |total: Decimal| OpenInterest {
+            token_id: "1".to_string(),
+            bid_open_interest: total,
+            ask_open_interest: Decimal::ZERO,
+            total_open_interest: total,
+        };
+        // Alternating one-share buys and sells fill every two-share bucket evenly
+        let trades: Vec<DataTrade> = (0..100)
+            .map(|i| {
+                let side = if i % 2 == 0 { Side::BUY } else { Side::SELL };
+                trade(100 + i, side, dec!(0.5), dec!(1))
+            })
+            .collect();
+
+        let baseline = open_interest(dec!(100));
+        let stats = OrderFlowStats::from_trades(
+            &trades,
+            0,
+            &open_interest(dec!(80)),
+            Some((149, &baseline)),
+        );
+        assert_eq!(stats.taker_buy_volume, dec!(50));
+        assert_eq!(stats.net_taker_flow, Decimal::ZERO);
+        assert_eq!(stats.order_flow_toxicity, Decimal::ZERO);
+        assert!(!stats.is_informed_trading_detected());
+        // Fills after second 149 took 50 shares while the book lost only 20
+        assert_eq!(stats.maker_add_volume, dec!(30));
+        assert_eq!(stats.maker_cancel_volume, Decimal::ZERO);
+
+        let stats = OrderFlowStats::from_trades(&trades[..1], 0, &baseline, None);
+        assert_eq!(stats.vpin_score(), Decimal::ONE);
+        assert_eq!(stats.maker_add_volume, Decimal::ZERO);
+
+        let empty = OrderFlowStats::from_trades(&[], 0, &baseline, None);
+        assert_eq!(empty.vpin_score(), Decimal::ZERO);
+    }
+}
@@ -0,0 +1,421 @@
+//! tower middleware for the REST client
+//!
+//! [`ClobClient::http_service`] exposes the transport under every request as a
+//! `tower::Service<reqwest::Request>`, and
+//! [`ClobClient::with_tower_middleware`] routes the client's requests through a stack
+//! built on top of it:
+//!
+//! ```ignore
+//! use tower::ServiceBuilder;
+//!
+//! let service = ServiceBuilder::new()
+//!     .layer(PolyLoggingLayer)
+//!     .layer(PolyRetryLayer::new(3))
+//!     .layer(PolyRateLimitLayer::new(RequestLimits::new(8)))
+//!     .timeout(Duration::from_secs(10))
+//!     .service(client.http_service());
+//! let client = client.with_tower_middleware(service);
+//! ```
+//!
+//! Layers listed first run outermost, so in this stack each retry queues for a slot
+//! again and the timeout applies per attempt. Any tower layer fits as long as the stack
+//! stays `Clone + Send + Sync`. The client's own limiter is bypassed while a stack is
+//! installed; [`PolyRateLimitLayer`] takes its place.
+//!
+//! [`ClobClient::http_service`]: crate::client::ClobClient::http_service
+//! [`ClobClient::with_tower_middleware`]: crate::client::ClobClient::with_tower_middleware
+
+use crate::client::execute_request;
+use crate::failover::HostPool;
+use crate::limiter::{Priority, RequestLimiter, RequestLimits};
+use futures::future::BoxFuture;
+use reqwest::{Client, Method, Request, Response};
+use std::fmt;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tower::{BoxError, Layer, Service, ServiceExt};
+use tracing::{debug, warn};
+
+/// The client's HTTP transport, including host failover and request metrics
+#[derive(Clone)]
+pub struct HttpService {
+    http_client: Client,
+    pools: Arc<[Arc<HostPool>]>,
+}
+
+impl HttpService {
+    pub(crate) fn new(http_client: Client, pools: Vec<Arc<HostPool>>) -> Self {
+        Self {
+            http_client,
+            pools: pools.into(),
+        }
+    }
+}
+
+impl fmt::Debug for HttpService {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpService")
+            .field("pools", &self.pools.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Service<Request> for HttpService {
+    type Response = Response;
+    type Error = reqwest::Error;
+    type Future = BoxFuture<'static, reqwest::Result<Response>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let this = self.clone();
+        Box::pin(
+            async move { execute_request(&this.http_client, this.pools.iter(), request).await },
+        )
+    }
+}
+
+/// Admits requests through a [`RequestLimiter`], order writes first
+///
+/// The same scheduling as [`ClobClient::with_request_limits`], as a layer. A slot is
+/// held until the inner service returns the response headers.
+///
+/// [`ClobClient::with_request_limits`]: crate::client::ClobClient::with_request_limits
+#[derive(Debug, Clone)]
+pub struct PolyRateLimitLayer {
+    limiter: RequestLimiter,
+}
+
+impl PolyRateLimitLayer {
+    pub fn new(limits: RequestLimits) -> Self {
+        Self {
+            limiter: RequestLimiter::new(limits),
+        }
+    }
+
+    /// The limiter shared by every service this layer builds
+    pub fn limiter(&self) -> &RequestLimiter {
+        &self.limiter
+    }
+}
+
+impl<S> Layer<S> for PolyRateLimitLayer {
+    type Service = PolyRateLimit<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        PolyRateLimit {
+            inner,
+            limiter: self.limiter.clone(),
+        }
+    }
+}
+
+/// Service built by [`PolyRateLimitLayer`]
+#[derive(Debug, Clone)]
+pub struct PolyRateLimit<S> {
+    inner: S,
+    limiter: RequestLimiter,
+}
+
+impl<S> Service<Request> for PolyRateLimit<S>
+where
+    S: Service<Request, Response = Response> + Clone + Send + 'static,
+    S::Future: Send,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Response, S::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // The inner service is polled for readiness once a slot is held
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let limiter = self.limiter.clone();
+        let inner = self.inner.clone();
+        Box::pin(async move {
+            let priority = Priority::for_request(request.method(), request.url().path());
+            let _permit = limiter.acquire(priority).await;
+            inner.oneshot(request).await
+        })
+    }
+}
+
+/// Retries `GET` and `HEAD` requests that hit a 429, a 5xx or a connection failure
+///
+/// Other methods, and requests whose body can't be replayed, get a single attempt: an
+/// order post that timed out may still have been accepted. Waits between attempts
+/// double from the base delay (100ms unless set).
+#[derive(Debug, Clone, Copy)]
+pub struct PolyRetryLayer {
+    max_retries: u32,
+    base_delay: Duration,
+}
+
+impl PolyRetryLayer {
+    pub fn new(max_retries: u32) -> Self {
+        Self {
+            max_retries,
+            base_delay: Duration::from_millis(100),
+        }
+    }
+
+    pub fn with_base_delay(mut self, delay: Duration) -> Self {
+        self.base_delay = delay;
+        self
+    }
+}
+
+impl<S> Layer<S> for PolyRetryLayer {
+    type Service = PolyRetry<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        PolyRetry {
+            inner,
+            config: *self,
+        }
+    }
+}
+
+/// Service built by [`PolyRetryLayer`]
+#[derive(Debug, Clone)]
+pub struct PolyRetry<S> {
+    inner: S,
+    config: PolyRetryLayer,
+}
+
+fn should_retry(result: &Result<Response, BoxError>) -> bool {
+    match result {
+        Ok(response) => {
+            response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS
+                || response.status().is_server_error()
+        }
+        Err(err) => err
+            .downcast_ref::<reqwest::Error>()
+            .is_some_and(|err| err.is_connect() || err.is_timeout()),
+    }
+}
+
+impl<S> Service<Request> for PolyRetry<S>
+where
+    S: Service<Request, Response = Response> + Clone + Send + 'static,
+    S::Error: Into<BoxError>,
+    S::Future: Send,
+{
+    type Response = Response;
+    type Error = BoxError;
+    type Future = BoxFuture<'static, Result<Response, BoxError>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let inner = self.inner.clone();
+        let PolyRetryLayer {
+            max_retries,
+            base_delay,
+        } = self.config;
+        let idempotent = matches!(*request.method(), Method::GET | Method::HEAD);
+        Box::pin(async move {
+            let mut attempt = 0;
+            loop {
+                let replay = (idempotent && attempt < max_retries)
+                    .then(|| request.try_clone())
+                    .flatten();
+                let Some(current) = replay else {
+                    return inner.oneshot(request).await.map_err(Into::into);
+                };
+                let result = inner.clone().oneshot(current).await.map_err(Into::into);
+                if !should_retry(&result) {
+                    return result;
+                }
+                let delay = base_delay.saturating_mul(1 << attempt.min(16));
+                warn!(
+                    path = request.url().path(),
+                    attempt = attempt + 1,
+                    "retrying request in {:?}",
+                    delay
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+        })
+    }
+}
+
+/// Logs each request's method, path, status and latency through `tracing`
+///
+/// Responses are logged at `debug`, failures at `warn`. Query strings are left out,
+/// since they can carry wallet addresses.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PolyLoggingLayer;
+
+impl<S> Layer<S> for PolyLoggingLayer {
+    type Service = PolyLogging<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        PolyLogging { inner }
+    }
+}
+
+/// Service built by [`PolyLoggingLayer`]
+#[derive(Debug, Clone)]
+pub struct PolyLogging<S> {
+    inner: S,
+}
+
+impl<S> Service<Request> for PolyLogging<S>
+where
+    S: Service<Request, Response = Response>,
+    S::Error: fmt::Display,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Response, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let method = request.method().clone();
+        let path = request.url().path().to_string();
+        let started = Instant::now();
+        let response = self.inner.call(request);
+        Box::pin(async move {
+            let result = response.await;
+            let elapsed_ms = started.elapsed().as_millis() as u64;
+            match &result {
+                Ok(response) => debug!(
+                    %method,
+                    path,
+                    status = response.status().as_u16(),
+                    elapsed_ms,
+                    "http request"
+                ),
+                Err(err) => warn!(%method, path, elapsed_ms, error = %err, "http request failed"),
+            }
+            result
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::ClobClient;
+    use crate::errors::PolyError;
+    use crate::testkit::MockExchange;
+    use mockito::Matcher;
+    use tower::ServiceBuilder;
+
+    #[tokio::test]
+    async fn test_retry_layer_replays_only_idempotent_requests() {
+        let mut server = MockExchange::empty().await;
+        let get = server
+            .mock("GET", "/midpoint")
+            .match_query(Matcher::Any)
+            .with_status(503)
+            .expect(3)
+            .create_async()
+            .await;
+        let post = server
+            .mock("POST", "/midpoints")
+            .with_status(503)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = ClobClient::new(&server.url());
+        let service = ServiceBuilder::new()
+            .layer(PolyLoggingLayer)
+            .layer(PolyRetryLayer::new(2).with_base_delay(Duration::from_millis(1)))
+            .service(client.http_service());
+        let client = client.with_tower_middleware(service);
+
+        let err = client.get_midpoint("1").await.unwrap_err();
+        assert!(matches!(err, PolyError::Api { status: 503, .. }));
+        assert!(client.get_midpoints(&["1".to_string()]).await.is_err());
+
+        get.assert_async().await;
+        post.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_layers_compose_with_tower_builtins() {
+        let mut server = MockExchange::empty().await;
+        server
+            .mock("GET", "/midpoint")
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(include_str!("../tests/fixtures/http/clob_midpoint.json"))
+            .create_async()
+            .await;
+
+        let base = ClobClient::new(&server.url());
+        let limit = PolyRateLimitLayer::new(RequestLimits::new(1));
+        let stack = |timeout: Duration| {
+            ServiceBuilder::new()
+                .layer(limit.clone())
+                .timeout(timeout)
+                .service(base.http_service())
+        };
+
+        let client =
+            ClobClient::new(&server.url()).with_tower_middleware(stack(Duration::from_secs(5)));
+        let midpoint = client.get_midpoint("1").await.unwrap();
+        assert_eq!(midpoint.mid.to_string(), "0.145");
+
+        // Errors raised by a layer, not the transport, surface as network errors
+        let client = ClobClient::new(&server.url()).with_tower_middleware(stack(Duration::ZERO));
+        let err = client.get_midpoint("1").await.unwrap_err();
+        assert!(matches!(err, PolyError::Network { .. }));
+        assert!(err.to_string().contains("timed out"));
+
+        assert_eq!(limit.limiter().stats().in_flight, 0);
+    }
+
+    #[tokio::test]
+    async fn test_middleware_bypasses_the_client_limiter() {
+        let mut server = MockExchange::empty().await;
+        server
+            .mock("GET", "/midpoint")
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(include_str!("../tests/fixtures/http/clob_midpoint.json"))
+            .expect(2)
+            .create_async()
+            .await;
+
+        // Both requests have to reach the stack before either goes out, which a
+        // one-slot client limiter in front of it would never allow
+        let inner = ClobClient::new(&server.url()).http_service();
+        let barrier = Arc::new(tokio::sync::Barrier::new(2));
+        let service = tower::service_fn(move |request: Request| {
+            let (inner, barrier) = (inner.clone(), barrier.clone());
+            async move {
+                barrier.wait().await;
+                inner.oneshot(request).await
+            }
+        });
+        let client = ClobClient::new(&server.url())
+            .with_max_concurrency(1)
+            .with_tower_middleware(service);
+
+        let (first, second) = tokio::time::timeout(
+            Duration::from_secs(5),
+            futures::future::join(client.get_midpoint("1"), client.get_midpoint("2")),
+        )
+        .await
+        .expect("the client limiter held a request back");
+        assert!(first.is_ok() && second.is_ok());
+    }
+}
@@ -0,0 +1,579 @@
+//! On-chain redemption of resolved positions (`onchain` feature)
+//!
+//! After resolution, winning outcome tokens pay out only once they're burned through
+//! `redeemPositions`: on the ConditionalTokens contract for standard markets, or on the
+//! NegRiskAdapter for neg-risk markets. [`SettlementClient`] builds that call, signs it
+//! as an EIP-1559 transaction and submits it over a plain JSON-RPC endpoint. It reuses
+//! alloy's transaction types and talks to the node through the crate's own `reqwest`
+//! client, so enabling the feature doesn't bring in a provider stack.
+
+use crate::errors::{OrderErrorKind, PolyError, Result};
+use alloy_consensus::{SignableTransaction, TxEip1559, TxEnvelope};
+use alloy_eips::eip2718::Encodable2718;
+use alloy_primitives::{Address, B256, Bytes, TxKind, U256};
+use alloy_signer::SignerSync;
+use alloy_signer_local::PrivateKeySigner;
+use alloy_sol_types::{SolCall, SolEvent, sol};
+use rust_decimal::Decimal;
+use serde_json::{Value, json};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// NegRiskAdapter on Polygon; neg-risk positions are redeemed through it
+pub const NEG_RISK_ADAPTER: &str = "0xd91E80cF2E7be2e162c6513ceD06f1dD0dA35296";
+
+/// Collateral (USDC.e) decimals, used to scale payouts
+const COLLATERAL_DECIMALS: u32 = 6;
+
+/// Headroom added on top of `eth_estimateGas`, in percent
+const GAS_LIMIT_BUFFER_PCT: u64 = 20;
+
+const DEFAULT_RECEIPT_TIMEOUT: Duration = Duration::from_secs(120);
+
+sol! {
+    interface IConditionalTokens {
+        function redeemPositions(
+            address collateralToken,
+            bytes32 parentCollectionId,
+            bytes32 conditionId,
+            uint256[] indexSets
+        );
+        function balanceOf(address owner, uint256 id) returns (uint256);
+
+        event PayoutRedemption(
+            address indexed redeemer,
+            address indexed collateralToken,
+            bytes32 indexed parentCollectionId,
+            bytes32 conditionId,
+            uint256[] indexSets,
+            uint256 payout
+        );
+    }
+
+    interface INegRiskAdapter {
+        function redeemPositions(bytes32 conditionId, uint256[] amounts);
+
+        event PayoutRedemption(
+            address indexed redeemer,
+            bytes32 indexed conditionId,
+            uint256[] amounts,
+            uint256 payout
+        );
+    }
+}
+
+/// Fee and gas settings that replace the values read from the node
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GasOverrides {
+    pub gas_limit: Option<u64>,
+    /// Wei; defaults to twice the latest base fee plus the priority fee
+    pub max_fee_per_gas: Option<u128>,
+    /// Wei; defaults to `eth_maxPriorityFeePerGas`
+    pub max_priority_fee_per_gas: Option<u128>,
+}
+
+/// How [`SettlementClient::redeem_positions`] should redeem
+#[derive(Debug, Clone, Default)]
+pub struct RedeemOptions {
+    /// Redeem through the NegRiskAdapter instead of ConditionalTokens
+    pub neg_risk: bool,
+    pub gas: GasOverrides,
+    /// Build and estimate the transaction without sending it
+    pub dry_run: bool,
+    /// How long to wait for the receipt (two minutes when `None`)
+    pub receipt_timeout: Option<Duration>,
+}
+
+/// A fully priced redemption that hasn't been sent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedeemEstimate {
+    pub to: Address,
+    pub data: Bytes,
+    pub gas_limit: u64,
+    pub max_fee_per_gas: u128,
+    pub max_priority_fee_per_gas: u128,
+}
+
+impl RedeemEstimate {
+    /// Upper bound on the fee in wei (`gas_limit * max_fee_per_gas`)
+    pub fn max_cost_wei(&self) -> U256 {
+        U256::from(self.gas_limit) * U256::from(self.max_fee_per_gas)
+    }
+}
+
+/// A mined redemption
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedeemReceipt {
+    pub tx_hash: B256,
+    pub block_number: u64,
+    pub gas_used: u64,
+    /// Collateral paid out, in base units
+    pub payout: U256,
+}
+
+impl RedeemReceipt {
+    /// Payout in USDC
+    pub fn payout_usdc(&self) -> Decimal {
+        let units = Decimal::from_str(&self.payout.to_string()).unwrap_or(Decimal::MAX);
+        units / Decimal::from(10u64.pow(COLLATERAL_DECIMALS))
+    }
+}
+
+/// Result of [`SettlementClient::redeem_positions`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RedeemOutcome {
+    /// `dry_run` was set; nothing was sent
+    DryRun(RedeemEstimate),
+    Redeemed(RedeemReceipt),
+}
+
+/// Submits settlement transactions through a JSON-RPC node
+#[derive(Debug, Clone)]
+pub struct SettlementClient {
+    http_client: reqwest::Client,
+    rpc_url: String,
+    chain_id: u64,
+    neg_risk_adapter: Address,
+    poll_interval: Duration,
+}
+
+impl SettlementClient {
+    /// Client for the node at `rpc_url` on `chain_id` (137 for Polygon mainnet)
+    pub fn new(rpc_url: impl Into<String>, chain_id: u64) -> Self {
+        Self {
+            http_client: reqwest::Client::new(),
+            rpc_url: rpc_url.into(),
+            chain_id,
+            neg_risk_adapter: Address::from_str(NEG_RISK_ADAPTER).unwrap_or_default(),
+            poll_interval: Duration::from_secs(2),
+        }
+    }
+
+    /// Override the NegRiskAdapter address (e.g. on a testnet)
+    pub fn with_neg_risk_adapter(mut self, adapter: Address) -> Self {
+        self.neg_risk_adapter = adapter;
+        self
+    }
+
+    /// How often to poll for the receipt
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Redeem `signer`'s positions in the resolved market `condition_id`
+    ///
+    /// Standard markets redeem both outcome slots against USDC; neg-risk markets pass the
+    /// signer's Yes/No balances to the adapter. Fails without sending anything when the
+    /// signer holds neither outcome. Otherwise waits for the receipt and reports the
+    /// collateral paid out (zero for a losing position, which still burns the tokens).
+    pub async fn redeem_positions(
+        &self,
+        condition_id: &str,
+        signer: &PrivateKeySigner,
+        options: &RedeemOptions,
+    ) -> Result<RedeemOutcome> {
+        let owner = signer.address();
+        let (to, data) = self
+            .redeem_call(condition_id, owner, options.neg_risk)
+            .await?;
+        let estimate = self.estimate(owner, to, data, &options.gas).await?;
+        if options.dry_run {
+            return Ok(RedeemOutcome::DryRun(estimate));
+        }
+
+        let nonce = self
+            .rpc("eth_getTransactionCount", json!([owner, "pending"]))
+            .await
+            .and_then(|value| quantity(&value))?;
+        let tx = TxEip1559 {
+            chain_id: self.chain_id,
+            nonce: nonce as u64,
+            gas_limit: estimate.gas_limit,
+            max_fee_per_gas: estimate.max_fee_per_gas,
+            max_priority_fee_per_gas: estimate.max_priority_fee_per_gas,
+            to: TxKind::Call(to),
+            value: U256::ZERO,
+            access_list: Default::default(),
+            input: estimate.data.clone(),
+        };
+        let signature = signer
+            .sign_hash_sync(&tx.signature_hash())
+            .map_err(|e| PolyError::crypto(format!("Failed to sign redemption: {}", e)))?;
+        let raw = TxEnvelope::from(tx.into_signed(signature)).encoded_2718();
+
+        let tx_hash = self
+            .rpc(
+                "eth_sendRawTransaction",
+                json!([alloy_primitives::hex::encode_prefixed(raw)]),
+            )
+            .await?;
+        let tx_hash = tx_hash
+            .as_str()
+            .and_then(|hash| B256::from_str(hash).ok())
+            .ok_or_else(|| PolyError::parse("Invalid transaction hash from node", None))?;
+
+        let timeout = options.receipt_timeout.unwrap_or(DEFAULT_RECEIPT_TIMEOUT);
+        let receipt = self.wait_for_receipt(tx_hash, timeout).await?;
+        Ok(RedeemOutcome::Redeemed(self.parse_receipt(
+            tx_hash,
+            &receipt,
+            to,
+            options.neg_risk,
+        )?))
+    }
+
+    /// Target contract and calldata for redeeming `condition_id`
+    async fn redeem_call(
+        &self,
+        condition_id: &str,
+        owner: Address,
+        neg_risk: bool,
+    ) -> Result<(Address, Bytes)> {
+        let condition = B256::from_str(condition_id)
+            .map_err(|e| PolyError::validation(format!("Invalid condition ID: {}", e)))?;
+        let contracts =
+            crate::config::get_contract_config(self.chain_id, neg_risk).ok_or_else(|| {
+                PolyError::config(format!(
+                    "No contracts configured for chain {}",
+                    self.chain_id
+                ))
+            })?;
+        let conditional_tokens = parse_address(&contracts.conditional_tokens)?;
+
+        let mut balances = [U256::ZERO; 2];
+        for (balance, token_id) in balances
+            .iter_mut()
+            .zip(crate::ctf::token_ids_for_condition(condition_id, neg_risk)?)
+        {
+            let id = U256::from_str(&token_id)
+                .map_err(|e| PolyError::internal_simple(format!("Invalid token ID: {}", e)))?;
+            *balance = self.balance_of(conditional_tokens, owner, id).await?;
+        }
+        if balances.iter().all(U256::is_zero) {
+            return Err(PolyError::validation(format!(
+                "{} holds no positions in {}",
+                owner, condition_id
+            )));
+        }
+
+        if neg_risk {
+            let call = INegRiskAdapter::redeemPositionsCall {
+                conditionId: condition,
+                amounts: balances.to_vec(),
+            };
+            Ok((self.neg_risk_adapter, call.abi_encode().into()))
+        } else {
+            let call = IConditionalTokens::redeemPositionsCall {
+                collateralToken: parse_address(&contracts.collateral)?,
+                parentCollectionId: B256::ZERO,
+                conditionId: condition,
+                indexSets: vec![U256::from(1), U256::from(2)],
+            };
+            Ok((conditional_tokens, call.abi_encode().into()))
+        }
+    }
+
+    async fn balance_of(&self, contract: Address, owner: Address, id: U256) -> Result<U256> {
+        let call = IConditionalTokens::balanceOfCall { owner, id };
+        let data = alloy_primitives::hex::encode_prefixed(call.abi_encode());
+        let result = self
+            .rpc(
+                "eth_call",
+                json!([{ "to": contract, "data": data }, "latest"]),
+            )
+            .await?;
+        let bytes = result
+            .as_str()
+            .and_then(|raw| alloy_primitives::hex::decode(raw).ok())
+            .ok_or_else(|| PolyError::parse("Invalid eth_call result", None))?;
+        IConditionalTokens::balanceOfCall::abi_decode_returns(&bytes)
+            .map_err(|e| PolyError::parse(format!("Invalid balanceOf result: {}", e), None))
+    }
+
+    async fn estimate(
+        &self,
+        from: Address,
+        to: Address,
+        data: Bytes,
+        gas: &GasOverrides,
+    ) -> Result<RedeemEstimate> {
+        let max_priority_fee_per_gas = match gas.max_priority_fee_per_gas {
+            Some(fee) => fee,
+            None => quantity(&self.rpc("eth_maxPriorityFeePerGas", json!([])).await?)?,
+        };
+        let max_fee_per_gas = match gas.max_fee_per_gas {
+            Some(fee) => fee,
+            None => {
+                let block = self
+                    .rpc("eth_getBlockByNumber", json!(["latest", false]))
+                    .await?;
+                let base_fee = quantity(&block["baseFeePerGas"])?;
+                base_fee
+                    .saturating_mul(2)
+                    .saturating_add(max_priority_fee_per_gas)
+            }
+        };
+        let gas_limit = match gas.gas_limit {
+            Some(limit) => limit,
+            None => {
+                let estimate = self
+                    .rpc(
+                        "eth_estimateGas",
+                        json!([{ "from": from, "to": to, "data": data }]),
+                    )
+                    .await
+                    .and_then(|value| quantity(&value))? as u64;
+                estimate + estimate * GAS_LIMIT_BUFFER_PCT / 100
+            }
+        };
+
+        Ok(RedeemEstimate {
+            to,
+            data,
+            gas_limit,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+        })
+    }
+
+    async fn wait_for_receipt(&self, tx_hash: B256, timeout: Duration) -> Result<Value> {
+        let started = Instant::now();
+        loop {
+            let receipt = self
+                .rpc("eth_getTransactionReceipt", json!([tx_hash]))
+                .await?;
+            if !receipt.is_null() {
+                return Ok(receipt);
+            }
+            if started.elapsed() >= timeout {
+                return Err(PolyError::timeout(timeout, "redeem_positions receipt"));
+            }
+            tokio::time::sleep(self.poll_interval).await;
+        }
+    }
+
+    fn parse_receipt(
+        &self,
+        tx_hash: B256,
+        receipt: &Value,
+        contract: Address,
+        neg_risk: bool,
+    ) -> Result<RedeemReceipt> {
+        if receipt["status"].as_str() != Some("0x1") {
+            return Err(PolyError::order(
+                format!("Redemption {} reverted", tx_hash),
+                OrderErrorKind::ExecutionFailed,
+            ));
+        }
+
+        let mut payout = U256::ZERO;
+        for log in receipt["logs"].as_array().into_iter().flatten() {
+            let address = log["address"]
+                .as_str()
+                .and_then(|a| Address::from_str(a).ok());
+            if address != Some(contract) {
+                continue;
+            }
+            let topics: Vec<B256> = log["topics"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|topic| topic.as_str().and_then(|t| B256::from_str(t).ok()))
+                .collect();
+            let data = log["data"]
+                .as_str()
+                .and_then(|raw| alloy_primitives::hex::decode(raw).ok())
+                .unwrap_or_default();
+            let amount = if neg_risk {
+                INegRiskAdapter::PayoutRedemption::decode_raw_log(topics, &data)
+                    .map(|event| event.payout)
+            } else {
+                IConditionalTokens::PayoutRedemption::decode_raw_log(topics, &data)
+                    .map(|event| event.payout)
+            };
+            if let Ok(amount) = amount {
+                payout += amount;
+            }
+        }
+
+        Ok(RedeemReceipt {
+            tx_hash,
+            block_number: quantity(&receipt["blockNumber"])? as u64,
+            gas_used: quantity(&receipt["gasUsed"])? as u64,
+            payout,
+        })
+    }
+
+    /// One JSON-RPC call, returning its `result`
+    async fn rpc(&self, method: &str, params: Value) -> Result<Value> {
+        let response = self
+            .http_client
+            .post(&self.rpc_url)
+            .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }))
+            .send()
+            .await?;
+        let status = response.status().as_u16();
+        let mut body: Value = response.json().await?;
+
+        if let Some(error) = body.get("error") {
+            return Err(PolyError::Api {
+                status,
+                message: format!(
+                    "{} failed: {}",
+                    method,
+                    error["message"].as_str().unwrap_or("unknown error")
+                ),
+                error_code: error.get("code").map(|code| code.to_string()),
+            });
+        }
+        Ok(body["result"].take())
+    }
+}
+
+fn parse_address(raw: &str) -> Result<Address> {
+    Address::from_str(raw).map_err(|e| PolyError::config(format!("Invalid address: {}", e)))
+}
+
+/// Decode a JSON-RPC hex quantity (`"0x1a"`)
+fn quantity(value: &Value) -> Result<u128> {
+    value
+        .as_str()
+        .and_then(|raw| raw.strip_prefix("0x"))
+        .and_then(|digits| u128::from_str_radix(digits, 16).ok())
+        .ok_or_else(|| PolyError::parse(format!("Invalid hex quantity: {}", value), None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_sol_types::SolValue;
+    use mockito::{Matcher, Server, ServerGuard};
+
+    const CONDITION: &str = "0xdd22472e552920b8438158ea7238bfadfa4f736aa4cee91a6b86c39ead110917";
+    const KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+
+    async fn respond(server: &mut ServerGuard, method: &str, result: Value) -> mockito::Mock {
+        server
+            .mock("POST", "/")
+            .match_body(Matcher::PartialJson(json!({ "method": method })))
+            .with_header("content-type", "application/json")
+            .with_body(json!({ "jsonrpc": "2.0", "id": 1, "result": result }).to_string())
+            .create_async()
+            .await
+    }
+
+    async fn fee_mocks(server: &mut ServerGuard) {
+        let balance = alloy_primitives::hex::encode_prefixed(U256::from(5_000_000).abi_encode());
+        respond(server, "eth_call", json!(balance)).await;
+        respond(server, "eth_maxPriorityFeePerGas", json!("0x77359400")).await;
+        respond(
+            server,
+            "eth_getBlockByNumber",
+            json!({ "baseFeePerGas": "0x3b9aca00" }),
+        )
+        .await;
+        respond(server, "eth_estimateGas", json!("0x186a0")).await;
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_prices_standard_redemption() {
+        let mut server = Server::new_async().await;
+        fee_mocks(&mut server).await;
+        let sent = server
+            .mock("POST", "/")
+            .match_body(Matcher::PartialJson(
+                json!({ "method": "eth_sendRawTransaction" }),
+            ))
+            .expect(0)
+            .create_async()
+            .await;
+
+        let client = SettlementClient::new(server.url(), 137);
+        let signer: PrivateKeySigner = KEY.parse().unwrap();
+        let options = RedeemOptions {
+            dry_run: true,
+            ..Default::default()
+        };
+        let RedeemOutcome::DryRun(estimate) = client
+            .redeem_positions(CONDITION, &signer, &options)
+            .await
+            .unwrap()
+        else {
+            panic!("dry run sent a transaction");
+        };
+
+        sent.assert_async().await;
+        assert_eq!(
+            estimate.to,
+            parse_address("0x4D97DCd97eC945f40cF65F87097ACe5EA0476045").unwrap()
+        );
+        assert_eq!(
+            estimate.data[..4],
+            IConditionalTokens::redeemPositionsCall::SELECTOR
+        );
+        assert_eq!(estimate.gas_limit, 120_000);
+        assert_eq!(estimate.max_priority_fee_per_gas, 2_000_000_000);
+        assert_eq!(estimate.max_fee_per_gas, 4_000_000_000);
+        assert_eq!(estimate.max_cost_wei(), U256::from(480_000_000_000_000u64));
+    }
+
+    #[tokio::test]
+    async fn test_neg_risk_redemption_reports_payout() {
+        let mut server = Server::new_async().await;
+        fee_mocks(&mut server).await;
+        let tx_hash = B256::repeat_byte(0xab);
+        respond(&mut server, "eth_getTransactionCount", json!("0x7")).await;
+        respond(&mut server, "eth_sendRawTransaction", json!(tx_hash)).await;
+
+        let signer: PrivateKeySigner = KEY.parse().unwrap();
+        let adapter = parse_address(NEG_RISK_ADAPTER).unwrap();
+        let event = INegRiskAdapter::PayoutRedemption {
+            redeemer: signer.address(),
+            conditionId: B256::from_str(CONDITION).unwrap(),
+            amounts: vec![U256::from(5_000_000), U256::from(5_000_000)],
+            payout: U256::from(5_000_000),
+        };
+        let topics: Vec<B256> = event
+            .encode_topics()
+            .into_iter()
+            .map(|topic| topic.into())
+            .collect();
+        respond(
+            &mut server,
+            "eth_getTransactionReceipt",
+            json!({
+                "status": "0x1",
+                "blockNumber": "0x10",
+                "gasUsed": "0x15f90",
+                "logs": [{
+                    "address": adapter,
+                    "topics": topics,
+                    "data": alloy_primitives::hex::encode_prefixed(event.encode_data()),
+                }],
+            }),
+        )
+        .await;
+
+        let client = SettlementClient::new(server.url(), 137);
+        let options = RedeemOptions {
+            neg_risk: true,
+            gas: GasOverrides {
+                gas_limit: Some(200_000),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let RedeemOutcome::Redeemed(receipt) = client
+            .redeem_positions(CONDITION, &signer, &options)
+            .await
+            .unwrap()
+        else {
+            panic!("expected a mined redemption");
+        };
+
+        assert_eq!(receipt.tx_hash, tx_hash);
+        assert_eq!((receipt.block_number, receipt.gas_used), (16, 90_000));
+        assert_eq!(receipt.payout_usdc(), Decimal::from(5));
+    }
+}
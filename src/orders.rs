@@ -3,7 +3,7 @@
 //! This module handles the complex process of creating and signing orders
 //! for the Polymarket CLOB, including EIP-712 signature generation.
 
-use crate::auth::sign_order_message;
+use crate::auth::{order_hash, sign_order_message};
 use crate::client::OrderArgs;
 use crate::errors::{OrderErrorKind, PolyError, Result};
 use crate::types::{ExtraOrderArgs, MarketOrderArgs, OrderOptions, Side, SignedOrderRequest};
@@ -13,13 +13,14 @@ use rand::Rng;
 use rust_decimal::Decimal;
 use rust_decimal::RoundingStrategy::{AwayFromZero, MidpointTowardZero, ToZero};
 use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::LazyLock;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Signature types for orders
-#[derive(Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SigType {
     /// ECDSA EIP712 signatures signed by EOAs
     Eoa = 0,
@@ -29,6 +30,33 @@ pub enum SigType {
     PolyGnosisSafe = 2,
 }
 
+impl SigType {
+    /// Pick the signature type implied by the signer/funder pair.
+    ///
+    /// No funder, or a funder equal to the signer, means the key signs for itself (EOA).
+    /// A distinct funder means a proxy wallet; `proxy` selects which kind and defaults to
+    /// [`SigType::PolyProxy`]. Combinations the exchange would reject are reported as
+    /// config errors instead of being silently rewritten: an EOA type with a foreign
+    /// funder, or a proxy type whose funder is the signer itself.
+    pub fn infer(signer: Address, funder: Option<Address>, proxy: Option<SigType>) -> Result<Self> {
+        let funder = funder.filter(|funder| *funder != signer);
+        match (funder, proxy) {
+            (None, None | Some(SigType::Eoa)) => Ok(SigType::Eoa),
+            (None, Some(kind)) => Err(PolyError::config(format!(
+                "signature type {:?} needs a proxy funder address distinct from the signer {}",
+                kind,
+                signer.to_checksum(None)
+            ))),
+            (Some(funder), Some(SigType::Eoa)) => Err(PolyError::config(format!(
+                "EOA signature type cannot fund orders from {}; EOA orders must be funded by the signer {}",
+                funder.to_checksum(None),
+                signer.to_checksum(None)
+            ))),
+            (Some(_), kind) => Ok(kind.unwrap_or(SigType::PolyProxy)),
+        }
+    }
+}
+
 /// Rounding configuration for different tick sizes
 pub struct RoundConfig {
     price: u32,
@@ -118,6 +146,23 @@ fn generate_seed() -> u64 {
 
 const TOKEN_UNIT_SCALE: i64 = 1_000_000;
 
+/// Format `value` with exactly `places` decimal places, the way the CLOB writes prices
+/// and sizes
+///
+/// Extra digits are rounded half toward zero, as order amounts are; short values are
+/// padded with zeros, so `0.5` at two places is `"0.50"`. The output never uses
+/// scientific notation and never reads `-0`. `places` is capped at 28, the most a
+/// [`Decimal`] can hold.
+pub fn canonical_decimal(value: Decimal, places: u32) -> String {
+    let places = places.min(28);
+    let mut value = value.round_dp_with_strategy(places, MidpointTowardZero);
+    value.rescale(places);
+    if value.is_zero() {
+        value.set_sign_positive(true);
+    }
+    value.to_string()
+}
+
 /// Convert decimal to token units (multiply by 1e6) with validation
 fn decimal_to_token_units(amount: Decimal) -> Result<u64> {
     if amount < Decimal::ZERO {
@@ -157,17 +202,59 @@ impl OrderBuilder {
         }
     }
 
+    /// Create an order builder, inferring the signature type from the funder.
+    ///
+    /// See [`SigType::infer`]; `proxy` only matters when `funder` differs from the signer.
+    pub fn try_new(
+        signer: PrivateKeySigner,
+        funder: Option<Address>,
+        proxy: Option<SigType>,
+    ) -> Result<Self> {
+        let sig_type = SigType::infer(signer.address(), funder, proxy)?;
+        Ok(Self::new(signer, Some(sig_type), funder))
+    }
+
     /// Get signature type as u8
     pub fn get_sig_type(&self) -> u8 {
         self.sig_type as u8
     }
 
+    /// Address that funds (and holds the positions from) signed orders
+    pub fn funder(&self) -> Address {
+        self.funder
+    }
+
+    /// Signature type used for signed orders
+    pub fn sig_type(&self) -> SigType {
+        self.sig_type
+    }
+
     /// Override the funder/maker address for signed orders.
+    ///
+    /// The signature type follows the funder: the signer's own address switches back to
+    /// EOA, anything else keeps the current proxy kind (or [`SigType::PolyProxy`] when the
+    /// builder was signing as an EOA).
     pub fn set_funder(&mut self, funder: Address) {
-        self.sig_type = SigType::PolyProxy;
+        let proxy = match self.sig_type {
+            SigType::Eoa => None,
+            kind => Some(kind),
+        };
+        // Only a funder equal to the signer can pair with EOA, and that path never errors
+        self.sig_type =
+            SigType::infer(self.signer.address(), Some(funder), proxy).unwrap_or(SigType::Eoa);
         self.funder = funder;
     }
 
+    /// Override the funder together with an explicit signature type.
+    ///
+    /// Unlike [`set_funder`](Self::set_funder) this rejects combinations the exchange would
+    /// not accept, leaving the builder unchanged.
+    pub fn set_funder_with_sig_type(&mut self, funder: Address, sig_type: SigType) -> Result<()> {
+        self.sig_type = SigType::infer(self.signer.address(), Some(funder), Some(sig_type))?;
+        self.funder = funder;
+        Ok(())
+    }
+
     /// Fix amount rounding according to configuration
     fn fix_amount_rounding(&self, mut amt: Decimal, round_config: &RoundConfig) -> Decimal {
         if amt.scale() > round_config.amount {
@@ -392,6 +479,51 @@ impl OrderBuilder {
     }
 }
 
+/// The CLOB order ID of `order`: its EIP-712 hash on the exchange `neg_risk` selects
+///
+/// Lets a caller find an order it posted without having seen the post's response.
+pub fn signed_order_hash(
+    order: &SignedOrderRequest,
+    chain_id: u64,
+    neg_risk: bool,
+) -> Result<String> {
+    let contract_config = get_contract_config(chain_id, neg_risk)
+        .ok_or_else(|| PolyError::config("No contract found with given chain_id and neg_risk"))?;
+    let address = |field: &str, value: &str| {
+        Address::from_str(value)
+            .map_err(|e| PolyError::validation(format!("Invalid {} address: {}", field, e)))
+    };
+    let number = |field: &str, value: &str| {
+        U256::from_str_radix(value, 10)
+            .map_err(|e| PolyError::validation(format!("Invalid {}: {}", field, e)))
+    };
+    let side = match order.side.as_str() {
+        "BUY" => Side::BUY,
+        "SELL" => Side::SELL,
+        other => return Err(PolyError::validation(format!("Invalid side: {}", other))),
+    };
+
+    let order_struct = crate::auth::Order {
+        salt: U256::from(order.salt),
+        maker: address("maker", &order.maker)?,
+        signer: address("signer", &order.signer)?,
+        taker: address("taker", &order.taker)?,
+        tokenId: number("tokenId", &order.token_id)?,
+        makerAmount: number("makerAmount", &order.maker_amount)?,
+        takerAmount: number("takerAmount", &order.taker_amount)?,
+        expiration: number("expiration", &order.expiration)?,
+        nonce: number("nonce", &order.nonce)?,
+        feeRateBps: number("feeRateBps", &order.fee_rate_bps)?,
+        side: side as u8,
+        signatureType: order.signature_type,
+    };
+    Ok(order_hash(
+        &order_struct,
+        chain_id,
+        address("exchange", &contract_config.exchange)?,
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -402,6 +534,25 @@ mod tests {
         assert_eq!(result, 1_500_000);
     }
 
+    #[test]
+    fn test_canonical_decimal() {
+        let d = |s: &str| Decimal::from_str(s).unwrap();
+        assert_eq!(canonical_decimal(d("0.5"), 2), "0.50");
+        assert_eq!(canonical_decimal(d("0.050"), 2), "0.05");
+        assert_eq!(canonical_decimal(d("0.050"), 3), "0.050");
+        assert_eq!(canonical_decimal(d("0.050"), 4), "0.0500");
+        assert_eq!(canonical_decimal(d("100"), 2), "100.00");
+        assert_eq!(canonical_decimal(d("0.125"), 2), "0.12");
+        assert_eq!(canonical_decimal(d("0.1234567890123456789"), 4), "0.1235");
+        assert_eq!(canonical_decimal(d("12.999999999999999999"), 2), "13.00");
+        assert_eq!(
+            canonical_decimal(d("0.0000000000000000000000000001"), 2),
+            "0.00"
+        );
+        assert_eq!(canonical_decimal(d("-0.001"), 2), "0.00");
+        assert_eq!(canonical_decimal(d("7.25"), 0), "7");
+    }
+
     #[test]
     fn test_generate_seed() {
         let seed1 = generate_seed();
@@ -450,6 +601,121 @@ mod tests {
         ));
     }
 
+    fn test_signer() -> PrivateKeySigner {
+        PrivateKeySigner::from_str(
+            "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318",
+        )
+        .unwrap()
+    }
+
+    const PROXY: &str = "0x1111111111111111111111111111111111111111";
+
+    #[test]
+    fn test_signed_order_hash_is_the_signed_digest() {
+        let builder = OrderBuilder::new(test_signer(), None, None);
+        let options = OrderOptions {
+            tick_size: Some(Decimal::from_str("0.01").unwrap()),
+            neg_risk: Some(true),
+            fee_rate_bps: None,
+        };
+        let args = OrderArgs::new(
+            "1234",
+            Decimal::from_str("0.42").unwrap(),
+            Decimal::from(10),
+            Side::SELL,
+        );
+        let order = builder
+            .create_order(137, &args, 0, &ExtraOrderArgs::default(), &options)
+            .unwrap();
+
+        let hash = signed_order_hash(&order, 137, true).unwrap();
+        let digest = alloy_primitives::B256::from_str(&hash).unwrap();
+        let signature = alloy_primitives::Signature::from_str(&order.signature).unwrap();
+        assert_eq!(
+            signature.recover_address_from_prehash(&digest).unwrap(),
+            test_signer().address()
+        );
+        // The other exchange is a different domain, so a different ID
+        assert_ne!(signed_order_hash(&order, 137, false).unwrap(), hash);
+    }
+
+    #[test]
+    fn test_infer_sig_type_eoa() {
+        let signer = test_signer().address();
+        assert_eq!(SigType::infer(signer, None, None).unwrap(), SigType::Eoa);
+        assert_eq!(
+            SigType::infer(signer, Some(signer), None).unwrap(),
+            SigType::Eoa
+        );
+        // Asking for EOA explicitly is consistent when the signer funds itself
+        assert!(SigType::infer(signer, Some(signer), Some(SigType::Eoa)).is_ok());
+    }
+
+    #[test]
+    fn test_infer_sig_type_proxy() {
+        let signer = test_signer().address();
+        let funder = Address::from_str(PROXY).unwrap();
+        assert_eq!(
+            SigType::infer(signer, Some(funder), None).unwrap(),
+            SigType::PolyProxy
+        );
+        assert_eq!(
+            SigType::infer(signer, Some(funder), Some(SigType::PolyGnosisSafe)).unwrap(),
+            SigType::PolyGnosisSafe
+        );
+    }
+
+    #[test]
+    fn test_infer_sig_type_ambiguous() {
+        let signer = test_signer().address();
+        let funder = Address::from_str(PROXY).unwrap();
+
+        let err = SigType::infer(signer, Some(funder), Some(SigType::Eoa)).unwrap_err();
+        assert!(matches!(err, PolyError::Config { .. }));
+
+        for kind in [SigType::PolyProxy, SigType::PolyGnosisSafe] {
+            assert!(matches!(
+                SigType::infer(signer, Some(signer), Some(kind)),
+                Err(PolyError::Config { .. })
+            ));
+            assert!(SigType::infer(signer, None, Some(kind)).is_err());
+        }
+    }
+
+    #[test]
+    fn test_order_builder_funder_tracks_sig_type() {
+        let signer = test_signer();
+        let own = signer.address();
+        let funder = Address::from_str(PROXY).unwrap();
+
+        let mut builder = OrderBuilder::try_new(signer.clone(), None, None).unwrap();
+        assert_eq!(builder.sig_type(), SigType::Eoa);
+
+        builder.set_funder(funder);
+        assert_eq!(builder.sig_type(), SigType::PolyProxy);
+
+        builder
+            .set_funder_with_sig_type(funder, SigType::PolyGnosisSafe)
+            .unwrap();
+        // Switching funders keeps the configured proxy kind
+        builder
+            .set_funder(Address::from_str("0x2222222222222222222222222222222222222222").unwrap());
+        assert_eq!(builder.sig_type(), SigType::PolyGnosisSafe);
+
+        assert!(
+            builder
+                .set_funder_with_sig_type(funder, SigType::Eoa)
+                .is_err()
+        );
+        assert_eq!(builder.sig_type(), SigType::PolyGnosisSafe);
+
+        builder.set_funder(own);
+        assert_eq!(builder.sig_type(), SigType::Eoa);
+        assert_eq!(builder.get_sig_type(), 0);
+
+        assert!(OrderBuilder::try_new(signer, Some(own), Some(SigType::PolyProxy)).is_err());
+    }
+
     #[test]
     fn test_get_contract_config() {
         // Test Polygon mainnet
@@ -0,0 +1,464 @@
+//! Realized P&L reconstructed from trade history
+//!
+//! The exchange doesn't report realized P&L per trade, so it is rebuilt here from the
+//! authenticated `/data/trades` history. Buys open lots per token and sells close them
+//! first-in, first-out; every (sell, lot) pair that closes becomes one
+//! [`RealizedPnlEntry`]. Fees are charged at each trade's `fee_rate_bps` on notional and
+//! split pro rata when a trade is only partly matched.
+//!
+//! Lots opened before the reporting window still count: history is replayed from the
+//! start and only entries whose close falls inside the window are reported.
+
+use crate::errors::{PolyError, Result};
+use crate::types::Side;
+use alloy_primitives::Address;
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use tracing::warn;
+
+/// Filters for [`ClobClient::get_realized_pnl_history`](crate::client::ClobClient::get_realized_pnl_history)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PnlHistoryParams<'a> {
+    /// First UTC day (inclusive) a closing trade may fall on
+    pub start_date: NaiveDate,
+    /// Last UTC day (inclusive) a closing trade may fall on
+    pub end_date: NaiveDate,
+    pub token_id: Option<&'a str>,
+    /// Entries per page; everything in one page when unset
+    pub limit: Option<usize>,
+    /// `next_cursor` from a previous page
+    pub cursor: Option<&'a str>,
+}
+
+impl<'a> PnlHistoryParams<'a> {
+    pub fn new(start_date: NaiveDate, end_date: NaiveDate) -> Self {
+        Self {
+            start_date,
+            end_date,
+            token_id: None,
+            limit: None,
+            cursor: None,
+        }
+    }
+
+    pub fn with_token_id(mut self, token_id: &'a str) -> Self {
+        self.token_id = Some(token_id);
+        self
+    }
+
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn with_cursor(mut self, cursor: &'a str) -> Self {
+        self.cursor = Some(cursor);
+        self
+    }
+
+    /// Reject inverted ranges
+    pub fn validate(&self) -> Result<()> {
+        if self.start_date > self.end_date {
+            return Err(PolyError::validation(format!(
+                "start_date {} is after end_date {}",
+                self.start_date, self.end_date
+            )));
+        }
+        Ok(())
+    }
+
+    /// First instant after the window, as epoch seconds
+    pub(crate) fn end_exclusive_ts(&self) -> i64 {
+        self.end_date
+            .succ_opt()
+            .unwrap_or(self.end_date)
+            .and_hms_opt(0, 0, 0)
+            .map(|dt| dt.and_utc().timestamp())
+            .unwrap_or(i64::MAX)
+    }
+
+    fn contains(&self, at: &DateTime<Utc>) -> bool {
+        let day = at.date_naive();
+        self.start_date <= day && day <= self.end_date
+    }
+}
+
+/// One closed lot: a buy matched against (part of) a later sell
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RealizedPnlEntry {
+    /// ID of the closing trade; a sell spanning several lots yields several entries
+    pub trade_id: String,
+    pub token_id: String,
+    pub outcome: String,
+    pub condition_id: String,
+    pub open_date: DateTime<Utc>,
+    pub close_date: DateTime<Utc>,
+    pub open_price: Decimal,
+    pub close_price: Decimal,
+    pub size: Decimal,
+    /// `(close_price - open_price) * size`
+    pub gross_pnl: Decimal,
+    /// Opening and closing fees attributable to this lot
+    pub fees: Decimal,
+    pub net_pnl: Decimal,
+}
+
+/// Totals over a tax year
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnnualPnlSummary {
+    pub year: u32,
+    /// Number of closed lots
+    pub entries: usize,
+    pub gross_pnl: Decimal,
+    pub fees: Decimal,
+    pub net_pnl: Decimal,
+    /// Sum of positive `net_pnl`
+    pub total_gains: Decimal,
+    /// Sum of negative `net_pnl` (not positive)
+    pub total_losses: Decimal,
+}
+
+impl AnnualPnlSummary {
+    pub fn from_entries(year: u32, entries: &[RealizedPnlEntry]) -> Self {
+        entries.iter().fold(
+            Self {
+                year,
+                ..Self::default()
+            },
+            |mut summary, entry| {
+                summary.entries += 1;
+                summary.gross_pnl += entry.gross_pnl;
+                summary.fees += entry.fees;
+                summary.net_pnl += entry.net_pnl;
+                if entry.net_pnl.is_sign_positive() {
+                    summary.total_gains += entry.net_pnl;
+                } else {
+                    summary.total_losses += entry.net_pnl;
+                }
+                summary
+            },
+        )
+    }
+}
+
+/// One `/data/trades` record
+///
+/// The top-level side, size and price are the taker's. When the authenticated
+/// user was the maker (`trader_side == "MAKER"`) their fills are the entries of
+/// `maker_orders` they own; see [`TradeRecord::user_legs`].
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct TradeRecord {
+    pub id: String,
+    pub asset_id: String,
+    pub market: String,
+    #[serde(default)]
+    pub outcome: String,
+    pub side: Side,
+    #[serde(with = "crate::serde_decimal")]
+    pub size: Decimal,
+    #[serde(with = "crate::serde_decimal")]
+    pub price: Decimal,
+    #[serde(default, with = "crate::serde_decimal::option")]
+    pub fee_rate_bps: Option<Decimal>,
+    #[serde(deserialize_with = "crate::decode::deserializers::number_from_string")]
+    pub match_time: u64,
+    #[serde(default, alias = "type")]
+    pub trader_side: Option<String>,
+    #[serde(default)]
+    pub maker_orders: Vec<MakerFill>,
+}
+
+/// A maker order matched by a [`TradeRecord`]
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct MakerFill {
+    /// API key of the order's owner
+    #[serde(default)]
+    pub owner: String,
+    #[serde(default)]
+    pub maker_address: String,
+    pub asset_id: String,
+    #[serde(default)]
+    pub outcome: String,
+    /// Older records omit it; see [`TradeRecord::user_legs`]
+    #[serde(default)]
+    pub side: Option<Side>,
+    #[serde(with = "crate::serde_decimal")]
+    pub matched_amount: Decimal,
+    #[serde(with = "crate::serde_decimal")]
+    pub price: Decimal,
+    #[serde(default, with = "crate::serde_decimal::option")]
+    pub fee_rate_bps: Option<Decimal>,
+}
+
+impl TradeRecord {
+    /// The authenticated user's side of the trade
+    ///
+    /// A taker record is one leg as reported. A maker record yields one leg per
+    /// maker order owned by `api_key` or placed from `funder`; a maker order
+    /// without a `side` traded against the taker on the same token and alongside
+    /// it on the complement.
+    pub(crate) fn user_legs(self, api_key: &str, funder: Option<Address>) -> Vec<HistoricalTrade> {
+        if !self
+            .trader_side
+            .as_deref()
+            .is_some_and(|side| side.eq_ignore_ascii_case("MAKER"))
+        {
+            return vec![HistoricalTrade {
+                id: self.id,
+                asset_id: self.asset_id,
+                market: self.market,
+                outcome: self.outcome,
+                side: self.side,
+                size: self.size,
+                price: self.price,
+                fee_rate_bps: self.fee_rate_bps,
+                match_time: self.match_time,
+            }];
+        }
+
+        let owned = |maker: &MakerFill| {
+            maker.owner == api_key
+                || funder.is_some_and(|funder| maker.maker_address.parse() == Ok(funder))
+        };
+        let legs: Vec<_> = self
+            .maker_orders
+            .iter()
+            .filter(|maker| owned(maker))
+            .map(|maker| HistoricalTrade {
+                id: self.id.clone(),
+                side: maker.side.unwrap_or(if maker.asset_id == self.asset_id {
+                    self.side.opposite()
+                } else {
+                    self.side
+                }),
+                asset_id: maker.asset_id.clone(),
+                market: self.market.clone(),
+                outcome: maker.outcome.clone(),
+                size: maker.matched_amount,
+                price: maker.price,
+                fee_rate_bps: maker.fee_rate_bps,
+                match_time: self.match_time,
+            })
+            .collect();
+        if legs.is_empty() {
+            warn!(trade_id = %self.id, "maker trade has no maker order of ours; skipping");
+        }
+        legs
+    }
+}
+
+/// The authenticated user's leg of a trade, as needed for lot matching and export
+#[derive(Debug, Clone)]
+pub(crate) struct HistoricalTrade {
+    pub id: String,
+    pub asset_id: String,
+    pub market: String,
+    pub outcome: String,
+    pub side: Side,
+    pub size: Decimal,
+    pub price: Decimal,
+    pub fee_rate_bps: Option<Decimal>,
+    pub match_time: u64,
+}
+
+impl HistoricalTrade {
+    pub(crate) fn fee(&self) -> Decimal {
+        let bps = self.fee_rate_bps.unwrap_or_default();
+        self.price * self.size * bps / Decimal::from(10_000)
+    }
+
+    pub(crate) fn time(&self) -> DateTime<Utc> {
+        DateTime::from_timestamp(self.match_time as i64, 0).unwrap_or_default()
+    }
+}
+
+struct Lot {
+    opened_at: DateTime<Utc>,
+    price: Decimal,
+    remaining: Decimal,
+    /// Opening fee per unit of size
+    fee_per_unit: Decimal,
+}
+
+/// Match `trades` first-in, first-out per token and return the closed lots in close order
+pub(crate) fn realize_fifo(mut trades: Vec<HistoricalTrade>) -> Vec<RealizedPnlEntry> {
+    trades.sort_by(|a, b| a.match_time.cmp(&b.match_time).then(a.id.cmp(&b.id)));
+
+    let mut lots: HashMap<String, VecDeque<Lot>> = HashMap::new();
+    let mut entries = Vec::new();
+    for trade in trades {
+        if trade.size.is_zero() {
+            continue;
+        }
+        let fee_per_unit = trade.fee() / trade.size;
+        let open = lots.entry(trade.asset_id.clone()).or_default();
+        match trade.side {
+            Side::BUY => open.push_back(Lot {
+                opened_at: trade.time(),
+                price: trade.price,
+                remaining: trade.size,
+                fee_per_unit,
+            }),
+            Side::SELL => {
+                let mut to_close = trade.size;
+                while !to_close.is_zero() {
+                    let Some(lot) = open.front_mut() else {
+                        // Tokens from a split or transfer have no cost basis in trade history
+                        warn!(
+                            trade_id = %trade.id,
+                            token_id = %trade.asset_id,
+                            unmatched = %to_close,
+                            "sell exceeds recorded buys; skipping unmatched size"
+                        );
+                        break;
+                    };
+                    let size = lot.remaining.min(to_close);
+                    let gross_pnl = (trade.price - lot.price) * size;
+                    let fees = (lot.fee_per_unit + fee_per_unit) * size;
+                    entries.push(RealizedPnlEntry {
+                        trade_id: trade.id.clone(),
+                        token_id: trade.asset_id.clone(),
+                        outcome: trade.outcome.clone(),
+                        condition_id: trade.market.clone(),
+                        open_date: lot.opened_at,
+                        close_date: trade.time(),
+                        open_price: lot.price,
+                        close_price: trade.price,
+                        size,
+                        gross_pnl,
+                        fees,
+                        net_pnl: gross_pnl - fees,
+                    });
+                    lot.remaining -= size;
+                    to_close -= size;
+                    if lot.remaining.is_zero() {
+                        open.pop_front();
+                    }
+                }
+            }
+        }
+    }
+    entries
+}
+
+/// Keep the entries closed inside the window, optionally for one token
+pub(crate) fn filter_entries(
+    entries: Vec<RealizedPnlEntry>,
+    params: &PnlHistoryParams<'_>,
+) -> Vec<RealizedPnlEntry> {
+    entries
+        .into_iter()
+        .filter(|entry| params.contains(&entry.close_date))
+        .filter(|entry| params.token_id.is_none_or(|id| entry.token_id == id))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn trade(
+        id: &str,
+        side: Side,
+        size: Decimal,
+        price: Decimal,
+        match_time: u64,
+    ) -> HistoricalTrade {
+        HistoricalTrade {
+            id: id.to_string(),
+            asset_id: "111".to_string(),
+            market: "0xabc".to_string(),
+            outcome: "Yes".to_string(),
+            side,
+            size,
+            price,
+            fee_rate_bps: Some(dec!(100)),
+            match_time,
+        }
+    }
+
+    #[test]
+    fn test_fifo_splits_sells_across_lots() {
+        let entries = realize_fifo(vec![
+            trade("s1", Side::SELL, dec!(15), dec!(0.60), 300),
+            trade("b1", Side::BUY, dec!(10), dec!(0.40), 100),
+            trade("b2", Side::BUY, dec!(10), dec!(0.50), 200),
+        ]);
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            (entries[0].size, entries[0].open_price),
+            (dec!(10), dec!(0.40))
+        );
+        assert_eq!(entries[0].gross_pnl, dec!(2.00));
+        // 1% of 4.00 to open plus 1% of 6.00 to close
+        assert_eq!(entries[0].fees, dec!(0.10));
+        assert_eq!(entries[0].net_pnl, dec!(1.90));
+        assert_eq!(
+            (entries[1].size, entries[1].open_price),
+            (dec!(5), dec!(0.50))
+        );
+        assert_eq!(entries[1].gross_pnl, dec!(0.50));
+        assert!(entries.iter().all(|e| e.trade_id == "s1"));
+
+        let summary = AnnualPnlSummary::from_entries(1970, &entries);
+        assert_eq!(summary.entries, 2);
+        assert_eq!(summary.gross_pnl, dec!(2.50));
+    }
+
+    #[test]
+    fn test_maker_record_yields_our_maker_legs() {
+        let record: TradeRecord = serde_json::from_value(serde_json::json!({
+            "id": "t1", "asset_id": "111", "market": "0xabc", "outcome": "Yes",
+            "side": "BUY", "size": "30", "price": "0.60", "fee_rate_bps": "0",
+            "match_time": "100", "trader_side": "MAKER",
+            "maker_orders": [
+                {"owner": "someone-else", "maker_address": "0x01", "asset_id": "111",
+                 "outcome": "Yes", "side": "SELL", "matched_amount": "20", "price": "0.60"},
+                {"owner": "our-key", "maker_address": "0x02", "asset_id": "111",
+                 "outcome": "Yes", "matched_amount": "4", "price": "0.59"},
+                {"owner": "our-key", "maker_address": "0x02", "asset_id": "222",
+                 "outcome": "No", "matched_amount": "6", "price": "0.40"}
+            ]
+        }))
+        .unwrap();
+
+        let legs = record.clone().user_legs("our-key", None);
+        let legs: Vec<_> = legs
+            .iter()
+            .map(|leg| (leg.asset_id.as_str(), leg.side, leg.size, leg.price))
+            .collect();
+        assert_eq!(
+            legs,
+            [
+                ("111", Side::SELL, dec!(4), dec!(0.59)),
+                ("222", Side::BUY, dec!(6), dec!(0.40)),
+            ]
+        );
+        assert!(record.user_legs("other-key", None).is_empty());
+    }
+
+    #[test]
+    fn test_filter_keeps_window_and_token() {
+        let entries = realize_fifo(vec![
+            trade("b1", Side::BUY, dec!(10), dec!(0.40), 1_700_000_000),
+            trade("s1", Side::SELL, dec!(4), dec!(0.30), 1_700_000_000),
+            trade("s2", Side::SELL, dec!(6), dec!(0.50), 1_710_000_000),
+        ]);
+        let day = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+
+        let params = PnlHistoryParams::new(day(2024, 1, 1), day(2024, 12, 31));
+        let kept = filter_entries(entries.clone(), &params);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].trade_id, "s2");
+        assert!(filter_entries(entries, &params.with_token_id("222")).is_empty());
+        assert!(
+            PnlHistoryParams::new(day(2024, 2, 1), day(2024, 1, 1))
+                .validate()
+                .is_err()
+        );
+    }
+}
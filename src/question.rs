@@ -0,0 +1,457 @@
+//! Structure pulled out of a market's question text
+//!
+//! [`ClobClient::get_market_question_analysis`] asks the server first; this module is the
+//! fallback it uses when there is no analysis endpoint. Everything here is a plain word
+//! scan over the question as written: dates are month names, day numbers, years and ISO
+//! dates; names are runs of capitalized words; hedged wording like "might" raises the
+//! ambiguity score. Scans return slices of the input, so nothing is copied until an
+//! analysis is assembled.
+//!
+//! [`ClobClient::get_market_question_analysis`]: crate::client::ClobClient::get_market_question_analysis
+
+use crate::types::Market;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// Ambiguity added per hedging word, up to 1
+const AMBIGUITY_PER_HEDGE: Decimal = Decimal::from_parts(25, 0, 0, false, 2);
+/// Keywords sent as the search for similar markets
+const SEARCH_KEYWORDS: usize = 5;
+/// Similar markets kept in a heuristic analysis
+const MAX_SIMILAR_MARKETS: usize = 5;
+
+const MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+/// Words that soften a question's resolution criteria
+const HEDGE_WORDS: [&str; 11] = [
+    "may",
+    "might",
+    "could",
+    "possibly",
+    "likely",
+    "unlikely",
+    "approximately",
+    "roughly",
+    "around",
+    "significant",
+    "substantially",
+];
+
+/// Words too common in questions to say anything about the subject
+const STOPWORDS: [&str; 44] = [
+    "a", "above", "after", "an", "and", "any", "are", "at", "be", "before", "below", "between",
+    "by", "does", "do", "during", "end", "for", "from", "has", "have", "his", "her", "in", "is",
+    "it", "its", "least", "less", "more", "of", "on", "or", "reach", "than", "that", "the",
+    "their", "this", "to", "what", "who", "will", "with",
+];
+
+/// What the server, or the heuristic, made of a market's question
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuestionAnalysis {
+    pub entities: Vec<Entity>,
+    /// How sure the analysis is of when the market resolves, from 0 to 1
+    #[serde(with = "crate::serde_decimal")]
+    pub resolution_date_confidence: Decimal,
+    /// How loosely the question is worded, from 0 to 1
+    #[serde(with = "crate::serde_decimal")]
+    pub ambiguity_score: Decimal,
+    /// Condition IDs of markets asking about the same things, closest first
+    pub similar_markets: Vec<String>,
+}
+
+/// A span of the question that names something
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entity {
+    pub text: String,
+    pub kind: EntityKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntityKind {
+    Date,
+    Name,
+    #[serde(other)]
+    Other,
+}
+
+impl QuestionAnalysis {
+    /// Analyse `market`'s question without the server, ranking `candidates` (typically
+    /// search results for [`search_query`]) by keyword overlap
+    ///
+    /// Resolution date confidence is 1 for a full date (a day and month, or an ISO
+    /// date), 0.5 when only a month or a year is named, and 0 otherwise. Each hedging
+    /// word adds 0.25 to the ambiguity score. Candidates sharing no keyword, and
+    /// `market` itself, are left out of `similar_markets`.
+    pub fn heuristic(market: &Market, candidates: &[Market]) -> Self {
+        let question = market.question.as_str();
+        let dates = date_mentions(question);
+        let resolution_date_confidence = if dates.iter().any(|date| date.full) {
+            Decimal::ONE
+        } else if dates.is_empty() {
+            Decimal::ZERO
+        } else {
+            Decimal::new(5, 1)
+        };
+
+        let mut entities: Vec<Entity> = dates
+            .iter()
+            .map(|date| Entity {
+                text: date.text.to_string(),
+                kind: EntityKind::Date,
+            })
+            .collect();
+        entities.extend(names(question).into_iter().map(|name| Entity {
+            text: name.to_string(),
+            kind: EntityKind::Name,
+        }));
+
+        let mut similar: Vec<(Decimal, &str)> = candidates
+            .iter()
+            .filter(|candidate| candidate.condition_id != market.condition_id)
+            .map(|candidate| {
+                (
+                    keyword_overlap(question, &candidate.question),
+                    candidate.condition_id.as_str(),
+                )
+            })
+            .filter(|(overlap, _)| *overlap > Decimal::ZERO)
+            .collect();
+        similar.sort_by_key(|(overlap, _)| std::cmp::Reverse(*overlap));
+        similar.dedup_by_key(|(_, condition_id)| *condition_id);
+
+        Self {
+            entities,
+            resolution_date_confidence,
+            ambiguity_score: ambiguity_score(question),
+            similar_markets: similar
+                .into_iter()
+                .take(MAX_SIMILAR_MARKETS)
+                .map(|(_, condition_id)| condition_id.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// A date named in a question
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateMention<'a> {
+    pub text: &'a str,
+    /// Whether a specific day is named, not just a month or a year
+    pub full: bool,
+}
+
+/// Dates in `text`, in order: `2024-11-05`, `November 5, 2024`, `November 5`,
+/// `November 2024`, `November`, or a bare year from 1900 to 2199
+pub fn date_mentions(text: &str) -> Vec<DateMention<'_>> {
+    let words = words(text);
+    let mut mentions = Vec::new();
+    let mut i = 0;
+    while i < words.len() {
+        let word = &words[i];
+        if is_iso_date(word.text) {
+            mentions.push(DateMention {
+                text: word.text,
+                full: true,
+            });
+        } else if is_month(word.text) {
+            let start = word.range.start;
+            let mut end = word.range.end;
+            let mut full = false;
+            if let Some(day) = words.get(i + 1).filter(|next| is_day(next.text)) {
+                end = day.range.end;
+                full = true;
+                i += 1;
+            }
+            if let Some(year) = words.get(i + 1).filter(|next| is_year(next.text)) {
+                end = year.range.end;
+                i += 1;
+            }
+            mentions.push(DateMention {
+                text: &text[start..end],
+                full,
+            });
+        } else if is_year(word.text) {
+            mentions.push(DateMention {
+                text: word.text,
+                full: false,
+            });
+        }
+        i += 1;
+    }
+    mentions
+}
+
+/// Runs of capitalized words in `text`, leaving out months and common words such as
+/// the "Will" a question opens with
+///
+/// A run stops at punctuation, so `"Biden, Trump"` is two names.
+pub fn names(text: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut run: Option<Range<usize>> = None;
+    for word in words(text) {
+        let capitalized = word.text.chars().next().is_some_and(char::is_uppercase)
+            && !is_month(word.text)
+            && !is_stopword(word.text);
+        if capitalized {
+            let range = run.get_or_insert(word.range.clone());
+            range.end = word.range.end;
+        } else if let Some(range) = run.take() {
+            names.push(&text[range]);
+        }
+        if word.punctuated
+            && let Some(range) = run.take()
+        {
+            names.push(&text[range]);
+        }
+    }
+    names.extend(run.map(|range| &text[range]));
+    names
+}
+
+/// 0 for plainly worded text, plus [`AMBIGUITY_PER_HEDGE`] for each hedging word, at
+/// most 1
+pub fn ambiguity_score(text: &str) -> Decimal {
+    let hedges = words(text)
+        .iter()
+        .filter(|word| is_hedge(word.text))
+        .count();
+    (AMBIGUITY_PER_HEDGE * Decimal::from(hedges)).min(Decimal::ONE)
+}
+
+/// Words of `text` that say what it is about: not common or hedging words, numbers or
+/// single letters. Repeats are kept.
+pub fn keywords(text: &str) -> impl Iterator<Item = &str> {
+    words(text)
+        .into_iter()
+        .map(|word| word.text)
+        .filter(|word| word.len() > 1 && !is_stopword(word) && !is_hedge(word))
+        .filter(|word| !word.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Share of keywords two texts have in common: the size of the intersection of their
+/// keyword sets over the size of the union, ignoring case
+pub fn keyword_overlap(a: &str, b: &str) -> Decimal {
+    let a = distinct_keywords(a);
+    let b = distinct_keywords(b);
+    let shared = a
+        .iter()
+        .filter(|word| b.iter().any(|other| other.eq_ignore_ascii_case(word)))
+        .count();
+    let union = a.len() + b.len() - shared;
+    if union == 0 {
+        return Decimal::ZERO;
+    }
+    Decimal::from(shared) / Decimal::from(union)
+}
+
+/// The first few keywords of `question`, as a market search query; `None` when it has
+/// none
+pub fn search_query(question: &str) -> Option<String> {
+    let query = distinct_keywords(question)
+        .into_iter()
+        .take(SEARCH_KEYWORDS)
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!query.is_empty()).then_some(query)
+}
+
+fn distinct_keywords(text: &str) -> Vec<&str> {
+    let mut distinct: Vec<&str> = Vec::new();
+    for word in keywords(text) {
+        if !distinct.iter().any(|seen| seen.eq_ignore_ascii_case(word)) {
+            distinct.push(word);
+        }
+    }
+    distinct
+}
+
+struct Word<'a> {
+    text: &'a str,
+    /// Byte range of `text` in the scanned string
+    range: Range<usize>,
+    /// Whether punctuation followed the word before the next space
+    punctuated: bool,
+}
+
+/// Whitespace-separated words with surrounding punctuation trimmed, skipping any that
+/// are only punctuation
+fn words(text: &str) -> Vec<Word<'_>> {
+    let is_punctuation = |c: char| !c.is_alphanumeric();
+    let mut words = Vec::new();
+    let mut offset = 0;
+    for raw in text.split(char::is_whitespace) {
+        let start = offset + (raw.len() - raw.trim_start_matches(is_punctuation).len());
+        let trimmed = raw.trim_matches(is_punctuation);
+        offset += raw.len()
+            + text[offset + raw.len()..]
+                .chars()
+                .next()
+                .map_or(0, char::len_utf8);
+        if trimmed.is_empty() {
+            continue;
+        }
+        words.push(Word {
+            text: trimmed,
+            range: start..start + trimmed.len(),
+            punctuated: raw.len() > raw.trim_end_matches(is_punctuation).len(),
+        });
+    }
+    words
+}
+
+fn is_stopword(word: &str) -> bool {
+    STOPWORDS
+        .iter()
+        .any(|stopword| word.eq_ignore_ascii_case(stopword))
+}
+
+fn is_hedge(word: &str) -> bool {
+    !is_month(word)
+        && HEDGE_WORDS
+            .iter()
+            .any(|hedge| word.eq_ignore_ascii_case(hedge))
+}
+
+fn is_month(word: &str) -> bool {
+    // "May" is a month only when capitalized; "may" is hedging
+    word.starts_with(char::is_uppercase)
+        && MONTHS.iter().any(|month| word.eq_ignore_ascii_case(month))
+}
+
+/// `5`, `05` or `5th`, up to 31
+fn is_day(word: &str) -> bool {
+    let digits = word
+        .strip_suffix("st")
+        .or_else(|| word.strip_suffix("nd"))
+        .or_else(|| word.strip_suffix("rd"))
+        .or_else(|| word.strip_suffix("th"))
+        .unwrap_or(word);
+    digits.len() <= 2
+        && digits
+            .parse::<u8>()
+            .is_ok_and(|day| (1..=31).contains(&day))
+}
+
+fn is_year(word: &str) -> bool {
+    word.len() == 4
+        && word
+            .parse::<u16>()
+            .is_ok_and(|year| (1900..2200).contains(&year))
+}
+
+/// `YYYY-MM-DD`
+fn is_iso_date(word: &str) -> bool {
+    let mut parts = word.split('-');
+    let (Some(year), Some(month), Some(day), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return false;
+    };
+    is_year(year)
+        && month.len() == 2
+        && month
+            .parse::<u8>()
+            .is_ok_and(|month| (1..=12).contains(&month))
+        && day.len() == 2
+        && is_day(day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn market(condition_id: &str, question: &str) -> Market {
+        serde_json::from_value::<crate::types::GammaMarket>(serde_json::json!({
+            "conditionId": condition_id, "slug": condition_id, "active": true,
+            "closed": false, "question": question
+        }))
+        .unwrap()
+        .into()
+    }
+
+    #[test]
+    fn test_dates_names_and_hedges() {
+        let question = "Will Donald Trump win the 2024 election, or might Kamala Harris win \
+                        before November 5th, 2024?";
+        let dates = date_mentions(question);
+        assert_eq!(
+            dates,
+            [
+                DateMention {
+                    text: "2024",
+                    full: false
+                },
+                DateMention {
+                    text: "November 5th, 2024",
+                    full: true
+                },
+            ]
+        );
+        assert_eq!(names(question), ["Donald Trump", "Kamala Harris"]);
+        assert_eq!(ambiguity_score(question), dec!(0.25));
+
+        let iso = date_mentions("Bitcoin above $100k on 2025-01-31? It may be close");
+        assert_eq!(iso.len(), 1);
+        assert_eq!(iso[0].text, "2025-01-31");
+        assert_eq!(date_mentions("Fed cut in May?")[0].text, "May");
+        assert_eq!(ambiguity_score("Fed cut in May?"), Decimal::ZERO);
+        assert_eq!(
+            ambiguity_score("Bitcoin above $100k on 2025-01-31? It may be close"),
+            dec!(0.25)
+        );
+        assert_eq!(
+            ambiguity_score("Could it possibly, likely, roughly happen?"),
+            Decimal::ONE
+        );
+    }
+
+    #[test]
+    fn test_heuristic_ranks_similar_markets() {
+        let target = market("0xt", "Will the Fed cut interest rates in March 2025?");
+        let candidates = [
+            market("0xt", "Will the Fed cut interest rates in March 2025?"),
+            market("0xa", "Will the Fed cut interest rates in June?"),
+            market("0xb", "Will the ECB cut rates?"),
+            market("0xc", "Will Arsenal win the league?"),
+        ];
+        assert_eq!(
+            search_query(&target.question).as_deref(),
+            Some("Fed cut interest rates March")
+        );
+
+        let analysis = QuestionAnalysis::heuristic(&target, &candidates);
+        assert_eq!(analysis.similar_markets, ["0xa", "0xb"]);
+        assert_eq!(analysis.resolution_date_confidence, dec!(0.5));
+        assert_eq!(analysis.ambiguity_score, Decimal::ZERO);
+        assert_eq!(
+            analysis.entities,
+            [
+                Entity {
+                    text: "March 2025".to_string(),
+                    kind: EntityKind::Date
+                },
+                Entity {
+                    text: "Fed".to_string(),
+                    kind: EntityKind::Name
+                },
+            ]
+        );
+        assert_eq!(search_query("Will the?"), None);
+    }
+}
@@ -0,0 +1,546 @@
+//! Two-sided quote maintenance around the book midpoint
+//!
+//! [`Quoter`] keeps one bid and one ask resting around the mid of a token's book in an
+//! [`OrderBookManager`], cancelling and replacing them when the mid drifts further than
+//! a threshold. It is a building block rather than a strategy: every threshold comes
+//! from [`QuoterConfig`], and the caller decides when to call [`Quoter::step`] or hands a
+//! shutdown future to [`Quoter::run`].
+
+use crate::book::OrderBookManager;
+use crate::client::{ClobClient, OrderArgs};
+use crate::errors::{PolyError, Result};
+use crate::types::{OrderType, Side};
+use rust_decimal::Decimal;
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// Window the requote cap applies to
+const REQUOTE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Caller-supplied quoting parameters
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuoterConfig {
+    pub token_id: String,
+    /// Full distance between bid and ask, centred on the mid
+    pub spread: Decimal,
+    /// Size posted on each side
+    pub size: Decimal,
+    /// Mid move (in price units) beyond which live quotes are replaced
+    pub requote_threshold: Decimal,
+    /// Hard cap on cancel/replace cycles in any rolling minute
+    pub max_requotes_per_minute: u32,
+    /// Market tick size; quote prices are rounded onto it
+    pub tick_size: Decimal,
+    /// Liquidity-reward minimum size, when quoting for rewards
+    pub reward_min_size: Option<Decimal>,
+    /// Liquidity-reward max distance from the mid, in price units (0.03 = 3¢)
+    pub reward_max_spread: Option<Decimal>,
+}
+
+impl QuoterConfig {
+    pub fn new(
+        token_id: impl Into<String>,
+        spread: Decimal,
+        size: Decimal,
+        requote_threshold: Decimal,
+        max_requotes_per_minute: u32,
+        tick_size: Decimal,
+    ) -> Self {
+        Self {
+            token_id: token_id.into(),
+            spread,
+            size,
+            requote_threshold,
+            max_requotes_per_minute,
+            tick_size,
+            reward_min_size: None,
+            reward_max_spread: None,
+        }
+    }
+
+    /// Keep quotes eligible for a reward program (see [`Rewards`](crate::types::Rewards))
+    pub fn with_reward_limits(mut self, min_size: Decimal, max_spread: Decimal) -> Self {
+        self.reward_min_size = Some(min_size);
+        self.reward_max_spread = Some(max_spread);
+        self
+    }
+
+    /// Reject parameters that could never produce valid quotes
+    pub fn validate(&self) -> Result<()> {
+        if self.token_id.is_empty() {
+            return Err(PolyError::validation("quoter token_id is empty"));
+        }
+        if self.spread <= Decimal::ZERO || self.size <= Decimal::ZERO {
+            return Err(PolyError::validation(
+                "quoter spread and size must be positive",
+            ));
+        }
+        if self.tick_size <= Decimal::ZERO {
+            return Err(PolyError::validation("quoter tick_size must be positive"));
+        }
+        if self.requote_threshold < Decimal::ZERO {
+            return Err(PolyError::validation(
+                "quoter requote_threshold must not be negative",
+            ));
+        }
+        if self.max_requotes_per_minute == 0 {
+            return Err(PolyError::validation(
+                "quoter max_requotes_per_minute must be at least 1",
+            ));
+        }
+        if let Some(min_size) = self.reward_min_size
+            && self.size < min_size
+        {
+            return Err(PolyError::validation(format!(
+                "quote size {} is below the reward minimum {}",
+                self.size, min_size
+            )));
+        }
+        if let Some(max_spread) = self.reward_max_spread
+            && self.spread / Decimal::TWO > max_spread
+        {
+            return Err(PolyError::validation(format!(
+                "spread {} puts quotes further than the reward max spread {} from the mid",
+                self.spread, max_spread
+            )));
+        }
+        Ok(())
+    }
+
+    /// Bid and ask prices for `mid`, rounded away from it onto the tick grid.
+    ///
+    /// When a reward band is set, a side that rounding pushed outside it is pulled back
+    /// one tick. Returns `None` when either side would leave the `[tick, 1 - tick]` range
+    /// or the quotes would cross.
+    pub fn quote_prices(&self, mid: Decimal) -> Option<(Decimal, Decimal)> {
+        let tick = self.tick_size;
+        let half = self.spread / Decimal::TWO;
+        let mut bid = ((mid - half) / tick).floor() * tick;
+        let mut ask = ((mid + half) / tick).ceil() * tick;
+
+        if let Some(max_spread) = self.reward_max_spread {
+            if mid - bid > max_spread {
+                bid += tick;
+            }
+            if ask - mid > max_spread {
+                ask -= tick;
+            }
+        }
+
+        if bid < tick || ask > Decimal::ONE - tick || bid >= ask {
+            return None;
+        }
+        Some((bid.normalize(), ask.normalize()))
+    }
+}
+
+/// One side of the quote as it rests on the book
+#[derive(Debug, Clone, PartialEq)]
+pub struct RestingQuote {
+    pub order_id: String,
+    pub side: Side,
+    pub price: Decimal,
+    pub size: Decimal,
+}
+
+/// Inspectable snapshot of what the quoter believes is live
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QuoteState {
+    /// Mid the live quotes were priced from
+    pub quoted_mid: Option<Decimal>,
+    pub bid: Option<RestingQuote>,
+    pub ask: Option<RestingQuote>,
+    /// Cancel/replace cycles performed
+    pub requotes: u64,
+    /// Requotes skipped because the per-minute cap was reached
+    pub throttled: u64,
+}
+
+impl QuoteState {
+    fn order_ids(&self) -> Vec<String> {
+        self.bid
+            .iter()
+            .chain(self.ask.iter())
+            .map(|quote| quote.order_id.clone())
+            .collect()
+    }
+}
+
+/// What a single [`Quoter::step`] did
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuoteAction {
+    /// The book is missing or one-sided; live quotes were left alone
+    NoMid,
+    /// Both quotes are live and the mid stayed within the threshold
+    Held,
+    /// A requote was due but the per-minute cap was already spent
+    Throttled,
+    /// Old quotes were cancelled and a fresh pair posted
+    Requoted,
+    /// No valid prices exist around the current mid; live quotes were cancelled
+    Pulled,
+}
+
+/// Keeps a bid and an ask at a target spread around the mid of one token
+pub struct Quoter {
+    client: Arc<ClobClient>,
+    books: Arc<OrderBookManager>,
+    config: QuoterConfig,
+    state: QuoteState,
+    requote_times: VecDeque<Instant>,
+}
+
+impl Quoter {
+    /// `client` must carry L2 credentials; mids are read from `books`, which the caller
+    /// keeps fed (typically from a market channel subscription).
+    pub fn new(
+        client: Arc<ClobClient>,
+        books: Arc<OrderBookManager>,
+        config: QuoterConfig,
+    ) -> Result<Self> {
+        config.validate()?;
+        Ok(Self {
+            client,
+            books,
+            config,
+            state: QuoteState::default(),
+            requote_times: VecDeque::new(),
+        })
+    }
+
+    pub fn config(&self) -> &QuoterConfig {
+        &self.config
+    }
+
+    /// Current quote state
+    pub fn state(&self) -> &QuoteState {
+        &self.state
+    }
+
+    /// Current mid of the tracked book, if both sides are present
+    pub fn current_mid(&self) -> Option<Decimal> {
+        let book = self.books.get_book(&self.config.token_id).ok()?;
+        let bid = book.bids.first()?.price;
+        let ask = book.asks.first()?.price;
+        (bid < ask).then(|| (bid + ask) / Decimal::TWO)
+    }
+
+    /// Forget a quote the caller saw fill or cancel elsewhere (e.g. on the user channel).
+    ///
+    /// The next [`step`](Self::step) then requotes the missing side. Returns whether the
+    /// order belonged to this quoter.
+    pub fn on_order_closed(&mut self, order_id: &str) -> bool {
+        for side in [&mut self.state.bid, &mut self.state.ask] {
+            if side
+                .as_ref()
+                .is_some_and(|quote| quote.order_id == order_id)
+            {
+                *side = None;
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Check the mid once and requote if needed
+    pub async fn step(&mut self) -> Result<QuoteAction> {
+        let Some(mid) = self.current_mid() else {
+            return Ok(QuoteAction::NoMid);
+        };
+
+        let fully_quoted = self.state.bid.is_some() && self.state.ask.is_some();
+        if fully_quoted
+            && let Some(quoted_mid) = self.state.quoted_mid
+            && (mid - quoted_mid).abs() <= self.config.requote_threshold
+        {
+            return Ok(QuoteAction::Held);
+        }
+
+        let Some((bid, ask)) = self.config.quote_prices(mid) else {
+            self.cancel_quotes().await?;
+            return Ok(QuoteAction::Pulled);
+        };
+
+        let now = Instant::now();
+        while self
+            .requote_times
+            .front()
+            .is_some_and(|at| now.duration_since(*at) >= REQUOTE_WINDOW)
+        {
+            self.requote_times.pop_front();
+        }
+        if self.requote_times.len() >= self.config.max_requotes_per_minute as usize {
+            self.state.throttled += 1;
+            return Ok(QuoteAction::Throttled);
+        }
+        self.requote_times.push_back(now);
+
+        self.cancel_quotes().await?;
+        self.post_quotes(mid, bid, ask).await?;
+        self.state.requotes += 1;
+        Ok(QuoteAction::Requoted)
+    }
+
+    /// Step every `interval` until `shutdown` resolves, then cancel the live quotes.
+    ///
+    /// Retryable errors are logged and the loop carries on; any other error stops it,
+    /// after a best-effort cancel, and is returned.
+    pub async fn run<F>(&mut self, interval: Duration, shutdown: F) -> Result<()>
+    where
+        F: Future<Output = ()>,
+    {
+        tokio::pin!(shutdown);
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            tokio::select! {
+                _ = &mut shutdown => break,
+                _ = ticker.tick() => {
+                    if let Err(err) = self.step().await {
+                        if !err.is_retryable() {
+                            if let Err(cancel_err) = self.cancel_quotes().await {
+                                warn!("quoter failed to cancel after error: {}", cancel_err);
+                            }
+                            return Err(err);
+                        }
+                        warn!("quoter step failed, retrying: {}", err);
+                    }
+                }
+            }
+        }
+
+        self.shutdown().await
+    }
+
+    /// Cancel whatever is live and clear the quote state
+    pub async fn shutdown(&mut self) -> Result<()> {
+        self.cancel_quotes().await
+    }
+
+    async fn cancel_quotes(&mut self) -> Result<()> {
+        let order_ids = self.state.order_ids();
+        if !order_ids.is_empty() {
+            // Orders the exchange reports as not cancelled have already filled or died
+            self.client.cancel_orders(&order_ids).await?;
+        }
+        self.state.bid = None;
+        self.state.ask = None;
+        self.state.quoted_mid = None;
+        Ok(())
+    }
+
+    async fn post_quotes(&mut self, mid: Decimal, bid: Decimal, ask: Decimal) -> Result<()> {
+        let token_id = &self.config.token_id;
+        let size = self.config.size;
+        let bid_args = OrderArgs::new(token_id, bid, size, Side::BUY);
+        let ask_args = OrderArgs::new(token_id, ask, size, Side::SELL);
+
+        let (bid_order, ask_order) = futures::try_join!(
+            self.client.create_order(&bid_args, None, None, None),
+            self.client.create_order(&ask_args, None, None, None),
+        )?;
+        let responses = self
+            .client
+            .post_orders(vec![bid_order, ask_order], OrderType::GTC)
+            .await?;
+
+        self.state.quoted_mid = Some(mid);
+        self.state.bid = resting_quote(responses.first(), &bid_args);
+        self.state.ask = resting_quote(responses.get(1), &ask_args);
+        Ok(())
+    }
+}
+
+fn resting_quote(response: Option<&Value>, args: &OrderArgs) -> Option<RestingQuote> {
+    let response = response?;
+    let order_id = response
+        .get("orderID")
+        .or_else(|| response.get("orderId"))
+        .and_then(Value::as_str)
+        .filter(|id| !id.is_empty());
+    let accepted = response
+        .get("success")
+        .and_then(Value::as_bool)
+        .unwrap_or(true);
+
+    match order_id {
+        Some(order_id) if accepted => Some(RestingQuote {
+            order_id: order_id.to_string(),
+            side: args.side,
+            price: args.price,
+            size: args.size,
+        }),
+        _ => {
+            warn!(
+                "quoter {:?} at {} was not accepted: {}",
+                args.side, args.price, response
+            );
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market_meta::{MarketMeta, MarketMetaCache};
+    use crate::testkit::MockExchange;
+    use crate::types::{ApiCredentials, OrderDelta};
+    use chrono::Utc;
+    use mockito::Matcher;
+    use rust_decimal_macros::dec;
+
+    const TOKEN: &str = "12345";
+
+    fn config() -> QuoterConfig {
+        QuoterConfig::new(TOKEN, dec!(0.04), dec!(50), dec!(0.01), 2, dec!(0.01))
+    }
+
+    fn client(url: &str) -> Arc<ClobClient> {
+        let cache = MarketMetaCache::default();
+        cache.insert(
+            TOKEN,
+            MarketMeta {
+                tick_size: dec!(0.01),
+                neg_risk: false,
+                min_order_size: None,
+            },
+        );
+        let client = ClobClient::with_l2_headers(
+            url,
+            "0x1234567890123456789012345678901234567890123456789012345678901234",
+            137,
+            ApiCredentials {
+                api_key: "test_key".to_string(),
+                secret: "dGVzdF9zZWNyZXQ=".to_string(),
+                passphrase: "test_passphrase".to_string(),
+            },
+        )
+        .with_market_meta_cache(Arc::new(cache));
+        Arc::new(client)
+    }
+
+    fn set_top(books: &OrderBookManager, bid: Decimal, ask: Decimal, sequence: u64) {
+        let book = books.get_book(TOKEN).unwrap();
+        let updates = book
+            .bids
+            .iter()
+            .map(|level| (Side::BUY, level.price, Decimal::ZERO))
+            .chain(
+                book.asks
+                    .iter()
+                    .map(|level| (Side::SELL, level.price, Decimal::ZERO)),
+            )
+            .chain([(Side::BUY, bid, dec!(100)), (Side::SELL, ask, dec!(100))]);
+        for (i, (side, price, size)) in updates.enumerate() {
+            books
+                .apply_delta(OrderDelta {
+                    token_id: TOKEN.to_string(),
+                    timestamp: Utc::now(),
+                    side,
+                    price,
+                    size,
+                    sequence: sequence * 100 + i as u64,
+                })
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_quote_prices_round_outward_and_respect_reward_band() {
+        let config = config();
+        assert_eq!(
+            config.quote_prices(dec!(0.505)),
+            Some((dec!(0.48), dec!(0.53)))
+        );
+        assert_eq!(config.quote_prices(dec!(0.01)), None);
+
+        // Outward rounding would put each side 2.5¢ from the mid
+        let banded = config.clone().with_reward_limits(dec!(20), dec!(0.02));
+        assert_eq!(
+            banded.quote_prices(dec!(0.505)),
+            Some((dec!(0.49), dec!(0.52)))
+        );
+
+        assert!(
+            config
+                .clone()
+                .with_reward_limits(dec!(100), dec!(0.05))
+                .validate()
+                .is_err()
+        );
+        assert!(
+            config
+                .clone()
+                .with_reward_limits(dec!(10), dec!(0.01))
+                .validate()
+                .is_err()
+        );
+        let mut zero_cap = config;
+        zero_cap.max_requotes_per_minute = 0;
+        assert!(zero_cap.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_quoter_requotes_on_mid_move_and_cancels_on_shutdown() {
+        let mut server = MockExchange::empty().await;
+        let post = server
+            .mock("POST", "/orders")
+            .match_body(Matcher::Regex(r#""side":"BUY".*"side":"SELL""#.to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[{"success": true, "orderID": "0xbid", "status": "live"},
+                    {"success": true, "orderID": "0xask", "status": "live"}]"#,
+            )
+            .expect(2)
+            .create_async()
+            .await;
+        let cancel = server
+            .mock("DELETE", "/orders")
+            .match_body(Matcher::Json(serde_json::json!(["0xbid", "0xask"])))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"canceled": ["0xbid", "0xask"], "not_canceled": {}}"#)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let books = Arc::new(OrderBookManager::new(10));
+        let mut quoter = Quoter::new(client(&server.url()), books.clone(), config()).unwrap();
+        assert_eq!(quoter.step().await.unwrap(), QuoteAction::NoMid);
+
+        books.get_or_create_book(TOKEN).unwrap();
+        set_top(&books, dec!(0.48), dec!(0.52), 1);
+        assert_eq!(quoter.step().await.unwrap(), QuoteAction::Requoted);
+        let state = quoter.state().clone();
+        assert_eq!(state.quoted_mid, Some(dec!(0.50)));
+        assert_eq!(state.bid.as_ref().unwrap().price, dec!(0.48));
+        assert_eq!(state.ask.as_ref().unwrap().order_id, "0xask");
+
+        // A one-tick move sits inside the threshold
+        set_top(&books, dec!(0.49), dec!(0.53), 2);
+        assert_eq!(quoter.step().await.unwrap(), QuoteAction::Held);
+
+        set_top(&books, dec!(0.54), dec!(0.58), 3);
+        assert_eq!(quoter.step().await.unwrap(), QuoteAction::Requoted);
+        assert_eq!(quoter.state().bid.as_ref().unwrap().price, dec!(0.54));
+
+        // Both requotes this minute are spent
+        set_top(&books, dec!(0.30), dec!(0.34), 4);
+        assert_eq!(quoter.step().await.unwrap(), QuoteAction::Throttled);
+        assert_eq!(quoter.state().throttled, 1);
+
+        quoter.shutdown().await.unwrap();
+        assert_eq!(quoter.state().bid, None);
+        assert_eq!(quoter.state().requotes, 2);
+
+        post.assert_async().await;
+        cancel.assert_async().await;
+    }
+}
@@ -0,0 +1,135 @@
+//! Ranking Gamma markets for [`ClobClient::get_top_markets`](crate::client::ClobClient::get_top_markets)
+//!
+//! A [`MarketScorer`] maps a market to a number where higher is better. The built-in
+//! scorers read one field each; [`CompositeScorer`] sums weighted scores from several.
+//! Closures of type `Fn(&Market) -> Decimal` are scorers too.
+
+use crate::types::Market;
+use rust_decimal::Decimal;
+
+/// Assigns a ranking score to a market; higher ranks first
+pub trait MarketScorer: Send + Sync {
+    fn score(&self, market: &Market) -> Decimal;
+}
+
+impl<F> MarketScorer for F
+where
+    F: Fn(&Market) -> Decimal + Send + Sync,
+{
+    fn score(&self, market: &Market) -> Decimal {
+        self(market)
+    }
+}
+
+/// Scores by `liquidity_num`; markets without it score zero
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LiquidityScorer;
+
+impl MarketScorer for LiquidityScorer {
+    fn score(&self, market: &Market) -> Decimal {
+        market.liquidity_num.unwrap_or_default()
+    }
+}
+
+/// Scores by trailing 24h volume; markets without it score zero
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VolumeScorer;
+
+impl MarketScorer for VolumeScorer {
+    fn score(&self, market: &Market) -> Decimal {
+        market.volume_24hr.unwrap_or_default()
+    }
+}
+
+/// Scores tighter books higher, as `1 - spread`
+///
+/// A market without a reported spread scores zero, the same as one whose spread is
+/// the full price range.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SpreadScorer;
+
+impl MarketScorer for SpreadScorer {
+    fn score(&self, market: &Market) -> Decimal {
+        market
+            .spread
+            .map_or(Decimal::ZERO, |spread| Decimal::ONE - spread)
+    }
+}
+
+/// Weighted sum of other scorers
+///
+/// Scores are added as they come, so weights have to account for scale: liquidity and
+/// volume are in dollars while [`SpreadScorer`] stays within `0..=1`.
+#[derive(Default)]
+pub struct CompositeScorer {
+    pub weights: Vec<(Box<dyn MarketScorer>, Decimal)>,
+}
+
+impl CompositeScorer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, scorer: impl MarketScorer + 'static, weight: Decimal) -> Self {
+        self.weights.push((Box::new(scorer), weight));
+        self
+    }
+}
+
+impl MarketScorer for CompositeScorer {
+    fn score(&self, market: &Market) -> Decimal {
+        self.weights
+            .iter()
+            .map(|(scorer, weight)| scorer.score(market) * weight)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::GammaMarket;
+    use rust_decimal_macros::dec;
+
+    fn market(
+        liquidity: Option<Decimal>,
+        volume: Option<Decimal>,
+        spread: Option<Decimal>,
+    ) -> Market {
+        let mut market: Market = serde_json::from_value::<GammaMarket>(serde_json::json!({
+            "conditionId": "0xabc",
+            "slug": "scored",
+            "active": true,
+            "closed": false
+        }))
+        .unwrap()
+        .into();
+        market.liquidity_num = liquidity;
+        market.volume_24hr = volume;
+        market.spread = spread;
+        market
+    }
+
+    #[test]
+    fn test_builtin_scorers() {
+        let m = market(Some(dec!(1000)), Some(dec!(250)), Some(dec!(0.02)));
+        assert_eq!(LiquidityScorer.score(&m), dec!(1000));
+        assert_eq!(VolumeScorer.score(&m), dec!(250));
+        assert_eq!(SpreadScorer.score(&m), dec!(0.98));
+
+        let empty = market(None, None, None);
+        assert_eq!(LiquidityScorer.score(&empty), Decimal::ZERO);
+        assert_eq!(SpreadScorer.score(&empty), Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_composite_scorer_sums_weights() {
+        let m = market(Some(dec!(1000)), Some(dec!(250)), Some(dec!(0.1)));
+        let scorer = CompositeScorer::new()
+            .with(LiquidityScorer, dec!(0.001))
+            .with(VolumeScorer, dec!(0.01))
+            .with(SpreadScorer, dec!(2))
+            .with(|_: &Market| dec!(5), Decimal::ONE);
+        assert_eq!(scorer.score(&m), dec!(1) + dec!(2.5) + dec!(1.8) + dec!(5));
+    }
+}
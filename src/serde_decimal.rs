@@ -0,0 +1,412 @@
+//! Serde adapters for `Decimal` fields that arrive as strings, numbers or both
+//!
+//! Most CLOB endpoints send prices and sizes as decimal strings, most Gamma endpoints
+//! send JSON numbers, and a few fields switch between the two depending on the endpoint.
+//! Every wire type in the crate goes through this module so a field's representation
+//! can change upstream without breaking parsing:
+//!
+//! ```ignore
+//! #[serde(with = "crate::serde_decimal")]
+//! pub price: Decimal,
+//! #[serde(default, with = "crate::serde_decimal::option")]
+//! pub liquidity_num: Option<Decimal>,
+//! #[serde(default, with = "crate::serde_decimal::vec")]
+//! pub outcome_prices: Vec<Decimal>,
+//! ```
+//!
+//! Deserializing accepts decimal strings (scientific notation too), integers and floats.
+//! A required field rejects `null` and empty strings; [`option`] fields read them as
+//! `None`. Strict [`NumberMode`] additionally rejects bare numbers and empty strings
+//! everywhere. Serializing always writes a decimal string. `Option` fields need
+//! `#[serde(default)]` to stay optional when the key is missing.
+
+use crate::decode::{NumberMode, number_mode};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer, Serializer};
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// What a decimal field held on the wire, before defaults are applied
+enum RawDecimal {
+    Value(Decimal),
+    Empty,
+    Null,
+}
+
+/// Visits the wire value directly so borrowed input never allocates
+struct DecimalVisitor(NumberMode);
+
+impl DecimalVisitor {
+    fn parse_number<E: serde::de::Error>(
+        &self,
+        raw: impl Display,
+    ) -> std::result::Result<RawDecimal, E> {
+        match self.0 {
+            NumberMode::Lenient => {
+                let raw = raw.to_string();
+                Decimal::from_str(&raw)
+                    .or_else(|_| Decimal::from_scientific(&raw))
+                    .map(RawDecimal::Value)
+                    .map_err(|e| E::custom(format!("invalid decimal {}: {}", raw, e)))
+            }
+            NumberMode::Strict => Err(E::custom(format!(
+                "expected decimal string, got number {}",
+                raw
+            ))),
+        }
+    }
+}
+
+impl<'de> serde::de::Visitor<'de> for DecimalVisitor {
+    type Value = RawDecimal;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("a decimal string or number")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, s: &str) -> std::result::Result<RawDecimal, E> {
+        let s = s.trim();
+        if s.is_empty() {
+            return match self.0 {
+                NumberMode::Lenient => Ok(RawDecimal::Empty),
+                NumberMode::Strict => Err(E::custom("empty string where a decimal was expected")),
+            };
+        }
+        Decimal::from_str(s)
+            .or_else(|_| match self.0 {
+                NumberMode::Lenient => Decimal::from_scientific(s),
+                NumberMode::Strict => Decimal::from_str(s),
+            })
+            .map(RawDecimal::Value)
+            .map_err(|e| E::custom(format!("invalid decimal {:?}: {}", s, e)))
+    }
+
+    fn visit_u64<E: serde::de::Error>(self, n: u64) -> std::result::Result<RawDecimal, E> {
+        self.parse_number(n)
+    }
+
+    fn visit_i64<E: serde::de::Error>(self, n: i64) -> std::result::Result<RawDecimal, E> {
+        self.parse_number(n)
+    }
+
+    fn visit_f64<E: serde::de::Error>(self, n: f64) -> std::result::Result<RawDecimal, E> {
+        self.parse_number(n)
+    }
+
+    fn visit_unit<E: serde::de::Error>(self) -> std::result::Result<RawDecimal, E> {
+        Ok(RawDecimal::Null)
+    }
+
+    fn visit_none<E: serde::de::Error>(self) -> std::result::Result<RawDecimal, E> {
+        Ok(RawDecimal::Null)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> std::result::Result<RawDecimal, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+fn required<E: serde::de::Error>(raw: RawDecimal) -> std::result::Result<Decimal, E> {
+    match raw {
+        RawDecimal::Value(decimal) => Ok(decimal),
+        RawDecimal::Empty => Err(E::custom("empty string where a decimal was expected")),
+        RawDecimal::Null => Err(E::custom("null where a decimal was expected")),
+    }
+}
+
+pub fn serialize<S: Serializer>(
+    value: &Decimal,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
+
+pub fn deserialize<'de, D>(deserializer: D) -> std::result::Result<Decimal, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    required(deserializer.deserialize_any(DecimalVisitor(number_mode()))?)
+}
+
+/// A bare `Decimal` read like a `with = "crate::serde_decimal"` field, for map values
+/// and other places a field attribute can't reach
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Wire(pub Decimal);
+
+impl<'de> Deserialize<'de> for Wire {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        deserialize(deserializer).map(Wire)
+    }
+}
+
+/// `Option<Decimal>`: `null`, empty strings and missing keys are `None`
+pub mod option {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        value: &Option<Decimal>,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        match value {
+            Some(decimal) => serializer.collect_str(decimal),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> std::result::Result<Option<Decimal>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match deserializer.deserialize_any(DecimalVisitor(number_mode()))? {
+            RawDecimal::Value(decimal) => Ok(Some(decimal)),
+            RawDecimal::Empty | RawDecimal::Null => Ok(None),
+        }
+    }
+}
+
+/// `Vec<Decimal>`: a JSON array, or a string holding one as Gamma sends
+/// `outcomePrices`; `null` is empty
+pub mod vec {
+    use super::*;
+    use serde::ser::SerializeSeq;
+
+    /// One array element, parsed like a required field
+    struct Element(Decimal);
+
+    impl<'de> Deserialize<'de> for Element {
+        fn deserialize<D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> std::result::Result<Self, D::Error> {
+            super::deserialize(deserializer).map(Element)
+        }
+    }
+
+    struct VecVisitor;
+
+    impl<'de> serde::de::Visitor<'de> for VecVisitor {
+        type Value = Vec<Decimal>;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            f.write_str("an array of decimals")
+        }
+
+        fn visit_seq<A: serde::de::SeqAccess<'de>>(
+            self,
+            mut seq: A,
+        ) -> std::result::Result<Vec<Decimal>, A::Error> {
+            let mut values = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+            while let Some(Element(value)) = seq.next_element()? {
+                values.push(value);
+            }
+            Ok(values)
+        }
+
+        fn visit_str<E: serde::de::Error>(self, s: &str) -> std::result::Result<Vec<Decimal>, E> {
+            if s.trim().is_empty() {
+                return Ok(Vec::new());
+            }
+            let elements: Vec<Element> = serde_json::from_str(s)
+                .map_err(|e| E::custom(format!("invalid decimal array {:?}: {}", s, e)))?;
+            Ok(elements.into_iter().map(|Element(value)| value).collect())
+        }
+
+        fn visit_unit<E: serde::de::Error>(self) -> std::result::Result<Vec<Decimal>, E> {
+            Ok(Vec::new())
+        }
+
+        fn visit_none<E: serde::de::Error>(self) -> std::result::Result<Vec<Decimal>, E> {
+            Ok(Vec::new())
+        }
+
+        fn visit_some<D: Deserializer<'de>>(
+            self,
+            deserializer: D,
+        ) -> std::result::Result<Vec<Decimal>, D::Error> {
+            deserializer.deserialize_any(self)
+        }
+    }
+
+    pub fn serialize<S: Serializer>(
+        values: &[Decimal],
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(values.len()))?;
+        for value in values {
+            seq.serialize_element(&value.to_string())?;
+        }
+        seq.end()
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> std::result::Result<Vec<Decimal>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(VecVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::with_number_mode;
+    use rust_decimal_macros::dec;
+    use serde::Serialize;
+    use serde::de::DeserializeOwned;
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Fields {
+        #[serde(with = "crate::serde_decimal")]
+        value: Decimal,
+        #[serde(default, with = "crate::serde_decimal::option")]
+        maybe: Option<Decimal>,
+        #[serde(default, with = "crate::serde_decimal::vec")]
+        many: Vec<Decimal>,
+    }
+
+    fn fields(json: &str) -> serde_json::Result<Fields> {
+        serde_json::from_str(json)
+    }
+
+    #[test]
+    fn test_strings_and_numbers_alike() {
+        let parsed =
+            fields(r#"{"value": "0.52", "maybe": 0.52, "many": ["0.5", 0.25, "1e-2"]}"#).unwrap();
+        assert_eq!(parsed.value, dec!(0.52));
+        assert_eq!(parsed.maybe, Some(dec!(0.52)));
+        assert_eq!(parsed.many, [dec!(0.5), dec!(0.25), dec!(0.01)]);
+
+        // Gamma sends arrays like `outcomePrices` JSON-encoded inside a string
+        let parsed = fields(r#"{"value": 3, "maybe": "", "many": "[\"0.6\", \"0.4\"]"}"#).unwrap();
+        assert_eq!(parsed.value, dec!(3));
+        assert_eq!(parsed.maybe, None);
+        assert_eq!(parsed.many, [dec!(0.6), dec!(0.4)]);
+
+        let json = serde_json::to_value(&parsed).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"value": "3", "maybe": null, "many": ["0.6", "0.4"]})
+        );
+    }
+
+    #[test]
+    fn test_strict_mode_only_takes_strings() {
+        with_number_mode(NumberMode::Strict, || {
+            assert!(fields(r#"{"value": "0.52"}"#).is_ok());
+            assert!(fields(r#"{"value": 0.52}"#).is_err());
+            assert!(fields(r#"{"value": ""}"#).is_err());
+            assert!(fields(r#"{"value": null}"#).is_err());
+            assert_eq!(
+                fields(r#"{"value": "1", "maybe": null}"#).unwrap().maybe,
+                None
+            );
+        });
+        assert!(fields(r#"{"value": null}"#).is_err());
+        assert!(fields(r#"{"value": ""}"#).is_err());
+        assert_eq!(
+            fields(r#"{"value": "1", "maybe": null}"#).unwrap().maybe,
+            None
+        );
+    }
+
+    fn parse<T: DeserializeOwned>(name: &str, body: &str) -> T {
+        serde_json::from_str(body)
+            .unwrap_or_else(|e| panic!("fixture {} no longer parses: {}", name, e))
+    }
+
+    /// One recorded payload per endpoint; a failure here means Polymarket changed a
+    /// field's type
+    #[test]
+    fn test_fixture_corpus_parses() {
+        use crate::types::*;
+
+        let markets: Vec<GammaMarket> = parse(
+            "gamma_markets",
+            include_str!("../tests/fixtures/http/gamma_markets.json"),
+        );
+        let market = Market::from(markets.into_iter().next().unwrap());
+        assert_eq!(market.liquidity_num, Some(dec!(182244.5731)));
+
+        let book: OrderBookSummary = parse(
+            "clob_book",
+            include_str!("../tests/fixtures/http/clob_book.json"),
+        );
+        assert!(!book.bids.is_empty());
+        let mid: MidpointResponse = parse(
+            "clob_midpoint",
+            include_str!("../tests/fixtures/http/clob_midpoint.json"),
+        );
+        assert_eq!(mid.mid, dec!(0.145));
+        let price: PriceResponse = parse(
+            "clob_price",
+            include_str!("../tests/fixtures/http/clob_price.json"),
+        );
+        assert_eq!(price.price, dec!(0.14));
+        let spread: SpreadResponse = parse(
+            "clob_spread",
+            include_str!("../tests/fixtures/http/clob_spread.json"),
+        );
+        assert_eq!(spread.spread, dec!(0.01));
+        let tick: TickSizeResponse = parse(
+            "clob_tick_size",
+            include_str!("../tests/fixtures/http/clob_tick_size.json"),
+        );
+        assert_eq!(tick.minimum_tick_size, dec!(0.01));
+        let interest: OpenInterest = parse(
+            "clob_open_interest",
+            include_str!("../tests/fixtures/http/clob_open_interest.json"),
+        );
+        assert_eq!(interest.total_open_interest, dec!(20142.75));
+        let orders: Vec<OpenOrder> = parse(
+            "clob_open_orders",
+            include_str!("../tests/fixtures/http/clob_open_orders.json"),
+        );
+        assert_eq!(orders[0].price, dec!(0.13));
+
+        let trades: PagedTrades = parse(
+            "data_trades",
+            include_str!("../tests/fixtures/http/data_trades.json"),
+        );
+        assert_eq!(trades.data[0].size, dec!(25));
+        let positions: Vec<DataPosition> = parse(
+            "data_positions",
+            include_str!("../tests/fixtures/http/data_positions.json"),
+        );
+        assert_eq!(positions[0].cur_price, dec!(0.145));
+        let values: Vec<DataPositionValue> = parse(
+            "data_value",
+            include_str!("../tests/fixtures/http/data_value.json"),
+        );
+        assert_eq!(values[0].value, dec!(3.625));
+
+        for (name, body) in [
+            (
+                "market_book",
+                include_str!("../tests/fixtures/wss/market_book.json"),
+            ),
+            (
+                "price_change",
+                include_str!("../tests/fixtures/wss/price_change.json"),
+            ),
+            (
+                "market_misc",
+                include_str!("../tests/fixtures/wss/market_misc.json"),
+            ),
+        ] {
+            let events = crate::wss::parse_market_events(body)
+                .unwrap_or_else(|e| panic!("fixture {} no longer parses: {}", name, e));
+            assert!(!events.is_empty(), "fixture {} parsed to nothing", name);
+        }
+        let events =
+            crate::wss::parse_user_events(include_str!("../tests/fixtures/wss/user_events.json"))
+                .unwrap_or_else(|e| panic!("fixture user_events no longer parses: {}", e));
+        assert!(!events.is_empty());
+    }
+
+    #[derive(Deserialize)]
+    struct PagedTrades {
+        data: Vec<crate::pnl::TradeRecord>,
+    }
+}
@@ -0,0 +1,136 @@
+//! Numeric metrics for dashboards, recorded through the `metrics` facade
+//!
+//! With the `metrics` feature enabled, the REST and websocket clients emit the series
+//! named by the constants below to whatever recorder the application installs (for
+//! example `metrics-exporter-prometheus`). Without the feature every hook here
+//! compiles to nothing, so the call sites cost nothing.
+//!
+//! Labels are kept low-cardinality: `endpoint` is the request path with ID-like
+//! segments replaced by `:id`, and `status` is the HTTP status code, or `error` when no
+//! response arrived.
+
+/// Counter of REST requests, labelled `endpoint` and `status`
+pub const HTTP_REQUESTS_TOTAL: &str = "polysqueeze_http_requests_total";
+/// Histogram of REST request latency in seconds, labelled `endpoint`
+pub const HTTP_REQUEST_DURATION_SECONDS: &str = "polysqueeze_http_request_duration_seconds";
+/// Counter of HTTP 429 responses, labelled `endpoint`
+pub const RATE_LIMITED_TOTAL: &str = "polysqueeze_rate_limited_total";
+/// Gauge of requests waiting for a slot in the client's limiter, labelled `priority`
+pub const REQUEST_QUEUE_DEPTH: &str = "polysqueeze_request_queue_depth";
+/// Counter of websocket events, labelled `channel` and `event_type`
+pub const WSS_EVENTS_TOTAL: &str = "polysqueeze_wss_events_total";
+/// Counter of websocket reconnects, labelled `channel`
+pub const WSS_RECONNECTS_TOTAL: &str = "polysqueeze_wss_reconnects_total";
+
+#[cfg(feature = "metrics")]
+mod enabled {
+    use super::*;
+    use std::time::Instant;
+
+    /// Tracks one REST request from send to response
+    pub(crate) struct RequestTimer {
+        endpoint: String,
+        started: Instant,
+    }
+
+    impl RequestTimer {
+        pub(crate) fn start(url: &reqwest::Url) -> Self {
+            Self {
+                endpoint: endpoint_label(url.path()),
+                started: Instant::now(),
+            }
+        }
+
+        pub(crate) fn finish(self, result: &reqwest::Result<reqwest::Response>) {
+            let status = match result {
+                Ok(response) => response.status().as_u16().to_string(),
+                Err(err) => err
+                    .status()
+                    .map(|status| status.as_u16().to_string())
+                    .unwrap_or_else(|| "error".to_string()),
+            };
+            if status == "429" {
+                metrics::counter!(RATE_LIMITED_TOTAL, "endpoint" => self.endpoint.clone())
+                    .increment(1);
+            }
+            metrics::histogram!(HTTP_REQUEST_DURATION_SECONDS, "endpoint" => self.endpoint.clone())
+                .record(self.started.elapsed().as_secs_f64());
+            metrics::counter!(HTTP_REQUESTS_TOTAL, "endpoint" => self.endpoint, "status" => status)
+                .increment(1);
+        }
+    }
+
+    pub(crate) fn wss_event(channel: &'static str, event_type: &'static str) {
+        metrics::counter!(WSS_EVENTS_TOTAL, "channel" => channel, "event_type" => event_type)
+            .increment(1);
+    }
+
+    pub(crate) fn wss_reconnect(channel: &'static str) {
+        metrics::counter!(WSS_RECONNECTS_TOTAL, "channel" => channel).increment(1);
+    }
+
+    pub(crate) fn request_queue_depth(priority: &'static str, depth: usize) {
+        metrics::gauge!(REQUEST_QUEUE_DEPTH, "priority" => priority).set(depth as f64);
+    }
+
+    /// Collapse per-resource path segments so each route is one label value
+    pub(crate) fn endpoint_label(path: &str) -> String {
+        let segments: Vec<&str> = path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(|segment| {
+                let id_like = segment.starts_with("0x")
+                    || segment.len() > 24
+                    || segment.bytes().all(|b| b.is_ascii_digit());
+                if id_like { ":id" } else { segment }
+            })
+            .collect();
+        format!("/{}", segments.join("/"))
+    }
+}
+
+#[cfg(feature = "metrics")]
+pub(crate) use enabled::*;
+
+#[cfg(not(feature = "metrics"))]
+mod disabled {
+    pub(crate) struct RequestTimer;
+
+    impl RequestTimer {
+        #[inline(always)]
+        pub(crate) fn start(_url: &reqwest::Url) -> Self {
+            Self
+        }
+
+        #[inline(always)]
+        pub(crate) fn finish(self, _result: &reqwest::Result<reqwest::Response>) {}
+    }
+
+    #[inline(always)]
+    pub(crate) fn wss_event(_channel: &'static str, _event_type: &'static str) {}
+
+    #[inline(always)]
+    pub(crate) fn wss_reconnect(_channel: &'static str) {}
+
+    #[inline(always)]
+    pub(crate) fn request_queue_depth(_priority: &'static str, _depth: usize) {}
+}
+
+#[cfg(not(feature = "metrics"))]
+pub(crate) use disabled::*;
+
+#[cfg(all(test, feature = "metrics"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_label_hides_ids() {
+        assert_eq!(endpoint_label("/book"), "/book");
+        assert_eq!(endpoint_label("/markets/0xabc123"), "/markets/:id");
+        assert_eq!(
+            endpoint_label("/live-activity/events/12345/"),
+            "/live-activity/events/:id"
+        );
+        assert_eq!(endpoint_label("/"), "/");
+    }
+}
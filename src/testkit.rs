@@ -0,0 +1,321 @@
+//! Offline stand-ins for the Polymarket APIs, for testing strategies without network
+//!
+//! [`MockExchange`] is a local HTTP server (mockito) answering the common CLOB and
+//! Gamma read endpoints from recorded [`fixtures`]; add or override routes through
+//! [`MockExchange::mock`]. [`MockMarketFeed`] is a websocket server that plays a
+//! [`FeedScript`] to each client connection: frames, pauses and forced disconnects.
+//!
+//! ```no_run
+//! # async fn demo() -> polysqueeze::Result<()> {
+//! use polysqueeze::testkit::{FeedScript, MockExchange, MockMarketFeed, fixtures};
+//!
+//! let exchange = MockExchange::start().await;
+//! let book = exchange
+//!     .clob_client()
+//!     .get_order_book(fixtures::YES_TOKEN_ID, None)
+//!     .await?;
+//!
+//! let feed = MockMarketFeed::start(
+//!     FeedScript::new()
+//!         .send(fixtures::WSS_MARKET_BOOK)
+//!         .disconnect()
+//!         .send(fixtures::WSS_PRICE_CHANGE),
+//! )
+//! .await?;
+//! let mut client = feed.market_client();
+//! client
+//!     .subscribe_market_channel(vec![fixtures::YES_TOKEN_ID.to_string()])
+//!     .await?;
+//! let first = client.next_event().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::client::ClobClient;
+use crate::errors::{PolyError, Result};
+use crate::wss::WssMarketClient;
+use futures::{SinkExt, StreamExt};
+use mockito::{Matcher, Server, ServerGuard};
+use serde_json::Value;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Message;
+
+/// Recorded API payloads, all describing the same market
+pub mod fixtures {
+    /// Condition ID of the market the fixtures describe
+    pub const CONDITION_ID: &str =
+        "0x5f65177b394277fd294cd75650044e32ba009a95022d88a0c1d565897d72f8f1";
+    pub const YES_TOKEN_ID: &str =
+        "71321045679252212594626385532706912750332728571942532289631379312455583992563";
+    pub const NO_TOKEN_ID: &str =
+        "52114319501245915516055106046884209969926127482827954674443846427813813222426";
+
+    /// Gamma `GET /markets` page holding the fixture market and a sibling
+    pub const GAMMA_MARKETS: &str = include_str!("../tests/fixtures/http/gamma_markets.json");
+    /// CLOB `GET /book` for [`YES_TOKEN_ID`]
+    pub const CLOB_BOOK: &str = include_str!("../tests/fixtures/http/clob_book.json");
+
+    /// Market channel `book` snapshots for both outcomes
+    pub const WSS_MARKET_BOOK: &str = include_str!("../tests/fixtures/wss/market_book.json");
+    pub const WSS_PRICE_CHANGE: &str = include_str!("../tests/fixtures/wss/price_change.json");
+    /// `tick_size_change` and `last_trade_price` events
+    pub const WSS_MARKET_MISC: &str = include_str!("../tests/fixtures/wss/market_misc.json");
+    pub const WSS_USER_EVENTS: &str = include_str!("../tests/fixtures/wss/user_events.json");
+}
+
+/// Local HTTP server serving the CLOB, Gamma and data API from fixtures
+///
+/// One server plays all three hosts, so paths must not collide; they don't for the
+/// routes preloaded here. Preloaded routes never fail `assert`, and a mock added later
+/// for the same request takes precedence over them.
+pub struct MockExchange {
+    server: ServerGuard,
+}
+
+impl MockExchange {
+    /// Start a server with the fixture routes loaded
+    pub async fn start() -> Self {
+        let mut exchange = Self::empty().await;
+        exchange.load_fixtures().await;
+        exchange
+    }
+
+    /// Start a server with no routes; every request gets a 501 until mocks are added
+    pub async fn empty() -> Self {
+        Self {
+            server: Server::new_async().await,
+        }
+    }
+
+    /// Base URL of the server
+    pub fn url(&self) -> String {
+        self.server.url()
+    }
+
+    /// The underlying mockito server, for adding routes and asserting on them
+    pub fn server(&mut self) -> &mut ServerGuard {
+        &mut self.server
+    }
+
+    /// Add a route, as [`ServerGuard::mock`] does on [`server`](Self::server)
+    pub fn mock<P: Into<Matcher>>(&mut self, method: &str, path: P) -> mockito::Mock {
+        self.server.mock(method, path)
+    }
+
+    /// A client whose CLOB, Gamma and data API requests all go to this server
+    pub fn clob_client(&self) -> ClobClient {
+        let url = self.url();
+        ClobClient::new(&url)
+            .with_gamma_base(&url)
+            .with_data_api_base(&url)
+    }
+
+    async fn load_fixtures(&mut self) {
+        let book: Value = serde_json::from_str(fixtures::CLOB_BOOK).unwrap_or(Value::Null);
+        let token_query = || Matcher::Regex("token_id=".to_string());
+        let routes: [(&str, &str, Matcher, String); 7] = [
+            ("GET", "/ok", Matcher::Any, "\"OK\"".to_string()),
+            ("GET", "/time", Matcher::Any, "1760000001".to_string()),
+            (
+                "GET",
+                "/markets",
+                Matcher::Any,
+                fixtures::GAMMA_MARKETS.to_string(),
+            ),
+            (
+                "GET",
+                "/book",
+                token_query(),
+                fixtures::CLOB_BOOK.to_string(),
+            ),
+            (
+                "GET",
+                "/midpoint",
+                token_query(),
+                serde_json::json!({"mid": "0.145"}).to_string(),
+            ),
+            (
+                "GET",
+                "/tick-size",
+                token_query(),
+                serde_json::json!({"minimum_tick_size": book["tick_size"]}).to_string(),
+            ),
+            (
+                "GET",
+                "/neg-risk",
+                token_query(),
+                serde_json::json!({"neg_risk": book["neg_risk"]}).to_string(),
+            ),
+        ];
+        for (method, path, query, body) in routes {
+            self.server
+                .mock(method, path)
+                .match_query(query)
+                .with_header("content-type", "application/json")
+                .with_body(body)
+                .expect_at_least(0)
+                .create_async()
+                .await;
+        }
+    }
+}
+
+/// One thing the feed does on a connection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FeedStep {
+    /// Send a text frame as-is
+    Frame(String),
+    /// Wait before the next step
+    Delay(Duration),
+    /// Close the socket; following steps play on the client's next connection
+    Disconnect,
+}
+
+/// Ordered steps for a [`MockMarketFeed`]
+///
+/// Playback starts once the client's subscription message arrives. After the last
+/// step the connection stays open and idle.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeedScript {
+    steps: Vec<FeedStep>,
+}
+
+impl FeedScript {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn send(mut self, frame: impl Into<String>) -> Self {
+        self.steps.push(FeedStep::Frame(frame.into()));
+        self
+    }
+
+    /// Send `value` serialized as one frame
+    pub fn send_json(self, value: &Value) -> Self {
+        self.send(value.to_string())
+    }
+
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.steps.push(FeedStep::Delay(delay));
+        self
+    }
+
+    pub fn disconnect(mut self) -> Self {
+        self.steps.push(FeedStep::Disconnect);
+        self
+    }
+
+    pub fn steps(&self) -> &[FeedStep] {
+        &self.steps
+    }
+
+    /// Steps per connection, split at each disconnect
+    fn connections(&self) -> Vec<Vec<FeedStep>> {
+        let mut connections = vec![Vec::new()];
+        for step in &self.steps {
+            match step {
+                FeedStep::Disconnect => connections.push(Vec::new()),
+                other => connections.last_mut().unwrap().push(other.clone()),
+            }
+        }
+        connections
+    }
+}
+
+/// Local websocket server playing a [`FeedScript`] to market channel clients
+///
+/// The server stops when this value is dropped.
+pub struct MockMarketFeed {
+    url: String,
+    subscriptions: Arc<Mutex<Vec<Value>>>,
+    task: JoinHandle<()>,
+}
+
+impl MockMarketFeed {
+    /// Bind to a free local port and start serving `script`
+    pub async fn start(script: FeedScript) -> Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .map_err(|e| PolyError::internal("Failed to bind mock market feed", e))?;
+        let addr = listener
+            .local_addr()
+            .map_err(|e| PolyError::internal("Failed to read mock feed address", e))?;
+        let subscriptions = Arc::new(Mutex::new(Vec::new()));
+        let task = tokio::spawn(serve(listener, script.connections(), subscriptions.clone()));
+        Ok(Self {
+            url: format!("ws://{}", addr),
+            subscriptions,
+            task,
+        })
+    }
+
+    /// Base URL to hand to websocket clients
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// A market channel client connected to this feed
+    pub fn market_client(&self) -> WssMarketClient {
+        WssMarketClient::with_url(&self.url)
+    }
+
+    /// Subscription messages received so far, one per connection
+    pub fn subscriptions(&self) -> Vec<Value> {
+        self.subscriptions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+impl Drop for MockMarketFeed {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn serve(
+    listener: TcpListener,
+    connections: Vec<Vec<FeedStep>>,
+    subscriptions: Arc<Mutex<Vec<Value>>>,
+) {
+    let mut connections = connections.into_iter();
+    loop {
+        let Ok((stream, _)) = listener.accept().await else {
+            return;
+        };
+        let Ok(mut socket) = tokio_tungstenite::accept_async(stream).await else {
+            continue;
+        };
+        let Some(Ok(Message::Text(subscription))) = socket.next().await else {
+            continue;
+        };
+        subscriptions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(serde_json::from_str(&subscription).unwrap_or(Value::Null));
+
+        let steps = connections.next();
+        let last = steps.is_none() || connections.len() == 0;
+        for step in steps.unwrap_or_default() {
+            match step {
+                FeedStep::Frame(frame) => {
+                    if socket.send(Message::Text(frame.into())).await.is_err() {
+                        break;
+                    }
+                }
+                FeedStep::Delay(delay) => tokio::time::sleep(delay).await,
+                FeedStep::Disconnect => unreachable!("scripts are split at disconnects"),
+            }
+        }
+        if last {
+            // Idle until the client goes away, then wait for any reconnect
+            while let Some(Ok(_)) = socket.next().await {}
+        } else {
+            let _ = socket.close(None).await;
+        }
+    }
+}
@@ -0,0 +1,901 @@
+//! Local shadow of open orders with reconciliation against the exchange
+//!
+//! `OrderTracker` keeps a per-order state machine fed by every source of truth a bot
+//! typically has: post-order responses, cancel confirmations, user-channel events, and
+//! periodic `get_orders` snapshots. When two sources disagree it never silently picks a
+//! winner - it emits a [`ReconciliationEvent`] and leaves the local view untouched so the
+//! caller can decide (re-query, cancel, adopt, alert...).
+//!
+//! Every state or fill change is also reported as an [`OrderChange`] to the callback set
+//! with [`OrderTracker::on_change`], so a strategy can react to fills without diffing
+//! the tracker itself.
+
+use crate::client::OrderArgs;
+use crate::errors::Result;
+use crate::types::{OpenOrder, Side};
+use crate::wss::{EventTiming, WssUserEvent, WssUserOrderMessage, WssUserTradeMessage};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Lifecycle of a tracked order
+///
+/// Transitions only move forward:
+/// `Pending → Live → PartiallyFilled → Filled`, with `Canceled`/`Expired` reachable
+/// from any non-terminal state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OrderState {
+    /// Submitted, not yet confirmed resting on the book
+    Pending,
+    /// Resting on the book with nothing filled
+    Live,
+    /// Resting on the book with some size matched
+    PartiallyFilled,
+    Filled,
+    Canceled,
+    Expired,
+}
+
+impl OrderState {
+    /// Terminal states never transition again
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            OrderState::Filled | OrderState::Canceled | OrderState::Expired
+        )
+    }
+}
+
+/// Where a piece of order information came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateSource {
+    CancelResponse,
+    UserChannel,
+    Snapshot,
+}
+
+/// Inconsistencies between the local shadow and what the exchange reports
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReconciliationEvent {
+    /// The exchange reports an order we are not tracking
+    UnknownOrder {
+        order_id: String,
+        source: UpdateSource,
+    },
+    /// We believe the order is open but the exchange no longer lists it
+    MissingOnServer {
+        order_id: String,
+        local_state: OrderState,
+    },
+    /// Both sides know the order but disagree on its state
+    StateMismatch {
+        order_id: String,
+        local: OrderState,
+        remote: OrderState,
+        source: UpdateSource,
+    },
+    /// Both sides know the order but disagree on how much has been matched
+    FillMismatch {
+        order_id: String,
+        local_matched: Decimal,
+        remote_matched: Decimal,
+        source: UpdateSource,
+    },
+    /// A cancel request was refused by the exchange
+    CancelRejected { order_id: String, reason: String },
+}
+
+/// Local view of a single order
+#[derive(Debug, Clone)]
+pub struct TrackedOrder {
+    pub order_id: String,
+    pub asset_id: String,
+    pub side: Side,
+    pub price: Decimal,
+    pub original_size: Decimal,
+    /// Matched size: the larger of the exchange's last cumulative figure and the sum of
+    /// the fills seen, so a fill reported both ways is only counted once
+    pub size_matched: Decimal,
+    pub state: OrderState,
+    /// Unix seconds after which a GTD order expires (`None` = good till cancel)
+    pub expiration: Option<u64>,
+    /// When the order was submitted, or created on the exchange for adopted orders
+    pub submitted_at: DateTime<Utc>,
+    /// Last state or fill change; the server's time for user-channel updates
+    pub updated_at: DateTime<Utc>,
+    /// When the order reached a terminal state
+    pub closed_at: Option<DateTime<Utc>>,
+    /// Fill ids already counted, so replays of the same trade don't double count
+    applied_trades: HashSet<String>,
+    /// Sum of the fills in `applied_trades`
+    traded: Decimal,
+    /// Cumulative matched size from the last order update or snapshot
+    reported_matched: Decimal,
+}
+
+impl TrackedOrder {
+    /// Size still open on the book
+    pub fn remaining_size(&self) -> Decimal {
+        (self.original_size - self.size_matched).max(Decimal::ZERO)
+    }
+
+    /// Recompute `size_matched` from the two fill sources
+    fn sync_matched(&mut self) {
+        self.size_matched = self.traded.max(self.reported_matched);
+    }
+
+    fn snapshot(&self) -> (OrderState, Decimal) {
+        (self.state, self.size_matched)
+    }
+
+    /// State implied by the matched size for an order that is still on the book
+    fn resting_state(&self) -> OrderState {
+        if self.size_matched >= self.original_size {
+            OrderState::Filled
+        } else if self.size_matched > Decimal::ZERO {
+            OrderState::PartiallyFilled
+        } else {
+            OrderState::Live
+        }
+    }
+}
+
+/// A state or fill change on a tracked order, as passed to [`OrderTracker::on_change`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderChange {
+    pub order_id: String,
+    pub previous_state: OrderState,
+    pub state: OrderState,
+    pub previous_matched: Decimal,
+    pub size_matched: Decimal,
+    pub at: DateTime<Utc>,
+}
+
+impl OrderChange {
+    /// Size matched by this change alone
+    pub fn filled_delta(&self) -> Decimal {
+        self.size_matched - self.previous_matched
+    }
+}
+
+type ChangeCallback = Box<dyn FnMut(&OrderChange) + Send>;
+
+/// Per-order state machine fed by REST responses, user-channel events, and snapshots
+#[derive(Default)]
+pub struct OrderTracker {
+    orders: HashMap<String, TrackedOrder>,
+    on_change: Option<ChangeCallback>,
+}
+
+impl fmt::Debug for OrderTracker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OrderTracker")
+            .field("orders", &self.orders)
+            .field("on_change", &self.on_change.is_some())
+            .finish()
+    }
+}
+
+impl OrderTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Call `callback` after every state or fill change, replacing any earlier callback
+    ///
+    /// It runs inside the ingesting call, so it should hand work off rather than block.
+    pub fn on_change(&mut self, callback: impl FnMut(&OrderChange) + Send + 'static) {
+        self.on_change = Some(Box::new(callback));
+    }
+
+    /// Start tracking an order we just submitted
+    pub fn track_submission(
+        &mut self,
+        order_id: impl Into<String>,
+        args: &OrderArgs,
+        expiration: Option<u64>,
+    ) -> &TrackedOrder {
+        let order_id = order_id.into();
+        let now = Utc::now();
+        self.orders
+            .entry(order_id.clone())
+            .or_insert_with(|| TrackedOrder {
+                order_id,
+                asset_id: args.token_id.clone(),
+                side: args.side,
+                price: args.price,
+                original_size: args.size,
+                size_matched: Decimal::ZERO,
+                state: OrderState::Pending,
+                expiration: expiration.filter(|ts| *ts > 0),
+                submitted_at: now,
+                updated_at: now,
+                closed_at: None,
+                applied_trades: HashSet::new(),
+                traded: Decimal::ZERO,
+                reported_matched: Decimal::ZERO,
+            })
+    }
+
+    /// Ingest the JSON returned by `post_order` for the order built from `args`.
+    ///
+    /// Returns the exchange order id. `"live"` responses promote the order straight to
+    /// `Live`; anything else (`"matched"`, `"delayed"`, ...) stays `Pending` until the user
+    /// channel or a snapshot tells us how much actually filled.
+    pub fn record_post_response(
+        &mut self,
+        args: &OrderArgs,
+        expiration: Option<u64>,
+        response: &Value,
+    ) -> Result<String> {
+        let order_id = crate::client::accepted_order_id(response)?;
+
+        let before = self
+            .track_submission(order_id.clone(), args, expiration)
+            .snapshot();
+        let status = response.get("status").and_then(Value::as_str);
+        if status.is_some_and(|s| s.eq_ignore_ascii_case("live"))
+            && let Some(order) = self.orders.get_mut(&order_id)
+            && order.state == OrderState::Pending
+        {
+            order.state = OrderState::Live;
+            self.record_change(&order_id, before, None);
+        }
+
+        Ok(order_id)
+    }
+
+    /// Ingest the JSON returned by `cancel` / `cancel_orders` / `cancel_all`
+    pub fn record_cancel_response(&mut self, response: &Value) -> Vec<ReconciliationEvent> {
+        let mut events = Vec::new();
+
+        let canceled = response
+            .get("canceled")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str);
+        for order_id in canceled {
+            let before = self.orders.get(order_id).map(TrackedOrder::snapshot);
+            self.transition(
+                order_id,
+                OrderState::Canceled,
+                UpdateSource::CancelResponse,
+                &mut events,
+            );
+            if let Some(before) = before {
+                self.record_change(order_id, before, None);
+            }
+        }
+
+        if let Some(rejected) = response.get("not_canceled").and_then(Value::as_object) {
+            for (order_id, reason) in rejected {
+                events.push(ReconciliationEvent::CancelRejected {
+                    order_id: order_id.clone(),
+                    reason: reason
+                        .as_str()
+                        .map(str::to_string)
+                        .unwrap_or_else(|| reason.to_string()),
+                });
+            }
+        }
+
+        events
+    }
+
+    /// Ingest an event from the authenticated user channel
+    pub fn apply_user_event(&mut self, event: &WssUserEvent) -> Vec<ReconciliationEvent> {
+        let affected: Vec<&str> = match event {
+            WssUserEvent::Order(order) => vec![order.id.as_str()],
+            WssUserEvent::Trade(trade) => trade
+                .maker_orders
+                .iter()
+                .map(|maker| maker.order_id.as_str())
+                .chain(Some(trade.taker_order_id.as_str()))
+                .collect(),
+        };
+        let mut before: Vec<_> = affected
+            .into_iter()
+            .filter_map(|id| Some((id, self.orders.get(id)?.snapshot())))
+            .collect();
+        before.sort_unstable_by_key(|(id, _)| *id);
+        before.dedup_by_key(|(id, _)| *id);
+
+        let events = match event {
+            WssUserEvent::Order(order) => self.apply_order_message(order),
+            WssUserEvent::Trade(trade) => self.apply_trade_message(trade),
+        };
+
+        let at = event.server_time();
+        for (order_id, before) in before {
+            self.record_change(order_id, before, at);
+        }
+        events
+    }
+
+    /// Compare the local shadow with a full `get_orders` snapshot.
+    ///
+    /// The snapshot must cover every market the tracker knows about; orders missing
+    /// from a partial snapshot would be reported as `MissingOnServer`. GTD orders whose
+    /// expiration has passed (per `now_secs`) are moved to `Expired` instead.
+    pub fn reconcile_snapshot(
+        &mut self,
+        open_orders: &[OpenOrder],
+        now_secs: u64,
+    ) -> Vec<ReconciliationEvent> {
+        let mut events = Vec::new();
+        let mut seen = HashSet::with_capacity(open_orders.len());
+        let mut changed = Vec::new();
+
+        for remote in open_orders {
+            seen.insert(remote.id.as_str());
+
+            let Some(local) = self.orders.get_mut(&remote.id) else {
+                events.push(ReconciliationEvent::UnknownOrder {
+                    order_id: remote.id.clone(),
+                    source: UpdateSource::Snapshot,
+                });
+                continue;
+            };
+
+            if local.state.is_terminal() {
+                events.push(ReconciliationEvent::StateMismatch {
+                    order_id: remote.id.clone(),
+                    local: local.state,
+                    remote: OrderState::Live,
+                    source: UpdateSource::Snapshot,
+                });
+                continue;
+            }
+
+            if remote.size_matched != local.size_matched {
+                events.push(ReconciliationEvent::FillMismatch {
+                    order_id: remote.id.clone(),
+                    local_matched: local.size_matched,
+                    remote_matched: remote.size_matched,
+                    source: UpdateSource::Snapshot,
+                });
+            } else if local.state == OrderState::Pending {
+                // Consistent, just confirming the order made it onto the book
+                changed.push((remote.id.clone(), local.snapshot()));
+                local.state = local.resting_state();
+            }
+        }
+
+        for order in self.orders.values_mut() {
+            if order.state.is_terminal() || seen.contains(order.order_id.as_str()) {
+                continue;
+            }
+
+            if order.expiration.is_some_and(|ts| ts <= now_secs) {
+                changed.push((order.order_id.clone(), order.snapshot()));
+                order.state = OrderState::Expired;
+            } else {
+                events.push(ReconciliationEvent::MissingOnServer {
+                    order_id: order.order_id.clone(),
+                    local_state: order.state,
+                });
+            }
+        }
+
+        for (order_id, before) in changed {
+            self.record_change(&order_id, before, None);
+        }
+        events
+    }
+
+    /// Explicitly start tracking an order the exchange reported, e.g. after an
+    /// `UnknownOrder` event the caller decided to trust
+    pub fn adopt(&mut self, remote: &OpenOrder) -> &TrackedOrder {
+        let now = Utc::now();
+        let mut order = TrackedOrder {
+            order_id: remote.id.clone(),
+            asset_id: remote.asset_id.clone(),
+            side: remote.side,
+            price: remote.price,
+            original_size: remote.original_size,
+            size_matched: remote.size_matched,
+            state: OrderState::Live,
+            expiration: Some(remote.expiration).filter(|ts| *ts > 0),
+            submitted_at: DateTime::from_timestamp(remote.created_at as i64, 0)
+                .filter(|_| remote.created_at > 0)
+                .unwrap_or(now),
+            updated_at: now,
+            closed_at: None,
+            applied_trades: HashSet::new(),
+            traded: Decimal::ZERO,
+            reported_matched: remote.size_matched,
+        };
+        order.state = order.resting_state();
+        self.orders.insert(remote.id.clone(), order);
+        &self.orders[&remote.id]
+    }
+
+    /// Stop tracking an order; returns the last known local view
+    pub fn forget(&mut self, order_id: &str) -> Option<TrackedOrder> {
+        self.orders.remove(order_id)
+    }
+
+    /// Drop every order in a terminal state
+    pub fn prune_terminal(&mut self) -> usize {
+        let before = self.orders.len();
+        self.orders.retain(|_, order| !order.state.is_terminal());
+        before - self.orders.len()
+    }
+
+    pub fn get(&self, order_id: &str) -> Option<&TrackedOrder> {
+        self.orders.get(order_id)
+    }
+
+    /// Orders that are not in a terminal state
+    pub fn open_orders(&self) -> impl Iterator<Item = &TrackedOrder> {
+        self.orders
+            .values()
+            .filter(|order| !order.state.is_terminal())
+    }
+
+    pub fn orders_in_state(&self, state: OrderState) -> impl Iterator<Item = &TrackedOrder> {
+        self.orders
+            .values()
+            .filter(move |order| order.state == state)
+    }
+
+    /// Every tracked order on `asset_id`, open or not
+    pub fn orders_for_asset<'a>(
+        &'a self,
+        asset_id: &'a str,
+    ) -> impl Iterator<Item = &'a TrackedOrder> + 'a {
+        self.orders
+            .values()
+            .filter(move |order| order.asset_id == asset_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.orders.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.orders.is_empty()
+    }
+
+    fn apply_order_message(&mut self, msg: &WssUserOrderMessage) -> Vec<ReconciliationEvent> {
+        let mut events = Vec::new();
+        let Some(order) = self.orders.get_mut(&msg.id) else {
+            events.push(ReconciliationEvent::UnknownOrder {
+                order_id: msg.id.clone(),
+                source: UpdateSource::UserChannel,
+            });
+            return events;
+        };
+
+        match msg.message_type.to_ascii_uppercase().as_str() {
+            "CANCELLATION" => {
+                self.transition(
+                    &msg.id,
+                    OrderState::Canceled,
+                    UpdateSource::UserChannel,
+                    &mut events,
+                );
+            }
+            // PLACEMENT and UPDATE both carry the cumulative matched size. It may
+            // already include fills we counted from trades, or lag behind them, so it
+            // only has to keep up with the previous update.
+            _ => {
+                if msg.size_matched < order.reported_matched {
+                    events.push(ReconciliationEvent::FillMismatch {
+                        order_id: msg.id.clone(),
+                        local_matched: order.size_matched,
+                        remote_matched: msg.size_matched,
+                        source: UpdateSource::UserChannel,
+                    });
+                    return events;
+                }
+
+                order.reported_matched = msg.size_matched;
+                order.sync_matched();
+                let next = order.resting_state();
+                self.transition(&msg.id, next, UpdateSource::UserChannel, &mut events);
+            }
+        }
+
+        events
+    }
+
+    fn apply_trade_message(&mut self, msg: &WssUserTradeMessage) -> Vec<ReconciliationEvent> {
+        let mut events = Vec::new();
+
+        // Trades are re-broadcast as they move MATCHED → MINED → CONFIRMED; only a
+        // FAILED trade would undo a fill, and that surfaces through order updates.
+        if msg.status.eq_ignore_ascii_case("FAILED") {
+            return events;
+        }
+
+        let mut fills: Vec<(&str, Decimal)> = msg
+            .maker_orders
+            .iter()
+            .map(|maker| (maker.order_id.as_str(), maker.matched_amount))
+            .collect();
+        if !msg.taker_order_id.is_empty() {
+            fills.push((msg.taker_order_id.as_str(), msg.size));
+        }
+
+        for (order_id, amount) in fills {
+            // Maker legs belong to other users unless we track them
+            let Some(order) = self.orders.get_mut(order_id) else {
+                continue;
+            };
+            if !order.applied_trades.insert(msg.id.clone()) {
+                continue;
+            }
+
+            order.traded += amount;
+            if order.traded > order.original_size {
+                events.push(ReconciliationEvent::FillMismatch {
+                    order_id: order_id.to_string(),
+                    local_matched: order.traded,
+                    remote_matched: order.original_size,
+                    source: UpdateSource::UserChannel,
+                });
+                order.traded = order.original_size;
+            }
+            order.sync_matched();
+
+            let next = order.resting_state();
+            self.transition(order_id, next, UpdateSource::UserChannel, &mut events);
+        }
+
+        events
+    }
+
+    /// Stamp an order and notify the callback if it moved on from `before`
+    fn record_change(
+        &mut self,
+        order_id: &str,
+        (previous_state, previous_matched): (OrderState, Decimal),
+        at: Option<DateTime<Utc>>,
+    ) {
+        let Some(order) = self.orders.get_mut(order_id) else {
+            return;
+        };
+        if order.state == previous_state && order.size_matched == previous_matched {
+            return;
+        }
+
+        let at = at.unwrap_or_else(Utc::now);
+        order.updated_at = at;
+        if order.state.is_terminal() && order.closed_at.is_none() {
+            order.closed_at = Some(at);
+        }
+        if let Some(callback) = self.on_change.as_mut() {
+            callback(&OrderChange {
+                order_id: order_id.to_string(),
+                previous_state,
+                state: order.state,
+                previous_matched,
+                size_matched: order.size_matched,
+                at,
+            });
+        }
+    }
+
+    /// Move an order forward, reporting attempts to leave a terminal state
+    fn transition(
+        &mut self,
+        order_id: &str,
+        next: OrderState,
+        source: UpdateSource,
+        events: &mut Vec<ReconciliationEvent>,
+    ) {
+        let Some(order) = self.orders.get_mut(order_id) else {
+            events.push(ReconciliationEvent::UnknownOrder {
+                order_id: order_id.to_string(),
+                source,
+            });
+            return;
+        };
+
+        if order.state == next {
+            return;
+        }
+        if order.state.is_terminal() {
+            events.push(ReconciliationEvent::StateMismatch {
+                order_id: order_id.to_string(),
+                local: order.state,
+                remote: next,
+                source,
+            });
+            return;
+        }
+
+        order.state = next;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::OrderType;
+    use rust_decimal_macros::dec;
+    use serde_json::json;
+
+    fn args() -> OrderArgs {
+        OrderArgs::new("token-1", dec!(0.45), dec!(100), Side::BUY)
+    }
+
+    fn open_order(id: &str, size_matched: Decimal) -> OpenOrder {
+        OpenOrder {
+            associate_trades: Vec::new(),
+            id: id.to_string(),
+            status: "LIVE".to_string(),
+            market: "0xmarket".to_string(),
+            original_size: dec!(100),
+            outcome: "Yes".to_string(),
+            maker_address: "0xmaker".to_string(),
+            owner: "owner".to_string(),
+            price: dec!(0.45),
+            side: Side::BUY,
+            size_matched,
+            asset_id: "token-1".to_string(),
+            expiration: 0,
+            order_type: OrderType::GTC,
+            created_at: 0,
+        }
+    }
+
+    fn order_update(id: &str, kind: &str, size_matched: Decimal) -> WssUserEvent {
+        WssUserEvent::Order(
+            serde_json::from_value(json!({
+                "event_type": "order",
+                "asset_id": "token-1",
+                "id": id,
+                "market": "0xmarket",
+                "order_owner": "owner",
+                "original_size": "100",
+                "outcome": "Yes",
+                "owner": "owner",
+                "price": "0.45",
+                "side": "BUY",
+                "size_matched": size_matched.to_string(),
+                "timestamp": "1700000000",
+                "type": kind,
+            }))
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_post_response_lifecycle() {
+        let mut tracker = OrderTracker::new();
+        let id = tracker
+            .record_post_response(
+                &args(),
+                None,
+                &json!({"success": true, "orderID": "0xabc", "status": "live"}),
+            )
+            .unwrap();
+        assert_eq!(tracker.get(&id).unwrap().state, OrderState::Live);
+
+        let events = tracker.apply_user_event(&order_update("0xabc", "UPDATE", dec!(40)));
+        assert!(events.is_empty());
+        let order = tracker.get("0xabc").unwrap();
+        assert_eq!(order.state, OrderState::PartiallyFilled);
+        assert_eq!(order.remaining_size(), dec!(60));
+
+        tracker.apply_user_event(&order_update("0xabc", "UPDATE", dec!(100)));
+        assert_eq!(tracker.get("0xabc").unwrap().state, OrderState::Filled);
+    }
+
+    #[test]
+    fn test_change_callback_follows_fills() {
+        use std::sync::{Arc, Mutex};
+
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        let mut tracker = OrderTracker::new();
+        let sink = changes.clone();
+        tracker.on_change(move |change| sink.lock().unwrap().push(change.clone()));
+
+        tracker.track_submission("0xabc", &args(), None);
+        tracker.reconcile_snapshot(&[open_order("0xabc", Decimal::ZERO)], 0);
+        tracker.apply_user_event(&order_update("0xabc", "UPDATE", dec!(40)));
+        // A repeated update changes nothing and is not reported
+        tracker.apply_user_event(&order_update("0xabc", "UPDATE", dec!(40)));
+        tracker.apply_user_event(&order_update("0xabc", "UPDATE", dec!(70)));
+        tracker.apply_user_event(&order_update("0xabc", "UPDATE", dec!(100)));
+
+        let changes = changes.lock().unwrap();
+        let transitions: Vec<_> = changes
+            .iter()
+            .map(|c| (c.previous_state, c.state, c.filled_delta()))
+            .collect();
+        assert_eq!(
+            transitions,
+            vec![
+                (OrderState::Pending, OrderState::Live, dec!(0)),
+                (OrderState::Live, OrderState::PartiallyFilled, dec!(40)),
+                (
+                    OrderState::PartiallyFilled,
+                    OrderState::PartiallyFilled,
+                    dec!(30)
+                ),
+                (OrderState::PartiallyFilled, OrderState::Filled, dec!(30)),
+            ]
+        );
+
+        let order = tracker.get("0xabc").unwrap();
+        let server_time = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        assert_eq!(changes[3].at, server_time);
+        assert_eq!(order.updated_at, server_time);
+        assert_eq!(order.closed_at, Some(server_time));
+        assert_eq!(tracker.orders_in_state(OrderState::Filled).count(), 1);
+        assert_eq!(tracker.orders_for_asset("token-1").count(), 1);
+    }
+
+    #[test]
+    fn test_rejected_post_is_an_error() {
+        let mut tracker = OrderTracker::new();
+        let result = tracker.record_post_response(
+            &args(),
+            None,
+            &json!({"success": false, "errorMsg": "not enough balance"}),
+        );
+        assert!(result.is_err());
+        assert!(tracker.is_empty());
+    }
+
+    #[test]
+    fn test_cancel_response() {
+        let mut tracker = OrderTracker::new();
+        tracker.track_submission("a", &args(), None);
+        tracker.track_submission("b", &args(), None);
+
+        let events = tracker.record_cancel_response(&json!({
+            "canceled": ["a", "ghost"],
+            "not_canceled": {"b": "order already matched"}
+        }));
+
+        assert_eq!(tracker.get("a").unwrap().state, OrderState::Canceled);
+        assert_eq!(tracker.get("b").unwrap().state, OrderState::Pending);
+        assert!(events.contains(&ReconciliationEvent::UnknownOrder {
+            order_id: "ghost".to_string(),
+            source: UpdateSource::CancelResponse,
+        }));
+        assert!(events.contains(&ReconciliationEvent::CancelRejected {
+            order_id: "b".to_string(),
+            reason: "order already matched".to_string(),
+        }));
+    }
+
+    #[test]
+    fn test_snapshot_reconciliation() {
+        let mut tracker = OrderTracker::new();
+        tracker.track_submission("pending", &args(), None);
+        tracker.track_submission("vanished", &args(), None);
+        tracker.track_submission("expired", &args(), Some(1_000));
+        tracker.track_submission("drifted", &args(), None);
+
+        let snapshot = vec![
+            open_order("pending", Decimal::ZERO),
+            open_order("drifted", dec!(25)),
+            open_order("stranger", Decimal::ZERO),
+        ];
+        let events = tracker.reconcile_snapshot(&snapshot, 2_000);
+
+        assert_eq!(tracker.get("pending").unwrap().state, OrderState::Live);
+        assert_eq!(tracker.get("expired").unwrap().state, OrderState::Expired);
+        // Disagreement is reported, not adopted
+        assert_eq!(tracker.get("drifted").unwrap().size_matched, Decimal::ZERO);
+
+        assert_eq!(events.len(), 3);
+        assert!(events.contains(&ReconciliationEvent::UnknownOrder {
+            order_id: "stranger".to_string(),
+            source: UpdateSource::Snapshot,
+        }));
+        assert!(events.contains(&ReconciliationEvent::MissingOnServer {
+            order_id: "vanished".to_string(),
+            local_state: OrderState::Pending,
+        }));
+        assert!(events.contains(&ReconciliationEvent::FillMismatch {
+            order_id: "drifted".to_string(),
+            local_matched: Decimal::ZERO,
+            remote_matched: dec!(25),
+            source: UpdateSource::Snapshot,
+        }));
+    }
+
+    #[test]
+    fn test_terminal_orders_do_not_reopen() {
+        let mut tracker = OrderTracker::new();
+        tracker.track_submission("a", &args(), None);
+        tracker.record_cancel_response(&json!({"canceled": ["a"]}));
+
+        let events = tracker.apply_user_event(&order_update("a", "UPDATE", dec!(10)));
+        assert_eq!(tracker.get("a").unwrap().state, OrderState::Canceled);
+        assert!(matches!(
+            events.as_slice(),
+            [ReconciliationEvent::StateMismatch {
+                local: OrderState::Canceled,
+                remote: OrderState::PartiallyFilled,
+                ..
+            }]
+        ));
+    }
+
+    fn taker_trade(id: &str, status: &str, size: Decimal) -> WssUserEvent {
+        WssUserEvent::Trade(
+            serde_json::from_value(json!({
+                "event_type": "trade",
+                "asset_id": "token-1",
+                "id": id,
+                "last_update": "1700000000",
+                "maker_orders": [],
+                "market": "0xmarket",
+                "matchtime": "1700000000",
+                "outcome": "Yes",
+                "owner": "owner",
+                "price": "0.45",
+                "side": "BUY",
+                "size": size.to_string(),
+                "status": status,
+                "taker_order_id": "taker",
+                "timestamp": "1700000000",
+                "trade_owner": "owner",
+                "type": "TRADE",
+            }))
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_trade_replays_are_counted_once() {
+        let mut tracker = OrderTracker::new();
+        tracker.track_submission("taker", &args(), None);
+
+        for status in ["MATCHED", "MINED", "CONFIRMED"] {
+            tracker.apply_user_event(&taker_trade("trade-1", status, dec!(30)));
+        }
+
+        let order = tracker.get("taker").unwrap();
+        assert_eq!(order.size_matched, dec!(30));
+        assert_eq!(order.state, OrderState::PartiallyFilled);
+    }
+
+    #[test]
+    fn test_fill_reported_by_update_and_trade_is_counted_once() {
+        let mut tracker = OrderTracker::new();
+        tracker.track_submission("taker", &args(), None);
+
+        // The update's cumulative size already covers the trade that follows it
+        assert!(
+            tracker
+                .apply_user_event(&order_update("taker", "UPDATE", dec!(30)))
+                .is_empty()
+        );
+        assert!(
+            tracker
+                .apply_user_event(&taker_trade("trade-1", "MATCHED", dec!(30)))
+                .is_empty()
+        );
+        let order = tracker.get("taker").unwrap();
+        assert_eq!(order.size_matched, dec!(30));
+        assert_eq!(order.state, OrderState::PartiallyFilled);
+
+        // The other way round, and an update lagging behind a newer trade
+        tracker.apply_user_event(&taker_trade("trade-2", "MATCHED", dec!(20)));
+        assert!(
+            tracker
+                .apply_user_event(&order_update("taker", "UPDATE", dec!(50)))
+                .is_empty()
+        );
+        tracker.apply_user_event(&taker_trade("trade-3", "MATCHED", dec!(10)));
+        assert!(
+            tracker
+                .apply_user_event(&order_update("taker", "UPDATE", dec!(50)))
+                .is_empty()
+        );
+        let order = tracker.get("taker").unwrap();
+        assert_eq!(order.size_matched, dec!(60));
+        assert_eq!(order.state, OrderState::PartiallyFilled);
+    }
+}
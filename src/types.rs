@@ -3,6 +3,7 @@
 //! This module defines all the stable public types used throughout the client.
 //! These types are optimized for latency-sensitive trading environments.
 
+use crate::errors::PolyError;
 use alloy_primitives::{Address, U256};
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
@@ -73,6 +74,13 @@ pub const MIN_PRICE_TICKS: Price = 1;
 /// Maximum valid quantity (prevents overflow in calculations)
 pub const MAX_QTY: Qty = Qty::MAX / 2; // Leave room for intermediate calculations
 
+/// Minimum order size assumed when a market doesn't report one (5 shares, the CLOB's
+/// usual floor)
+pub const DEFAULT_MIN_ORDER_SIZE: Decimal = Decimal::from_parts(5, 0, 0, false, 0);
+
+/// Tick size assumed when a market doesn't report one ($0.01, the CLOB's coarsest tick)
+pub const DEFAULT_MIN_TICK_SIZE: Decimal = Decimal::from_parts(1, 0, 0, false, 2);
+
 // ============================================================================
 // CONVERSION FUNCTIONS BETWEEN DECIMAL AND FIXED-POINT
 // ============================================================================
@@ -208,6 +216,12 @@ impl Side {
     }
 }
 
+impl fmt::Display for Side {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Order type specifications
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum OrderType {
@@ -226,6 +240,12 @@ impl OrderType {
     }
 }
 
+impl fmt::Display for OrderType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Order status in the system
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum OrderStatus {
@@ -241,6 +261,25 @@ pub enum OrderStatus {
     Expired,
 }
 
+impl OrderStatus {
+    /// The status as the API writes it
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderStatus::Live => "LIVE",
+            OrderStatus::Cancelled => "CANCELLED",
+            OrderStatus::Filled => "FILLED",
+            OrderStatus::Partial => "PARTIAL",
+            OrderStatus::Expired => "EXPIRED",
+        }
+    }
+}
+
+impl fmt::Display for OrderStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Market snapshot representing current state
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MarketSnapshot {
@@ -261,9 +300,9 @@ pub struct MarketSnapshot {
 /// It uses Decimal for precision and human readability.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BookLevel {
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::serde_decimal")]
     pub price: Decimal,
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::serde_decimal")]
     pub size: Decimal,
 }
 
@@ -349,6 +388,17 @@ pub struct OrderDelta {
     pub sequence: u64,
 }
 
+/// A single price level update, as carried by `price_change` websocket events.
+///
+/// Unlike `OrderDelta` this carries no token or sequence - a batch of these is
+/// applied to one book as a unit via `OrderBook::apply_price_change_batch`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PriceChange {
+    pub side: Side,
+    pub price: Decimal,
+    pub size: Decimal, // 0 means remove level
+}
+
 /// Order book delta for streaming updates - INTERNAL HOT PATH VERSION
 ///
 /// This is what we use internally for processing order book updates.
@@ -380,10 +430,10 @@ impl FastOrderDelta {
         tick_size: Option<Decimal>,
     ) -> std::result::Result<Self, &'static str> {
         // Validate tick alignment if we have a tick size
-        if let Some(tick_size) = tick_size {
-            if !is_price_tick_aligned(delta.price, tick_size) {
-                return Err("Price not aligned to tick size");
-            }
+        if let Some(tick_size) = tick_size
+            && !is_price_tick_aligned(delta.price, tick_size)
+        {
+            return Err("Price not aligned to tick size");
         }
 
         // Convert to fixed-point with validation
@@ -466,6 +516,31 @@ pub struct MarketOrderRequest {
     pub client_id: Option<String>,
 }
 
+/// How [`ClobClient::place_order_with_retry`](crate::client::ClobClient::place_order_with_retry)
+/// retries a placement and confirms the exchange has it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Posts to try, counting the first; at least one is always made
+    pub max_attempts: u32,
+    /// Wait before each retry
+    pub backoff: std::time::Duration,
+    /// How long an accepted order may take to become visible to order lookups
+    pub confirmation_timeout: std::time::Duration,
+    /// Wait between order lookups while confirming
+    pub poll_interval: std::time::Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            backoff: std::time::Duration::from_millis(500),
+            confirmation_timeout: std::time::Duration::from_secs(5),
+            poll_interval: std::time::Duration::from_millis(250),
+        }
+    }
+}
+
 /// Order state in the system
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Order {
@@ -564,7 +639,54 @@ impl PostOrder {
     }
 }
 
+/// An order post exactly as it would go out, built without sending it
+///
+/// See [`ClobClient::post_order_dry_run`](crate::client::ClobClient::post_order_dry_run).
+#[derive(Debug, Clone)]
+pub struct SignedOrderPreview {
+    pub method: String,
+    pub url: String,
+    /// L2 auth headers, keyed by lowercase name
+    pub headers: std::collections::BTreeMap<String, String>,
+    /// JSON request body, byte for byte
+    pub body: String,
+    pub order: SignedOrderRequest,
+}
+
+fn default_min_order_size() -> Decimal {
+    DEFAULT_MIN_ORDER_SIZE
+}
+
+fn default_min_tick_size() -> Decimal {
+    DEFAULT_MIN_TICK_SIZE
+}
+
+/// Null, empty and zero sizes mean "not reported"
+fn deserialize_min_order_size<'de, D>(deserializer: D) -> std::result::Result<Decimal, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(crate::serde_decimal::option::deserialize(deserializer)?
+        .filter(|size| !size.is_zero())
+        .unwrap_or(DEFAULT_MIN_ORDER_SIZE))
+}
+
+/// Null, empty and zero ticks mean "not reported"
+fn deserialize_min_tick_size<'de, D>(deserializer: D) -> std::result::Result<Decimal, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(crate::serde_decimal::option::deserialize(deserializer)?
+        .filter(|tick| !tick.is_zero())
+        .unwrap_or(DEFAULT_MIN_TICK_SIZE))
+}
+
 /// Market information
+///
+/// Equality and hashing use `condition_id` alone, so snapshots of the same market taken
+/// at different times compare equal and dedupe in a `HashSet`. Use
+/// [`is_stale_version_of`](Market::is_stale_version_of) to tell whether two snapshots
+/// actually differ.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Market {
     pub condition_id: String,
@@ -572,63 +694,413 @@ pub struct Market {
     #[serde(default, skip)]
     pub clob_token_ids: Vec<String>,
     pub rewards: Rewards,
-    pub min_incentive_size: Option<String>,
-    pub max_incentive_spread: Option<String>,
+    #[serde(default, with = "crate::serde_decimal::option")]
+    pub min_incentive_size: Option<Decimal>,
+    /// Widest spread that still earns liquidity rewards, as the CLOB reports it
+    #[serde(default, with = "crate::serde_decimal::option")]
+    pub max_incentive_spread: Option<Decimal>,
     pub active: bool,
     pub closed: bool,
+    /// Whether the market settles through the neg-risk adapter
+    #[serde(default)]
+    pub neg_risk: bool,
+    /// ID shared by every market of the same neg-risk group
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub neg_risk_market_id: Option<String>,
     pub question_id: String,
-    #[serde(with = "rust_decimal::serde::str")]
+    /// Smallest accepted order size; [`DEFAULT_MIN_ORDER_SIZE`] when the market omits it
+    #[serde(
+        default = "default_min_order_size",
+        deserialize_with = "deserialize_min_order_size"
+    )]
     pub minimum_order_size: Decimal,
-    #[serde(with = "rust_decimal::serde::str")]
+    /// Price increment; [`DEFAULT_MIN_TICK_SIZE`] when the market omits it
+    #[serde(
+        default = "default_min_tick_size",
+        deserialize_with = "deserialize_min_tick_size"
+    )]
     pub minimum_tick_size: Decimal,
     pub description: String,
     pub category: Option<String>,
+    /// Gamma category ID, when the source reports one
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "crate::decode::deserializers::optional_number_from_string"
+    )]
+    pub category_id: Option<u32>,
     pub end_date_iso: Option<String>,
-    pub game_start_time: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "crate::decode::deserializers::optional_flexible_datetime"
+    )]
+    pub game_start_time: Option<DateTime<Utc>>,
+    /// Sports market flavour reported by Gamma (`moneyline`, `spreads`, `totals`, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sports_market_type: Option<String>,
+    /// Identifier of the underlying game, shared by every market on it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub game_id: Option<String>,
+    /// Spread or total line for `spreads`/`totals` markets
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::serde_decimal::option"
+    )]
+    pub line: Option<Decimal>,
     pub question: String,
     pub market_slug: String,
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::serde_decimal")]
     pub seconds_delay: Decimal,
     pub icon: String,
     pub fpmm: String,
     pub liquidity: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::serde_decimal::option"
+    )]
     pub liquidity_num: Option<Decimal>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::serde_decimal::option"
+    )]
     pub liquidity_amm: Option<Decimal>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::serde_decimal::option"
+    )]
     pub liquidity_clob: Option<Decimal>,
     pub volume: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::serde_decimal::option"
+    )]
     pub volume_num: Option<Decimal>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::serde_decimal::option"
+    )]
     pub volume_24hr: Option<Decimal>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::serde_decimal::option"
+    )]
     pub volume_1wk: Option<Decimal>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::serde_decimal::option"
+    )]
     pub volume_1mo: Option<Decimal>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::serde_decimal::option"
+    )]
     pub volume_1yr: Option<Decimal>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::serde_decimal::option"
+    )]
     pub volume_24hr_amm: Option<Decimal>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::serde_decimal::option"
+    )]
     pub volume_1wk_amm: Option<Decimal>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::serde_decimal::option"
+    )]
     pub volume_1mo_amm: Option<Decimal>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::serde_decimal::option"
+    )]
     pub volume_1yr_amm: Option<Decimal>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::serde_decimal::option"
+    )]
     pub volume_24hr_clob: Option<Decimal>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::serde_decimal::option"
+    )]
     pub volume_1wk_clob: Option<Decimal>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::serde_decimal::option"
+    )]
     pub volume_1mo_clob: Option<Decimal>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::serde_decimal::option"
+    )]
     pub volume_1yr_clob: Option<Decimal>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::serde_decimal::option"
+    )]
     pub volume_amm: Option<Decimal>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::serde_decimal::option"
+    )]
     pub volume_clob: Option<Decimal>,
+    /// Gap between the best ask and best bid, as reported by Gamma
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::serde_decimal::option"
+    )]
+    pub spread: Option<Decimal>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub banner_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category_icon_url: Option<String>,
+    /// Where the market resolves from, as Gamma's `resolutionSource`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolution_source: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl PartialEq for Market {
+    fn eq(&self, other: &Self) -> bool {
+        self.condition_id == other.condition_id
+    }
+}
+
+impl Eq for Market {}
+
+impl std::hash::Hash for Market {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.condition_id.hash(state);
+    }
+}
+
+impl Market {
+    /// Whether the market belongs to `category`
+    ///
+    /// Compares IDs when the market has one; otherwise falls back to matching the
+    /// free-form `category` against the category's name or slug, ignoring case.
+    pub fn matches_category(&self, category: &Category) -> bool {
+        if let Some(id) = self.category_id {
+            return id == category.id;
+        }
+        self.category.as_deref().is_some_and(|name| {
+            name.eq_ignore_ascii_case(&category.name) || name.eq_ignore_ascii_case(&category.slug)
+        })
+    }
+
+    /// Whether both values describe the same market; same as `==`
+    pub fn same_market(&self, other: &Market) -> bool {
+        self == other
+    }
+
+    /// Whether `self` and `other` are different snapshots of the same market.
+    ///
+    /// `==` only looks at `condition_id`, so this compares the full serialized contents
+    /// (prices, volumes, flags...) instead. Neither side is assumed to be newer.
+    pub fn is_stale_version_of(&self, other: &Market) -> bool {
+        self.same_market(other)
+            && serde_json::to_value(self).ok() != serde_json::to_value(other).ok()
+    }
+
+    /// Token IDs to subscribe to for this market
+    ///
+    /// Prefers `clob_token_ids` and falls back to the IDs in `tokens`; empty when the
+    /// market lists neither.
+    pub fn asset_ids(&self) -> Vec<String> {
+        if !self.clob_token_ids.is_empty() {
+            return self.clob_token_ids.clone();
+        }
+        self.tokens
+            .iter()
+            .map(|token| token.token_id.clone())
+            .filter(|id| !id.is_empty())
+            .collect()
+    }
+
+    /// Whether the market has an icon to show, either `icon_url` or the legacy `icon`
+    pub fn has_icon(&self) -> bool {
+        self.icon_url.as_deref().is_some_and(|url| !url.is_empty()) || !self.icon.is_empty()
+    }
+
+    /// The resolution source, if the market lists one
+    pub fn primary_source_url(&self) -> Option<&str> {
+        self.resolution_source.as_deref()
+    }
+
+    /// Whether the market is part of a neg-risk group
+    pub fn is_neg_risk(&self) -> bool {
+        self.neg_risk || self.neg_risk_market_id.is_some()
+    }
+
+    /// The other market of a two-market neg-risk group, given the group's markets
+    ///
+    /// `group` is typically the result of
+    /// [`get_neg_risk_siblings`](crate::client::ClobClient::get_neg_risk_siblings); only
+    /// the markets sharing this one's `neg_risk_market_id` count. In a two-market group
+    /// exactly one resolves Yes, so each market's Yes token is the other's No. `None`
+    /// for markets outside a neg-risk group, and for groups with more than one sibling,
+    /// where no single market is the complement.
+    pub fn complement_condition_id<'a>(&self, group: &'a [Market]) -> Option<&'a str> {
+        let group_id = self.neg_risk_market_id.as_deref()?;
+        let mut siblings = group.iter().filter(|market| {
+            market.condition_id != self.condition_id
+                && market.neg_risk_market_id.as_deref() == Some(group_id)
+        });
+        match (siblings.next(), siblings.next()) {
+            (Some(other), None) => Some(&other.condition_id),
+            _ => None,
+        }
+    }
+
+    /// The one-line [`Display`](fmt::Display) form, as a method
+    pub fn to_display_string(&self) -> String {
+        self.to_string()
+    }
+
+    /// A multi-line summary listing each outcome and its token, for logs and terminals
+    pub fn to_rich_string(&self) -> String {
+        let mut out = format!(
+            "{}\n  condition: {}\n  slug:      {}\n  status:    {}\n  ends:      {}\n  liquidity: {}\n  volume:    {}\n  tick:      {}  min size: {}\n  outcomes:",
+            or_dash(Some(self.question.as_str()).filter(|question| !question.is_empty())),
+            self.condition_id,
+            or_dash(Some(self.market_slug.as_str()).filter(|slug| !slug.is_empty())),
+            self.status_label(),
+            or_dash(self.end_date_iso.as_deref()),
+            whole_dollars(self.liquidity_num),
+            whole_dollars(self.volume_num),
+            self.minimum_tick_size.normalize(),
+            self.minimum_order_size.normalize(),
+        );
+        for token in &self.tokens {
+            out.push_str(&format!("\n    {}", token));
+        }
+        out
+    }
+
+    fn status_label(&self) -> &'static str {
+        if self.closed {
+            "closed"
+        } else if self.active {
+            "active"
+        } else {
+            "inactive"
+        }
+    }
+
+    /// Whether Gamma tagged this as a sports market
+    pub fn is_sports(&self) -> bool {
+        self.sports_market_type.is_some() || self.game_id.is_some()
+    }
+
+    /// Seconds from `now` until kickoff; negative once the game has started.
+    ///
+    /// Books on sports markets are usually pulled at game start, so quoting logic should
+    /// check this before resting orders. `None` for markets without a game start time.
+    pub fn seconds_until_game_start(&self, now: DateTime<Utc>) -> Option<i64> {
+        self.game_start_time
+            .map(|start| start.signed_duration_since(now).num_seconds())
+    }
+
+    /// Whether the game has kicked off as of `now`
+    pub fn has_game_started(&self, now: DateTime<Utc>) -> bool {
+        self.game_start_time.is_some_and(|start| start <= now)
+    }
+
+    /// Limit price on `token_id` that expresses `probability` of the first ("Yes") outcome.
+    ///
+    /// The first token's price is the probability itself; the second token is priced at
+    /// the complement. The result is not rounded to the tick size.
+    pub fn price_for_probability(
+        &self,
+        token_id: &str,
+        probability: Decimal,
+    ) -> crate::errors::Result<Decimal> {
+        validate_probability(probability)?;
+        match self
+            .tokens
+            .iter()
+            .position(|token| token.token_id == token_id)
+        {
+            Some(0) => Ok(probability),
+            Some(_) => Ok(Decimal::ONE - probability),
+            None => Err(PolyError::validation(format!(
+                "token {} is not an outcome of market {}",
+                token_id, self.condition_id
+            ))),
+        }
+    }
+
+    /// Outcome prices scaled to sum to 1: the implied probability of each outcome.
+    ///
+    /// The many-outcome counterpart of
+    /// [`price_for_probability`](Self::price_for_probability)'s complement rule, for
+    /// events split into one market per outcome (such as neg-risk events, with the Yes
+    /// token price of each). Order is kept. All zeros when the prices sum to zero.
+    pub fn outcome_probabilities(prices: &[Decimal]) -> Vec<Decimal> {
+        let total: Decimal = prices.iter().sum();
+        if total.is_zero() {
+            return vec![Decimal::ZERO; prices.len()];
+        }
+        prices.iter().map(|price| price / total).collect()
+    }
+
+    /// How far the outcome prices sum from 1, when that is more than `tolerance`.
+    ///
+    /// A positive deviation means the outcomes together cost more than the $1 one of
+    /// them pays, so selling the full set locks in the excess; a negative one means
+    /// buying the full set does.
+    pub fn outcome_price_deviation(prices: &[Decimal], tolerance: Decimal) -> Option<Decimal> {
+        let deviation = prices.iter().sum::<Decimal>() - Decimal::ONE;
+        (deviation.abs() > tolerance).then_some(deviation)
+    }
+}
+
+/// Every market's [`asset_ids`](Market::asset_ids), for a single `subscribe` call
+///
+/// IDs keep the order they are first seen in, market by market, and a token listed by
+/// more than one market appears once.
+pub fn asset_ids_for(markets: &[Market]) -> Vec<String> {
+    let mut seen = std::collections::HashSet::new();
+    markets
+        .iter()
+        .flat_map(Market::asset_ids)
+        .filter(|id| seen.insert(id.clone()))
+        .collect()
+}
+
+/// Reject probabilities outside the open interval `(0, 1)`
+pub(crate) fn validate_probability(probability: Decimal) -> crate::errors::Result<()> {
+    if probability <= Decimal::ZERO || probability >= Decimal::ONE {
+        return Err(PolyError::validation(format!(
+            "probability {} must be strictly between 0 and 1",
+            probability
+        )));
+    }
+    Ok(())
 }
 
 /// Token information within a market
@@ -638,8 +1110,42 @@ pub struct Token {
     pub outcome: String,
 }
 
+/// `condition_id | question | liq=$1234 | ends 2024-11-05 | active`, with `-` for
+/// whatever the market doesn't carry
+impl fmt::Display for Market {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let question = Some(self.question.as_str()).filter(|question| !question.is_empty());
+        write!(
+            f,
+            "{} | {} | liq={} | ends {} | {}",
+            self.condition_id,
+            or_dash(question),
+            whole_dollars(self.liquidity_num),
+            or_dash(self.end_date_iso.as_deref()),
+            self.status_label()
+        )
+    }
+}
+
+/// `Yes [token_id]`
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} [{}]", self.outcome, self.token_id)
+    }
+}
+
+/// `$1235` for an amount, rounded to the dollar, or `-` when there is none
+fn whole_dollars(amount: Option<Decimal>) -> String {
+    or_dash(amount.map(|amount| format!("${}", crate::orders::canonical_decimal(amount, 0))))
+}
+
+/// `value`'s display form, or `-` when there is none
+fn or_dash(value: Option<impl fmt::Display>) -> String {
+    value.map_or_else(|| "-".to_string(), |value| value.to_string())
+}
+
 impl GammaMarket {
-    fn parse_token_ids(&self) -> Vec<String> {
+    pub(crate) fn parse_token_ids(&self) -> Vec<String> {
         self.clob_token_ids
             .as_ref()
             .and_then(|raw| serde_json::from_str::<Vec<String>>(raw).ok())
@@ -648,10 +1154,10 @@ impl GammaMarket {
 
     fn normalized_outcomes(&self) -> Vec<String> {
         let default_outcomes = vec!["Yes".to_string(), "No".to_string()];
-        if let Some(raw) = self.outcomes.as_ref() {
-            if let Ok(values) = serde_json::from_str::<Vec<String>>(raw) {
-                return values;
-            }
+        if let Some(raw) = self.outcomes.as_ref()
+            && let Ok(values) = serde_json::from_str::<Vec<String>>(raw)
+        {
+            return values;
         }
 
         default_outcomes
@@ -662,6 +1168,7 @@ impl From<GammaMarket> for Market {
     fn from(gamma: GammaMarket) -> Self {
         let token_ids = gamma.parse_token_ids();
         let outcomes = gamma.normalized_outcomes();
+        let icon = gamma.icon.clone();
 
         let tokens = [
             Token {
@@ -694,17 +1201,29 @@ impl From<GammaMarket> for Market {
             max_incentive_spread: None,
             active: gamma.active,
             closed: gamma.closed,
+            neg_risk: gamma.neg_risk.unwrap_or(false),
+            neg_risk_market_id: gamma.neg_risk_market_id.filter(|id| !id.is_empty()),
             question_id: gamma.condition_id.clone(),
-            minimum_order_size: gamma.order_min_size.unwrap_or(Decimal::ZERO),
-            minimum_tick_size: gamma.order_tick_size.unwrap_or(Decimal::ZERO),
+            minimum_order_size: gamma
+                .order_min_size
+                .filter(|size| !size.is_zero())
+                .unwrap_or(DEFAULT_MIN_ORDER_SIZE),
+            minimum_tick_size: gamma
+                .order_tick_size
+                .filter(|tick| !tick.is_zero())
+                .unwrap_or(DEFAULT_MIN_TICK_SIZE),
             description: gamma.description.unwrap_or_default(),
             category: gamma.category.clone(),
+            category_id: gamma.category_id,
             end_date_iso: gamma.end_date.clone(),
-            game_start_time: None,
+            game_start_time: gamma.game_start_time,
+            sports_market_type: gamma.sports_market_type.clone(),
+            game_id: gamma.game_id.clone(),
+            line: gamma.line,
             question: gamma.question.unwrap_or_default(),
             market_slug: gamma.slug.clone(),
             seconds_delay: Decimal::ZERO,
-            icon: gamma.icon.unwrap_or_default(),
+            icon: icon.clone().unwrap_or_default(),
             fpmm: String::new(),
             liquidity: gamma.liquidity.clone(),
             liquidity_num: gamma.liquidity_num,
@@ -726,11 +1245,17 @@ impl From<GammaMarket> for Market {
             volume_1yr_clob: gamma.volume_1yr_clob,
             volume_amm: gamma.volume_amm,
             volume_clob: gamma.volume_clob,
+            spread: gamma.spread,
+            icon_url: gamma.icon_url.or(icon).filter(|url| !url.is_empty()),
+            banner_url: gamma.banner_url,
+            category_icon_url: gamma.category_icon_url,
+            resolution_source: gamma.resolution_source.filter(|source| !source.is_empty()),
+            tags: gamma.tags,
         }
     }
 }
 
-/// Client configuration for PolyClient
+/// Client configuration for [`PolyFacade`](crate::client::PolyFacade)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClientConfig {
     /// Base URL for the API
@@ -749,6 +1274,16 @@ pub struct ClientConfig {
     pub timeout: Option<std::time::Duration>,
     /// Maximum number of connections
     pub max_connections: Option<usize>,
+    /// Maximum requests in flight at once; extra requests queue (unlimited when `None`)
+    #[serde(default)]
+    pub max_concurrency: Option<usize>,
+    /// Address orders are funded from, such as a proxy wallet or Gnosis safe (the
+    /// signer when `None`)
+    #[serde(default)]
+    pub funder: Option<String>,
+    /// Signature type for `funder`; inferred from it when `None`
+    #[serde(default)]
+    pub signature_type: Option<crate::orders::SigType>,
 }
 
 impl Default for ClientConfig {
@@ -760,8 +1295,11 @@ impl Default for ClientConfig {
             api_credentials: None,
             timeout: Some(std::time::Duration::from_secs(30)),
             max_connections: Some(100),
+            max_concurrency: None,
             max_slippage: None,
             fee_rate: None,
+            funder: None,
+            signature_type: None,
         }
     }
 }
@@ -847,7 +1385,7 @@ impl WssChannelType {
 pub struct Quote {
     pub token_id: String,
     pub side: Side,
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::serde_decimal")]
     pub price: Decimal,
     pub timestamp: DateTime<Utc>,
 }
@@ -946,6 +1484,637 @@ impl TradeParams {
     }
 }
 
+/// Winning tokens of one outcome redeemed by [`ClobClient::redeem_positions`](crate::client::ClobClient::redeem_positions)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenRedemption {
+    pub token_id: String,
+    #[serde(default)]
+    pub outcome: Option<String>,
+    /// Conditional tokens burned
+    #[serde(with = "crate::serde_decimal")]
+    pub size: Decimal,
+    #[serde(with = "crate::serde_decimal")]
+    pub usdc_received: Decimal,
+}
+
+/// Settlement of a resolved market's winning positions
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RedemptionReceipt {
+    pub condition_id: String,
+    #[serde(default)]
+    pub redeemed_tokens: Vec<TokenRedemption>,
+    #[serde(with = "crate::serde_decimal")]
+    pub total_usdc_received: Decimal,
+    #[serde(
+        default,
+        deserialize_with = "crate::decode::deserializers::number_from_string"
+    )]
+    pub gas_estimate: u64,
+    /// Hash of the settlement transaction, once it has been submitted
+    #[serde(default)]
+    pub tx_hash: Option<String>,
+}
+
+/// Kind of entry in the account activity log
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ActivityType {
+    OrderPlaced,
+    OrderCancelled,
+    OrderFilled,
+    PositionRedeemed,
+    Deposit,
+    Withdrawal,
+}
+
+impl ActivityType {
+    pub const ALL: [ActivityType; 6] = [
+        ActivityType::OrderPlaced,
+        ActivityType::OrderCancelled,
+        ActivityType::OrderFilled,
+        ActivityType::PositionRedeemed,
+        ActivityType::Deposit,
+        ActivityType::Withdrawal,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ActivityType::OrderPlaced => "ORDER_PLACED",
+            ActivityType::OrderCancelled => "ORDER_CANCELLED",
+            ActivityType::OrderFilled => "ORDER_FILLED",
+            ActivityType::PositionRedeemed => "POSITION_REDEEMED",
+            ActivityType::Deposit => "DEPOSIT",
+            ActivityType::Withdrawal => "WITHDRAWAL",
+        }
+    }
+}
+
+/// Filters for [`ClobClient::get_user_activity`](crate::client::ClobClient::get_user_activity)
+#[derive(Debug, Clone, Default)]
+pub struct ActivityParams {
+    /// Only entries at or after this Unix time (seconds)
+    pub start_ts: Option<u64>,
+    /// Only entries at or before this Unix time (seconds)
+    pub end_ts: Option<u64>,
+    pub event_type: Option<ActivityType>,
+    /// `next_cursor` of the previous page
+    pub cursor: Option<String>,
+}
+
+impl ActivityParams {
+    pub fn to_query_params(&self) -> Vec<(&str, String)> {
+        let mut params = Vec::with_capacity(4);
+
+        if let Some(x) = &self.start_ts {
+            params.push(("start_ts", x.to_string()));
+        }
+
+        if let Some(x) = &self.end_ts {
+            params.push(("end_ts", x.to_string()));
+        }
+
+        if let Some(x) = &self.event_type {
+            params.push(("event_type", x.as_str().to_string()));
+        }
+
+        if let Some(x) = &self.cursor {
+            params.push(("next_cursor", x.clone()));
+        }
+        params
+    }
+}
+
+/// One entry of the account activity log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActivityEntry {
+    pub id: String,
+    pub event_type: ActivityType,
+    #[serde(deserialize_with = "crate::decode::deserializers::datetime_from_timestamp")]
+    pub timestamp: DateTime<Utc>,
+    /// Details that depend on the event type, such as the order id or transfer amount
+    #[serde(default)]
+    pub metadata: serde_json::Value,
+}
+
+/// A cursor-paged list as the CLOB sends it
+#[derive(Debug, Deserialize)]
+pub(crate) struct CursorPage<T> {
+    #[serde(default = "Vec::new")]
+    pub data: Vec<T>,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+    #[serde(default)]
+    pub count: Option<usize>,
+}
+
+impl<T> CursorPage<T> {
+    pub fn into_paged(self) -> PagedResponse<T> {
+        PagedResponse {
+            total: self.count.unwrap_or(self.data.len()),
+            next_cursor: self
+                .next_cursor
+                .filter(|cursor| cursor_has_more(Some(cursor))),
+            data: self.data,
+        }
+    }
+}
+
+/// Activity counts per [`ActivityType`] since a point in time
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActivitySummary {
+    pub since: DateTime<Utc>,
+    pub counts: std::collections::HashMap<ActivityType, usize>,
+}
+
+impl ActivitySummary {
+    pub fn count(&self, event_type: ActivityType) -> usize {
+        self.counts.get(&event_type).copied().unwrap_or(0)
+    }
+
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+}
+
+/// Filters for [`ClobClient::get_market_participants`](crate::client::ClobClient::get_market_participants)
+#[derive(Debug, Clone, Default)]
+pub struct ParticipantParams {
+    /// Only addresses that traded at least this much USDC in the market
+    pub min_volume: Option<Decimal>,
+    pub limit: Option<u32>,
+    /// `next_cursor` of the previous page
+    pub cursor: Option<String>,
+}
+
+impl ParticipantParams {
+    pub fn to_query_params(&self) -> Vec<(&str, String)> {
+        let mut params = Vec::with_capacity(3);
+
+        if let Some(x) = &self.min_volume {
+            params.push(("min_volume", x.to_string()));
+        }
+
+        if let Some(x) = &self.limit {
+            params.push(("limit", x.to_string()));
+        }
+
+        if let Some(x) = &self.cursor {
+            params.push(("next_cursor", x.clone()));
+        }
+        params
+    }
+}
+
+/// One address's trading in a single market
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Participant {
+    pub address: String,
+    /// USDC traded, buys and sells
+    #[serde(with = "crate::serde_decimal")]
+    pub volume_traded: Decimal,
+    #[serde(
+        default,
+        deserialize_with = "crate::decode::deserializers::number_from_string"
+    )]
+    pub num_trades: u32,
+    #[serde(with = "crate::serde_decimal")]
+    pub avg_price: Decimal,
+    /// Shares held now: positive long, negative net sold
+    #[serde(with = "crate::serde_decimal")]
+    pub net_position: Decimal,
+    /// Whether most of the address's fills were as maker
+    #[serde(default)]
+    pub is_market_maker: bool,
+}
+
+/// An address's trading across every market
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GlobalParticipantStats {
+    pub address: String,
+    #[serde(with = "crate::serde_decimal")]
+    pub volume_traded: Decimal,
+    #[serde(
+        default,
+        deserialize_with = "crate::decode::deserializers::number_from_string"
+    )]
+    pub num_trades: u64,
+    #[serde(
+        default,
+        deserialize_with = "crate::decode::deserializers::number_from_string"
+    )]
+    pub markets_traded: u32,
+    /// Markets in which the address was mostly a maker
+    #[serde(
+        default,
+        deserialize_with = "crate::decode::deserializers::number_from_string"
+    )]
+    pub markets_made: u32,
+    #[serde(default, with = "crate::serde_decimal")]
+    pub realized_pnl: Decimal,
+    #[serde(
+        default,
+        deserialize_with = "crate::decode::deserializers::optional_datetime_from_timestamp"
+    )]
+    pub first_trade_at: Option<DateTime<Utc>>,
+    #[serde(
+        default,
+        deserialize_with = "crate::decode::deserializers::optional_datetime_from_timestamp"
+    )]
+    pub last_trade_at: Option<DateTime<Utc>>,
+}
+
+/// How often a scheduled market resolves
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScheduleRecurrence {
+    OneTime,
+    Daily,
+    Weekly { day: chrono::Weekday },
+    Monthly { day_of_month: u8 },
+}
+
+/// A settled occurrence of a scheduled market
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PastResolution {
+    #[serde(deserialize_with = "crate::decode::deserializers::datetime_from_timestamp")]
+    pub resolution_date: DateTime<Utc>,
+    pub winning_outcome: String,
+    #[serde(with = "crate::serde_decimal")]
+    pub settlement_price: Decimal,
+}
+
+/// Resolution calendar of a market, from `GET /markets/{condition_id}/schedule`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MarketSchedule {
+    pub recurrence: ScheduleRecurrence,
+    #[serde(deserialize_with = "crate::decode::deserializers::datetime_from_timestamp")]
+    pub next_resolution: DateTime<Utc>,
+    /// Oldest first
+    #[serde(default)]
+    pub past_resolutions: Vec<PastResolution>,
+}
+
+impl MarketSchedule {
+    /// Whole days from now until the next resolution; negative once it has passed
+    pub fn days_until_next(&self) -> i64 {
+        self.days_until_next_from(Utc::now())
+    }
+
+    /// [`days_until_next`](Self::days_until_next) counted from `now`
+    pub fn days_until_next_from(&self, now: DateTime<Utc>) -> i64 {
+        (self.next_resolution - now).num_days()
+    }
+
+    pub fn is_recurring(&self) -> bool {
+        self.recurrence != ScheduleRecurrence::OneTime
+    }
+}
+
+/// The on-chain transaction that reported a condition's payouts, from `GET /settlements`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SettlementTx {
+    pub tx_hash: String,
+    #[serde(deserialize_with = "crate::decode::deserializers::number_from_string")]
+    pub block_number: u64,
+    #[serde(deserialize_with = "crate::decode::deserializers::datetime_from_timestamp")]
+    pub timestamp: DateTime<Utc>,
+    pub winning_condition_index: u32,
+    /// Payout weight of each outcome, in outcome-index order
+    pub payout_numerators: Vec<u64>,
+    #[serde(deserialize_with = "crate::decode::deserializers::number_from_string")]
+    pub payout_denominator: u64,
+}
+
+impl SettlementTx {
+    /// Share of the collateral one token of `outcome_index` redeems for
+    ///
+    /// `None` for an index past the reported outcomes or a zero denominator.
+    pub fn winning_payout_fraction(&self, outcome_index: usize) -> Option<Decimal> {
+        let numerator = *self.payout_numerators.get(outcome_index)?;
+        if self.payout_denominator == 0 {
+            return None;
+        }
+        Some(Decimal::from(numerator) / Decimal::from(self.payout_denominator))
+    }
+}
+
+/// One of the authenticated user's redemptions against a [`SettlementTx`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PersonalSettlement {
+    pub condition_id: String,
+    pub outcome_index: u32,
+    /// Tokens redeemed
+    #[serde(with = "crate::serde_decimal")]
+    pub size: Decimal,
+    /// USDC received for them
+    #[serde(with = "crate::serde_decimal")]
+    pub payout: Decimal,
+    /// Hash of the redemption transaction itself
+    pub redeem_tx_hash: String,
+    pub settlement: SettlementTx,
+}
+
+/// A loss alert the server raised when unrealized P&L crossed the account's threshold
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Alert {
+    pub alert_id: String,
+    pub triggered_at: DateTime<Utc>,
+    /// Unrealized P&L in USDC when the alert fired; negative
+    #[serde(with = "crate::serde_decimal")]
+    pub pnl_at_trigger: Decimal,
+    #[serde(default)]
+    pub positions_at_trigger: Vec<DataPosition>,
+}
+
+/// Gas cost of redeeming a resolved condition, from `GET /gas-estimate`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasEstimate {
+    #[serde(deserialize_with = "crate::decode::deserializers::number_from_string")]
+    pub gas_units: u64,
+    #[serde(with = "crate::serde_decimal")]
+    pub gas_price_gwei: Decimal,
+    #[serde(with = "crate::serde_decimal")]
+    pub total_cost_eth: Decimal,
+    /// `total_cost_eth` at the server's cached ETH price
+    #[serde(with = "crate::serde_decimal")]
+    pub total_cost_usd: Decimal,
+}
+
+impl GasEstimate {
+    /// Whether redeeming `position_value_usdc` clears the gas cost with a 10% margin
+    pub fn is_worth_redeeming(&self, position_value_usdc: Decimal) -> bool {
+        position_value_usdc > self.total_cost_usd * Decimal::new(11, 1)
+    }
+}
+
+/// Margin buffer above which [`LiquidationScenario::is_safe`] holds
+pub const SAFE_MARGIN_BUFFER: Decimal = Decimal::from_parts(2, 0, 0, false, 1);
+
+/// Where a set of margined positions would be liquidated, from
+/// [`ClobClient::simulate_liquidation`](crate::client::ClobClient::simulate_liquidation)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LiquidationScenario {
+    /// Price of each position's token at which its equity is gone, keyed by asset;
+    /// `None` for a position that no price in `[0, 1]` liquidates
+    pub token_liquidation_prices: std::collections::HashMap<String, Option<Decimal>>,
+    /// Cash posted against the positions at entry
+    pub total_margin_used: Decimal,
+    /// Equity as a fraction of the positions' current value, zero at or past liquidation
+    pub margin_buffer: Decimal,
+    /// Smallest fractional price fall that liquidates any one position, one when none
+    /// can be
+    pub distance_to_liquidation: Decimal,
+}
+
+impl LiquidationScenario {
+    /// Whether the margin buffer exceeds [`SAFE_MARGIN_BUFFER`]
+    pub fn is_safe(&self) -> bool {
+        self.margin_buffer > SAFE_MARGIN_BUFFER
+    }
+}
+
+/// Coverage ratio at or above which [`ProtocolStats::is_healthy`] holds
+pub const MIN_HEALTHY_COVERAGE_RATIO: Decimal = Decimal::ONE;
+
+/// The protocol insurance fund, from `GET /protocol/insurance`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InsuranceFund {
+    #[serde(with = "crate::serde_decimal")]
+    pub balance_usdc: Decimal,
+    /// Fund balance over the losses it is sized to absorb
+    #[serde(with = "crate::serde_decimal")]
+    pub coverage_ratio: Decimal,
+    pub last_updated: DateTime<Utc>,
+}
+
+/// Protocol-wide activity totals, from `GET /protocol/stats`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProtocolStats {
+    #[serde(with = "crate::serde_decimal")]
+    pub total_volume_usdc: Decimal,
+    #[serde(with = "crate::serde_decimal")]
+    pub total_open_interest_usdc: Decimal,
+    pub active_markets: u32,
+    pub total_users: u64,
+    pub total_trades: u64,
+}
+
+impl ProtocolStats {
+    /// Whether `fund` covers the protocol: trivially so with nothing open, otherwise
+    /// when its coverage ratio reaches [`MIN_HEALTHY_COVERAGE_RATIO`]
+    pub fn is_healthy(&self, fund: &InsuranceFund) -> bool {
+        self.total_open_interest_usdc <= Decimal::ZERO
+            || fund.coverage_ratio >= MIN_HEALTHY_COVERAGE_RATIO
+    }
+}
+
+/// A liquidity reward epoch, from `GET /epoch`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Epoch {
+    pub id: u32,
+    /// Unix time in seconds
+    pub start_ts: i64,
+    /// Unix time in seconds
+    pub end_ts: i64,
+    pub is_active: bool,
+    #[serde(with = "crate::serde_decimal")]
+    pub reward_pool_usdc: Decimal,
+}
+
+/// Volume-weighted mean "Yes" price over the busiest markets, from
+/// [`ClobClient::get_prediction_market_index`](crate::client::ClobClient::get_prediction_market_index)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PredictionIndex {
+    /// Weighted mean price, between 0 and 1
+    #[serde(with = "crate::serde_decimal")]
+    pub composite_score: Decimal,
+    /// The markets averaged, highest volume first
+    pub top_markets: Vec<IndexComponent>,
+    pub computed_at: DateTime<Utc>,
+}
+
+/// One market's share of a [`PredictionIndex`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexComponent {
+    pub condition_id: String,
+    /// Fraction of the index's total volume; the weights sum to one
+    #[serde(with = "crate::serde_decimal")]
+    pub weight: Decimal,
+    /// Price of the first ("Yes") outcome
+    #[serde(with = "crate::serde_decimal")]
+    pub current_price: Decimal,
+    /// Lifetime volume in USDC
+    #[serde(with = "crate::serde_decimal")]
+    pub volume: Decimal,
+}
+
+impl PredictionIndex {
+    /// Index the `n` highest-volume markets of `markets`
+    ///
+    /// Only markets that are active, not closed, and carry both a positive volume and
+    /// a "Yes" price in `outcomePrices` count. `None` when none of them do.
+    pub fn from_markets(
+        markets: &[GammaMarket],
+        n: usize,
+        computed_at: DateTime<Utc>,
+    ) -> Option<Self> {
+        let mut candidates: Vec<(&GammaMarket, Decimal, Decimal)> = markets
+            .iter()
+            .filter(|market| market.active && !market.closed)
+            .filter_map(|market| {
+                let volume = market.volume_num.filter(|volume| *volume > Decimal::ZERO)?;
+                Some((market, *market.outcome_prices.first()?, volume))
+            })
+            .collect();
+        candidates.sort_by_key(|(_, _, volume)| std::cmp::Reverse(*volume));
+        candidates.truncate(n);
+
+        let total_volume: Decimal = candidates.iter().map(|(_, _, volume)| volume).sum();
+        if total_volume.is_zero() {
+            return None;
+        }
+        let top_markets: Vec<IndexComponent> = candidates
+            .into_iter()
+            .map(|(market, current_price, volume)| IndexComponent {
+                condition_id: market.condition_id.clone(),
+                weight: volume / total_volume,
+                current_price,
+                volume,
+            })
+            .collect();
+        let composite_score = top_markets
+            .iter()
+            .map(|component| component.current_price * component.volume)
+            .sum::<Decimal>()
+            / total_volume;
+
+        Some(Self {
+            composite_score,
+            top_markets,
+            computed_at,
+        })
+    }
+
+    /// The composite score on a 0–100 scale
+    pub fn normalized_score(&self) -> Decimal {
+        (self.composite_score * Decimal::ONE_HUNDRED).clamp(Decimal::ZERO, Decimal::ONE_HUNDRED)
+    }
+}
+
+/// One entry of a market's event timeline, from `GET /markets/{condition_id}/events`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum MarketEvent {
+    PriceChanged {
+        token_id: String,
+        #[serde(with = "crate::serde_decimal")]
+        old_price: Decimal,
+        #[serde(with = "crate::serde_decimal")]
+        new_price: Decimal,
+        #[serde(deserialize_with = "crate::decode::deserializers::datetime_from_timestamp")]
+        timestamp: DateTime<Utc>,
+    },
+    TradeExecuted {
+        trade_id: String,
+        token_id: String,
+        #[serde(with = "crate::serde_decimal")]
+        price: Decimal,
+        #[serde(with = "crate::serde_decimal")]
+        size: Decimal,
+        #[serde(deserialize_with = "crate::decode::deserializers::datetime_from_timestamp")]
+        timestamp: DateTime<Utc>,
+    },
+    OrderBookChanged {
+        #[serde(deserialize_with = "crate::decode::deserializers::datetime_from_timestamp")]
+        timestamp: DateTime<Utc>,
+        /// The server's description of the change
+        summary: String,
+    },
+    MarketResolved {
+        winning_outcome: String,
+        #[serde(deserialize_with = "crate::decode::deserializers::datetime_from_timestamp")]
+        timestamp: DateTime<Utc>,
+    },
+    MarketClosed {
+        #[serde(deserialize_with = "crate::decode::deserializers::datetime_from_timestamp")]
+        timestamp: DateTime<Utc>,
+    },
+}
+
+impl MarketEvent {
+    /// When the event happened
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        match self {
+            MarketEvent::PriceChanged { timestamp, .. }
+            | MarketEvent::TradeExecuted { timestamp, .. }
+            | MarketEvent::OrderBookChanged { timestamp, .. }
+            | MarketEvent::MarketResolved { timestamp, .. }
+            | MarketEvent::MarketClosed { timestamp } => *timestamp,
+        }
+    }
+}
+
+/// Account tier, which sets the trading limits in [`UserLimits`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AccountTier {
+    Retail,
+    Institutional,
+    MarketMaker,
+}
+
+/// Request and trading limits of the authenticated account, from `GET /user/limits`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserLimits {
+    pub requests_per_second: u32,
+    pub orders_per_day: u32,
+    pub max_open_orders: u32,
+    /// Largest single order, in shares
+    #[serde(with = "crate::serde_decimal")]
+    pub max_order_size: Decimal,
+    #[serde(with = "crate::serde_decimal")]
+    pub max_position_size_usdc: Decimal,
+    pub tier: AccountTier,
+}
+
+impl UserLimits {
+    /// Whether `current_open_orders` leaves no room for another resting order
+    pub fn is_at_capacity(&self, current_open_orders: u32) -> bool {
+        current_open_orders >= self.max_open_orders
+    }
+}
+
+/// Result of a cancel request: which orders went and why the rest didn't
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CancelResponse {
+    #[serde(default)]
+    pub canceled: Vec<String>,
+    /// Order ID to the exchange's reason for keeping it
+    #[serde(default, deserialize_with = "cancel_reasons")]
+    pub not_canceled: std::collections::HashMap<String, String>,
+}
+
+fn cancel_reasons<'de, D>(
+    deserializer: D,
+) -> std::result::Result<std::collections::HashMap<String, String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let raw =
+        Option::<std::collections::HashMap<String, serde_json::Value>>::deserialize(deserializer)?;
+    Ok(raw
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(order_id, reason)| {
+            let reason = match reason {
+                serde_json::Value::String(reason) => reason,
+                other => other.to_string(),
+            };
+            (order_id, reason)
+        })
+        .collect())
+}
+
 /// Open order information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OpenOrder {
@@ -953,15 +2122,15 @@ pub struct OpenOrder {
     pub id: String,
     pub status: String,
     pub market: String,
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::serde_decimal")]
     pub original_size: Decimal,
     pub outcome: String,
     pub maker_address: String,
     pub owner: String,
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::serde_decimal")]
     pub price: Decimal,
     pub side: Side,
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::serde_decimal")]
     pub size_matched: Decimal,
     pub asset_id: String,
     #[serde(deserialize_with = "crate::decode::deserializers::number_from_string")]
@@ -976,9 +2145,9 @@ pub struct OpenOrder {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BalanceAllowance {
     pub asset_id: String,
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::serde_decimal")]
     pub balance: Decimal,
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::serde_decimal")]
     pub allowance: Decimal,
 }
 
@@ -1057,11 +2226,23 @@ pub struct BatchPriceRequest {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TokenPrice {
     pub token_id: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::serde_decimal::option"
+    )]
     pub bid: Option<Decimal>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::serde_decimal::option"
+    )]
     pub ask: Option<Decimal>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::serde_decimal::option"
+    )]
     pub mid: Option<Decimal>,
 }
 
@@ -1080,25 +2261,53 @@ pub struct ApiKeysResponse {
 
 #[derive(Debug, Deserialize)]
 pub struct MidpointResponse {
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::serde_decimal")]
     pub mid: Decimal,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct PriceResponse {
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::serde_decimal")]
     pub price: Decimal,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct SpreadResponse {
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::serde_decimal")]
     pub spread: Decimal,
 }
 
+/// Funding terms for a leveraged/perpetual token
+///
+/// Polymarket only lists spot markets today; this mirrors the shape a funding endpoint
+/// would most likely take so callers can code against it ahead of time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FundingRate {
+    pub token_id: String,
+    /// Rate charged per funding interval
+    #[serde(with = "crate::serde_decimal")]
+    pub rate: Decimal,
+    pub interval_hours: u32,
+    /// Unix timestamp (seconds) of the next funding event
+    pub next_funding_at: i64,
+    #[serde(with = "crate::serde_decimal")]
+    pub cumulative_rate: Decimal,
+}
+
+impl FundingRate {
+    /// Per-interval rate scaled to a year (`rate * 8760 / interval_hours`); zero when the
+    /// interval is unset
+    pub fn annualized_rate(&self) -> Decimal {
+        if self.interval_hours == 0 {
+            return Decimal::ZERO;
+        }
+        self.rate * Decimal::from(8760) / Decimal::from(self.interval_hours)
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct TickSizeResponse {
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::serde_decimal")]
     pub minimum_tick_size: Decimal,
 }
 
@@ -1107,6 +2316,41 @@ pub struct NegRiskResponse {
     pub neg_risk: bool,
 }
 
+/// Outcome of [`ClobClient::check_health`](crate::client::ClobClient::check_health)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HealthStatus {
+    /// Whether `/time` answered with a success status and a readable time
+    pub ok: bool,
+    /// Round trip of the check, in milliseconds
+    pub latency_ms: u64,
+    /// Server time in Unix seconds when it answered, else the client clock's
+    pub timestamp: i64,
+}
+
+/// Resting size on each side of a token's book
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenInterest {
+    pub token_id: String,
+    #[serde(with = "crate::serde_decimal")]
+    pub bid_open_interest: Decimal,
+    #[serde(with = "crate::serde_decimal")]
+    pub ask_open_interest: Decimal,
+    #[serde(with = "crate::serde_decimal")]
+    pub total_open_interest: Decimal,
+}
+
+impl OpenInterest {
+    /// Book imbalance in `[-1, 1]`: `(bid - ask) / (bid + ask)`.
+    /// Positive values mean more resting buy interest. Returns zero for an empty book.
+    pub fn imbalance_ratio(&self) -> Decimal {
+        let total = self.bid_open_interest + self.ask_open_interest;
+        if total.is_zero() {
+            return Decimal::ZERO;
+        }
+        (self.bid_open_interest - self.ask_open_interest) / total
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BookParams {
     pub token_id: String,
@@ -1122,36 +2366,197 @@ pub struct OrderBookSummary {
     pub timestamp: u64,
     pub bids: Vec<OrderSummary>,
     pub asks: Vec<OrderSummary>,
+    /// Market parameters sent alongside the levels, `None` when the response leaves
+    /// them out. The server's `hash` covers them too.
+    #[serde(default, with = "crate::serde_decimal::option")]
+    pub min_order_size: Option<Decimal>,
+    #[serde(default, with = "crate::serde_decimal::option")]
+    pub tick_size: Option<Decimal>,
+    #[serde(default)]
+    pub neg_risk: Option<bool>,
+    #[serde(default, with = "crate::serde_decimal::option")]
+    pub last_trade_price: Option<Decimal>,
+    /// Set when levels were dropped (see [`OrderBookSummary::truncate_to_depth`]), so the
+    /// real book may be deeper than `bids`/`asks` show. Depth-based estimates like VWAP
+    /// or market impact should be treated as a lower bound on available liquidity.
+    #[serde(default)]
+    pub truncated: bool,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+impl OrderBookSummary {
+    /// Keep only the best `depth` levels on each side, marking the book as truncated if
+    /// anything was dropped.
+    ///
+    /// Both sides come back best first (highest bids, lowest asks), with levels quoted at
+    /// the same price merged into one before counting.
+    pub fn truncate_to_depth(&mut self, depth: usize) {
+        let bids_cut = retain_best_levels(&mut self.bids, depth, Side::BUY);
+        let asks_cut = retain_best_levels(&mut self.asks, depth, Side::SELL);
+        self.truncated |= bids_cut || asks_cut;
+    }
+}
+
+/// Sort `levels` best first, merge levels quoted at the same price and keep the best
+/// `depth`, returning whether any were dropped
+fn retain_best_levels(levels: &mut Vec<OrderSummary>, depth: usize, side: Side) -> bool {
+    match side {
+        Side::BUY => levels.sort_by_key(|level| std::cmp::Reverse(level.price)),
+        Side::SELL => levels.sort_by_key(|level| level.price),
+    }
+    levels.dedup_by(|level, kept| {
+        let same = level.price == kept.price;
+        if same {
+            kept.size += level.size;
+        }
+        same
+    });
+    let dropped = levels.len() > depth;
+    levels.truncate(depth);
+    dropped
+}
+
+/// Top-of-book snapshot for one token, as produced by `ClobClient::scan_spreads`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpreadSummary {
+    pub token_id: String,
+    pub market: String,
+    pub best_bid: Option<Decimal>,
+    pub best_ask: Option<Decimal>,
+    /// `best_ask - best_bid`, when both sides are quoted
+    pub spread: Option<Decimal>,
+    pub midpoint: Option<Decimal>,
+    /// Size resting at the best bid
+    pub bid_depth: Decimal,
+    /// Size resting at the best ask
+    pub ask_depth: Decimal,
+}
+
+impl SpreadSummary {
+    /// Summarize the best level on each side of `book`
+    pub fn from_book(book: &OrderBookSummary) -> Self {
+        let best_bid = book.bids.iter().max_by_key(|level| level.price);
+        let best_ask = book.asks.iter().min_by_key(|level| level.price);
+        let depth_at = |best: Option<&OrderSummary>, levels: &[OrderSummary]| {
+            best.map(|best| {
+                levels
+                    .iter()
+                    .filter(|level| level.price == best.price)
+                    .map(|level| level.size)
+                    .sum()
+            })
+            .unwrap_or(Decimal::ZERO)
+        };
+
+        let (bid_price, ask_price) = (best_bid.map(|l| l.price), best_ask.map(|l| l.price));
+        let (spread, midpoint) = match (bid_price, ask_price) {
+            (Some(bid), Some(ask)) => (Some(ask - bid), Some((ask + bid) / Decimal::TWO)),
+            _ => (None, None),
+        };
+
+        Self {
+            token_id: book.asset_id.clone(),
+            market: book.market.clone(),
+            best_bid: bid_price,
+            best_ask: ask_price,
+            spread,
+            midpoint,
+            bid_depth: depth_at(best_bid, &book.bids),
+            ask_depth: depth_at(best_ask, &book.asks),
+        }
+    }
+
+    /// The thinner of the two top levels, i.e. what can trade in either direction
+    pub fn top_depth(&self) -> Decimal {
+        self.bid_depth.min(self.ask_depth)
+    }
+
+    /// Ordering for "tight and liquid first": narrower spreads first, one-sided books
+    /// last, ties broken by deeper top-of-book and then token ID.
+    pub fn cmp_tightness(&self, other: &Self) -> std::cmp::Ordering {
+        let spread_key = |summary: &Self| (summary.spread.is_none(), summary.spread);
+        spread_key(self)
+            .cmp(&spread_key(other))
+            .then_with(|| other.top_depth().cmp(&self.top_depth()))
+            .then_with(|| self.token_id.cmp(&other.token_id))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct OrderSummary {
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::serde_decimal")]
     pub price: Decimal,
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::serde_decimal")]
     pub size: Decimal,
 }
 
+/// A list element that failed to deserialize: its index in the page, the error, and the
+/// raw JSON it came from
+pub type SkippedRecord = (usize, serde_json::Error, serde_json::Value);
+
+/// Cursor the CLOB returns on the last page of a listing
+pub const END_CURSOR: &str = "LTE=";
+
+/// Whether a listing page's `next_cursor` points at another page
+fn cursor_has_more(next_cursor: Option<&str>) -> bool {
+    next_cursor.is_some_and(|cursor| !cursor.is_empty() && cursor != END_CURSOR)
+}
+
+/// One page of markets with the envelope's pagination fields
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct MarketsResponse {
-    #[serde(with = "rust_decimal::serde::str")]
+    /// Page size the page was requested with
+    #[serde(with = "crate::serde_decimal")]
     pub limit: Decimal,
-    #[serde(with = "rust_decimal::serde::str")]
+    /// Markets in this page
+    #[serde(with = "crate::serde_decimal")]
     pub count: Decimal,
+    /// Pass back to fetch the following page; see [`has_more`](Self::has_more)
     pub next_cursor: Option<String>,
+    /// Position of the page's first record in the full listing, when known
+    #[serde(default)]
+    pub offset: Option<u64>,
     pub data: Vec<Market>,
 }
 
+impl MarketsResponse {
+    /// Whether another page follows this one
+    ///
+    /// False once `next_cursor` is missing, empty or the CLOB's `LTE=` end marker.
+    pub fn has_more(&self) -> bool {
+        cursor_has_more(self.next_cursor.as_deref())
+    }
+}
+
+/// One page of simplified markets with the envelope's pagination fields
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SimplifiedMarketsResponse {
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::serde_decimal")]
     pub limit: Decimal,
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::serde_decimal")]
     pub count: Decimal,
     pub next_cursor: Option<String>,
+    #[serde(default)]
+    pub offset: Option<u64>,
     pub data: Vec<SimplifiedMarket>,
 }
 
+impl SimplifiedMarketsResponse {
+    /// Whether another page follows this one; see [`MarketsResponse::has_more`]
+    pub fn has_more(&self) -> bool {
+        cursor_has_more(self.next_cursor.as_deref())
+    }
+}
+
+/// One page of results computed client-side, with a cursor for the next page
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PagedResponse<T> {
+    pub data: Vec<T>,
+    /// Pass back to fetch the following page; `None` on the last page
+    pub next_cursor: Option<String>,
+    /// Total matching records across all pages
+    pub total: usize,
+}
+
 /// Simplified market structure for batch operations
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimplifiedMarket {
@@ -1172,13 +2577,34 @@ pub struct GammaListParams {
     pub offset: Option<u32>,
     pub closed: Option<bool>,
     pub tag_id: Option<String>,
+    /// Only markets in this Gamma category; see [`Category`]
+    pub category_id: Option<u32>,
     pub exclude_tag_id: Option<String>,
     pub related_tags: Option<String>,
     pub order: Option<String>,
     pub ascending: Option<bool>,
+    /// Only these Gamma market IDs, sent as repeated `id` keys
+    pub ids: Vec<String>,
+    /// Only these market slugs, sent as repeated `slug` keys
+    pub slugs: Vec<String>,
+    /// Only markets containing these CLOB token IDs, sent as repeated `clob_token_ids` keys
+    pub clob_token_ids: Vec<String>,
     pub liquidity_num_min: Option<Decimal>,
+    /// Lower bound on lifetime volume (`volumeNumMin`)
+    pub volume_num_min: Option<Decimal>,
+    /// Upper bound on lifetime volume (`volumeNumMax`)
+    pub volume_num_max: Option<Decimal>,
+    /// Lower bound on trailing 24h volume (`volume24hMin`)
+    pub volume_24h_min: Option<Decimal>,
     pub end_date_max: Option<DateTime<Utc>>,
     pub start_date_min: Option<DateTime<Utc>>,
+    /// Only keep markets whose game starts at or after this time.
+    ///
+    /// Gamma has no server-side filter for `gameStartTime`, so the game window is
+    /// applied client-side and is not part of [`GammaListParams::to_query_params`].
+    pub game_start_min: Option<DateTime<Utc>>,
+    /// Only keep markets whose game starts at or before this time
+    pub game_start_max: Option<DateTime<Utc>>,
 }
 
 impl GammaListParams {
@@ -1200,6 +2626,9 @@ impl GammaListParams {
         if let Some(tag_id) = &self.tag_id {
             params.push(("tag_id", tag_id.clone()));
         }
+        if let Some(category_id) = self.category_id {
+            params.push(("category_id", category_id.to_string()));
+        }
         if let Some(exclude_tag_id) = &self.exclude_tag_id {
             params.push(("exclude_tag_id", exclude_tag_id.clone()));
         }
@@ -1212,9 +2641,11 @@ impl GammaListParams {
         if let Some(ascending) = self.ascending {
             params.push(("ascending", ascending.to_string()));
         }
+        params.extend(self.multi_value_query_params());
         if let Some(liquidity_num_min) = &self.liquidity_num_min {
             params.push(("liquidity_num_min", liquidity_num_min.to_string()));
         }
+        params.extend(self.volume_query_params());
         if let Some(end_date_max) = &self.end_date_max {
             params.push(("end_date_max", end_date_max.to_rfc3339()));
         }
@@ -1223,6 +2654,48 @@ impl GammaListParams {
         }
         params
     }
+
+    /// ID, slug and token filters as one key per value.
+    ///
+    /// Gamma reads these as arrays of repeated keys (`id=1&id=2`); a comma-joined value is
+    /// taken as a single ID and matches nothing.
+    pub(crate) fn multi_value_query_params(&self) -> Vec<(&'static str, String)> {
+        [
+            ("id", &self.ids),
+            ("slug", &self.slugs),
+            ("clob_token_ids", &self.clob_token_ids),
+        ]
+        .into_iter()
+        .flat_map(|(key, values)| values.iter().map(move |value| (key, value.clone())))
+        .collect()
+    }
+
+    /// Volume bounds, keyed the way Gamma expects them
+    pub(crate) fn volume_query_params(&self) -> Vec<(&'static str, String)> {
+        [
+            ("volumeNumMin", self.volume_num_min),
+            ("volumeNumMax", self.volume_num_max),
+            ("volume24hMin", self.volume_24h_min),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| value.map(|value| (key, value.to_string())))
+        .collect()
+    }
+
+    /// Whether `market` falls inside the `game_start_min`/`game_start_max` window.
+    ///
+    /// Always true when no window is set; markets without a game start time never match
+    /// an active window.
+    pub fn matches_game_window(&self, market: &Market) -> bool {
+        if self.game_start_min.is_none() && self.game_start_max.is_none() {
+            return true;
+        }
+
+        market.game_start_time.is_some_and(|start| {
+            self.game_start_min.is_none_or(|min| start >= min)
+                && self.game_start_max.is_none_or(|max| start <= max)
+        })
+    }
 }
 
 /// Parameters supported by the Data API `/positions` endpoint.
@@ -1232,6 +2705,8 @@ pub struct DataApiPositionsParams {
     pub size_threshold: Option<u32>,
     /// Maximum number of rows to return.
     pub limit: Option<u32>,
+    /// Rows to skip, for paging past `limit`.
+    pub offset: Option<u32>,
     /// Field to sort by.
     pub sort_by: Option<DataApiSortBy>,
     /// Direction to sort (`ASC` or `DESC`).
@@ -1245,20 +2720,25 @@ impl DataApiPositionsParams {
         let sort_by = self.sort_by.unwrap_or_default();
         let sort_direction = self.sort_direction.unwrap_or_default();
 
-        vec![
+        let mut query = vec![
             ("sizeThreshold", size_threshold.to_string()),
             ("limit", limit.to_string()),
             ("sortBy", sort_by.as_str().to_string()),
             ("sortDirection", sort_direction.as_str().to_string()),
-        ]
+        ];
+        if let Some(offset) = self.offset {
+            query.push(("offset", offset.to_string()));
+        }
+        query
     }
 }
 
 /// Fields allowed for sorting the `/positions` response.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default)]
 pub enum DataApiSortBy {
     Current,
     Initial,
+    #[default]
     Tokens,
     CashPnl,
     PercentPnl,
@@ -1284,16 +2764,11 @@ impl DataApiSortBy {
     }
 }
 
-impl Default for DataApiSortBy {
-    fn default() -> Self {
-        DataApiSortBy::Tokens
-    }
-}
-
 /// Sort direction for the Data API `/positions` response.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default)]
 pub enum DataApiSortDirection {
     Asc,
+    #[default]
     Desc,
 }
 
@@ -1306,12 +2781,6 @@ impl DataApiSortDirection {
     }
 }
 
-impl Default for DataApiSortDirection {
-    fn default() -> Self {
-        DataApiSortDirection::Desc
-    }
-}
-
 /// A single row from the `/positions` endpoint.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DataPosition {
@@ -1320,24 +2789,25 @@ pub struct DataPosition {
     pub asset: String,
     #[serde(rename = "conditionId")]
     pub condition_id: String,
+    #[serde(with = "crate::serde_decimal")]
     pub size: Decimal,
-    #[serde(rename = "avgPrice")]
+    #[serde(rename = "avgPrice", with = "crate::serde_decimal")]
     pub avg_price: Decimal,
-    #[serde(rename = "initialValue")]
+    #[serde(rename = "initialValue", with = "crate::serde_decimal")]
     pub initial_value: Decimal,
-    #[serde(rename = "currentValue")]
+    #[serde(rename = "currentValue", with = "crate::serde_decimal")]
     pub current_value: Decimal,
-    #[serde(rename = "cashPnl")]
+    #[serde(rename = "cashPnl", with = "crate::serde_decimal")]
     pub cash_pnl: Decimal,
-    #[serde(rename = "percentPnl")]
+    #[serde(rename = "percentPnl", with = "crate::serde_decimal")]
     pub percent_pnl: Decimal,
-    #[serde(rename = "totalBought")]
+    #[serde(rename = "totalBought", with = "crate::serde_decimal")]
     pub total_bought: Decimal,
-    #[serde(rename = "realizedPnl")]
+    #[serde(rename = "realizedPnl", with = "crate::serde_decimal")]
     pub realized_pnl: Decimal,
-    #[serde(rename = "percentRealizedPnl")]
+    #[serde(rename = "percentRealizedPnl", with = "crate::serde_decimal")]
     pub percent_realized_pnl: Decimal,
-    #[serde(rename = "curPrice")]
+    #[serde(rename = "curPrice", with = "crate::serde_decimal")]
     pub cur_price: Decimal,
     pub redeemable: bool,
     pub mergeable: bool,
@@ -1361,13 +2831,159 @@ pub struct DataPosition {
     pub negative_risk: Option<bool>,
 }
 
+/// A Data API position marked at a live price
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionValue {
+    #[serde(flatten)]
+    pub position: DataPosition,
+    #[serde(rename = "currentPrice")]
+    pub current_price: Decimal,
+    #[serde(rename = "markToMarket")]
+    pub mark_to_market: Decimal,
+}
+
+impl PositionValue {
+    /// Value `position` at `current_price`
+    pub fn new(position: DataPosition, current_price: Decimal) -> Self {
+        let mark_to_market = position.size * current_price;
+        Self {
+            position,
+            current_price,
+            mark_to_market,
+        }
+    }
+
+    /// What the position cost to open (`size * avgPrice`)
+    pub fn cost_basis(&self) -> Decimal {
+        self.position.size * self.position.avg_price
+    }
+
+    pub fn unrealized_pnl(&self) -> Decimal {
+        self.mark_to_market - self.cost_basis()
+    }
+}
+
+/// Totals across a wallet's marked positions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortfolioValue {
+    pub positions: Vec<PositionValue>,
+    pub total_mark_to_market: Decimal,
+    pub total_cost_basis: Decimal,
+    pub total_unrealized_pnl: Decimal,
+    pub total_realized_pnl: Decimal,
+}
+
+impl PortfolioValue {
+    pub fn from_positions(positions: Vec<PositionValue>) -> Self {
+        let total_mark_to_market = positions.iter().map(|p| p.mark_to_market).sum();
+        let total_cost_basis = positions.iter().map(PositionValue::cost_basis).sum();
+        let total_realized_pnl = positions.iter().map(|p| p.position.realized_pnl).sum();
+        Self {
+            positions,
+            total_mark_to_market,
+            total_cost_basis,
+            total_unrealized_pnl: total_mark_to_market - total_cost_basis,
+            total_realized_pnl,
+        }
+    }
+}
+
+/// How a position moved between two snapshots
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PositionChangeDirection {
+    Increasing,
+    Decreasing,
+    /// From nothing to a holding
+    Opened,
+    /// From a holding to nothing
+    Closed,
+}
+
+/// A change in one token's position size, as emitted by
+/// [`ClobClient::subscribe_position_updates`](crate::client::ClobClient::subscribe_position_updates)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PositionUpdate {
+    pub token_id: String,
+    /// Size in the earlier snapshot; `None` when the token wasn't held
+    pub previous_size: Option<Decimal>,
+    /// Size now; zero once closed
+    pub current_size: Decimal,
+    /// `current_size` minus the previous size
+    pub change: Decimal,
+    pub direction: PositionChangeDirection,
+}
+
+impl PositionUpdate {
+    /// Every token whose size differs between `previous` and `current`, by token ID
+    ///
+    /// Positions of size zero count as not held.
+    pub fn diff(previous: &[DataPosition], current: &[DataPosition]) -> Vec<Self> {
+        let sizes = |positions: &[DataPosition]| {
+            positions
+                .iter()
+                .filter(|position| !position.size.is_zero())
+                .map(|position| (position.asset.clone(), position.size))
+                .collect::<std::collections::BTreeMap<_, _>>()
+        };
+        let (before, after) = (sizes(previous), sizes(current));
+
+        let mut tokens: Vec<&String> = before.keys().chain(after.keys()).collect();
+        tokens.sort();
+        tokens.dedup();
+        tokens
+            .into_iter()
+            .filter_map(|token_id| {
+                let previous_size = before.get(token_id).copied();
+                let current_size = after.get(token_id).copied().unwrap_or_default();
+                let change = current_size - previous_size.unwrap_or_default();
+                let direction = match (previous_size, after.contains_key(token_id)) {
+                    (None, true) => PositionChangeDirection::Opened,
+                    (Some(_), false) => PositionChangeDirection::Closed,
+                    _ if change > Decimal::ZERO => PositionChangeDirection::Increasing,
+                    _ if change < Decimal::ZERO => PositionChangeDirection::Decreasing,
+                    _ => return None,
+                };
+                Some(Self {
+                    token_id: token_id.clone(),
+                    previous_size,
+                    current_size,
+                    change,
+                    direction,
+                })
+            })
+            .collect()
+    }
+}
+
 /// Response returned by the `/value` endpoint.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DataPositionValue {
     pub user: String,
+    #[serde(with = "crate::serde_decimal")]
     pub value: Decimal,
 }
 
+/// A single row from the `/trades` endpoint: one fill in a market
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DataTrade {
+    #[serde(rename = "proxyWallet", default)]
+    pub proxy_wallet: Option<String>,
+    /// Side of `proxy_wallet` in the fill
+    pub side: Side,
+    /// Token ID that traded
+    pub asset: String,
+    #[serde(rename = "conditionId")]
+    pub condition_id: String,
+    #[serde(with = "crate::serde_decimal")]
+    pub size: Decimal,
+    #[serde(with = "crate::serde_decimal")]
+    pub price: Decimal,
+    /// Unix time in seconds
+    pub timestamp: i64,
+    #[serde(rename = "transactionHash", default)]
+    pub transaction_hash: Option<String>,
+}
+
 /// Gamma API event metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GammaEvent {
@@ -1390,6 +3006,57 @@ pub struct GammaEvent {
     pub metadata: serde_json::Value,
 }
 
+/// Tone of a [`NewsArticle`] as labelled by the source
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NewsSentiment {
+    #[serde(alias = "Positive", alias = "POSITIVE")]
+    Positive,
+    #[serde(alias = "Negative", alias = "NEGATIVE")]
+    Negative,
+    #[serde(alias = "Neutral", alias = "NEUTRAL")]
+    Neutral,
+}
+
+impl NewsSentiment {
+    /// +1, -1 or 0
+    pub fn value(&self) -> Decimal {
+        match self {
+            NewsSentiment::Positive => Decimal::ONE,
+            NewsSentiment::Negative => Decimal::NEGATIVE_ONE,
+            NewsSentiment::Neutral => Decimal::ZERO,
+        }
+    }
+}
+
+/// News article Gamma links to a market
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewsArticle {
+    pub title: String,
+    pub url: String,
+    #[serde(default)]
+    pub source: String,
+    #[serde(rename = "publishedAt", alias = "published_at")]
+    pub published_at: DateTime<Utc>,
+    #[serde(default)]
+    pub sentiment: Option<NewsSentiment>,
+}
+
+/// Average sentiment of `articles`, counting positive as +1, negative as -1 and
+/// neutral as 0
+///
+/// Articles without a sentiment label are left out; `None` when none has one.
+pub fn news_sentiment_score(articles: &[NewsArticle]) -> Option<Decimal> {
+    let values: Vec<Decimal> = articles
+        .iter()
+        .filter_map(|article| article.sentiment.map(|sentiment| sentiment.value()))
+        .collect();
+    if values.is_empty() {
+        return None;
+    }
+    Some(values.iter().sum::<Decimal>() / Decimal::from(values.len()))
+}
+
 /// Lightweight market info returned inside a Gamma event listing.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GammaEventMarket {
@@ -1417,6 +3084,27 @@ pub struct Tag {
     pub metadata: serde_json::Value,
 }
 
+/// Entry in Gamma's market category taxonomy
+///
+/// Gamma's camelCase keys (`label`, `icon`, `marketCount`) are accepted on input; the
+/// fields always serialize in snake_case.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Category {
+    #[serde(deserialize_with = "crate::decode::deserializers::number_from_string")]
+    pub id: u32,
+    #[serde(alias = "label")]
+    pub name: String,
+    pub slug: String,
+    #[serde(default, alias = "icon", alias = "iconUrl", alias = "image")]
+    pub icon_url: Option<String>,
+    #[serde(
+        default,
+        alias = "marketCount",
+        deserialize_with = "crate::decode::deserializers::number_from_string"
+    )]
+    pub market_count: u32,
+}
+
 /// Sports metadata for Gamma API filtering
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Sport {
@@ -1441,6 +3129,13 @@ pub struct GammaMarket {
     pub question: Option<String>,
     pub description: Option<String>,
     pub category: Option<String>,
+    #[serde(
+        default,
+        rename = "categoryId",
+        alias = "category_id",
+        deserialize_with = "crate::decode::deserializers::optional_number_from_string"
+    )]
+    pub category_id: Option<u32>,
     pub active: bool,
     pub closed: bool,
     pub outcomes: Option<String>,
@@ -1450,62 +3145,166 @@ pub struct GammaMarket {
     #[serde(rename = "endDate")]
     pub end_date: Option<String>,
     pub liquidity: Option<String>,
-    #[serde(rename = "liquidityNum")]
+    #[serde(
+        rename = "liquidityNum",
+        default,
+        with = "crate::serde_decimal::option"
+    )]
     pub liquidity_num: Option<Decimal>,
     pub volume: Option<String>,
-    #[serde(rename = "volumeNum")]
+    #[serde(rename = "volumeNum", default, with = "crate::serde_decimal::option")]
     pub volume_num: Option<Decimal>,
-    #[serde(rename = "volume24hr")]
+    #[serde(rename = "volume24hr", default, with = "crate::serde_decimal::option")]
     pub volume_24hr: Option<Decimal>,
-    #[serde(rename = "volume1wk")]
+    #[serde(rename = "volume1wk", default, with = "crate::serde_decimal::option")]
     pub volume_1wk: Option<Decimal>,
-    #[serde(rename = "volume1mo")]
+    #[serde(rename = "volume1mo", default, with = "crate::serde_decimal::option")]
     pub volume_1mo: Option<Decimal>,
-    #[serde(rename = "volume1yr")]
+    #[serde(rename = "volume1yr", default, with = "crate::serde_decimal::option")]
     pub volume_1yr: Option<Decimal>,
-    #[serde(rename = "volume24hrAmm")]
+    #[serde(
+        rename = "volume24hrAmm",
+        default,
+        with = "crate::serde_decimal::option"
+    )]
     pub volume_24hr_amm: Option<Decimal>,
-    #[serde(rename = "volume1wkAmm")]
+    #[serde(
+        rename = "volume1wkAmm",
+        default,
+        with = "crate::serde_decimal::option"
+    )]
     pub volume_1wk_amm: Option<Decimal>,
-    #[serde(rename = "volume1moAmm")]
+    #[serde(
+        rename = "volume1moAmm",
+        default,
+        with = "crate::serde_decimal::option"
+    )]
     pub volume_1mo_amm: Option<Decimal>,
-    #[serde(rename = "volume1yrAmm")]
+    #[serde(
+        rename = "volume1yrAmm",
+        default,
+        with = "crate::serde_decimal::option"
+    )]
     pub volume_1yr_amm: Option<Decimal>,
-    #[serde(rename = "volume24hrClob")]
+    #[serde(
+        rename = "volume24hrClob",
+        default,
+        with = "crate::serde_decimal::option"
+    )]
     pub volume_24hr_clob: Option<Decimal>,
-    #[serde(rename = "volume1wkClob")]
+    #[serde(
+        rename = "volume1wkClob",
+        default,
+        with = "crate::serde_decimal::option"
+    )]
     pub volume_1wk_clob: Option<Decimal>,
-    #[serde(rename = "volume1moClob")]
+    #[serde(
+        rename = "volume1moClob",
+        default,
+        with = "crate::serde_decimal::option"
+    )]
     pub volume_1mo_clob: Option<Decimal>,
-    #[serde(rename = "volume1yrClob")]
+    #[serde(
+        rename = "volume1yrClob",
+        default,
+        with = "crate::serde_decimal::option"
+    )]
     pub volume_1yr_clob: Option<Decimal>,
-    #[serde(rename = "volumeAmm")]
+    #[serde(rename = "volumeAmm", default, with = "crate::serde_decimal::option")]
     pub volume_amm: Option<Decimal>,
-    #[serde(rename = "volumeClob")]
+    #[serde(rename = "volumeClob", default, with = "crate::serde_decimal::option")]
     pub volume_clob: Option<Decimal>,
-    #[serde(rename = "liquidityAmm")]
+    #[serde(
+        rename = "liquidityAmm",
+        default,
+        with = "crate::serde_decimal::option"
+    )]
     pub liquidity_amm: Option<Decimal>,
-    #[serde(rename = "liquidityClob")]
+    #[serde(
+        rename = "liquidityClob",
+        default,
+        with = "crate::serde_decimal::option"
+    )]
     pub liquidity_clob: Option<Decimal>,
-    #[serde(rename = "orderMinSize")]
+    #[serde(
+        rename = "orderMinSize",
+        default,
+        with = "crate::serde_decimal::option"
+    )]
     pub order_min_size: Option<Decimal>,
-    #[serde(rename = "orderPriceMinTickSize")]
+    #[serde(
+        rename = "orderPriceMinTickSize",
+        default,
+        with = "crate::serde_decimal::option"
+    )]
     pub order_tick_size: Option<Decimal>,
+    #[serde(rename = "negRisk", default)]
+    pub neg_risk: Option<bool>,
+    #[serde(rename = "negRiskMarketID", default)]
+    pub neg_risk_market_id: Option<String>,
+    #[serde(rename = "outcomePrices", default, with = "crate::serde_decimal::vec")]
+    pub outcome_prices: Vec<Decimal>,
+    #[serde(
+        rename = "gameStartTime",
+        default,
+        deserialize_with = "crate::decode::deserializers::optional_flexible_datetime"
+    )]
+    pub game_start_time: Option<DateTime<Utc>>,
+    #[serde(rename = "sportsMarketType", default)]
+    pub sports_market_type: Option<String>,
+    #[serde(rename = "gameId", default)]
+    pub game_id: Option<String>,
+    #[serde(default, with = "crate::serde_decimal::option")]
+    pub line: Option<Decimal>,
+    #[serde(default, with = "crate::serde_decimal::option")]
+    pub spread: Option<Decimal>,
+    #[serde(rename = "iconUrl", default)]
+    pub icon_url: Option<String>,
+    #[serde(rename = "bannerUrl", default)]
+    pub banner_url: Option<String>,
+    #[serde(rename = "categoryIconUrl", default)]
+    pub category_icon_url: Option<String>,
+    #[serde(rename = "resolutionSource", default)]
+    pub resolution_source: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "crate::decode::deserializers::string_list"
+    )]
+    pub tags: Vec<String>,
+    /// The events the market is listed under
+    #[serde(default)]
+    pub events: Vec<GammaMarketEvent>,
+}
+
+/// An event as Gamma nests it in a market, with only the fields needed to look it up
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GammaMarketEvent {
+    pub id: String,
+    #[serde(default)]
+    pub slug: Option<String>,
 }
 
 /// Rewards structure for markets
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Rewards {
     pub rates: Option<serde_json::Value>,
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::serde_decimal")]
     pub min_size: Decimal,
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::serde_decimal")]
     pub max_spread: Decimal,
     pub event_start_date: Option<String>,
     pub event_end_date: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::serde_decimal::option"
+    )]
     pub in_game_multiplier: Option<Decimal>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::serde_decimal::option"
+    )]
     pub reward_epoch: Option<Decimal>,
 }
 
@@ -1514,3 +3313,456 @@ pub type ClientResult<T> = anyhow::Result<T>;
 
 /// Result type used throughout the client
 pub type Result<T> = std::result::Result<T, crate::errors::PolyError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use std::collections::HashSet;
+
+    fn gamma_market(condition_id: &str, liquidity: f64) -> Market {
+        serde_json::from_value::<GammaMarket>(serde_json::json!({
+            "conditionId": condition_id,
+            "slug": "identity",
+            "active": true,
+            "closed": false,
+            "liquidityNum": liquidity
+        }))
+        .unwrap()
+        .into()
+    }
+
+    fn clob_market_json() -> serde_json::Value {
+        serde_json::json!({
+            "condition_id": "0x123",
+            "tokens": [
+                {"token_id": "1", "outcome": "Yes"},
+                {"token_id": "2", "outcome": "No"}
+            ],
+            "rewards": {"min_size": "20", "max_spread": "3.5"},
+            "active": true,
+            "closed": false,
+            "question_id": "0x123",
+            "description": "",
+            "question": "Will it rain?",
+            "market_slug": "rain",
+            "seconds_delay": "0",
+            "icon": "",
+            "fpmm": ""
+        })
+    }
+
+    #[test]
+    fn test_outcome_probabilities_normalize_three_outcomes() {
+        let over = [dec!(0.50), dec!(0.35), dec!(0.25)];
+        let probabilities = Market::outcome_probabilities(&over);
+        assert_eq!(
+            probabilities.iter().sum::<Decimal>().round_dp(20),
+            Decimal::ONE
+        );
+        assert_eq!(probabilities[0].round_dp(6), dec!(0.454545));
+        assert_eq!(probabilities[2].round_dp(6), dec!(0.227273));
+        assert_eq!(
+            Market::outcome_price_deviation(&over, dec!(0.02)),
+            Some(dec!(0.10))
+        );
+
+        let under = [dec!(0.40), dec!(0.30), dec!(0.20)];
+        let probabilities = Market::outcome_probabilities(&under);
+        assert_eq!(probabilities[1].round_dp(6), dec!(0.333333));
+        assert_eq!(
+            Market::outcome_price_deviation(&under, dec!(0.02)),
+            Some(dec!(-0.10))
+        );
+        assert_eq!(Market::outcome_price_deviation(&under, dec!(0.10)), None);
+
+        assert_eq!(
+            Market::outcome_probabilities(&[Decimal::ZERO; 3]),
+            [Decimal::ZERO; 3]
+        );
+    }
+
+    #[test]
+    fn test_market_order_constraints_with_and_without_fields() {
+        let mut raw = clob_market_json();
+        raw["minimum_order_size"] = "15".into();
+        raw["minimum_tick_size"] = "0.001".into();
+        raw["min_incentive_size"] = "50".into();
+        raw["max_incentive_spread"] = 3.5.into();
+        let market: Market = serde_json::from_value(raw).unwrap();
+        assert_eq!(market.minimum_order_size, dec!(15));
+        assert_eq!(market.minimum_tick_size, dec!(0.001));
+        assert_eq!(market.min_incentive_size, Some(dec!(50)));
+        assert_eq!(market.max_incentive_spread, Some(dec!(3.5)));
+
+        let mut raw = clob_market_json();
+        raw["minimum_tick_size"] = "0".into();
+        raw["max_incentive_spread"] = "".into();
+        let market: Market = serde_json::from_value(raw).unwrap();
+        assert_eq!(market.minimum_order_size, DEFAULT_MIN_ORDER_SIZE);
+        assert_eq!(market.minimum_tick_size, DEFAULT_MIN_TICK_SIZE);
+        assert_eq!(market.min_incentive_size, None);
+        assert_eq!(market.max_incentive_spread, None);
+
+        // Gamma omits both constraints for some markets as well
+        let gamma = gamma_market("0xaaa", 1.0);
+        assert_eq!(gamma.minimum_tick_size, dec!(0.01));
+        assert_eq!(gamma.minimum_order_size, dec!(5));
+    }
+
+    #[test]
+    fn test_market_schedule_parses_recurrence() {
+        let schedule: MarketSchedule = serde_json::from_value(serde_json::json!({
+            "recurrence": {"type": "weekly", "day": "Fri"},
+            "next_resolution": "1700222400",
+            "past_resolutions": [{
+                "resolution_date": 1699617600,
+                "winning_outcome": "Yes",
+                "settlement_price": "1",
+            }],
+        }))
+        .unwrap();
+
+        assert_eq!(
+            schedule.recurrence,
+            ScheduleRecurrence::Weekly {
+                day: chrono::Weekday::Fri
+            }
+        );
+        assert!(schedule.is_recurring());
+        assert_eq!(schedule.past_resolutions[0].winning_outcome, "Yes");
+        let now = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        assert_eq!(schedule.days_until_next_from(now), 2);
+
+        let one_time: ScheduleRecurrence =
+            serde_json::from_value(serde_json::json!({"type": "one_time"})).unwrap();
+        assert_eq!(one_time, ScheduleRecurrence::OneTime);
+        let monthly: ScheduleRecurrence =
+            serde_json::from_value(serde_json::json!({"type": "monthly", "day_of_month": 15}))
+                .unwrap();
+        assert_eq!(monthly, ScheduleRecurrence::Monthly { day_of_month: 15 });
+    }
+
+    #[test]
+    fn test_truncate_to_depth_merges_duplicate_prices() {
+        let level = |price, size| OrderSummary { price, size };
+        let mut book = OrderBookSummary {
+            market: "0x123".to_string(),
+            asset_id: "1".to_string(),
+            hash: String::new(),
+            timestamp: 0,
+            bids: vec![
+                level(dec!(0.71), dec!(5)),
+                level(dec!(0.72), dec!(10)),
+                level(dec!(0.70), dec!(1)),
+                level(dec!(0.72), dec!(20)),
+            ],
+            asks: vec![level(dec!(0.75), dec!(3)), level(dec!(0.75), dec!(4))],
+            min_order_size: None,
+            tick_size: None,
+            neg_risk: None,
+            last_trade_price: None,
+            truncated: false,
+        };
+
+        let levels = |levels: &[OrderSummary]| -> Vec<(Decimal, Decimal)> {
+            levels.iter().map(|l| (l.price, l.size)).collect()
+        };
+
+        book.truncate_to_depth(2);
+        assert!(book.truncated);
+        assert_eq!(
+            levels(&book.bids),
+            [(dec!(0.72), dec!(30)), (dec!(0.71), dec!(5))]
+        );
+        assert_eq!(levels(&book.asks), [(dec!(0.75), dec!(7))]);
+
+        book.truncated = false;
+        book.truncate_to_depth(2);
+        assert!(!book.truncated);
+    }
+
+    #[test]
+    fn test_asset_ids_for_dedupes_in_order() {
+        let market = |condition_id: &str, tokens: &str| -> Market {
+            serde_json::from_value::<GammaMarket>(serde_json::json!({
+                "conditionId": condition_id,
+                "slug": condition_id,
+                "active": true,
+                "closed": false,
+                "clobTokenIds": tokens,
+            }))
+            .unwrap()
+            .into()
+        };
+        let markets = [
+            market("0xa", r#"["3", "1"]"#),
+            market("0xb", r#"["1", "2"]"#),
+            market("0xc", "[]"),
+            market("0xd", r#"["2", "4", "3"]"#),
+        ];
+        assert_eq!(asset_ids_for(&markets), ["3", "1", "2", "4"]);
+        assert!(asset_ids_for(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_gamma_rich_metadata() {
+        let market: Market = serde_json::from_value::<GammaMarket>(serde_json::json!({
+            "conditionId": "0xaaa",
+            "slug": "metadata",
+            "active": true,
+            "closed": false,
+            "iconUrl": "https://img.example.com/icon.png",
+            "bannerUrl": "https://img.example.com/banner.png",
+            "categoryIconUrl": "https://img.example.com/politics.png",
+            "resolutionSource": "https://source.example.com/a",
+            "tags": [{"id": "2", "label": "Politics"}, "Elections"]
+        }))
+        .unwrap()
+        .into();
+        assert!(market.has_icon());
+        assert_eq!(
+            market.banner_url.as_deref(),
+            Some("https://img.example.com/banner.png")
+        );
+        assert_eq!(
+            market.category_icon_url.as_deref(),
+            Some("https://img.example.com/politics.png")
+        );
+        assert_eq!(
+            market.primary_source_url(),
+            Some("https://source.example.com/a")
+        );
+        assert_eq!(market.tags, ["Politics", "Elections"]);
+
+        let market: Market = serde_json::from_value::<GammaMarket>(serde_json::json!({
+            "conditionId": "0xaaa",
+            "slug": "metadata",
+            "active": true,
+            "closed": false,
+            "icon": "https://img.example.com/legacy.png",
+            "resolutionSource": "",
+            "tags": null
+        }))
+        .unwrap()
+        .into();
+        assert_eq!(
+            market.icon_url.as_deref(),
+            Some("https://img.example.com/legacy.png")
+        );
+        assert_eq!(market.primary_source_url(), None);
+        assert!(market.tags.is_empty());
+
+        // CLOB payloads carry none of these fields
+        let market: Market = serde_json::from_value(clob_market_json()).unwrap();
+        assert!(!market.has_icon());
+        assert_eq!(market.primary_source_url(), None);
+    }
+
+    #[test]
+    fn test_settlement_payout_fraction() {
+        let tx: SettlementTx = serde_json::from_value(serde_json::json!({
+            "tx_hash": "0xabc",
+            "block_number": "61234567",
+            "timestamp": 1717200000,
+            "winning_condition_index": 0,
+            "payout_numerators": [1, 0],
+            "payout_denominator": 1
+        }))
+        .unwrap();
+        assert_eq!(tx.block_number, 61_234_567);
+        assert_eq!(tx.winning_payout_fraction(0), Some(Decimal::ONE));
+        assert_eq!(tx.winning_payout_fraction(1), Some(Decimal::ZERO));
+        assert_eq!(tx.winning_payout_fraction(2), None);
+
+        let split = SettlementTx {
+            payout_numerators: vec![1, 3],
+            payout_denominator: 4,
+            ..tx.clone()
+        };
+        assert_eq!(split.winning_payout_fraction(1), Some(dec!(0.75)));
+        let broken = SettlementTx {
+            payout_denominator: 0,
+            ..tx
+        };
+        assert_eq!(broken.winning_payout_fraction(0), None);
+    }
+
+    #[test]
+    fn test_neg_risk_complement() {
+        let gamma = |condition_id: &str, group: &str| -> Market {
+            serde_json::from_value::<GammaMarket>(serde_json::json!({
+                "conditionId": condition_id,
+                "slug": "group",
+                "active": true,
+                "closed": false,
+                "negRisk": !group.is_empty(),
+                "negRiskMarketID": group
+            }))
+            .unwrap()
+            .into()
+        };
+
+        let pair = gamma("0xaaa", "0xgroup");
+        assert!(pair.is_neg_risk());
+        assert_eq!(pair.neg_risk_market_id.as_deref(), Some("0xgroup"));
+        let group = [
+            pair.clone(),
+            gamma("0xbbb", "0xgroup"),
+            gamma("0xddd", "0xother"),
+        ];
+        assert_eq!(pair.complement_condition_id(&group), Some("0xbbb"));
+
+        let triple = [
+            group[0].clone(),
+            group[1].clone(),
+            gamma("0xccc", "0xgroup"),
+        ];
+        assert_eq!(pair.complement_condition_id(&triple), None);
+
+        let plain = gamma("0xaaa", "");
+        assert!(!plain.is_neg_risk());
+        assert_eq!(plain.neg_risk_market_id, None);
+        assert_eq!(plain.complement_condition_id(&group), None);
+
+        let market: Market = serde_json::from_value(clob_market_json()).unwrap();
+        assert!(!market.is_neg_risk());
+    }
+
+    #[test]
+    fn test_market_display() {
+        let mut market: Market = serde_json::from_value::<GammaMarket>(serde_json::json!({
+            "conditionId": "0xaaa",
+            "question": "Will it rain?",
+            "slug": "rain",
+            "active": true,
+            "closed": false,
+            "endDate": "2024-11-05T00:00:00Z",
+            "liquidityNum": 1234.6,
+            "outcomes": "[\"Yes\", \"No\"]",
+            "clobTokenIds": "[\"1\", \"2\"]"
+        }))
+        .unwrap()
+        .into();
+
+        assert_eq!(
+            market.to_string(),
+            "0xaaa | Will it rain? | liq=$1235 | ends 2024-11-05T00:00:00Z | active"
+        );
+        assert_eq!(market.tokens[0].to_string(), "Yes [1]");
+        let rich = market.to_rich_string();
+        assert!(rich.starts_with("Will it rain?\n  condition: 0xaaa"));
+        assert!(rich.ends_with("outcomes:\n    Yes [1]\n    No [2]"));
+
+        market.question.clear();
+        market.liquidity_num = None;
+        market.end_date_iso = None;
+        market.closed = true;
+        assert_eq!(
+            market.to_display_string(),
+            "0xaaa | - | liq=- | ends - | closed"
+        );
+        assert_eq!(OrderStatus::Partial.to_string(), "PARTIAL");
+        assert_eq!(format!("{} {}", Side::BUY, OrderType::FOK), "BUY FOK");
+    }
+
+    #[test]
+    fn test_markets_response_has_more() {
+        let full: MarketsResponse = serde_json::from_value(serde_json::json!({
+            "limit": 2,
+            "count": 2,
+            "next_cursor": "Mg==",
+            "data": [],
+        }))
+        .unwrap();
+        assert!(full.has_more());
+        assert_eq!(full.offset, None);
+
+        let last: SimplifiedMarketsResponse = serde_json::from_value(serde_json::json!({
+            "limit": "2",
+            "count": "1",
+            "next_cursor": END_CURSOR,
+            "offset": 2,
+            "data": [],
+        }))
+        .unwrap();
+        assert!(!last.has_more());
+        assert_eq!(last.offset, Some(2));
+    }
+
+    #[test]
+    fn test_market_identity_ignores_mutable_fields() {
+        let earlier = gamma_market("0xaaa", 1000.0);
+        let later = gamma_market("0xaaa", 2500.0);
+        let other = gamma_market("0xbbb", 1000.0);
+
+        assert_eq!(earlier, later);
+        assert!(earlier.same_market(&later));
+        assert_ne!(earlier, other);
+
+        assert!(earlier.is_stale_version_of(&later));
+        assert!(!earlier.is_stale_version_of(&earlier.clone()));
+        assert!(!earlier.is_stale_version_of(&other));
+
+        let markets: HashSet<Market> = [earlier, later, other].into_iter().collect();
+        assert_eq!(markets.len(), 2);
+    }
+
+    #[test]
+    fn test_gamma_volume_query_keys() {
+        let params = GammaListParams {
+            liquidity_num_min: Some(dec!(5000)),
+            volume_num_min: Some(dec!(10000)),
+            volume_num_max: Some(dec!(2500000.5)),
+            volume_24h_min: Some(dec!(750)),
+            ..Default::default()
+        };
+
+        let query = params.to_query_params();
+        for (key, value) in [
+            ("liquidity_num_min", "5000"),
+            ("volumeNumMin", "10000"),
+            ("volumeNumMax", "2500000.5"),
+            ("volume24hMin", "750"),
+        ] {
+            assert!(
+                query.contains(&(key, value.to_string())),
+                "missing {}={} in {:?}",
+                key,
+                value,
+                query
+            );
+        }
+    }
+
+    #[test]
+    fn test_gamma_volume_bounds_are_optional() {
+        let params = GammaListParams {
+            volume_num_max: Some(dec!(1)),
+            ..Default::default()
+        };
+        assert_eq!(
+            params.to_query_params(),
+            vec![("volumeNumMax", "1".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_gamma_multi_value_query_string() {
+        let params = GammaListParams {
+            limit: Some(2),
+            ids: vec!["12".to_string(), "34".to_string()],
+            slugs: vec!["will-it-rain".to_string()],
+            clob_token_ids: vec!["111".to_string(), "222".to_string()],
+            ..Default::default()
+        };
+
+        let mut url = reqwest::Url::parse("https://gamma.example.com/markets").unwrap();
+        url.query_pairs_mut().extend_pairs(params.to_query_params());
+        assert_eq!(
+            url.query(),
+            Some("limit=2&id=12&id=34&slug=will-it-rain&clob_token_ids=111&clob_token_ids=222")
+        );
+        assert!(GammaListParams::default().to_query_params().is_empty());
+    }
+}
@@ -106,6 +106,24 @@ pub mod crypto {
         Ok(URL_SAFE.encode(result.into_bytes()))
     }
 
+    /// Check the HMAC-SHA256 `signature` of a webhook delivery
+    ///
+    /// `signature` is the hex digest of `payload` keyed with the raw bytes of the
+    /// shared secret, optionally prefixed `sha256=`. The comparison runs
+    /// in constant time; a malformed signature is simply not valid.
+    pub fn verify_webhook_signature(payload: &[u8], signature: &str, secret: &str) -> bool {
+        let signature = signature.trim();
+        let hex_digest = signature.strip_prefix("sha256=").unwrap_or(signature);
+        let Ok(expected) = alloy_primitives::hex::decode(hex_digest) else {
+            return false;
+        };
+        let Ok(mut mac) = HmacSha256::new_from_slice(secret.as_bytes()) else {
+            return false;
+        };
+        mac.update(payload);
+        mac.verify_slice(&expected).is_ok()
+    }
+
     /// Generate a secure random nonce
     pub fn generate_nonce() -> U256 {
         use rand::RngCore;
@@ -393,7 +411,7 @@ pub mod retry {
         Err(last_error.unwrap_or_else(|| {
             PolyError::internal(
                 "Retry loop failed",
-                std::io::Error::new(std::io::ErrorKind::Other, "No error captured"),
+                std::io::Error::other("No error captured"),
             )
         }))
     }
@@ -510,6 +528,26 @@ pub mod rate_limit {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_verify_webhook_signature() {
+        use crypto::verify_webhook_signature;
+
+        let payload = br#"{"event":"ORDER_FILLED","order_id":"0x1"}"#;
+        let mut mac = HmacSha256::new_from_slice(b"whsec").unwrap();
+        mac.update(payload);
+        let digest = alloy_primitives::hex::encode(mac.finalize().into_bytes());
+
+        assert!(verify_webhook_signature(payload, &digest, "whsec"));
+        assert!(verify_webhook_signature(
+            payload,
+            &format!("sha256={}", digest),
+            "whsec"
+        ));
+        assert!(!verify_webhook_signature(payload, &digest, "other"));
+        assert!(!verify_webhook_signature(b"{}", &digest, "whsec"));
+        assert!(!verify_webhook_signature(payload, "not hex", "whsec"));
+    }
+
     #[test]
     fn test_round_to_tick() {
         use math::round_to_tick;
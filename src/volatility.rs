@@ -0,0 +1,192 @@
+//! Realized volatility from a token's price history
+//!
+//! [`VolatilityMetrics::from_history`] takes log returns between consecutive price
+//! points and scales their standard deviation by how many sampling intervals fit in a
+//! day and in a year. The interval is read from the timestamps rather than assumed, so
+//! a history served at coarser fidelity than requested is still annualized correctly.
+//! Prediction markets trade around the clock, so a year is 365 full days.
+//!
+//! Prices are probabilities, which makes these figures large next to equities: a
+//! token drifting between 0.40 and 0.60 over a week routinely annualizes above 100%.
+
+use crate::errors::{PolyError, Result};
+use rust_decimal::{Decimal, MathematicalOps};
+use serde::{Deserialize, Serialize};
+
+/// Annualized volatility above which [`VolatilityMetrics::is_high_vol`] is true
+pub const DEFAULT_HIGH_VOL_THRESHOLD: Decimal = Decimal::TWO;
+
+const SECONDS_PER_DAY: i64 = 86_400;
+const DAYS_PER_YEAR: i64 = 365;
+
+/// One point of `GET /prices-history`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PricePoint {
+    /// Unix time in seconds
+    #[serde(rename = "t")]
+    pub timestamp: i64,
+    #[serde(rename = "p", with = "crate::serde_decimal")]
+    pub price: Decimal,
+}
+
+/// Summary statistics of a token's returns over a lookback window
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VolatilityMetrics {
+    /// Standard deviation of log returns, annualized
+    pub realized_vol_ann: Decimal,
+    /// Standard deviation of log returns, scaled to one day
+    pub realized_vol_daily: Decimal,
+    /// Mean log return per sampling interval
+    pub mean_return: Decimal,
+    /// Largest peak-to-trough fall, as a fraction of the peak
+    pub max_drawdown: Decimal,
+    /// Annualized mean return over annualized volatility, with no risk-free rate; zero
+    /// when the price never moved
+    pub sharpe_ratio: Decimal,
+    pub lookback_hours: u32,
+    /// Returns the statistics were computed from
+    pub num_observations: u32,
+}
+
+impl VolatilityMetrics {
+    /// Compute the metrics from `history`, in any order
+    ///
+    /// Points at a non-positive price are skipped, as are repeats of a timestamp. At
+    /// least three usable points (two returns) are needed.
+    pub fn from_history(history: &[PricePoint], lookback_hours: u32) -> Result<Self> {
+        let mut points: Vec<PricePoint> = history
+            .iter()
+            .copied()
+            .filter(|point| point.price > Decimal::ZERO)
+            .collect();
+        points.sort_by_key(|point| point.timestamp);
+        points.dedup_by_key(|point| point.timestamp);
+        if points.len() < 3 {
+            return Err(PolyError::validation(format!(
+                "Need at least 3 price points for volatility, got {}",
+                points.len()
+            )));
+        }
+
+        let returns: Vec<Decimal> = points
+            .windows(2)
+            .map(|pair| {
+                (pair[1].price / pair[0].price)
+                    .checked_ln()
+                    .ok_or_else(|| PolyError::internal_simple("log return out of range"))
+            })
+            .collect::<Result<_>>()?;
+        let n = Decimal::from(returns.len());
+        let mean_return = returns.iter().sum::<Decimal>() / n;
+        let variance = returns
+            .iter()
+            .map(|r| (*r - mean_return) * (*r - mean_return))
+            .sum::<Decimal>()
+            / (n - Decimal::ONE);
+        let std_dev = sqrt(variance)?;
+
+        // Mean sampling interval, from the span the returns cover
+        let span = points[points.len() - 1].timestamp - points[0].timestamp;
+        let interval = Decimal::from(span) / n;
+        let per_day = sqrt(Decimal::from(SECONDS_PER_DAY) / interval)?;
+        let per_year = per_day * sqrt(Decimal::from(DAYS_PER_YEAR))?;
+
+        let realized_vol_ann = std_dev * per_year;
+        let sharpe_ratio = if std_dev.is_zero() {
+            Decimal::ZERO
+        } else {
+            mean_return / std_dev * per_year
+        };
+
+        Ok(Self {
+            realized_vol_ann,
+            realized_vol_daily: std_dev * per_day,
+            mean_return,
+            max_drawdown: max_drawdown(&points),
+            sharpe_ratio,
+            lookback_hours,
+            num_observations: returns.len() as u32,
+        })
+    }
+
+    /// Whether annualized volatility exceeds [`DEFAULT_HIGH_VOL_THRESHOLD`]
+    pub fn is_high_vol(&self) -> bool {
+        self.exceeds(DEFAULT_HIGH_VOL_THRESHOLD)
+    }
+
+    /// Whether annualized volatility exceeds `threshold`, for markets that warrant a
+    /// different cut-off than the default
+    pub fn exceeds(&self, threshold: Decimal) -> bool {
+        self.realized_vol_ann > threshold
+    }
+}
+
+fn sqrt(value: Decimal) -> Result<Decimal> {
+    value
+        .sqrt()
+        .ok_or_else(|| PolyError::internal_simple(format!("sqrt of {} out of range", value)))
+}
+
+fn max_drawdown(points: &[PricePoint]) -> Decimal {
+    let mut peak = Decimal::ZERO;
+    let mut worst = Decimal::ZERO;
+    for point in points {
+        peak = peak.max(point.price);
+        worst = worst.max((peak - point.price) / peak);
+    }
+    worst
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn history(prices: &[Decimal]) -> Vec<PricePoint> {
+        prices
+            .iter()
+            .enumerate()
+            .map(|(i, price)| PricePoint {
+                timestamp: 1_700_000_000 + 60 * i as i64,
+                price: *price,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_alternating_prices() {
+        // Returns alternate +ln(1.1), -ln(1.1): mean zero, std ln(1.1) * sqrt(4/3)
+        let prices = [dec!(0.50), dec!(0.55), dec!(0.50), dec!(0.55), dec!(0.50)];
+        let metrics = VolatilityMetrics::from_history(&history(&prices), 1).unwrap();
+
+        assert_eq!(metrics.num_observations, 4);
+        assert_eq!(metrics.mean_return.round_dp(12), Decimal::ZERO);
+        let per_minute = dec!(1.1).ln() * dec!(1.333333333333333).sqrt().unwrap();
+        assert_eq!(
+            metrics.realized_vol_daily.round_dp(6),
+            (per_minute * dec!(1440).sqrt().unwrap()).round_dp(6)
+        );
+        assert_eq!(
+            metrics.realized_vol_ann.round_dp(6),
+            (metrics.realized_vol_daily * dec!(365).sqrt().unwrap()).round_dp(6)
+        );
+        assert_eq!(metrics.max_drawdown.round_dp(6), dec!(0.090909));
+        assert!(metrics.is_high_vol());
+        assert!(!metrics.exceeds(dec!(100)));
+    }
+
+    #[test]
+    fn test_flat_and_short_histories() {
+        let flat = VolatilityMetrics::from_history(&history(&[dec!(0.3); 4]), 1).unwrap();
+        assert_eq!(flat.realized_vol_ann, Decimal::ZERO);
+        assert_eq!(flat.sharpe_ratio, Decimal::ZERO);
+        assert!(!flat.is_high_vol());
+
+        // Zero prices are dropped, leaving too few points
+        let sparse = history(&[dec!(0.3), dec!(0), dec!(0.4)]);
+        assert!(matches!(
+            VolatilityMetrics::from_history(&sparse, 1),
+            Err(PolyError::Validation { .. })
+        ));
+    }
+}
@@ -5,29 +5,90 @@
 //! reconnecting connection, replays the most recent market/asset subscriptions,
 //! and exposes typed events for books, price changes, tick size changes, and
 //! last trade notifications.
+//!
+//! [`WssClient`] multiplexes the market and user channels behind a single
+//! `next_event` call for consumers that want both feeds.
 
 use crate::errors::{PolyError, Result};
-use crate::types::{ApiCredentials, OrderSummary, Side};
+use crate::market_meta::MarketMetaCache;
+use crate::telemetry;
+use crate::types::{ApiCredentials, OrderSummary, PriceChange, Side};
 use chrono::{DateTime, Utc};
-use futures::{SinkExt, StreamExt};
-use serde::Deserialize;
+use futures::{SinkExt, Stream, StreamExt};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
 use serde_json::{Value, json};
-use std::collections::VecDeque;
-use std::time::Duration;
+use std::borrow::Cow;
+use std::collections::{HashSet, VecDeque};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::net::TcpStream;
 use tokio::time::{sleep, timeout};
 use tokio_tungstenite::{
     MaybeTlsStream, WebSocketStream, connect_async, tungstenite::protocol::Message,
 };
-use tracing::warn;
+use tracing::{info, warn};
 
-const DEFAULT_WSS_BASE: &str = "wss://ws-subscriptions-clob.polymarket.com";
+pub(crate) const DEFAULT_WSS_BASE: &str = "wss://ws-subscriptions-clob.polymarket.com";
 const MARKET_CHANNEL_PATH: &str = "/ws/market";
 const USER_CHANNEL_PATH: &str = "/ws/user";
 const BASE_RECONNECT_DELAY: Duration = Duration::from_millis(250);
 const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(10);
 const MAX_RECONNECT_ATTEMPTS: u32 = 8;
 const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(25);
+/// Events from earlier frames [`WssMarketClient`] holds for its consumer unless
+/// [`with_buffer_capacity`](WssMarketClient::with_buffer_capacity) says otherwise; the
+/// frame decoded last is always kept whole on top
+pub const DEFAULT_EVENT_BUFFER_CAPACITY: usize = 1024;
+/// Suggested wait for [`WssMarketClient::subscribe_with_ack`]
+pub const DEFAULT_SUBSCRIBE_ACK_TIMEOUT: Duration = Duration::from_secs(5);
+const DEFAULT_RTDS_BASE: &str = "wss://ws-live-data.polymarket.com";
+/// RTDS drops connections that stay quiet for longer than ~10s
+const ACTIVITY_KEEPALIVE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Open a socket to `url`, retrying with linear backoff up to [`MAX_RECONNECT_ATTEMPTS`]
+/// times
+///
+/// Each failed attempt counts as an error in `stats`, and a connection that needed
+/// retries counts as a reconnect of `channel`.
+async fn dial_with_backoff(
+    url: &str,
+    channel: &'static str,
+    stats: &mut WssStats,
+) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>> {
+    let mut attempts = 0;
+    loop {
+        match connect_async(url).await {
+            Ok((socket, _)) => {
+                if attempts > 0 {
+                    stats.reconnect_count += 1;
+                    telemetry::wss_reconnect(channel);
+                }
+                return Ok(socket);
+            }
+            Err(err) => {
+                attempts += 1;
+                stats.errors += 1;
+                if attempts >= MAX_RECONNECT_ATTEMPTS {
+                    return Err(PolyError::stream(
+                        format!("Failed to connect after {} attempts: {}", attempts, err),
+                        crate::errors::StreamErrorKind::ConnectionFailed,
+                    ));
+                }
+                let millis = BASE_RECONNECT_DELAY.as_millis() * attempts as u128;
+                sleep(Duration::from_millis(
+                    millis.min(MAX_RECONNECT_DELAY.as_millis()) as u64,
+                ))
+                .await;
+            }
+        }
+    }
+}
 
 /// Represents a parsed market broadcast from the public market channel.
 #[derive(Debug, Clone)]
@@ -36,6 +97,42 @@ pub enum WssMarketEvent {
     PriceChange(PriceChangeMessage),
     TickSizeChange(TickSizeChangeMessage),
     LastTrade(LastTradeMessage),
+    /// The server refused (part of) the subscription
+    SubscriptionError(SubscriptionError),
+    /// The client's event buffer was full and its oldest events were discarded
+    ///
+    /// Raised by the client, not the server, right before the first event that
+    /// survived. Only events carried over from earlier frames are dropped, never part
+    /// of the frame just decoded: raise [`WssMarketClient::with_buffer_capacity`] if
+    /// this shows up, and treat any locally maintained book as stale until the next
+    /// snapshot.
+    BufferOverflow {
+        dropped_count: u64,
+    },
+}
+
+/// Data event types of the market channel, for [`WssMarketClient::only_events`]
+///
+/// Subscription errors and buffer overflows aren't listed: they are always delivered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventKind {
+    Book,
+    PriceChange,
+    TickSizeChange,
+    LastTrade,
+}
+
+impl EventKind {
+    /// The kind for a wire `event_type`; `None` for errors and unknown types
+    pub fn from_event_type(event_type: &str) -> Option<Self> {
+        match event_type {
+            "book" => Some(EventKind::Book),
+            "price_change" => Some(EventKind::PriceChange),
+            "tick_size_change" => Some(EventKind::TickSizeChange),
+            "last_trade_price" => Some(EventKind::LastTrade),
+            _ => None,
+        }
+    }
 }
 
 /// Events emitted by the authenticated user channel.
@@ -45,6 +142,264 @@ pub enum WssUserEvent {
     Order(WssUserOrderMessage),
 }
 
+/// An event from either channel, as returned by [`WssClient::next_event`].
+// Unboxed so callers can match nested variants directly; events are moved once per frame
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone)]
+pub enum WssEvent {
+    Market(WssMarketEvent),
+    User(WssUserEvent),
+}
+
+/// Server and arrival times of a websocket event.
+///
+/// Servers send `timestamp` as an epoch string (milliseconds on the CLOB channels);
+/// [`server_time`](Self::server_time) decodes it so callers never compare raw millis
+/// against a clock in seconds. A missing or unparseable timestamp is `None`.
+pub trait EventTiming {
+    /// The `timestamp` field as the server sent it
+    fn raw_timestamp(&self) -> &str;
+
+    /// When the client read the frame; `None` for events that didn't come off a socket
+    fn received_at(&self) -> Option<SystemTime>;
+
+    fn server_time(&self) -> Option<DateTime<Utc>> {
+        crate::decode::deserializers::parse_epoch_timestamp(self.raw_timestamp())
+    }
+
+    /// Delay between the server stamping the event and the client receiving it.
+    ///
+    /// Negative when the local clock runs behind the server's.
+    fn latency(&self) -> Option<chrono::Duration> {
+        let received: DateTime<Utc> = self.received_at()?.into();
+        Some(received - self.server_time()?)
+    }
+}
+
+macro_rules! impl_event_timing {
+    ($($message:ty),* $(,)?) => {
+        $(impl EventTiming for $message {
+            fn raw_timestamp(&self) -> &str {
+                &self.timestamp
+            }
+
+            fn received_at(&self) -> Option<SystemTime> {
+                self.received_at
+            }
+        })*
+    };
+}
+
+impl_event_timing!(
+    WssUserTradeMessage,
+    WssUserOrderMessage,
+    MarketBook,
+    PriceChangeMessage,
+    TickSizeChangeMessage,
+    LastTradeMessage,
+    SubscriptionError,
+);
+
+impl WssMarketEvent {
+    /// `None` for buffer overflows, which have no server time
+    fn timing(&self) -> Option<&dyn EventTiming> {
+        match self {
+            WssMarketEvent::Book(book) => Some(book),
+            WssMarketEvent::PriceChange(change) => Some(change),
+            WssMarketEvent::TickSizeChange(change) => Some(change),
+            WssMarketEvent::LastTrade(trade) => Some(trade),
+            WssMarketEvent::SubscriptionError(error) => Some(error),
+            WssMarketEvent::BufferOverflow { .. } => None,
+        }
+    }
+
+    pub(crate) fn stamp_received(&mut self, at: SystemTime) {
+        let received_at = match self {
+            WssMarketEvent::Book(book) => &mut book.received_at,
+            WssMarketEvent::PriceChange(change) => &mut change.received_at,
+            WssMarketEvent::TickSizeChange(change) => &mut change.received_at,
+            WssMarketEvent::LastTrade(trade) => &mut trade.received_at,
+            WssMarketEvent::SubscriptionError(error) => &mut error.received_at,
+            WssMarketEvent::BufferOverflow { .. } => return,
+        };
+        *received_at = Some(at);
+    }
+
+    /// The event in the JSON shape the market channel sends
+    fn to_wire(&self) -> Result<Value> {
+        let value = match self {
+            WssMarketEvent::Book(book) => serde_json::to_value(book),
+            WssMarketEvent::PriceChange(change) => serde_json::to_value(change),
+            WssMarketEvent::TickSizeChange(change) => serde_json::to_value(change),
+            WssMarketEvent::LastTrade(trade) => serde_json::to_value(trade),
+            WssMarketEvent::SubscriptionError(error) => Ok(json!({
+                "event_type": "error",
+                "asset_id": error.asset_id,
+                "error": error.reason,
+                "timestamp": error.timestamp,
+            })),
+            WssMarketEvent::BufferOverflow { dropped_count } => Ok(json!({
+                "event_type": "buffer_overflow",
+                "dropped_count": dropped_count,
+            })),
+        };
+        value.map_err(|e| PolyError::parse(format!("Failed to serialize event: {}", e), None))
+    }
+
+    /// Whether the event concerns `asset_id`.
+    ///
+    /// A price change matches when any of its entries does; subscription errors
+    /// that name no asset, and buffer overflows, apply to every asset.
+    pub fn involves_asset(&self, asset_id: &str) -> bool {
+        match self {
+            WssMarketEvent::Book(book) => book.asset_id == asset_id,
+            WssMarketEvent::PriceChange(change) => change
+                .price_changes
+                .iter()
+                .any(|entry| entry.asset_id == asset_id),
+            WssMarketEvent::TickSizeChange(change) => change.asset_id == asset_id,
+            WssMarketEvent::LastTrade(trade) => trade.asset_id == asset_id,
+            WssMarketEvent::SubscriptionError(error) => {
+                error.asset_id.as_deref().is_none_or(|id| id == asset_id)
+            }
+            WssMarketEvent::BufferOverflow { .. } => true,
+        }
+    }
+
+    /// The event's kind; `None` for subscription errors and buffer overflows
+    pub fn kind(&self) -> Option<EventKind> {
+        EventKind::from_event_type(self.event_type())
+    }
+
+    /// Wire name of the event, as used for metrics labels
+    pub(crate) fn event_type(&self) -> &'static str {
+        match self {
+            WssMarketEvent::Book(_) => "book",
+            WssMarketEvent::PriceChange(_) => "price_change",
+            WssMarketEvent::TickSizeChange(_) => "tick_size_change",
+            WssMarketEvent::LastTrade(_) => "last_trade_price",
+            WssMarketEvent::SubscriptionError(_) => "error",
+            WssMarketEvent::BufferOverflow { .. } => "buffer_overflow",
+        }
+    }
+
+    /// Key fields for structured logs.
+    ///
+    /// Level lists are reduced to counts, so a book snapshot logs as a handful of
+    /// fields however deep it is. Decimals are strings, as on the wire.
+    pub fn to_log_fields(&self) -> serde_json::Map<String, Value> {
+        let mut fields = serde_json::Map::new();
+        fields.insert("event".into(), self.event_type().into());
+        match self {
+            WssMarketEvent::Book(book) => {
+                fields.insert("market".into(), book.market.clone().into());
+                fields.insert("asset_id".into(), book.asset_id.clone().into());
+                fields.insert("bids".into(), book.bids.len().into());
+                fields.insert("asks".into(), book.asks.len().into());
+            }
+            WssMarketEvent::PriceChange(change) => {
+                fields.insert("market".into(), change.market.clone().into());
+                fields.insert("num_changes".into(), change.price_changes.len().into());
+            }
+            WssMarketEvent::TickSizeChange(change) => {
+                fields.insert("market".into(), change.market.clone().into());
+                fields.insert("asset_id".into(), change.asset_id.clone().into());
+                fields.insert(
+                    "old_tick_size".into(),
+                    change.old_tick_size.to_string().into(),
+                );
+                fields.insert(
+                    "new_tick_size".into(),
+                    change.new_tick_size.to_string().into(),
+                );
+            }
+            WssMarketEvent::LastTrade(trade) => {
+                fields.insert("market".into(), trade.market.clone().into());
+                fields.insert("asset_id".into(), trade.asset_id.clone().into());
+                fields.insert("side".into(), trade.side.as_str().into());
+                fields.insert("price".into(), trade.price.to_string().into());
+                fields.insert("size".into(), trade.size.to_string().into());
+            }
+            WssMarketEvent::SubscriptionError(error) => {
+                if let Some(asset_id) = &error.asset_id {
+                    fields.insert("asset_id".into(), asset_id.clone().into());
+                }
+                fields.insert("reason".into(), error.reason.clone().into());
+            }
+            WssMarketEvent::BufferOverflow { dropped_count } => {
+                fields.insert("dropped_count".into(), (*dropped_count).into());
+            }
+        }
+        fields
+    }
+
+    /// Log [`to_log_fields`](Self::to_log_fields) at `info` level.
+    pub fn log(&self) {
+        let fields = Value::Object(self.to_log_fields());
+        info!(fields = %fields, "market channel event");
+    }
+}
+
+impl WssUserEvent {
+    fn timing(&self) -> &dyn EventTiming {
+        match self {
+            WssUserEvent::Trade(trade) => trade,
+            WssUserEvent::Order(order) => order,
+        }
+    }
+
+    fn stamp_received(&mut self, at: SystemTime) {
+        let received_at = match self {
+            WssUserEvent::Trade(trade) => &mut trade.received_at,
+            WssUserEvent::Order(order) => &mut order.received_at,
+        };
+        *received_at = Some(at);
+    }
+
+    fn event_type(&self) -> &'static str {
+        match self {
+            WssUserEvent::Trade(_) => "trade",
+            WssUserEvent::Order(_) => "order",
+        }
+    }
+}
+
+impl EventTiming for WssMarketEvent {
+    fn raw_timestamp(&self) -> &str {
+        self.timing().map_or("", |timing| timing.raw_timestamp())
+    }
+
+    fn received_at(&self) -> Option<SystemTime> {
+        self.timing()?.received_at()
+    }
+}
+
+impl EventTiming for WssUserEvent {
+    fn raw_timestamp(&self) -> &str {
+        self.timing().raw_timestamp()
+    }
+
+    fn received_at(&self) -> Option<SystemTime> {
+        self.timing().received_at()
+    }
+}
+
+impl EventTiming for WssEvent {
+    fn raw_timestamp(&self) -> &str {
+        match self {
+            WssEvent::Market(event) => event.raw_timestamp(),
+            WssEvent::User(event) => event.raw_timestamp(),
+        }
+    }
+
+    fn received_at(&self) -> Option<SystemTime> {
+        match self {
+            WssEvent::Market(event) => event.received_at(),
+            WssEvent::User(event) => event.received_at(),
+        }
+    }
+}
+
 /// Trade notifications scoped to the authenticated user.
 #[derive(Debug, Clone, Deserialize)]
 pub struct WssUserTradeMessage {
@@ -59,29 +414,33 @@ pub struct WssUserTradeMessage {
     pub matchtime: String,
     pub outcome: String,
     pub owner: String,
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::serde_decimal")]
     pub price: rust_decimal::Decimal,
     pub side: Side,
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::serde_decimal")]
     pub size: rust_decimal::Decimal,
     pub status: String,
     pub taker_order_id: String,
+    #[serde(default)]
     pub timestamp: String,
     pub trade_owner: String,
     #[serde(rename = "type")]
     pub message_type: String,
+    /// When this client read the frame carrying the event (not sent by the server)
+    #[serde(skip)]
+    pub received_at: Option<SystemTime>,
 }
 
 /// Maker order details included in user trade events.
 #[derive(Debug, Clone, Deserialize)]
 pub struct MakerOrder {
     pub asset_id: String,
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::serde_decimal")]
     pub matched_amount: rust_decimal::Decimal,
     pub order_id: String,
     pub outcome: String,
     pub owner: String,
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::serde_decimal")]
     pub price: rust_decimal::Decimal,
 }
 
@@ -96,89 +455,330 @@ pub struct WssUserOrderMessage {
     pub id: String,
     pub market: String,
     pub order_owner: String,
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::serde_decimal")]
     pub original_size: rust_decimal::Decimal,
     pub outcome: String,
     pub owner: String,
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::serde_decimal")]
     pub price: rust_decimal::Decimal,
     pub side: Side,
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::serde_decimal")]
     pub size_matched: rust_decimal::Decimal,
+    #[serde(default)]
     pub timestamp: String,
     #[serde(rename = "type")]
     pub message_type: String,
+    /// When this client read the frame carrying the event (not sent by the server)
+    #[serde(skip)]
+    pub received_at: Option<SystemTime>,
 }
 
 /// Book summary message
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MarketBook {
     #[serde(rename = "event_type")]
     pub event_type: String,
     pub asset_id: String,
     pub market: String,
+    #[serde(default)]
     pub timestamp: String,
     pub hash: String,
     pub bids: Vec<OrderSummary>,
     pub asks: Vec<OrderSummary>,
+    /// When this client read the frame carrying the event (not sent by the server)
+    #[serde(skip)]
+    pub received_at: Option<SystemTime>,
 }
 
 /// Payload for price change notifications.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PriceChangeMessage {
     #[serde(rename = "event_type")]
     pub event_type: String,
     pub market: String,
     #[serde(rename = "price_changes")]
     pub price_changes: Vec<PriceChangeEntry>,
+    #[serde(default)]
     pub timestamp: String,
+    /// When this client read the frame carrying the event (not sent by the server)
+    #[serde(skip)]
+    pub received_at: Option<SystemTime>,
 }
 
 /// Individual price change entry.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PriceChangeEntry {
     pub asset_id: String,
-    #[serde(with = "rust_decimal::serde::str")]
+    /// Price level that changed
+    #[serde(with = "crate::serde_decimal")]
     pub price: rust_decimal::Decimal,
-    #[serde(with = "rust_decimal::serde::str")]
+    /// New total size resting at `price`; zero removes the level
+    #[serde(with = "crate::serde_decimal")]
     pub size: rust_decimal::Decimal,
     pub side: Side,
+    /// Book hash after the change
+    #[serde(default)]
     pub hash: String,
-    #[serde(with = "rust_decimal::serde::str")]
+    /// Best bid after the change (zero when the bid side is empty)
+    #[serde(with = "crate::serde_decimal")]
     pub best_bid: rust_decimal::Decimal,
-    #[serde(with = "rust_decimal::serde::str")]
+    /// Best ask after the change
+    #[serde(with = "crate::serde_decimal")]
     pub best_ask: rust_decimal::Decimal,
 }
 
+impl From<&PriceChangeEntry> for PriceChange {
+    fn from(entry: &PriceChangeEntry) -> Self {
+        Self {
+            side: entry.side,
+            price: entry.price,
+            size: entry.size,
+        }
+    }
+}
+
 /// Tick size change events.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TickSizeChangeMessage {
     #[serde(rename = "event_type")]
     pub event_type: String,
     pub asset_id: String,
     pub market: String,
-    #[serde(rename = "old_tick_size", with = "rust_decimal::serde::str")]
+    #[serde(rename = "old_tick_size", with = "crate::serde_decimal")]
     pub old_tick_size: rust_decimal::Decimal,
-    #[serde(rename = "new_tick_size", with = "rust_decimal::serde::str")]
+    #[serde(rename = "new_tick_size", with = "crate::serde_decimal")]
     pub new_tick_size: rust_decimal::Decimal,
     pub side: String,
+    #[serde(default)]
     pub timestamp: String,
+    /// When this client read the frame carrying the event (not sent by the server)
+    #[serde(skip)]
+    pub received_at: Option<SystemTime>,
 }
 
 /// Trade events emitted when a trade settles.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LastTradeMessage {
     #[serde(rename = "event_type")]
     pub event_type: String,
     pub asset_id: String,
     pub fee_rate_bps: String,
     pub market: String,
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::serde_decimal")]
     pub price: rust_decimal::Decimal,
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::serde_decimal")]
     pub size: rust_decimal::Decimal,
     pub side: Side,
+    #[serde(default)]
+    pub timestamp: String,
+    /// When this client read the frame carrying the event (not sent by the server)
+    #[serde(skip)]
+    pub received_at: Option<SystemTime>,
+}
+
+/// Error reported by the market channel after a subscribe message.
+///
+/// JSON frames tagged `"event_type": "error"` (or carrying only an `error` field) become
+/// this event, as do bare text replies such as `INVALID OPERATION`, which the server
+/// sends for payloads it can't read at all.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubscriptionError {
+    /// The asset the server complained about, when it named one
+    pub asset_id: Option<String>,
+    pub reason: String,
     pub timestamp: String,
+    /// When this client read the frame carrying the event (not sent by the server)
+    pub received_at: Option<SystemTime>,
+}
+
+impl SubscriptionError {
+    fn from_text(text: &str) -> Self {
+        Self {
+            asset_id: None,
+            reason: text.to_string(),
+            timestamp: String::new(),
+            received_at: None,
+        }
+    }
+}
+
+/// Wire shape of a JSON error frame
+#[derive(Deserialize)]
+struct ErrorFrame {
+    #[serde(default)]
+    asset_id: Option<String>,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(default)]
+    timestamp: String,
+}
+
+impl From<ErrorFrame> for SubscriptionError {
+    fn from(frame: ErrorFrame) -> Self {
+        Self {
+            asset_id: frame.asset_id,
+            reason: frame
+                .error
+                .or(frame.message)
+                .unwrap_or_else(|| "unspecified error".to_string()),
+            timestamp: frame.timestamp,
+            received_at: None,
+        }
+    }
+}
+
+/// What the server made of one asset in a subscription
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubscriptionStatus {
+    /// A `book` snapshot arrived for the asset
+    Accepted,
+    /// The server reported an error naming the asset
+    Rejected(String),
+    /// Nothing about the asset arrived before the timeout
+    Unknown,
+}
+
+/// Borrowed view of a market channel event.
+///
+/// Produced by [`parse_market_events_borrowed`], which decodes straight from the
+/// frame text: identifiers borrow from the frame instead of being copied into
+/// `String`s, and levels are decoded without an intermediate JSON tree. Use it
+/// when events are processed inline (e.g. fed into an
+/// [`OrderBookManager`](crate::book::OrderBookManager)) and call
+/// [`to_owned`](Self::to_owned) for the few you need to keep.
+#[derive(Debug, Clone)]
+pub enum WssMarketEventRef<'a> {
+    Book(MarketBookRef<'a>),
+    PriceChange(PriceChangeMessageRef<'a>),
+    TickSizeChange(TickSizeChangeMessage),
+    LastTrade(LastTradeMessage),
+    SubscriptionError(SubscriptionError),
+}
+
+impl WssMarketEventRef<'_> {
+    /// Copy into the owned event type
+    pub fn to_owned(&self) -> WssMarketEvent {
+        match self {
+            Self::Book(book) => WssMarketEvent::Book(book.to_owned()),
+            Self::PriceChange(change) => WssMarketEvent::PriceChange(change.to_owned()),
+            Self::TickSizeChange(change) => WssMarketEvent::TickSizeChange(change.clone()),
+            Self::LastTrade(trade) => WssMarketEvent::LastTrade(trade.clone()),
+            Self::SubscriptionError(error) => WssMarketEvent::SubscriptionError(error.clone()),
+        }
+    }
+
+    /// Borrowed counterpart of [`WssMarketEvent::involves_asset`]
+    pub fn involves_asset(&self, asset_id: &str) -> bool {
+        match self {
+            Self::Book(book) => book.asset_id == asset_id,
+            Self::PriceChange(change) => change
+                .price_changes
+                .iter()
+                .any(|entry| entry.asset_id == asset_id),
+            Self::TickSizeChange(change) => change.asset_id == asset_id,
+            Self::LastTrade(trade) => trade.asset_id == asset_id,
+            Self::SubscriptionError(error) => {
+                error.asset_id.as_deref().is_none_or(|id| id == asset_id)
+            }
+        }
+    }
+}
+
+/// Borrowed form of [`MarketBook`]
+#[derive(Debug, Clone, Deserialize)]
+pub struct MarketBookRef<'a> {
+    #[serde(borrow)]
+    pub asset_id: Cow<'a, str>,
+    #[serde(borrow)]
+    pub market: Cow<'a, str>,
+    #[serde(borrow)]
+    pub timestamp: Cow<'a, str>,
+    #[serde(borrow)]
+    pub hash: Cow<'a, str>,
+    pub bids: Vec<OrderSummary>,
+    pub asks: Vec<OrderSummary>,
+}
+
+impl MarketBookRef<'_> {
+    /// Copy into an owned [`MarketBook`]
+    pub fn to_owned(&self) -> MarketBook {
+        MarketBook {
+            event_type: "book".to_string(),
+            asset_id: self.asset_id.to_string(),
+            market: self.market.to_string(),
+            timestamp: self.timestamp.to_string(),
+            received_at: None,
+            hash: self.hash.to_string(),
+            bids: self.bids.clone(),
+            asks: self.asks.clone(),
+        }
+    }
+}
+
+/// Borrowed form of [`PriceChangeMessage`]
+#[derive(Debug, Clone, Deserialize)]
+pub struct PriceChangeMessageRef<'a> {
+    #[serde(borrow)]
+    pub market: Cow<'a, str>,
+    #[serde(borrow)]
+    pub price_changes: Vec<PriceChangeEntryRef<'a>>,
+    #[serde(borrow)]
+    pub timestamp: Cow<'a, str>,
+}
+
+impl PriceChangeMessageRef<'_> {
+    /// Copy into an owned [`PriceChangeMessage`]
+    pub fn to_owned(&self) -> PriceChangeMessage {
+        PriceChangeMessage {
+            event_type: "price_change".to_string(),
+            market: self.market.to_string(),
+            price_changes: self
+                .price_changes
+                .iter()
+                .map(|entry| PriceChangeEntry {
+                    asset_id: entry.asset_id.to_string(),
+                    price: entry.price,
+                    size: entry.size,
+                    side: entry.side,
+                    hash: entry.hash.to_string(),
+                    best_bid: entry.best_bid,
+                    best_ask: entry.best_ask,
+                })
+                .collect(),
+            timestamp: self.timestamp.to_string(),
+            received_at: None,
+        }
+    }
+}
+
+/// Borrowed form of [`PriceChangeEntry`]
+#[derive(Debug, Clone, Deserialize)]
+pub struct PriceChangeEntryRef<'a> {
+    #[serde(borrow)]
+    pub asset_id: Cow<'a, str>,
+    #[serde(with = "crate::serde_decimal")]
+    pub price: rust_decimal::Decimal,
+    #[serde(with = "crate::serde_decimal")]
+    pub size: rust_decimal::Decimal,
+    pub side: Side,
+    #[serde(borrow, default)]
+    pub hash: Cow<'a, str>,
+    #[serde(with = "crate::serde_decimal")]
+    pub best_bid: rust_decimal::Decimal,
+    #[serde(with = "crate::serde_decimal")]
+    pub best_ask: rust_decimal::Decimal,
+}
+
+impl From<&PriceChangeEntryRef<'_>> for PriceChange {
+    fn from(entry: &PriceChangeEntryRef<'_>) -> Self {
+        Self {
+            side: entry.side,
+            price: entry.price,
+            size: entry.size,
+        }
+    }
 }
 
 /// Simple stats for monitoring connection health.
@@ -193,13 +793,36 @@ pub struct WssStats {
 
 
 /// Reconnecting client for the market channel.
+///
+/// Construction never touches the network: the socket is opened by the first
+/// [`subscribe`](Self::subscribe_market_channel) or [`next_event`](Self::next_event),
+/// or early by [`connect`](Self::connect), so a client can be built at startup and
+/// subscribed once the markets are known.
 pub struct WssMarketClient {
     connect_url: String,
     connection: Option<WebSocketStream<MaybeTlsStream<TcpStream>>>,
     subscribed_asset_ids: Vec<String>,
+    subscription_pending: bool,
     stats: WssStats,
     disconnect_history: VecDeque<DateTime<Utc>>,
     pending_events: VecDeque<WssMarketEvent>,
+    /// Most events `pending_events` holds before the oldest are dropped
+    buffer_capacity: usize,
+    /// Events at the back of `pending_events` from the frame decoded last, which are
+    /// never dropped however many there are
+    frame_events: usize,
+    /// Frames decoded so far, so read-ahead can tell where the last one starts
+    frames_decoded: u64,
+    /// Events dropped since the last [`WssMarketEvent::BufferOverflow`] was returned
+    dropped_events: u64,
+    meta_cache: Option<Arc<MarketMetaCache>>,
+    /// Set by [`WssMarketClient::filter_by_asset`]; other assets' events are dropped
+    /// before they are copied out of the frame
+    asset_filter: Option<String>,
+    /// Set by [`WssMarketClient::only_events`]; other kinds are never decoded
+    event_filter: Option<Vec<EventKind>>,
+    /// Set by [`WssMarketClient::with_dump_to_file`]
+    dump: Option<EventDump>,
 }
 
 impl Default for WssMarketClient {
@@ -221,11 +844,74 @@ impl WssMarketClient {
         Self {
             connection: None,
             subscribed_asset_ids: Vec::new(),
+            subscription_pending: false,
             stats: WssStats::default(),
             disconnect_history: VecDeque::with_capacity(5),
             connect_url,
             pending_events: VecDeque::new(),
+            buffer_capacity: DEFAULT_EVENT_BUFFER_CAPACITY,
+            frame_events: 0,
+            frames_decoded: 0,
+            dropped_events: 0,
+            meta_cache: None,
+            asset_filter: None,
+            event_filter: None,
+            dump: None,
+        }
+    }
+
+    /// Keep `cache` in sync with `tick_size_change` events as they are read.
+    pub fn with_meta_cache(mut self, cache: Arc<MarketMetaCache>) -> Self {
+        self.meta_cache = Some(cache);
+        self
+    }
+
+    /// Hold at most `capacity` parsed events (at least one) carried over from earlier
+    /// frames for the consumer.
+    ///
+    /// Frames are read off the socket only as fast as events are asked for, and the
+    /// frame decoded last is always kept whole, so a large initial `book` array is never
+    /// cut. Events pile up across frames only while
+    /// [`subscribe_with_ack`](Self::subscribe_with_ack) reads ahead. Past `capacity` the
+    /// oldest carried-over events are dropped, and the next read returns a
+    /// [`WssMarketEvent::BufferOverflow`] with the number lost. The default is
+    /// [`DEFAULT_EVENT_BUFFER_CAPACITY`].
+    pub fn with_buffer_capacity(mut self, capacity: usize) -> Self {
+        self.buffer_capacity = capacity.max(1);
+        self.trim_buffer();
+        self
+    }
+
+    /// How full the event buffer is, from `0.0` to `1.0`
+    pub fn buffer_utilization(&self) -> f64 {
+        (self.pending_events.len() as f64 / self.buffer_capacity as f64).min(1.0)
+    }
+
+    /// Queue `evt` from the frame being decoded; it is never evicted
+    fn buffer_event(&mut self, evt: WssMarketEvent) {
+        self.pending_events.push_back(evt);
+        self.frame_events += 1;
+    }
+
+    /// Drop the oldest events past the capacity, sparing the last frame's
+    fn trim_buffer(&mut self) {
+        let keep = self.buffer_capacity.max(self.frame_events);
+        while self.pending_events.len() > keep {
+            self.pending_events.pop_front();
+            self.dropped_events += 1;
+        }
+    }
+
+    /// The next buffered event, preceded by an overflow report when events were dropped
+    fn pop_buffered(&mut self) -> Option<WssMarketEvent> {
+        if self.dropped_events > 0 && !self.pending_events.is_empty() {
+            let dropped_count = std::mem::take(&mut self.dropped_events);
+            warn!(dropped_count, "market channel event buffer overflowed");
+            return Some(WssMarketEvent::BufferOverflow { dropped_count });
         }
+        let evt = self.pending_events.pop_front();
+        self.frame_events = self.frame_events.min(self.pending_events.len());
+        evt
     }
 
     /// Access connection stats for observability.
@@ -233,6 +919,23 @@ impl WssMarketClient {
         self.stats.clone()
     }
 
+    /// Full URL of the market channel, base plus path.
+    pub fn url(&self) -> &str {
+        &self.connect_url
+    }
+
+    /// Whether a socket is currently open.
+    pub fn is_connected(&self) -> bool {
+        self.connection.is_some()
+    }
+
+    /// Open the socket now rather than on the first subscribe or read.
+    ///
+    /// Sends the current subscription, if any. Does nothing when already connected.
+    pub async fn connect(&mut self) -> Result<()> {
+        self.ensure_connection().await
+    }
+
     fn format_subscription(&self) -> Value {
         json!({
             "type": "market",
@@ -274,98 +977,428 @@ impl WssMarketClient {
         ))
     }
 
-    async fn connect(&mut self) -> Result<()> {
-        let mut attempts = 0;
-        loop {
-            match connect_async(&self.connect_url).await {
-                Ok((socket, _)) => {
-                    self.connection = Some(socket);
-                    if attempts > 0 {
-                        self.stats.reconnect_count += 1;
-                    }
-                    return Ok(());
-                }
-                Err(err) => {
-                    attempts += 1;
-                    let delay = self.reconnect_delay(attempts);
-                    self.stats.errors += 1;
-                    if attempts >= MAX_RECONNECT_ATTEMPTS {
-                        return Err(PolyError::stream(
-                            format!("Failed to connect after {} attempts: {}", attempts, err),
-                            crate::errors::StreamErrorKind::ConnectionFailed,
-                        ));
-                    }
-                    sleep(delay).await;
-                }
-            }
-        }
-    }
-
-    fn reconnect_delay(&self, attempts: u32) -> Duration {
-        let millis = BASE_RECONNECT_DELAY.as_millis() * attempts as u128;
-        
-        Duration::from_millis(millis.min(MAX_RECONNECT_DELAY.as_millis()) as u64)
+    async fn dial(&mut self) -> Result<()> {
+        self.connection =
+            Some(dial_with_backoff(&self.connect_url, "market", &mut self.stats).await?);
+        Ok(())
     }
 
     async fn ensure_connection(&mut self) -> Result<()> {
         if self.connection.is_none() {
-            self.connect().await?;
+            self.dial().await?;
+            self.subscription_pending = true;
+        }
+        // Tracked separately so a caller dropping us mid-reconnect (e.g. from a
+        // `select!`) still replays the subscription on the next call
+        if self.subscription_pending {
             self.send_subscription().await?;
+            self.subscription_pending = false;
         }
         Ok(())
     }
 
+    /// Subscribe to the public market channel for the provided asset (token) IDs.
+    ///
+    /// Replaces any previous subscription; no credentials are needed.
+    pub async fn subscribe_market_channel(&mut self, asset_ids: Vec<String>) -> Result<()> {
+        self.subscribed_asset_ids = asset_ids;
+        self.subscription_pending = true;
+        self.ensure_connection().await
+    }
+
     /// Subscribe to the market channel for the provided token/market IDs.
+    ///
+    /// Kept for existing callers; prefer [`WssMarketClient::subscribe_market_channel`].
     pub async fn subscribe(&mut self, asset_ids: Vec<String>) -> Result<()> {
-        self.subscribed_asset_ids = asset_ids;
-        self.ensure_connection().await?;
-        self.send_subscription().await
+        self.subscribe_market_channel(asset_ids).await
     }
 
-    /// Read the next market channel event, reconnecting transparently when
-    /// the socket drops.
-    pub async fn next_event(&mut self) -> Result<WssMarketEvent> {
-        loop {
-            if let Some(evt) = self.pending_events.pop_front() {
-                return Ok(evt);
-            }
-            self.ensure_connection().await?;
+    /// Subscribe and wait for the server to acknowledge each asset.
+    ///
+    /// The market channel has no explicit ack: an asset counts as accepted once its
+    /// initial `book` snapshot arrives and as rejected when an error frame names it.
+    /// Assets still unresolved after `wait` are reported as
+    /// [`SubscriptionStatus::Unknown`]. Fails when the server rejects the subscription
+    /// as a whole, or when nothing at all is heard back within `wait` (a bogus
+    /// subscription otherwise just goes quiet). Events read while waiting are kept and
+    /// returned by [`next_event`](Self::next_event) as usual.
+    pub async fn subscribe_with_ack(
+        &mut self,
+        asset_ids: Vec<String>,
+        wait: Duration,
+    ) -> Result<Vec<(String, SubscriptionStatus)>> {
+        let mut statuses: Vec<(String, SubscriptionStatus)> = asset_ids
+            .iter()
+            .map(|id| (id.clone(), SubscriptionStatus::Unknown))
+            .collect();
+        self.subscribe_market_channel(asset_ids).await?;
 
-            match self.connection.as_mut().unwrap().next().await {
-                Some(Ok(Message::Text(text))) => {
-                    let trimmed = text.trim();
-                    if trimmed.eq_ignore_ascii_case("ping") || trimmed.eq_ignore_ascii_case("pong")
+        let deadline = tokio::time::Instant::now() + wait;
+        let mut seen = Vec::new();
+        // Events at the end of `seen` from the last frame decoded, kept whole below
+        let mut frame_tail = 0;
+        let mut frame = self.frames_decoded;
+        let mut outcome = Ok(());
+        while statuses
+            .iter()
+            .any(|(_, status)| *status == SubscriptionStatus::Unknown)
+        {
+            let evt = match tokio::time::timeout_at(deadline, self.next_event()).await {
+                Ok(Ok(evt)) => evt,
+                Ok(Err(err)) => {
+                    outcome = Err(err);
+                    break;
+                }
+                Err(_) => break,
+            };
+            if self.frames_decoded != frame {
+                frame = self.frames_decoded;
+                frame_tail = 0;
+            }
+            frame_tail += 1;
+            match &evt {
+                WssMarketEvent::Book(book) => {
+                    if let Some((_, status)) =
+                        statuses.iter_mut().find(|(id, _)| *id == book.asset_id)
                     {
-                        continue;
-                    }
-                    let first_char = trimmed.chars().next();
-                    if first_char != Some('{') && first_char != Some('[') {
-                        warn!("ignoring unexpected text frame: {}", trimmed);
-                        continue;
-                    }
-                    let events = parse_market_events(&text)?;
-                    self.stats.messages_received += events.len() as u64;
-                    self.stats.last_message_time = Some(Utc::now());
-                    for evt in events {
-                        self.pending_events.push_back(evt);
-                    }
-                    if let Some(evt) = self.pending_events.pop_front() {
-                        return Ok(evt);
+                        *status = SubscriptionStatus::Accepted;
                     }
-                    continue;
                 }
-                Some(Ok(Message::Ping(payload))) => {
-                    if let Some(connection) = self.connection.as_mut() {
-                        let _ = connection.send(Message::Pong(payload)).await;
+                WssMarketEvent::SubscriptionError(error) => match &error.asset_id {
+                    Some(asset_id) => {
+                        if let Some((_, status)) =
+                            statuses.iter_mut().find(|(id, _)| id == asset_id)
+                        {
+                            *status = SubscriptionStatus::Rejected(error.reason.clone());
+                        }
                     }
-                }
-                Some(Ok(Message::Pong(_))) => {}
-                Some(Ok(Message::Close(_))) => {
-                    self.disconnect_history.push_back(Utc::now());
-                    if self.disconnect_history.len() > 5 {
-                        self.disconnect_history.pop_front();
+                    None => {
+                        outcome = Err(PolyError::stream(
+                            format!("Market subscription rejected: {}", error.reason),
+                            crate::errors::StreamErrorKind::SubscriptionFailed,
+                        ));
                     }
-                    self.connection = None;
+                },
+                _ => {}
+            }
+            seen.push(evt);
+            if outcome.is_err() {
+                break;
+            }
+        }
+
+        // Hand everything read here back to `next_event`, ahead of anything queued later
+        for evt in seen.into_iter().rev() {
+            self.pending_events.push_front(evt);
+        }
+        self.frame_events += frame_tail;
+        self.trim_buffer();
+        outcome?;
+
+        if statuses
+            .iter()
+            .all(|(_, status)| *status == SubscriptionStatus::Unknown)
+            && !statuses.is_empty()
+        {
+            return Err(PolyError::timeout(
+                wait,
+                "market subscription acknowledgment",
+            ));
+        }
+        Ok(statuses)
+    }
+
+    /// Turn the client into a stream of the events matching `pred`.
+    ///
+    /// Errors are always passed through. The stream keeps reconnecting like
+    /// [`next_event`](Self::next_event); drop it to disconnect.
+    pub fn filter_events(
+        self,
+        pred: impl Fn(&WssMarketEvent) -> bool + Send + 'static,
+    ) -> FilteredWssStream {
+        let events = futures::stream::unfold((self, pred), |(mut client, pred)| async move {
+            loop {
+                match client.next_event().await {
+                    Ok(evt) if !pred(&evt) => continue,
+                    item => return Some((item, (client, pred))),
+                }
+            }
+        });
+        FilteredWssStream {
+            inner: Box::pin(events),
+        }
+    }
+
+    /// Turn the client into a stream of `f` applied to each event.
+    ///
+    /// Client errors are passed through unmapped. Reconnects like
+    /// [`filter_events`](Self::filter_events); drop the stream to disconnect.
+    pub fn map_events<T, F>(self, f: F) -> MappedStream<T>
+    where
+        T: Send + 'static,
+        F: Fn(WssMarketEvent) -> T + Send + 'static,
+    {
+        let events = futures::stream::unfold((self, f), |(mut client, f)| async move {
+            let item = client.next_event().await.map(&f);
+            Some((item, (client, f)))
+        });
+        MappedStream {
+            inner: Box::pin(events),
+        }
+    }
+
+    /// Turn the client into a stream of the results of the async `f` on each event.
+    ///
+    /// Each event is mapped before the next is read, so the output keeps the feed's
+    /// order. An error from `f` is yielded in place of its event and the stream goes
+    /// on, as it does after a client error.
+    pub fn and_then_events<T, F, Fut>(self, f: F) -> AndThenStream<T>
+    where
+        T: Send + 'static,
+        F: Fn(WssMarketEvent) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<T>> + Send,
+    {
+        let events = futures::stream::unfold((self, f), |(mut client, f)| async move {
+            let item = match client.next_event().await {
+                Ok(evt) => f(evt).await,
+                Err(err) => Err(err),
+            };
+            Some((item, (client, f)))
+        });
+        AndThenStream {
+            inner: Box::pin(events),
+        }
+    }
+
+    /// Append every event this client returns to `path`, one JSON line each.
+    ///
+    /// Lines are `{"received_at_ms": ..., "event": ...}`, with the event in the shape
+    /// the market channel sent it. Filtered-out events aren't written. The file is
+    /// written on a background thread, so the stream never waits on the disk; it is
+    /// flushed whenever the thread catches up, and dropping the client waits for the
+    /// last lines. A file that can't be opened, or a failed write, is logged and
+    /// otherwise ignored so capture never interrupts the stream. Read a capture back
+    /// with [`replay_from_file`](Self::replay_from_file).
+    pub fn with_dump_to_file(mut self, path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref().to_path_buf();
+        match std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .and_then(|file| EventDump::start(path.clone(), file))
+        {
+            Ok(dump) => self.dump = Some(dump),
+            Err(err) => warn!("not capturing events to {}: {}", path.display(), err),
+        }
+        self
+    }
+
+    /// Play back a capture written by [`with_dump_to_file`](Self::with_dump_to_file).
+    ///
+    /// Events come out in file order, spaced by the gaps between their capture times
+    /// scaled by `speed_factor`: 1.0 replays in real time, 0.1 ten times faster and 0
+    /// as fast as the file can be read. Each event's `received_at` is the time it was
+    /// captured, so latency figures match the original run. A line that can't be
+    /// parsed yields an error and playback moves on to the next.
+    pub fn replay_from_file(
+        path: impl AsRef<Path>,
+        speed_factor: f64,
+    ) -> Result<impl Stream<Item = Result<WssMarketEvent>> + Send> {
+        use tokio::io::AsyncBufReadExt;
+
+        if !speed_factor.is_finite() || speed_factor < 0.0 {
+            return Err(PolyError::validation(format!(
+                "Replay speed factor must be finite and non-negative, got {}",
+                speed_factor
+            )));
+        }
+        let path = path.as_ref();
+        let file = std::fs::File::open(path).map_err(|e| {
+            PolyError::internal(format!("Failed to open capture {}", path.display()), e)
+        })?;
+        let lines = tokio::io::BufReader::new(tokio::fs::File::from_std(file)).lines();
+
+        Ok(futures::stream::unfold(
+            (lines, None::<u64>),
+            move |(mut lines, mut previous_ms)| async move {
+                loop {
+                    let line = match lines.next_line().await {
+                        Ok(Some(line)) => line,
+                        Ok(None) => return None,
+                        Err(e) => {
+                            let err = PolyError::internal("Failed to read capture", e);
+                            return Some((Err(err), (lines, previous_ms)));
+                        }
+                    };
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    let captured = match serde_json::from_str::<CapturedEvent>(&line) {
+                        Ok(captured) => captured,
+                        Err(e) => {
+                            let err = PolyError::parse(
+                                format!("Invalid capture line: {}", e),
+                                Some(Box::new(e)),
+                            );
+                            return Some((Err(err), (lines, previous_ms)));
+                        }
+                    };
+                    if let Some(previous) = previous_ms {
+                        let gap_ms = captured.received_at_ms.saturating_sub(previous) as f64;
+                        if gap_ms > 0.0 && speed_factor > 0.0 {
+                            sleep(Duration::from_secs_f64(gap_ms * speed_factor / 1000.0)).await;
+                        }
+                    }
+                    previous_ms = Some(captured.received_at_ms);
+                    let event = parse_market_event_value(captured.event).map(|mut evt| {
+                        evt.stamp_received(
+                            UNIX_EPOCH + Duration::from_millis(captured.received_at_ms),
+                        );
+                        evt
+                    });
+                    return Some((event, (lines, previous_ms)));
+                }
+            },
+        ))
+    }
+
+    /// Only deliver events of `kinds`; others are skipped while parsing.
+    ///
+    /// A skipped event is recognised by its `event_type` alone and never decoded, so
+    /// dropping full `book` snapshots from a trades-only consumer saves their
+    /// allocations, and it isn't counted as an error or a received message.
+    /// Subscription errors are always delivered. An empty list skips every data event.
+    pub fn only_events(mut self, kinds: &[EventKind]) -> Self {
+        let kinds = kinds.to_vec();
+        self.pending_events
+            .retain(|evt| evt.kind().is_none_or(|kind| kinds.contains(&kind)));
+        self.event_filter = Some(kinds);
+        self
+    }
+
+    /// Only events for `token_id`.
+    ///
+    /// Frames are matched against the asset ID while still borrowed, so events for
+    /// other assets are skipped without being copied.
+    pub fn filter_by_asset(mut self, token_id: String) -> FilteredWssStream {
+        self.asset_filter = Some(token_id.clone());
+        // Events queued before the filter was set still need checking
+        self.filter_events(move |evt| evt.involves_asset(&token_id))
+    }
+
+    /// Only `price_change` events
+    pub fn filter_by_type_price_change(self) -> FilteredWssStream {
+        self.filter_events(|evt| matches!(evt, WssMarketEvent::PriceChange(_)))
+    }
+
+    /// Only `book` snapshots
+    pub fn filter_by_type_book(self) -> FilteredWssStream {
+        self.filter_events(|evt| matches!(evt, WssMarketEvent::Book(_)))
+    }
+
+    /// [`next_event`](Self::next_event), giving up after `dur` with `Ok(None)`.
+    ///
+    /// Useful for loops that have periodic work to do whether or not the market moves.
+    /// Timing out loses nothing: events already read from a frame stay queued, and a
+    /// frame is only taken off the socket once it can be returned.
+    pub async fn next_event_timeout(&mut self, dur: Duration) -> Result<Option<WssMarketEvent>> {
+        if let Some(evt) = self.pop_buffered() {
+            return Ok(Some(evt));
+        }
+        match timeout(dur, self.next_event()).await {
+            Ok(evt) => evt.map(Some),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Read the next market channel event, reconnecting transparently when
+    /// the socket drops.
+    ///
+    /// Connects first if needed. Called before any subscription, it waits on the bare
+    /// socket, so anything the server sends unprompted is still returned.
+    pub async fn next_event(&mut self) -> Result<WssMarketEvent> {
+        loop {
+            if let Some(evt) = self.pop_buffered() {
+                return Ok(evt);
+            }
+            self.ensure_connection().await?;
+
+            match self.connection.as_mut().unwrap().next().await {
+                Some(Ok(Message::Text(text))) => {
+                    let trimmed = text.trim();
+                    if trimmed.eq_ignore_ascii_case("ping") || trimmed.eq_ignore_ascii_case("pong")
+                    {
+                        continue;
+                    }
+                    let first_char = trimmed.chars().next();
+                    if first_char.is_none() {
+                        continue;
+                    }
+                    if first_char != Some('{') && first_char != Some('[') {
+                        // Replies like `INVALID OPERATION` answer a subscribe the server
+                        // couldn't read
+                        warn!("market channel rejected a request: {}", trimmed);
+                        let mut evt = WssMarketEvent::SubscriptionError(
+                            SubscriptionError::from_text(trimmed),
+                        );
+                        evt.stamp_received(SystemTime::now());
+                        telemetry::wss_event("market", evt.event_type());
+                        if let Some(dump) = &self.dump {
+                            dump.record(&evt);
+                        }
+                        return Ok(evt);
+                    }
+                    let events = if self.asset_filter.is_some() || self.event_filter.is_some() {
+                        // Filter on the borrowed form so dropped events never allocate
+                        let kinds = self.event_filter.as_deref();
+                        parse_market_events_borrowed_where(&text, |kind| {
+                            kinds.is_none_or(|kinds| kinds.contains(&kind))
+                        })?
+                        .iter()
+                        .filter(|evt| {
+                            self.asset_filter
+                                .as_deref()
+                                .is_none_or(|asset_id| evt.involves_asset(asset_id))
+                        })
+                        .map(WssMarketEventRef::to_owned)
+                        .collect()
+                    } else {
+                        parse_market_events(&text)?
+                    };
+                    let received_at = SystemTime::now();
+                    self.frame_events = 0;
+                    self.frames_decoded += 1;
+                    self.stats.messages_received += events.len() as u64;
+                    self.stats.last_message_time = Some(Utc::now());
+                    for mut evt in events {
+                        evt.stamp_received(received_at);
+                        telemetry::wss_event("market", evt.event_type());
+                        if let Some(cache) = &self.meta_cache {
+                            cache.observe(&evt);
+                        }
+                        if let Some(dump) = &self.dump {
+                            dump.record(&evt);
+                        }
+                        self.buffer_event(evt);
+                    }
+                    self.trim_buffer();
+                    if let Some(evt) = self.pop_buffered() {
+                        return Ok(evt);
+                    }
+                    continue;
+                }
+                Some(Ok(Message::Ping(payload))) => {
+                    if let Some(connection) = self.connection.as_mut() {
+                        let _ = connection.send(Message::Pong(payload)).await;
+                    }
+                }
+                Some(Ok(Message::Pong(_))) => {}
+                Some(Ok(Message::Close(_))) => {
+                    self.disconnect_history.push_back(Utc::now());
+                    if self.disconnect_history.len() > 5 {
+                        self.disconnect_history.pop_front();
+                    }
+                    self.connection = None;
                 }
                 Some(Ok(_)) => {}
                 Some(Err(err)) => {
@@ -382,15 +1415,137 @@ impl WssMarketClient {
     }
 }
 
+/// One line of a capture file
+#[derive(Serialize, Deserialize)]
+struct CapturedEvent {
+    received_at_ms: u64,
+    event: Value,
+}
+
+/// Capture file written by [`WssMarketClient::with_dump_to_file`]
+///
+/// Lines are handed to a writer thread over a channel. It flushes each time the
+/// channel runs dry, so a burst is written in one go, and finishes when the dump is
+/// dropped.
+struct EventDump {
+    path: PathBuf,
+    lines: Option<std::sync::mpsc::Sender<String>>,
+    writer: Option<std::thread::JoinHandle<()>>,
+}
+
+impl EventDump {
+    fn start(path: PathBuf, file: std::fs::File) -> std::io::Result<Self> {
+        let (lines, queued) = std::sync::mpsc::channel::<String>();
+        let thread_path = path.clone();
+        let writer = std::thread::Builder::new()
+            .name("wss-capture".to_string())
+            .spawn(move || {
+                let mut file = std::io::BufWriter::new(file);
+                while let Ok(line) = queued.recv() {
+                    let mut written = writeln!(file, "{}", line);
+                    while written.is_ok()
+                        && let Ok(line) = queued.try_recv()
+                    {
+                        written = writeln!(file, "{}", line);
+                    }
+                    if let Err(err) = written.and_then(|()| file.flush()) {
+                        warn!("event capture {}: {}", thread_path.display(), err);
+                    }
+                }
+            })?;
+        Ok(Self {
+            path,
+            lines: Some(lines),
+            writer: Some(writer),
+        })
+    }
+
+    fn record(&self, evt: &WssMarketEvent) {
+        let received_at_ms = evt
+            .received_at()
+            .and_then(|at| at.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |since| since.as_millis() as u64);
+        let line = evt.to_wire().and_then(|event| {
+            serde_json::to_string(&CapturedEvent {
+                received_at_ms,
+                event,
+            })
+            .map_err(|e| PolyError::parse(format!("Failed to serialize event: {}", e), None))
+        });
+        let queued = line.and_then(|line| {
+            self.lines
+                .as_ref()
+                .and_then(|lines| lines.send(line).ok())
+                .ok_or_else(|| PolyError::internal_simple("Capture writer stopped"))
+        });
+        if let Err(err) = queued {
+            warn!("event capture {}: {}", self.path.display(), err);
+        }
+    }
+}
+
+impl Drop for EventDump {
+    fn drop(&mut self) {
+        // Closing the channel lets the writer drain what is queued and exit
+        self.lines.take();
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
+
+/// Market channel events that passed a filter; see [`WssMarketClient::filter_events`]
+pub struct FilteredWssStream {
+    inner: Pin<Box<dyn Stream<Item = Result<WssMarketEvent>> + Send>>,
+}
+
+impl Stream for FilteredWssStream {
+    type Item = Result<WssMarketEvent>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+/// Market channel events passed through a function; see [`WssMarketClient::map_events`]
+pub struct MappedStream<T> {
+    inner: Pin<Box<dyn Stream<Item = Result<T>> + Send>>,
+}
+
+impl<T> Stream for MappedStream<T> {
+    type Item = Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+/// Market channel events passed through an async function; see
+/// [`WssMarketClient::and_then_events`]
+pub struct AndThenStream<T> {
+    inner: Pin<Box<dyn Stream<Item = Result<T>> + Send>>,
+}
+
+impl<T> Stream for AndThenStream<T> {
+    type Item = Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
 /// Reconnecting client for the authenticated user channel.
 pub struct WssUserClient {
     connect_url: String,
     connection: Option<WebSocketStream<MaybeTlsStream<TcpStream>>>,
     subscribed_markets: Vec<String>,
+    subscribed: bool,
+    subscription_pending: bool,
     stats: WssStats,
     disconnect_history: VecDeque<DateTime<Utc>>,
     pending_events: VecDeque<WssUserEvent>,
     auth: ApiCredentials,
+    session_id: String,
 }
 
 impl WssUserClient {
@@ -406,11 +1561,14 @@ impl WssUserClient {
         Self {
             connection: None,
             subscribed_markets: Vec::new(),
+            subscribed: false,
+            subscription_pending: false,
             stats: WssStats::default(),
             disconnect_history: VecDeque::with_capacity(5),
             connect_url,
             pending_events: VecDeque::new(),
             auth,
+            session_id: uuid::Uuid::new_v4().to_string(),
         }
     }
 
@@ -419,8 +1577,21 @@ impl WssUserClient {
         self.stats.clone()
     }
 
+    /// Full URL of the user channel, base plus path.
+    pub fn url(&self) -> &str {
+        &self.connect_url
+    }
+
+    /// Identifier sent with every subscription from this client.
+    ///
+    /// Generated once and reused on every reconnect, so the server treats a dropped
+    /// socket coming back as the same session rather than a new one.
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
     fn format_subscription(&self) -> Option<Value> {
-        if self.subscribed_markets.is_empty() {
+        if !self.subscribed {
             return None;
         }
 
@@ -432,6 +1603,7 @@ impl WssUserClient {
                 "passphrase": self.auth.passphrase,
             },
             "markets": self.subscribed_markets,
+            "session_id": self.session_id,
         }))
     }
 
@@ -469,51 +1641,42 @@ impl WssUserClient {
     }
 
     async fn connect(&mut self) -> Result<()> {
-        let mut attempts = 0;
-        loop {
-            match connect_async(&self.connect_url).await {
-                Ok((socket, _)) => {
-                    self.connection = Some(socket);
-                    if attempts > 0 {
-                        self.stats.reconnect_count += 1;
-                    }
-                    return Ok(());
-                }
-                Err(err) => {
-                    attempts += 1;
-                    let delay = self.reconnect_delay(attempts);
-                    self.stats.errors += 1;
-                    if attempts >= MAX_RECONNECT_ATTEMPTS {
-                        return Err(PolyError::stream(
-                            format!("Failed to connect after {} attempts: {}", attempts, err),
-                            crate::errors::StreamErrorKind::ConnectionFailed,
-                        ));
-                    }
-                    sleep(delay).await;
-                }
-            }
-        }
-    }
-
-    fn reconnect_delay(&self, attempts: u32) -> Duration {
-        let millis = BASE_RECONNECT_DELAY.as_millis() * attempts as u128;
-        
-        Duration::from_millis(millis.min(MAX_RECONNECT_DELAY.as_millis()) as u64)
+        self.connection =
+            Some(dial_with_backoff(&self.connect_url, "user", &mut self.stats).await?);
+        Ok(())
     }
 
     async fn ensure_connection(&mut self) -> Result<()> {
         if self.connection.is_none() {
             self.connect().await?;
+            self.subscription_pending = true;
+        }
+        if self.subscription_pending {
             self.send_subscription().await?;
+            self.subscription_pending = false;
         }
         Ok(())
     }
 
     /// Subscribe to the user channel for the provided market IDs.
+    ///
+    /// An empty list subscribes to fills and order updates across every market.
     pub async fn subscribe(&mut self, market_ids: Vec<String>) -> Result<()> {
         self.subscribed_markets = market_ids;
-        self.ensure_connection().await?;
-        self.send_subscription().await
+        self.subscribed = true;
+        self.subscription_pending = true;
+        self.ensure_connection().await
+    }
+
+    /// Whether [`WssUserClient::subscribe`] has been called.
+    pub fn is_subscribed(&self) -> bool {
+        self.subscribed
+    }
+
+    /// Authenticate with `credentials` and subscribe to the user channel for every market.
+    pub async fn subscribe_user_channel(&mut self, credentials: &ApiCredentials) -> Result<()> {
+        self.auth = credentials.clone();
+        self.subscribe(Vec::new()).await
     }
 
     /// Read the next user channel event, reconnecting transparently when the
@@ -538,9 +1701,12 @@ impl WssUserClient {
                         continue;
                     }
                     let events = parse_user_events(&text)?;
+                    let received_at = SystemTime::now();
                     self.stats.messages_received += events.len() as u64;
                     self.stats.last_message_time = Some(Utc::now());
-                    for evt in events {
+                    for mut evt in events {
+                        evt.stamp_received(received_at);
+                        telemetry::wss_event("user", evt.event_type());
                         self.pending_events.push_back(evt);
                     }
                     if let Some(evt) = self.pending_events.pop_front() {
@@ -581,118 +1747,1545 @@ impl WssUserClient {
     }
 }
 
-fn parse_market_events(text: &str) -> Result<Vec<WssMarketEvent>> {
-    let value: Value = serde_json::from_str(text)
-        .map_err(|err| PolyError::parse(format!("Invalid JSON: {}", err), Some(Box::new(err))))?;
+/// Single handle over both the public market channel and the authenticated user channel.
+///
+/// Each channel keeps its own reconnecting socket; [`WssClient::next_event`] waits on
+/// whichever channels have been subscribed and returns the first event to arrive.
+pub struct WssClient {
+    base_url: String,
+    market: WssMarketClient,
+    user: Option<WssUserClient>,
+}
 
-    if let Some(array) = value.as_array() {
-        array
-            .iter()
-            .map(parse_market_event_value)
-            .collect::<Result<Vec<_>>>()
-    } else {
-        Ok(vec![parse_market_event_value(&value)?])
+impl Default for WssClient {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-fn parse_market_event_value(value: &Value) -> Result<WssMarketEvent> {
-    let event_type = value
-        .get("event_type")
-        .and_then(|v| v.as_str())
-        .or_else(|| value.get("type").and_then(|v| v.as_str()))
-        .ok_or_else(|| PolyError::parse("Missing event_type/type in market message", None))?;
+impl WssClient {
+    /// Create a new instance using the default Polymarket WSS base.
+    pub fn new() -> Self {
+        Self::with_url(DEFAULT_WSS_BASE)
+    }
 
-    match event_type {
-        "book" => {
-            let parsed: MarketBook = serde_json::from_value(value.clone()).map_err(|err| {
-                PolyError::parse(
-                    format!("Failed to parse book message: {}", err),
-                    Some(Box::new(err)),
-                )
-            })?;
-            Ok(WssMarketEvent::Book(parsed))
-        }
-        "price_change" => {
-            let parsed =
-                serde_json::from_value::<PriceChangeMessage>(value.clone()).map_err(|err| {
-                    PolyError::parse(
-                        format!("Failed to parse price_change: {}", err),
-                        Some(Box::new(err)),
-                    )
-                })?;
-            Ok(WssMarketEvent::PriceChange(parsed))
-        }
-        "tick_size_change" => {
-            let parsed =
-                serde_json::from_value::<TickSizeChangeMessage>(value.clone()).map_err(|err| {
-                    PolyError::parse(
-                        format!("Failed to parse tick_size_change: {}", err),
-                        Some(Box::new(err)),
-                    )
-                })?;
-            Ok(WssMarketEvent::TickSizeChange(parsed))
-        }
-        "last_trade_price" => {
-            let parsed =
-                serde_json::from_value::<LastTradeMessage>(value.clone()).map_err(|err| {
-                    PolyError::parse(
-                        format!("Failed to parse last_trade_price: {}", err),
-                        Some(Box::new(err)),
-                    )
-                })?;
-            Ok(WssMarketEvent::LastTrade(parsed))
+    /// Create a new client against a custom endpoint (useful for tests).
+    pub fn with_url(url: &str) -> Self {
+        Self {
+            base_url: url.trim_end_matches('/').to_string(),
+            market: WssMarketClient::with_url(url),
+            user: None,
+        }
+    }
+
+    /// Subscribe to the public market channel for the provided asset (token) IDs.
+    pub async fn subscribe_market_channel(&mut self, asset_ids: Vec<String>) -> Result<()> {
+        self.market.subscribe_market_channel(asset_ids).await
+    }
+
+    /// Subscribe to the authenticated user channel (fills and order updates).
+    pub async fn subscribe_user_channel(&mut self, credentials: &ApiCredentials) -> Result<()> {
+        let base_url = &self.base_url;
+        self.user
+            .get_or_insert_with(|| WssUserClient::with_url(base_url, credentials.clone()))
+            .subscribe_user_channel(credentials)
+            .await
+    }
+
+    /// Connection stats for the market channel.
+    pub fn market_stats(&self) -> WssStats {
+        self.market.stats()
+    }
+
+    /// Connection stats for the user channel, if it has been subscribed.
+    pub fn user_stats(&self) -> Option<WssStats> {
+        self.user.as_ref().map(WssUserClient::stats)
+    }
+
+    /// Read the next event from any subscribed channel.
+    ///
+    /// Cancel safe: events already read off a socket stay queued on their channel, so
+    /// dropping this future (or losing a `select!`) never loses data.
+    pub async fn next_event(&mut self) -> Result<WssEvent> {
+        let market_subscribed = !self.market.subscribed_asset_ids.is_empty();
+        match (market_subscribed, self.user.as_mut()) {
+            (true, Some(user)) => tokio::select! {
+                event = self.market.next_event() => event.map(WssEvent::Market),
+                event = user.next_event() => event.map(WssEvent::User),
+            },
+            (true, None) => self.market.next_event().await.map(WssEvent::Market),
+            (false, Some(user)) => user.next_event().await.map(WssEvent::User),
+            (false, None) => Err(PolyError::validation(
+                "Subscribe to the market or user channel before reading events",
+            )),
         }
-        other => Err(PolyError::parse(
-            format!("Unknown market event_type: {}", other),
-            None,
-        )),
     }
 }
 
-fn parse_user_events(text: &str) -> Result<Vec<WssUserEvent>> {
-    let value: Value = serde_json::from_str(text)
-        .map_err(|err| PolyError::parse(format!("Invalid JSON: {}", err), Some(Box::new(err))))?;
+/// Trade payload published on the RTDS `activity` topic.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ActivityTradePayload {
+    asset: String,
+    condition_id: String,
+    #[serde(with = "crate::serde_decimal")]
+    price: rust_decimal::Decimal,
+    #[serde(with = "crate::serde_decimal")]
+    size: rust_decimal::Decimal,
+    side: Side,
+    timestamp: Value,
+}
 
-    if let Some(array) = value.as_array() {
-        array
-            .iter()
-            .map(parse_user_event_value)
-            .collect::<Result<Vec<_>>>()
-    } else {
-        Ok(vec![parse_user_event_value(&value)?])
+impl From<ActivityTradePayload> for LastTradeMessage {
+    fn from(trade: ActivityTradePayload) -> Self {
+        let timestamp = match trade.timestamp {
+            Value::String(s) => s,
+            other => other.to_string(),
+        };
+        Self {
+            event_type: "last_trade_price".to_string(),
+            asset_id: trade.asset,
+            // The firehose doesn't carry the fee rate
+            fee_rate_bps: String::new(),
+            market: trade.condition_id,
+            price: trade.price,
+            size: trade.size,
+            side: trade.side,
+            timestamp,
+            received_at: None,
+        }
     }
 }
 
-fn parse_user_event_value(value: &Value) -> Result<WssUserEvent> {
-    let event_type = value
-        .get("event_type")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| PolyError::parse("Missing event_type in user message", None))?;
-
-    match event_type {
-        "trade" => {
-            let parsed =
-                serde_json::from_value::<WssUserTradeMessage>(value.clone()).map_err(|err| {
-                    PolyError::parse(
-                        format!("Failed to parse user trade message: {}", err),
-                        Some(Box::new(err)),
-                    )
-                })?;
-            Ok(WssUserEvent::Trade(parsed))
-        }
-        "order" => {
-            let parsed =
-                serde_json::from_value::<WssUserOrderMessage>(value.clone()).map_err(|err| {
-                    PolyError::parse(
-                        format!("Failed to parse user order message: {}", err),
-                        Some(Box::new(err)),
+/// Reconnecting client for the exchange-wide trade firehose.
+///
+/// Subscribes to the `activity`/`trades` topic on the real-time data service,
+/// which streams every trade on the exchange without per-asset subscriptions.
+/// Trades are surfaced as [`WssMarketEvent::LastTrade`] so consumers of the
+/// market channel can share handling code.
+pub struct WssActivityClient {
+    connect_url: String,
+    connection: Option<WebSocketStream<MaybeTlsStream<TcpStream>>>,
+    subscribed: bool,
+    subscription_pending: bool,
+    condition_filter: HashSet<String>,
+    stats: WssStats,
+    disconnect_history: VecDeque<DateTime<Utc>>,
+    pending_events: VecDeque<WssMarketEvent>,
+}
+
+impl Default for WssActivityClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WssActivityClient {
+    /// Create a new instance using the default Polymarket RTDS base.
+    pub fn new() -> Self {
+        Self::with_url(DEFAULT_RTDS_BASE)
+    }
+
+    /// Create a new client against a custom endpoint (useful for tests).
+    pub fn with_url(url: &str) -> Self {
+        Self {
+            connect_url: url.trim_end_matches('/').to_string(),
+            connection: None,
+            subscribed: false,
+            subscription_pending: false,
+            condition_filter: HashSet::new(),
+            stats: WssStats::default(),
+            disconnect_history: VecDeque::with_capacity(5),
+            pending_events: VecDeque::new(),
+        }
+    }
+
+    /// Only pass through trades for these condition IDs.
+    ///
+    /// Filtering happens client-side; an empty list lets every trade through.
+    pub fn set_condition_filter(&mut self, condition_ids: Vec<String>) {
+        self.condition_filter = condition_ids
+            .into_iter()
+            .map(|id| id.to_ascii_lowercase())
+            .collect();
+    }
+
+    /// Access connection stats for observability.
+    pub fn stats(&self) -> WssStats {
+        self.stats.clone()
+    }
+
+    fn format_subscription(&self) -> Value {
+        json!({
+            "action": "subscribe",
+            "subscriptions": [{ "topic": "activity", "type": "trades" }],
+        })
+    }
+
+    async fn send_subscription(&mut self) -> Result<()> {
+        if !self.subscribed {
+            return Ok(());
+        }
+
+        let message = self.format_subscription();
+        self.send_raw_message(message).await
+    }
+
+    async fn send_raw_message(&mut self, message: Value) -> Result<()> {
+        if let Some(connection) = self.connection.as_mut() {
+            let text = serde_json::to_string(&message).map_err(|e| {
+                PolyError::parse(
+                    format!("Failed to serialize subscription message: {}", e),
+                    None,
+                )
+            })?;
+            connection
+                .send(Message::Text(text.into()))
+                .await
+                .map_err(|e| {
+                    PolyError::stream(
+                        format!("Failed to send message: {}", e),
+                        crate::errors::StreamErrorKind::MessageCorrupted,
                     )
                 })?;
-            Ok(WssUserEvent::Order(parsed))
+            return Ok(());
         }
-        other => Err(PolyError::parse(
-            format!("Unknown user event_type: {}", other),
-            None,
-        )),
+        Err(PolyError::stream(
+            "WebSocket connection not established",
+            crate::errors::StreamErrorKind::ConnectionFailed,
+        ))
+    }
+
+    async fn connect(&mut self) -> Result<()> {
+        self.connection =
+            Some(dial_with_backoff(&self.connect_url, "activity", &mut self.stats).await?);
+        Ok(())
+    }
+
+    async fn ensure_connection(&mut self) -> Result<()> {
+        if self.connection.is_none() {
+            self.connect().await?;
+            self.subscription_pending = true;
+        }
+        if self.subscription_pending {
+            self.send_subscription().await?;
+            self.subscription_pending = false;
+        }
+        Ok(())
+    }
+
+    /// Subscribe to the exchange-wide trade stream.
+    pub async fn subscribe(&mut self) -> Result<()> {
+        self.subscribed = true;
+        self.subscription_pending = true;
+        self.ensure_connection().await
+    }
+
+    /// Read the next trade that passes the condition filter, reconnecting
+    /// transparently when the socket drops.
+    pub async fn next_event(&mut self) -> Result<WssMarketEvent> {
+        loop {
+            if let Some(evt) = self.pending_events.pop_front() {
+                return Ok(evt);
+            }
+            self.ensure_connection().await?;
+
+            match timeout(
+                ACTIVITY_KEEPALIVE_INTERVAL,
+                self.connection.as_mut().unwrap().next(),
+            )
+            .await
+            {
+                Ok(Some(Ok(Message::Text(text)))) => {
+                    let trimmed = text.trim();
+                    if !trimmed.starts_with('{') && !trimmed.starts_with('[') {
+                        // PONG replies and empty keepalive frames
+                        continue;
+                    }
+                    let events = match parse_activity_events(trimmed, &self.condition_filter) {
+                        Ok(events) => events,
+                        Err(err) => {
+                            warn!("Skipping malformed activity frame: {}", err);
+                            self.stats.errors += 1;
+                            continue;
+                        }
+                    };
+                    let received_at = SystemTime::now();
+                    self.stats.messages_received += events.len() as u64;
+                    self.stats.last_message_time = Some(Utc::now());
+                    self.pending_events
+                        .extend(events.into_iter().map(|mut evt| {
+                            evt.stamp_received(received_at);
+                            telemetry::wss_event("activity", evt.event_type());
+                            evt
+                        }));
+                }
+                Ok(Some(Ok(Message::Ping(payload)))) => {
+                    if let Some(connection) = self.connection.as_mut() {
+                        let _ = connection.send(Message::Pong(payload)).await;
+                    }
+                }
+                Ok(Some(Ok(Message::Close(_)))) => {
+                    self.disconnect_history.push_back(Utc::now());
+                    if self.disconnect_history.len() > 5 {
+                        self.disconnect_history.pop_front();
+                    }
+                    self.connection = None;
+                }
+                Ok(Some(Ok(_))) => {}
+                Ok(Some(Err(err))) => {
+                    warn!("WebSocket error: {}", err);
+                    self.connection = None;
+                    self.stats.errors += 1;
+                }
+                Ok(None) => {
+                    self.connection = None;
+                }
+                Err(_) => {
+                    if let Some(connection) = self.connection.as_mut() {
+                        let _ = connection.send(Message::Text("PING".into())).await;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Parse an RTDS frame, keeping only `activity`/`trades` messages whose
+/// condition ID is in `condition_filter` (or all of them when it's empty).
+///
+/// A trade that doesn't decode is logged and skipped; only a frame that isn't JSON
+/// is an error.
+fn parse_activity_events(
+    text: &str,
+    condition_filter: &HashSet<String>,
+) -> Result<Vec<WssMarketEvent>> {
+    let value: Value = serde_json::from_str(text)
+        .map_err(|err| PolyError::parse(format!("Invalid JSON: {}", err), Some(Box::new(err))))?;
+
+    let mut events = Vec::new();
+    for mut message in value.into_events() {
+        if message.str_field("topic") != Some("activity")
+            || message.str_field("type") != Some("trades")
+        {
+            continue;
+        }
+        let Some(payload) = message.get_mut("payload").map(Value::take) else {
+            continue;
+        };
+        let trade: ActivityTradePayload = match payload.decode("activity trade") {
+            Ok(trade) => trade,
+            Err(err) => {
+                warn!("Skipping malformed activity trade: {}", err);
+                continue;
+            }
+        };
+        if !condition_filter.is_empty()
+            && !condition_filter.contains(&trade.condition_id.to_ascii_lowercase())
+        {
+            continue;
+        }
+        events.push(WssMarketEvent::LastTrade(trade.into()));
+    }
+    Ok(events)
+}
+
+/// A parsed JSON frame from one of the JSON backends.
+///
+/// Keeping the typed decode on the backend's own value type matters for
+/// simd-json: converting its tape into a `serde_json::Value` first costs more
+/// than the SIMD parse saves.
+trait FrameValue: Sized {
+    fn into_events(self) -> Vec<Self>;
+    fn str_field(&self, key: &str) -> Option<&str>;
+    fn decode<T: DeserializeOwned>(self, label: &str) -> Result<T>;
+}
+
+impl FrameValue for Value {
+    fn into_events(self) -> Vec<Self> {
+        match self {
+            Value::Array(items) => items,
+            value => vec![value],
+        }
+    }
+
+    fn str_field(&self, key: &str) -> Option<&str> {
+        self.get(key).and_then(|v| v.as_str())
+    }
+
+    fn decode<T: DeserializeOwned>(self, label: &str) -> Result<T> {
+        serde_json::from_value(self).map_err(|err| {
+            PolyError::parse(
+                format!("Failed to parse {}: {}", label, err),
+                Some(Box::new(err)),
+            )
+        })
+    }
+}
+
+#[cfg(feature = "simd-json")]
+impl FrameValue for simd_json::BorrowedValue<'_> {
+    fn into_events(self) -> Vec<Self> {
+        match self {
+            simd_json::BorrowedValue::Array(items) => *items,
+            value => vec![value],
+        }
+    }
+
+    fn str_field(&self, key: &str) -> Option<&str> {
+        use simd_json::prelude::*;
+        self.get_str(key)
+    }
+
+    fn decode<T: DeserializeOwned>(self, label: &str) -> Result<T> {
+        simd_json::serde::from_borrowed_value(self).map_err(|err| {
+            PolyError::parse(
+                format!("Failed to parse {}: {}", label, err),
+                Some(Box::new(err)),
+            )
+        })
+    }
+}
+
+#[cfg_attr(feature = "simd-json", allow(dead_code))]
+fn parse_frame_serde<E>(text: &str, parse_event: impl Fn(Value) -> Result<E>) -> Result<Vec<E>> {
+    let value: Value = serde_json::from_str(text)
+        .map_err(|err| PolyError::parse(format!("Invalid JSON: {}", err), Some(Box::new(err))))?;
+    value.into_events().into_iter().map(parse_event).collect()
+}
+
+/// simd-json parses in place and scribbles over its input, so the frame is
+/// copied into an owned buffer rather than handing it the socket's bytes.
+#[cfg(feature = "simd-json")]
+fn parse_frame_simd<E>(
+    text: &str,
+    parse_event: impl for<'a> Fn(simd_json::BorrowedValue<'a>) -> Result<E>,
+) -> Result<Vec<E>> {
+    let mut buffer = text.as_bytes().to_vec();
+    let value = simd_json::to_borrowed_value(&mut buffer)
+        .map_err(|err| PolyError::parse(format!("Invalid JSON: {}", err), Some(Box::new(err))))?;
+    value.into_events().into_iter().map(parse_event).collect()
+}
+
+/// Parse a market channel frame (a single event or an array of events).
+///
+/// Frames are parsed with serde_json, or with simd-json when the `simd-json`
+/// feature is enabled.
+pub fn parse_market_events(text: &str) -> Result<Vec<WssMarketEvent>> {
+    #[cfg(feature = "simd-json")]
+    return parse_frame_simd(text, |value| parse_market_event_value(value));
+    #[cfg(not(feature = "simd-json"))]
+    return parse_frame_serde(text, parse_market_event_value);
+}
+
+fn parse_market_event_value<V: FrameValue>(value: V) -> Result<WssMarketEvent> {
+    let event_type = value
+        .str_field("event_type")
+        .or_else(|| value.str_field("type"))
+        .or_else(|| value.str_field("error").map(|_| "error"))
+        .ok_or_else(|| PolyError::parse("Missing event_type/type in market message", None))?
+        .to_owned();
+
+    match event_type.as_str() {
+        "error" => Ok(WssMarketEvent::SubscriptionError(
+            value.decode::<ErrorFrame>("error frame")?.into(),
+        )),
+        "book" => Ok(WssMarketEvent::Book(value.decode("book message")?)),
+        "price_change" => Ok(WssMarketEvent::PriceChange(value.decode("price_change")?)),
+        "tick_size_change" => Ok(WssMarketEvent::TickSizeChange(
+            value.decode("tick_size_change")?,
+        )),
+        "last_trade_price" => Ok(WssMarketEvent::LastTrade(value.decode("last_trade_price")?)),
+        other => Err(PolyError::parse(
+            format!("Unknown market event_type: {}", other),
+            None,
+        )),
+    }
+}
+
+/// Just the discriminator fields of a market event; everything else is skipped.
+#[derive(Deserialize)]
+struct EventTag<'a> {
+    #[serde(borrow, default)]
+    event_type: Option<Cow<'a, str>>,
+    #[serde(borrow, default, rename = "type")]
+    message_type: Option<Cow<'a, str>>,
+    #[serde(borrow, default)]
+    error: Option<Cow<'a, str>>,
+}
+
+/// Parse a market channel frame into borrowed events.
+///
+/// Each event is read twice - once for its `event_type`, once into its typed
+/// form - but neither pass builds a JSON tree, so the only allocations are the
+/// level vectors. This path always uses serde_json, since simd-json can't lend
+/// out slices of a buffer it has rewritten in place.
+pub fn parse_market_events_borrowed(text: &str) -> Result<Vec<WssMarketEventRef<'_>>> {
+    parse_market_events_borrowed_where(text, |_| true)
+}
+
+/// [`parse_market_events_borrowed`], skipping events whose kind fails `keep`
+///
+/// Skipped events are only read as far as their `event_type`. Subscription errors are
+/// always kept, and unknown event types are still an error.
+pub fn parse_market_events_borrowed_where(
+    text: &str,
+    keep: impl Fn(EventKind) -> bool,
+) -> Result<Vec<WssMarketEventRef<'_>>> {
+    if text.trim_start().starts_with('[') {
+        let raw: Vec<&RawValue> = serde_json::from_str(text).map_err(|err| {
+            PolyError::parse(format!("Invalid JSON: {}", err), Some(Box::new(err)))
+        })?;
+        raw.into_iter()
+            .filter_map(|event| parse_market_event_borrowed(event.get(), &keep).transpose())
+            .collect()
+    } else {
+        Ok(parse_market_event_borrowed(text, &keep)?
+            .into_iter()
+            .collect())
+    }
+}
+
+fn parse_market_event_borrowed<'a>(
+    text: &'a str,
+    keep: &impl Fn(EventKind) -> bool,
+) -> Result<Option<WssMarketEventRef<'a>>> {
+    fn typed<'a, T: Deserialize<'a>>(text: &'a str, label: &str) -> Result<T> {
+        serde_json::from_str(text).map_err(|err| {
+            PolyError::parse(
+                format!("Failed to parse {}: {}", label, err),
+                Some(Box::new(err)),
+            )
+        })
+    }
+
+    let tag: EventTag<'_> = typed(text, "market message")?;
+    let event_type = tag
+        .event_type
+        .or(tag.message_type)
+        .or_else(|| tag.error.map(|_| Cow::Borrowed("error")))
+        .ok_or_else(|| PolyError::parse("Missing event_type/type in market message", None))?;
+    if EventKind::from_event_type(&event_type).is_some_and(|kind| !keep(kind)) {
+        return Ok(None);
+    }
+
+    let event = match event_type.as_ref() {
+        "error" => {
+            WssMarketEventRef::SubscriptionError(typed::<ErrorFrame>(text, "error frame")?.into())
+        }
+        "book" => WssMarketEventRef::Book(typed(text, "book message")?),
+        "price_change" => WssMarketEventRef::PriceChange(typed(text, "price_change")?),
+        "tick_size_change" => WssMarketEventRef::TickSizeChange(typed(text, "tick_size_change")?),
+        "last_trade_price" => WssMarketEventRef::LastTrade(typed(text, "last_trade_price")?),
+        other => {
+            return Err(PolyError::parse(
+                format!("Unknown market event_type: {}", other),
+                None,
+            ));
+        }
+    };
+    Ok(Some(event))
+}
+
+/// Parse a user channel frame (a single event or an array of events).
+pub fn parse_user_events(text: &str) -> Result<Vec<WssUserEvent>> {
+    #[cfg(feature = "simd-json")]
+    return parse_frame_simd(text, |value| parse_user_event_value(value));
+    #[cfg(not(feature = "simd-json"))]
+    return parse_frame_serde(text, parse_user_event_value);
+}
+
+fn parse_user_event_value<V: FrameValue>(value: V) -> Result<WssUserEvent> {
+    let event_type = value
+        .str_field("event_type")
+        .ok_or_else(|| PolyError::parse("Missing event_type in user message", None))?
+        .to_owned();
+
+    match event_type.as_str() {
+        "trade" => Ok(WssUserEvent::Trade(value.decode("user trade message")?)),
+        "order" => Ok(WssUserEvent::Order(value.decode("user order message")?)),
+        other => Err(PolyError::parse(
+            format!("Unknown user event_type: {}", other),
+            None,
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+    use std::str::FromStr;
+
+    const MARKET_BOOK: &str = include_str!("../tests/fixtures/wss/market_book.json");
+    const PRICE_CHANGE: &str = include_str!("../tests/fixtures/wss/price_change.json");
+    const MARKET_MISC: &str = include_str!("../tests/fixtures/wss/market_misc.json");
+    const USER_EVENTS: &str = include_str!("../tests/fixtures/wss/user_events.json");
+
+    // These run against whichever backend is compiled in, so `cargo test` and
+    // `cargo test --features simd-json` cover both.
+
+    #[test]
+    fn test_parse_book_snapshots() {
+        let events = parse_market_events(MARKET_BOOK).unwrap();
+        assert_eq!(events.len(), 2);
+        let WssMarketEvent::Book(book) = &events[0] else {
+            panic!("expected a book event");
+        };
+        assert_eq!(book.bids.len(), 40);
+        assert_eq!(book.asks.len(), 40);
+        assert_eq!(
+            book.bids.last().unwrap().price,
+            Decimal::from_str("0.51").unwrap()
+        );
+        assert_eq!(book.asks[0].price, Decimal::from_str("0.53").unwrap());
+    }
+
+    #[test]
+    fn test_price_change_levels_are_typed() {
+        let events = parse_market_events(PRICE_CHANGE).unwrap();
+        let WssMarketEvent::PriceChange(change) = &events[0] else {
+            panic!("expected a price_change event");
+        };
+        let levels: Vec<_> = change
+            .price_changes
+            .iter()
+            .map(|level| {
+                (
+                    level.side,
+                    level.price,
+                    level.size,
+                    level.best_bid,
+                    level.best_ask,
+                    level.hash.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            levels,
+            vec![
+                (
+                    Side::BUY,
+                    dec!(0.5),
+                    dec!(200),
+                    dec!(0.5),
+                    dec!(1),
+                    "56621a121a47ed9333273e21c83b660cff37ae50"
+                ),
+                (
+                    Side::SELL,
+                    dec!(0.5),
+                    dec!(200),
+                    dec!(0),
+                    dec!(0.5),
+                    "1895759e4df7a796bf4f1c5a5950b748306923e2"
+                ),
+            ]
+        );
+
+        // Bare numbers decode the same as quoted ones, and the hash is optional
+        let numeric = r#"{"event_type": "price_change", "market": "0xabc", "timestamp": "1",
+            "price_changes": [{"asset_id": "1", "price": 0.47, "size": 0, "side": "SELL",
+            "best_bid": 0.46, "best_ask": "0.48"}]}"#;
+        let events = parse_market_events(numeric).unwrap();
+        let WssMarketEvent::PriceChange(change) = &events[0] else {
+            panic!("expected a price_change event");
+        };
+        let level = &change.price_changes[0];
+        assert_eq!((level.price, level.size), (dec!(0.47), Decimal::ZERO));
+        assert_eq!((level.best_bid, level.best_ask), (dec!(0.46), dec!(0.48)));
+        assert!(level.hash.is_empty());
+    }
+
+    #[test]
+    fn test_log_fields_summarise_events() {
+        let events = parse_market_events(MARKET_BOOK).unwrap();
+        let WssMarketEvent::Book(book) = &events[0] else {
+            panic!("expected a book event");
+        };
+        let fields = events[0].to_log_fields();
+        assert_eq!(fields["event"], "book");
+        assert_eq!(fields["market"], book.market.as_str());
+        assert_eq!(fields["bids"], book.bids.len());
+        assert_eq!(fields["asks"], book.asks.len());
+        assert!(!fields.contains_key("hash"));
+
+        let events = parse_market_events(PRICE_CHANGE).unwrap();
+        let fields = events[0].to_log_fields();
+        assert_eq!(
+            fields.keys().collect::<Vec<_>>(),
+            ["event", "market", "num_changes"]
+        );
+        assert_eq!(fields["event"], "price_change");
+        assert_eq!(fields["num_changes"], 2);
+
+        let error =
+            WssMarketEvent::SubscriptionError(SubscriptionError::from_text("INVALID OPERATION"));
+        assert_eq!(error.to_log_fields()["reason"], "INVALID OPERATION");
+        error.log();
+    }
+
+    #[test]
+    fn test_parse_price_change_and_misc() {
+        let events = parse_market_events(PRICE_CHANGE).unwrap();
+        let WssMarketEvent::PriceChange(change) = &events[0] else {
+            panic!("expected a price_change event");
+        };
+        assert_eq!(change.price_changes.len(), 2);
+        assert_eq!(change.price_changes[1].side, Side::SELL);
+
+        let events = parse_market_events(MARKET_MISC).unwrap();
+        assert!(matches!(events[0], WssMarketEvent::TickSizeChange(_)));
+        let WssMarketEvent::LastTrade(trade) = &events[1] else {
+            panic!("expected a last_trade_price event");
+        };
+        assert_eq!(trade.size, Decimal::from_str("219.217767").unwrap());
+    }
+
+    #[test]
+    fn test_parse_user_events() {
+        let events = parse_user_events(USER_EVENTS).unwrap();
+        let WssUserEvent::Trade(trade) = &events[0] else {
+            panic!("expected a trade event");
+        };
+        assert_eq!(trade.maker_orders.len(), 1);
+        let WssUserEvent::Order(order) = &events[1] else {
+            panic!("expected an order event");
+        };
+        assert_eq!(order.message_type, "PLACEMENT");
+        assert!(order.associate_trades.is_none());
+    }
+
+    #[test]
+    fn test_invalid_frames() {
+        assert!(parse_market_events("{\"event_type\": \"book\"").is_err());
+        assert!(parse_market_events("{\"event_type\": \"mystery\"}").is_err());
+        assert!(parse_user_events("{}").is_err());
+    }
+
+    #[test]
+    fn test_borrowed_events_match_owned() {
+        for fixture in [MARKET_BOOK, PRICE_CHANGE, MARKET_MISC] {
+            let owned = parse_market_events(fixture).unwrap();
+            let borrowed: Vec<WssMarketEvent> = parse_market_events_borrowed(fixture)
+                .unwrap()
+                .iter()
+                .map(WssMarketEventRef::to_owned)
+                .collect();
+            assert_eq!(format!("{:?}", owned), format!("{:?}", borrowed));
+        }
+
+        let events = parse_market_events_borrowed(MARKET_BOOK).unwrap();
+        let WssMarketEventRef::Book(book) = &events[0] else {
+            panic!("expected a book event");
+        };
+        assert!(matches!(book.asset_id, Cow::Borrowed(_)));
+        assert!(parse_market_events_borrowed("{\"event_type\": \"mystery\"}").is_err());
+    }
+
+    #[test]
+    fn test_parse_error_frames() {
+        let frame =
+            r#"{"event_type":"error","asset_id":"999","error":"invalid asset id","timestamp":"1"}"#;
+        let WssMarketEvent::SubscriptionError(error) =
+            parse_market_events(frame).unwrap().remove(0)
+        else {
+            panic!("expected a subscription error");
+        };
+        assert_eq!(error.asset_id.as_deref(), Some("999"));
+        assert_eq!(error.reason, "invalid asset id");
+
+        let untagged = r#"{"error":"too many assets"}"#;
+        let Ok(WssMarketEventRef::SubscriptionError(error)) =
+            parse_market_events_borrowed(untagged).map(|mut events| events.remove(0))
+        else {
+            panic!("expected a subscription error");
+        };
+        assert_eq!(error.asset_id, None);
+        assert_eq!(error.reason, "too many assets");
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_with_ack_reports_each_asset() {
+        use crate::testkit::{FeedScript, MockMarketFeed};
+
+        let feed = MockMarketFeed::start(
+            FeedScript::new()
+                .send_json(&json!([{
+                    "event_type": "book",
+                    "asset_id": "111",
+                    "market": "0xabc",
+                    "bids": [{"price": "0.40", "size": "10"}],
+                    "asks": [],
+                    "timestamp": "1",
+                    "hash": "0x0"
+                }]))
+                .send_json(
+                    &json!({"event_type": "error", "asset_id": "999", "error": "invalid asset id"}),
+                ),
+        )
+        .await
+        .unwrap();
+
+        let mut client = feed.market_client();
+        let statuses = client
+            .subscribe_with_ack(
+                vec!["111".to_string(), "999".to_string()],
+                DEFAULT_SUBSCRIBE_ACK_TIMEOUT,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            statuses,
+            vec![
+                ("111".to_string(), SubscriptionStatus::Accepted),
+                (
+                    "999".to_string(),
+                    SubscriptionStatus::Rejected("invalid asset id".to_string())
+                ),
+            ]
+        );
+        // The snapshot read while waiting is still delivered
+        let WssMarketEvent::Book(book) = client.next_event().await.unwrap() else {
+            panic!("expected the queued book snapshot");
+        };
+        assert_eq!(book.bids[0].price, dec!(0.40));
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_with_ack_times_out_when_silent() {
+        let feed = crate::testkit::MockMarketFeed::start(Default::default())
+            .await
+            .unwrap();
+
+        let mut client = feed.market_client();
+        let err = client
+            .subscribe_with_ack(vec!["bogus".to_string()], Duration::from_millis(200))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, PolyError::Timeout { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_market_client_resubscribes_after_disconnect() {
+        use crate::testkit::{FeedScript, MockMarketFeed, fixtures};
+
+        let feed = MockMarketFeed::start(
+            FeedScript::new()
+                .send(fixtures::WSS_MARKET_BOOK)
+                .disconnect()
+                .delay(Duration::from_millis(20))
+                .send(fixtures::WSS_MARKET_MISC),
+        )
+        .await
+        .unwrap();
+
+        let mut client = feed.market_client();
+        client
+            .subscribe_market_channel(vec![fixtures::YES_TOKEN_ID.to_string()])
+            .await
+            .unwrap();
+        let mut kinds = Vec::new();
+        for _ in 0..4 {
+            kinds.push(match client.next_event().await.unwrap() {
+                WssMarketEvent::Book(_) => "book",
+                WssMarketEvent::TickSizeChange(_) => "tick",
+                WssMarketEvent::LastTrade(_) => "trade",
+                other => panic!("unexpected event {:?}", other),
+            });
+        }
+        assert_eq!(kinds, ["book", "book", "tick", "trade"]);
+
+        let subscriptions = feed.subscriptions();
+        assert_eq!(subscriptions.len(), 2);
+        assert_eq!(subscriptions[1]["assets_ids"][0], fixtures::YES_TOKEN_ID);
+    }
+
+    #[tokio::test]
+    async fn test_market_client_connects_lazily() {
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+
+        let mut client = WssMarketClient::with_url(&url);
+        assert!(!client.is_connected());
+        assert!(
+            timeout(Duration::from_millis(50), listener.accept())
+                .await
+                .is_err()
+        );
+
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let Some(Ok(Message::Text(subscription))) = socket.next().await else {
+                panic!("expected a subscription frame");
+            };
+            let subscription: Value = serde_json::from_str(&subscription).unwrap();
+            assert_eq!(subscription["assets_ids"][0], "111");
+            socket
+                .send(Message::Text(MARKET_BOOK.into()))
+                .await
+                .unwrap();
+            let _ = socket.next().await;
+        });
+
+        client.subscribe(vec!["111".to_string()]).await.unwrap();
+        assert!(client.is_connected());
+        assert!(matches!(
+            client.next_event().await.unwrap(),
+            WssMarketEvent::Book(_)
+        ));
+
+        drop(client);
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_market_client_reads_before_subscribing() {
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+            socket
+                .send(Message::Text(MARKET_BOOK.into()))
+                .await
+                .unwrap();
+            // The client subscribes only after reading, so this is its first frame
+            let Some(Ok(Message::Text(subscription))) = socket.next().await else {
+                panic!("expected a subscription frame");
+            };
+            let subscription: Value = serde_json::from_str(&subscription).unwrap();
+            assert_eq!(subscription["assets_ids"][0], "222");
+            let _ = socket.next().await;
+        });
+
+        let mut client = WssMarketClient::with_url(&url);
+        client.connect().await.unwrap();
+        assert!(client.is_connected());
+        // Nothing is subscribed yet; reading neither fails nor sends anything
+        assert!(matches!(
+            client.next_event().await.unwrap(),
+            WssMarketEvent::Book(_)
+        ));
+        client.subscribe(vec!["222".to_string()]).await.unwrap();
+
+        drop(client);
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_filtered_streams() {
+        use crate::testkit::{FeedScript, MockMarketFeed, fixtures};
+
+        let script = FeedScript::new()
+            .send(fixtures::WSS_MARKET_BOOK)
+            .send(fixtures::WSS_PRICE_CHANGE)
+            .send(fixtures::WSS_MARKET_MISC)
+            .send(fixtures::WSS_MARKET_BOOK);
+        let subscribed = || {
+            vec![
+                fixtures::YES_TOKEN_ID.to_string(),
+                fixtures::NO_TOKEN_ID.to_string(),
+            ]
+        };
+
+        let feed = MockMarketFeed::start(script.clone()).await.unwrap();
+        let mut client = feed.market_client();
+        client.subscribe_market_channel(subscribed()).await.unwrap();
+        let events: Vec<WssMarketEvent> = client
+            .filter_by_asset(fixtures::NO_TOKEN_ID.to_string())
+            .take(3)
+            .map(|evt| evt.unwrap())
+            .collect()
+            .await;
+        assert!(
+            matches!(&events[0], WssMarketEvent::Book(book) if book.asset_id == fixtures::NO_TOKEN_ID)
+        );
+        // The price change touches both assets, the misc events only the other one
+        assert!(matches!(events[1], WssMarketEvent::PriceChange(_)));
+        assert!(
+            matches!(&events[2], WssMarketEvent::Book(book) if book.asset_id == fixtures::NO_TOKEN_ID)
+        );
+
+        let feed = MockMarketFeed::start(script).await.unwrap();
+        let mut client = feed.market_client();
+        client.subscribe_market_channel(subscribed()).await.unwrap();
+        let books = client
+            .filter_by_type_book()
+            .take(4)
+            .filter(|evt| futures::future::ready(matches!(evt, Ok(WssMarketEvent::Book(_)))))
+            .count()
+            .await;
+        assert_eq!(books, 4);
+    }
+
+    #[tokio::test]
+    async fn test_map_and_and_then_events() {
+        use crate::testkit::{FeedScript, MockMarketFeed, fixtures};
+
+        let script = FeedScript::new()
+            .send(fixtures::WSS_MARKET_BOOK)
+            .send(fixtures::WSS_PRICE_CHANGE);
+        let subscribed = vec![fixtures::YES_TOKEN_ID.to_string()];
+
+        let feed = MockMarketFeed::start(script.clone()).await.unwrap();
+        let mut client = feed.market_client();
+        client
+            .subscribe_market_channel(subscribed.clone())
+            .await
+            .unwrap();
+        let kinds: Vec<String> = client
+            .map_events(|evt| evt.event_type().to_string())
+            .take(3)
+            .map(|kind| kind.unwrap())
+            .collect()
+            .await;
+        // The book fixture carries one book per outcome
+        assert_eq!(kinds, ["book", "book", "price_change"]);
+
+        let feed = MockMarketFeed::start(script).await.unwrap();
+        let mut client = feed.market_client();
+        client.subscribe_market_channel(subscribed).await.unwrap();
+        let mapped: Vec<Result<String>> = client
+            .and_then_events(|evt| async move {
+                match evt {
+                    WssMarketEvent::Book(book) => Ok(book.asset_id),
+                    _ => Err(PolyError::validation("not a book")),
+                }
+            })
+            .take(3)
+            .collect()
+            .await;
+        assert!(mapped[..2].iter().all(Result::is_ok));
+        assert!(matches!(mapped[2], Err(PolyError::Validation { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_full_buffer_drops_oldest_and_reports_overflow() {
+        use crate::testkit::{FeedScript, MockMarketFeed, fixtures};
+
+        let script = FeedScript::new()
+            .send(fixtures::WSS_MARKET_BOOK)
+            .send(fixtures::WSS_PRICE_CHANGE);
+        let feed = MockMarketFeed::start(script).await.unwrap();
+        let mut client = feed.market_client().with_buffer_capacity(1);
+        assert_eq!(client.buffer_utilization(), 0.0);
+        // The unknown asset keeps the ack wait reading past the book frame
+        client
+            .subscribe_with_ack(
+                vec![
+                    fixtures::YES_TOKEN_ID.to_string(),
+                    fixtures::NO_TOKEN_ID.to_string(),
+                    "unknown".to_string(),
+                ],
+                Duration::from_millis(300),
+            )
+            .await
+            .unwrap();
+
+        // Both books came from the earlier frame; the price change is from the last
+        let first = client.next_event().await.unwrap();
+        assert!(matches!(
+            first,
+            WssMarketEvent::BufferOverflow { dropped_count: 2 }
+        ));
+        assert!(first.involves_asset("any"));
+        assert_eq!(first.kind(), None);
+        assert_eq!(client.buffer_utilization(), 1.0);
+        assert!(matches!(
+            client.next_event().await.unwrap(),
+            WssMarketEvent::PriceChange(_)
+        ));
+        assert_eq!(client.buffer_utilization(), 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_frame_larger_than_buffer_is_delivered_whole() {
+        use crate::testkit::{FeedScript, MockMarketFeed};
+
+        let asset_ids: Vec<String> = (0..DEFAULT_EVENT_BUFFER_CAPACITY + 76)
+            .map(|i| format!("{}", 1000 + i))
+            .collect();
+        let frame: Vec<Value> = asset_ids
+            .iter()
+            .map(|id| {
+                json!({
+                    "event_type": "book",
+                    "asset_id": id,
+                    "market": "0xabc",
+                    "bids": [{"price": "0.40", "size": "10"}],
+                    "asks": [],
+                    "timestamp": "1",
+                    "hash": "0x0"
+                })
+            })
+            .collect();
+        let frame = Value::Array(frame);
+
+        // Straight off the socket
+        let feed = MockMarketFeed::start(FeedScript::new().send_json(&frame))
+            .await
+            .unwrap();
+        let mut client = feed.market_client();
+        client
+            .subscribe_market_channel(asset_ids.clone())
+            .await
+            .unwrap();
+        for id in &asset_ids {
+            let WssMarketEvent::Book(book) = client.next_event().await.unwrap() else {
+                panic!("expected a book for {id}");
+            };
+            assert_eq!(&book.asset_id, id);
+        }
+
+        // And through the ack wait, which reads the whole frame ahead
+        let feed = MockMarketFeed::start(FeedScript::new().send_json(&frame))
+            .await
+            .unwrap();
+        let mut client = feed.market_client();
+        let statuses = client
+            .subscribe_with_ack(asset_ids.clone(), DEFAULT_SUBSCRIBE_ACK_TIMEOUT)
+            .await
+            .unwrap();
+        assert!(
+            statuses
+                .iter()
+                .all(|(_, status)| *status == SubscriptionStatus::Accepted)
+        );
+        for id in &asset_ids {
+            let WssMarketEvent::Book(book) = client.next_event().await.unwrap() else {
+                panic!("expected a book for {id}");
+            };
+            assert_eq!(&book.asset_id, id);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_only_events_skips_other_kinds() {
+        use crate::testkit::{FeedScript, MockMarketFeed, fixtures};
+
+        // Skipped kinds are never decoded, so a malformed book is no error either
+        let frame = r#"[{"event_type": "book", "asset_id": 1},
+            {"event_type": "last_trade_price", "asset_id": "1", "fee_rate_bps": "0",
+             "market": "0xabc", "price": "0.5", "size": "10", "side": "BUY"}]"#;
+        let kept =
+            parse_market_events_borrowed_where(frame, |kind| kind == EventKind::LastTrade).unwrap();
+        assert!(matches!(kept[..], [WssMarketEventRef::LastTrade(_)]));
+
+        let feed = MockMarketFeed::start(
+            FeedScript::new()
+                .send(fixtures::WSS_MARKET_BOOK)
+                .send(fixtures::WSS_PRICE_CHANGE)
+                .send(fixtures::WSS_MARKET_MISC)
+                .send(fixtures::WSS_MARKET_BOOK),
+        )
+        .await
+        .unwrap();
+        let mut client = feed.market_client().only_events(&[EventKind::LastTrade]);
+        client
+            .subscribe_market_channel(vec![fixtures::YES_TOKEN_ID.to_string()])
+            .await
+            .unwrap();
+
+        let mut trades = 0;
+        while let Ok(event) =
+            tokio::time::timeout(Duration::from_millis(300), client.next_event()).await
+        {
+            let event = event.unwrap();
+            assert_eq!(event.kind(), Some(EventKind::LastTrade), "{:?}", event);
+            trades += 1;
+        }
+        assert!(trades > 0);
+        let stats = client.stats();
+        assert_eq!(stats.messages_received, trades);
+        assert_eq!(stats.errors, 0);
+    }
+
+    #[tokio::test]
+    async fn test_next_event_timeout_keeps_pending_events() {
+        use crate::testkit::{FeedScript, MockMarketFeed, fixtures};
+
+        let feed = MockMarketFeed::start(
+            FeedScript::new()
+                .delay(Duration::from_millis(200))
+                .send(fixtures::WSS_MARKET_MISC),
+        )
+        .await
+        .unwrap();
+        let mut client = feed.market_client();
+        client
+            .subscribe_market_channel(vec![fixtures::YES_TOKEN_ID.to_string()])
+            .await
+            .unwrap();
+
+        let silent = client
+            .next_event_timeout(Duration::from_millis(20))
+            .await
+            .unwrap();
+        assert!(silent.is_none());
+
+        // The frame carries two events; the second is queued and survives a zero wait
+        let first = client
+            .next_event_timeout(Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert!(first.is_some());
+        let second = client.next_event_timeout(Duration::ZERO).await.unwrap();
+        assert!(second.is_some());
+        assert!(
+            client
+                .next_event_timeout(Duration::from_millis(20))
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(client.stats().messages_received, 2);
+    }
+
+    #[tokio::test]
+    async fn test_dump_and_replay_round_trip() {
+        use crate::testkit::{FeedScript, MockMarketFeed, fixtures};
+
+        let feed = MockMarketFeed::start(
+            FeedScript::new()
+                .send(fixtures::WSS_MARKET_BOOK)
+                .send(fixtures::WSS_PRICE_CHANGE)
+                .delay(Duration::from_millis(80))
+                .send(fixtures::WSS_MARKET_MISC),
+        )
+        .await
+        .unwrap();
+        let path = std::env::temp_dir().join(format!("capture-{}.jsonl", uuid::Uuid::new_v4()));
+        let mut client = feed.market_client().with_dump_to_file(&path);
+        client
+            .subscribe_market_channel(vec![fixtures::YES_TOKEN_ID.to_string()])
+            .await
+            .unwrap();
+        let mut live = Vec::new();
+        while let Ok(event) =
+            tokio::time::timeout(Duration::from_millis(300), client.next_event()).await
+        {
+            live.push(event.unwrap());
+        }
+        drop(client);
+        assert!(live.len() >= 3);
+
+        let replayed: Vec<_> = WssMarketClient::replay_from_file(&path, 0.0)
+            .unwrap()
+            .collect()
+            .await;
+        assert_eq!(replayed.len(), live.len());
+        let millis = |evt: &WssMarketEvent| {
+            evt.received_at()
+                .unwrap()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_millis()
+        };
+        for (live, replayed) in live.iter().zip(&replayed) {
+            let replayed = replayed.as_ref().unwrap();
+            assert_eq!(replayed.to_wire().unwrap(), live.to_wire().unwrap());
+            assert_eq!(millis(replayed), millis(live));
+        }
+
+        // Half speed keeps half of the 80ms pause before the last frame
+        let started = std::time::Instant::now();
+        let count = WssMarketClient::replay_from_file(&path, 0.5)
+            .unwrap()
+            .count()
+            .await;
+        assert_eq!(count, live.len());
+        assert!(started.elapsed() >= Duration::from_millis(35));
+
+        std::fs::write(&path, "not json\n\n").unwrap();
+        let lines: Vec<_> = WssMarketClient::replay_from_file(&path, 1.0)
+            .unwrap()
+            .collect()
+            .await;
+        assert!(matches!(lines[..], [Err(PolyError::Parse { .. })]));
+        assert!(WssMarketClient::replay_from_file(&path, -1.0).is_err());
+        std::fs::remove_file(&path).unwrap();
+        assert!(WssMarketClient::replay_from_file(&path, 1.0).is_err());
+    }
+
+    fn activity_frame(condition_id: &str, asset: &str, price: f64) -> String {
+        json!({
+            "connection_id": "Mw6yAfvMLPECG7g=",
+            "payload": {
+                "asset": asset,
+                "conditionId": condition_id,
+                "eventSlug": "fed-decision-in-october",
+                "outcome": "Yes",
+                "outcomeIndex": 0,
+                "price": price,
+                "proxyWallet": "0x6af75d4e4aaf700450efbac3708cce1665810ff1",
+                "side": "BUY",
+                "size": 25.5,
+                "slug": "fed-decreases-interest-rates-by-25-bps",
+                "timestamp": 1760000001,
+                "transactionHash": "0x2d5bda5a0a9c4b2e5a1f0a4d2c5c8d99e3fb0cbe6d7b1f0a9e4d2c1b0a987654"
+            },
+            "timestamp": 1760000001234u64,
+            "topic": "activity",
+            "type": "trades"
+        })
+        .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_activity_firehose_filters_by_condition() {
+        use tokio::net::TcpListener;
+
+        let wanted = "0xAAA1";
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let frames = vec![
+            activity_frame("0xbbb2", "111", 0.31),
+            json!({"topic": "comments", "type": "comment_created", "payload": {}}).to_string(),
+            activity_frame("0xaaa1", "222", 0.64),
+            "{not json".to_string(),
+            json!({"topic": "activity", "type": "trades", "payload": {"asset": "444"}}).to_string(),
+            activity_frame("0xccc3", "333", 0.12),
+            activity_frame("0xaaa1", "223", 0.36),
+        ];
+
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let Some(Ok(Message::Text(subscription))) = socket.next().await else {
+                panic!("expected a subscription frame");
+            };
+            let subscription: Value = serde_json::from_str(&subscription).unwrap();
+            assert_eq!(subscription["subscriptions"][0]["topic"], "activity");
+            for frame in frames {
+                socket.send(Message::Text(frame.into())).await.unwrap();
+            }
+            // Hold the socket open until the client has read everything; the
+            // subscription must not be sent again in the meantime
+            let next = socket.next().await;
+            assert!(
+                !matches!(next, Some(Ok(Message::Text(_)))),
+                "unexpected frame {:?}",
+                next
+            );
+        });
+
+        let mut client = WssActivityClient::with_url(&url);
+        client.set_condition_filter(vec![wanted.to_string()]);
+        client.subscribe().await.unwrap();
+
+        for expected in ["222", "223"] {
+            let WssMarketEvent::LastTrade(trade) = client.next_event().await.unwrap() else {
+                panic!("expected a last trade event");
+            };
+            assert_eq!(trade.asset_id, expected);
+            assert_eq!(trade.market, "0xaaa1");
+            assert_eq!(trade.size, Decimal::from_str("25.5").unwrap());
+        }
+        assert_eq!(client.stats().messages_received, 2);
+        assert_eq!(client.stats().errors, 1);
+
+        drop(client);
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_market_events_carry_server_and_arrival_times() {
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let sent_at = Utc::now() - chrono::Duration::milliseconds(250);
+        let frame = json!([
+            {
+                "event_type": "price_change",
+                "market": "0xabc",
+                "timestamp": sent_at.timestamp_millis().to_string(),
+                "price_changes": []
+            },
+            // Some events omit the timestamp entirely
+            {
+                "event_type": "tick_size_change",
+                "asset_id": "1",
+                "market": "0xabc",
+                "old_tick_size": "0.01",
+                "new_tick_size": "0.001",
+                "side": "BUY"
+            }
+        ])
+        .to_string();
+
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let _subscription = socket.next().await;
+            socket.send(Message::Text(frame.into())).await.unwrap();
+            let _ = socket.next().await;
+        });
+
+        let mut client = WssMarketClient::with_url(&url);
+        client
+            .subscribe_market_channel(vec!["1".to_string()])
+            .await
+            .unwrap();
+
+        let change = client.next_event().await.unwrap();
+        assert!(matches!(change, WssMarketEvent::PriceChange(_)));
+        assert_eq!(
+            change.server_time().map(|at| at.timestamp_millis()),
+            Some(sent_at.timestamp_millis())
+        );
+        assert!(change.received_at().is_some());
+        let latency = change.latency().unwrap();
+        assert!(
+            latency >= chrono::Duration::milliseconds(250)
+                && latency < chrono::Duration::seconds(10),
+            "{:?}",
+            latency
+        );
+
+        let tick = client.next_event().await.unwrap();
+        assert!(tick.received_at().is_some());
+        assert_eq!(tick.server_time(), None);
+        assert_eq!(tick.latency(), None);
+
+        drop(client);
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_unified_client_multiplexes_channels() {
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+
+        let server = tokio::spawn(async move {
+            let mut handlers = Vec::new();
+            for _ in 0..2 {
+                let (stream, _) = listener.accept().await.unwrap();
+                handlers.push(tokio::spawn(async move {
+                    let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+                    let Some(Ok(Message::Text(subscription))) = socket.next().await else {
+                        panic!("expected a subscription frame");
+                    };
+                    let subscription: Value = serde_json::from_str(&subscription).unwrap();
+                    let frame = match subscription["type"].as_str() {
+                        Some("market") => MARKET_MISC,
+                        Some("user") => {
+                            assert_eq!(subscription["auth"]["apiKey"], "key");
+                            assert_eq!(subscription["markets"], json!([]));
+                            USER_EVENTS
+                        }
+                        other => panic!("unexpected subscription type {:?}", other),
+                    };
+                    socket.send(Message::Text(frame.into())).await.unwrap();
+                    let _ = socket.next().await;
+                }));
+            }
+            for handler in handlers {
+                handler.await.unwrap();
+            }
+        });
+
+        let credentials = ApiCredentials {
+            api_key: "key".to_string(),
+            secret: "secret".to_string(),
+            passphrase: "passphrase".to_string(),
+        };
+        let mut client = WssClient::with_url(&url);
+        client
+            .subscribe_market_channel(vec!["123".to_string()])
+            .await
+            .unwrap();
+        client.subscribe_user_channel(&credentials).await.unwrap();
+
+        let (mut market, mut user) = (0, 0);
+        for _ in 0..4 {
+            match client.next_event().await.unwrap() {
+                WssEvent::Market(_) => market += 1,
+                WssEvent::User(_) => user += 1,
+            }
+        }
+        assert_eq!((market, user), (2, 2));
+        assert_eq!(client.market_stats().messages_received, 2);
+        assert_eq!(client.user_stats().unwrap().messages_received, 2);
+
+        drop(client);
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_user_session_id_survives_reconnect() {
+        use crate::testkit::{FeedScript, MockMarketFeed};
+
+        let feed = MockMarketFeed::start(
+            FeedScript::new()
+                .send(USER_EVENTS)
+                .disconnect()
+                .send(USER_EVENTS),
+        )
+        .await
+        .unwrap();
+        let credentials = ApiCredentials {
+            api_key: "key".to_string(),
+            secret: "secret".to_string(),
+            passphrase: "passphrase".to_string(),
+        };
+        let mut client = WssUserClient::with_url(feed.url(), credentials.clone());
+        client.subscribe(Vec::new()).await.unwrap();
+        for _ in 0..4 {
+            client.next_event().await.unwrap();
+        }
+
+        let subscriptions = feed.subscriptions();
+        assert_eq!(subscriptions.len(), 2);
+        for subscription in &subscriptions {
+            assert_eq!(subscription["session_id"], client.session_id());
+        }
+        let other = WssUserClient::with_url(feed.url(), credentials);
+        assert_ne!(other.session_id(), client.session_id());
+    }
+
+    #[tokio::test]
+    async fn test_unified_client_requires_a_subscription() {
+        let mut client = WssClient::with_url("ws://127.0.0.1:9");
+        assert!(client.next_event().await.is_err());
+    }
+
+    #[test]
+    fn test_activity_without_filter_passes_everything() {
+        let frame = format!(
+            "[{},{}]",
+            activity_frame("0xaaa1", "1", 0.5),
+            activity_frame("0xbbb2", "2", 0.5)
+        );
+        let events = parse_activity_events(&frame, &HashSet::new()).unwrap();
+        assert_eq!(events.len(), 2);
+    }
+
+    #[cfg(feature = "simd-json")]
+    #[test]
+    fn test_backends_agree() {
+        // The event types don't implement PartialEq, so compare their Debug output
+        for fixture in [MARKET_BOOK, PRICE_CHANGE, MARKET_MISC] {
+            let serde = parse_frame_serde(fixture, parse_market_event_value).unwrap();
+            let simd = parse_frame_simd(fixture, |v| parse_market_event_value(v)).unwrap();
+            assert_eq!(format!("{:?}", serde), format!("{:?}", simd));
+        }
+        let serde = parse_frame_serde(USER_EVENTS, parse_user_event_value).unwrap();
+        let simd = parse_frame_simd(USER_EVENTS, |v| parse_user_event_value(v)).unwrap();
+        assert_eq!(format!("{:?}", serde), format!("{:?}", simd));
+
+        // The caller's frame must be left intact after parsing in place
+        let frame = MARKET_BOOK.to_string();
+        parse_frame_simd(&frame, |v| parse_market_event_value(v)).unwrap();
+        assert_eq!(frame, MARKET_BOOK);
     }
 }
@@ -0,0 +1,52 @@
+//! Offline harness smoke tests; run with `cargo test --features testkit`
+#![cfg(feature = "testkit")]
+
+use polysqueeze::WssMarketEvent;
+use polysqueeze::testkit::{FeedScript, MockExchange, MockMarketFeed, fixtures};
+use std::time::{Duration, Instant};
+
+#[tokio::test]
+async fn rest_snapshot_then_stream_across_a_disconnect() {
+    let exchange = MockExchange::start().await;
+    let client = exchange.clob_client();
+    let book = client
+        .get_order_book(fixtures::YES_TOKEN_ID, None)
+        .await
+        .expect("fixture book");
+    assert_eq!(book.asset_id, fixtures::YES_TOKEN_ID);
+
+    let feed = MockMarketFeed::start(
+        FeedScript::new()
+            .send(fixtures::WSS_MARKET_BOOK)
+            .disconnect()
+            .delay(Duration::from_millis(50))
+            .send(fixtures::WSS_PRICE_CHANGE),
+    )
+    .await
+    .expect("mock feed");
+
+    let mut ws = feed.market_client();
+    ws.subscribe_market_channel(vec![
+        fixtures::YES_TOKEN_ID.to_string(),
+        fixtures::NO_TOKEN_ID.to_string(),
+    ])
+    .await
+    .expect("subscribe");
+
+    let started = Instant::now();
+    let mut snapshots = Vec::new();
+    loop {
+        match ws.next_event().await.expect("scripted event") {
+            WssMarketEvent::Book(book) => snapshots.push(book.asset_id),
+            WssMarketEvent::PriceChange(change) => {
+                assert_eq!(change.market, fixtures::CONDITION_ID);
+                break;
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    assert_eq!(snapshots, [fixtures::YES_TOKEN_ID, fixtures::NO_TOKEN_ID]);
+    assert!(started.elapsed() >= Duration::from_millis(50));
+    assert_eq!(feed.subscriptions().len(), 2);
+}
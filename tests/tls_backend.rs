@@ -0,0 +1,108 @@
+//! Both clients speak TLS through whichever backend feature is enabled. Run once per
+//! backend:
+//!
+//! ```text
+//! cargo test --test tls_backend
+//! cargo test --test tls_backend --no-default-features --features rustls-tls
+//! ```
+//!
+//! The server is a one-shot rustls listener presenting `fixtures/tls/localhost.pem`,
+//! issued for 127.0.0.1 by the test CA in `fixtures/tls/ca.pem`.
+
+#![cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+
+use polysqueeze::client::ClobClient;
+use polysqueeze::wss::WssMarketClient;
+use rust_decimal::Decimal;
+use rustls_pki_types::pem::PemObject;
+use rustls_pki_types::{CertificateDer, PrivateKeyDer};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio_rustls::TlsAcceptor;
+use tokio_rustls::rustls::{self, ServerConfig};
+
+const CA: &[u8] = include_bytes!("fixtures/tls/ca.pem");
+const CERT: &[u8] = include_bytes!("fixtures/tls/localhost.pem");
+const KEY: &[u8] = include_bytes!("fixtures/tls/localhost.key");
+
+fn acceptor() -> TlsAcceptor {
+    let certs = CertificateDer::pem_slice_iter(CERT)
+        .collect::<Result<Vec<_>, _>>()
+        .expect("test certificate");
+    let key = PrivateKeyDer::from_pem_slice(KEY).expect("test key");
+    let config =
+        ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .expect("protocol versions")
+            .with_no_client_auth()
+            .with_single_cert(certs, key)
+            .expect("server config");
+    TlsAcceptor::from(Arc::new(config))
+}
+
+#[tokio::test]
+async fn rest_client_round_trips_over_tls() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("https://{}", listener.local_addr().unwrap());
+
+    let server = tokio::spawn(async move {
+        let (socket, _) = listener.accept().await.unwrap();
+        let mut tls = acceptor().accept(socket).await.expect("TLS handshake");
+        let mut request = Vec::new();
+        let mut buf = [0u8; 1024];
+        while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+            let n = tls.read(&mut buf).await.unwrap();
+            assert!(n > 0, "connection closed mid-request");
+            request.extend_from_slice(&buf[..n]);
+        }
+        let body = r#"{"mid": "0.5"}"#;
+        let response = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        tls.write_all(response.as_bytes()).await.unwrap();
+        tls.shutdown().await.unwrap();
+        String::from_utf8(request).unwrap()
+    });
+
+    let http = reqwest::Client::builder()
+        .add_root_certificate(reqwest::Certificate::from_pem(CA).unwrap())
+        .build()
+        .unwrap();
+    let client = ClobClient::new(&url).with_http_client(http);
+    let midpoint = tokio::time::timeout(Duration::from_secs(5), client.get_midpoint("1"))
+        .await
+        .expect("response in time")
+        .expect("midpoint over TLS");
+    assert_eq!(midpoint.mid, Decimal::new(5, 1));
+
+    let request = server.await.unwrap();
+    assert!(request.starts_with("GET /midpoint?token_id=1 HTTP/1.1\r\n"));
+}
+
+#[tokio::test]
+async fn market_stream_verifies_the_server_certificate() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("wss://{}", listener.local_addr().unwrap());
+
+    let stream = tokio::spawn(async move { WssMarketClient::with_url(&url).connect().await });
+    let (socket, _) = tokio::time::timeout(Duration::from_secs(5), listener.accept())
+        .await
+        .expect("client connected")
+        .unwrap();
+    // The client sees the whole handshake up to the certificate, which the test CA
+    // signed and the system roots don't trust, so it must refuse it
+    let err = acceptor()
+        .accept(socket)
+        .await
+        .expect_err("untrusted certificate accepted");
+    assert!(
+        err.to_string().contains("UnknownCA") || err.to_string().contains("BadCertificate"),
+        "unexpected handshake failure: {}",
+        err
+    );
+    stream.abort();
+}